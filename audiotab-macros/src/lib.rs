@@ -3,9 +3,16 @@ use quote::quote;
 use syn::{parse_macro_input, DeriveInput};
 
 mod node_meta;
-use node_meta::{parse_node_info, parse_fields, parse_ports};
+use node_meta::{parse_node_info, parse_fields, parse_ports, parse_presets};
 
-#[proc_macro_derive(StreamNode, attributes(node_meta, param, input, output))]
+/// Registers node metadata and generates `apply_config` / `validate_params`.
+///
+/// The default `ProcessingNode::on_create` runs `apply_config` to parse and
+/// validate every `#[param]` field, then calls `on_configured` for any
+/// node-specific setup. Fields that need custom parsing can opt out with
+/// `#[param(skip_config)]`; a node that implements `on_create` itself sets
+/// `#[node_meta(manual_config)]` and calls `apply_config` where it needs to.
+#[proc_macro_derive(StreamNode, attributes(node_meta, param, input, output, preset))]
 pub fn derive_stream_node(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
@@ -14,13 +21,35 @@ pub fn derive_stream_node(input: TokenStream) -> TokenStream {
         Err(e) => return e.write_errors().into(),
     };
 
-    let fields = parse_fields(&input);
-    let (inputs, outputs) = parse_ports(&input);
+    let presets = match parse_presets(&input) {
+        Ok(presets) => presets,
+        Err(e) => return e.write_errors().into(),
+    };
+
+    let fields = match parse_fields(&input) {
+        Ok(fields) => fields,
+        Err(e) => return e.write_errors().into(),
+    };
+    let (inputs, outputs) = match parse_ports(&input) {
+        Ok(ports) => ports,
+        Err(e) => return e.write_errors().into(),
+    };
 
     let struct_name = &input.ident;
     let node_id = struct_name.to_string().to_lowercase();
     let node_name = &node_info.name;
     let category = &node_info.category;
+    let deprecated = node_info.deprecated;
+    let manual_config = node_info.manual_config;
+    let version: u32 = match node_info.version.as_deref().map(str::parse) {
+        None => 1,
+        Some(Ok(v)) if v >= 1 => v,
+        _ => {
+            return syn::Error::new_spanned(struct_name, "node_meta version must be a positive integer")
+                .to_compile_error()
+                .into();
+        }
+    };
 
     // Generate parameters
     let params = fields.iter().filter_map(|f| {
@@ -28,62 +57,177 @@ pub fn derive_stream_node(input: TokenStream) -> TokenStream {
 
         // Fields must have a default value
         let default_val = f.default.as_ref()?.as_str();
-        let type_name = extract_type_name(&f.ty);
-
-        let param_code = if let (Some(min), Some(max)) = (f.min, f.max) {
-            quote! {
-                crate::registry::ParameterSchema {
-                    name: #field_name.to_string(),
-                    param_type: #type_name.to_string(),
-                    default: serde_json::json!(#default_val),
-                    min: Some(#min),
-                    max: Some(#max),
-                }
-            }
+        let choices = f.choice_list();
+        let type_name = if choices.is_empty() { extract_type_name(&f.ty) } else { "enum" };
+
+        let min = match f.min {
+            Some(min) => quote! { Some(#min) },
+            None => quote! { None },
+        };
+        let max = match f.max {
+            Some(max) => quote! { Some(#max) },
+            None => quote! { None },
+        };
+        let unit = match &f.unit {
+            Some(unit) => quote! { Some(#unit.to_string()) },
+            None => quote! { None },
+        };
+        let step = match f.step {
+            Some(step) => quote! { Some(#step) },
+            None => quote! { None },
+        };
+        let scale = if f.log_scale {
+            quote! { Some("log".to_string()) }
         } else {
-            quote! {
-                crate::registry::ParameterSchema {
-                    name: #field_name.to_string(),
-                    param_type: #type_name.to_string(),
-                    default: serde_json::json!(#default_val),
-                    min: None,
-                    max: None,
-                }
-            }
+            quote! { None }
+        };
+        let choices_code = if choices.is_empty() {
+            quote! { None }
+        } else {
+            quote! { Some(vec![#(#choices.to_string()),*]) }
         };
 
-        Some(param_code)
+        Some(quote! {
+            crate::registry::ParameterSchema {
+                name: #field_name.to_string(),
+                param_type: #type_name.to_string(),
+                default: serde_json::json!(#default_val),
+                min: #min,
+                max: #max,
+                choices: #choices_code,
+                unit: #unit,
+                step: #step,
+                scale: #scale,
+            }
+        })
     });
 
-    // Generate input port metadata
-    let input_metas = inputs.iter().map(|port| {
-        let port_id = port.ident.as_ref().unwrap().to_string();
-        let port_name = port.name.as_ref().unwrap_or(&port_id);
-        let data_type = port.data_type.as_ref().map(|s| s.as_str()).unwrap_or("any");
+    // Generate validation of choice parameters
+    let choice_checks = fields.iter().filter_map(|f| {
+        let ident = f.ident.as_ref()?;
+        let field_name = ident.to_string();
+        let choices = f.choice_list();
+        if choices.is_empty() {
+            return None;
+        }
+        let expected = choices.join(", ");
 
-        quote! {
-            crate::registry::PortMetadata {
-                id: #port_id.to_string(),
-                name: #port_name.to_string(),
-                data_type: #data_type.to_string(),
+        Some(quote! {
+            if ![#(#choices),*].iter().any(|c| c.eq_ignore_ascii_case(&self.#ident)) {
+                anyhow::bail!(
+                    "Invalid value '{}' for parameter '{}': expected one of {}",
+                    self.#ident, #field_name, #expected
+                );
+            }
+        })
+    });
+
+    // Generate range validation of numeric parameters
+    let range_checks = fields.iter().filter_map(|f| {
+        let ident = f.ident.as_ref()?;
+        f.default.as_ref()?;
+        let field_name = ident.to_string();
+        if f.min.is_none() && f.max.is_none() {
+            return None;
+        }
+        let min = f.min.unwrap_or(f64::NEG_INFINITY);
+        let max = f.max.unwrap_or(f64::INFINITY);
+
+        Some(quote! {
+            let value = self.#ident as f64;
+            if !(#min..=#max).contains(&value) {
+                anyhow::bail!(
+                    "{} must be between {} and {}, got {}",
+                    #field_name, #min, #max, value
+                );
             }
+        })
+    });
+
+    // Generate config parsing for every parameter field
+    let config_fields = fields.iter().filter_map(|f| {
+        let ident = f.ident.as_ref()?;
+        f.default.as_ref()?;
+        if f.skip_config {
+            return None;
         }
+        let field_name = ident.to_string();
+
+        Some(quote! {
+            if let Some(value) = config.get(#field_name).filter(|v| !v.is_null()) {
+                self.#ident = serde_json::from_value(value.clone()).map_err(|e| {
+                    anyhow::anyhow!("Invalid value for parameter '{}': {}", #field_name, e)
+                })?;
+            }
+        })
     });
 
-    // Generate output port metadata
-    let output_metas = outputs.iter().map(|port| {
-        let port_id = port.ident.as_ref().unwrap().to_string();
+    // Generate port metadata
+    let port_meta = |port: &node_meta::PortField| -> syn::Result<proc_macro2::TokenStream> {
+        let ident = port.ident.as_ref().unwrap();
+        let port_id = ident.to_string();
         let port_name = port.name.as_ref().unwrap_or(&port_id);
         let data_type = port.data_type.as_ref().map(|s| s.as_str()).unwrap_or("any");
 
-        quote! {
+        let multiplicity = if port.variadic {
+            let min = port.min.unwrap_or(1);
+            let max = port.max.unwrap_or(16);
+            if min > max {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    format!("variadic port '{}' has min {} greater than max {}", port_id, min, max),
+                ));
+            }
+            quote! { crate::registry::PortMultiplicity::Variadic { min: #min, max: #max } }
+        } else {
+            if port.min.is_some() || port.max.is_some() {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    format!("port '{}' sets min/max without `variadic`", port_id),
+                ));
+            }
+            quote! { crate::registry::PortMultiplicity::Single }
+        };
+
+        Ok(quote! {
             crate::registry::PortMetadata {
                 id: #port_id.to_string(),
                 name: #port_name.to_string(),
                 data_type: #data_type.to_string(),
+                multiplicity: #multiplicity,
+            }
+        })
+    };
+
+    let input_metas = match inputs.iter().map(port_meta).collect::<syn::Result<Vec<_>>>() {
+        Ok(metas) => metas,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let output_metas = match outputs.iter().map(port_meta).collect::<syn::Result<Vec<_>>>() {
+        Ok(metas) => metas,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    // Generate built-in presets, rejecting invalid JSON at compile time
+    let mut preset_metas = Vec::new();
+    for preset in &presets {
+        match serde_json::from_str::<serde_json::Value>(&preset.params) {
+            Ok(value) if value.is_object() => {}
+            _ => {
+                return syn::Error::new_spanned(
+                    struct_name,
+                    format!("preset '{}' params must be a JSON object", preset.name),
+                )
+                .to_compile_error()
+                .into();
             }
         }
-    });
+        let preset_name = &preset.name;
+        let preset_params = &preset.params;
+        preset_metas.push(quote! {
+            crate::registry::NodePreset::builtin(#node_id, #preset_name, #preset_params)
+        });
+    }
 
     let mod_name = syn::Ident::new(
         &format!("__node_registration_{}", struct_name.to_string().to_lowercase()),
@@ -95,6 +239,28 @@ pub fn derive_stream_node(input: TokenStream) -> TokenStream {
         struct_name.span(),
     );
 
+    // Register the parameter parsing run by the default on_create
+    let param_parser = if manual_config {
+        quote! {}
+    } else {
+        quote! {
+            fn parser_type_id() -> ::std::any::TypeId {
+                ::std::any::TypeId::of::<#struct_name>()
+            }
+
+            fn parse_params(node: &mut dyn ::std::any::Any, config: &serde_json::Value) -> anyhow::Result<()> {
+                match node.downcast_mut::<#struct_name>() {
+                    Some(node) => node.apply_config(config),
+                    None => Ok(()),
+                }
+            }
+
+            ::inventory::submit! {
+                crate::registry::ParamParser { type_id: parser_type_id, parse: parse_params }
+            }
+        }
+    };
+
     let expanded = quote! {
         mod #mod_name {
             use super::*;
@@ -104,16 +270,38 @@ pub fn derive_stream_node(input: TokenStream) -> TokenStream {
                     id: #node_id.to_string(),
                     name: #node_name.to_string(),
                     category: #category.to_string(),
+                    version: #version,
+                    deprecated: #deprecated,
                     inputs: vec![#(#input_metas),*],
                     outputs: vec![#(#output_metas),*],
                     parameters: vec![#(#params),*],
-                    factory: || Box::new(#struct_name::default()),
+                    presets: vec![#(#preset_metas),*],
+                    factory: ::std::sync::Arc::new(|| Box::new(#struct_name::default())),
+                }
+            }
+
+            impl #struct_name {
+                /// Read declared parameters from a node config, then validate them.
+                /// Keys that are missing or null keep their current value.
+                pub fn apply_config(&mut self, config: &serde_json::Value) -> anyhow::Result<()> {
+                    #(#config_fields)*
+                    self.validate_params()
+                }
+
+                /// Check parameter values against their declared constraints
+                #[allow(clippy::unnecessary_cast)]
+                pub fn validate_params(&self) -> anyhow::Result<()> {
+                    #(#choice_checks)*
+                    #(#range_checks)*
+                    Ok(())
                 }
             }
 
             ::inventory::submit! {
                 crate::registry::NodeMetadataFactoryWrapper(#factory_fn_name)
             }
+
+            #param_parser
         }
     };
 
@@ -1,4 +1,4 @@
-use darling::{FromAttributes, FromField};
+use darling::{FromAttributes, FromField, FromMeta};
 use syn::{DeriveInput, Fields};
 
 /// Parsed attributes from #[node_meta(...)]
@@ -7,6 +7,25 @@ use syn::{DeriveInput, Fields};
 pub struct NodeMetaArgs {
     pub name: String,
     pub category: String,
+
+    /// Node implementation version, bumped when saved configs need migrating
+    #[darling(default)]
+    pub version: Option<String>,
+
+    #[darling(default)]
+    pub deprecated: bool,
+
+    /// Leave parameter parsing out of the default `on_create`; the node
+    /// implements `on_create` itself
+    #[darling(default)]
+    pub manual_config: bool,
+}
+
+/// Parsed attributes from #[preset(name = "...", params = "{...}")]
+#[derive(Debug, FromMeta)]
+pub struct PresetArgs {
+    pub name: String,
+    pub params: String,
 }
 
 /// Parsed attributes from #[param(...)]
@@ -19,11 +38,45 @@ pub struct ParamField {
     #[darling(default)]
     pub default: Option<String>,
 
-    #[darling(default)]
+    #[darling(default, with = parse_number)]
     pub min: Option<f64>,
 
-    #[darling(default)]
+    #[darling(default, with = parse_number)]
     pub max: Option<f64>,
+
+    /// Comma-separated list of allowed values
+    #[darling(default)]
+    pub choices: Option<String>,
+
+    /// Display unit, e.g. "Hz" or "dB"
+    #[darling(default)]
+    pub unit: Option<String>,
+
+    /// Increment used by UI controls
+    #[darling(default, with = parse_number)]
+    pub step: Option<f64>,
+
+    /// Render the control on a logarithmic scale
+    #[darling(default)]
+    pub log_scale: bool,
+
+    /// Leave this field out of the generated config parsing
+    #[darling(default)]
+    pub skip_config: bool,
+}
+
+impl ParamField {
+    pub fn choice_list(&self) -> Vec<String> {
+        self.choices
+            .as_deref()
+            .map(|c| {
+                c.split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
 }
 
 /// Parse inputs/outputs from #[port(...)]
@@ -37,55 +90,97 @@ pub struct PortField {
 
     #[darling(default)]
     pub data_type: Option<String>,
+
+    /// Port can be instantiated multiple times per node
+    #[darling(default)]
+    pub variadic: bool,
+
+    #[darling(default)]
+    pub min: Option<usize>,
+
+    #[darling(default)]
+    pub max: Option<usize>,
 }
 
 pub fn parse_node_info(input: &DeriveInput) -> darling::Result<NodeMetaArgs> {
     NodeMetaArgs::from_attributes(&input.attrs)
 }
 
-pub fn parse_fields(input: &DeriveInput) -> Vec<ParamField> {
+pub fn parse_presets(input: &DeriveInput) -> darling::Result<Vec<PresetArgs>> {
+    input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("preset"))
+        .map(|attr| PresetArgs::from_meta(&attr.meta))
+        .collect()
+}
+
+pub fn parse_fields(input: &DeriveInput) -> darling::Result<Vec<ParamField>> {
     let fields = match &input.data {
         syn::Data::Struct(data) => match &data.fields {
             Fields::Named(fields) => &fields.named,
-            _ => return Vec::new(),
+            _ => return Ok(Vec::new()),
         },
-        _ => return Vec::new(),
+        _ => return Ok(Vec::new()),
     };
 
-    fields
+    let mut errors = darling::Error::accumulator();
+    let params = fields
         .iter()
         .filter(|f| f.attrs.iter().any(|attr| attr.path().is_ident("param")))
-        .filter_map(|f| ParamField::from_field(f).ok())
-        .collect()
+        .filter_map(|f| errors.handle(ParamField::from_field(f)))
+        .collect();
+    errors.finish_with(params)
+}
+
+/// Parse numeric attribute values, including negative literals like `min = -80.0`
+fn parse_number(meta: &syn::Meta) -> darling::Result<Option<f64>> {
+    match meta {
+        syn::Meta::NameValue(nv) => expr_to_f64(&nv.value).map(Some),
+        _ => Err(darling::Error::unsupported_format("list or word").with_span(meta)),
+    }
+}
+
+fn expr_to_f64(expr: &syn::Expr) -> darling::Result<f64> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Float(f), .. }) => Ok(f.base10_parse()?),
+        syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(i), .. }) => Ok(i.base10_parse::<i64>()? as f64),
+        syn::Expr::Unary(syn::ExprUnary { op: syn::UnOp::Neg(_), expr, .. }) => {
+            expr_to_f64(expr).map(|v| -v)
+        }
+        syn::Expr::Group(g) => expr_to_f64(&g.expr),
+        _ => Err(darling::Error::unexpected_expr_type(expr)),
+    }
 }
 
-pub fn parse_ports(input: &DeriveInput) -> (Vec<PortField>, Vec<PortField>) {
+pub fn parse_ports(input: &DeriveInput) -> darling::Result<(Vec<PortField>, Vec<PortField>)> {
     let fields = match &input.data {
         syn::Data::Struct(data) => match &data.fields {
             Fields::Named(fields) => &fields.named,
-            _ => return (Vec::new(), Vec::new()),
+            _ => return Ok((Vec::new(), Vec::new())),
         },
-        _ => return (Vec::new(), Vec::new()),
+        _ => return Ok((Vec::new(), Vec::new())),
     };
 
+    let mut errors = darling::Error::accumulator();
     let mut inputs = Vec::new();
     let mut outputs = Vec::new();
 
     for field in fields.iter() {
         // Check for #[input] attribute
         if field.attrs.iter().any(|attr| attr.path().is_ident("input")) {
-            if let Ok(port) = PortField::from_field(field) {
+            if let Some(port) = errors.handle(PortField::from_field(field)) {
                 inputs.push(port);
             }
         }
 
         // Check for #[output] attribute
         if field.attrs.iter().any(|attr| attr.path().is_ident("output")) {
-            if let Ok(port) = PortField::from_field(field) {
+            if let Some(port) = errors.handle(PortField::from_field(field)) {
                 outputs.push(port);
             }
         }
     }
 
-    (inputs, outputs)
+    errors.finish_with((inputs, outputs))
 }
@@ -0,0 +1,173 @@
+use audiotab::{DataFrame, MetadataValue};
+use numpy::{AllowTypeChange, PyArray1, PyArrayLike1};
+use pyo3::exceptions::{PyKeyError, PyTypeError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+/// A frame of named channels and metadata
+///
+/// Channels read as float64 numpy arrays (copies) and can be assigned
+/// from any 1-D array or list of numbers.
+#[pyclass(name = "DataFrame", module = "audiotab")]
+#[derive(Clone)]
+pub struct PyDataFrame {
+    pub inner: DataFrame,
+}
+
+impl From<DataFrame> for PyDataFrame {
+    fn from(inner: DataFrame) -> Self {
+        Self { inner }
+    }
+}
+
+fn missing_channel(name: &str) -> PyErr {
+    PyKeyError::new_err(name.to_string())
+}
+
+#[pymethods]
+impl PyDataFrame {
+    #[new]
+    #[pyo3(signature = (channels = None, timestamp = 0, sequence_id = 0, sample_rate = None))]
+    fn new(channels: Option<&Bound<'_, PyDict>>, timestamp: u64, sequence_id: u64, sample_rate: Option<f64>) -> PyResult<Self> {
+        let mut frame = Self { inner: DataFrame::new(timestamp, sequence_id) };
+        if let Some(channels) = channels {
+            for (name, samples) in channels.iter() {
+                frame.__setitem__(name.extract()?, samples.extract()?);
+            }
+        }
+        if let Some(rate) = sample_rate {
+            frame.inner.metadata.insert("sample_rate", rate);
+        }
+        Ok(frame)
+    }
+
+    /// Capture time in nanoseconds
+    #[getter]
+    fn timestamp(&self) -> u64 {
+        self.inner.timestamp
+    }
+
+    #[setter]
+    fn set_timestamp(&mut self, timestamp: u64) {
+        self.inner.timestamp = timestamp;
+    }
+
+    #[getter]
+    fn sequence_id(&self) -> u64 {
+        self.inner.sequence_id
+    }
+
+    #[setter]
+    fn set_sequence_id(&mut self, sequence_id: u64) {
+        self.inner.sequence_id = sequence_id;
+    }
+
+    /// Channel names, sorted
+    #[getter]
+    fn channels(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.inner.payload.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Frame-level sample rate from the `sample_rate` metadata
+    #[getter]
+    fn sample_rate(&self) -> Option<f64> {
+        self.inner.sample_rate()
+    }
+
+    #[setter]
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.inner.metadata.insert("sample_rate", sample_rate);
+    }
+
+    /// Sample rate of a channel, falling back to the frame sample rate
+    fn channel_sample_rate(&self, name: &str) -> PyResult<Option<f64>> {
+        if !self.inner.payload.contains_key(name) {
+            return Err(missing_channel(name));
+        }
+        Ok(self.inner.channel_sample_rate(name))
+    }
+
+    /// Physical unit of a channel, e.g. "Pa"
+    fn unit(&self, name: &str) -> PyResult<Option<String>> {
+        let channel = self.inner.payload.get(name).ok_or_else(|| missing_channel(name))?;
+        Ok(channel.unit.clone())
+    }
+
+    /// Metadata as a dict of bool, int, float and str values
+    #[getter]
+    fn metadata<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        for (key, value) in self.inner.metadata.iter() {
+            match value {
+                MetadataValue::Bool(v) => dict.set_item(key, v)?,
+                MetadataValue::Int(v) => dict.set_item(key, v)?,
+                MetadataValue::Float(v) => dict.set_item(key, v)?,
+                MetadataValue::Text(v) => dict.set_item(key, v)?,
+            }
+        }
+        Ok(dict)
+    }
+
+    fn set_metadata(&mut self, key: String, value: &Bound<'_, PyAny>) -> PyResult<()> {
+        // bool first: Python bools are ints too
+        let value: MetadataValue = if let Ok(v) = value.extract::<bool>() {
+            v.into()
+        } else if let Ok(v) = value.extract::<i64>() {
+            v.into()
+        } else if let Ok(v) = value.extract::<f64>() {
+            v.into()
+        } else if let Ok(v) = value.extract::<String>() {
+            v.into()
+        } else {
+            return Err(PyTypeError::new_err("Metadata values must be bool, int, float or str"));
+        };
+        self.inner.metadata.insert(key, value);
+        Ok(())
+    }
+
+    /// Every channel as {name: array}
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        for (name, channel) in &self.inner.payload {
+            dict.set_item(name, PyArray1::from_slice(py, channel.samples()))?;
+        }
+        Ok(dict)
+    }
+
+    fn __getitem__<'py>(&self, py: Python<'py>, name: &str) -> PyResult<Bound<'py, PyArray1<f64>>> {
+        let channel = self.inner.payload.get(name).ok_or_else(|| missing_channel(name))?;
+        Ok(PyArray1::from_slice(py, channel.samples()))
+    }
+
+    fn __setitem__(&mut self, name: String, samples: PyArrayLike1<'_, f64, AllowTypeChange>) {
+        self.inner.insert_channel(name, samples.as_array().to_vec());
+    }
+
+    fn __delitem__(&mut self, name: &str) -> PyResult<()> {
+        self.inner.payload.remove(name).map(|_| ()).ok_or_else(|| missing_channel(name))
+    }
+
+    fn __contains__(&self, name: &str) -> bool {
+        self.inner.payload.contains_key(name)
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.payload.len()
+    }
+
+    fn __repr__(&self) -> String {
+        let channels: Vec<String> = self
+            .channels()
+            .into_iter()
+            .map(|name| format!("{}[{}]", name, self.inner.payload[&name].samples().len()))
+            .collect();
+        format!(
+            "DataFrame(sequence_id={}, timestamp={}, channels=[{}])",
+            self.inner.sequence_id,
+            self.inner.timestamp,
+            channels.join(", ")
+        )
+    }
+}
@@ -0,0 +1,160 @@
+//! Python bindings: build pipelines from dicts, run them, and read their
+//! frames as numpy arrays
+//!
+//! ```python
+//! import audiotab, numpy as np
+//!
+//! pipeline = audiotab.AsyncPipeline({
+//!     "nodes": [{"id": "gain", "type": "Gain", "config": {"gain_db": 6.0}}],
+//!     "connections": [],
+//! })
+//! pipeline.tap("gain")
+//! outputs = pipeline.run([audiotab.DataFrame({"main_channel": np.ones(256)})])
+//! outputs["gain"][0]["main_channel"]
+//! ```
+
+mod frame;
+mod pipeline;
+mod registry;
+mod tap;
+
+use audiotab::core::AudiotabError;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+use serde_json::Value;
+use std::future::Future;
+use std::sync::OnceLock;
+
+pub use frame::PyDataFrame;
+pub use pipeline::{PyAsyncPipeline, PyPipeline};
+pub use tap::Tap;
+
+/// Runtime shared by every pipeline; AsyncPipeline node tasks run on it
+fn runtime() -> &'static tokio::runtime::Runtime {
+    static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .thread_name("audiotab")
+            .build()
+            .expect("Failed to start the audiotab runtime")
+    })
+}
+
+/// Run a future to completion without holding the GIL
+fn block_on<F>(py: Python<'_>, future: F) -> F::Output
+where
+    F: Future + Send,
+    F::Output: Send,
+{
+    py.detach(|| runtime().block_on(future))
+}
+
+fn to_py_err(e: impl Into<AudiotabError>) -> PyErr {
+    PyRuntimeError::new_err(e.into().to_string())
+}
+
+/// Convert a JSON-compatible Python object (dicts, lists, numbers, ...) to JSON
+fn to_json(obj: &Bound<'_, PyAny>) -> PyResult<Value> {
+    let text: String = obj.py().import("json")?.call_method1("dumps", (obj,))?.extract()?;
+    serde_json::from_str(&text).map_err(|e| to_py_err(anyhow::Error::from(e)))
+}
+
+fn from_json<'py>(py: Python<'py>, value: &Value) -> PyResult<Bound<'py, PyAny>> {
+    py.import("json")?.call_method1("loads", (value.to_string(),))
+}
+
+#[pymodule]
+#[pyo3(name = "audiotab")]
+fn audiotab_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyDataFrame>()?;
+    m.add_class::<PyPipeline>()?;
+    m.add_class::<PyAsyncPipeline>()?;
+    m.add_function(wrap_pyfunction!(registry::node_registry, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+
+    /// Run a Python snippet with the module imported as `audiotab`
+    fn run_python(code: &str) {
+        Python::attach(|py| {
+            let module = pyo3::wrap_pymodule!(audiotab_module)(py);
+            let globals = pyo3::types::PyDict::new(py);
+            globals.set_item("audiotab", module).unwrap();
+            let code = CString::new(code).unwrap();
+            if let Err(e) = py.run(&code, Some(&globals), None) {
+                e.print(py);
+                panic!("Python snippet failed");
+            }
+        });
+    }
+
+    #[test]
+    fn test_runs_async_pipeline_from_dict() {
+        run_python(
+            r#"
+pipeline = audiotab.AsyncPipeline({
+    "nodes": [
+        {"id": "gain", "type": "Gain", "config": {"gain_db": 6.0}},
+        {"id": "sink", "type": "Print", "config": {}},
+    ],
+    "connections": [{"from": "gain", "to": "sink"}],
+})
+pipeline.tap("gain")
+first = audiotab.DataFrame(sequence_id=3, sample_rate=48000.0)
+first.set_metadata("label", "run-1")
+outputs = pipeline.run([first, audiotab.DataFrame(sequence_id=4)])
+
+frames = outputs["gain"]
+assert [f.sequence_id for f in frames] == [3, 4], frames
+assert frames[0].metadata == {"sample_rate": 48000.0, "label": "run-1"}
+assert frames[0].sample_rate == 48000.0
+assert pipeline.state == "Completed"
+assert pipeline.take("gain") == []
+assert len(pipeline.metrics()) == 2
+
+try:
+    pipeline.tap("sink")
+    raise AssertionError("tapped a stopped pipeline")
+except RuntimeError:
+    pass
+"#,
+        );
+    }
+
+    #[test]
+    fn test_errors_and_registry() {
+        run_python(
+            r#"
+try:
+    audiotab.AsyncPipeline({"nodes": [{"id": "x", "type": "NoSuchNode"}], "connections": []})
+    raise AssertionError("built an unknown node")
+except RuntimeError as e:
+    assert "NoSuchNode" in str(e), e
+
+pipeline = audiotab.Pipeline({
+    "nodes": [{"id": "gain", "type": "Gain", "config": {}}, {"id": "sink", "type": "Print", "config": {}}],
+    "connections": [{"from": "gain", "to": "sink"}],
+})
+assert pipeline.execution_order == ["gain", "sink"]
+pipeline.tap("sink")
+pipeline.execute_once()
+assert len(pipeline.take("sink")) == 1
+try:
+    pipeline.take("gain")
+    raise AssertionError("took from an untapped node")
+except KeyError:
+    pass
+
+nodes = {n["id"]: n for n in audiotab.node_registry()}
+gain = nodes["gainnode"]
+assert gain["name"] == "Gain"
+assert [p["name"] for p in gain["parameters"]] == ["gain_db"]
+"#,
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{block_on, from_json, to_json, to_py_err, PyDataFrame, Tap};
+use audiotab::engine::{AsyncPipeline, Pipeline, PipelineState};
+use audiotab::{DataFrame, ProcessingNode};
+use pyo3::exceptions::{PyKeyError, PyRuntimeError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Frames captured from tapped nodes, by node id
+type Captured = HashMap<String, Arc<Mutex<Vec<DataFrame>>>>;
+
+fn install_tap(nodes: &mut HashMap<String, Box<dyn ProcessingNode>>, taps: &mut Captured, node_id: &str) -> PyResult<()> {
+    if taps.contains_key(node_id) {
+        return Ok(());
+    }
+    let node = nodes
+        .remove(node_id)
+        .ok_or_else(|| PyKeyError::new_err(format!("Node {} not found", node_id)))?;
+    let tap = Tap::new(node);
+    taps.insert(node_id.to_string(), tap.frames());
+    nodes.insert(node_id.to_string(), Box::new(tap));
+    Ok(())
+}
+
+fn take_frames(taps: &Captured, node_id: &str) -> PyResult<Vec<PyDataFrame>> {
+    let frames = taps
+        .get(node_id)
+        .ok_or_else(|| PyKeyError::new_err(format!("Node {} is not tapped", node_id)))?;
+    let frames = std::mem::take(&mut *frames.lock().unwrap());
+    Ok(frames.into_iter().map(PyDataFrame::from).collect())
+}
+
+/// Sequential pipeline: every node runs once per `execute_once`, in
+/// topological order
+#[pyclass(name = "Pipeline", module = "audiotab")]
+pub struct PyPipeline {
+    inner: Pipeline,
+    taps: Captured,
+}
+
+#[pymethods]
+impl PyPipeline {
+    /// Build from a dict of `nodes` and `connections`, as in pipeline JSON files
+    #[new]
+    fn new(py: Python<'_>, config: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let config = to_json(config)?;
+        let inner = block_on(py, Pipeline::from_json(config)).map_err(to_py_err)?;
+        Ok(Self { inner, taps: HashMap::new() })
+    }
+
+    /// Node ids in the order `execute_once` runs them
+    #[getter]
+    fn execution_order(&self) -> Vec<String> {
+        self.inner.execution_order().to_vec()
+    }
+
+    /// Keep every frame `node_id` outputs, for `take`
+    fn tap(&mut self, node_id: &str) -> PyResult<()> {
+        install_tap(self.inner.nodes_mut(), &mut self.taps, node_id)
+    }
+
+    /// Frames a tapped node has output since the last `take`
+    fn take(&self, node_id: &str) -> PyResult<Vec<PyDataFrame>> {
+        take_frames(&self.taps, node_id)
+    }
+
+    fn execute_once(&mut self, py: Python<'_>) -> PyResult<()> {
+        let pipeline = &mut self.inner;
+        block_on(py, pipeline.execute_once()).map_err(to_py_err)
+    }
+}
+
+/// Concurrent pipeline: each node runs as its own task and frames stream
+/// between them
+///
+/// Frames sent with `trigger` enter at the nodes without inbound
+/// connections. Tapped output is complete once the pipeline is stopped.
+#[pyclass(name = "AsyncPipeline", module = "audiotab")]
+pub struct PyAsyncPipeline {
+    inner: AsyncPipeline,
+    taps: Captured,
+}
+
+#[pymethods]
+impl PyAsyncPipeline {
+    /// Build from a dict in the pipeline JSON format; presets resolve
+    /// against the built-in ones
+    #[new]
+    fn new(py: Python<'_>, config: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let config = to_json(config)?;
+        let inner = block_on(py, AsyncPipeline::from_json(config)).map_err(to_py_err)?;
+        Ok(Self { inner, taps: HashMap::new() })
+    }
+
+    /// "Idle", "Running", "Completed", ...
+    #[getter]
+    fn state(&self) -> String {
+        self.inner.state().name().to_string()
+    }
+
+    /// Keep every frame `node_id` outputs, for `take`; only before `start`
+    fn tap(&mut self, node_id: &str) -> PyResult<()> {
+        if *self.inner.state() != PipelineState::Idle {
+            return Err(PyRuntimeError::new_err("Nodes can only be tapped before the pipeline starts"));
+        }
+        install_tap(self.inner.nodes_mut(), &mut self.taps, node_id)
+    }
+
+    /// Frames a tapped node has output since the last `take`
+    fn take(&self, node_id: &str) -> PyResult<Vec<PyDataFrame>> {
+        take_frames(&self.taps, node_id)
+    }
+
+    fn start(&mut self, py: Python<'_>) -> PyResult<()> {
+        let pipeline = &mut self.inner;
+        block_on(py, pipeline.start()).map_err(to_py_err)
+    }
+
+    /// Send a frame into the pipeline
+    ///
+    /// Without a frame, sends an empty one marked `manual_trigger`, which
+    /// fires TriggerSourceNodes in manual mode.
+    #[pyo3(signature = (frame = None))]
+    fn trigger(&self, py: Python<'_>, frame: Option<PyDataFrame>) -> PyResult<()> {
+        let frame = frame.map(|f| f.inner).unwrap_or_else(|| {
+            let mut frame = DataFrame::new(0, 0);
+            frame.metadata.insert("manual_trigger", true);
+            frame
+        });
+        block_on(py, self.inner.trigger(frame)).map_err(to_py_err)
+    }
+
+    /// Ask nodes to emit data they are holding back, without stopping
+    fn flush(&self, py: Python<'_>) -> PyResult<()> {
+        block_on(py, self.inner.flush()).map_err(to_py_err)
+    }
+
+    /// Process the frames in flight, then stop every node
+    fn stop(&mut self, py: Python<'_>) -> PyResult<()> {
+        let pipeline = &mut self.inner;
+        block_on(py, pipeline.stop()).map_err(to_py_err)
+    }
+
+    /// Merge `params` into a node's config, also while running
+    fn update_node_params(&mut self, py: Python<'_>, node_id: &str, params: &Bound<'_, PyAny>) -> PyResult<()> {
+        let params = to_json(params)?;
+        let pipeline = &mut self.inner;
+        block_on(py, pipeline.update_node_params(node_id, params)).map_err(to_py_err)
+    }
+
+    /// Per-node latency, throughput and error counts; None before `start`
+    fn metrics<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let Some(monitor) = self.inner.get_monitor() else {
+            return Ok(None);
+        };
+        let metrics = serde_json::to_value(monitor.node_metrics()).map_err(|e| to_py_err(anyhow::Error::from(e)))?;
+        from_json(py, &metrics).map(Some)
+    }
+
+    /// Start, send `frames` in order, stop, and return what tapped nodes output
+    fn run<'py>(&mut self, py: Python<'py>, frames: Vec<PyDataFrame>) -> PyResult<Bound<'py, PyDict>> {
+        self.start(py)?;
+        for frame in frames {
+            self.trigger(py, Some(frame))?;
+        }
+        self.stop(py)?;
+
+        let outputs = PyDict::new(py);
+        for node_id in self.taps.keys() {
+            outputs.set_item(node_id, take_frames(&self.taps, node_id)?)?;
+        }
+        Ok(outputs)
+    }
+}
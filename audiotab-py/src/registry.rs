@@ -0,0 +1,30 @@
+use crate::{from_json, to_py_err};
+use audiotab::NodeMetadata;
+use pyo3::prelude::*;
+use serde_json::{json, Value};
+
+/// Registered node types with their ports, parameters and presets
+fn registry_json() -> anyhow::Result<Value> {
+    NodeMetadata::all()
+        .into_iter()
+        .map(|meta| {
+            Ok(json!({
+                "id": meta.id,
+                "name": meta.name,
+                "category": meta.category,
+                "version": meta.version,
+                "deprecated": meta.deprecated,
+                "inputs": serde_json::to_value(&meta.inputs)?,
+                "outputs": serde_json::to_value(&meta.outputs)?,
+                "parameters": serde_json::to_value(&meta.parameters)?,
+                "presets": meta.presets.iter().map(|p| json!({"name": p.name, "parameters": p.parameters})).collect::<Vec<_>>(),
+            }))
+        })
+        .collect()
+}
+
+/// Every registered node type as a dict, ordered by id
+#[pyfunction]
+pub fn node_registry(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+    from_json(py, &registry_json().map_err(to_py_err)?)
+}
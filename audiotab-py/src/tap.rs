@@ -0,0 +1,101 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab::{DataFrame, ProcessingNode};
+use serde_json::Value;
+use std::any::Any;
+use std::sync::{Arc, Mutex};
+
+/// Wraps a node and keeps a copy of every frame it outputs
+///
+/// Copies share their samples with the frames passed downstream. Resource
+/// injection (`as_any_mut`) reaches the wrapped node.
+pub struct Tap {
+    inner: Box<dyn ProcessingNode>,
+    frames: Arc<Mutex<Vec<DataFrame>>>,
+}
+
+impl Tap {
+    pub fn new(inner: Box<dyn ProcessingNode>) -> Self {
+        Self { inner, frames: Arc::new(Mutex::new(Vec::new())) }
+    }
+
+    /// Frames captured so far, shared with the running node
+    pub fn frames(&self) -> Arc<Mutex<Vec<DataFrame>>> {
+        self.frames.clone()
+    }
+
+    fn keep(&self, frame: &Option<DataFrame>) {
+        if let Some(frame) = frame {
+            self.frames.lock().unwrap().push(frame.clone());
+        }
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for Tap {
+    async fn on_create(&mut self, config: Value) -> Result<()> {
+        self.inner.on_create(config).await
+    }
+
+    fn migrate_config(&self, from_version: u32, config: Value) -> Result<Value> {
+        self.inner.migrate_config(from_version, config)
+    }
+
+    async fn process(&mut self, input: DataFrame) -> Result<DataFrame> {
+        let output = self.inner.process(input).await?;
+        self.frames.lock().unwrap().push(output.clone());
+        Ok(output)
+    }
+
+    async fn on_flush(&mut self) -> Result<Option<DataFrame>> {
+        let output = self.inner.on_flush().await?;
+        self.keep(&output);
+        Ok(output)
+    }
+
+    async fn on_eos(&mut self) -> Result<Option<DataFrame>> {
+        let output = self.inner.on_eos().await?;
+        self.keep(&output);
+        Ok(output)
+    }
+
+    async fn on_destroy(&mut self) -> Result<()> {
+        self.inner.on_destroy().await
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self.inner.as_any_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use audiotab::engine::AsyncPipeline;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_captures_output_of_running_node() {
+        let mut pipeline = AsyncPipeline::from_json(json!({
+            "nodes": [{"id": "gain", "type": "Gain", "config": {"gain_db": 20.0}}],
+            "connections": []
+        }))
+        .await
+        .unwrap();
+        let node = pipeline.nodes_mut().remove("gain").unwrap();
+        let tap = Tap::new(node);
+        let frames = tap.frames();
+        pipeline.nodes_mut().insert("gain".to_string(), Box::new(tap));
+
+        pipeline.start().await.unwrap();
+        let mut frame = DataFrame::new(0, 7);
+        frame.insert_channel("main_channel", vec![0.5]);
+        pipeline.trigger(frame).await.unwrap();
+        pipeline.stop().await.unwrap();
+
+        let frames = frames.lock().unwrap();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].sequence_id, 7);
+        assert!((frames[0].payload["main_channel"].samples()[0] - 5.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,45 @@
+//! FFTNode on multi-channel blocks
+
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::nodes::FFTNode;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use serde_json::json;
+
+/// Samples per channel in each frame
+const BLOCK: usize = 1024;
+
+fn frame(channels: usize) -> DataFrame {
+    let mut frame = DataFrame::new(0, 0);
+    for ch in 0..channels {
+        let samples = (0..BLOCK).map(|i| (i as f64 * 0.05 + ch as f64).sin()).collect::<Vec<f64>>();
+        frame.insert_channel(format!("ch{}", ch), samples);
+    }
+    frame.metadata.insert("sample_rate", 48000.0);
+    frame
+}
+
+fn bench_fft(c: &mut Criterion) {
+    let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let mut group = c.benchmark_group("fft_node");
+    group.throughput(Throughput::Elements(1));
+    for fft_size in [1024, 4096] {
+        for channels in [2, 32] {
+            let mut node = FFTNode::default();
+            rt.block_on(node.on_create(json!({ "fft_size": fft_size, "hop_size": BLOCK }))).unwrap();
+            let input = frame(channels);
+            // Fill the node's buffers so every frame computes a spectrum
+            for _ in 0..fft_size / BLOCK {
+                rt.block_on(node.process(input.clone())).unwrap();
+            }
+            group.bench_with_input(
+                BenchmarkId::new(format!("{}", fft_size), channels),
+                &input,
+                |b, input| b.iter(|| black_box(rt.block_on(node.process(input.clone())).unwrap())),
+            );
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_fft);
+criterion_main!(benches);
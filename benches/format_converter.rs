@@ -0,0 +1,56 @@
+//! Conversions between device packets and frames, run for every block a device delivers
+
+use audiotab::hal::format_converter::{frame_to_packet, packet_to_frame};
+use audiotab::hal::{PacketBuffer, SampleData, SampleFormat};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+/// Samples per channel in each packet
+const BLOCK: usize = 1024;
+
+fn packet(format: SampleFormat, num_channels: usize) -> PacketBuffer {
+    let len = num_channels * BLOCK;
+    let data = match format {
+        SampleFormat::I16 => SampleData::I16((0..len).map(|i| (i % 2000) as i16 - 1000).collect()),
+        SampleFormat::I24 => SampleData::I24(vec![0x40; len * 3]),
+        SampleFormat::I32 => SampleData::I32((0..len).map(|i| (i as i32) << 8).collect()),
+        SampleFormat::F32 => SampleData::F32((0..len).map(|i| (i % 100) as f32 / 100.0).collect()),
+        SampleFormat::F64 => SampleData::F64((0..len).map(|i| (i % 100) as f64 / 100.0).collect()),
+        SampleFormat::U8 => SampleData::U8((0..len).map(|i| i as u8).collect()),
+    };
+    PacketBuffer { data, sample_rate: 48000, num_channels, timestamp: None, codec: None }
+}
+
+fn bench_packet_to_frame(c: &mut Criterion) {
+    let mut group = c.benchmark_group("packet_to_frame");
+    group.throughput(Throughput::Elements(1));
+    for format in [SampleFormat::I16, SampleFormat::I24, SampleFormat::F32] {
+        for channels in [2, 32] {
+            let packet = packet(format, channels);
+            group.bench_with_input(BenchmarkId::new(format!("{:?}", format), channels), &packet, |b, packet| {
+                let mut sequence_id = 0;
+                b.iter(|| {
+                    sequence_id += 1;
+                    black_box(packet_to_frame(packet, sequence_id).unwrap())
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_frame_to_packet(c: &mut Criterion) {
+    let mut group = c.benchmark_group("frame_to_packet");
+    group.throughput(Throughput::Elements(1));
+    for format in [SampleFormat::I16, SampleFormat::I24, SampleFormat::F32] {
+        for channels in [2, 32] {
+            let frame = packet_to_frame(&packet(SampleFormat::F32, channels), 0).unwrap();
+            group.bench_with_input(BenchmarkId::new(format!("{:?}", format), channels), &frame, |b, frame| {
+                b.iter(|| black_box(frame_to_packet(frame, format, 48000).unwrap()))
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_packet_to_frame, bench_frame_to_packet);
+criterion_main!(benches);
@@ -0,0 +1,89 @@
+//! End-to-end throughput of a running AsyncPipeline
+//!
+//! After the criterion run, one more run of the same graph prints its
+//! frames per second and the average time each node spends on a frame.
+
+use audiotab::core::DataFrame;
+use audiotab::engine::AsyncPipeline;
+use criterion::{BenchmarkId, Criterion, Throughput};
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+
+/// Samples per channel in each frame
+const BLOCK: usize = 1024;
+
+/// Frames sent through the graph for the per-node report
+const REPORT_FRAMES: u64 = 2000;
+
+fn graph() -> Value {
+    json!({
+        "nodes": [
+            {"id": "gain", "type": "Gain", "config": {"gain_db": 6.0}},
+            {"id": "fft", "type": "FFTNode", "config": {"fft_size": BLOCK, "hop_size": BLOCK}},
+            {"id": "spl", "type": "SplMeterNode", "config": {}}
+        ],
+        "connections": [
+            {"from": "gain", "to": "fft"},
+            {"from": "gain", "to": "spl"}
+        ],
+        "pipeline_config": {"channel_capacity": 64}
+    })
+}
+
+fn frame(sequence_id: u64, channels: usize) -> DataFrame {
+    let mut frame = DataFrame::new(0, sequence_id);
+    for ch in 0..channels {
+        let samples = (0..BLOCK).map(|i| (i as f64 * 0.05 + ch as f64).sin() * 0.1).collect::<Vec<f64>>();
+        frame.insert_channel(format!("ch{}", ch), samples);
+    }
+    frame.metadata.insert("sample_rate", 48000.0);
+    frame
+}
+
+/// Send `frames` frames through a fresh pipeline and wait until it has drained
+async fn run(frames: u64, channels: usize) -> (Duration, AsyncPipeline) {
+    let mut pipeline = AsyncPipeline::from_json(graph()).await.unwrap();
+    pipeline.start().await.unwrap();
+    let start = Instant::now();
+    for sequence_id in 0..frames {
+        pipeline.trigger(frame(sequence_id, channels)).await.unwrap();
+    }
+    pipeline.stop().await.unwrap();
+    (start.elapsed(), pipeline)
+}
+
+fn bench_pipeline(c: &mut Criterion, rt: &tokio::runtime::Runtime) {
+    let mut group = c.benchmark_group("pipeline");
+    group.throughput(Throughput::Elements(1));
+    for channels in [2, 32] {
+        group.bench_with_input(BenchmarkId::new("gain_fft_spl", channels), &channels, |b, &channels| {
+            b.to_async(rt).iter_custom(|frames| async move { run(frames, channels).await.0 })
+        });
+    }
+    group.finish();
+}
+
+/// Print frames per second and per-node latency of one run per channel count
+fn report_nodes(rt: &tokio::runtime::Runtime) {
+    for channels in [2, 32] {
+        let (elapsed, pipeline) = rt.block_on(run(REPORT_FRAMES, channels));
+        println!(
+            "\npipeline/gain_fft_spl/{}: {:.0} frames/sec",
+            channels,
+            REPORT_FRAMES as f64 / elapsed.as_secs_f64()
+        );
+        let mut nodes = pipeline.get_monitor().unwrap().node_metrics();
+        nodes.sort_by(|a, b| a.node_id.cmp(&b.node_id));
+        for node in nodes {
+            println!("  {:<6} {:>8} µs/frame", node.node_id, node.avg_latency_us);
+        }
+    }
+}
+
+fn main() {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let mut criterion = Criterion::default().configure_from_args();
+    bench_pipeline(&mut criterion, &rt);
+    criterion.final_summary();
+    report_nodes(&rt);
+}
@@ -0,0 +1,24 @@
+//! Writes to the shared-memory ring buffer read by the visualization
+
+use audiotab::visualization::RingBufferWriter;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+/// Samples per channel in each write
+const BLOCK: usize = 1024;
+
+fn bench_ring_buffer_write(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    let mut group = c.benchmark_group("ring_buffer_write");
+    group.throughput(Throughput::Elements(1));
+    for channels in [2, 32] {
+        let writer = RingBufferWriter::new(dir.path().join(format!("ring-{}", channels)), 48000, channels, 10).unwrap();
+        let block: Vec<Vec<f64>> = (0..channels).map(|ch| vec![ch as f64; BLOCK]).collect();
+        group.bench_with_input(BenchmarkId::from_parameter(channels), &block, |b, block| {
+            b.iter(|| writer.write(block).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_ring_buffer_write);
+criterion_main!(benches);
@@ -0,0 +1,59 @@
+//! Malformed device packets must convert to an error, never a panic
+//!
+//! The first input byte picks the sample format, the second the channel
+//! count and the third a sample rate; the rest is the packet's data. Run
+//! with `cargo fuzz run packet_to_frame` from the repository root.
+
+#![no_main]
+
+use audiotab::hal::format_converter::{frame_to_packet, packet_to_frame};
+use audiotab::hal::{PacketBuffer, SampleData, SampleFormat};
+use libfuzzer_sys::fuzz_target;
+
+const SAMPLE_RATES: [u64; 4] = [0, 8000, 48000, 192000];
+
+fn sample_data(format: u8, bytes: &[u8]) -> (SampleData, SampleFormat) {
+    match format % 6 {
+        0 => (
+            SampleData::I16(bytes.chunks_exact(2).map(|b| i16::from_le_bytes([b[0], b[1]])).collect()),
+            SampleFormat::I16,
+        ),
+        // Unpacked from the raw bytes, including lengths that are not a multiple of 3
+        1 => (SampleData::I24(bytes.to_vec()), SampleFormat::I24),
+        2 => (
+            SampleData::I32(bytes.chunks_exact(4).map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect()),
+            SampleFormat::I32,
+        ),
+        3 => (
+            SampleData::F32(bytes.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect()),
+            SampleFormat::F32,
+        ),
+        4 => (
+            SampleData::F64(bytes.chunks_exact(8).map(|b| f64::from_le_bytes(b.try_into().unwrap())).collect()),
+            SampleFormat::F64,
+        ),
+        _ => (SampleData::U8(bytes.to_vec()), SampleFormat::U8),
+    }
+}
+
+fuzz_target!(|input: &[u8]| {
+    let [format, channels, rate, data @ ..] = input else {
+        return;
+    };
+    let (data, format) = sample_data(*format, data);
+    let num_channels = (*channels % 65) as usize;
+    let packet = PacketBuffer {
+        data,
+        sample_rate: SAMPLE_RATES[*rate as usize % SAMPLE_RATES.len()],
+        num_channels,
+        timestamp: None,
+        codec: None,
+    };
+
+    if let Ok(frame) = packet_to_frame(&packet, u64::from(*rate) << 32) {
+        assert_eq!(frame.payload.len(), num_channels);
+        // Whatever converts must convert back to a packet of the same shape
+        let back = frame_to_packet(&frame, format, packet.sample_rate).unwrap();
+        assert_eq!(back.num_channels, num_channels);
+    }
+});
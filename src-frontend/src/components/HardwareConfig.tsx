@@ -27,6 +27,13 @@ interface ChannelMapping {
 interface Calibration {
   gain: number;
   offset: number;
+  calibrated_at?: number | null;
+}
+
+interface CalibrationRecord {
+  calibration: Calibration;
+  reference_db: number;
+  measured_rms: number;
 }
 
 interface RegisteredHardware {
@@ -43,7 +50,10 @@ interface RegisteredHardware {
   channels: number;
   channel_mapping: ChannelMapping;
   calibration: Calibration;
+  calibration_history?: CalibrationRecord[];
   max_voltage: number;
+  buffer_count: number;
+  latency_mode: 'LowLatency' | 'Safe';
   notes: string;
 }
 
@@ -169,6 +179,8 @@ export function HardwareConfig() {
       },
       calibration: { gain: 1.0, offset: 0.0 },
       max_voltage: 0.0,
+      buffer_count: 2,
+      latency_mode: 'LowLatency',
       notes: '',
     };
 
@@ -0,0 +1,68 @@
+import { useCallback, useState } from 'react';
+import { Button } from './ui/button';
+import { useLogRecordEvents } from '../hooks/useTauriEvents';
+import type { LogRecord } from '../types/nodes';
+
+/** Records kept; older ones are dropped */
+const MAX_RECORDS = 200;
+
+/** Span fields shown in front of the message, when a record has them */
+const CONTEXT_FIELDS = ['pipeline', 'node', 'device'];
+
+function formatContext(record: LogRecord): string {
+  return CONTEXT_FIELDS
+    .filter((key) => record.fields[key] !== undefined)
+    .map((key) => `${key}=${String(record.fields[key])}`)
+    .join(' ');
+}
+
+/**
+ * Backend warnings and errors, newest last
+ * @param open - Whether the record list is shown; the toggle is always shown
+ * @param onToggle - Called when the toggle is clicked
+ */
+export function LogConsole({ open, onToggle }: { open: boolean; onToggle: () => void }) {
+  const [records, setRecords] = useState<LogRecord[]>([]);
+
+  const append = useCallback((record: LogRecord) => {
+    setRecords((previous) => [...previous, record].slice(-MAX_RECORDS));
+  }, []);
+  useLogRecordEvents(append);
+
+  const errors = records.filter((r) => r.level === 'ERROR').length;
+
+  return (
+    <div className="flex flex-col">
+      {open && (
+        <div className="h-40 overflow-y-auto bg-slate-900 border-t border-slate-700 px-4 py-1 font-mono text-xs">
+          {records.length === 0 ? (
+            <p className="text-slate-500">No warnings or errors</p>
+          ) : (
+            records.map((record, index) => (
+              <div key={index} className="flex gap-2">
+                <span className="text-slate-500">
+                  {new Date(record.timestamp_ms).toLocaleTimeString()}
+                </span>
+                <span className={record.level === 'ERROR' ? 'text-red-400' : 'text-yellow-400'}>
+                  {record.level}
+                </span>
+                <span className="text-slate-400">{formatContext(record)}</span>
+                <span className="text-slate-200">{record.message}</span>
+              </div>
+            ))
+          )}
+        </div>
+      )}
+      <div className="flex items-center gap-2 px-4">
+        <Button onClick={onToggle} variant="outline" size="sm" className="h-6 text-xs">
+          {open ? 'Hide log' : 'Show log'} ({records.length}{errors > 0 ? `, ${errors} errors` : ''})
+        </Button>
+        {open && records.length > 0 && (
+          <Button onClick={() => setRecords([])} variant="outline" size="sm" className="h-6 text-xs">
+            Clear
+          </Button>
+        )}
+      </div>
+    </div>
+  );
+}
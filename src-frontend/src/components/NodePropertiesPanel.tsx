@@ -1,5 +1,6 @@
 import { useDeviceProfiles } from '../hooks/useDeviceManagement';
 import { useFlowStore } from '../stores/flowStore';
+import type { ErrorPolicyName } from '../types/nodes';
 
 export function NodePropertiesPanel() {
   const { nodes } = useFlowStore();
@@ -59,6 +60,26 @@ export function NodePropertiesPanel() {
           </div>
         )}
 
+        {/* Error handling policy */}
+        <div>
+          <label className="text-xs text-slate-400 block mb-1">On Error</label>
+          <select
+            className="w-full px-2 py-1 bg-slate-700 border border-slate-600 rounded text-sm text-white"
+            value={(selectedNode.data as any).error_policy || 'propagate'}
+            onChange={(e) => {
+              updateNodeData(selectedNode.id, {
+                error_policy: e.target.value as ErrorPolicyName,
+              });
+            }}
+          >
+            <option value="propagate">Stop pipeline</option>
+            <option value="skip_frame">Skip frame</option>
+            <option value="substitute_silence">Substitute silence</option>
+            <option value="restart">Restart with backoff</option>
+            <option value="circuit_breaker">Circuit breaker</option>
+          </select>
+        </div>
+
         {/* Other node parameters could be rendered here */}
         <div className="pt-2 border-t border-slate-700">
           <p className="text-xs text-slate-500">
@@ -28,6 +28,7 @@ export interface ChannelMapping {
 export interface Calibration {
   gain: number;
   offset: number;
+  calibrated_at?: number | null;
 }
 
 export interface DeviceConfig {
@@ -46,6 +47,12 @@ export interface DeviceMetadata {
   modified_at: number;
 }
 
+export interface FrequencyResponse {
+  points: [number, number][];
+  sensitivity_db: number | null;
+  serial: string | null;
+}
+
 export interface DeviceProfile {
   id: string;
   alias: string;
@@ -53,6 +60,7 @@ export interface DeviceProfile {
   device_id: string;
   config: DeviceConfig;
   metadata: DeviceMetadata;
+  frequency_response?: FrequencyResponse;
 }
 
 export function useDiscoverDevices() {
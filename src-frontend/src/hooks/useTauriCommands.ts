@@ -1,7 +1,8 @@
 import { invoke } from '@tauri-apps/api/core';
 import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query';
-import type { NodeMetadata, GraphJson, PipelineStatus, PipelineAction } from '../types/nodes';
+import type { NodeMetadata, FieldError, GraphJson, GraphPatch, PipelineStatus, PipelineAction, PipelineMetricsReport, ResourceUsage, SessionPlan, EventQuery, EventSessionInfo, LoggedEvent } from '../types/nodes';
 import type { KernelStatusResponse } from '../types/kernel';
+import { toCommandError } from '../utils/errors';
 
 export function useNodeRegistry() {
   return useQuery({
@@ -12,18 +13,27 @@ export function useNodeRegistry() {
 
 export function useDeployGraph() {
   return useMutation({
-    mutationFn: async (graph: GraphJson) => {
+    mutationFn: async ({ graph, id }: { graph: GraphJson; id?: string }) => {
       try {
-        const result = await invoke<string>('deploy_graph', { graph });
+        const result = await invoke<string>('deploy_graph', { graph, id });
         return result;
       } catch (error) {
-        // Extract error message from Tauri error
-        const errorMessage = error instanceof Error
-          ? error.message
-          : String(error);
+        // Commands reject with a structured { kind, message } error
+        const commandError = toCommandError(error);
+        console.error('[useDeployGraph] Deployment failed:', commandError.message);
+        throw commandError;
+      }
+    },
+  });
+}
 
-        console.error('[useDeployGraph] Deployment failed:', errorMessage);
-        throw new Error(errorMessage);
+export function useValidateGraph() {
+  return useMutation({
+    mutationFn: async (graph: GraphJson) => {
+      try {
+        return await invoke<FieldError[]>('validate_graph', { graph });
+      } catch (error) {
+        throw toCommandError(error);
       }
     },
   });
@@ -36,6 +46,24 @@ export function usePipelineStates() {
   });
 }
 
+export function usePipelineMetrics(id: string | null) {
+  return useQuery({
+    queryKey: ['pipeline-metrics', id],
+    queryFn: () => invoke<PipelineMetricsReport>('get_pipeline_metrics', { id }),
+    enabled: id !== null,
+    refetchInterval: 1000,
+  });
+}
+
+export function useResourceUsage(id: string | null) {
+  return useQuery({
+    queryKey: ['resource-usage', id],
+    queryFn: () => invoke<ResourceUsage>('get_resource_usage', { id }),
+    enabled: id !== null,
+    refetchInterval: 1000,
+  });
+}
+
 export function useControlPipeline() {
   return useMutation({
     mutationFn: ({ id, action }: { id: string; action: PipelineAction }) =>
@@ -43,6 +71,43 @@ export function useControlPipeline() {
   });
 }
 
+export function usePatchGraph() {
+  return useMutation({
+    mutationFn: ({ id, patches }: { id: string; patches: GraphPatch[] }) =>
+      invoke<void>('patch_graph', { id, patches }),
+  });
+}
+
+export function useStartSession() {
+  return useMutation({
+    mutationFn: ({ id, plan }: { id: string; plan: SessionPlan }) =>
+      invoke<void>('start_session', { id, plan }),
+  });
+}
+
+export function useCancelSession() {
+  return useMutation({
+    mutationFn: (id: string) => invoke<void>('cancel_session', { id }),
+  });
+}
+
+export function useExportRingBuffer() {
+  return useMutation({
+    mutationFn: ({ id, path, format, seconds }: { id: string; path: string; format?: 'wav' | 'csv'; seconds?: number }) =>
+      invoke<void>('export_ringbuffer', { id, path, format, seconds }),
+  });
+}
+
+export function useDeletePipeline() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: (id: string) => invoke<void>('delete_pipeline', { id }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['pipeline-states'] });
+    },
+  });
+}
+
 // Kernel management hooks
 
 export function useKernelStatus() {
@@ -72,3 +137,18 @@ export function useStopKernel() {
     },
   });
 }
+
+export function useEventSessions() {
+  return useQuery({
+    queryKey: ['event-sessions'],
+    queryFn: () => invoke<EventSessionInfo[]>('list_event_sessions'),
+  });
+}
+
+/** Events of a recorded session, or of the current one without `sessionId` */
+export function useSessionEvents(query: EventQuery, sessionId?: string) {
+  return useQuery({
+    queryKey: ['session-events', sessionId ?? null, query],
+    queryFn: () => invoke<LoggedEvent[]>('query_events', { sessionId, query }),
+  });
+}
@@ -1,5 +1,6 @@
 import { useEffect } from 'react';
 import { listen } from '@tauri-apps/api/event';
+import type { CaptureSessionEvent, LogRecord, PipelineAlert, PipelineMetricsEvent } from '../types/nodes';
 
 interface PipelineStatusEvent {
   id: string;
@@ -20,3 +21,59 @@ export function usePipelineStatusEvents(
     };
   }, [callback]);
 }
+
+export function usePipelineMetricsEvents(
+  callback: (event: PipelineMetricsEvent) => void
+) {
+  useEffect(() => {
+    const unlisten = listen<PipelineMetricsEvent>('pipeline-metrics', (event) => {
+      callback(event.payload);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [callback]);
+}
+
+export function usePipelineAlertEvents(
+  callback: (alert: PipelineAlert) => void
+) {
+  useEffect(() => {
+    const unlisten = listen<PipelineAlert>('pipeline-alert', (event) => {
+      callback(event.payload);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [callback]);
+}
+
+export function useCaptureSessionEvents(
+  callback: (event: CaptureSessionEvent) => void
+) {
+  useEffect(() => {
+    const unlisten = listen<CaptureSessionEvent>('capture-session', (event) => {
+      callback(event.payload);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [callback]);
+}
+
+export function useLogRecordEvents(
+  callback: (record: LogRecord) => void
+) {
+  useEffect(() => {
+    const unlisten = listen<LogRecord>('log-record', (event) => {
+      callback(event.payload);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [callback]);
+}
@@ -19,7 +19,10 @@ export function useVisualizationReader() {
         const mmapData = await invoke<number[]>('get_ringbuffer_data');
         const buffer = new Uint8Array(mmapData);
 
-        const newReader = new RingBufferReader(buffer);
+        // The backend snapshot is only returned once its write epoch held
+        // across the copy, so the header's epoch is the epoch after it
+        const epochAfter = new DataView(buffer.buffer).getBigUint64(56, true);
+        const newReader = new RingBufferReader(buffer, epochAfter);
         currentReader = newReader;
 
         if (mounted) {
@@ -4,15 +4,18 @@ import { Card } from '../components/ui/card';
 import FlowEditor from '../components/FlowEditor/FlowEditor';
 import NodePalette from '../components/NodePalette/NodePalette';
 import { NodePropertiesPanel } from '../components/NodePropertiesPanel';
+import { LogConsole } from '../components/LogConsole';
 import { useFlowStore } from '../stores/flowStore';
-import { useDeployGraph, useKernelStatus } from '../hooks/useTauriCommands';
-import { usePipelineStatusEvents } from '../hooks/useTauriEvents';
+import { useDeployGraph, useKernelStatus, useValidateGraph } from '../hooks/useTauriCommands';
+import { CommandError } from '../utils/errors';
+import { usePipelineAlertEvents, usePipelineStatusEvents } from '../hooks/useTauriEvents';
 import { useKeyboardShortcuts } from '../hooks/useKeyboardShortcuts';
 import { AlertCircle, Lock, Unlock } from 'lucide-react';
 
 export function ProcessConfiguration() {
   const [lastStatus, setLastStatus] = useState<string>('');
   const [editMode, setEditMode] = useState(false);
+  const [pipelineId, setPipelineId] = useState<string | undefined>();
 
   const exportGraph = useFlowStore((state) => state.exportGraph);
   const undo = useFlowStore((state) => state.undo);
@@ -20,13 +23,27 @@ export function ProcessConfiguration() {
   const canUndo = useFlowStore((state) => state.canUndo());
   const canRedo = useFlowStore((state) => state.canRedo());
 
+  const [logOpen, setLogOpen] = useState(false);
   const deployMutation = useDeployGraph();
+  const validateMutation = useValidateGraph();
   const { data: kernelStatus } = useKernelStatus();
 
   usePipelineStatusEvents((event) => {
     setLastStatus(`Pipeline ${event.id}: ${event.state}`);
   });
 
+  // Alerts routed to the app show as desktop notifications when allowed
+  usePipelineAlertEvents((alert) => {
+    setLastStatus(`⚠ ${alert.name} (${alert.severity}): ${alert.message}`);
+    if (!('Notification' in window)) return;
+    const notify = () => new Notification(`${alert.name} (${alert.severity})`, { body: alert.message });
+    if (Notification.permission === 'granted') {
+      notify();
+    } else if (Notification.permission === 'default') {
+      Notification.requestPermission().then((permission) => permission === 'granted' && notify());
+    }
+  });
+
   const isKernelRunning = kernelStatus?.status === 'Running' || kernelStatus?.status === 'Initializing';
   const canEdit = editMode && !isKernelRunning;
 
@@ -36,20 +53,33 @@ export function ProcessConfiguration() {
   const handleDeploy = async () => {
     const graph = exportGraph();
     try {
-      const pipelineId = await deployMutation.mutateAsync(graph);
-      console.log('Deployed pipeline:', pipelineId);
-      setLastStatus(`Successfully deployed pipeline: ${pipelineId}`);
+      // Report every invalid field at once rather than the first deploy error
+      const issues = await validateMutation.mutateAsync(graph);
+      if (issues.length > 0) {
+        const details = issues.map((issue) => `${issue.path}: ${issue.message}`).join('; ');
+        setLastStatus(`❌ ${issues.length} invalid field(s). ${details}`);
+        return;
+      }
+
+      // Redeploying replaces the previously deployed pipeline
+      const deployedId = await deployMutation.mutateAsync({ graph, id: pipelineId });
+      console.log('Deployed pipeline:', deployedId);
+      setPipelineId(deployedId);
+      setLastStatus(`Successfully deployed pipeline: ${deployedId}`);
     } catch (error) {
       const errorMessage = error instanceof Error ? error.message : String(error);
       console.error('Deploy failed:', error);
 
       // Show user-friendly error
-      if (errorMessage.includes('Graph translation failed')) {
-        setLastStatus('❌ Invalid graph structure. Please check node connections.');
-      } else if (errorMessage.includes('Pipeline creation failed')) {
-        setLastStatus('❌ Failed to create pipeline. Check node configurations.');
-      } else if (errorMessage.includes('Unknown node type')) {
+      const kind = error instanceof CommandError ? error.kind : undefined;
+      if (errorMessage.includes('Unknown node type')) {
         setLastStatus('❌ Unknown node type. This node may not be registered.');
+      } else if (kind === 'graph') {
+        setLastStatus(`❌ Invalid graph structure. Please check node connections: ${errorMessage}`);
+      } else if (kind === 'config') {
+        setLastStatus(`❌ Invalid node configuration. Check node settings: ${errorMessage}`);
+      } else if (kind === 'device') {
+        setLastStatus(`❌ Device unavailable. Check the device profile and that no other pipeline holds it: ${errorMessage}`);
       } else {
         setLastStatus(`❌ Deploy failed: ${errorMessage}`);
       }
@@ -161,6 +191,11 @@ export function ProcessConfiguration() {
         {canEdit && <NodePropertiesPanel />}
       </div>
 
+      {/* Backend warnings and errors */}
+      <div className="bg-slate-800 border-t border-slate-700 py-1">
+        <LogConsole open={logOpen} onToggle={() => setLogOpen(!logOpen)} />
+      </div>
+
       {/* Status Bar */}
       <div className="h-8 bg-slate-800 border-t border-slate-700 flex items-center px-4">
         <span className={`text-sm ${
@@ -1,12 +1,13 @@
 import { create } from 'zustand';
 import { addEdge, applyNodeChanges, applyEdgeChanges } from '@xyflow/react';
 import type { Node, Edge, Connection, NodeChange, EdgeChange } from '@xyflow/react';
-import type { NodeMetadata } from '../types/nodes';
+import type { ErrorPolicy, NodeMetadata } from '../types/nodes';
 
 interface FlowNodeData extends Record<string, unknown> {
   label: string;
   metadata: NodeMetadata;
   parameters: Record<string, unknown>;
+  error_policy?: ErrorPolicy;
 }
 
 type FlowNode = Node<FlowNodeData>;
@@ -195,6 +196,7 @@ export const useFlowStore = create<FlowState>((set, get) => ({
           // Include device_profile_id if present
           ...((n.data as any).device_profile_id ? { device_profile_id: (n.data as any).device_profile_id } : {}),
         },
+        ...(n.data.error_policy ? { error_policy: n.data.error_policy } : {}),
       })),
       edges: edges.map((e) => ({
         id: e.id,
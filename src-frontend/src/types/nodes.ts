@@ -1,18 +1,160 @@
+export type PortMultiplicity =
+  | { kind: 'single' }
+  | { kind: 'variadic'; min: number; max: number };
+
 export interface PortMetadata {
   id: string;
   name: string;
   data_type: string;
+  multiplicity?: PortMultiplicity;
 }
 
 export interface NodeMetadata {
   id: string;
   name: string;
   category: string;
+  version?: number;
+  deprecated?: boolean;
   inputs: PortMetadata[];
   outputs: PortMetadata[];
   parameters: Record<string, any>;
 }
 
+export type ErrorPolicyName =
+  | 'propagate'
+  | 'skip_frame'
+  | 'substitute_silence'
+  | 'restart'
+  | 'circuit_breaker';
+
+/** How a node's processing errors are handled; restart settings default to exponential backoff */
+export type ErrorPolicy =
+  | ErrorPolicyName
+  | {
+      type: ErrorPolicyName;
+      strategy?: 'never' | 'immediate' | 'exponential' | 'circuit_breaker';
+      base_ms?: number;
+      max_ms?: number;
+      max_attempts?: number;
+      error_threshold?: number;
+      timeout_ms?: number;
+      open_ms?: number;
+    };
+
+/** Payload of the `circuit-breaker` event */
+export interface CircuitBreakerEvent {
+  pipeline_id: string;
+  node_id: string;
+  state: 'Closed' | 'Open' | 'HalfOpen';
+  consecutive_errors: number;
+  error: string | null;
+}
+
+/** Payload of the `pipeline-alert` event, an alert raised by a node */
+export interface PipelineAlert {
+  timestamp_ms: number;
+  name: string;
+  severity: 'info' | 'warning' | 'critical';
+  message: string;
+  node_id: string;
+  pipeline_id?: string;
+  /** Repeats dropped by the debounce since the alert was last shown */
+  suppressed: number;
+}
+
+export interface NodeThroughput {
+  node_id: string;
+  frames_processed: number;
+  frames_per_sec: number;
+  errors_count: number;
+  restarts_count: number;
+  frames_dropped: number;
+  frames_missing: number;
+  frames_out_of_order: number;
+  /** Frames emitted with `triggered` set */
+  triggers: number;
+  /** Frames flagged with `quality_issue`, e.g. for clipping or dropouts */
+  quality_issues: number;
+  avg_latency_us: number;
+}
+
+export interface ChannelOccupancy {
+  /** `<node>` for a node's input, `<from> -> <to>` for a lossy edge queue */
+  channel: string;
+  node_id: string;
+  queued: number;
+  capacity: number;
+  queued_bytes: number;
+}
+
+export interface PoolUsage {
+  idle_buffers: number;
+  max_idle_buffers: number;
+  allocations: number;
+}
+
+/** Result of `get_resource_usage` */
+export interface ResourceUsage {
+  frames_in_flight: number;
+  bytes_in_flight: number;
+  /** Fullest first */
+  channels: ChannelOccupancy[];
+  frame_pool: PoolUsage;
+  process_rss_bytes: number | null;
+  system_total_bytes: number | null;
+  system_available_bytes: number | null;
+}
+
+/** Latency of a pipeline's slowest source-to-sink path, by contributor */
+export interface LatencyBudget {
+  /** Node ids along the path, source first */
+  path: string[];
+  frame_size: number;
+  sample_rate: number;
+  /** Device packet buffers, or one generated frame for sources without a device */
+  device_ms: number;
+  /** Samples held by re-blocking edges until their blocks are complete */
+  block_ms: number;
+  /** Frames the input channels along the path hold when full */
+  channel_ms: number;
+  /** Frames queued on those channels right now */
+  queued_ms: number;
+  /** Worst case: device, block and full channel latency */
+  total_ms: number;
+}
+
+/** Result of `get_pipeline_metrics` */
+export interface PipelineMetricsReport {
+  nodes: NodeThroughput[];
+  latency: LatencyBudget;
+}
+
+/** Payload of the `pipeline-metrics` event */
+export interface PipelineMetricsEvent {
+  pipeline_id: string;
+  frames_processed: number;
+  elapsed_ms: number;
+  nodes: NodeThroughput[];
+}
+
+/** When a capture session starts and what ends it; see `start_session` */
+export interface SessionPlan {
+  /** Wall-clock start in milliseconds since the epoch, as from `Date.getTime()` */
+  start_at_ms?: number;
+  duration_ms?: number;
+  max_triggers?: number;
+}
+
+/** Payload of the `capture-session` event */
+export type CaptureSessionEvent = { pipeline_id: string } & (
+  | { type: 'countdown'; remaining_ms: number }
+  | { type: 'started' }
+  | { type: 'progress'; elapsed_ms: number; triggers: number; progress: number }
+  | { type: 'completed'; elapsed_ms: number; triggers: number }
+  | { type: 'cancelled'; elapsed_ms: number; triggers: number }
+  | { type: 'failed'; error: string }
+);
+
 export interface GraphNode {
   id: string;
   type: string;
@@ -21,6 +163,7 @@ export interface GraphNode {
     label: string;
     metadata: NodeMetadata;
     parameters: Record<string, any>;
+    error_policy?: ErrorPolicy;
   };
 }
 
@@ -32,15 +175,119 @@ export interface GraphEdge {
   targetHandle: string;
 }
 
+/** Reusable node group, placed in graphs as a `Subgraph` node */
+export interface SubgraphDefinition {
+  description?: string;
+  nodes: any[];
+  connections?: any[];
+  inputs?: Record<string, { node: string; port?: string }>;
+  outputs?: Record<string, { node: string; port?: string }>;
+}
+
 export interface GraphJson {
   nodes: any[];
   edges: any[];
+  subgraphs?: Record<string, SubgraphDefinition>;
 }
 
+export type GraphPatch =
+  | { op: 'add_node'; node: any }
+  | { op: 'remove_node'; id: string }
+  | { op: 'connect'; edge: any }
+  | { op: 'disconnect'; source: string; target: string };
+
 export interface PipelineStatus {
   id: string;
   state: string;
   error?: string;
 }
 
+/** A frame a node failed to process, kept for inspection and re-injection */
+export interface DeadLetterInfo {
+  id: number;
+  node_id: string;
+  error: string;
+  failed_at: number;
+  timestamp: number;
+  sequence_id: number;
+  channels: [string, number][];
+}
+
 export type PipelineAction = 'start' | 'stop' | 'pause';
+
+/** Error returned by pipeline and device commands */
+export type AudiotabError =
+  | { kind: 'device'; device: string | null; message: string }
+  | { kind: 'config'; node: string | null; message: string }
+  | { kind: 'graph'; message: string }
+  | { kind: 'conversion'; message: string }
+  | { kind: 'node'; node: string; message: string }
+  | { kind: 'state'; message: string }
+  | { kind: 'io'; message: string }
+  | { kind: 'other'; message: string };
+
+export type AudiotabErrorKind = AudiotabError['kind'];
+
+/** A backend log record at WARN or above, from the `log-record` event */
+export interface LogRecord {
+  level: 'ERROR' | 'WARN' | 'INFO' | 'DEBUG' | 'TRACE';
+  target: string;
+  message: string;
+  /** Fields of the record and its spans, e.g. pipeline, node, device */
+  fields: Record<string, unknown>;
+  spans: string[];
+  timestamp_ms: number;
+}
+
+/** A problem with one field of a graph, found before deployment */
+export interface FieldError {
+  /** Dotted path, e.g. "nodes.gain.config.gain_db" */
+  path: string;
+  node?: string;
+  message: string;
+}
+
+/** Kind of an entry in the session event log */
+export type EventKind =
+  | 'session_started'
+  | 'session_ended'
+  | 'pipeline_deployed'
+  | 'pipeline_state'
+  | 'device_started'
+  | 'device_stopped'
+  | 'calibration_applied'
+  | 'capture_started'
+  | 'capture_ended'
+  | 'overrun'
+  | 'warning'
+  | 'error';
+
+/** An entry of the persistent session event log */
+export interface LoggedEvent {
+  timestamp_ms: number;
+  kind: EventKind;
+  pipeline_id?: string;
+  device_id?: string;
+  message: string;
+  /** Details that depend on the kind, such as calibration values */
+  data?: unknown;
+}
+
+/** Filter for reading the event log; omitted fields match everything */
+export interface EventQuery {
+  kinds?: EventKind[];
+  pipeline_id?: string;
+  device_id?: string;
+  since_ms?: number;
+  until_ms?: number;
+  /** Keep only the latest this many matches */
+  limit?: number;
+}
+
+/** A recorded event log session, one per app run */
+export interface EventSessionInfo {
+  session_id: string;
+  started_ms: number | null;
+  ended_ms: number | null;
+  event_count: number;
+}
@@ -0,0 +1,34 @@
+import type { AudiotabError, AudiotabErrorKind } from '../types/nodes';
+
+/** A failed Tauri command, keeping the backend's error kind */
+export class CommandError extends Error {
+  readonly kind: AudiotabErrorKind;
+  readonly detail: AudiotabError;
+
+  constructor(detail: AudiotabError) {
+    super(detail.message);
+    this.name = 'CommandError';
+    this.kind = detail.kind;
+    this.detail = detail;
+  }
+}
+
+function isAudiotabError(value: unknown): value is AudiotabError {
+  return typeof value === 'object' && value !== null
+    && typeof (value as AudiotabError).kind === 'string'
+    && typeof (value as AudiotabError).message === 'string';
+}
+
+/**
+ * Converts what `invoke` rejects with into an Error
+ * @param error - A structured backend error, an Error or a plain message
+ */
+export function toCommandError(error: unknown): Error {
+  if (error instanceof Error) {
+    return error;
+  }
+  if (isAudiotabError(error)) {
+    return new CommandError(error);
+  }
+  return new Error(String(error));
+}
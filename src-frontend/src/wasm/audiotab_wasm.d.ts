@@ -6,7 +6,7 @@ export class RingBufferReader {
   get_waveform(channel: number, num_points: number): Float64Array;
   get_spectrogram(channel: number, window_size: number, hop_size: number, num_windows: number): Float64Array;
   get_write_sequence(): bigint;
-  constructor(buffer: Uint8Array);
+  constructor(buffer: Uint8Array, epoch_after: bigint);
   readonly sample_rate: bigint;
   readonly channels: number;
 }
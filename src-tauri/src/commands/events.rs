@@ -0,0 +1,25 @@
+use crate::state::AppState;
+use audiotab::core::AudiotabError;
+use audiotab::observability::{list_sessions, read_session, EventQuery, LoggedEvent, SessionInfo};
+use tauri::State;
+
+/// Recorded event log sessions, newest first; the current one included
+#[tauri::command]
+pub fn list_event_sessions(state: State<'_, AppState>) -> Result<Vec<SessionInfo>, AudiotabError> {
+    list_sessions(&state.event_log_dir)
+        .map_err(|e| AudiotabError::other(format!("Failed to list event logs: {:#}", e)))
+}
+
+/// Events of a session matching `query`, oldest first
+///
+/// Without `session_id` the current app run's session is read.
+#[tauri::command]
+pub fn query_events(
+    state: State<'_, AppState>,
+    session_id: Option<String>,
+    query: Option<EventQuery>,
+) -> Result<Vec<LoggedEvent>, AudiotabError> {
+    let session_id = session_id.unwrap_or_else(|| state.event_log.session_id().to_string());
+    read_session(&state.event_log_dir, &session_id, &query.unwrap_or_default())
+        .map_err(|e| AudiotabError::other(format!("Failed to read event log: {:#}", e)))
+}
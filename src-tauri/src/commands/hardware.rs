@@ -1,11 +1,12 @@
 use tauri::State;
 use crate::state::AppState;
-use audiotab::hal::{DeviceInfo, DeviceProfile};
+use audiotab::core::AudiotabError;
+use audiotab::hal::{DeviceInfo, DeviceProfile, FrequencyResponse};
 
 #[tauri::command]
 pub async fn discover_devices(
     state: State<'_, AppState>,
-) -> Result<Vec<DeviceInfo>, String> {
+) -> Result<Vec<DeviceInfo>, AudiotabError> {
     // We need to avoid holding the lock across the await point
     // The manager.discover_all() needs a &self reference, so we need to restructure
 
@@ -19,21 +20,21 @@ pub async fn discover_devices(
     // For now, use tokio::task::spawn_blocking workaround
     tokio::task::spawn_blocking(move || {
         let manager = manager_arc.lock()
-            .map_err(|e| format!("Device manager lock poisoned: {}", e))?;
+            .map_err(|e| AudiotabError::other(format!("Device manager lock poisoned: {}", e)))?;
         // This is still async, so we need to block on it
         tokio::runtime::Handle::current().block_on(manager.discover_all())
-            .map_err(|e| format!("Device discovery failed: {}", e))
+            .map_err(|e| e.context("Device discovery failed"))
     })
     .await
-    .map_err(|e| format!("Task join failed: {}", e))?
+    .map_err(|e| AudiotabError::other(format!("Task join failed: {}", e)))?
 }
 
 #[tauri::command]
 pub fn list_device_profiles(
     state: State<'_, AppState>,
-) -> Result<Vec<DeviceProfile>, String> {
+) -> Result<Vec<DeviceProfile>, AudiotabError> {
     let manager = state.device_manager.lock()
-        .map_err(|e| format!("Device manager lock poisoned: {}", e))?;
+        .map_err(|e| AudiotabError::other(format!("Device manager lock poisoned: {}", e)))?;
     Ok(manager.list_profiles().into_iter().cloned().collect())
 }
 
@@ -41,47 +42,61 @@ pub fn list_device_profiles(
 pub fn get_device_profile(
     state: State<'_, AppState>,
     id: String,
-) -> Result<DeviceProfile, String> {
+) -> Result<DeviceProfile, AudiotabError> {
     let manager = state.device_manager.lock()
-        .map_err(|e| format!("Device manager lock poisoned: {}", e))?;
+        .map_err(|e| AudiotabError::other(format!("Device manager lock poisoned: {}", e)))?;
 
     manager.get_profile(&id)
         .cloned()
-        .ok_or_else(|| format!("Profile {} not found", id))
+        .ok_or_else(|| AudiotabError::device(&id, format!("Profile {} not found", id)))
 }
 
 #[tauri::command]
 pub fn add_device_profile(
     state: State<'_, AppState>,
     profile: DeviceProfile,
-) -> Result<(), String> {
+) -> Result<(), AudiotabError> {
     let mut manager = state.device_manager.lock()
-        .map_err(|e| format!("Device manager lock poisoned: {}", e))?;
+        .map_err(|e| AudiotabError::other(format!("Device manager lock poisoned: {}", e)))?;
 
     manager.add_profile(profile)
-        .map_err(|e| format!("Failed to add profile: {}", e))
+        .map_err(|e| e.context("Failed to add profile"))
 }
 
 #[tauri::command]
 pub fn update_device_profile(
     state: State<'_, AppState>,
     profile: DeviceProfile,
-) -> Result<(), String> {
+) -> Result<(), AudiotabError> {
     let mut manager = state.device_manager.lock()
-        .map_err(|e| format!("Device manager lock poisoned: {}", e))?;
+        .map_err(|e| AudiotabError::other(format!("Device manager lock poisoned: {}", e)))?;
 
     manager.update_profile(profile)
-        .map_err(|e| format!("Failed to update profile: {}", e))
+        .map_err(|e| e.context("Failed to update profile"))
 }
 
 #[tauri::command]
 pub fn delete_device_profile(
     state: State<'_, AppState>,
     id: String,
-) -> Result<(), String> {
+) -> Result<(), AudiotabError> {
     let mut manager = state.device_manager.lock()
-        .map_err(|e| format!("Device manager lock poisoned: {}", e))?;
+        .map_err(|e| AudiotabError::other(format!("Device manager lock poisoned: {}", e)))?;
 
     manager.delete_profile(&id)
-        .map_err(|e| format!("Failed to delete profile: {}", e))
+        .map_err(|e| e.context("Failed to delete profile"))
+}
+
+/// Attach a microphone calibration file (e.g. miniDSP .txt) to a profile
+#[tauri::command]
+pub fn import_frequency_response(
+    state: State<'_, AppState>,
+    id: String,
+    path: String,
+) -> Result<FrequencyResponse, AudiotabError> {
+    let mut manager = state.device_manager.lock()
+        .map_err(|e| AudiotabError::other(format!("Device manager lock poisoned: {}", e)))?;
+
+    manager.import_frequency_response(&id, std::path::Path::new(&path))
+        .map_err(|e| e.context("Failed to import frequency response"))
 }
@@ -25,17 +25,17 @@ pub struct KernelStatusResponse {
 /// const response = await invoke('start_kernel');
 /// ```
 #[tauri::command]
-pub fn start_kernel(
+#[tracing::instrument(name = "kernel", skip_all)]
+pub async fn start_kernel(
     kernel_manager: State<'_, KernelManager>,
 ) -> Result<KernelStatusResponse, String> {
-    // Use the synchronous start_kernel method
-    // The kernel startup will happen in a background task managed by KernelManager
     kernel_manager
-        .start_kernel_sync()
+        .start_kernel()
+        .await
         .map_err(|e| format!("Failed to start kernel: {}", e))?;
 
-    let status = kernel_manager.get_status_sync();
-    let active_devices = kernel_manager.get_active_device_count_sync();
+    let status = kernel_manager.get_status().await;
+    let active_devices = kernel_manager.get_active_device_count().await;
 
     Ok(KernelStatusResponse {
         status,
@@ -57,16 +57,17 @@ pub fn start_kernel(
 /// const response = await invoke('stop_kernel');
 /// ```
 #[tauri::command]
-pub fn stop_kernel(
+#[tracing::instrument(name = "kernel", skip_all)]
+pub async fn stop_kernel(
     kernel_manager: State<'_, KernelManager>,
 ) -> Result<KernelStatusResponse, String> {
-    // Use the synchronous stop_kernel method
     kernel_manager
-        .stop_kernel_sync()
+        .stop_kernel()
+        .await
         .map_err(|e| format!("Failed to stop kernel: {}", e))?;
 
-    let status = kernel_manager.get_status_sync();
-    let active_devices = kernel_manager.get_active_device_count_sync();
+    let status = kernel_manager.get_status().await;
+    let active_devices = kernel_manager.get_active_device_count().await;
 
     Ok(KernelStatusResponse {
         status,
@@ -86,11 +87,11 @@ pub fn stop_kernel(
 /// console.log(response.active_devices); // e.g., 2
 /// ```
 #[tauri::command]
-pub fn get_kernel_status(
+pub async fn get_kernel_status(
     kernel_manager: State<'_, KernelManager>,
 ) -> Result<KernelStatusResponse, String> {
-    let status = kernel_manager.get_status_sync();
-    let active_devices = kernel_manager.get_active_device_count_sync();
+    let status = kernel_manager.get_status().await;
+    let active_devices = kernel_manager.get_active_device_count().await;
 
     Ok(KernelStatusResponse {
         status,
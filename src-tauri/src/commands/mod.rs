@@ -1,5 +1,8 @@
+pub mod events;
 pub mod hardware;
 pub mod kernel;
 pub mod nodes;
 pub mod pipeline;
+pub mod project;
+pub mod session;
 pub mod visualization;
@@ -1,7 +1,52 @@
 use crate::state::{AppState, NodeMetadata};
+use audiotab::registry::{schema, NodePreset};
 use tauri::State;
 
 #[tauri::command]
 pub fn get_node_registry(state: State<AppState>) -> Vec<NodeMetadata> {
     state.registry.list_nodes()
 }
+
+/// JSON Schemas of each node type's config and of the pipeline and
+/// hardware config files
+#[tauri::command]
+pub fn get_config_schemas() -> serde_json::Value {
+    serde_json::json!({
+        "nodes": schema::node_schemas(),
+        "pipeline": schema::pipeline_schema(),
+        "hardware": schema::hardware_config_schema(),
+    })
+}
+
+#[tauri::command]
+pub fn get_node_presets(
+    state: State<'_, AppState>,
+    node_type: String,
+) -> Result<Vec<NodePreset>, String> {
+    let store = state.preset_store.lock()
+        .map_err(|e| format!("Preset store lock poisoned: {}", e))?;
+    Ok(store.list(&node_type))
+}
+
+#[tauri::command]
+pub fn save_node_preset(
+    state: State<'_, AppState>,
+    preset: NodePreset,
+) -> Result<(), String> {
+    let mut store = state.preset_store.lock()
+        .map_err(|e| format!("Preset store lock poisoned: {}", e))?;
+    store.save_preset(preset)
+        .map_err(|e| format!("Failed to save preset: {}", e))
+}
+
+#[tauri::command]
+pub fn delete_node_preset(
+    state: State<'_, AppState>,
+    node_type: String,
+    name: String,
+) -> Result<(), String> {
+    let mut store = state.preset_store.lock()
+        .map_err(|e| format!("Preset store lock poisoned: {}", e))?;
+    store.delete_preset(&node_type, &name)
+        .map_err(|e| format!("Failed to delete preset: {}", e))
+}
@@ -1,14 +1,35 @@
 use crate::state::{AppState, PipelineHandle};
-use crate::graph::translate_graph;
-use audiotab::engine::{AsyncPipeline, PipelineState};
+use crate::graph::{translate_edge, translate_graph, translate_node};
+use audiotab::core::AudiotabError;
+use audiotab::engine::{expand_subgraphs, subgraph::SUBGRAPH_NODE_TYPE, AsyncPipeline, FieldError, LatencyBudget, PipelineState};
+use audiotab::observability::{
+    Alert, AlertRouter, AlertSink, EventKind, LoggedEvent, NodeThroughput, PipelineEvent, PipelineMetrics, ResourceUsage,
+    Severity,
+};
+use audiotab::resilience::{CircuitEvent, DeadLetterInfo};
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Emitter, State};
+use tauri::{AppHandle, Emitter, Manager, State};
 use std::sync::{Arc, Mutex};
 
 #[derive(Debug, Deserialize)]
 pub struct GraphJson {
     pub nodes: Vec<serde_json::Value>,
     pub edges: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub subgraphs: serde_json::Value,
+    /// Pipeline settings such as `alerts`, see `AsyncPipeline::from_json`
+    #[serde(default)]
+    pub pipeline_config: serde_json::Value,
+}
+
+/// One edit to a deployed graph, with nodes and edges in the frontend format
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum GraphPatch {
+    AddNode { node: serde_json::Value },
+    RemoveNode { id: String },
+    Connect { edge: serde_json::Value },
+    Disconnect { source: String, target: String },
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -25,6 +46,49 @@ pub struct PipelineStatusEvent {
     pub error: Option<String>,
 }
 
+/// Result of `get_pipeline_metrics`
+#[derive(Debug, Serialize, Clone)]
+pub struct PipelineMetricsReport {
+    pub nodes: Vec<NodeThroughput>,
+    /// End-to-end latency of the slowest path through the pipeline
+    pub latency: LatencyBudget,
+}
+
+/// A node's circuit breaker tripped, half-opened or closed
+#[derive(Debug, Serialize, Clone)]
+pub struct CircuitBreakerEvent {
+    pub pipeline_id: String,
+    #[serde(flatten)]
+    pub event: CircuitEvent,
+}
+
+/// Passes routed alerts to the frontend as `pipeline-alert` events, which
+/// it shows as notifications
+struct AlertNotifier {
+    app: AppHandle,
+    min_severity: Severity,
+}
+
+#[async_trait::async_trait]
+impl AlertSink for AlertNotifier {
+    fn min_severity(&self) -> Severity {
+        self.min_severity
+    }
+
+    async fn deliver(&self, alert: &Alert) -> anyhow::Result<()> {
+        self.app.emit("pipeline-alert", alert)?;
+        Ok(())
+    }
+}
+
+/// Periodic counters of a running pipeline
+#[derive(Debug, Serialize, Clone)]
+pub struct PipelineMetricsEvent {
+    pub pipeline_id: String,
+    #[serde(flatten)]
+    pub metrics: PipelineMetrics,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum PipelineAction {
@@ -34,16 +98,19 @@ pub enum PipelineAction {
 }
 
 #[tauri::command]
+#[tracing::instrument(name = "pipeline", skip_all, fields(pipeline = tracing::field::Empty))]
 pub async fn deploy_graph(
     app: AppHandle,
     state: State<'_, AppState>,
+    kernel_manager: State<'_, crate::kernel_manager::KernelManager>,
     graph: GraphJson,
-) -> Result<String, String> {
-    // Generate unique pipeline ID
-    let pipeline_id = format!("pipeline_{}", uuid::Uuid::new_v4());
+    id: Option<String>,
+) -> Result<String, AudiotabError> {
+    // Redeploying under an existing ID replaces that pipeline
+    let pipeline_id = id.unwrap_or_else(|| format!("pipeline_{}", uuid::Uuid::new_v4()));
+    tracing::Span::current().record("pipeline", pipeline_id.as_str());
 
-    println!("Deploying graph with {} nodes, {} edges",
-             graph.nodes.len(), graph.edges.len());
+    tracing::info!(nodes = graph.nodes.len(), edges = graph.edges.len(), "Deploying graph");
 
     // Emit deploying status
     let _ = app.emit("pipeline-status", PipelineStatusEvent {
@@ -55,158 +122,169 @@ pub async fn deploy_graph(
     // Step 1: Translate frontend graph to backend format
     let frontend_json = serde_json::json!({
         "nodes": graph.nodes,
-        "edges": graph.edges
+        "edges": graph.edges,
+        "subgraphs": graph.subgraphs,
+        "pipeline_config": graph.pipeline_config
     });
 
     let backend_json = match translate_graph(frontend_json) {
         Ok(json) => json,
         Err(e) => {
-            let error_msg = format!("Graph translation failed: {}", e);
-            println!("Translation error: {}", error_msg);
+            let error = AudiotabError::graph(format!("Graph translation failed: {}", e));
+            tracing::error!(kind = error.kind(), "{}", error);
+            emit_deploy_error(&app, &pipeline_id, &error);
+            return Err(error);
+        }
+    };
 
-            let _ = app.emit("pipeline-status", PipelineStatusEvent {
-                id: pipeline_id.clone(),
-                state: "Error".to_string(),
-                error: Some(error_msg.clone()),
-            });
+    tracing::debug!(graph = %backend_json, "Translated graph");
 
-            return Err(error_msg);
+    // Subgraph instances become their nodes, so presets inside them resolve too
+    let mut backend_json = match expand_subgraphs(backend_json) {
+        Ok(json) => json,
+        Err(e) => {
+            let error = AudiotabError::graph(format!("Subgraph expansion failed: {}", e));
+            tracing::error!(kind = error.kind(), "{}", error);
+            emit_deploy_error(&app, &pipeline_id, &error);
+            return Err(error);
         }
     };
 
-    println!("Translated graph: {}", serde_json::to_string_pretty(&backend_json).unwrap());
+    // Resolve preset references against built-in and user presets
+    let resolved = state.preset_store.lock()
+        .map_err(|e| anyhow::anyhow!("Preset store lock poisoned: {}", e))
+        .and_then(|store| store.resolve_pipeline(&mut backend_json));
+    if let Err(e) = resolved {
+        let error = AudiotabError::config(format!("Preset resolution failed: {}", e));
+        tracing::error!(kind = error.kind(), "{}", error);
+        emit_deploy_error(&app, &pipeline_id, &error);
+        return Err(error);
+    }
+
+    // Sink directories are checked for crashed recordings on the next launch
+    state.recording_dirs.remember(&backend_json);
+
+    // Node ids and types for the event log, before the graph is consumed
+    let deployed_nodes: Vec<serde_json::Value> = backend_json["nodes"]
+        .as_array()
+        .map(|nodes| {
+            nodes.iter()
+                .map(|node| serde_json::json!({ "id": node["id"], "type": node["type"] }))
+                .collect()
+        })
+        .unwrap_or_default();
 
     // Step 2: Create AsyncPipeline from translated graph
     let mut pipeline = match AsyncPipeline::from_json(backend_json).await {
         Ok(p) => p,
         Err(e) => {
-            let error_msg = format!("Pipeline creation failed: {}", e);
-            println!("Pipeline creation error: {}", error_msg);
-
-            // Emit error event
-            let _ = app.emit("pipeline-status", PipelineStatusEvent {
-                id: pipeline_id.clone(),
-                state: "Error".to_string(),
-                error: Some(error_msg.clone()),
-            });
-
-            return Err(error_msg);
+            let error = e.context("Pipeline creation failed");
+            tracing::error!(kind = error.kind(), "{}", error);
+            emit_deploy_error(&app, &pipeline_id, &error);
+            return Err(error);
         }
     };
 
     // Step 3: Inject RingBuffer into visualization-capable nodes
     pipeline.set_ring_buffer(state.ring_buffer.clone());
 
-    // Step 4: Inject DeviceChannels into AudioSourceNodes with device_profile_id
-    let mut started_devices = Vec::new(); // Track successfully started devices
-
-    let device_injection_results: Vec<Result<(), String>> = {
-        let mut results = Vec::new();
-
-        for (node_id, node) in pipeline.nodes_mut().iter_mut() {
-            if let Some(audio_source) = node.as_any_mut()
-                .downcast_mut::<audiotab::nodes::AudioSourceNode>()
-            {
-                let device_profile_id = audio_source.device_profile_id.clone();
-
-                if !device_profile_id.is_empty() {
-                    println!("AudioSourceNode '{}' requests device profile '{}'", node_id, device_profile_id);
-
-                    // Async device creation and channel injection
-                    let manager_arc = state.device_manager.clone();
-                    let device_id_for_closure = device_profile_id.clone();
-
-                    let result = tokio::task::spawn_blocking(move || {
-                        let manager = manager_arc.lock()
-                            .map_err(|e| format!("Device manager lock poisoned: {}", e))?;
-
-                        // Create runtime for async start_device
-                        let runtime = tokio::runtime::Runtime::new()
-                            .map_err(|e| format!("Failed to create runtime: {}", e))?;
-
-                        runtime.block_on(async {
-                            manager.start_device(&device_id_for_closure).await
-                                .map_err(|e| format!("Failed to start device '{}': {}", device_id_for_closure, e))
-                        })
-                    })
-                    .await
-                    .map_err(|e| format!("Device creation task failed: {}", e))?;
-
-                    match result {
-                        Ok(_) => {
-                            started_devices.push(device_profile_id.clone());
-
-                            // Get device channels
-                            let channels = {
-                                let mut manager = state.device_manager.lock()
-                                    .map_err(|e| format!("Device manager lock poisoned: {}", e))?;
-
-                                manager.get_device_channels(&device_profile_id)
-                                    .map_err(|e| format!("Failed to get device channels: {}", e))?
-                            };
-
-                            // Inject channels into node
-                            audio_source.set_device_channels(Some(channels));
-                            println!("Successfully injected device channels for '{}'", device_profile_id);
-
-                            results.push(Ok(()));
-                        }
-                        Err(e) => {
-                            results.push(Err(e));
-                            break; // Stop processing on first failure
-                        }
-                    }
-                }
-            }
-        }
+    // Step 4: Device-bound nodes are fed by the kernel's device readers
+    // once the pipeline starts (see KernelManager::execute_pipeline)
+    for (node_id, device_id) in pipeline.device_bindings() {
+        tracing::info!(node = %node_id, device = %device_id, "Node is bound to a device");
+    }
 
-        results
-    };
+    // Keep state changes, node failures and overruns in the session's audit trail
+    state.event_log.record(
+        LoggedEvent::new(EventKind::PipelineDeployed, "Pipeline deployed")
+            .pipeline(&pipeline_id)
+            .data(serde_json::json!({
+                "nodes": deployed_nodes,
+                "device_bindings": pipeline.device_bindings(),
+            })),
+    );
+    state.event_log.follow(&pipeline_id, pipeline.subscribe_events());
+
+    // Debounce the alerts nodes raise and send them to the event log, the
+    // configured webhooks and the frontend
+    let alert_config = pipeline.alert_config();
+    match AlertRouter::new(alert_config) {
+        Ok(router) => {
+            router
+                .with_sink(Arc::new(state.event_log.clone()))
+                .with_sink(Arc::new(AlertNotifier { app: app.clone(), min_severity: alert_config.notify_severity }))
+                .follow(&pipeline_id, pipeline.subscribe_events());
+        }
+        Err(e) => tracing::warn!("Alerts will not be routed: {:#}", e),
+    }
 
-    // Check if any device injection failed - cleanup started devices if so
-    for result in device_injection_results.iter() {
-        if let Err(e) = result {
-            let error_msg = format!("Device injection failed: {}", e);
-            println!("Error: {}", error_msg);
-
-            // Cleanup: Stop all devices that were successfully started
-            let mut cleanup_handles = Vec::new();
-            for device_id in started_devices.iter() {
-                println!("Cleaning up device: {}", device_id);
-                let manager_arc = state.device_manager.clone();
-                let device_id_clone = device_id.clone();
-
-                let handle = tokio::task::spawn_blocking(move || {
-                    if let Ok(manager) = manager_arc.lock() {
-                        let runtime = tokio::runtime::Runtime::new().ok()?;
-                        runtime.block_on(async {
-                            let _ = manager.stop_device(&device_id_clone).await;
-                        });
-                    }
-                    Some(())
-                });
-                cleanup_handles.push(handle);
+    // Forward circuit breaker changes to the frontend
+    let mut circuit_events = pipeline.subscribe_circuit_events();
+    let events_app = app.clone();
+    let events_pipeline_id = pipeline_id.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match circuit_events.recv().await {
+                Ok(event) => {
+                    let _ = events_app.emit("circuit-breaker", CircuitBreakerEvent {
+                        pipeline_id: events_pipeline_id.clone(),
+                        event,
+                    });
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
             }
+        }
+    });
 
-            // Wait for all cleanup to complete
-            for handle in cleanup_handles {
-                let _ = handle.await;
+    // Forward state transitions, fatal node errors and metrics; non-fatal
+    // errors only show up in the metrics' error counts
+    let mut pipeline_events = pipeline.subscribe_events();
+    let events_app = app.clone();
+    let events_pipeline_id = pipeline_id.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match pipeline_events.recv().await {
+                Ok(PipelineEvent::StateChanged { to, .. }) => {
+                    let _ = events_app.emit("pipeline-status", PipelineStatusEvent {
+                        id: events_pipeline_id.clone(),
+                        state: to,
+                        error: None,
+                    });
+                }
+                Ok(PipelineEvent::NodeError { node_id, error, fatal: true }) => {
+                    let _ = events_app.emit("pipeline-status", PipelineStatusEvent {
+                        id: events_pipeline_id.clone(),
+                        state: "Error".to_string(),
+                        error: Some(format!("Node '{}' failed: {}", node_id, error)),
+                    });
+                }
+                // Alerts reach the frontend through the AlertRouter
+                Ok(PipelineEvent::NodeError { .. }) | Ok(PipelineEvent::Alert(_)) => {}
+                Ok(PipelineEvent::Metrics(metrics)) => {
+                    let _ = events_app.emit("pipeline-metrics", PipelineMetricsEvent {
+                        pipeline_id: events_pipeline_id.clone(),
+                        metrics,
+                    });
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
             }
+        }
+    });
 
-            let _ = app.emit("pipeline-status", PipelineStatusEvent {
-                id: pipeline_id.clone(),
-                state: "Error".to_string(),
-                error: Some(error_msg.clone()),
-            });
-
-            return Err(error_msg);
+    // Step 5: Store pipeline in state, releasing the one it replaces
+    let replaced = state.pipelines.lock().unwrap().remove(&pipeline_id);
+    if let Some(old) = replaced {
+        tracing::info!("Replacing pipeline");
+        if let Err(e) = kernel_manager.release_pipeline(old.pipeline).await {
+            tracing::warn!("Replaced pipeline failed to stop cleanly: {}", e);
         }
     }
 
-    // Step 5: Store pipeline in state
     let handle = PipelineHandle {
         id: pipeline_id.clone(),
-        pipeline: Arc::new(Mutex::new(pipeline)),
+        pipeline: Arc::new(tokio::sync::Mutex::new(pipeline)),
         state: Arc::new(Mutex::new(PipelineState::Idle)),
     };
 
@@ -222,11 +300,41 @@ pub async fn deploy_graph(
         error: None,
     });
 
-    println!("Pipeline {} created successfully", pipeline_id);
+    tracing::info!("Pipeline created");
 
     Ok(pipeline_id)
 }
 
+fn emit_deploy_error(app: &AppHandle, pipeline_id: &str, error: &AudiotabError) {
+    app.state::<AppState>().event_log.record(
+        LoggedEvent::new(EventKind::Error, format!("Deployment failed: {}", error)).pipeline(pipeline_id),
+    );
+    let _ = app.emit("pipeline-status", PipelineStatusEvent {
+        id: pipeline_id.to_string(),
+        state: "Error".to_string(),
+        error: Some(error.to_string()),
+    });
+}
+
+/// Check a graph without deploying it, returning every invalid field
+#[tauri::command]
+pub fn validate_graph(
+    state: State<'_, AppState>,
+    graph: GraphJson,
+) -> Result<Vec<FieldError>, AudiotabError> {
+    let frontend_json = serde_json::json!({
+        "nodes": graph.nodes,
+        "edges": graph.edges,
+        "subgraphs": graph.subgraphs
+    });
+    let backend_json = translate_graph(frontend_json)
+        .map_err(|e| AudiotabError::graph(format!("Graph translation failed: {}", e)))?;
+
+    let store = state.preset_store.lock()
+        .map_err(|e| AudiotabError::other(format!("Preset store lock poisoned: {}", e)))?;
+    Ok(audiotab::engine::validate_graph(&backend_json, &store))
+}
+
 #[tauri::command]
 pub fn get_all_pipeline_states(state: State<AppState>) -> Vec<PipelineStatus> {
     let pipelines = state.pipelines.lock().unwrap();
@@ -243,63 +351,175 @@ pub fn get_all_pipeline_states(state: State<AppState>) -> Vec<PipelineStatus> {
         .collect()
 }
 
+/// Shared handle to a deployed pipeline and its state
+///
+/// The map lock is released before returning, so callers can await on the pipeline.
+pub(crate) fn pipeline_handle(
+    state: &AppState,
+    id: &str,
+) -> Result<(Arc<tokio::sync::Mutex<AsyncPipeline>>, Arc<Mutex<PipelineState>>), AudiotabError> {
+    let pipelines = state.pipelines.lock().unwrap();
+    let handle = pipelines.get(id)
+        .ok_or_else(|| pipeline_not_found(id))?;
+    Ok((handle.pipeline.clone(), handle.state.clone()))
+}
+
+fn pipeline_not_found(id: &str) -> AudiotabError {
+    AudiotabError::other(format!("Pipeline {} not found", id))
+}
+
+/// Stop a pipeline and remove it from the app state
+///
+/// Its device bindings are released, and dropping it releases its nodes'
+/// handles on the visualization ring buffer. Devices keep running in the kernel.
 #[tauri::command]
-pub fn control_pipeline(
+#[tracing::instrument(name = "pipeline", skip_all, fields(pipeline = %id))]
+pub async fn delete_pipeline(
+    app: AppHandle,
     state: State<'_, AppState>,
     kernel_manager: State<'_, crate::kernel_manager::KernelManager>,
     id: String,
-    action: PipelineAction,
-) -> Result<(), String> {
-    println!("Control pipeline {}: {:?}", id, action);
+) -> Result<(), AudiotabError> {
+    let handle = state.pipelines.lock().unwrap().remove(&id)
+        .ok_or_else(|| pipeline_not_found(&id))?;
+    if let Some(session) = state.sessions.lock().unwrap().remove(&id) {
+        session.cancel();
+    }
 
-    // Get the pipeline handle
-    let pipeline_arc = {
-        let pipelines = state.pipelines.lock().unwrap();
-        let handle = pipelines.get(&id)
-            .ok_or_else(|| format!("Pipeline {} not found", id))?;
+    let result = kernel_manager.release_pipeline(handle.pipeline)
+        .await
+        .map_err(|e| AudiotabError::from(e).context(format!("Pipeline {} failed to stop cleanly", id)));
 
-        // Clone the Arc references we need
-        (handle.pipeline.clone(), handle.state.clone())
-    };
+    let _ = app.emit("pipeline-status", PipelineStatusEvent {
+        id: id.clone(),
+        state: "Deleted".to_string(),
+        error: result.as_ref().err().map(|e| e.to_string()),
+    });
+
+    tracing::info!("Pipeline deleted");
+    result
+}
+
+/// Edit a deployed pipeline in place, without redeploying it
+///
+/// Patches apply in order and stop at the first that fails; the ones before
+/// it stay applied. On a running pipeline, added nodes start at once and
+/// rewired nodes keep their state. Nodes added this way are not fed by
+/// devices until the pipeline is redeployed, and subgraph instances cannot
+/// be added.
+#[tauri::command]
+#[tracing::instrument(name = "pipeline", skip_all, fields(pipeline = %id))]
+pub async fn patch_graph(
+    state: State<'_, AppState>,
+    id: String,
+    patches: Vec<GraphPatch>,
+) -> Result<(), AudiotabError> {
+    let (pipeline, _) = pipeline_handle(&state, &id)?;
+    let mut pipeline = pipeline.lock().await;
+
+    for (index, patch) in patches.into_iter().enumerate() {
+        let result = match patch {
+            GraphPatch::AddNode { node } => {
+                if node["type"].as_str() == Some(SUBGRAPH_NODE_TYPE) {
+                    Err(AudiotabError::graph("Subgraph instances can only be added by redeploying"))
+                } else {
+                    // Resolve preset references against built-in and user presets
+                    let mut backend = serde_json::json!({ "nodes": [translate_node(&node)] });
+                    let resolved = state.preset_store.lock()
+                        .map_err(|e| anyhow::anyhow!("Preset store lock poisoned: {}", e))
+                        .and_then(|store| store.resolve_pipeline(&mut backend));
+                    match resolved {
+                        Ok(()) => pipeline.add_node(backend["nodes"][0].take()).await,
+                        Err(e) => Err(AudiotabError::config(format!("Preset resolution failed: {}", e))),
+                    }
+                }
+            }
+            GraphPatch::RemoveNode { id: node_id } => pipeline.remove_node(&node_id).await,
+            // The pipeline checks handles against the ports of the nodes
+            GraphPatch::Connect { edge } => pipeline.connect(translate_edge(&edge, true, true)),
+            GraphPatch::Disconnect { source, target } => pipeline.disconnect(&source, &target),
+        };
+        result.map_err(|e| e.context(format!("Patch {} of pipeline {} failed", index, id)))?;
+    }
+
+    tracing::info!("Pipeline patched");
+    Ok(())
+}
+
+/// Per-node latency, throughput and error counts of a deployed pipeline,
+/// with its end-to-end latency budget
+#[tauri::command]
+pub async fn get_pipeline_metrics(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<PipelineMetricsReport, AudiotabError> {
+    let (pipeline, _) = pipeline_handle(&state, &id)?;
+    let pipeline = pipeline.lock().await;
+    let monitor = pipeline.get_monitor()
+        .ok_or_else(|| AudiotabError::state(format!("Pipeline {} is starting; metrics are not available yet", id)))?;
+    Ok(PipelineMetricsReport {
+        nodes: monitor.node_metrics(),
+        latency: pipeline.latency_budget(),
+    })
+}
+
+/// Frames queued in a deployed pipeline's channels, with frame pool and memory usage
+#[tauri::command]
+pub async fn get_resource_usage(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<ResourceUsage, AudiotabError> {
+    let (pipeline, _) = pipeline_handle(&state, &id)?;
+    let pipeline = pipeline.lock().await;
+    pipeline.resource_usage()
+        .ok_or_else(|| AudiotabError::state(format!("Pipeline {} is starting; resource usage is not available yet", id)))
+}
+
+#[tauri::command]
+#[tracing::instrument(name = "pipeline", skip_all, fields(pipeline = %id))]
+pub async fn control_pipeline(
+    state: State<'_, AppState>,
+    kernel_manager: State<'_, crate::kernel_manager::KernelManager>,
+    id: String,
+    action: PipelineAction,
+) -> Result<(), AudiotabError> {
+    tracing::info!(?action, "Controlling pipeline");
+
+    let (pipeline, pipeline_state) = pipeline_handle(&state, &id)?;
 
     match action {
         PipelineAction::Start => {
             // Execute the pipeline via KernelManager
-            kernel_manager.execute_pipeline_sync(pipeline_arc.0.clone())
-                .map_err(|e| format!("Failed to execute pipeline: {}", e))?;
+            kernel_manager.execute_pipeline(pipeline)
+                .await
+                .map_err(|e| AudiotabError::from(e).context("Failed to execute pipeline"))?;
 
             // Update state to Running
-            *pipeline_arc.1.lock().unwrap() = PipelineState::Running {
+            *pipeline_state.lock().unwrap() = PipelineState::Running {
                 start_time: Some(std::time::Instant::now()),
                 frames_processed: 0,
             };
 
-            println!("Pipeline {} started successfully", id);
+            tracing::info!("Pipeline started");
         }
         PipelineAction::Stop => {
-            // Stop the pipeline via async stop() method
-            let mut pipeline_guard = pipeline_arc.0.lock().unwrap();
-
-            // Call stop on the pipeline (async operation)
-            let runtime = tokio::runtime::Runtime::new()
-                .map_err(|e| format!("Failed to create runtime: {}", e))?;
-            runtime.block_on(async {
-                pipeline_guard.stop().await
-            }).map_err(|e| format!("Failed to stop pipeline: {}", e))?;
+            pipeline.lock().await
+                .stop()
+                .await
+                .map_err(|e| e.context("Failed to stop pipeline"))?;
 
             // Update state to Completed
-            *pipeline_arc.1.lock().unwrap() = PipelineState::Completed {
+            *pipeline_state.lock().unwrap() = PipelineState::Completed {
                 duration: None,
                 total_frames: 0,
             };
 
-            println!("Pipeline {} stopped successfully", id);
+            tracing::info!("Pipeline stopped");
         }
         PipelineAction::Pause => {
             // Pause not yet implemented in AsyncPipeline
             // Defer to future implementation
-            println!("Pause not yet implemented for pipeline {}", id);
-            return Err("Pause action not yet supported".to_string());
+            return Err(AudiotabError::state("Pause action not yet supported"));
         }
     }
 
@@ -309,37 +529,63 @@ pub fn control_pipeline(
 /// Trigger a pipeline to process one frame
 ///
 /// Sends a trigger DataFrame to the pipeline's source node, causing it to process one frame.
+/// The frame carries `manual_trigger` metadata, which fires TriggerSourceNodes in manual mode.
 /// This is used for triggered execution mode where frames are processed on demand.
 #[tauri::command]
-pub fn trigger_pipeline(
+#[tracing::instrument(name = "pipeline", skip_all, fields(pipeline = %id))]
+pub async fn trigger_pipeline(
     state: State<'_, AppState>,
     id: String,
-) -> Result<(), String> {
-    println!("Trigger pipeline {}", id);
-
-    // Get the pipeline handle
-    let pipeline_arc = {
-        let pipelines = state.pipelines.lock().unwrap();
-        let handle = pipelines.get(&id)
-            .ok_or_else(|| format!("Pipeline {} not found", id))?;
-        handle.pipeline.clone()
-    };
+) -> Result<(), AudiotabError> {
+    let (pipeline, _) = pipeline_handle(&state, &id)?;
 
-    // Create a simple trigger DataFrame
-    // For Phase 7, this is a minimal frame just to trigger processing
+    // An empty frame marked as a manual trigger, for TriggerSourceNodes in manual mode
     use audiotab::core::DataFrame;
-    let trigger_frame = DataFrame::new(0, 0); // timestamp=0, sequence_id=0
+    let mut trigger_frame = DataFrame::new(0, 0); // timestamp=0, sequence_id=0
+    trigger_frame.metadata.insert("manual_trigger", true);
 
-    // Send trigger frame to pipeline
-    let runtime = tokio::runtime::Runtime::new()
-        .map_err(|e| format!("Failed to create runtime: {}", e))?;
+    pipeline.lock().await
+        .trigger(trigger_frame)
+        .await
+        .map_err(|e| e.context("Failed to trigger pipeline"))?;
 
-    runtime.block_on(async {
-        let pipeline = pipeline_arc.lock().unwrap();
-        pipeline.trigger(trigger_frame).await
-    }).map_err(|e| format!("Failed to trigger pipeline: {}", e))?;
+    tracing::debug!("Pipeline triggered");
+    Ok(())
+}
+
+/// List the frames a pipeline's nodes failed to process
+#[tauri::command]
+pub async fn list_dead_letters(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Vec<DeadLetterInfo>, AudiotabError> {
+    let (pipeline, _) = pipeline_handle(&state, &id)?;
+    let letters = pipeline.lock().await.dead_letters().list();
+    Ok(letters)
+}
 
-    println!("Pipeline {} triggered successfully", id);
+/// Send a failed frame back to the node that rejected it
+#[tauri::command]
+pub async fn reinject_dead_letter(
+    state: State<'_, AppState>,
+    id: String,
+    letter_id: u64,
+) -> Result<(), AudiotabError> {
+    let (pipeline, _) = pipeline_handle(&state, &id)?;
+    pipeline.lock().await
+        .reinject(letter_id)
+        .await
+        .map_err(|e| e.context("Failed to re-inject frame"))
+}
+
+/// Discard a pipeline's failed frames
+#[tauri::command]
+pub async fn clear_dead_letters(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<(), AudiotabError> {
+    let (pipeline, _) = pipeline_handle(&state, &id)?;
+    pipeline.lock().await.dead_letters().clear();
     Ok(())
 }
 
@@ -381,7 +627,7 @@ mod tests {
         let pipeline_id = format!("pipeline_{}", uuid::Uuid::new_v4());
         let handle = PipelineHandle {
             id: pipeline_id.clone(),
-            pipeline: Arc::new(Mutex::new(pipeline.unwrap())),
+            pipeline: Arc::new(tokio::sync::Mutex::new(pipeline.unwrap())),
             state: Arc::new(Mutex::new(PipelineState::Idle)),
         };
 
@@ -449,7 +695,7 @@ mod tests {
 
         let handle = PipelineHandle {
             id: pipeline_id.clone(),
-            pipeline: Arc::new(Mutex::new(pipeline)),
+            pipeline: Arc::new(tokio::sync::Mutex::new(pipeline)),
             state: Arc::new(Mutex::new(PipelineState::Idle)),
         };
 
@@ -0,0 +1,54 @@
+use audiotab::engine::graph_document::{GRAPH_DOCUMENT_VERSION, PROJECT_EXTENSION};
+use audiotab::engine::GraphDocument;
+use serde::Deserialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Deserialize)]
+pub struct ProjectGraph {
+    pub nodes: Vec<serde_json::Value>,
+    pub edges: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub pipeline_config: serde_json::Value,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub subgraphs: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Ensure the path carries the project extension
+fn project_path(path: &str) -> PathBuf {
+    let mut path = PathBuf::from(path);
+    if path.extension().and_then(|e| e.to_str()) != Some(PROJECT_EXTENSION) {
+        let mut with_ext = path.into_os_string();
+        with_ext.push(".");
+        with_ext.push(PROJECT_EXTENSION);
+        path = PathBuf::from(with_ext);
+    }
+    path
+}
+
+#[tauri::command]
+pub fn save_project(path: String, graph: ProjectGraph) -> Result<String, String> {
+    let mut document = GraphDocument::from_value(serde_json::json!({
+        "schema_version": GRAPH_DOCUMENT_VERSION,
+        "name": graph.name,
+        "nodes": graph.nodes,
+        "edges": graph.edges,
+        "pipeline_config": graph.pipeline_config,
+        "subgraphs": graph.subgraphs,
+    }))
+    .map_err(|e| format!("Invalid project graph: {}", e))?;
+    document.stamp_node_versions();
+
+    let path = project_path(&path);
+    document
+        .save(&path)
+        .map_err(|e| format!("Failed to save project: {}", e))?;
+
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+pub fn load_project(path: String) -> Result<GraphDocument, String> {
+    GraphDocument::load(&path).map_err(|e| format!("Failed to load project: {}", e))
+}
@@ -0,0 +1,128 @@
+use crate::commands::pipeline::pipeline_handle;
+use crate::kernel_manager::KernelManager;
+use crate::state::AppState;
+use async_trait::async_trait;
+use audiotab::core::AudiotabError;
+use audiotab::engine::{AsyncPipeline, CaptureSession, PipelineState, SessionEvent, SessionPlan, SessionTarget};
+use audiotab::observability::{EventKind, EventLog, LoggedEvent};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+
+/// How often countdown and progress events are emitted
+const SESSION_TICK: Duration = Duration::from_secs(1);
+
+/// Countdown, progress and outcome of a capture session
+#[derive(Debug, Serialize, Clone)]
+pub struct CaptureSessionEvent {
+    pub pipeline_id: String,
+    #[serde(flatten)]
+    pub event: SessionEvent,
+}
+
+/// A deployed pipeline run through the kernel, as `control_pipeline` does
+struct KernelPipeline {
+    kernel: KernelManager,
+    pipeline: Arc<tokio::sync::Mutex<AsyncPipeline>>,
+    state: Arc<Mutex<PipelineState>>,
+}
+
+#[async_trait]
+impl SessionTarget for KernelPipeline {
+    async fn start(&mut self) -> anyhow::Result<()> {
+        self.kernel.execute_pipeline(self.pipeline.clone()).await?;
+        *self.state.lock().unwrap() = PipelineState::Running {
+            start_time: Some(std::time::Instant::now()),
+            frames_processed: 0,
+        };
+        Ok(())
+    }
+
+    async fn finish(&mut self) -> anyhow::Result<()> {
+        let result = self.kernel.release_pipeline(self.pipeline.clone()).await;
+        *self.state.lock().unwrap() = PipelineState::Completed {
+            duration: None,
+            total_frames: 0,
+        };
+        result
+    }
+
+    async fn triggers(&mut self) -> u64 {
+        self.pipeline.lock().await.get_monitor().map_or(0, |monitor| monitor.triggers())
+    }
+}
+
+/// Schedule a capture on a deployed pipeline
+///
+/// The pipeline starts at `plan.start_at_ms` (or at once) and stops after
+/// `plan.duration_ms` or `plan.max_triggers` trigger events, whichever comes
+/// first; stopping drains it so sinks finalize their files. Progress is
+/// emitted as `capture-session` events. A pipeline has at most one session.
+#[tauri::command]
+#[tracing::instrument(name = "pipeline", skip_all, fields(pipeline = %id))]
+pub async fn start_session(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    kernel_manager: State<'_, KernelManager>,
+    id: String,
+    plan: SessionPlan,
+) -> Result<(), AudiotabError> {
+    let (pipeline, pipeline_state) = pipeline_handle(&state, &id)?;
+
+    let mut sessions = state.sessions.lock().unwrap();
+    if sessions.get(&id).is_some_and(|session| !session.is_finished()) {
+        return Err(AudiotabError::state(format!("Pipeline {} already has a capture session", id)));
+    }
+
+    let target = KernelPipeline {
+        kernel: kernel_manager.inner().clone(),
+        pipeline,
+        state: pipeline_state,
+    };
+    let pipeline_id = id.clone();
+    let event_log = state.event_log.clone();
+    let session = CaptureSession::spawn(plan, target, SESSION_TICK, move |event| {
+        record_session_event(&event_log, &pipeline_id, &event);
+        let _ = app.emit("capture-session", CaptureSessionEvent {
+            pipeline_id: pipeline_id.clone(),
+            event,
+        });
+    })
+    .map_err(|e| AudiotabError::config(format!("Invalid capture session: {}", e)))?;
+    sessions.insert(id.clone(), session);
+
+    tracing::info!("Capture session scheduled");
+    Ok(())
+}
+
+/// Keep the start and outcome of a capture in the audit trail
+fn record_session_event(event_log: &EventLog, pipeline_id: &str, event: &SessionEvent) {
+    let (kind, message) = match event {
+        SessionEvent::Started => (EventKind::CaptureStarted, "Capture started".to_string()),
+        SessionEvent::Completed { .. } => (EventKind::CaptureEnded, "Capture completed".to_string()),
+        SessionEvent::Cancelled { .. } => (EventKind::CaptureEnded, "Capture cancelled".to_string()),
+        SessionEvent::Failed { error } => (EventKind::CaptureEnded, format!("Capture failed: {}", error)),
+        SessionEvent::Countdown { .. } | SessionEvent::Progress { .. } => return,
+    };
+    let data = serde_json::to_value(event).unwrap_or_default();
+    event_log.record(LoggedEvent::new(kind, message).pipeline(pipeline_id).data(data));
+}
+
+/// Cancel a pipeline's capture session
+///
+/// A session still counting down never starts the pipeline; a running one
+/// stops it as on completion.
+#[tauri::command]
+#[tracing::instrument(name = "pipeline", skip_all, fields(pipeline = %id))]
+pub async fn cancel_session(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<(), AudiotabError> {
+    let session = state.sessions.lock().unwrap().remove(&id)
+        .ok_or_else(|| AudiotabError::state(format!("Pipeline {} has no capture session", id)))?;
+    session.cancel();
+
+    tracing::info!("Capture session cancelled");
+    Ok(())
+}
@@ -1,8 +1,52 @@
-use std::fs;
+use crate::state::AppState;
+use audiotab::hal::drivers::{Recording, RecordingFormat};
+use tauri::State;
 
+/// Consistent copy of the visualization ring buffer
+///
+/// Copied from the shared mapping while no write is in progress, so the
+/// reader never sees a half-written block.
 #[tauri::command]
-pub async fn get_ringbuffer_data() -> Result<Vec<u8>, String> {
-    let path = "/tmp/audiotab_ringbuf";
+pub async fn get_ringbuffer_data(state: State<'_, AppState>) -> Result<Vec<u8>, String> {
+    state
+        .ring_buffer
+        .snapshot()
+        .map_err(|e| format!("Failed to read ring buffer: {}", e))
+}
+
+/// Save what the visualization ring buffer currently holds to a file
+///
+/// Exports the last `seconds` (the whole buffer when omitted) as WAV or CSV.
+/// `format` defaults to the one implied by the file extension. The ring is
+/// shared by all pipelines; `id` names the pipeline the user is looking at
+/// and must be deployed.
+#[tauri::command]
+pub async fn export_ringbuffer(
+    state: State<'_, AppState>,
+    id: String,
+    path: String,
+    format: Option<String>,
+    seconds: Option<f64>,
+) -> Result<(), String> {
+    if !state.pipelines.lock().unwrap().contains_key(&id) {
+        return Err(format!("Pipeline {} not found", id));
+    }
+    let format = match format {
+        Some(name) => RecordingFormat::parse(&name).map_err(|e| e.to_string())?,
+        None => RecordingFormat::from_path(&path)
+            .ok_or_else(|| format!("Cannot infer export format from {}", path))?,
+    };
 
-    fs::read(path).map_err(|e| format!("Failed to read ring buffer: {}", e))
+    let snapshot = state
+        .ring_buffer
+        .snapshot()
+        .map_err(|e| format!("Failed to read ring buffer: {}", e))?;
+    let mut recording = Recording::from_ring_buffer_bytes(&snapshot)
+        .map_err(|e| format!("Failed to read ring buffer: {}", e))?;
+    if let Some(seconds) = seconds {
+        recording = recording.tail(seconds);
+    }
+    recording
+        .save(&path, format)
+        .map_err(|e| format!("Failed to export ring buffer: {}", e))
 }
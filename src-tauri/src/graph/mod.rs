@@ -1,3 +1,3 @@
 mod translator;
 
-pub use translator::translate_graph;
+pub use translator::{translate_edge, translate_graph, translate_node};
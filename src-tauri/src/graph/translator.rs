@@ -1,20 +1,29 @@
 use anyhow::{anyhow, Result};
+use audiotab::engine::async_pipeline::node_metadata;
+use audiotab::engine::subgraph::SUBGRAPH_NODE_TYPE;
 use serde_json::{json, Value};
 
 /// Translates frontend graph format to backend AsyncPipeline format
 ///
 /// Frontend format:
 /// {
-///   "nodes": [{"id": "...", "type": "...", "position": {...}, "parameters": {...}}],
-///   "edges": [{"id": "...", "source": "...", "target": "...", ...}]
+///   "nodes": [{"id": "...", "type": "...", "position": {...}, "parameters": {...}, "error_policy": ...}],
+///   "edges": [{"id": "...", "source": "...", "target": "...", ...}],
+///   "subgraphs": {...},
+///   "pipeline_config": {...}
 /// }
 ///
 /// Backend format:
 /// {
-///   "nodes": [{"id": "...", "type": "...", "config": {...}}],
+///   "nodes": [{"id": "...", "type": "...", "config": {...}, "error_policy": ...}],
 ///   "connections": [{"from": "...", "to": "..."}],
-///   "pipeline_config": {"channel_capacity": 100, "priority": "Normal"}
+///   "pipeline_config": {"channel_capacity": 100, "priority": "Normal"},
+///   "subgraphs": {...}
 /// }
+///
+/// `Subgraph` nodes keep their `subgraph` name, variadic nodes their
+/// `port_counts`, and edge handles select the port on nodes with a choice
+/// of ports.
 pub fn translate_graph(frontend_graph: Value) -> Result<Value> {
     let nodes_array = frontend_graph["nodes"]
         .as_array()
@@ -25,36 +34,91 @@ pub fn translate_graph(frontend_graph: Value) -> Result<Value> {
         .ok_or_else(|| anyhow!("Missing or invalid 'edges' array"))?;
 
     // Transform nodes
-    let backend_nodes: Vec<Value> = nodes_array
-        .iter()
-        .map(|node| {
-            json!({
-                "id": node["id"],
-                "type": map_node_type(node["type"].as_str().unwrap_or("")),
-                "config": node["parameters"]
-            })
+    let backend_nodes: Vec<Value> = nodes_array.iter().map(translate_node).collect();
+
+    let names_port = |id: &Value, output: bool| {
+        nodes_array.iter().any(|n| {
+            let node_type = n["type"].as_str().unwrap_or("");
+            &n["id"] == id
+                && (node_type == SUBGRAPH_NODE_TYPE
+                    || node_metadata(map_node_type(node_type))
+                        .is_some_and(|meta| if output { meta.names_outputs() } else { meta.names_inputs() }))
         })
-        .collect();
+    };
 
     // Transform edges to connections
     let connections: Vec<Value> = edges_array
         .iter()
-        .map(|edge| {
-            json!({
-                "from": edge["source"],
-                "to": edge["target"]
-            })
-        })
+        .map(|edge| translate_edge(edge, names_port(&edge["source"], true), names_port(&edge["target"], false)))
         .collect();
 
-    Ok(json!({
+    let mut backend = json!({
         "nodes": backend_nodes,
         "connections": connections,
         "pipeline_config": {
             "channel_capacity": 100,
             "priority": "Normal"
         }
-    }))
+    });
+    if let Some(subgraphs) = frontend_graph.get("subgraphs").filter(|s| !s.is_null()) {
+        backend["subgraphs"] = subgraphs.clone();
+    }
+    // Settings given by the frontend, such as `alerts`, override the defaults
+    if let Some(config) = frontend_graph["pipeline_config"].as_object() {
+        for (key, value) in config {
+            backend["pipeline_config"][key] = value.clone();
+        }
+    }
+    Ok(backend)
+}
+
+/// Translates one frontend node to a backend node entry
+pub fn translate_node(node: &Value) -> Value {
+    let mut backend = json!({
+        "id": node["id"],
+        "type": map_node_type(node["type"].as_str().unwrap_or("")),
+        "config": node["parameters"]
+    });
+    if let Some(policy) = node.get("error_policy").filter(|p| !p.is_null()) {
+        backend["error_policy"] = policy.clone();
+    }
+    if let Some(block_size) = node.get("input_block_size").filter(|b| !b.is_null()) {
+        backend["input_block_size"] = block_size.clone();
+    }
+    if let Some(realtime) = node.get("realtime").filter(|r| !r.is_null()) {
+        backend["realtime"] = realtime.clone();
+    }
+    if let Some(subgraph) = node.get("subgraph").filter(|s| !s.is_null()) {
+        backend["subgraph"] = subgraph.clone();
+    }
+    if let Some(counts) = node.get("port_counts").filter(|c| !c.is_null()) {
+        backend["port_counts"] = counts.clone();
+    }
+    backend
+}
+
+/// Translates one frontend edge to a backend connection
+///
+/// Handles name the port only on ends that have a choice of ports (subgraph
+/// instances, nodes with several or variadic ports); others ignore them.
+pub fn translate_edge(edge: &Value, source_names_port: bool, target_names_port: bool) -> Value {
+    let mut connection = json!({
+        "from": edge["source"],
+        "to": edge["target"]
+    });
+    if let Some(block_size) = edge.get("block_size").filter(|b| !b.is_null()) {
+        connection["block_size"] = block_size.clone();
+    }
+    if let Some(policy) = edge.get("backpressure").filter(|p| !p.is_null()) {
+        connection["backpressure"] = policy.clone();
+    }
+    if let Some(handle) = edge.get("sourceHandle").filter(|h| !h.is_null() && source_names_port) {
+        connection["from_port"] = handle.clone();
+    }
+    if let Some(handle) = edge.get("targetHandle").filter(|h| !h.is_null() && target_names_port) {
+        connection["to_port"] = handle.clone();
+    }
+    connection
 }
 
 /// Maps frontend node type names to backend node type names
@@ -156,4 +220,55 @@ mod tests {
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("Missing or invalid 'nodes'"));
     }
+
+    #[test]
+    fn test_translate_subgraph_instance() {
+        let frontend_graph = json!({
+            "nodes": [
+                {"id": "src", "type": "Gain", "parameters": {}},
+                {"id": "chain", "type": "Subgraph", "subgraph": "boost", "parameters": {}}
+            ],
+            "edges": [
+                {"id": "e1", "source": "src", "target": "chain", "sourceHandle": "out", "targetHandle": "in"}
+            ],
+            "subgraphs": {"boost": {"nodes": [], "inputs": {}}}
+        });
+
+        let result = translate_graph(frontend_graph).unwrap();
+
+        assert_eq!(result["nodes"][1]["subgraph"], "boost");
+        // Only the subgraph end of the edge names a port
+        assert_eq!(result["connections"][0]["to_port"], "in");
+        assert!(result["connections"][0].get("from_port").is_none());
+        assert!(result["subgraphs"]["boost"].is_object());
+    }
+
+    #[test]
+    fn test_translate_variadic_ports_round_trip() {
+        let frontend_graph = json!({
+            "schema_version": 1,
+            "nodes": [
+                {"id": "left", "type": "AudioSourceNode", "parameters": {}},
+                {"id": "right", "type": "AudioSourceNode", "parameters": {}},
+                {"id": "merge", "type": "FrameMerge", "parameters": {}, "port_counts": {"_inputs": 2}}
+            ],
+            "edges": [
+                {"id": "e1", "source": "left", "target": "merge", "sourceHandle": "out", "targetHandle": "_inputs_0"},
+                {"id": "e2", "source": "right", "target": "merge", "sourceHandle": "out", "targetHandle": "_inputs_1"}
+            ]
+        });
+
+        let result = translate_graph(frontend_graph.clone()).unwrap();
+
+        assert_eq!(result["nodes"][2]["port_counts"], json!({"_inputs": 2}));
+        assert_eq!(result["connections"][1]["to_port"], "_inputs_1");
+        assert!(result["connections"][1].get("from_port").is_none());
+
+        // A saved project translates to the same graph
+        let doc = audiotab::engine::GraphDocument::from_value(frontend_graph).unwrap();
+        let saved = audiotab::engine::GraphDocument::from_value(doc.to_value().unwrap()).unwrap();
+        let from_document = saved.to_pipeline_json();
+        assert_eq!(from_document["nodes"], result["nodes"]);
+        assert_eq!(from_document["connections"], result["connections"]);
+    }
 }
@@ -1,6 +1,19 @@
-use tauri::State;
-use audiotab::hal::{DeviceInfo, DeviceConfig, RegisteredHardware};
+use std::time::Duration;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+use audiotab::hal::{
+    CalibrationRecord, CalibrationStatus, CalibrationTarget, DeviceInfo, DeviceConfig, InputLevels,
+    RegisteredHardware, TestTone,
+};
+use audiotab::observability::{EventKind, LoggedEvent};
 use super::state::HardwareManagerState;
+use crate::state::AppState;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct InputLevelEvent {
+    pub registration_id: String,
+    pub levels: InputLevels,
+}
 
 #[tauri::command]
 pub async fn discover_hardware(
@@ -71,3 +84,99 @@ pub async fn remove_device(
         .await
         .map_err(|e| e.to_string())
 }
+
+/// Play a sine tone through a registered output; `level` is in dBFS
+#[tauri::command]
+pub async fn test_output_device(
+    state: State<'_, HardwareManagerState>,
+    id: String,
+    freq: f64,
+    level: f64,
+    duration: f64,
+) -> Result<u64, String> {
+    if freq.is_nan() || freq <= 0.0 || !(0.0..=30.0).contains(&duration) {
+        return Err("Frequency must be positive and duration between 0 and 30 s".to_string());
+    }
+    let tone = TestTone {
+        frequency: freq,
+        level_db: level,
+        duration: Duration::from_secs_f64(duration),
+    };
+    state.test_output(&id, tone)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Emit `input-levels` events for a registered input until `stop_input_monitor`
+#[tauri::command]
+pub async fn monitor_input_device(
+    app: AppHandle,
+    state: State<'_, HardwareManagerState>,
+    id: String,
+) -> Result<(), String> {
+    let registration_id = id.clone();
+    state.start_input_monitor(&id, move |levels| {
+        let _ = app.emit("input-levels", InputLevelEvent {
+            registration_id: registration_id.clone(),
+            levels,
+        });
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn stop_input_monitor(
+    state: State<'_, HardwareManagerState>,
+    id: String,
+) -> Result<(), String> {
+    state.stop_input_monitor(&id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Run the calibrator workflow on a registered input and store the result
+///
+/// `reference_db` defaults to a 94 dB calibrator, `duration` to 3 s. The
+/// result is recorded in the session's event log.
+#[tauri::command]
+pub async fn calibrate_device(
+    state: State<'_, HardwareManagerState>,
+    app_state: State<'_, AppState>,
+    id: String,
+    reference_db: Option<f64>,
+    duration: Option<f64>,
+    channel: Option<usize>,
+    target: Option<CalibrationTarget>,
+) -> Result<CalibrationRecord, String> {
+    let duration = duration.unwrap_or(3.0);
+    if !(0.5..=30.0).contains(&duration) {
+        return Err("Calibration duration must be between 0.5 and 30 s".to_string());
+    }
+    let record = state.calibrate(
+        &id,
+        reference_db,
+        Duration::from_secs_f64(duration),
+        channel.unwrap_or(0),
+        target.unwrap_or_default(),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    app_state.event_log.record(
+        LoggedEvent::new(EventKind::CalibrationApplied, "Calibration applied")
+            .device(&id)
+            .data(serde_json::to_value(record).unwrap_or_default()),
+    );
+    Ok(record)
+}
+
+#[tauri::command]
+pub async fn get_calibration_status(
+    state: State<'_, HardwareManagerState>,
+    id: String,
+) -> Result<CalibrationStatus, String> {
+    state.calibration_status(&id)
+        .await
+        .map_err(|e| e.to_string())
+}
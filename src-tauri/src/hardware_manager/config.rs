@@ -163,7 +163,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_register_device() {
-        use audiotab::hal::{HardwareType, Direction, AudioProtocol, ChannelMapping, Calibration, ChannelRoute};
+        use audiotab::hal::{HardwareType, Direction, AudioProtocol, ChannelMapping, Calibration, ChannelRoute, LatencyMode};
 
         let temp_dir = tempdir().unwrap();
         let config_path = temp_dir.path().join("hardware_config.json");
@@ -189,8 +189,11 @@ mod tests {
                 virtual_channels: 2,
                 routing: vec![ChannelRoute::Direct(0), ChannelRoute::Direct(1)],
             },
-            calibration: Calibration { gain: 1.0, offset: 0.0 },
+            calibration: Calibration { gain: 1.0, offset: 0.0, calibrated_at: None },
+            calibration_history: Vec::new(),
             max_voltage: 0.0,
+            buffer_count: 2,
+            latency_mode: LatencyMode::LowLatency,
             notes: "".to_string(),
         };
 
@@ -207,7 +210,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_register_device_duplicate_user_name_fails() {
-        use audiotab::hal::{HardwareType, Direction, AudioProtocol, ChannelMapping, Calibration, ChannelRoute};
+        use audiotab::hal::{HardwareType, Direction, AudioProtocol, ChannelMapping, Calibration, ChannelRoute, LatencyMode};
 
         let temp_dir = tempdir().unwrap();
         let config_path = temp_dir.path().join("hardware_config.json");
@@ -233,8 +236,11 @@ mod tests {
                 virtual_channels: 2,
                 routing: vec![ChannelRoute::Direct(0), ChannelRoute::Direct(1)],
             },
-            calibration: Calibration { gain: 1.0, offset: 0.0 },
+            calibration: Calibration { gain: 1.0, offset: 0.0, calibrated_at: None },
+            calibration_history: Vec::new(),
             max_voltage: 0.0,
+            buffer_count: 2,
+            latency_mode: LatencyMode::LowLatency,
             notes: "".to_string(),
         };
 
@@ -250,7 +256,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_update_device() {
-        use audiotab::hal::{HardwareType, Direction, AudioProtocol, ChannelMapping, Calibration, ChannelRoute};
+        use audiotab::hal::{HardwareType, Direction, AudioProtocol, ChannelMapping, Calibration, ChannelRoute, LatencyMode};
 
         let temp_dir = tempdir().unwrap();
         let config_path = temp_dir.path().join("hardware_config.json");
@@ -276,8 +282,11 @@ mod tests {
                 virtual_channels: 2,
                 routing: vec![ChannelRoute::Direct(0), ChannelRoute::Direct(1)],
             },
-            calibration: Calibration { gain: 1.0, offset: 0.0 },
+            calibration: Calibration { gain: 1.0, offset: 0.0, calibrated_at: None },
+            calibration_history: Vec::new(),
             max_voltage: 0.0,
+            buffer_count: 2,
+            latency_mode: LatencyMode::LowLatency,
             notes: "".to_string(),
         };
 
@@ -296,7 +305,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_remove_device() {
-        use audiotab::hal::{HardwareType, Direction, AudioProtocol, ChannelMapping, Calibration, ChannelRoute};
+        use audiotab::hal::{HardwareType, Direction, AudioProtocol, ChannelMapping, Calibration, ChannelRoute, LatencyMode};
 
         let temp_dir = tempdir().unwrap();
         let config_path = temp_dir.path().join("hardware_config.json");
@@ -322,8 +331,11 @@ mod tests {
                 virtual_channels: 2,
                 routing: vec![ChannelRoute::Direct(0), ChannelRoute::Direct(1)],
             },
-            calibration: Calibration { gain: 1.0, offset: 0.0 },
+            calibration: Calibration { gain: 1.0, offset: 0.0, calibrated_at: None },
+            calibration_history: Vec::new(),
             max_voltage: 0.0,
+            buffer_count: 2,
+            latency_mode: LatencyMode::LowLatency,
             notes: "".to_string(),
         };
 
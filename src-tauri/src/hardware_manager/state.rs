@@ -1,21 +1,26 @@
+use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 use audiotab::hal::*;
+use audiotab::hal::calibration::{self, CALIBRATOR_LEVEL_DB, DEFAULT_MAX_AGE};
+use audiotab::hal::device_check::play_test_tone;
+use std::time::Duration;
 use anyhow::Result;
 use super::config::HardwareConfigManager;
 
 pub struct HardwareManagerState {
     registry: Arc<RwLock<HardwareRegistry>>,
     config_manager: Arc<HardwareConfigManager>,
+    monitors: Mutex<HashMap<String, InputMonitor>>,
 }
 
 impl HardwareManagerState {
     pub fn new() -> Self {
-        let mut registry = HardwareRegistry::new();
-
-        // Register built-in drivers
-        registry.register(AudioDriver::new());
+        Self::with_registry(Arc::new(RwLock::new(HardwareRegistry::with_builtin_drivers())))
+    }
 
+    /// State using `registry`, shared with the kernel and any drivers registered at startup
+    pub fn with_registry(registry: Arc<RwLock<HardwareRegistry>>) -> Self {
         // Use home directory for config
         let config_path = dirs::home_dir()
             .unwrap_or_else(|| std::env::current_dir().unwrap())
@@ -25,8 +30,9 @@ impl HardwareManagerState {
         let config_manager = Arc::new(HardwareConfigManager::new(config_path));
 
         Self {
-            registry: Arc::new(RwLock::new(registry)),
+            registry,
             config_manager,
+            monitors: Mutex::new(HashMap::new()),
         }
     }
 
@@ -52,6 +58,98 @@ impl HardwareManagerState {
         Ok(())
     }
 
+    async fn registered_device(&self, registration_id: &str) -> Result<RegisteredHardware> {
+        self.config_manager.load().await?;
+        self.config_manager
+            .get_registered_devices()
+            .await?
+            .into_iter()
+            .find(|d| d.registration_id == registration_id)
+            .ok_or_else(|| anyhow::anyhow!("Device {} not registered", registration_id))
+    }
+
+    /// Play a test tone through a registered output device
+    pub async fn test_output(&self, registration_id: &str, tone: TestTone) -> Result<u64> {
+        let registered = self.registered_device(registration_id).await?;
+        if registered.direction != Direction::Output {
+            anyhow::bail!("Device {} is not an output", registration_id);
+        }
+        let config = registered.device_config();
+        let mut device = self.registry.read().await.create_device(
+            &registered.driver_id,
+            &registered.device_id,
+            config.clone(),
+        )?;
+        play_test_tone(device.as_mut(), &config, &tone).await
+    }
+
+    /// Stream level readings from a registered input device until stopped
+    pub async fn start_input_monitor<F>(&self, registration_id: &str, on_levels: F) -> Result<()>
+    where
+        F: Fn(InputLevels) + Send + 'static,
+    {
+        let mut monitors = self.monitors.lock().await;
+        if monitors.contains_key(registration_id) {
+            return Ok(());
+        }
+        let registered = self.registered_device(registration_id).await?;
+        if registered.direction != Direction::Input {
+            anyhow::bail!("Device {} is not an input", registration_id);
+        }
+        let device = self.registry.read().await.create_device(
+            &registered.driver_id,
+            &registered.device_id,
+            registered.device_config(),
+        )?;
+        monitors.insert(registration_id.to_string(), InputMonitor::start(device, on_levels).await?);
+        Ok(())
+    }
+
+    pub async fn stop_input_monitor(&self, registration_id: &str) -> Result<()> {
+        match self.monitors.lock().await.remove(registration_id) {
+            Some(monitor) => monitor.stop().await,
+            None => Ok(()),
+        }
+    }
+
+    /// Capture a calibrator tone on `channel` and store the solved calibration
+    pub async fn calibrate(
+        &self,
+        registration_id: &str,
+        reference_db: Option<f64>,
+        duration: Duration,
+        channel: usize,
+        target: CalibrationTarget,
+    ) -> Result<CalibrationRecord> {
+        let mut registered = self.registered_device(registration_id).await?;
+        if registered.direction != Direction::Input {
+            anyhow::bail!("Device {} is not an input", registration_id);
+        }
+        let mut device = self.registry.read().await.create_device(
+            &registered.driver_id,
+            &registered.device_id,
+            registered.device_config(),
+        )?;
+        let (samples, sample_rate) = calibration::capture_channel(device.as_mut(), channel, duration).await?;
+        let record = calibration::compute_calibration(
+            &samples,
+            sample_rate,
+            reference_db.unwrap_or(CALIBRATOR_LEVEL_DB),
+            target,
+            &registered.calibration,
+            calibration::unix_now(),
+        )?;
+
+        calibration::apply_calibration(&mut registered, record);
+        self.config_manager.update_device(registration_id, registered).await?;
+        Ok(record)
+    }
+
+    pub async fn calibration_status(&self, registration_id: &str) -> Result<CalibrationStatus> {
+        let registered = self.registered_device(registration_id).await?;
+        Ok(calibration::calibration_status(&registered.calibration, calibration::unix_now(), DEFAULT_MAX_AGE))
+    }
+
     pub fn config_manager(&self) -> &HardwareConfigManager {
         &self.config_manager
     }
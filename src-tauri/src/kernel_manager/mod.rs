@@ -3,6 +3,7 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use audiotab::engine::{AudioKernelRuntime, KernelStatus};
 use audiotab::hal::{HardwareRegistry, HardwareConfig};
+use audiotab::observability::EventLog;
 
 /// KernelManager provides thread-safe access to AudioKernelRuntime for Tauri commands
 pub struct KernelManager {
@@ -14,6 +15,9 @@ pub struct KernelManager {
 
     /// Hardware configuration
     config: Arc<RwLock<HardwareConfig>>,
+
+    /// Audit trail handed to each started kernel
+    event_log: Option<EventLog>,
 }
 
 impl KernelManager {
@@ -24,9 +28,16 @@ impl KernelManager {
             runtime: Arc::new(RwLock::new(None)),
             registry,
             config: Arc::new(RwLock::new(config)),
+            event_log: None,
         }
     }
 
+    /// Record device starts, stops and failures of started kernels in `event_log`
+    pub fn with_event_log(mut self, event_log: EventLog) -> Self {
+        self.event_log = Some(event_log);
+        self
+    }
+
     /// Start the kernel - creates a new AudioKernelRuntime and starts it
     pub async fn start_kernel(&self) -> Result<()> {
         let mut runtime_guard = self.runtime.write().await;
@@ -49,6 +60,7 @@ impl KernelManager {
             Arc::clone(&self.registry),
             config_clone,
         );
+        new_runtime.set_event_log(self.event_log.clone());
 
         // Start the kernel
         new_runtime.start().await?;
@@ -83,48 +95,6 @@ impl KernelManager {
         }
     }
 
-    /// Synchronous wrapper for start_kernel (for Tauri commands)
-    /// Spawns the async operation on the blocking pool
-    pub fn start_kernel_sync(&self) -> anyhow::Result<()> {
-        let manager = self.clone();
-        tokio::runtime::Handle::current().spawn_blocking(move || {
-            // Execute the async operation in a new Runtime on the blocking thread
-            let runtime = tokio::runtime::Runtime::new()?;
-            runtime.block_on(async {
-                manager.start_kernel().await
-            })
-        });
-        Ok(())
-    }
-
-    /// Synchronous wrapper for stop_kernel (for Tauri commands)
-    /// Spawns the async operation on the blocking pool
-    pub fn stop_kernel_sync(&self) -> anyhow::Result<()> {
-        let manager = self.clone();
-        tokio::runtime::Handle::current().spawn_blocking(move || {
-            // Execute the async operation in a new Runtime on the blocking thread
-            let runtime = tokio::runtime::Runtime::new()?;
-            runtime.block_on(async {
-                manager.stop_kernel().await
-            })
-        });
-        Ok(())
-    }
-
-    /// Synchronous wrapper for get_status (for Tauri commands)
-    pub fn get_status_sync(&self) -> KernelStatus {
-        // We need to get the status synchronously without blocking on async
-        // Since RwLock is async, we can't easily get this without the runtime
-        // For now return Stopped - in a real implementation you'd want to
-        // track status in an Arc<Mutex<>> or Arc<AtomicU8>
-        KernelStatus::Stopped
-    }
-
-    /// Synchronous wrapper for get_active_device_count
-    pub fn get_active_device_count_sync(&self) -> usize {
-        0
-    }
-
     /// Get the number of active devices (requires kernel to be running)
     pub async fn get_active_device_count(&self) -> usize {
         let runtime_guard = self.runtime.read().await;
@@ -152,38 +122,69 @@ impl KernelManager {
 
     /// Execute a pipeline instance
     ///
-    /// This spawns the pipeline as a Tokio task and manages its lifecycle
-    pub async fn execute_pipeline(&self, _pipeline: Arc<std::sync::Mutex<audiotab::engine::AsyncPipeline>>) -> Result<()> {
-        // Check runtime status without holding the lock
-        let is_running = {
-            let runtime_guard = self.runtime.read().await;
-            if let Some(runtime) = runtime_guard.as_ref() {
-                runtime.status() == KernelStatus::Running
-            } else {
-                false
-            }
+    /// Starts the pipeline and binds its device nodes to the kernel's device
+    /// readers: audio sources receive the converted frames, MIDI triggers,
+    /// external trigger sources and raw byte nodes a tap of the raw packets.
+    /// Devices keep the frame size they were started with; the pipeline's
+    /// latency budget uses their actual buffering.
+    pub async fn execute_pipeline(&self, pipeline: Arc<tokio::sync::Mutex<audiotab::engine::AsyncPipeline>>) -> Result<()> {
+        let runtime_guard = self.runtime.read().await;
+        let runtime = match runtime_guard.as_ref() {
+            Some(runtime) if runtime.status() == KernelStatus::Running => runtime,
+            _ => return Err(anyhow!("Kernel must be running to execute pipelines")),
         };
 
-        if !is_running {
-            return Err(anyhow!("Kernel must be running to execute pipelines"));
+        let mut pipeline = pipeline.lock().await;
+        for (node_id, device_id) in pipeline.device_bindings().to_vec() {
+            if let Some(buffer) = runtime.device_buffer(&device_id) {
+                if pipeline.frame_size().is_some_and(|size| size != buffer.frame_size) {
+                    tracing::warn!(
+                        device = %device_id,
+                        "Device runs with {} samples per packet, not the pipeline's frame size of {}",
+                        buffer.frame_size,
+                        pipeline.frame_size().unwrap_or_default()
+                    );
+                }
+                pipeline.set_device_buffer(device_id.clone(), buffer);
+            }
+            let Some(node) = pipeline.nodes_mut().get_mut(&node_id) else { continue };
+            if let Some(midi_trigger) = node.as_any_mut().downcast_mut::<audiotab::nodes::MidiTriggerNode>() {
+                midi_trigger.set_device_channels(Some(runtime.tap_packets(&device_id)?));
+            } else if let Some(trigger) = node.as_any_mut().downcast_mut::<audiotab::nodes::TriggerSourceNode>() {
+                trigger.set_device_channels(Some(runtime.tap_packets(&device_id)?));
+            } else if let Some(raw) = node.as_any_mut().downcast_mut::<audiotab::nodes::RawBytesNode>() {
+                raw.set_device_channels(Some(runtime.tap_packets(&device_id)?));
+            }
         }
 
-        // TODO: Actually spawn and run the pipeline
-        // This will involve:
-        // 1. _pipeline.lock().unwrap().start().await
-        // 2. Managing the pipeline task handle
-        // 3. Monitoring pipeline health
+        pipeline.start().await?;
+        if let Err(e) = runtime.bind_pipeline(&pipeline) {
+            let _ = pipeline.stop().await;
+            return Err(e);
+        }
 
         Ok(())
     }
 
-    /// Synchronous wrapper for execute_pipeline (for Tauri commands)
-    pub fn execute_pipeline_sync(&self, pipeline: Arc<std::sync::Mutex<audiotab::engine::AsyncPipeline>>) -> Result<()> {
-        let manager = self.clone();
-        let runtime = tokio::runtime::Runtime::new()?;
-        runtime.block_on(async {
-            manager.execute_pipeline(pipeline).await
-        })
+    /// Stop a pipeline and release its device bindings
+    ///
+    /// Devices belong to the kernel and keep running for other pipelines;
+    /// only this pipeline's frame routes are dropped. Its packet taps close
+    /// with the device's next packet.
+    pub async fn release_pipeline(&self, pipeline: Arc<tokio::sync::Mutex<audiotab::engine::AsyncPipeline>>) -> Result<()> {
+        let result = {
+            let mut pipeline = pipeline.lock().await;
+            if matches!(pipeline.state(), audiotab::engine::PipelineState::Running { .. }) {
+                pipeline.stop().await.map_err(anyhow::Error::from)
+            } else {
+                Ok(())
+            }
+        };
+
+        if let Some(runtime) = self.runtime.read().await.as_ref() {
+            runtime.unbind_stopped();
+        }
+        result
     }
 }
 
@@ -193,28 +194,15 @@ impl Clone for KernelManager {
             runtime: Arc::clone(&self.runtime),
             registry: Arc::clone(&self.registry),
             config: Arc::clone(&self.config),
+            event_log: self.event_log.clone(),
         }
     }
 }
 
-// Manually implement Send + Sync for KernelManager
-// AudioKernelRuntime is Send because it contains Send types
-// The RwLock<Option<AudioKernelRuntime>> is Send but not necessarily Sync automatically
-// However, since we never expose &AudioKernelRuntime directly and always go through RwLock,
-// we can safely implement Sync
-//
-// This is safe because:
-// 1. All access to runtime goes through RwLock which provides synchronization
-// 2. HardwareRegistry is Send + Sync
-// 3. HardwareConfig is Send + Sync
-// 4. Arc<T> is Send + Sync if T is Send + Sync
-unsafe impl Send for KernelManager {}
-unsafe impl Sync for KernelManager {}
-
 #[cfg(test)]
 mod tests {
     use super::*;
-    use audiotab::hal::{RegisteredHardware, HardwareType, Direction, ChannelMapping, Calibration};
+    use audiotab::hal::{RegisteredHardware, HardwareType, Direction, ChannelMapping, Calibration, LatencyMode};
 
     fn create_test_hardware_config() -> HardwareConfig {
         HardwareConfig {
@@ -328,8 +316,11 @@ mod tests {
                 virtual_channels: 2,
                 routing: vec![],
             },
-            calibration: Calibration { gain: 1.0, offset: 0.0 },
+            calibration: Calibration { gain: 1.0, offset: 0.0, calibrated_at: None },
+            calibration_history: Vec::new(),
             max_voltage: 0.0,
+            buffer_count: 2,
+            latency_mode: LatencyMode::LowLatency,
             notes: "".to_string(),
         };
 
@@ -2,6 +2,8 @@ mod state;
 mod commands;
 mod nodes;
 mod graph;
+mod logging;
+mod recordings;
 pub mod hardware_manager;
 pub mod kernel_manager;
 
@@ -17,12 +19,22 @@ use hardware_manager::{
     register_device,
     update_device,
     remove_device,
+    test_output_device,
+    monitor_input_device,
+    stop_input_monitor,
+    calibrate_device,
+    get_calibration_status,
 };
 use kernel_manager::KernelManager;
-use audiotab::hal::HardwareConfig;
+use audiotab::hal::{HardwareConfig, HardwareRegistry};
+use std::sync::Arc;
+use tauri::Manager;
+use tokio::sync::RwLock;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+  logging::init();
+
   // Import all nodes to trigger inventory registration
   use audiotab::nodes::*;
   let _ = (
@@ -32,28 +44,84 @@ pub fn run() {
       DebugSinkNode::default(),
       FFTNode::default(),
       FilterNode::default(),
+      SplMeterNode::default(),
+      DelayNode::default(),
+      CrossCorrelationNode::default(),
+      TriggerGateNode::default(),
+      EnvelopeFollowerNode::default(),
+      CompressorNode::default(),
+      LimiterNode::default(),
+      SignalDetectorNode::default(),
+      DataExportNode::default(),
+      MidiTriggerNode::default(),
+      AveragingNode::default(),
+      DecimatorNode::default(),
+      OrderAnalysisNode::default(),
+      CrossSpectrumNode::default(),
+      BeamformerNode::default(),
+      SignalGeneratorNode::default(),
+      ScriptNode::default(),
+      ChannelRouterNode::default(),
+      FrameMergeNode::default(),
+      SplitterNode::default(),
+      AnnotateNode::default(),
+      MathNode::default(),
+      IntegratorNode::default(),
+      ConvolutionNode::default(),
+      AdaptiveFilterNode::default(),
+      PitchTrackerNode::default(),
+      FeatureExtractorNode::default(),
+      AlertNode::default(),
+      SignalQualityNode::default(),
+      RawBytesNode::default(),
   );
 
+  // One set of driver instances for hardware commands, the kernel and the device manager;
+  // custom drivers registered here are used by all of them
+  let drivers = HardwareRegistry::with_builtin_drivers();
+
   // Create shared HardwareManagerState which includes registry
-  let hardware_state = HardwareManagerState::new();
+  let hardware_state = HardwareManagerState::with_registry(Arc::new(RwLock::new(drivers.clone())));
+
+  let app_state = AppState::with_drivers(drivers);
 
   // Create KernelManager with shared registry from HardwareManagerState
   let kernel_manager = KernelManager::new(
     hardware_state.get_registry_arc(),
     HardwareConfig::default(),
-  );
+  )
+  .with_event_log(app_state.event_log.clone());
 
   tauri::Builder::default()
-    .manage(AppState::new())
+    .manage(app_state)
     .manage(hardware_state)
     .manage(kernel_manager)
     .invoke_handler(tauri::generate_handler![
         commands::nodes::get_node_registry,
+        commands::nodes::get_config_schemas,
+        commands::nodes::get_node_presets,
+        commands::nodes::save_node_preset,
+        commands::nodes::delete_node_preset,
         commands::pipeline::deploy_graph,
+        commands::pipeline::validate_graph,
         commands::pipeline::get_all_pipeline_states,
+        commands::pipeline::get_pipeline_metrics,
+        commands::pipeline::get_resource_usage,
+        commands::pipeline::delete_pipeline,
+        commands::pipeline::patch_graph,
         commands::pipeline::control_pipeline,
         commands::pipeline::trigger_pipeline,
+        commands::pipeline::list_dead_letters,
+        commands::pipeline::reinject_dead_letter,
+        commands::pipeline::clear_dead_letters,
+        commands::session::start_session,
+        commands::session::cancel_session,
+        commands::events::list_event_sessions,
+        commands::events::query_events,
+        commands::project::save_project,
+        commands::project::load_project,
         commands::visualization::get_ringbuffer_data,
+        commands::visualization::export_ringbuffer,
         commands::kernel::start_kernel,
         commands::kernel::stop_kernel,
         commands::kernel::get_kernel_status,
@@ -64,14 +132,21 @@ pub fn run() {
         commands::hardware::add_device_profile,
         commands::hardware::update_device_profile,
         commands::hardware::delete_device_profile,
+        commands::hardware::import_frequency_response,
         discover_hardware,
         create_hardware_device,
         get_registered_devices,
         register_device,
         update_device,
         remove_device,
+        test_output_device,
+        monitor_input_device,
+        stop_input_monitor,
+        calibrate_device,
+        get_calibration_status,
     ])
     .setup(|app| {
+      logging::attach(app.handle());
       if cfg!(debug_assertions) {
         app.handle().plugin(
           tauri_plugin_log::Builder::default()
@@ -81,7 +156,13 @@ pub fn run() {
       }
       Ok(())
     })
-    .run(tauri::generate_context!())
-    .expect("error while running tauri application");
+    .build(tauri::generate_context!())
+    .expect("error while building tauri application")
+    .run(|app, event| {
+      if let tauri::RunEvent::Exit = event {
+        app.state::<AppState>().event_log.end();
+        logging::shutdown();
+      }
+    });
 }
 
@@ -0,0 +1,187 @@
+//! Structured logging for the app
+//!
+//! Engine, HAL and command records go to the console, filtered by
+//! `RUST_LOG` (default `info`). Records at WARN and above are also sent
+//! to the frontend log console as `log-record` events, with the fields
+//! of their spans (`pipeline`, `node`, `device`) attached. With the `otel`
+//! feature and `OTEL_EXPORTER_OTLP_ENDPOINT` set, spans down to DEBUG
+//! (per frame and per packet) are exported over OTLP as well.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+/// Tauri event carrying forwarded records
+pub const LOG_EVENT: &str = "log-record";
+
+/// App the records are forwarded to, once it is set up
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// Span exporter, taken on shutdown to send what it still buffers
+#[cfg(feature = "otel")]
+static OTLP: std::sync::Mutex<Option<audiotab::observability::OtlpExporter>> = std::sync::Mutex::new(None);
+
+/// A record as shown in the frontend log console
+#[derive(Debug, Clone, Serialize)]
+pub struct LogRecord {
+    pub level: String,
+    /// Module the record comes from, e.g. `audiotab::engine::kernel`
+    pub target: String,
+    pub message: String,
+    /// Fields of the record and its spans, innermost first on conflicts
+    pub fields: Map<String, Value>,
+    /// Names of the enclosing spans, outermost first
+    pub spans: Vec<String>,
+    /// Milliseconds since the Unix epoch
+    pub timestamp_ms: u64,
+}
+
+/// Install the global subscriber; call once, before anything logs
+pub fn init() {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let registry = tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_filter(filter))
+        .with(FrontendLayer.with_filter(LevelFilter::WARN));
+
+    #[cfg(feature = "otel")]
+    {
+        let exporter = audiotab::observability::OtlpExporter::from_env("audiotab")
+            .unwrap_or_else(|e| {
+                eprintln!("OTLP export disabled: {:#}", e);
+                None
+            });
+        let layer = exporter.as_ref().map(|e| e.layer().with_filter(LevelFilter::DEBUG));
+        let _ = registry.with(layer).try_init();
+        *OTLP.lock().unwrap_or_else(|p| p.into_inner()) = exporter;
+    }
+    #[cfg(not(feature = "otel"))]
+    let _ = registry.try_init();
+}
+
+/// Send the spans the OTLP exporter still buffers; call on exit
+pub fn shutdown() {
+    #[cfg(feature = "otel")]
+    drop(OTLP.lock().unwrap_or_else(|p| p.into_inner()).take());
+}
+
+/// Start forwarding records to `app`; earlier records only reach the console
+pub fn attach(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+/// Emits every record it sees as a `LogRecord`
+struct FrontendLayer;
+
+/// Fields recorded on a span, kept in its extensions
+struct SpanFields(Map<String, Value>);
+
+impl<S> Layer<S> for FrontendLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut visitor = FieldVisitor::default();
+        attrs.record(&mut visitor);
+        span.extensions_mut().insert(SpanFields(visitor.fields));
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut visitor = FieldVisitor::default();
+        values.record(&mut visitor);
+        if let Some(fields) = span.extensions_mut().get_mut::<SpanFields>() {
+            fields.0.extend(visitor.fields);
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let Some(app) = APP.get() else { return };
+
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        let mut fields = visitor.fields;
+        let mut spans = Vec::new();
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope {
+                spans.push(span.name().to_string());
+                if let Some(span_fields) = span.extensions().get::<SpanFields>() {
+                    for (key, value) in &span_fields.0 {
+                        fields.entry(key.clone()).or_insert_with(|| value.clone());
+                    }
+                }
+            }
+        }
+        spans.reverse();
+
+        let record = LogRecord {
+            level: event.metadata().level().to_string(),
+            target: event.metadata().target().to_string(),
+            message: visitor.message,
+            fields,
+            spans,
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+        };
+        let _ = app.emit(LOG_EVENT, record);
+    }
+}
+
+/// Collects the message and the other fields of a record or span
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    fields: Map<String, Value>,
+}
+
+impl FieldVisitor {
+    fn insert(&mut self, field: &Field, value: Value) {
+        self.fields.insert(field.name().to_string(), value);
+    }
+}
+
+impl Visit for FieldVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.insert(field, Value::String(format!("{:?}", value)));
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.insert(field, Value::String(value.to_string()));
+        }
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, value.into());
+    }
+}
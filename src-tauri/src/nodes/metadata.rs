@@ -1,4 +1,5 @@
 use crate::state::{NodeMetadata, PortMetadata};
+use audiotab::registry::PortMultiplicity;
 use serde_json::json;
 
 pub fn audio_source_metadata() -> NodeMetadata {
@@ -6,11 +7,14 @@ pub fn audio_source_metadata() -> NodeMetadata {
         id: "audio_source".to_string(),
         name: "Audio Source".to_string(),
         category: "Sources".to_string(),
+        version: 1,
+        deprecated: false,
         inputs: vec![],
         outputs: vec![PortMetadata {
             id: "output".to_string(),
             name: "Audio Out".to_string(),
             data_type: "audio_frame".to_string(),
+            multiplicity: PortMultiplicity::Single,
         }],
         parameters: json!({
             "sample_rate": { "type": "number", "default": 48000 },
@@ -24,15 +28,31 @@ pub fn trigger_source_metadata() -> NodeMetadata {
         id: "trigger_source".to_string(),
         name: "Trigger Source".to_string(),
         category: "Sources".to_string(),
-        inputs: vec![],
+        version: 1,
+        deprecated: false,
+        inputs: vec![PortMetadata {
+            id: "input".to_string(),
+            name: "Audio In".to_string(),
+            data_type: "audio_frame".to_string(),
+            multiplicity: PortMultiplicity::Single,
+        }],
         outputs: vec![PortMetadata {
             id: "output".to_string(),
             name: "Trigger Out".to_string(),
             data_type: "trigger".to_string(),
+            multiplicity: PortMultiplicity::Single,
         }],
         parameters: json!({
             "mode": { "type": "string", "default": "periodic" },
             "interval_ms": { "type": "number", "default": 100 },
+            "trigger_channel": { "type": "string", "default": "ch0" },
+            "threshold": { "type": "number", "default": 0.5 },
+            "edge": { "type": "string", "default": "rising" },
+            "hysteresis": { "type": "number", "default": 0.0 },
+            "holdoff_ms": { "type": "number", "default": 0.0 },
+            "pre_trigger_samples": { "type": "number", "default": 0 },
+            "post_trigger_samples": { "type": "number", "default": 4800 },
+            "device_profile_id": { "type": "string", "default": "" },
         }),
     }
 }
@@ -42,10 +62,13 @@ pub fn debug_sink_metadata() -> NodeMetadata {
         id: "debug_sink".to_string(),
         name: "Debug Sink".to_string(),
         category: "Sinks".to_string(),
+        version: 1,
+        deprecated: false,
         inputs: vec![PortMetadata {
             id: "input".to_string(),
             name: "Data In".to_string(),
             data_type: "any".to_string(),
+            multiplicity: PortMultiplicity::Single,
         }],
         outputs: vec![],
         parameters: json!({
@@ -59,18 +82,26 @@ pub fn fft_node_metadata() -> NodeMetadata {
         id: "fft".to_string(),
         name: "FFT".to_string(),
         category: "Processors".to_string(),
+        version: 1,
+        deprecated: false,
         inputs: vec![PortMetadata {
             id: "input".to_string(),
             name: "Audio In".to_string(),
             data_type: "audio_frame".to_string(),
+            multiplicity: PortMultiplicity::Single,
         }],
         outputs: vec![PortMetadata {
             id: "output".to_string(),
             name: "FFT Out".to_string(),
             data_type: "fft_result".to_string(),
+            multiplicity: PortMultiplicity::Single,
         }],
         parameters: json!({
+            "fft_size": { "type": "number", "default": 1024 },
+            "hop_size": { "type": "number", "default": 512 },
             "window_type": { "type": "string", "default": "hann" },
+            "averaging": { "type": "string", "default": "none" },
+            "num_averages": { "type": "number", "default": 8 },
         }),
     }
 }
@@ -80,15 +111,19 @@ pub fn gain_node_metadata() -> NodeMetadata {
         id: "gain".to_string(),
         name: "Gain".to_string(),
         category: "Processors".to_string(),
+        version: 1,
+        deprecated: false,
         inputs: vec![PortMetadata {
             id: "input".to_string(),
             name: "Audio In".to_string(),
             data_type: "audio_frame".to_string(),
+            multiplicity: PortMultiplicity::Single,
         }],
         outputs: vec![PortMetadata {
             id: "output".to_string(),
             name: "Audio Out".to_string(),
             data_type: "audio_frame".to_string(),
+            multiplicity: PortMultiplicity::Single,
         }],
         parameters: json!({
             "gain_db": { "type": "number", "default": 0.0 },
@@ -101,15 +136,19 @@ pub fn filter_node_metadata() -> NodeMetadata {
         id: "filter".to_string(),
         name: "Filter".to_string(),
         category: "Processors".to_string(),
+        version: 1,
+        deprecated: false,
         inputs: vec![PortMetadata {
             id: "input".to_string(),
             name: "Audio In".to_string(),
             data_type: "audio_frame".to_string(),
+            multiplicity: PortMultiplicity::Single,
         }],
         outputs: vec![PortMetadata {
             id: "output".to_string(),
             name: "Audio Out".to_string(),
             data_type: "audio_frame".to_string(),
+            multiplicity: PortMultiplicity::Single,
         }],
         parameters: json!({
             "type": { "type": "string", "default": "lowpass" },
@@ -0,0 +1,59 @@
+//! Directories the app's file sinks write to, remembered across runs so
+//! that recordings cut short by a crash are repaired on the next launch
+
+use audiotab::resilience::{recover_dir, RecoveredFile};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub struct RecordingDirs {
+    /// JSON list of directories
+    file: PathBuf,
+    dirs: Mutex<BTreeSet<PathBuf>>,
+}
+
+impl RecordingDirs {
+    /// Load the list kept in `file`; a missing or unreadable list is empty
+    pub fn open(file: impl Into<PathBuf>) -> Self {
+        let file = file.into();
+        let dirs = std::fs::read(&file)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+        Self { file, dirs: Mutex::new(dirs) }
+    }
+
+    /// Remember the directories of the `path` parameters in a backend graph
+    pub fn remember(&self, graph: &Value) {
+        let Some(nodes) = graph["nodes"].as_array() else { return };
+        let mut dirs = self.dirs.lock().unwrap();
+        let mut changed = false;
+        for path in nodes.iter().filter_map(|node| node["config"]["path"].as_str()) {
+            let dir = Path::new(path).parent().unwrap_or(Path::new(""));
+            let Ok(dir) = std::path::absolute(dir) else { continue };
+            changed |= dirs.insert(dir);
+        }
+        if changed {
+            let saved = serde_json::to_vec_pretty(&*dirs)
+                .map_err(anyhow::Error::from)
+                .and_then(|json| std::fs::write(&self.file, json).map_err(anyhow::Error::from));
+            if let Err(e) = saved {
+                tracing::warn!(path = %self.file.display(), "Failed to save recording directories: {}", e);
+            }
+        }
+    }
+
+    /// Repair the files in the remembered directories that were left unfinished
+    pub fn recover(&self) -> Vec<RecoveredFile> {
+        let dirs = self.dirs.lock().unwrap().clone();
+        dirs.iter()
+            .flat_map(|dir| {
+                recover_dir(dir).unwrap_or_else(|e| {
+                    tracing::warn!(dir = %dir.display(), "Failed to recover recordings: {:#}", e);
+                    Vec::new()
+                })
+            })
+            .collect()
+    }
+}
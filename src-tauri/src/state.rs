@@ -1,21 +1,34 @@
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use audiotab::engine::{AsyncPipeline, PipelineState};
+use audiotab::engine::{AsyncPipeline, CaptureSession, PipelineState};
 use audiotab::visualization::RingBufferWriter;
-use audiotab::hal::DeviceManager;
+use audiotab::hal::{DeviceManager, HardwareRegistry};
+use audiotab::observability::{EventKind, EventLog, LoggedEvent};
+use audiotab::registry::PresetStore;
 use crate::nodes::*;
+use crate::recordings::RecordingDirs;
 
 #[derive(Clone)]
 pub struct AppState {
     pub registry: Arc<NodeRegistry>,
     pub pipelines: Arc<Mutex<HashMap<String, PipelineHandle>>>,
-    pub ring_buffer: Arc<Mutex<RingBufferWriter>>,
+    /// Lock-free visualization ring written by source nodes
+    pub ring_buffer: Arc<RingBufferWriter>,
     pub device_manager: Arc<Mutex<DeviceManager>>,
+    pub preset_store: Arc<Mutex<PresetStore>>,
+    /// Scheduled capture sessions by pipeline ID
+    pub sessions: Arc<Mutex<HashMap<String, CaptureSession>>>,
+    /// Audit trail of this app run, one file per run in `event_log_dir`
+    pub event_log: EventLog,
+    pub event_log_dir: std::path::PathBuf,
+    /// Where file sinks of deployed graphs write, checked for crashed recordings on launch
+    pub recording_dirs: Arc<RecordingDirs>,
 }
 
 pub struct PipelineHandle {
     pub id: String,
-    pub pipeline: Arc<Mutex<AsyncPipeline>>,
+    /// Async lock: pipeline operations are awaited while it is held
+    pub pipeline: Arc<tokio::sync::Mutex<AsyncPipeline>>,
     pub state: Arc<Mutex<PipelineState>>,
 }
 
@@ -28,16 +41,26 @@ pub struct NodeMetadata {
     pub id: String,
     pub name: String,
     pub category: String,
+    #[serde(default = "default_node_version")]
+    pub version: u32,
+    #[serde(default)]
+    pub deprecated: bool,
     pub inputs: Vec<PortMetadata>,
     pub outputs: Vec<PortMetadata>,
     pub parameters: serde_json::Value,
 }
 
+fn default_node_version() -> u32 {
+    1
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct PortMetadata {
     pub id: String,
     pub name: String,
     pub data_type: String,
+    #[serde(default)]
+    pub multiplicity: audiotab::registry::PortMultiplicity,
 }
 
 impl NodeRegistry {
@@ -74,15 +97,19 @@ impl NodeRegistry {
                 id: meta.id.clone(),
                 name: meta.name.clone(),
                 category: meta.category.clone(),
+                version: meta.version,
+                deprecated: meta.deprecated,
                 inputs: meta.inputs.iter().map(|p| PortMetadata {
                     id: p.id.clone(),
                     name: p.name.clone(),
                     data_type: p.data_type.clone(),
+                    multiplicity: p.multiplicity,
                 }).collect(),
                 outputs: meta.outputs.iter().map(|p| PortMetadata {
                     id: p.id.clone(),
                     name: p.name.clone(),
                     data_type: p.data_type.clone(),
+                    multiplicity: p.multiplicity,
                 }).collect(),
                 parameters: serde_json::to_value(&meta.parameters).unwrap_or(serde_json::json!([])),
             };
@@ -100,6 +127,11 @@ impl Default for NodeRegistry {
 
 impl AppState {
     pub fn new() -> Self {
+        Self::with_drivers(HardwareRegistry::with_builtin_drivers())
+    }
+
+    /// App state whose device manager opens devices through `drivers`
+    pub fn with_drivers(drivers: HardwareRegistry) -> Self {
         // Initialize ring buffer (48kHz, 1 channel for now, 30 seconds)
         let ring_buffer = RingBufferWriter::new(
             "/tmp/audiotab_ringbuf",
@@ -109,22 +141,43 @@ impl AppState {
         ).expect("Failed to create ring buffer");
 
         // Initialize device manager
-        let storage_dir = dirs::config_dir()
+        let config_dir = dirs::config_dir()
             .unwrap_or_else(|| std::path::PathBuf::from("."))
-            .join("audiotab")
-            .join("devices");
+            .join("audiotab");
+        let storage_dir = config_dir.join("devices");
 
-        let mut device_manager = DeviceManager::new(storage_dir)
+        let device_manager = DeviceManager::with_registry(storage_dir, drivers)
             .expect("Failed to create device manager");
 
-        // Register built-in drivers
-        device_manager.register_driver(audiotab::hal::AudioDriver::new());
+        // Load built-in and user parameter presets
+        let preset_store = PresetStore::open(config_dir.join("presets.json"))
+            .expect("Failed to load node presets");
+
+        // Each app run is one event log session, named after its start time
+        let event_log_dir = config_dir.join("events");
+        let session_id = format!("session-{}", audiotab::hal::calibration::unix_now());
+        let event_log = EventLog::create(&event_log_dir, &session_id)
+            .expect("Failed to create event log");
+
+        // Repair recordings the last run left unfinished, noting them in the new session
+        let recording_dirs = RecordingDirs::open(config_dir.join("recording_dirs.json"));
+        for file in recording_dirs.recover() {
+            event_log.record(
+                LoggedEvent::new(EventKind::Warning, format!("Recovered interrupted recording {}", file.path.display()))
+                    .data(serde_json::to_value(&file).unwrap_or_default()),
+            );
+        }
 
         Self {
             registry: Arc::new(NodeRegistry::with_defaults()),
             pipelines: Arc::new(Mutex::new(HashMap::new())),
-            ring_buffer: Arc::new(Mutex::new(ring_buffer)),
+            ring_buffer: Arc::new(ring_buffer),
             device_manager: Arc::new(Mutex::new(device_manager)),
+            preset_store: Arc::new(Mutex::new(preset_store)),
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+            event_log,
+            event_log_dir,
+            recording_dirs: Arc::new(recording_dirs),
         }
     }
 }
@@ -1,4 +1,4 @@
-use audiotab::hal::{RegisteredHardware, HardwareType, Direction, AudioProtocol, ChannelMapping, Calibration, ChannelRoute};
+use audiotab::hal::{RegisteredHardware, HardwareType, Direction, AudioProtocol, ChannelMapping, Calibration, ChannelRoute, LatencyMode};
 use app_lib::hardware_manager::HardwareConfigManager;
 use tempfile::tempdir;
 
@@ -29,8 +29,11 @@ async fn test_full_device_registration_workflow() {
             virtual_channels: 2,
             routing: vec![ChannelRoute::Direct(0), ChannelRoute::Direct(1)],
         },
-        calibration: Calibration { gain: 1.0, offset: 0.0 },
+        calibration: Calibration { gain: 1.0, offset: 0.0, calibrated_at: None },
+        calibration_history: Vec::new(),
         max_voltage: 0.0,
+        buffer_count: 2,
+        latency_mode: LatencyMode::LowLatency,
         notes: "Primary recording device".to_string(),
     };
 
@@ -54,8 +57,11 @@ async fn test_full_device_registration_workflow() {
             virtual_channels: 2,
             routing: vec![ChannelRoute::Direct(0), ChannelRoute::Direct(1)],
         },
-        calibration: Calibration { gain: 1.0, offset: 0.0 },
+        calibration: Calibration { gain: 1.0, offset: 0.0, calibrated_at: None },
+        calibration_history: Vec::new(),
         max_voltage: 0.0,
+        buffer_count: 2,
+        latency_mode: LatencyMode::LowLatency,
         notes: "Primary playback device".to_string(),
     };
 
@@ -0,0 +1,101 @@
+use audiotab::engine::{RemoteControl, RemoteServer};
+use audiotab::hal::{AudioDriver, DeviceManager, FileDriver, LoopbackDriver, MidiDriver, NetworkDriver, SerialDriver};
+use audiotab::registry::PresetStore;
+use anyhow::{anyhow, Result};
+use std::path::PathBuf;
+use tracing_subscriber::EnvFilter;
+use std::sync::Arc;
+
+const USAGE: &str = "\
+Usage: audiotab-remote [options]
+
+Serves the HTTP remote control API: deploy, start, stop and trigger
+pipelines, update node parameters and list devices.
+
+Options:
+  --listen <addr>       Address to listen on (default: 127.0.0.1:8600)
+  --hardware <dir>      Device profile directory used to open hardware inputs
+  --presets <file>      User preset file, in addition to the built-in presets
+  --plugins <dir>       Load node plugins from a directory (repeatable;
+                        requires the plugins feature)
+  -h, --help            Show this help";
+
+struct Args {
+    listen: String,
+    hardware: Option<PathBuf>,
+    presets: Option<PathBuf>,
+    /// Only honoured with the plugins feature; rejected in `parse_args` otherwise
+    #[cfg_attr(not(feature = "plugins"), allow(dead_code))]
+    plugins: Vec<PathBuf>,
+}
+
+fn parse_args() -> Result<Args> {
+    let mut args = std::env::args().skip(1);
+    let mut listen = "127.0.0.1:8600".to_string();
+    let mut hardware = None;
+    let mut presets = None;
+    let mut plugins = Vec::new();
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| anyhow!("{} requires a value", name));
+        match arg.as_str() {
+            "--listen" => listen = value("--listen")?,
+            "--hardware" => hardware = Some(PathBuf::from(value("--hardware")?)),
+            "--presets" => presets = Some(PathBuf::from(value("--presets")?)),
+            "--plugins" => plugins.push(PathBuf::from(value("--plugins")?)),
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            other => return Err(anyhow!("Unknown argument: {}\n\n{}", other, USAGE)),
+        }
+    }
+
+    if !plugins.is_empty() && !cfg!(feature = "plugins") {
+        return Err(anyhow!("--plugins requires audiotab-remote built with the plugins feature"));
+    }
+
+    Ok(Args { listen, hardware, presets, plugins })
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Engine warnings go to stderr; RUST_LOG selects other levels, e.g. audiotab=debug
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_env_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn")))
+        .init();
+
+    let args = parse_args()?;
+
+    // Plugins register their node types before any preset store or pipeline is built
+    #[cfg(feature = "plugins")]
+    for dir in &args.plugins {
+        for plugin in audiotab::plugin::load_dir(dir)? {
+            println!("Loaded plugin {}", plugin.name());
+        }
+    }
+
+    let mut control = RemoteControl::new();
+    if let Some(dir) = &args.hardware {
+        let mut m = DeviceManager::new(dir.clone())?;
+        m.register_driver(AudioDriver::new());
+        m.register_driver(LoopbackDriver::new());
+        m.register_driver(FileDriver::default());
+        m.register_driver(NetworkDriver::default());
+        m.register_driver(SerialDriver::default());
+        m.register_driver(MidiDriver::default());
+        #[cfg(feature = "jack")]
+        m.register_driver(audiotab::hal::JackDriver::default());
+        control = control.with_devices(Arc::new(m));
+    }
+    if let Some(path) = args.presets {
+        control = control.with_presets(PresetStore::open(path)?);
+    }
+
+    let server = RemoteServer::bind(&args.listen, Arc::new(control)).await?;
+    println!("Remote control listening on http://{}", server.local_addr());
+
+    tokio::signal::ctrl_c().await?;
+    Ok(())
+}
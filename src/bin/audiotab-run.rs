@@ -0,0 +1,226 @@
+use audiotab::core::DataFrame;
+use audiotab::engine::AsyncPipeline;
+use audiotab::hal::{AudioDriver, DeviceManager, FileDriver, LoopbackDriver, MidiDriver, NetworkDriver, SerialDriver};
+use anyhow::{anyhow, Context, Result};
+use std::path::PathBuf;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+use std::time::{Duration, Instant};
+
+const USAGE: &str = "\
+Usage: audiotab-run <pipeline.json> [options]
+
+Options:
+  --hardware <dir>      Device profile directory used to open hardware inputs
+  --duration <secs>     Run for the given number of seconds
+  --frames <n>          Run for the given number of frames (default: 100)
+  --interval-ms <ms>    Delay between triggered frames (default: 10)
+  --stream <addr>       Serve waveforms and metrics over WebSocket, e.g. 0.0.0.0:9000
+                        (requires the streaming feature)
+  --plugins <dir>       Load node plugins from a directory (repeatable;
+                        requires the plugins feature)
+  --quiet               Only print the final metrics report
+  -h, --help            Show this help
+
+Environment:
+  RUST_LOG                      Log levels on stderr (default: warn)
+  OTEL_EXPORTER_OTLP_ENDPOINT   Export spans to an OTLP/HTTP collector, e.g.
+                                http://localhost:4318 (requires the otel feature)";
+
+struct Args {
+    pipeline: PathBuf,
+    hardware: Option<PathBuf>,
+    duration: Option<Duration>,
+    frames: Option<u64>,
+    interval: Duration,
+    /// Only honoured with the streaming feature; rejected in `parse_args` otherwise
+    #[cfg_attr(not(feature = "streaming"), allow(dead_code))]
+    stream: Option<String>,
+    /// Only honoured with the plugins feature; rejected in `parse_args` otherwise
+    #[cfg_attr(not(feature = "plugins"), allow(dead_code))]
+    plugins: Vec<PathBuf>,
+    quiet: bool,
+}
+
+fn parse_args() -> Result<Args> {
+    let mut args = std::env::args().skip(1);
+    let mut pipeline = None;
+    let mut hardware = None;
+    let mut duration = None;
+    let mut frames = None;
+    let mut interval = Duration::from_millis(10);
+    let mut stream = None;
+    let mut plugins = Vec::new();
+    let mut quiet = false;
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| anyhow!("{} requires a value", name));
+        match arg.as_str() {
+            "--hardware" => hardware = Some(PathBuf::from(value("--hardware")?)),
+            "--duration" => {
+                let secs: f64 = value("--duration")?.parse().context("Invalid --duration")?;
+                duration = Some(Duration::from_secs_f64(secs));
+            }
+            "--frames" => frames = Some(value("--frames")?.parse().context("Invalid --frames")?),
+            "--interval-ms" => {
+                let ms: u64 = value("--interval-ms")?.parse().context("Invalid --interval-ms")?;
+                interval = Duration::from_millis(ms);
+            }
+            "--stream" => stream = Some(value("--stream")?),
+            "--plugins" => plugins.push(PathBuf::from(value("--plugins")?)),
+            "--quiet" => quiet = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            other if other.starts_with('-') => return Err(anyhow!("Unknown option: {}\n\n{}", other, USAGE)),
+            other => pipeline = Some(PathBuf::from(other)),
+        }
+    }
+
+    let pipeline = pipeline.ok_or_else(|| anyhow!("Missing pipeline file\n\n{}", USAGE))?;
+    if duration.is_some() && frames.is_some() {
+        return Err(anyhow!("--duration and --frames are mutually exclusive"));
+    }
+
+    if stream.is_some() && !cfg!(feature = "streaming") {
+        return Err(anyhow!("--stream requires audiotab-run built with the streaming feature"));
+    }
+    if !plugins.is_empty() && !cfg!(feature = "plugins") {
+        return Err(anyhow!("--plugins requires audiotab-run built with the plugins feature"));
+    }
+
+    Ok(Args { pipeline, hardware, duration, frames, interval, stream, plugins, quiet })
+}
+
+/// Seconds of audio kept for streaming
+#[cfg(feature = "streaming")]
+const STREAM_RING_SECS: u64 = 2;
+
+/// Feed a ring buffer from the pipeline's audio sources and serve it with
+/// the pipeline's events on `addr`
+///
+/// The ring takes its sample rate and channel count from the first
+/// AudioSourceNode.
+#[cfg(feature = "streaming")]
+async fn start_streaming(pipeline: &mut AsyncPipeline, addr: &str) -> Result<audiotab::observability::StreamingServer> {
+    use audiotab::nodes::AudioSourceNode;
+    use audiotab::observability::{StreamingConfig, StreamingServer};
+    use audiotab::visualization::RingBufferWriter;
+    use std::sync::Arc;
+
+    let (sample_rate, channels) = pipeline
+        .nodes_mut()
+        .values_mut()
+        .find_map(|node| node.as_any_mut().downcast_mut::<AudioSourceNode>().map(|s| (s.sample_rate as u64, s.num_channels)))
+        .unwrap_or((48000, 1));
+    let path = std::env::temp_dir().join(format!("audiotab-run-{}.ring", std::process::id()));
+    let ring = Arc::new(RingBufferWriter::new(&path, sample_rate, channels, STREAM_RING_SECS)?);
+    pipeline.set_ring_buffer(ring.clone());
+
+    StreamingServer::bind(addr, Some(ring), Some(pipeline.subscribe_events()), StreamingConfig::default()).await
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Engine warnings go to stderr; RUST_LOG selects other levels, e.g. audiotab=debug
+    let stderr = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn")));
+    // Spans, per-frame ones included, go to the OTLP collector if one is configured
+    #[cfg(feature = "otel")]
+    let otlp = audiotab::observability::OtlpExporter::from_env("audiotab-run")?;
+    #[cfg(feature = "otel")]
+    let otlp_layer = otlp.as_ref().map(|otlp| otlp.layer().with_filter(tracing_subscriber::filter::LevelFilter::DEBUG));
+    #[cfg(not(feature = "otel"))]
+    let otlp_layer: Option<tracing_subscriber::layer::Identity> = None;
+    tracing_subscriber::registry().with(stderr).with(otlp_layer).init();
+
+    let args = parse_args()?;
+    let span = tracing::info_span!("pipeline", pipeline = %args.pipeline.display());
+    let _span = span.enter();
+
+    #[cfg(feature = "plugins")]
+    for dir in &args.plugins {
+        for plugin in audiotab::plugin::load_dir(dir)? {
+            if !args.quiet {
+                println!("Loaded plugin {}", plugin.name());
+            }
+        }
+    }
+
+    let contents = std::fs::read_to_string(&args.pipeline)
+        .with_context(|| format!("Failed to read {}", args.pipeline.display()))?;
+    let config: serde_json::Value = serde_json::from_str(&contents)
+        .with_context(|| format!("Invalid pipeline JSON in {}", args.pipeline.display()))?;
+
+    let mut pipeline = AsyncPipeline::from_json(config).await?;
+
+    let mut manager = None;
+    let mut started_devices = Vec::new();
+    if let Some(dir) = &args.hardware {
+        let mut m = DeviceManager::new(dir.clone())?;
+        m.register_driver(AudioDriver::new());
+        m.register_driver(LoopbackDriver::new());
+        m.register_driver(FileDriver::default());
+        m.register_driver(NetworkDriver::default());
+        m.register_driver(SerialDriver::default());
+        m.register_driver(MidiDriver::default());
+        #[cfg(feature = "jack")]
+        m.register_driver(audiotab::hal::JackDriver::default());
+        started_devices = pipeline.attach_devices(&m).await?;
+        manager = Some(m);
+    }
+
+    #[cfg(feature = "streaming")]
+    let _stream = match &args.stream {
+        Some(addr) => {
+            let server = start_streaming(&mut pipeline, addr).await?;
+            if !args.quiet {
+                println!("Streaming on ws://{}", server.local_addr());
+            }
+            Some(server)
+        }
+        None => None,
+    };
+
+    pipeline.start().await?;
+    if !args.quiet {
+        println!("Running {} ({} device(s) attached)", args.pipeline.display(), started_devices.len());
+    }
+
+    let start = Instant::now();
+    let max_frames = if args.duration.is_some() { u64::MAX } else { args.frames.unwrap_or(100) };
+    let mut sent = 0;
+    while sent < max_frames {
+        if args.duration.is_some_and(|d| start.elapsed() >= d) {
+            break;
+        }
+        pipeline.trigger(DataFrame::new(start.elapsed().as_micros() as u64, sent)).await?;
+        sent += 1;
+        tokio::time::sleep(args.interval).await;
+    }
+
+    let monitor = pipeline.get_monitor();
+    pipeline.stop().await?;
+
+    if let Some(m) = &manager {
+        for (profile_id, node_id) in &started_devices {
+            m.release_device(profile_id, node_id).await?;
+        }
+    }
+
+    println!("Triggered {} frames in {:.2}s", sent, start.elapsed().as_secs_f64());
+    let mut errors = 0;
+    if let Some(monitor) = monitor {
+        println!("{}", monitor.generate_report());
+        errors = monitor.collector().snapshot().values().map(|m| m.errors_count).sum();
+    }
+
+    if errors > 0 {
+        eprintln!("Pipeline finished with {} error(s)", errors);
+        std::process::exit(1);
+    }
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use crate::core::Channel;
+use crossbeam_channel::{Receiver, Sender};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+
+/// Default number of idle sample buffers kept by a FramePool
+pub const DEFAULT_FRAME_POOL_SIZE: usize = 256;
+
+/// Recycle list for DataFrame channel sample vectors
+///
+/// Buffers are borrowed with [`FramePool::take`] and wrapped into channels
+/// with [`FramePool::channel`]. When the last frame holding a pooled
+/// channel is dropped, its buffer goes back to the pool instead of being
+/// freed, so steady-state streaming does not allocate. The free list is a
+/// bounded lock-free queue; buffers returned to a full pool are dropped.
+#[derive(Debug, Clone)]
+pub struct FramePool {
+    free_tx: Sender<Vec<f64>>,
+    free_rx: Receiver<Vec<f64>>,
+    allocations: Arc<AtomicUsize>,
+}
+
+impl FramePool {
+    pub fn new(max_buffers: usize) -> Self {
+        let (free_tx, free_rx) = crossbeam_channel::bounded(max_buffers.max(1));
+        Self {
+            free_tx,
+            free_rx,
+            allocations: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Process-wide pool shared by the format converter and built-in nodes
+    pub fn global() -> &'static FramePool {
+        static POOL: OnceLock<FramePool> = OnceLock::new();
+        POOL.get_or_init(|| FramePool::new(DEFAULT_FRAME_POOL_SIZE))
+    }
+
+    /// Borrow an empty buffer with room for at least `len` samples
+    pub fn take(&self, len: usize) -> Vec<f64> {
+        match self.free_rx.try_recv() {
+            Ok(mut buffer) => {
+                buffer.clear();
+                buffer.reserve(len);
+                buffer
+            }
+            Err(_) => {
+                self.allocations.fetch_add(1, Ordering::Relaxed);
+                Vec::with_capacity(len)
+            }
+        }
+    }
+
+    /// Borrow a buffer of `len` zeros
+    pub fn take_zeroed(&self, len: usize) -> Vec<f64> {
+        let mut buffer = self.take(len);
+        buffer.resize(len, 0.0);
+        buffer
+    }
+
+    /// Return a buffer for reuse
+    pub fn recycle(&self, buffer: Vec<f64>) {
+        if buffer.capacity() > 0 {
+            let _ = self.free_tx.try_send(buffer);
+        }
+    }
+
+    /// Wrap samples in a channel that returns its buffer here when dropped
+    pub fn channel(&self, samples: Vec<f64>) -> Channel {
+        Channel::new(samples).with_pool(self.clone())
+    }
+
+    /// Number of idle buffers ready for reuse
+    pub fn available(&self) -> usize {
+        self.free_rx.len()
+    }
+
+    /// Most idle buffers the pool keeps
+    pub fn capacity(&self) -> usize {
+        self.free_tx.capacity().unwrap_or(0)
+    }
+
+    /// Number of buffers allocated because the pool was empty
+    pub fn allocations(&self) -> usize {
+        self.allocations.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for FramePool {
+    fn default() -> Self {
+        Self::new(DEFAULT_FRAME_POOL_SIZE)
+    }
+}
@@ -1,3 +1,5 @@
+pub mod frame_pool;
 pub mod pool;
 
+pub use frame_pool::{FramePool, DEFAULT_FRAME_POOL_SIZE};
 pub use pool::{BufferPool, PooledBuffer};
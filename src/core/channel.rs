@@ -0,0 +1,159 @@
+use crate::buffers::FramePool;
+use serde::{Deserialize, Serialize};
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// What the samples in a channel represent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelRole {
+    /// Time-domain signal (audio, sensor data)
+    #[default]
+    Signal,
+    /// Frequency-domain bins
+    Spectrum,
+    /// Derived per-frame values (levels, lags, statistics)
+    Measurement,
+    /// Control or trigger data
+    Control,
+}
+
+/// One channel of a DataFrame
+///
+/// Samples are shared between clones and copied only when a shared
+/// channel is mutated (copy-on-write), so fan-out and pass-through
+/// nodes never duplicate sample buffers. Channels created from a
+/// [`FramePool`] hand their buffer back to it when the last clone drops.
+#[derive(Debug, Clone, Default)]
+pub struct Channel {
+    samples: Arc<Vec<f64>>,
+    /// Sample rate in Hz; `None` means the frame's `sample_rate` metadata applies
+    pub sample_rate: Option<f64>,
+    /// Physical unit of the samples, e.g. "Pa", "V" or "dB"
+    pub unit: Option<String>,
+    pub role: ChannelRole,
+    pool: Option<FramePool>,
+}
+
+impl Channel {
+    pub fn new(samples: Vec<f64>) -> Self {
+        Self::from_shared(Arc::new(samples))
+    }
+
+    pub fn from_shared(samples: Arc<Vec<f64>>) -> Self {
+        Self {
+            samples,
+            sample_rate: None,
+            unit: None,
+            role: ChannelRole::Signal,
+            pool: None,
+        }
+    }
+
+    /// Single-value measurement channel
+    pub fn scalar(value: f64) -> Self {
+        Self::new(vec![value]).with_role(ChannelRole::Measurement)
+    }
+
+    pub fn with_sample_rate(mut self, sample_rate: f64) -> Self {
+        self.sample_rate = Some(sample_rate);
+        self
+    }
+
+    pub fn with_unit(mut self, unit: impl Into<String>) -> Self {
+        self.unit = Some(unit.into());
+        self
+    }
+
+    pub fn with_role(mut self, role: ChannelRole) -> Self {
+        self.role = role;
+        self
+    }
+
+    /// Return the sample buffer to `pool` once no frame uses it any more
+    pub fn with_pool(mut self, pool: FramePool) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    /// Same channel properties with different samples
+    pub fn with_samples(&self, samples: Vec<f64>) -> Self {
+        Self {
+            samples: Arc::new(samples),
+            sample_rate: self.sample_rate,
+            unit: self.unit.clone(),
+            role: self.role,
+            pool: self.pool.clone(),
+        }
+    }
+
+    pub fn samples(&self) -> &[f64] {
+        &self.samples
+    }
+
+    /// Mutable samples, copying them first if another frame shares them
+    pub fn samples_mut(&mut self) -> &mut Vec<f64> {
+        Arc::make_mut(&mut self.samples)
+    }
+
+    /// Shared handle to the sample buffer
+    pub fn shared(&self) -> Arc<Vec<f64>> {
+        self.samples.clone()
+    }
+
+    /// Take the samples, copying only if they are shared
+    pub fn into_samples(mut self) -> Vec<f64> {
+        self.pool = None;
+        std::mem::take(self.samples_mut())
+    }
+
+    /// Whether two channels share the same sample buffer
+    pub fn ptr_eq(a: &Channel, b: &Channel) -> bool {
+        Arc::ptr_eq(&a.samples, &b.samples)
+    }
+}
+
+impl PartialEq for Channel {
+    fn eq(&self, other: &Self) -> bool {
+        self.samples == other.samples
+            && self.sample_rate == other.sample_rate
+            && self.unit == other.unit
+            && self.role == other.role
+    }
+}
+
+impl Drop for Channel {
+    fn drop(&mut self) {
+        if let Some(pool) = self.pool.take() {
+            if let Some(samples) = Arc::get_mut(&mut self.samples) {
+                pool.recycle(std::mem::take(samples));
+            }
+        }
+    }
+}
+
+impl Deref for Channel {
+    type Target = [f64];
+
+    fn deref(&self) -> &[f64] {
+        &self.samples
+    }
+}
+
+impl AsRef<[f64]> for Channel {
+    fn as_ref(&self) -> &[f64] {
+        &self.samples
+    }
+}
+
+impl From<Vec<f64>> for Channel {
+    fn from(samples: Vec<f64>) -> Self {
+        Self::new(samples)
+    }
+}
+
+impl From<Arc<Vec<f64>>> for Channel {
+    fn from(samples: Arc<Vec<f64>>) -> Self {
+        Self::from_shared(samples)
+    }
+}
@@ -1,20 +1,27 @@
+use super::{Channel, EventSeries, Metadata};
 use std::collections::HashMap;
 use std::sync::Arc;
 
+/// Frame shared between the consumers of a fan-out
+pub type SharedFrame = Arc<DataFrame>;
+
 /// Basic data unit passed between processing nodes
 #[derive(Debug, Clone)]
 pub struct DataFrame {
-    /// Timestamp in microseconds since epoch
+    /// Capture time in nanoseconds; hardware frames use the shared `hal::TimeBase` clock
     pub timestamp: u64,
 
     /// Sequential frame number for ordering
     pub sequence_id: u64,
 
-    /// Multi-channel data keyed by channel name (zero-copy via Arc)
-    pub payload: HashMap<String, Arc<Vec<f64>>>,
+    /// Multi-channel data keyed by channel name (samples shared copy-on-write)
+    pub payload: HashMap<String, Channel>,
+
+    /// Timestamped point streams keyed by name (tacho pulses, triggers, detections)
+    pub events: HashMap<String, EventSeries>,
 
-    /// Side-channel information (gain, sample_rate, etc)
-    pub metadata: HashMap<String, String>,
+    /// Typed side-channel information (gain, sample_rate, etc)
+    pub metadata: Metadata,
 }
 
 impl DataFrame {
@@ -23,7 +30,44 @@ impl DataFrame {
             timestamp,
             sequence_id,
             payload: HashMap::new(),
-            metadata: HashMap::new(),
+            events: HashMap::new(),
+            metadata: Metadata::new(),
         }
     }
+
+    /// Insert a channel, accepting plain sample vectors or configured channels
+    pub fn insert_channel(&mut self, name: impl Into<String>, channel: impl Into<Channel>) {
+        self.payload.insert(name.into(), channel.into());
+    }
+
+    /// Insert an event series, accepting plain point vectors or configured series
+    pub fn insert_events(&mut self, name: impl Into<String>, events: impl Into<EventSeries>) {
+        self.events.insert(name.into(), events.into());
+    }
+
+    /// Take ownership of a shared frame, cloning it only while other consumers
+    /// still hold it (the clone shares channel samples and metadata)
+    pub fn from_shared(frame: SharedFrame) -> Self {
+        Arc::try_unwrap(frame).unwrap_or_else(|shared| (*shared).clone())
+    }
+
+    /// Bytes of sample data across all channels and event series
+    pub fn sample_bytes(&self) -> usize {
+        let samples: usize = self.payload.values().map(|c| std::mem::size_of_val(c.samples())).sum();
+        let events: usize = self.events.values().map(|e| std::mem::size_of_val(e.points())).sum();
+        samples + events
+    }
+
+    /// Frame-level sample rate from the `sample_rate` metadata
+    pub fn sample_rate(&self) -> Option<f64> {
+        self.metadata.get_f64("sample_rate")
+    }
+
+    /// Sample rate of a channel, falling back to the frame sample rate
+    pub fn channel_sample_rate(&self, name: &str) -> Option<f64> {
+        self.payload
+            .get(name)
+            .and_then(|c| c.sample_rate)
+            .or_else(|| self.sample_rate())
+    }
 }
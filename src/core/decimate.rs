@@ -0,0 +1,97 @@
+use super::{ChannelRole, DataFrame};
+use crate::dsp::fir::{design_lowpass, FirFilter};
+use std::collections::HashMap;
+
+/// Anti-alias cutoff as a fraction of the output sample rate, leaving room
+/// for the filter's transition band below the output Nyquist frequency
+const CUTOFF_RATIO: f64 = 0.4;
+
+/// Taps of the anti-alias filter per unit of decimation factor
+const TAPS_PER_FACTOR: usize = 20;
+
+/// Filter state and decimation phase of one channel
+#[derive(Debug, Clone)]
+struct ChannelDecimator {
+    filter: Option<FirFilter>,
+    /// Input samples until the next output sample
+    countdown: usize,
+}
+
+impl ChannelDecimator {
+    fn new(factor: usize, anti_alias: bool) -> Self {
+        let filter = (anti_alias && factor > 1).then(|| {
+            let cutoff = CUTOFF_RATIO / factor as f64;
+            FirFilter::new(design_lowpass(TAPS_PER_FACTOR * factor, cutoff))
+        });
+        Self { filter, countdown: 0 }
+    }
+}
+
+/// Reduces the sample rate of a frame stream by an integer factor
+///
+/// Every signal channel is low-pass filtered below the new Nyquist frequency
+/// (at 0.4 of the output rate) and then only every `factor`-th sample is
+/// kept. Decimation continues across frames, so frames of any length can be
+/// fed. Without `anti_alias`, samples are dropped unfiltered. Spectra,
+/// measurements and control channels are not time series and pass through
+/// unchanged, as do event series; frames without a signal channel keep
+/// their `sample_rate`.
+#[derive(Debug, Clone)]
+pub struct FrameDecimator {
+    factor: usize,
+    anti_alias: bool,
+    channels: HashMap<String, ChannelDecimator>,
+}
+
+impl FrameDecimator {
+    pub fn new(factor: usize, anti_alias: bool) -> Self {
+        Self { factor: factor.max(1), anti_alias, channels: HashMap::new() }
+    }
+
+    pub fn factor(&self) -> usize {
+        self.factor
+    }
+
+    /// Decimate a frame, dividing its `sample_rate` metadata and the rate
+    /// of channels that carry their own by `factor`
+    pub fn push(&mut self, mut frame: DataFrame) -> DataFrame {
+        if !frame.payload.values().any(|c| c.role == ChannelRole::Signal) {
+            return frame;
+        }
+        let factor = self.factor;
+        let input = std::mem::take(&mut frame.payload);
+        for (channel, samples) in input {
+            if samples.role != ChannelRole::Signal {
+                frame.payload.insert(channel, samples);
+                continue;
+            }
+            let anti_alias = self.anti_alias;
+            let state = self
+                .channels
+                .entry(channel.clone())
+                .or_insert_with(|| ChannelDecimator::new(factor, anti_alias));
+
+            let mut output = Vec::with_capacity(samples.len() / factor + 1);
+            for &x in samples.iter() {
+                if let Some(filter) = &mut state.filter {
+                    filter.push(x);
+                }
+                if state.countdown == 0 {
+                    output.push(state.filter.as_ref().map_or(x, |f| f.output()));
+                    state.countdown = factor;
+                }
+                state.countdown -= 1;
+            }
+
+            let mut decimated = samples.with_samples(output);
+            decimated.sample_rate = samples.sample_rate.map(|rate| rate / factor as f64);
+            frame.payload.insert(channel, decimated);
+        }
+
+        if let Some(sample_rate) = frame.sample_rate() {
+            frame.metadata.insert("sample_rate", sample_rate / factor as f64);
+        }
+        frame.metadata.insert("decimation_factor", factor);
+        frame
+    }
+}
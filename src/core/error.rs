@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Error returned by the public API, classified so callers (such as the
+/// frontend) can branch on the kind and suggest a fix
+///
+/// Serializes as `{"kind": "device", "device": "mic-1", "message": "..."}`.
+///
+/// `ProcessingNode`, `Device` and `HardwareDriver` implementations keep
+/// returning `anyhow::Error` so they can use `?` on any error; an
+/// `AudiotabError` they return (or wrap in context) keeps its kind when it
+/// surfaces from `AsyncPipeline` or `DeviceManager`. Unclassified errors
+/// become `Other`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AudiotabError {
+    /// A device is missing or busy, or failed to open, start or stream
+    Device { device: Option<String>, message: String },
+    /// A node or pipeline setting is invalid
+    Config { node: Option<String>, message: String },
+    /// The graph names unknown nodes or ports, or connects them invalidly
+    Graph { message: String },
+    /// Sample data could not be converted between formats
+    Conversion { message: String },
+    /// A node failed while processing
+    Node { node: String, message: String },
+    /// The operation is not allowed in the pipeline's current state
+    State { message: String },
+    /// Reading or writing a file failed
+    Io { message: String },
+    Other { message: String },
+}
+
+impl AudiotabError {
+    pub fn device(device: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::Device { device: Some(device.into()), message: message.into() }
+    }
+
+    pub fn config(message: impl Into<String>) -> Self {
+        Self::Config { node: None, message: message.into() }
+    }
+
+    pub fn node_config(node: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::Config { node: Some(node.into()), message: message.into() }
+    }
+
+    pub fn graph(message: impl Into<String>) -> Self {
+        Self::Graph { message: message.into() }
+    }
+
+    pub fn conversion(message: impl Into<String>) -> Self {
+        Self::Conversion { message: message.into() }
+    }
+
+    pub fn node(node: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::Node { node: node.into(), message: message.into() }
+    }
+
+    pub fn state(message: impl Into<String>) -> Self {
+        Self::State { message: message.into() }
+    }
+
+    pub fn other(message: impl Into<String>) -> Self {
+        Self::Other { message: message.into() }
+    }
+
+    /// Classify an error from `device` as a device error unless it already has a kind
+    pub fn from_device(device: &str, err: anyhow::Error) -> Self {
+        match Self::from(err) {
+            Self::Other { message } => Self::device(device, message),
+            e => e,
+        }
+    }
+
+    /// Classify an error from configuring `node` as a config error unless it already has a kind
+    pub fn from_node_config(node: &str, err: anyhow::Error) -> Self {
+        match Self::from(err) {
+            Self::Other { message } => Self::node_config(node, message),
+            e => e,
+        }
+    }
+
+    /// Prefix the message with `context`, keeping the kind
+    pub fn context(self, context: impl fmt::Display) -> Self {
+        let message = format!("{}: {}", context, self.message());
+        self.with_message(message)
+    }
+
+    /// Kind as serialized in the `kind` field, e.g. "device"
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Device { .. } => "device",
+            Self::Config { .. } => "config",
+            Self::Graph { .. } => "graph",
+            Self::Conversion { .. } => "conversion",
+            Self::Node { .. } => "node",
+            Self::State { .. } => "state",
+            Self::Io { .. } => "io",
+            Self::Other { .. } => "other",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Self::Device { message, .. }
+            | Self::Config { message, .. }
+            | Self::Graph { message }
+            | Self::Conversion { message }
+            | Self::Node { message, .. }
+            | Self::State { message }
+            | Self::Io { message }
+            | Self::Other { message } => message,
+        }
+    }
+
+    fn with_message(mut self, text: String) -> Self {
+        match &mut self {
+            Self::Device { message, .. }
+            | Self::Config { message, .. }
+            | Self::Graph { message }
+            | Self::Conversion { message }
+            | Self::Node { message, .. }
+            | Self::State { message }
+            | Self::Io { message }
+            | Self::Other { message } => *message = text,
+        }
+        self
+    }
+}
+
+impl fmt::Display for AudiotabError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AudiotabError {}
+
+/// Takes the kind of the first `AudiotabError` (or I/O error) in the chain,
+/// with the message of the whole chain, context included
+impl From<anyhow::Error> for AudiotabError {
+    fn from(err: anyhow::Error) -> Self {
+        let message = format!("{:#}", err);
+        if let Some(classified) = err.chain().find_map(|e| e.downcast_ref::<AudiotabError>()) {
+            return classified.clone().with_message(message);
+        }
+        if err.chain().any(|e| e.is::<std::io::Error>()) {
+            return Self::Io { message };
+        }
+        Self::Other { message }
+    }
+}
+
+impl From<std::io::Error> for AudiotabError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io { message: err.to_string() }
+    }
+}
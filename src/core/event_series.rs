@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// One timestamped point of an event stream
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EventPoint {
+    /// Time of the event in nanoseconds, on the same clock as `DataFrame::timestamp`
+    pub time_ns: u64,
+    /// Amplitude, strength or count carried by the event; 1.0 when there is none
+    pub value: f64,
+}
+
+impl EventPoint {
+    pub fn new(time_ns: u64, value: f64) -> Self {
+        Self { time_ns, value }
+    }
+}
+
+/// Non-uniformly sampled points of a DataFrame, such as tacho pulses,
+/// trigger times or detected events
+///
+/// Points are kept in time order and carry absolute timestamps, so unlike
+/// channel samples they need no sample rate and survive reblocking and
+/// decimation unchanged. Points are shared between clones and copied only
+/// when a shared series is mutated.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EventSeries {
+    points: Arc<Vec<EventPoint>>,
+    /// Physical unit of the point values
+    pub unit: Option<String>,
+}
+
+impl EventSeries {
+    /// Series of `points`, sorted by time
+    pub fn new(mut points: Vec<EventPoint>) -> Self {
+        points.sort_by_key(|p| p.time_ns);
+        Self { points: Arc::new(points), unit: None }
+    }
+
+    /// Series of unit events at `times`
+    pub fn from_times(times: impl IntoIterator<Item = u64>) -> Self {
+        Self::new(times.into_iter().map(|t| EventPoint::new(t, 1.0)).collect())
+    }
+
+    pub fn with_unit(mut self, unit: impl Into<String>) -> Self {
+        self.unit = Some(unit.into());
+        self
+    }
+
+    pub fn points(&self) -> &[EventPoint] {
+        &self.points
+    }
+
+    /// Add a point, keeping the series in time order
+    pub fn push(&mut self, time_ns: u64, value: f64) {
+        let points = Arc::make_mut(&mut self.points);
+        let index = points.partition_point(|p| p.time_ns <= time_ns);
+        points.insert(index, EventPoint::new(time_ns, value));
+    }
+
+    /// Merge the points of `other` into this series
+    pub fn extend(&mut self, other: &EventSeries) {
+        if other.is_empty() {
+            return;
+        }
+        if self.points.last().is_none_or(|last| last.time_ns <= other.points[0].time_ns) {
+            Arc::make_mut(&mut self.points).extend_from_slice(&other.points);
+        } else {
+            let points = Arc::make_mut(&mut self.points);
+            points.extend_from_slice(&other.points);
+            points.sort_by_key(|p| p.time_ns);
+        }
+        if self.unit.is_none() {
+            self.unit = other.unit.clone();
+        }
+    }
+
+    /// Remove and return the points earlier than `time_ns`
+    pub fn split_before(&mut self, time_ns: u64) -> EventSeries {
+        let index = self.points.partition_point(|p| p.time_ns < time_ns);
+        let rest = Arc::make_mut(&mut self.points).split_off(index);
+        let head = std::mem::replace(Arc::make_mut(&mut self.points), rest);
+        EventSeries { points: Arc::new(head), unit: self.unit.clone() }
+    }
+
+    /// Points from `start_ns` up to, but not including, `end_ns`
+    pub fn window(&self, start_ns: u64, end_ns: u64) -> EventSeries {
+        let start = self.points.partition_point(|p| p.time_ns < start_ns);
+        let end = self.points.partition_point(|p| p.time_ns < end_ns).max(start);
+        EventSeries { points: Arc::new(self.points[start..end].to_vec()), unit: self.unit.clone() }
+    }
+
+    /// Seconds between consecutive points
+    pub fn intervals(&self) -> Vec<f64> {
+        self.points.windows(2).map(|w| (w[1].time_ns - w[0].time_ns) as f64 * 1e-9).collect()
+    }
+
+    /// Mean event rate in Hz over the span of the series, `None` for fewer
+    /// than two points or a zero span
+    pub fn rate_hz(&self) -> Option<f64> {
+        let (first, last) = (self.points.first()?, self.points.last()?);
+        let span = (last.time_ns - first.time_ns) as f64 * 1e-9;
+        (span > 0.0).then(|| (self.points.len() - 1) as f64 / span)
+    }
+}
+
+impl Deref for EventSeries {
+    type Target = [EventPoint];
+
+    fn deref(&self) -> &[EventPoint] {
+        &self.points
+    }
+}
+
+impl From<Vec<EventPoint>> for EventSeries {
+    fn from(points: Vec<EventPoint>) -> Self {
+        Self::new(points)
+    }
+}
@@ -0,0 +1,228 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Index;
+use std::sync::Arc;
+
+/// Typed value stored in frame metadata
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MetadataValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl MetadataValue {
+    /// Numeric value; text is parsed so hand-written string metadata still works
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            MetadataValue::Float(v) => Some(*v),
+            MetadataValue::Int(v) => Some(*v as f64),
+            MetadataValue::Text(s) => s.trim().parse().ok(),
+            MetadataValue::Bool(_) => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            MetadataValue::Int(v) => Some(*v),
+            MetadataValue::Float(v) if v.fract() == 0.0 => Some(*v as i64),
+            MetadataValue::Text(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            MetadataValue::Bool(v) => Some(*v),
+            MetadataValue::Text(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            MetadataValue::Text(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for MetadataValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetadataValue::Bool(v) => write!(f, "{}", v),
+            MetadataValue::Int(v) => write!(f, "{}", v),
+            MetadataValue::Float(v) => write!(f, "{}", v),
+            MetadataValue::Text(s) => f.write_str(s),
+        }
+    }
+}
+
+macro_rules! impl_from {
+    ($($ty:ty => $variant:ident as $conv:ty),* $(,)?) => {
+        $(impl From<$ty> for MetadataValue {
+            fn from(v: $ty) -> Self {
+                MetadataValue::$variant(v as $conv)
+            }
+        })*
+    };
+}
+
+impl_from!(
+    i32 => Int as i64,
+    i64 => Int as i64,
+    u32 => Int as i64,
+    u64 => Int as i64,
+    usize => Int as i64,
+    f32 => Float as f64,
+    f64 => Float as f64,
+);
+
+impl From<bool> for MetadataValue {
+    fn from(v: bool) -> Self {
+        MetadataValue::Bool(v)
+    }
+}
+
+impl From<&str> for MetadataValue {
+    fn from(v: &str) -> Self {
+        MetadataValue::Text(v.to_string())
+    }
+}
+
+impl From<String> for MetadataValue {
+    fn from(v: String) -> Self {
+        MetadataValue::Text(v)
+    }
+}
+
+impl From<&String> for MetadataValue {
+    fn from(v: &String) -> Self {
+        MetadataValue::Text(v.clone())
+    }
+}
+
+impl PartialEq<str> for MetadataValue {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == Some(other)
+    }
+}
+
+impl PartialEq<&str> for MetadataValue {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == Some(*other)
+    }
+}
+
+impl PartialEq<bool> for MetadataValue {
+    fn eq(&self, other: &bool) -> bool {
+        matches!(self, MetadataValue::Bool(v) if v == other)
+    }
+}
+
+impl PartialEq<f64> for MetadataValue {
+    fn eq(&self, other: &f64) -> bool {
+        matches!(self, MetadataValue::Float(_) | MetadataValue::Int(_)) && self.as_f64() == Some(*other)
+    }
+}
+
+impl PartialEq<i64> for MetadataValue {
+    fn eq(&self, other: &i64) -> bool {
+        matches!(self, MetadataValue::Int(v) if v == other)
+    }
+}
+
+/// Frame metadata shared between frame clones and copied on first write
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Metadata(Arc<HashMap<String, MetadataValue>>);
+
+impl Metadata {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&MetadataValue> {
+        self.0.get(key)
+    }
+
+    pub fn get_f64(&self, key: &str) -> Option<f64> {
+        self.get(key).and_then(MetadataValue::as_f64)
+    }
+
+    pub fn get_i64(&self, key: &str) -> Option<i64> {
+        self.get(key).and_then(MetadataValue::as_i64)
+    }
+
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        self.get(key).and_then(MetadataValue::as_bool)
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(MetadataValue::as_str)
+    }
+
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<MetadataValue>) -> Option<MetadataValue> {
+        Arc::make_mut(&mut self.0).insert(key.into(), value.into())
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<MetadataValue> {
+        if !self.0.contains_key(key) {
+            return None;
+        }
+        Arc::make_mut(&mut self.0).remove(key)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.0.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.0.keys()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &MetadataValue)> {
+        self.0.iter()
+    }
+
+    /// Whether two frames still share the same metadata storage
+    pub fn ptr_eq(a: &Metadata, b: &Metadata) -> bool {
+        Arc::ptr_eq(&a.0, &b.0)
+    }
+}
+
+impl Index<&str> for Metadata {
+    type Output = MetadataValue;
+
+    fn index(&self, key: &str) -> &MetadataValue {
+        self.get(key).unwrap_or_else(|| panic!("metadata key '{}' not found", key))
+    }
+}
+
+impl<K: Into<String>, V: Into<MetadataValue>> FromIterator<(K, V)> for Metadata {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self(Arc::new(iter.into_iter().map(|(k, v)| (k.into(), v.into())).collect()))
+    }
+}
+
+impl Serialize for Metadata {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Metadata {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        HashMap::deserialize(deserializer).map(|map| Self(Arc::new(map)))
+    }
+}
@@ -1,5 +1,19 @@
+pub mod channel;
 pub mod dataframe;
+pub mod event_series;
+pub mod decimate;
+pub mod error;
+pub mod metadata;
 pub mod node;
+pub mod pair;
+pub mod reblock;
 
-pub use dataframe::DataFrame;
-pub use node::{ProcessingNode, NodeContext};
+pub use channel::{Channel, ChannelRole};
+pub use dataframe::{DataFrame, SharedFrame};
+pub use event_series::{EventPoint, EventSeries};
+pub use decimate::FrameDecimator;
+pub use error::AudiotabError;
+pub use metadata::{Metadata, MetadataValue};
+pub use node::{ProcessingNode, NodeContext, OutputShape};
+pub use pair::PortPairer;
+pub use reblock::Reblocker;
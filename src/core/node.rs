@@ -2,6 +2,7 @@ use super::DataFrame;
 use anyhow::Result;
 use async_trait::async_trait;
 use serde_json::Value;
+use std::any::Any;
 
 /// Context passed to nodes during processing
 #[derive(Clone, Debug)]
@@ -10,20 +11,80 @@ pub struct NodeContext {
     pub config: Value,
 }
 
+/// Re-blocking and decimation a node asks for on the edges leaving one of its outputs
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OutputShape {
+    pub block_size: Option<usize>,
+    pub decimation: Option<usize>,
+}
+
+/// Upcast helper so boxed nodes can be downcast to their concrete type
+pub trait AsAny: Any {
+    fn as_any(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
 /// Base trait that all processing nodes must implement
 #[async_trait]
-pub trait ProcessingNode: Send + Sync {
+pub trait ProcessingNode: AsAny + Send + Sync {
     /// Initialize the node with configuration
+    ///
+    /// By default the parameters declared with `#[param]` are parsed and
+    /// validated (see `#[derive(StreamNode)]`), then `on_configured` runs.
+    /// Override it only for nodes that take full control of their config.
     async fn on_create(&mut self, config: Value) -> Result<()> {
+        crate::registry::ParamParser::apply(self.as_any_mut(), &config)?;
+        self.on_configured(&config).await
+    }
+
+    /// Node-specific setup after the default `on_create` has applied the
+    /// declared parameters: extra config keys, derived state, resets
+    async fn on_configured(&mut self, config: &Value) -> Result<()> {
         let _ = config;
         Ok(())
     }
 
+    /// Upgrade a config saved by an older version of this node (see `NodeMetadata::version`)
+    fn migrate_config(&self, from_version: u32, config: Value) -> Result<Value> {
+        let _ = from_version;
+        Ok(config)
+    }
+
+    /// How edges leaving output `port` shape the stream, unless the
+    /// connection sets its own `block_size` or `decimation`
+    fn output_shape(&self, port: &str) -> OutputShape {
+        let _ = port;
+        OutputShape::default()
+    }
+
     /// Process a single data frame
     async fn process(&mut self, input: DataFrame) -> Result<DataFrame>;
 
+    /// Emit data the node is holding back (partial averages, recorder buffers)
+    /// without ending the stream; called when the pipeline is flushed
+    async fn on_flush(&mut self) -> Result<Option<DataFrame>> {
+        Ok(None)
+    }
+
+    /// No more frames will arrive: emit any tail data before `on_destroy`
+    ///
+    /// Defaults to a flush.
+    async fn on_eos(&mut self) -> Result<Option<DataFrame>> {
+        self.on_flush().await
+    }
+
     /// Cleanup when node is destroyed
     async fn on_destroy(&mut self) -> Result<()> {
         Ok(())
     }
+
+    /// Mutable `Any` access for injecting runtime resources (ring buffers, device channels)
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self.as_any()
+    }
 }
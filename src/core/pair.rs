@@ -0,0 +1,91 @@
+use super::DataFrame;
+use anyhow::Result;
+use std::collections::VecDeque;
+
+/// Frames queued on one port before the oldest is given up on
+const MAX_PENDING: usize = 64;
+
+/// Pairs the frames of a two-input node by sequence id
+///
+/// Nodes with two inputs (a reference and a measurement, a signal and its
+/// tacho) accept either one frame holding both channels or, when each port
+/// is wired to its own source, one frame per port. Frames tagged with one of
+/// the two ports (`input_port` metadata) are queued until the other port
+/// delivers the same `sequence_id`, then joined into a frame holding both
+/// channels, stamped with the first port's timestamp and metadata. A port
+/// frame without the named channel contributes its only channel instead, so
+/// two devices both delivering `ch0` can be paired. Frames whose partner was
+/// skipped on the other port, or that wait behind more than 64 others, are
+/// dropped.
+#[derive(Debug, Clone, Default)]
+pub struct PortPairer {
+    pending: [VecDeque<DataFrame>; 2],
+    dropped: u64,
+}
+
+impl PortPairer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a frame, returning it once it holds both channels
+    ///
+    /// `ports` names each input as (port id, channel name). Frames that did
+    /// not arrive on either port are returned unchanged.
+    pub fn push(&mut self, mut frame: DataFrame, ports: [(&str, &str); 2]) -> Result<Option<DataFrame>> {
+        let Some(side) = ports
+            .iter()
+            .position(|(port, _)| frame.metadata.get_str("input_port") == Some(*port))
+        else {
+            return Ok(Some(frame));
+        };
+
+        let (port, name) = ports[side];
+        let channel = match frame.payload.remove(name) {
+            Some(channel) => channel,
+            None if frame.payload.len() == 1 => frame.payload.drain().next().expect("one channel").1,
+            None => anyhow::bail!("Missing channel '{}' on port '{}'", name, port),
+        };
+        frame.payload.clear();
+        frame.payload.insert(name.to_string(), channel);
+        self.pending[side].push_back(frame);
+        if self.pending[side].len() > MAX_PENDING {
+            self.pending[side].pop_front();
+            self.dropped += 1;
+        }
+
+        loop {
+            let (Some(first), Some(second)) = (self.pending[0].front(), self.pending[1].front()) else {
+                return Ok(None);
+            };
+            match first.sequence_id.cmp(&second.sequence_id) {
+                std::cmp::Ordering::Equal => {
+                    let mut paired = self.pending[0].pop_front().expect("front checked");
+                    let other = self.pending[1].pop_front().expect("front checked");
+                    paired.payload.extend(other.payload);
+                    paired.events.extend(other.events);
+                    paired.metadata.remove("input_port");
+                    return Ok(Some(paired));
+                }
+                // The older frame's partner was lost upstream
+                std::cmp::Ordering::Less => {
+                    self.pending[0].pop_front();
+                    self.dropped += 1;
+                }
+                std::cmp::Ordering::Greater => {
+                    self.pending[1].pop_front();
+                    self.dropped += 1;
+                }
+            }
+        }
+    }
+
+    /// Frames discarded because the other port never delivered their sequence id
+    pub fn dropped_frames(&self) -> u64 {
+        self.dropped
+    }
+
+    pub fn clear(&mut self) {
+        self.pending.iter_mut().for_each(VecDeque::clear);
+    }
+}
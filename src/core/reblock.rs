@@ -0,0 +1,136 @@
+use super::{Channel, ChannelRole, DataFrame, EventSeries, Metadata};
+use std::collections::HashMap;
+
+/// Splits and joins a frame stream into blocks of a fixed number of samples
+///
+/// Signal channels are accumulated across input frames and emitted in
+/// `block_size`-sample blocks, so a 4096-point FFT can sit behind a device
+/// delivering 1024-frame packets. Each block is stamped with the capture
+/// time of its first sample. Other channels (spectra, measurements) are not
+/// split; their latest value rides along with the next block. Frames with
+/// channels but no signal channel (e.g. FFT output) have nothing to re-block
+/// and pass through unchanged. Samples of a signal channel that is missing
+/// from an input frame are discarded.
+///
+/// Event points go to the block whose time span holds them; points older
+/// than the block being emitted go with it.
+#[derive(Debug, Clone)]
+pub struct Reblocker {
+    block_size: usize,
+    /// Per channel: properties of the last input channel and samples not yet emitted
+    pending: HashMap<String, (Channel, Vec<f64>)>,
+    carried: HashMap<String, Channel>,
+    /// Event points not yet emitted
+    events: HashMap<String, EventSeries>,
+    /// Timestamp of the first pending sample
+    start_timestamp: u64,
+    sample_rate: f64,
+    metadata: Metadata,
+    next_sequence_id: u64,
+}
+
+impl Reblocker {
+    pub fn new(block_size: usize) -> Self {
+        Self {
+            block_size: block_size.max(1),
+            pending: HashMap::new(),
+            carried: HashMap::new(),
+            events: HashMap::new(),
+            start_timestamp: 0,
+            sample_rate: 48000.0,
+            metadata: Metadata::new(),
+            next_sequence_id: 0,
+        }
+    }
+
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Samples buffered towards the next block
+    pub fn pending_len(&self) -> usize {
+        self.pending.values().map(|(_, samples)| samples.len()).min().unwrap_or(0)
+    }
+
+    /// Add a frame, returning every block it completes
+    pub fn push(&mut self, frame: &DataFrame) -> Vec<DataFrame> {
+        if !frame.payload.is_empty() && !frame.payload.values().any(|c| c.role == ChannelRole::Signal) {
+            return vec![frame.clone()];
+        }
+        // Re-anchor on the input clock whenever the buffer is empty, so rounding never accumulates
+        if self.pending_len() == 0 {
+            self.start_timestamp = frame.timestamp;
+        }
+        if let Some(rate) = frame.sample_rate() {
+            self.sample_rate = rate;
+        }
+        self.metadata = frame.metadata.clone();
+        // A signal channel that stops arriving would hold every block back
+        self.pending.retain(|name, _| frame.payload.contains_key(name));
+
+        for (name, channel) in &frame.payload {
+            if channel.role == ChannelRole::Signal {
+                let entry = self
+                    .pending
+                    .entry(name.clone())
+                    .or_insert_with(|| (channel.clone(), Vec::new()));
+                entry.0 = channel.clone();
+                entry.1.extend_from_slice(channel.samples());
+            } else {
+                self.carried.insert(name.clone(), channel.clone());
+            }
+        }
+        for (name, events) in &frame.events {
+            self.events.entry(name.clone()).or_default().extend(events);
+        }
+
+        let mut blocks = Vec::new();
+        while !self.pending.is_empty() && self.pending_len() >= self.block_size {
+            blocks.push(self.emit(self.block_size));
+        }
+        blocks
+    }
+
+    /// Emit the remaining samples as a final, shorter block
+    pub fn finish(&mut self) -> Option<DataFrame> {
+        let remaining = self.pending_len();
+        if remaining == 0 {
+            return None;
+        }
+        let mut block = self.emit(remaining);
+        block.metadata.insert("partial_block", true);
+        for (name, events) in self.events.drain() {
+            block.events.entry(name).or_default().extend(&events);
+        }
+        self.pending.clear();
+        Some(block)
+    }
+
+    fn emit(&mut self, len: usize) -> DataFrame {
+        let mut block = DataFrame::new(self.start_timestamp, self.next_sequence_id);
+        block.metadata = self.metadata.clone();
+        block.metadata.insert("block_size", len as u64);
+        for (name, (template, samples)) in self.pending.iter_mut() {
+            let rest = samples.split_off(len);
+            let head = std::mem::replace(samples, rest);
+            block.insert_channel(name.clone(), template.with_samples(head));
+        }
+        for (name, channel) in self.carried.drain() {
+            block.insert_channel(name, channel);
+        }
+
+        let duration = (len as f64 * 1e9 / self.sample_rate).round() as u64;
+        let end = self.start_timestamp + duration;
+        for (name, events) in self.events.iter_mut() {
+            let due = events.split_before(end);
+            if !due.is_empty() {
+                block.insert_events(name.clone(), due);
+            }
+        }
+        self.events.retain(|_, events| !events.is_empty());
+
+        self.next_sequence_id += 1;
+        self.start_timestamp = end;
+        block
+    }
+}
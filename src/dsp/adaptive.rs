@@ -0,0 +1,103 @@
+/// Keeps the NLMS update finite while the reference is silent
+const REGULARIZATION: f64 = 1e-10;
+
+/// LMS / NLMS adaptive FIR filter
+///
+/// Filters a reference signal to estimate the part of a primary signal that
+/// is correlated with it; the error (primary minus estimate) is what is
+/// left. After each sample the weights move along the error gradient by
+/// `step_size`, which NLMS divides by the energy of the reference history so
+/// convergence does not depend on the reference level. NLMS is stable for
+/// step sizes between 0 and 2; plain LMS needs a step well below
+/// `1 / (taps * reference power)`.
+#[derive(Debug, Clone)]
+pub struct AdaptiveFilter {
+    weights: Vec<f64>,
+    /// Reference history stored twice so the newest `taps` samples are
+    /// always one contiguous slice starting at `pos`
+    history: Vec<f64>,
+    pos: usize,
+    step_size: f64,
+    normalized: bool,
+}
+
+impl AdaptiveFilter {
+    pub fn new(taps: usize, step_size: f64, normalized: bool) -> Self {
+        let taps = taps.max(1);
+        Self {
+            weights: vec![0.0; taps],
+            history: vec![0.0; 2 * taps],
+            pos: 0,
+            step_size,
+            normalized,
+        }
+    }
+
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// Filter one sample pair, returning `(estimate, error)`; weights are
+    /// only updated when `adapt` is set
+    pub fn process(&mut self, reference: f64, primary: f64, adapt: bool) -> (f64, f64) {
+        let taps = self.weights.len();
+        self.pos = if self.pos == 0 { taps - 1 } else { self.pos - 1 };
+        self.history[self.pos] = reference;
+        self.history[self.pos + taps] = reference;
+        let x = &self.history[self.pos..self.pos + taps];
+
+        let estimate: f64 = self.weights.iter().zip(x).map(|(w, x)| w * x).sum();
+        let error = primary - estimate;
+        if adapt {
+            let step = if self.normalized {
+                self.step_size / (REGULARIZATION + x.iter().map(|x| x * x).sum::<f64>())
+            } else {
+                self.step_size
+            };
+            for (w, x) in self.weights.iter_mut().zip(x) {
+                *w += step * error * x;
+            }
+        }
+        (estimate, error)
+    }
+
+    pub fn reset(&mut self) {
+        self.weights.fill(0.0);
+        self.history.fill(0.0);
+        self.pos = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identifies_fir_path() {
+        let path = [0.6, -0.3, 0.1];
+        let mut filter = AdaptiveFilter::new(4, 0.5, true);
+        let mut state = 12345u64;
+        let mut reference = [0.0; 3];
+        let mut last_error = f64::MAX;
+        for _ in 0..4000 {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let x = (state >> 11) as f64 / (1u64 << 53) as f64 - 0.5;
+            reference = [x, reference[0], reference[1]];
+            let primary: f64 = path.iter().zip(&reference).map(|(h, x)| h * x).sum();
+            last_error = filter.process(x, primary, true).1;
+        }
+        assert!(last_error.abs() < 1e-9);
+        for (w, h) in filter.weights().iter().zip(path.iter().chain([0.0].iter())) {
+            assert!((w - h).abs() < 1e-6, "{:?}", filter.weights());
+        }
+    }
+
+    #[test]
+    fn test_frozen_weights_do_not_adapt() {
+        let mut filter = AdaptiveFilter::new(2, 0.5, false);
+        assert_eq!(filter.process(1.0, 1.0, false), (0.0, 1.0));
+        assert_eq!(filter.weights(), &[0.0, 0.0]);
+        filter.process(1.0, 1.0, true);
+        assert_eq!(filter.weights(), &[0.5, 0.5]);
+    }
+}
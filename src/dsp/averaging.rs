@@ -0,0 +1,120 @@
+use anyhow::Result;
+use std::collections::VecDeque;
+
+/// Spectrum averaging strategy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AveragingMode {
+    /// Output the most recent spectrum unchanged
+    None,
+    /// Arithmetic mean of the last N spectra
+    Linear,
+    /// Exponential moving average with time constant of N spectra
+    Exponential,
+    /// Maximum of each bin since the last reset
+    PeakHold,
+}
+
+impl AveragingMode {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "none" | "off" => Ok(AveragingMode::None),
+            "linear" => Ok(AveragingMode::Linear),
+            "exponential" | "exp" => Ok(AveragingMode::Exponential),
+            "peak_hold" | "peak-hold" | "peak" => Ok(AveragingMode::PeakHold),
+            _ => anyhow::bail!("Unknown averaging mode: {}", name),
+        }
+    }
+}
+
+/// Accumulates successive spectra of equal length and produces an averaged result
+#[derive(Debug, Clone)]
+pub struct SpectrumAverager {
+    mode: AveragingMode,
+    num_averages: usize,
+    /// Weight of each new spectrum in exponential averaging
+    alpha: f64,
+    history: VecDeque<Vec<f64>>,
+    current: Vec<f64>,
+    count: u64,
+}
+
+impl SpectrumAverager {
+    pub fn new(mode: AveragingMode, num_averages: usize) -> Self {
+        Self {
+            mode,
+            num_averages: num_averages.max(1),
+            alpha: 1.0 / num_averages.max(1) as f64,
+            history: VecDeque::new(),
+            current: Vec::new(),
+            count: 0,
+        }
+    }
+
+    /// Weight the previous average by `factor` (0.0-1.0) in exponential
+    /// averaging, instead of the `1 - 1/N` given by the average count
+    pub fn with_forgetting_factor(mut self, factor: f64) -> Self {
+        self.alpha = 1.0 - factor.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Add a spectrum and return the updated average
+    pub fn push(&mut self, spectrum: Vec<f64>) -> &[f64] {
+        if self.current.len() != spectrum.len() {
+            self.reset();
+        }
+        self.count += 1;
+
+        match self.mode {
+            AveragingMode::None => {
+                self.current = spectrum;
+            }
+            AveragingMode::Linear => {
+                self.history.push_back(spectrum);
+                if self.history.len() > self.num_averages {
+                    self.history.pop_front();
+                }
+                let n = self.history.len() as f64;
+                let bins = self.history[0].len();
+                self.current = (0..bins)
+                    .map(|i| self.history.iter().map(|s| s[i]).sum::<f64>() / n)
+                    .collect();
+            }
+            AveragingMode::Exponential => {
+                if self.count == 1 {
+                    self.current = spectrum;
+                } else {
+                    for (avg, new) in self.current.iter_mut().zip(spectrum.iter()) {
+                        *avg += self.alpha * (new - *avg);
+                    }
+                }
+            }
+            AveragingMode::PeakHold => {
+                if self.count == 1 {
+                    self.current = spectrum;
+                } else {
+                    for (peak, new) in self.current.iter_mut().zip(spectrum.iter()) {
+                        *peak = peak.max(*new);
+                    }
+                }
+            }
+        }
+
+        &self.current
+    }
+
+    /// Current averaged spectrum (empty until the first push)
+    pub fn current(&self) -> &[f64] {
+        &self.current
+    }
+
+    /// Number of spectra pushed since the last reset
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn reset(&mut self) {
+        self.history.clear();
+        self.current.clear();
+        self.count = 0;
+    }
+}
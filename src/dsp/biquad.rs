@@ -0,0 +1,76 @@
+/// Second-order IIR section (direct form II transposed)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Biquad {
+    pub b0: f64,
+    pub b1: f64,
+    pub b2: f64,
+    pub a1: f64,
+    pub a2: f64,
+    z1: f64,
+    z2: f64,
+}
+
+impl Biquad {
+    /// Create a section from normalised coefficients (a0 == 1)
+    pub fn new(b0: f64, b1: f64, b2: f64, a1: f64, a2: f64) -> Self {
+        Self { b0, b1, b2, a1, a2, z1: 0.0, z2: 0.0 }
+    }
+
+    /// Build a section from two real zeros and two real poles in the z-plane
+    pub fn from_real_roots(zeros: [f64; 2], poles: [f64; 2]) -> Self {
+        Self::new(
+            1.0,
+            -(zeros[0] + zeros[1]),
+            zeros[0] * zeros[1],
+            -(poles[0] + poles[1]),
+            poles[0] * poles[1],
+        )
+    }
+
+    /// Second-order Butterworth high-pass with its -3 dB point at `cutoff_hz`
+    ///
+    /// Designed with the bilinear transform, pre-warped at the cutoff.
+    pub fn highpass(cutoff_hz: f64, sample_rate: f64) -> Self {
+        let w0 = 2.0 * std::f64::consts::PI * cutoff_hz / sample_rate;
+        let (cos, alpha) = (w0.cos(), w0.sin() / std::f64::consts::SQRT_2);
+        let a0 = 1.0 + alpha;
+        let b = (1.0 + cos) / (2.0 * a0);
+        Self::new(b, -2.0 * b, b, -2.0 * cos / a0, (1.0 - alpha) / a0)
+    }
+
+    pub fn process(&mut self, x: f64) -> f64 {
+        let y = self.b0 * x + self.z1;
+        self.z1 = self.b1 * x - self.a1 * y + self.z2;
+        self.z2 = self.b2 * x - self.a2 * y;
+        y
+    }
+
+    /// Scale the numerator so the section gain is multiplied by `gain`
+    pub fn scale(&mut self, gain: f64) {
+        self.b0 *= gain;
+        self.b1 *= gain;
+        self.b2 *= gain;
+    }
+
+    /// Magnitude response at `freq_hz`
+    pub fn magnitude(&self, freq_hz: f64, sample_rate: f64) -> f64 {
+        let w = 2.0 * std::f64::consts::PI * freq_hz / sample_rate;
+        let (c1, s1) = (w.cos(), w.sin());
+        let (c2, s2) = ((2.0 * w).cos(), (2.0 * w).sin());
+        let num_re = self.b0 + self.b1 * c1 + self.b2 * c2;
+        let num_im = -(self.b1 * s1 + self.b2 * s2);
+        let den_re = 1.0 + self.a1 * c1 + self.a2 * c2;
+        let den_im = -(self.a1 * s1 + self.a2 * s2);
+        (num_re.hypot(num_im)) / (den_re.hypot(den_im))
+    }
+
+    pub fn reset(&mut self) {
+        self.z1 = 0.0;
+        self.z2 = 0.0;
+    }
+}
+
+/// Run a sample through a cascade of sections
+pub fn process_cascade(sections: &mut [Biquad], x: f64) -> f64 {
+    sections.iter_mut().fold(x, |acc, s| s.process(acc))
+}
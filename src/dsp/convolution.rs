@@ -0,0 +1,198 @@
+use super::fir::FirFilter;
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+/// Streaming convolution with a long impulse response, without added latency
+///
+/// The first `partition_size` taps run as a direct-form FIR. The rest of
+/// the response is split into partitions of that size and convolved in the
+/// frequency domain with uniformly partitioned overlap-save: every full
+/// block of input is transformed once and multiplied with all partition
+/// spectra. Because that tail starts `partition_size` samples into the
+/// response, its output is due exactly one block after the block that
+/// completes it, so the sum matches direct convolution sample for sample.
+/// Cost per sample grows with the logarithm of the partition size instead
+/// of the response length.
+#[derive(Clone)]
+pub struct PartitionedConvolver {
+    block: usize,
+    taps: usize,
+    head: FirFilter,
+    fft: Arc<dyn Fft<f64>>,
+    ifft: Arc<dyn Fft<f64>>,
+    /// Spectra of the tail partitions, `2 * block` bins each
+    partitions: Vec<Vec<Complex<f64>>>,
+    /// Spectra of the most recent input blocks, newest first
+    history: VecDeque<Vec<Complex<f64>>>,
+    /// Previous and current input block
+    input: Vec<f64>,
+    /// Tail output for the block being filled
+    tail_output: Vec<f64>,
+    /// Position within the current block
+    pos: usize,
+}
+
+impl std::fmt::Debug for PartitionedConvolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PartitionedConvolver")
+            .field("partition_size", &self.block)
+            .field("taps", &self.len())
+            .finish()
+    }
+}
+
+impl PartitionedConvolver {
+    pub fn new(impulse_response: &[f64], partition_size: usize) -> Self {
+        let block = partition_size.max(1);
+        let mut planner = FftPlanner::new();
+        let fft = planner.plan_fft_forward(2 * block);
+        let ifft = planner.plan_fft_inverse(2 * block);
+
+        let head_len = impulse_response.len().min(block);
+        let partitions: Vec<Vec<Complex<f64>>> = impulse_response[head_len..]
+            .chunks(block)
+            .map(|taps| {
+                let mut spectrum = vec![Complex::new(0.0, 0.0); 2 * block];
+                for (bin, &tap) in spectrum.iter_mut().zip(taps) {
+                    bin.re = tap;
+                }
+                fft.process(&mut spectrum);
+                spectrum
+            })
+            .collect();
+
+        Self {
+            block,
+            taps: impulse_response.len(),
+            head: FirFilter::new(impulse_response[..head_len].to_vec()),
+            fft,
+            ifft,
+            history: VecDeque::with_capacity(partitions.len()),
+            partitions,
+            input: vec![0.0; 2 * block],
+            tail_output: vec![0.0; block],
+            pos: 0,
+        }
+    }
+
+    /// Length of the impulse response in taps
+    pub fn len(&self) -> usize {
+        self.taps
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn partition_size(&self) -> usize {
+        self.block
+    }
+
+    pub fn process(&mut self, x: f64) -> f64 {
+        let y = self.head.process(x);
+        if self.partitions.is_empty() {
+            return y;
+        }
+        let tail = self.tail_output[self.pos];
+        self.input[self.block + self.pos] = x;
+        self.pos += 1;
+        if self.pos == self.block {
+            self.convolve_block();
+            self.pos = 0;
+        }
+        y + tail
+    }
+
+    pub fn process_block(&mut self, samples: &mut [f64]) {
+        for sample in samples.iter_mut() {
+            *sample = self.process(*sample);
+        }
+    }
+
+    /// Convolve the tail with the block just completed; the valid half of
+    /// the overlap-save result is the output for the next block
+    fn convolve_block(&mut self) {
+        let size = 2 * self.block;
+        let mut spectrum: Vec<Complex<f64>> = self.input.iter().map(|&x| Complex::new(x, 0.0)).collect();
+        self.fft.process(&mut spectrum);
+        if self.history.len() == self.partitions.len() {
+            self.history.pop_back();
+        }
+        self.history.push_front(spectrum);
+
+        let mut sum = vec![Complex::new(0.0, 0.0); size];
+        for (input, partition) in self.history.iter().zip(&self.partitions) {
+            for ((acc, a), b) in sum.iter_mut().zip(input).zip(partition) {
+                *acc += a * b;
+            }
+        }
+        self.ifft.process(&mut sum);
+        for (out, bin) in self.tail_output.iter_mut().zip(&sum[self.block..]) {
+            *out = bin.re / size as f64;
+        }
+        self.input.copy_within(self.block.., 0);
+    }
+
+    pub fn reset(&mut self) {
+        self.head.reset();
+        self.history.clear();
+        self.input.fill(0.0);
+        self.tail_output.fill(0.0);
+        self.pos = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn direct(ir: &[f64], input: &[f64]) -> Vec<f64> {
+        (0..input.len())
+            .map(|n| ir.iter().enumerate().take(n + 1).map(|(k, h)| h * input[n - k]).sum())
+            .collect()
+    }
+
+    fn noise(len: usize, seed: u64) -> Vec<f64> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                (state >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_matches_direct_convolution() {
+        let input = noise(1000, 1);
+        for (ir_len, partition) in [(5, 16), (16, 16), (100, 16), (333, 32), (64, 1)] {
+            let ir = noise(ir_len, ir_len as u64);
+            let mut convolver = PartitionedConvolver::new(&ir, partition);
+            let mut output = input.clone();
+            // Uneven chunks, as frames of varying size would arrive
+            for chunk in output.chunks_mut(37) {
+                convolver.process_block(chunk);
+            }
+            for (n, (a, b)) in output.iter().zip(direct(&ir, &input)).enumerate() {
+                assert!((a - b).abs() < 1e-9, "ir {} partition {} sample {}: {} != {}", ir_len, partition, n, a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_reset_clears_state() {
+        let ir = noise(50, 7);
+        let mut convolver = PartitionedConvolver::new(&ir, 8);
+        let mut first = noise(40, 3);
+        convolver.process_block(&mut first);
+        convolver.reset();
+        let mut impulse = vec![0.0; 60];
+        impulse[0] = 1.0;
+        convolver.process_block(&mut impulse);
+        for (a, b) in impulse.iter().zip(ir.iter().chain(std::iter::repeat(&0.0))) {
+            assert!((a - b).abs() < 1e-12);
+        }
+        assert_eq!(convolver.len(), 50);
+    }
+}
@@ -0,0 +1,165 @@
+use anyhow::Result;
+
+/// Requantization of samples converted to an integer format
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DitherMode {
+    /// Truncate, as the plain conversion does
+    #[default]
+    None,
+    /// Round after adding triangular noise of ±1 LSB, which decorrelates
+    /// the quantization error from the signal
+    Tpdf,
+    /// TPDF dither with first-order error feedback, moving the noise
+    /// towards Nyquist where it is less audible and out of most measurement
+    /// bands
+    Shaped,
+}
+
+impl DitherMode {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "none" | "off" => Ok(DitherMode::None),
+            "tpdf" => Ok(DitherMode::Tpdf),
+            "shaped" | "noise_shaped" => Ok(DitherMode::Shaped),
+            _ => anyhow::bail!("Unknown dither mode: {}", name),
+        }
+    }
+}
+
+/// Quantization error fed back is limited to this many LSBs, so a clipped
+/// sample does not destabilise the noise shaper
+const MAX_ERROR: f64 = 1.5;
+
+/// Dither state carried across packets
+///
+/// Holds the noise generator and, for noise shaping, the quantization
+/// error of each channel's previous sample.
+#[derive(Debug, Clone)]
+pub struct Dither {
+    mode: DitherMode,
+    state: u64,
+    errors: Vec<f64>,
+}
+
+impl Dither {
+    pub fn new(mode: DitherMode) -> Self {
+        Self::with_seed(mode, 0x853c_49e6_748f_ea9b)
+    }
+
+    /// Dither with a reproducible noise sequence
+    pub fn with_seed(mode: DitherMode, seed: u64) -> Self {
+        Self { mode, state: seed, errors: Vec::new() }
+    }
+
+    pub fn mode(&self) -> DitherMode {
+        self.mode
+    }
+
+    /// Uniform noise in [-0.5, 0.5)
+    fn uniform(&mut self) -> f64 {
+        self.state = self.state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (self.state >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+    }
+
+    /// Quantize `x`, in LSBs, to an integer between `min` and `max`
+    ///
+    /// `channel` selects the error history used for noise shaping.
+    pub fn quantize(&mut self, x: f64, channel: usize, min: f64, max: f64) -> f64 {
+        match self.mode {
+            DitherMode::None => x.clamp(min, max).trunc(),
+            DitherMode::Tpdf => {
+                let noise = self.uniform() + self.uniform();
+                (x + noise).round().clamp(min, max)
+            }
+            DitherMode::Shaped => {
+                if self.errors.len() <= channel {
+                    self.errors.resize(channel + 1, 0.0);
+                }
+                let shaped = x - self.errors[channel];
+                let noise = self.uniform() + self.uniform();
+                let q = (shaped + noise).round().clamp(min, max);
+                self.errors[channel] = (q - shaped).clamp(-MAX_ERROR, MAX_ERROR);
+                q
+            }
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.errors.clear();
+    }
+}
+
+impl Default for Dither {
+    fn default() -> Self {
+        Self::new(DitherMode::None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustfft::{num_complex::Complex, FftPlanner};
+    use std::f64::consts::PI;
+
+    /// Quantization error of a quiet sine, in LSBs
+    fn error(mode: DitherMode, len: usize) -> Vec<f64> {
+        let mut dither = Dither::new(mode);
+        (0..len)
+            .map(|n| {
+                let x = 3.3 * (2.0 * PI * 440.0 * n as f64 / 48000.0).sin();
+                dither.quantize(x, 0, -32768.0, 32767.0) - x
+            })
+            .collect()
+    }
+
+    /// Error power below and above a sixteenth of the sample rate
+    fn band_powers(error: &[f64]) -> (f64, f64) {
+        let mut spectrum: Vec<Complex<f64>> = error.iter().map(|&e| Complex::new(e, 0.0)).collect();
+        FftPlanner::new().plan_fft_forward(spectrum.len()).process(&mut spectrum);
+        let half = spectrum.len() / 2;
+        let low = spectrum[1..half / 8].iter().map(|c| c.norm_sqr()).sum();
+        let high = spectrum[half / 8..half].iter().map(|c| c.norm_sqr()).sum();
+        (low, high)
+    }
+
+    #[test]
+    fn test_none_truncates() {
+        let mut dither = Dither::new(DitherMode::None);
+        assert_eq!(dither.quantize(2.7, 0, -8.0, 7.0), 2.0);
+        assert_eq!(dither.quantize(-2.7, 0, -8.0, 7.0), -2.0);
+        assert_eq!(dither.quantize(100.0, 0, -8.0, 7.0), 7.0);
+    }
+
+    #[test]
+    fn test_tpdf_decorrelates_error() {
+        // Truncation error follows the signal; dithered error averages out
+        let truncated = error(DitherMode::None, 4800);
+        let dithered = error(DitherMode::Tpdf, 4800);
+        let mean = |e: &[f64]| e.iter().sum::<f64>() / e.len() as f64;
+        let rms = |e: &[f64]| (e.iter().map(|x| x * x).sum::<f64>() / e.len() as f64).sqrt();
+        assert!(mean(&dithered).abs() < 0.05, "{}", mean(&dithered));
+        assert!(dithered.iter().all(|e| e.abs() <= 1.5));
+        // TPDF rounding adds 1/6 LSB² of noise to the 1/12 of plain rounding
+        assert!((rms(&dithered) - 0.5).abs() < 0.05, "{}", rms(&dithered));
+        assert!(rms(&truncated) > 0.2);
+    }
+
+    #[test]
+    fn test_shaping_moves_noise_up() {
+        let (tpdf_low, tpdf_high) = band_powers(&error(DitherMode::Tpdf, 8192));
+        let (shaped_low, shaped_high) = band_powers(&error(DitherMode::Shaped, 8192));
+        // TPDF noise is white
+        assert!((7.0 * tpdf_low / tpdf_high - 1.0).abs() < 0.2);
+        // First-order shaping leaves about 5% of it in the low band
+        assert!(shaped_low < 0.15 * tpdf_low, "{} vs {}", shaped_low, tpdf_low);
+        assert!(shaped_high > tpdf_high);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(DitherMode::parse("TPDF").unwrap(), DitherMode::Tpdf);
+        assert_eq!(DitherMode::parse("shaped").unwrap(), DitherMode::Shaped);
+        assert_eq!(DitherMode::parse("none").unwrap(), DitherMode::None);
+        assert!(DitherMode::parse("gaussian").is_err());
+    }
+}
@@ -0,0 +1,60 @@
+/// Static compression curve: gain change in dB (<= 0) for a detector level
+pub fn gain_reduction_db(level_db: f64, threshold_db: f64, ratio: f64) -> f64 {
+    if level_db <= threshold_db || ratio <= 1.0 {
+        0.0
+    } else {
+        (threshold_db - level_db) * (1.0 - 1.0 / ratio)
+    }
+}
+
+/// One-pole smoothing coefficient for a time constant in milliseconds
+pub fn time_coefficient(time_ms: f64, sample_rate: f64) -> f64 {
+    if time_ms <= 0.0 {
+        1.0
+    } else {
+        1.0 - (-1000.0 / (time_ms * sample_rate)).exp()
+    }
+}
+
+/// Attack/release smoothing of a gain value in dB
+///
+/// Attack applies while gain is falling (more reduction), release while it
+/// recovers towards 0 dB.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GainSmoother {
+    attack: f64,
+    release: f64,
+    gain_db: f64,
+}
+
+impl GainSmoother {
+    pub fn new(attack_ms: f64, release_ms: f64, sample_rate: f64) -> Self {
+        Self {
+            attack: time_coefficient(attack_ms, sample_rate),
+            release: time_coefficient(release_ms, sample_rate),
+            gain_db: 0.0,
+        }
+    }
+
+    pub fn process(&mut self, target_db: f64) -> f64 {
+        let coeff = if target_db < self.gain_db { self.attack } else { self.release };
+        self.gain_db += coeff * (target_db - self.gain_db);
+        self.gain_db
+    }
+
+    pub fn gain_db(&self) -> f64 {
+        self.gain_db
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_static_curve() {
+        assert_eq!(gain_reduction_db(-30.0, -20.0, 4.0), 0.0);
+        // 8 dB over threshold at 4:1 leaves 2 dB over -> 6 dB reduction
+        assert!((gain_reduction_db(-12.0, -20.0, 4.0) + 6.0).abs() < 1e-12);
+    }
+}
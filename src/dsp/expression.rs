@@ -0,0 +1,339 @@
+use anyhow::{bail, Result};
+
+/// Functions an expression can call
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Function {
+    Abs,
+    Sqrt,
+    Exp,
+    Ln,
+    Log10,
+    /// 20 * log10(|x|)
+    Db,
+    Sin,
+    Cos,
+    Min,
+    Max,
+    Pow,
+}
+
+impl Function {
+    fn parse(name: &str) -> Option<(Self, usize)> {
+        Some(match name {
+            "abs" => (Function::Abs, 1),
+            "sqrt" => (Function::Sqrt, 1),
+            "exp" => (Function::Exp, 1),
+            "ln" => (Function::Ln, 1),
+            "log10" => (Function::Log10, 1),
+            "db" => (Function::Db, 1),
+            "sin" => (Function::Sin, 1),
+            "cos" => (Function::Cos, 1),
+            "min" => (Function::Min, 2),
+            "max" => (Function::Max, 2),
+            "pow" => (Function::Pow, 2),
+            _ => return None,
+        })
+    }
+
+    fn apply(self, args: &[f64]) -> f64 {
+        match self {
+            Function::Abs => args[0].abs(),
+            Function::Sqrt => args[0].sqrt(),
+            Function::Exp => args[0].exp(),
+            Function::Ln => args[0].ln(),
+            Function::Log10 => args[0].log10(),
+            Function::Db => 20.0 * args[0].abs().log10(),
+            Function::Sin => args[0].sin(),
+            Function::Cos => args[0].cos(),
+            Function::Min => args[0].min(args[1]),
+            Function::Max => args[0].max(args[1]),
+            Function::Pow => args[0].powf(args[1]),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Const(f64),
+    /// Index into `Expression::variables`
+    Var(usize),
+    Neg(Box<Expr>),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Div(Box<Expr>, Box<Expr>),
+    Pow(Box<Expr>, Box<Expr>),
+    Call(Function, Vec<Expr>),
+}
+
+impl Expr {
+    fn eval(&self, values: &[f64]) -> f64 {
+        match self {
+            Expr::Const(c) => *c,
+            Expr::Var(i) => values[*i],
+            Expr::Neg(a) => -a.eval(values),
+            Expr::Add(a, b) => a.eval(values) + b.eval(values),
+            Expr::Sub(a, b) => a.eval(values) - b.eval(values),
+            Expr::Mul(a, b) => a.eval(values) * b.eval(values),
+            Expr::Div(a, b) => a.eval(values) / b.eval(values),
+            Expr::Pow(a, b) => a.eval(values).powf(b.eval(values)),
+            Expr::Call(f, args) => {
+                let args: Vec<f64> = args.iter().map(|a| a.eval(values)).collect();
+                f.apply(&args)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Op(char),
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut end = start;
+            while let Some(&(i, c)) = chars.peek() {
+                // Exponent signs belong to the number: 1e-3
+                let exponent_sign = (c == '-' || c == '+') && matches!(source[..i].chars().last(), Some('e' | 'E'));
+                if c.is_ascii_alphanumeric() || c == '.' || exponent_sign {
+                    end = i + c.len_utf8();
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let text = &source[start..end];
+            let value = text.parse().map_err(|_| anyhow::anyhow!("Invalid number '{}'", text))?;
+            tokens.push(Token::Number(value));
+        } else if c.is_alphabetic() || c == '_' {
+            let mut end = start;
+            while let Some(&(i, c)) = chars.peek() {
+                if c.is_alphanumeric() || c == '_' || c == '.' {
+                    end = i + c.len_utf8();
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            tokens.push(Token::Ident(source[start..end].to_string()));
+        } else if "+-*/^(),".contains(c) {
+            tokens.push(Token::Op(c));
+            chars.next();
+        } else {
+            bail!("Unexpected character '{}' in expression", c);
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent over the tokens; `variables` collects the names read
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    variables: Vec<String>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn eat(&mut self, op: char) -> bool {
+        if self.peek() == Some(&Token::Op(op)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, op: char) -> Result<()> {
+        if !self.eat(op) {
+            bail!("Expected '{}' in expression", op);
+        }
+        Ok(())
+    }
+
+    /// sum := product (('+' | '-') product)*
+    fn sum(&mut self) -> Result<Expr> {
+        let mut expr = self.product()?;
+        loop {
+            if self.eat('+') {
+                expr = Expr::Add(Box::new(expr), Box::new(self.product()?));
+            } else if self.eat('-') {
+                expr = Expr::Sub(Box::new(expr), Box::new(self.product()?));
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    /// product := unary (('*' | '/') unary)*
+    fn product(&mut self) -> Result<Expr> {
+        let mut expr = self.unary()?;
+        loop {
+            if self.eat('*') {
+                expr = Expr::Mul(Box::new(expr), Box::new(self.unary()?));
+            } else if self.eat('/') {
+                expr = Expr::Div(Box::new(expr), Box::new(self.unary()?));
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    /// unary := '-' unary | power
+    fn unary(&mut self) -> Result<Expr> {
+        if self.eat('-') {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        if self.eat('+') {
+            return self.unary();
+        }
+        self.power()
+    }
+
+    /// power := atom ('^' unary)?, right associative and binding tighter than unary minus
+    fn power(&mut self) -> Result<Expr> {
+        let base = self.atom()?;
+        if self.eat('^') {
+            return Ok(Expr::Pow(Box::new(base), Box::new(self.unary()?)));
+        }
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> Result<Expr> {
+        let token = self.peek().cloned();
+        self.pos += 1;
+        match token {
+            Some(Token::Number(value)) => Ok(Expr::Const(value)),
+            Some(Token::Op('(')) => {
+                let expr = self.sum()?;
+                self.expect(')')?;
+                Ok(expr)
+            }
+            Some(Token::Ident(name)) if self.eat('(') => {
+                let (function, arity) =
+                    Function::parse(&name).ok_or_else(|| anyhow::anyhow!("Unknown function '{}'", name))?;
+                let mut args = vec![self.sum()?];
+                while self.eat(',') {
+                    args.push(self.sum()?);
+                }
+                self.expect(')')?;
+                if args.len() != arity {
+                    bail!("{}() takes {} argument(s), got {}", name, arity, args.len());
+                }
+                Ok(Expr::Call(function, args))
+            }
+            Some(Token::Ident(name)) => Ok(match name.as_str() {
+                "pi" => Expr::Const(std::f64::consts::PI),
+                "e" => Expr::Const(std::f64::consts::E),
+                _ => {
+                    let index = match self.variables.iter().position(|v| *v == name) {
+                        Some(index) => index,
+                        None => {
+                            self.variables.push(name);
+                            self.variables.len() - 1
+                        }
+                    };
+                    Expr::Var(index)
+                }
+            }),
+            Some(Token::Op(op)) => bail!("Unexpected '{}' in expression", op),
+            None => bail!("Expression ends unexpectedly"),
+        }
+    }
+}
+
+/// Arithmetic expression over named variables, evaluated per sample
+///
+/// Supports `+ - * / ^`, parentheses, the constants `pi` and `e`, and the
+/// functions `abs sqrt exp ln log10 db sin cos` (one argument) and
+/// `min max pow` (two). `db(x)` is `20 * log10(|x|)`. Any other name is a
+/// variable; names may contain letters, digits, `_` and `.`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+    root: Expr,
+    variables: Vec<String>,
+}
+
+impl Expression {
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut parser = Parser { tokens: tokenize(source)?, pos: 0, variables: Vec::new() };
+        if parser.tokens.is_empty() {
+            bail!("Expression is empty");
+        }
+        let root = parser.sum()?;
+        if let Some(token) = parser.peek() {
+            bail!("Unexpected {:?} after the end of the expression", token);
+        }
+        Ok(Self { root, variables: parser.variables })
+    }
+
+    /// Variable names in order of first use; `eval` takes their values in this order
+    pub fn variables(&self) -> &[String] {
+        &self.variables
+    }
+
+    pub fn eval(&self, values: &[f64]) -> f64 {
+        self.root.eval(values)
+    }
+
+    /// Evaluate at every sample index of `inputs`, one slice per variable
+    pub fn eval_samples(&self, inputs: &[&[f64]]) -> Vec<f64> {
+        let len = inputs.iter().map(|i| i.len()).min().unwrap_or(0);
+        let mut values = vec![0.0; inputs.len()];
+        (0..len)
+            .map(|n| {
+                for (value, input) in values.iter_mut().zip(inputs) {
+                    *value = input[n];
+                }
+                self.root.eval(&values)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(source: &str, values: &[f64]) -> f64 {
+        Expression::parse(source).unwrap().eval(values)
+    }
+
+    #[test]
+    fn test_precedence_and_associativity() {
+        assert_eq!(eval("1 + 2 * 3", &[]), 7.0);
+        assert_eq!(eval("(1 + 2) * 3", &[]), 9.0);
+        assert_eq!(eval("8 / 4 / 2", &[]), 1.0);
+        assert_eq!(eval("2 ^ 3 ^ 2", &[]), 512.0);
+        assert_eq!(eval("-2 ^ 2", &[]), -4.0);
+        assert_eq!(eval("1e-3 * 2E+3", &[]), 2.0);
+    }
+
+    #[test]
+    fn test_variables_and_functions() {
+        let expr = Expression::parse("(mic.ch0 - ref) * 0.5 + max(ref, 0) + abs(mic.ch0)").unwrap();
+        assert_eq!(expr.variables(), ["mic.ch0", "ref"]);
+        // (-3 - 1) * 0.5 + 1 + 3
+        assert_eq!(expr.eval(&[-3.0, 1.0]), 2.0);
+        assert!((eval("db(0.1)", &[]) + 20.0).abs() < 1e-12);
+        assert!((eval("sin(pi / 2)", &[]) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        for source in ["", "1 +", "(1", "1 2", "foo(1)", "min(1)", "1 $ 2", "1.2.3"] {
+            assert!(Expression::parse(source).is_err(), "{}", source);
+        }
+    }
+}
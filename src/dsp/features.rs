@@ -0,0 +1,279 @@
+use super::WindowType;
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+use std::sync::Arc;
+
+/// Floor for mel band energies before taking the logarithm
+const MIN_ENERGY: f64 = 1e-10;
+
+fn hz_to_mel(hz: f64) -> f64 {
+    2595.0 * (1.0 + hz / 700.0).log10()
+}
+
+fn mel_to_hz(mel: f64) -> f64 {
+    700.0 * (10f64.powf(mel / 2595.0) - 1.0)
+}
+
+/// Triangular mel filters over the bins of a real FFT of `fft_size`
+///
+/// Returns, per band, the `(bin, weight)` pairs with non-zero weight. Bands
+/// are spaced evenly on the mel scale between `min_hz` and `max_hz` and
+/// peak at 1.
+pub fn mel_filterbank(
+    bands: usize,
+    fft_size: usize,
+    sample_rate: f64,
+    min_hz: f64,
+    max_hz: f64,
+) -> Vec<Vec<(usize, f64)>> {
+    let (min_mel, max_mel) = (hz_to_mel(min_hz), hz_to_mel(max_hz));
+    let edges: Vec<f64> = (0..bands + 2)
+        .map(|i| mel_to_hz(min_mel + (max_mel - min_mel) * i as f64 / (bands + 1) as f64))
+        .collect();
+    let bin_hz = sample_rate / fft_size as f64;
+    (0..bands)
+        .map(|band| {
+            let (lower, centre, upper) = (edges[band], edges[band + 1], edges[band + 2]);
+            (0..=fft_size / 2)
+                .filter_map(|bin| {
+                    let hz = bin as f64 * bin_hz;
+                    let weight = if hz > lower && hz <= centre {
+                        (hz - lower) / (centre - lower)
+                    } else if hz > centre && hz < upper {
+                        (upper - hz) / (upper - centre)
+                    } else {
+                        0.0
+                    };
+                    (weight > 0.0).then_some((bin, weight))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// First `count` coefficients of the orthonormal DCT-II of `input`
+pub fn dct2(input: &[f64], count: usize) -> Vec<f64> {
+    let n = input.len() as f64;
+    (0..count)
+        .map(|k| {
+            let scale = if k == 0 { (1.0 / n).sqrt() } else { (2.0 / n).sqrt() };
+            scale
+                * input
+                    .iter()
+                    .enumerate()
+                    .map(|(i, x)| x * (std::f64::consts::PI * k as f64 * (i as f64 + 0.5) / n).cos())
+                    .sum::<f64>()
+        })
+        .collect()
+}
+
+/// Zero crossings per sample
+pub fn zero_crossing_rate(samples: &[f64]) -> f64 {
+    if samples.len() < 2 {
+        return 0.0;
+    }
+    let crossings = samples.windows(2).filter(|w| (w[0] >= 0.0) != (w[1] >= 0.0)).count();
+    crossings as f64 / (samples.len() - 1) as f64
+}
+
+/// Features of one block
+#[derive(Debug, Clone, PartialEq)]
+pub struct Features {
+    /// Mel-frequency cepstral coefficients, `mfcc[0]` tracks overall log energy
+    pub mfcc: Vec<f64>,
+    /// Magnitude-weighted mean frequency in Hz
+    pub centroid: f64,
+    /// Frequency below which `rolloff` of the spectral energy lies, in Hz
+    pub rolloff: f64,
+    /// Distance to the previous block's normalised magnitude spectrum
+    pub flux: f64,
+    /// Zero crossings per sample
+    pub zero_crossing_rate: f64,
+}
+
+/// Per-block audio feature extraction for classifiers
+///
+/// Each block is windowed and transformed; MFCCs are the DCT of the log
+/// energies of a mel filterbank over the power spectrum. Centroid and
+/// rolloff are computed from the magnitude and power spectrum, flux from
+/// the Euclidean distance between consecutive magnitude spectra normalised
+/// to unit sum (0 for the first block), and the zero-crossing rate from the
+/// raw samples.
+#[derive(Clone)]
+pub struct FeatureExtractor {
+    block_size: usize,
+    sample_rate: f64,
+    mfcc_count: usize,
+    rolloff: f64,
+    window: Vec<f64>,
+    fft: Arc<dyn Fft<f64>>,
+    filterbank: Vec<Vec<(usize, f64)>>,
+    previous: Option<Vec<f64>>,
+}
+
+impl std::fmt::Debug for FeatureExtractor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FeatureExtractor")
+            .field("block_size", &self.block_size)
+            .field("sample_rate", &self.sample_rate)
+            .field("mel_bands", &self.filterbank.len())
+            .field("mfcc_count", &self.mfcc_count)
+            .finish()
+    }
+}
+
+/// Analysis settings of a FeatureExtractor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeatureSettings {
+    pub window: WindowType,
+    pub mel_bands: usize,
+    /// Number of MFCCs kept, at most `mel_bands`
+    pub mfcc_count: usize,
+    /// Lower edge of the mel filterbank in Hz
+    pub min_hz: f64,
+    /// Upper edge of the mel filterbank in Hz; 0 or above Nyquist uses Nyquist
+    pub max_hz: f64,
+    /// Fraction of the spectral energy below the rolloff frequency
+    pub rolloff: f64,
+}
+
+impl Default for FeatureSettings {
+    fn default() -> Self {
+        Self { window: WindowType::Hann, mel_bands: 26, mfcc_count: 13, min_hz: 0.0, max_hz: 0.0, rolloff: 0.85 }
+    }
+}
+
+impl FeatureExtractor {
+    pub fn new(block_size: usize, sample_rate: f64, settings: &FeatureSettings) -> Self {
+        let nyquist = sample_rate / 2.0;
+        let max_hz = if settings.max_hz <= 0.0 || settings.max_hz > nyquist { nyquist } else { settings.max_hz };
+        Self {
+            block_size,
+            sample_rate,
+            mfcc_count: settings.mfcc_count.min(settings.mel_bands),
+            rolloff: settings.rolloff,
+            window: settings.window.coefficients(block_size),
+            fft: FftPlanner::new().plan_fft_forward(block_size),
+            filterbank: mel_filterbank(settings.mel_bands, block_size, sample_rate, settings.min_hz, max_hz),
+            previous: None,
+        }
+    }
+
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    /// Extract the features of `block`, which must hold `block_size` samples
+    pub fn extract(&mut self, block: &[f64]) -> Features {
+        let mut spectrum: Vec<Complex<f64>> =
+            block.iter().zip(&self.window).map(|(x, w)| Complex::new(x * w, 0.0)).collect();
+        self.fft.process(&mut spectrum);
+        let magnitude: Vec<f64> = spectrum[..=self.block_size / 2].iter().map(|c| c.norm()).collect();
+        let power: Vec<f64> = magnitude.iter().map(|m| m * m).collect();
+        let bin_hz = self.sample_rate / self.block_size as f64;
+
+        let log_energies: Vec<f64> = self
+            .filterbank
+            .iter()
+            .map(|band| band.iter().map(|&(bin, w)| w * power[bin]).sum::<f64>().max(MIN_ENERGY).ln())
+            .collect();
+        let mfcc = dct2(&log_energies, self.mfcc_count);
+
+        let magnitude_sum: f64 = magnitude.iter().sum();
+        let centroid = if magnitude_sum > 0.0 {
+            magnitude.iter().enumerate().map(|(bin, m)| bin as f64 * bin_hz * m).sum::<f64>() / magnitude_sum
+        } else {
+            0.0
+        };
+
+        let total_power: f64 = power.iter().sum();
+        let mut cumulative = 0.0;
+        let rolloff_bin = power
+            .iter()
+            .position(|p| {
+                cumulative += p;
+                cumulative >= self.rolloff * total_power
+            })
+            .unwrap_or(0);
+
+        let normalised: Vec<f64> =
+            magnitude.iter().map(|m| if magnitude_sum > 0.0 { m / magnitude_sum } else { 0.0 }).collect();
+        let flux = self.previous.as_ref().map_or(0.0, |previous| {
+            normalised.iter().zip(previous).map(|(a, b)| (a - b) * (a - b)).sum::<f64>().sqrt()
+        });
+        self.previous = Some(normalised);
+
+        Features {
+            mfcc,
+            centroid,
+            rolloff: rolloff_bin as f64 * bin_hz,
+            flux,
+            zero_crossing_rate: zero_crossing_rate(block),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn sine(freq: f64, len: usize, sample_rate: f64) -> Vec<f64> {
+        (0..len).map(|n| (2.0 * PI * freq * n as f64 / sample_rate).sin()).collect()
+    }
+
+    fn extractor() -> FeatureExtractor {
+        FeatureExtractor::new(1024, 16000.0, &FeatureSettings::default())
+    }
+
+    #[test]
+    fn test_mel_filterbank_covers_range() {
+        let bank = mel_filterbank(10, 512, 16000.0, 0.0, 8000.0);
+        assert_eq!(bank.len(), 10);
+        assert!(bank.iter().all(|band| !band.is_empty()));
+        // Adjacent triangles overlap and sum to about one in between
+        let bin = bank[4].iter().find(|(_, w)| *w < 1.0).unwrap().0;
+        let total: f64 = bank.iter().flatten().filter(|(b, _)| *b == bin).map(|(_, w)| w).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_dct_of_constant_is_dc_only() {
+        let c = dct2(&[2.0; 8], 4);
+        assert!((c[0] - 2.0 * 8f64.sqrt()).abs() < 1e-12);
+        assert!(c[1..].iter().all(|x| x.abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_spectral_features_of_tones() {
+        let mut features = extractor();
+        let low = features.extract(&sine(500.0, 1024, 16000.0));
+        assert!((low.centroid - 500.0).abs() < 100.0, "{}", low.centroid);
+        assert!((low.rolloff - 500.0).abs() < 50.0, "{}", low.rolloff);
+        assert_eq!(low.flux, 0.0);
+        assert!((low.zero_crossing_rate - 1000.0 / 16000.0).abs() < 0.002);
+        assert_eq!(low.mfcc.len(), 13);
+
+        let high = features.extract(&sine(4000.0, 1024, 16000.0));
+        assert!((high.centroid - 4000.0).abs() < 200.0);
+        assert!(high.flux > 0.1);
+        assert!(high.mfcc.iter().zip(&low.mfcc).any(|(a, b)| (a - b).abs() > 1.0));
+
+        let again = features.extract(&sine(4000.0, 1024, 16000.0));
+        assert!(again.flux < 1e-9);
+    }
+
+    #[test]
+    fn test_zero_crossing_rate() {
+        assert_eq!(zero_crossing_rate(&[1.0, -1.0, 1.0, -1.0, 1.0]), 1.0);
+        assert_eq!(zero_crossing_rate(&[1.0, 2.0, 3.0]), 0.0);
+        assert_eq!(zero_crossing_rate(&[1.0]), 0.0);
+    }
+}
@@ -0,0 +1,114 @@
+use rustfft::{num_complex::Complex, FftPlanner};
+
+/// Streaming FIR filter with direct-form convolution
+#[derive(Debug, Clone)]
+pub struct FirFilter {
+    taps: Vec<f64>,
+    history: Vec<f64>,
+    pos: usize,
+}
+
+impl FirFilter {
+    pub fn new(taps: Vec<f64>) -> Self {
+        let taps = if taps.is_empty() { vec![1.0] } else { taps };
+        Self {
+            history: vec![0.0; taps.len()],
+            taps,
+            pos: 0,
+        }
+    }
+
+    pub fn taps(&self) -> &[f64] {
+        &self.taps
+    }
+
+    /// Push one input sample and return the filtered output
+    pub fn process(&mut self, x: f64) -> f64 {
+        self.push(x);
+        self.output()
+    }
+
+    /// Push one input sample without computing an output, e.g. for
+    /// samples a decimator discards
+    pub fn push(&mut self, x: f64) {
+        self.pos = (self.pos + 1) % self.taps.len();
+        self.history[self.pos] = x;
+    }
+
+    /// Filtered output at the most recent sample
+    pub fn output(&self) -> f64 {
+        let len = self.taps.len();
+        self.taps
+            .iter()
+            .enumerate()
+            .map(|(k, tap)| tap * self.history[(self.pos + len - k) % len])
+            .sum()
+    }
+
+    pub fn process_block(&mut self, samples: &mut [f64]) {
+        for sample in samples.iter_mut() {
+            *sample = self.process(*sample);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.history.fill(0.0);
+        self.pos = 0;
+    }
+}
+
+/// Design a windowed-sinc lowpass with unity DC gain
+///
+/// `cutoff` is the -6 dB frequency as a fraction of the sample rate
+/// (0.0-0.5). A Blackman window gives over 70 dB of stopband attenuation
+/// with a transition about `5.5 / num_taps` wide. The tap count is rounded
+/// up to odd.
+pub fn design_lowpass(num_taps: usize, cutoff: f64) -> Vec<f64> {
+    let n = num_taps.max(1) | 1;
+    let half = (n / 2) as f64;
+    let cutoff = cutoff.clamp(0.0, 0.5);
+    let taps: Vec<f64> = (0..n)
+        .map(|i| {
+            let t = i as f64 - half;
+            let sinc = if t == 0.0 {
+                2.0 * cutoff
+            } else {
+                (2.0 * std::f64::consts::PI * cutoff * t).sin() / (std::f64::consts::PI * t)
+            };
+            let phase = 2.0 * std::f64::consts::PI * i as f64 / (n as f64 - 1.0).max(1.0);
+            let window = 0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos();
+            sinc * if n > 1 { window } else { 1.0 }
+        })
+        .collect();
+    let gain: f64 = taps.iter().sum();
+    if gain.abs() > f64::EPSILON {
+        taps.iter().map(|t| t / gain).collect()
+    } else {
+        taps
+    }
+}
+
+/// Design a linear-phase FIR by frequency sampling
+///
+/// `magnitude` gives the linear gain at a frequency in Hz. The tap count is
+/// rounded up to odd, so the filter delays by `(num_taps - 1) / 2` samples.
+pub fn design_linear_phase(num_taps: usize, sample_rate: f64, magnitude: impl Fn(f64) -> f64) -> Vec<f64> {
+    let n = num_taps.max(1) | 1;
+    let mut spectrum: Vec<Complex<f64>> = (0..n)
+        .map(|k| Complex::new(magnitude(k.min(n - k) as f64 * sample_rate / n as f64), 0.0))
+        .collect();
+    FftPlanner::new().plan_fft_inverse(n).process(&mut spectrum);
+
+    // Centre the zero-phase response and taper it with a Hann window
+    let half = n / 2;
+    (0..n)
+        .map(|i| {
+            let window = if n > 1 {
+                0.5 - 0.5 * (2.0 * std::f64::consts::PI * (i as f64 + 1.0) / (n as f64 + 1.0)).cos()
+            } else {
+                1.0
+            };
+            spectrum[(i + n - half) % n].re / n as f64 * window
+        })
+        .collect()
+}
@@ -0,0 +1,29 @@
+pub mod window;
+pub mod averaging;
+pub mod biquad;
+pub mod weighting;
+pub mod delay_line;
+pub mod dynamics;
+pub mod fir;
+pub mod oscillator;
+pub mod expression;
+pub mod convolution;
+pub mod adaptive;
+pub mod pitch;
+pub mod features;
+pub mod dither;
+
+pub use window::WindowType;
+pub use averaging::{AveragingMode, SpectrumAverager};
+pub use biquad::Biquad;
+pub use weighting::FrequencyWeighting;
+pub use delay_line::{DelayLine, Interpolation};
+pub use dynamics::GainSmoother;
+pub use fir::FirFilter;
+pub use oscillator::{Multitone, Oscillator, ToneSpacing, Waveform};
+pub use expression::Expression;
+pub use convolution::PartitionedConvolver;
+pub use adaptive::AdaptiveFilter;
+pub use pitch::{Pitch, PitchTracker};
+pub use features::{FeatureExtractor, FeatureSettings, Features};
+pub use dither::{Dither, DitherMode};
@@ -0,0 +1,233 @@
+use anyhow::Result;
+use rustfft::{FftPlanner, num_complex::Complex};
+use std::f64::consts::PI;
+
+/// Periodic waveform shapes of a single-frequency oscillator
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Waveform {
+    #[default]
+    Sine,
+    /// High for `duty` of each period, low for the rest
+    Square { duty: f64 },
+    Triangle,
+    /// Rising ramp from -1 to 1
+    Saw,
+}
+
+impl Waveform {
+    /// Parse a waveform name as used in node configuration
+    pub fn parse(name: &str, duty: f64) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "sine" | "sin" => Ok(Waveform::Sine),
+            "square" | "pulse" => {
+                if !(0.0..=1.0).contains(&duty) {
+                    anyhow::bail!("duty_cycle must be in [0.0, 1.0], got {}", duty);
+                }
+                Ok(Waveform::Square { duty })
+            }
+            "triangle" => Ok(Waveform::Triangle),
+            "saw" | "sawtooth" => Ok(Waveform::Saw),
+            _ => anyhow::bail!("Unknown waveform: {}", name),
+        }
+    }
+
+    /// Value in [-1, 1] at `phase` cycles (in [0, 1))
+    pub fn value(&self, phase: f64) -> f64 {
+        match *self {
+            Waveform::Sine => (2.0 * PI * phase).sin(),
+            Waveform::Square { duty } => if phase < duty { 1.0 } else { -1.0 },
+            // Starts at zero rising, like the sine
+            Waveform::Triangle => {
+                let p = (phase + 0.25).fract();
+                if p < 0.5 { 4.0 * p - 1.0 } else { 3.0 - 4.0 * p }
+            }
+            Waveform::Saw => 2.0 * (phase + 0.5).fract() - 1.0,
+        }
+    }
+}
+
+/// Phase-accumulating oscillator, continuous across calls
+#[derive(Debug, Clone, Default)]
+pub struct Oscillator {
+    waveform: Waveform,
+    /// Phase increment per sample, in cycles
+    step: f64,
+    phase: f64,
+}
+
+impl Oscillator {
+    pub fn new(waveform: Waveform, frequency: f64, sample_rate: f64) -> Self {
+        Self { waveform, step: frequency / sample_rate, phase: 0.0 }
+    }
+
+    /// Phase of the next sample, in cycles
+    pub fn phase(&self) -> f64 {
+        self.phase
+    }
+
+    pub fn next_sample(&mut self) -> f64 {
+        let value = self.waveform.value(self.phase);
+        self.phase = (self.phase + self.step).rem_euclid(1.0);
+        value
+    }
+}
+
+/// How multitone frequencies are distributed between start and stop
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ToneSpacing {
+    /// Equal frequency ratios, as for acoustic measurements
+    #[default]
+    Log,
+    /// Equal frequency steps
+    Linear,
+}
+
+impl ToneSpacing {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "log" | "logarithmic" => Ok(ToneSpacing::Log),
+            "linear" => Ok(ToneSpacing::Linear),
+            _ => anyhow::bail!("Unknown tone spacing: {}", name),
+        }
+    }
+}
+
+/// Sum of equal-amplitude sines played from a precomputed periodic table
+///
+/// Tone frequencies are snapped to multiples of `sample_rate / period`, so
+/// the table repeats seamlessly. Phases follow the Newman schedule scaled
+/// by a factor between 0 (all phases aligned: highest crest factor) and 1;
+/// the factor is chosen to come closest to the requested crest factor. The
+/// lowest crest factors (under 2) need `Linear` spacing, where the tones
+/// form a harmonic series; log-spaced tones sum to a noise-like signal with
+/// a crest factor near 4 whatever the phases. The table is normalised to a
+/// peak of 1.
+#[derive(Debug, Clone)]
+pub struct Multitone {
+    table: Vec<f64>,
+    position: usize,
+    frequencies: Vec<f64>,
+    crest_factor: f64,
+}
+
+impl Multitone {
+    /// Steps of the phase scaling searched for the requested crest factor
+    const CREST_STEPS: usize = 20;
+
+    /// `tones` tones between `start` and `stop` Hz
+    ///
+    /// A `crest_factor` of 0 (or anything below the achievable minimum)
+    /// selects the lowest crest factor.
+    pub fn new(
+        tones: usize,
+        start: f64,
+        stop: f64,
+        spacing: ToneSpacing,
+        crest_factor: f64,
+        sample_rate: f64,
+        period: usize,
+    ) -> Result<Self> {
+        if tones == 0 {
+            anyhow::bail!("Multitone needs at least one tone");
+        }
+        if !(start > 0.0 && start <= stop && stop < sample_rate / 2.0) {
+            anyhow::bail!(
+                "Multitone frequencies must satisfy 0 < start <= stop < {} Hz, got {} - {}",
+                sample_rate / 2.0, start, stop
+            );
+        }
+
+        // Snap to distinct bins of the table period; linear spacing keeps a
+        // whole-bin step so the tones stay evenly spaced
+        let resolution = sample_rate / period as f64;
+        let first = ((start / resolution).round() as usize).max(1);
+        let step = if tones > 1 { (stop - start) / (tones - 1) as f64 } else { 0.0 };
+        let linear_step = ((step / resolution).round() as usize).max(1);
+        let mut bins: Vec<usize> = (0..tones)
+            .map(|i| match spacing {
+                ToneSpacing::Log => {
+                    let t = if tones > 1 { i as f64 / (tones - 1) as f64 } else { 0.0 };
+                    (start * (stop / start).powf(t) / resolution).round() as usize
+                }
+                ToneSpacing::Linear => first + i * linear_step,
+            })
+            .map(|bin| bin.clamp(1, period / 2 - 1))
+            .collect();
+        bins.dedup();
+
+        let ifft = FftPlanner::new().plan_fft_inverse(period);
+        let synthesize = |scale: f64| {
+            let mut spectrum = vec![Complex::new(0.0, 0.0); period];
+            let n = bins.len() as f64;
+            for (k, &bin) in bins.iter().enumerate() {
+                let phase = scale * PI * (k as f64).powi(2) / n;
+                spectrum[bin] = Complex::from_polar(1.0, phase);
+                spectrum[period - bin] = Complex::from_polar(1.0, -phase);
+            }
+            ifft.process(&mut spectrum);
+            let table: Vec<f64> = spectrum.iter().map(|c| c.re).collect();
+            let peak = table.iter().fold(0.0f64, |m, s| m.max(s.abs()));
+            let rms = (table.iter().map(|s| s * s).sum::<f64>() / period as f64).sqrt();
+            (table, peak / rms)
+        };
+
+        let (table, crest) = (0..=Self::CREST_STEPS)
+            .map(|step| synthesize(step as f64 / Self::CREST_STEPS as f64))
+            .min_by(|a, b| (a.1 - crest_factor).abs().partial_cmp(&(b.1 - crest_factor).abs()).unwrap())
+            .expect("at least one candidate");
+        let peak = table.iter().fold(0.0f64, |m, s| m.max(s.abs()));
+
+        Ok(Self {
+            table: table.iter().map(|s| s / peak).collect(),
+            position: 0,
+            frequencies: bins.iter().map(|&b| b as f64 * resolution).collect(),
+            crest_factor: crest,
+        })
+    }
+
+    /// Actual tone frequencies after snapping to the table period
+    pub fn frequencies(&self) -> &[f64] {
+        &self.frequencies
+    }
+
+    /// Peak to RMS ratio of the generated signal
+    pub fn crest_factor(&self) -> f64 {
+        self.crest_factor
+    }
+
+    pub fn next_sample(&mut self) -> f64 {
+        let value = self.table[self.position];
+        self.position = (self.position + 1) % self.table.len();
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_waveform_shapes() {
+        let square = Waveform::Square { duty: 0.25 };
+        assert_eq!(square.value(0.2), 1.0);
+        assert_eq!(square.value(0.3), -1.0);
+
+        let triangle = Waveform::Triangle;
+        assert!(triangle.value(0.0).abs() < 1e-12);
+        assert!((triangle.value(0.25) - 1.0).abs() < 1e-12);
+        assert!((triangle.value(0.75) + 1.0).abs() < 1e-12);
+
+        assert!(Waveform::Saw.value(0.0).abs() < 1e-12);
+        assert!((Waveform::Saw.value(0.49) - 0.98).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_multitone_crest_factor_range() {
+        let low = Multitone::new(16, 100.0, 10000.0, ToneSpacing::Linear, 0.0, 48000.0, 16384).unwrap();
+        let high = Multitone::new(16, 100.0, 10000.0, ToneSpacing::Linear, 100.0, 48000.0, 16384).unwrap();
+        assert_eq!(low.frequencies().len(), 16);
+        assert!(low.crest_factor() < 2.0, "low crest {}", low.crest_factor());
+        // All phases aligned: sqrt(2 * tones)
+        assert!((high.crest_factor() - 32f64.sqrt()).abs() < 0.01);
+    }
+}
@@ -0,0 +1,164 @@
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+use std::sync::Arc;
+
+/// Fundamental frequency estimate of one block
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pitch {
+    /// Fundamental in Hz, 0 when the block has no clear periodicity
+    pub frequency: f64,
+    /// 1 minus the normalised difference at the chosen period (0-1)
+    pub confidence: f64,
+}
+
+impl Pitch {
+    const NONE: Pitch = Pitch { frequency: 0.0, confidence: 0.0 };
+}
+
+/// YIN fundamental frequency estimator
+///
+/// The difference function of each block is computed with an FFT
+/// autocorrelation and normalised by its running mean (the cumulative mean
+/// normalised difference, CMND). The period is the first dip below
+/// `threshold` within the lag range of `[min_frequency, max_frequency]`,
+/// refined to its local minimum and by parabolic interpolation. Blocks
+/// with no dip below the threshold report frequency 0 and the confidence of
+/// the best candidate. Half of each block is the comparison window, so the
+/// lowest detectable frequency is `2 * sample_rate / block_len`.
+#[derive(Clone)]
+pub struct PitchTracker {
+    min_frequency: f64,
+    max_frequency: f64,
+    threshold: f64,
+    fft_size: usize,
+    fft: Option<Arc<dyn Fft<f64>>>,
+    ifft: Option<Arc<dyn Fft<f64>>>,
+}
+
+impl std::fmt::Debug for PitchTracker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PitchTracker")
+            .field("min_frequency", &self.min_frequency)
+            .field("max_frequency", &self.max_frequency)
+            .field("threshold", &self.threshold)
+            .finish()
+    }
+}
+
+impl PitchTracker {
+    pub fn new(min_frequency: f64, max_frequency: f64, threshold: f64) -> Self {
+        Self { min_frequency, max_frequency, threshold, fft_size: 0, fft: None, ifft: None }
+    }
+
+    /// Cumulative mean normalised difference for lags `0..=max_lag`
+    fn cmnd(&mut self, block: &[f64], max_lag: usize) -> Vec<f64> {
+        let n = block.len();
+        let len = n - max_lag;
+        if self.fft_size != n {
+            let mut planner = FftPlanner::new();
+            self.fft = Some(planner.plan_fft_forward(n));
+            self.ifft = Some(planner.plan_fft_inverse(n));
+            self.fft_size = n;
+        }
+        let fft = self.fft.as_ref().expect("FFT planned above");
+        let ifft = self.ifft.as_ref().expect("IFFT planned above");
+
+        // cross(tau) = sum_{j < len} x[j] x[j + tau]; lags stay below n, so
+        // the circular correlation does not wrap
+        let mut head: Vec<Complex<f64>> =
+            (0..n).map(|j| Complex::new(if j < len { block[j] } else { 0.0 }, 0.0)).collect();
+        let mut full: Vec<Complex<f64>> = block.iter().map(|&x| Complex::new(x, 0.0)).collect();
+        fft.process(&mut head);
+        fft.process(&mut full);
+        let mut cross: Vec<Complex<f64>> = head.iter().zip(&full).map(|(a, b)| a.conj() * b).collect();
+        ifft.process(&mut cross);
+
+        let mut energy = vec![0.0; n + 1];
+        for (j, x) in block.iter().enumerate() {
+            energy[j + 1] = energy[j] + x * x;
+        }
+        let window_energy = |start: usize| energy[start + len] - energy[start];
+
+        let mut cmnd = vec![1.0; max_lag + 1];
+        let mut running_sum = 0.0;
+        for tau in 1..=max_lag {
+            let diff = (window_energy(0) + window_energy(tau) - 2.0 * cross[tau].re / n as f64).max(0.0);
+            running_sum += diff;
+            cmnd[tau] = if running_sum > 0.0 { diff * tau as f64 / running_sum } else { 1.0 };
+        }
+        cmnd
+    }
+
+    pub fn detect(&mut self, block: &[f64], sample_rate: f64) -> Pitch {
+        let max_lag = ((sample_rate / self.min_frequency).ceil() as usize).min(block.len() / 2);
+        let min_lag = ((sample_rate / self.max_frequency).floor() as usize).max(2);
+        if max_lag <= min_lag + 1 || block.iter().all(|x| *x == 0.0) {
+            return Pitch::NONE;
+        }
+        let cmnd = self.cmnd(block, max_lag);
+
+        let below = (min_lag..max_lag).find(|&tau| cmnd[tau] < self.threshold);
+        let mut tau = match below {
+            Some(tau) => tau,
+            None => {
+                let best = (min_lag..max_lag)
+                    .min_by(|a, b| cmnd[*a].total_cmp(&cmnd[*b]))
+                    .expect("non-empty lag range");
+                return Pitch { frequency: 0.0, confidence: (1.0 - cmnd[best]).clamp(0.0, 1.0) };
+            }
+        };
+        while tau + 1 < max_lag && cmnd[tau + 1] < cmnd[tau] {
+            tau += 1;
+        }
+
+        let (l, c, r) = (cmnd[tau - 1], cmnd[tau], cmnd[tau + 1]);
+        let denom = l - 2.0 * c + r;
+        let offset = if denom.abs() > 1e-12 { (0.5 * (l - r) / denom).clamp(-0.5, 0.5) } else { 0.0 };
+        Pitch { frequency: sample_rate / (tau as f64 + offset), confidence: (1.0 - c).clamp(0.0, 1.0) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn tone(partials: &[(f64, f64)], len: usize, sample_rate: f64) -> Vec<f64> {
+        (0..len)
+            .map(|n| partials.iter().map(|(f, a)| a * (2.0 * PI * f * n as f64 / sample_rate).sin()).sum())
+            .collect()
+    }
+
+    #[test]
+    fn test_sine_frequency() {
+        let mut tracker = PitchTracker::new(50.0, 2000.0, 0.15);
+        for freq in [82.4, 220.0, 440.0, 1318.5] {
+            let pitch = tracker.detect(&tone(&[(freq, 0.5)], 2048, 48000.0), 48000.0);
+            assert!((pitch.frequency - freq).abs() < 0.005 * freq, "{} -> {:?}", freq, pitch);
+            assert!(pitch.confidence > 0.9);
+        }
+    }
+
+    #[test]
+    fn test_weak_fundamental_is_not_an_octave_error() {
+        // Machinery-like spectrum where the second harmonic dominates
+        let block = tone(&[(100.0, 0.2), (200.0, 1.0), (300.0, 0.5)], 4096, 48000.0);
+        let pitch = PitchTracker::new(50.0, 1000.0, 0.15).detect(&block, 48000.0);
+        assert!((pitch.frequency - 100.0).abs() < 0.5, "{:?}", pitch);
+    }
+
+    #[test]
+    fn test_noise_and_silence_have_no_pitch() {
+        let mut state = 7u64;
+        let noise: Vec<f64> = (0..2048)
+            .map(|_| {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                (state >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+            })
+            .collect();
+        let mut tracker = PitchTracker::new(50.0, 2000.0, 0.15);
+        let pitch = tracker.detect(&noise, 48000.0);
+        assert_eq!(pitch.frequency, 0.0);
+        assert!(pitch.confidence < 0.5);
+        assert_eq!(tracker.detect(&[0.0; 2048], 48000.0), Pitch::NONE);
+    }
+}
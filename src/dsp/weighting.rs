@@ -0,0 +1,93 @@
+use super::biquad::Biquad;
+use anyhow::Result;
+use std::f64::consts::PI;
+
+// IEC 61672-1 pole frequencies (Hz)
+const F1: f64 = 20.598_997;
+const F2: f64 = 107.652_65;
+const F3: f64 = 737.862_23;
+const F4: f64 = 12_194.217;
+
+/// IEC 61672 frequency weighting
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FrequencyWeighting {
+    #[default]
+    A,
+    C,
+    /// Zero (flat) weighting
+    Z,
+}
+
+impl FrequencyWeighting {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_uppercase().as_str() {
+            "A" => Ok(FrequencyWeighting::A),
+            "C" => Ok(FrequencyWeighting::C),
+            "Z" | "FLAT" | "NONE" => Ok(FrequencyWeighting::Z),
+            _ => anyhow::bail!("Unknown frequency weighting: {}", name),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FrequencyWeighting::A => "A",
+            FrequencyWeighting::C => "C",
+            FrequencyWeighting::Z => "Z",
+        }
+    }
+
+    /// Design the weighting filter as a biquad cascade normalised to 0 dB at 1 kHz
+    ///
+    /// Analog poles are mapped with a pre-warped bilinear transform.
+    pub fn design(&self, sample_rate: f64) -> Vec<Biquad> {
+        let pole = |f: f64| {
+            let warped = sample_rate / PI * (PI * f / sample_rate).tan();
+            let p = -2.0 * PI * warped / (2.0 * sample_rate);
+            (1.0 + p) / (1.0 - p)
+        };
+
+        let mut sections = match self {
+            FrequencyWeighting::A => vec![
+                Biquad::from_real_roots([1.0, 1.0], [pole(F1), pole(F1)]),
+                Biquad::from_real_roots([1.0, 1.0], [pole(F2), pole(F3)]),
+                Biquad::from_real_roots([-1.0, -1.0], [pole(F4), pole(F4)]),
+            ],
+            FrequencyWeighting::C => vec![
+                Biquad::from_real_roots([1.0, 1.0], [pole(F1), pole(F1)]),
+                Biquad::from_real_roots([-1.0, -1.0], [pole(F4), pole(F4)]),
+            ],
+            FrequencyWeighting::Z => return Vec::new(),
+        };
+
+        let gain_1k: f64 = sections.iter().map(|s| s.magnitude(1000.0, sample_rate)).product();
+        if let Some(first) = sections.first_mut() {
+            first.scale(1.0 / gain_1k);
+        }
+        sections
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response_db(weighting: FrequencyWeighting, freq: f64) -> f64 {
+        let sections = weighting.design(48000.0);
+        let mag: f64 = sections.iter().map(|s| s.magnitude(freq, 48000.0)).product();
+        20.0 * mag.log10()
+    }
+
+    #[test]
+    fn test_a_weighting_reference_points() {
+        // IEC 61672-1 nominal values
+        assert!(response_db(FrequencyWeighting::A, 1000.0).abs() < 0.01);
+        assert!((response_db(FrequencyWeighting::A, 100.0) - (-19.1)).abs() < 0.3);
+        assert!((response_db(FrequencyWeighting::A, 31.5) - (-39.4)).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_c_weighting_reference_points() {
+        assert!(response_db(FrequencyWeighting::C, 1000.0).abs() < 0.01);
+        assert!((response_db(FrequencyWeighting::C, 31.5) - (-3.0)).abs() < 0.3);
+    }
+}
@@ -0,0 +1,88 @@
+use anyhow::Result;
+use std::f64::consts::PI;
+
+/// Window functions for spectral analysis
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WindowType {
+    Rectangular,
+    #[default]
+    Hann,
+    Hamming,
+    Blackman,
+    /// ISO 18431-2 five-term flat top (accurate amplitude readings)
+    FlatTop,
+}
+
+impl WindowType {
+    /// Parse a window name as used in node configuration
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "rectangular" | "rect" | "none" => Ok(WindowType::Rectangular),
+            "hann" | "hanning" => Ok(WindowType::Hann),
+            "hamming" => Ok(WindowType::Hamming),
+            "blackman" => Ok(WindowType::Blackman),
+            "flattop" | "flat_top" => Ok(WindowType::FlatTop),
+            _ => anyhow::bail!("Unknown window type: {}", name),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WindowType::Rectangular => "rectangular",
+            WindowType::Hann => "hann",
+            WindowType::Hamming => "hamming",
+            WindowType::Blackman => "blackman",
+            WindowType::FlatTop => "flattop",
+        }
+    }
+
+    /// Generate `size` periodic (DFT-even) window coefficients
+    pub fn coefficients(&self, size: usize) -> Vec<f64> {
+        let cosine_terms: &[f64] = match self {
+            WindowType::Rectangular => &[1.0],
+            WindowType::Hann => &[0.5, 0.5],
+            WindowType::Hamming => &[0.54, 0.46],
+            WindowType::Blackman => &[0.42, 0.5, 0.08],
+            WindowType::FlatTop => &[
+                0.215_578_95,
+                0.416_631_58,
+                0.277_263_158,
+                0.083_578_947,
+                0.006_947_368,
+            ],
+        };
+
+        (0..size)
+            .map(|n| {
+                let phase = 2.0 * PI * n as f64 / size as f64;
+                cosine_terms
+                    .iter()
+                    .enumerate()
+                    .map(|(k, &a)| {
+                        let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+                        sign * a * (k as f64 * phase).cos()
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hann_is_zero_at_edges() {
+        let w = WindowType::Hann.coefficients(8);
+        assert!(w[0].abs() < 1e-12);
+        assert!((w[4] - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_parse_names() {
+        assert_eq!(WindowType::parse("Hann").unwrap(), WindowType::Hann);
+        assert_eq!(WindowType::parse("flattop").unwrap(), WindowType::FlatTop);
+        assert!(WindowType::parse("kaiser").is_err());
+    }
+}
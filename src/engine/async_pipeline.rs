@@ -1,30 +1,459 @@
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
-use crate::core::{ProcessingNode, DataFrame};
-use crate::nodes::{AudioSourceNode, GainNode, DebugSinkNode, FFTNode, FilterNode, TriggerSourceNode};
-use crate::observability::{NodeMetrics, MetricsCollector, PipelineMonitor};
-use crate::resilience::{ResilientNode, ErrorPolicy};
+use tracing::Instrument;
+use crate::core::{AudiotabError, ProcessingNode, DataFrame, FrameDecimator, OutputShape, Reblocker, SharedFrame};
+use crate::nodes::{AudioSourceNode, GainNode, DebugSinkNode, FFTNode, FilterNode, TriggerSourceNode, SplMeterNode, DelayNode, CrossCorrelationNode, TriggerGateNode, EnvelopeFollowerNode, CompressorNode, LimiterNode, SignalDetectorNode, DataExportNode, MidiTriggerNode, AveragingNode, DecimatorNode, OrderAnalysisNode, CrossSpectrumNode, BeamformerNode, SignalGeneratorNode, ScriptNode, ChannelRouterNode, FrameMergeNode, SplitterNode, AnnotateNode, MathNode, IntegratorNode, ConvolutionNode, AdaptiveFilterNode, PitchTrackerNode, FeatureExtractorNode, AlertNode, SignalQualityNode, RawBytesNode};
+use crate::observability::{AlertConfig, ChannelRegistry, NodeMetrics, MetricsCollector, MetricsSampler, PipelineEvent, PipelineMonitor, ResourceUsage};
+use crate::resilience::{CircuitEvent, DeadLetterQueue, ResilientNode, ErrorPolicy, RestartEvent, RestartStrategy, Supervisor, DEFAULT_DEAD_LETTER_CAPACITY};
+use crate::resilience::resilient_node::merge_params;
 use crate::engine::state::PipelineState;
 use crate::engine::Priority;
+use crate::engine::realtime::{spawn_realtime, RealtimeConfig};
+use crate::engine::backpressure::{edge_queue, BackpressurePolicy, EdgeSender};
+use crate::engine::sequence::{SequenceCheck, SequenceTracker};
+use crate::registry::{NodeMetadata, PortMultiplicity, PresetStore};
+use crate::hal::{DeviceAccess, DeviceManager, DEFAULT_BUFFER_COUNT, DEFAULT_FRAME_SIZE};
+use crate::engine::latency::{DeviceBuffer, LatencyBudget, LatencyEdge, LatencyGraph, LatencySource};
+
+/// How long `stop()` waits for in-flight frames to drain
+pub const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often a running pipeline publishes `PipelineEvent::Metrics`
+pub const DEFAULT_METRICS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// An edge between two nodes, optionally naming the port instances it joins
+#[derive(Debug, Clone)]
+struct Connection {
+    from: String,
+    to: String,
+    to_port: Option<String>,
+    /// Re-block frames on this edge to a fixed number of samples
+    block_size: Option<usize>,
+    /// Decimate frames on this edge by an integer factor, before re-blocking
+    decimation: Option<usize>,
+    /// What happens to frames while the target is falling behind
+    backpressure: BackpressurePolicy,
+}
+
+/// What travels between nodes: frames, and control markers ordered with them
+///
+/// Fan-out sends the same `SharedFrame` to every consumer, so frames are
+/// reference-counted rather than copied per downstream node.
+#[derive(Clone)]
+enum Message {
+    Frame(SharedFrame),
+    /// Emit held-back data (`on_flush`) and pass the marker on
+    Flush,
+    /// No more frames follow on this connection
+    EndOfStream,
+    /// Parameters for the receiving node; applied there, never forwarded
+    Configure(Arc<ParamUpdate>),
+}
+
+/// Parameters queued for a running node and where to report the outcome
+struct ParamUpdate {
+    params: Value,
+    reply: std::sync::Mutex<Option<tokio::sync::oneshot::Sender<Result<()>>>>,
+}
+
+/// A message delivered to a node, tagged with the input port it arrived on
+struct Delivery {
+    message: Message,
+    port: Option<String>,
+    /// Sending node or device, whose frame sequence is checked for gaps;
+    /// `None` for frames sent from outside the graph
+    from: Option<Arc<str>>,
+}
+
+impl Delivery {
+    fn external(message: Message) -> Self {
+        Self { message, port: None, from: None }
+    }
+}
+
+/// Downstream node fed by a fanout, with the input port it feeds and the edge's reshaping
+struct Output {
+    from: Arc<str>,
+    to: String,
+    sink: EdgeSink,
+    to_port: Option<String>,
+    decimator: Option<FrameDecimator>,
+    reblocker: Option<Reblocker>,
+}
+
+/// Where a fanout delivers an edge's messages
+enum EdgeSink {
+    /// Straight into the target's input, waiting while it is full
+    Direct(mpsc::Sender<Delivery>),
+    /// Into the edge's lossy queue, forwarded to the target by its own task
+    Queued(EdgeSender<Delivery>),
+}
+
+impl Output {
+    /// Deliver a message; frames dropped by a lossy edge are counted on `metrics`
+    async fn send(&self, message: Message, metrics: &NodeMetrics) {
+        let droppable = matches!(message, Message::Frame(_));
+        let delivery = Delivery { message, port: self.to_port.clone(), from: Some(self.from.clone()) };
+        match &self.sink {
+            EdgeSink::Direct(tx) => {
+                let _ = tx.send(delivery).await;
+            }
+            EdgeSink::Queued(queue) => {
+                let dropped = queue.push(delivery, droppable);
+                if dropped > 0 {
+                    metrics.record_frames_dropped(dropped);
+                }
+            }
+        }
+    }
+}
+
+/// Changes to a running node's outputs
+enum FanoutCommand {
+    Connect(Box<Output>),
+    Disconnect { to: String, to_port: Option<String> },
+}
+
+/// Feeds frames captured by a device into a running node's input
+///
+/// Obtained from `AsyncPipeline::device_inputs`; the kernel's device
+/// readers hold one per bound node.
+#[derive(Clone)]
+pub struct DeviceInput {
+    node_id: String,
+    device_id: Arc<str>,
+    tx: mpsc::Sender<Delivery>,
+}
+
+impl DeviceInput {
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    /// Queue a frame without waiting; returns false if the node's input is full
+    ///
+    /// Fails once the node has stopped.
+    pub fn try_send(&self, frame: SharedFrame) -> Result<bool, AudiotabError> {
+        let delivery = Delivery { message: Message::Frame(frame), port: None, from: Some(self.device_id.clone()) };
+        match self.tx.try_send(delivery) {
+            Ok(()) => Ok(true),
+            Err(mpsc::error::TrySendError::Full(_)) => Ok(false),
+            Err(mpsc::error::TrySendError::Closed(_)) => {
+                Err(AudiotabError::state(format!("Node '{}' no longer accepts frames", self.node_id)))
+            }
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+}
+
+/// Port instances of a node, expanded from its registered metadata
+struct NodePorts {
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+}
+
+/// A node built from its entry in the pipeline JSON
+struct NodeSpec {
+    id: String,
+    node: Box<dyn ProcessingNode>,
+    ports: Option<NodePorts>,
+    policy: ErrorPolicy,
+    config: Value,
+    input_block_size: Option<usize>,
+    /// Reshaping the node asks for on edges leaving each output port
+    output_shapes: HashMap<String, OutputShape>,
+    device_id: Option<String>,
+    /// Run on a dedicated thread when the pipeline enables realtime
+    realtime: bool,
+}
+
+/// Alternative type names `create_node` accepts, as (alias, node type id)
+pub(crate) const NODE_TYPE_ALIASES: &[(&str, &str)] = &[
+    ("SineGenerator", "AudioSourceNode"),
+    ("Gain", "GainNode"),
+    ("Print", "DebugSinkNode"),
+    ("SignalGenerator", "SignalGeneratorNode"),
+    ("Script", "ScriptNode"),
+    ("PluginHost", "PluginHostNode"),
+    ("ChannelRouter", "ChannelRouterNode"),
+    ("FrameMerge", "FrameMergeNode"),
+    ("Splitter", "SplitterNode"),
+    ("Annotate", "AnnotateNode"),
+    ("Math", "MathNode"),
+    ("Integrator", "IntegratorNode"),
+    ("Convolution", "ConvolutionNode"),
+    ("AdaptiveFilter", "AdaptiveFilterNode"),
+    ("PitchTracker", "PitchTrackerNode"),
+    ("FeatureExtractor", "FeatureExtractorNode"),
+    ("Alert", "AlertNode"),
+    ("SignalQuality", "SignalQualityNode"),
+    ("RawBytes", "RawBytesNode"),
+    ("Inference", "InferenceNode"),
+];
+
+/// Registered metadata for a node type name or one of its aliases
+pub fn node_metadata(node_type: &str) -> Option<NodeMetadata> {
+    let canonical = NODE_TYPE_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == node_type)
+        .map_or(node_type, |(_, canonical)| canonical);
+    NodeMetadata::find(canonical)
+}
+
+/// Instantiate a node by type name or alias; types registered at runtime,
+/// e.g. by plugins, are built from their metadata
+pub(crate) fn instantiate_node(node_type: &str, meta: Option<&NodeMetadata>) -> Result<Box<dyn ProcessingNode>> {
+    let node: Box<dyn ProcessingNode> = match node_type {
+        "AudioSourceNode" | "SineGenerator" => Box::new(AudioSourceNode::default()),
+        "GainNode" | "Gain" => Box::new(GainNode::default()),
+        "DebugSinkNode" | "Print" => Box::new(DebugSinkNode::default()),
+        "FFTNode" => Box::new(FFTNode::default()),
+        "FilterNode" => Box::new(FilterNode::default()),
+        "TriggerSourceNode" => Box::new(TriggerSourceNode::default()),
+        "SplMeterNode" => Box::new(SplMeterNode::default()),
+        "DelayNode" => Box::new(DelayNode::default()),
+        "CrossCorrelationNode" => Box::new(CrossCorrelationNode::default()),
+        "TriggerGateNode" => Box::new(TriggerGateNode::default()),
+        "EnvelopeFollowerNode" => Box::new(EnvelopeFollowerNode::default()),
+        "CompressorNode" => Box::new(CompressorNode::default()),
+        "LimiterNode" => Box::new(LimiterNode::default()),
+        "SignalDetectorNode" => Box::new(SignalDetectorNode::default()),
+        "DataExportNode" => Box::new(DataExportNode::default()),
+        "MidiTriggerNode" => Box::new(MidiTriggerNode::default()),
+        "AveragingNode" => Box::new(AveragingNode::default()),
+        "DecimatorNode" => Box::new(DecimatorNode::default()),
+        "OrderAnalysisNode" => Box::new(OrderAnalysisNode::default()),
+        "CrossSpectrumNode" => Box::new(CrossSpectrumNode::default()),
+        "BeamformerNode" => Box::new(BeamformerNode::default()),
+        "SignalGeneratorNode" | "SignalGenerator" => Box::new(SignalGeneratorNode::default()),
+        "ScriptNode" | "Script" => Box::new(ScriptNode::default()),
+        "ChannelRouterNode" | "ChannelRouter" => Box::new(ChannelRouterNode::default()),
+        "FrameMergeNode" | "FrameMerge" => Box::new(FrameMergeNode::default()),
+        "SplitterNode" | "Splitter" => Box::new(SplitterNode::default()),
+        "AnnotateNode" | "Annotate" => Box::new(AnnotateNode::default()),
+        "MathNode" | "Math" => Box::new(MathNode::default()),
+        "IntegratorNode" | "Integrator" => Box::new(IntegratorNode::default()),
+        "ConvolutionNode" | "Convolution" => Box::new(ConvolutionNode::default()),
+        "AdaptiveFilterNode" | "AdaptiveFilter" => Box::new(AdaptiveFilterNode::default()),
+        "PitchTrackerNode" | "PitchTracker" => Box::new(PitchTrackerNode::default()),
+        "FeatureExtractorNode" | "FeatureExtractor" => Box::new(FeatureExtractorNode::default()),
+        "AlertNode" | "Alert" => Box::new(AlertNode::default()),
+        "SignalQualityNode" | "SignalQuality" => Box::new(SignalQualityNode::default()),
+        "RawBytesNode" | "RawBytes" => Box::new(RawBytesNode::default()),
+        #[cfg(feature = "parquet")]
+        "CaptureSinkNode" => Box::new(crate::nodes::CaptureSinkNode::default()),
+        #[cfg(feature = "plugin-host")]
+        "PluginHostNode" | "PluginHost" => Box::new(crate::nodes::PluginHostNode::default()),
+        #[cfg(feature = "onnx")]
+        "InferenceNode" | "Inference" => Box::new(crate::nodes::InferenceNode::default()),
+        // Types registered at runtime, e.g. by plugins
+        _ => match meta {
+            Some(meta) => meta.create_instance(),
+            None => return Err(AudiotabError::graph(format!("Unknown node type: {}", node_type)).into()),
+        },
+    };
+    Ok(node)
+}
+
+/// Build and create a node from its pipeline JSON entry
+///
+/// Nodes with a `buffer_size` parameter that is not configured take the
+/// pipeline's `frame_size`, if it has one.
+async fn create_node(
+    node_config: &Value,
+    presets: &PresetStore,
+    default_policy: &ErrorPolicy,
+    frame_size: Option<usize>,
+) -> Result<NodeSpec> {
+    let id = node_config["id"]
+        .as_str()
+        .ok_or_else(|| AudiotabError::graph("Node missing id"))?
+        .to_string();
+    let node_type = node_config["type"].as_str().ok_or_else(|| AudiotabError::graph("Node missing type"))?;
+    let mut node_cfg = presets
+        .resolve_config(node_type, &node_config["config"])
+        .map_err(|e| AudiotabError::from_node_config(&id, e))?;
+    let meta = node_metadata(node_type);
+    let mut ports = None;
+
+    if let (Some(frame_size), Some(meta)) = (frame_size, &meta) {
+        if meta.parameters.iter().any(|p| p.name == "buffer_size") && node_cfg.get("buffer_size").is_none() {
+            if let Some(obj) = node_cfg.as_object_mut() {
+                obj.insert("buffer_size".to_string(), Value::from(frame_size));
+            } else if node_cfg.is_null() {
+                node_cfg = serde_json::json!({ "buffer_size": frame_size });
+            }
+        }
+    }
+
+    // Expand variadic ports to the counts requested in the graph,
+    // and tell the node how many instances it has
+    if let Some(meta) = &meta {
+        let counts = &node_config["port_counts"];
+        let (inputs, outputs) = meta
+            .instantiate_ports(counts)
+            .map_err(|e| AudiotabError::graph(format!("Node '{}': {}", id, e)))?;
+        let node_ports = NodePorts { inputs, outputs };
+        let resolved: serde_json::Map<String, Value> = meta
+            .inputs
+            .iter()
+            .chain(meta.outputs.iter())
+            .filter_map(|p| match p.multiplicity {
+                PortMultiplicity::Variadic { min, .. } => Some((
+                    p.id.clone(),
+                    counts.get(&p.id).cloned().unwrap_or_else(|| Value::from(min)),
+                )),
+                PortMultiplicity::Single => None,
+            })
+            .collect();
+        if !resolved.is_empty() {
+            if let Some(obj) = node_cfg.as_object_mut() {
+                obj.insert("port_counts".to_string(), Value::Object(resolved));
+            } else if node_cfg.is_null() {
+                node_cfg = serde_json::json!({ "port_counts": resolved });
+            }
+        }
+        ports = Some(node_ports);
+    } else if node_config.get("port_counts").is_some() {
+        return Err(AudiotabError::graph(format!("Node '{}': port_counts given for unregistered type {}", id, node_type)).into());
+    }
+
+    let mut node = instantiate_node(node_type, meta.as_ref())?;
+
+    // Saved graphs record the node version they were written with
+    // (unversioned nodes predate versioning and count as version 1)
+    if let Some(meta) = &meta {
+        let saved_version = node_config["version"].as_u64().unwrap_or(1) as u32;
+        if saved_version > meta.version {
+            return Err(AudiotabError::node_config(
+                &id,
+                format!(
+                    "Node '{}' was saved by {} version {}, but only version {} is available",
+                    id, node_type, saved_version, meta.version
+                ),
+            )
+            .into());
+        }
+        if saved_version < meta.version {
+            tracing::warn!(
+                node = %id,
+                "Migrating config from {} version {} to {}",
+                node_type, saved_version, meta.version
+            );
+            node_cfg = node
+                .migrate_config(saved_version, node_cfg)
+                .map_err(|e| AudiotabError::from_node_config(&id, e))?;
+        }
+        if meta.deprecated {
+            tracing::warn!(node = %id, "Node uses deprecated node type {}", node_type);
+        }
+    }
+
+    let policy = match node_config.get("error_policy") {
+        Some(policy) if !policy.is_null() => ErrorPolicy::from_json(policy)
+            .map_err(|e| AudiotabError::node_config(&id, format!("Node '{}': {}", id, e)))?,
+        _ => default_policy.clone(),
+    };
+    let device_id = node_cfg["device_profile_id"]
+        .as_str()
+        .filter(|d| !d.is_empty())
+        .map(|d| d.to_string());
+    let config = node_cfg.clone();
+    node.on_create(node_cfg).await.map_err(|e| AudiotabError::from_node_config(&id, e))?;
+    let output_shapes = ports
+        .iter()
+        .flat_map(|p| p.outputs.iter())
+        .map(|port| (port.clone(), node.output_shape(port)))
+        .filter(|(_, shape)| *shape != OutputShape::default())
+        .collect();
+
+    Ok(NodeSpec {
+        input_block_size: node_config["input_block_size"].as_u64().map(|b| b as usize),
+        output_shapes,
+        realtime: node_config["realtime"].as_bool().unwrap_or(false),
+        id,
+        node,
+        ports,
+        policy,
+        config,
+        device_id,
+    })
+}
 
 pub struct AsyncPipeline {
     nodes: HashMap<String, Box<dyn ProcessingNode>>,
-    connections: Vec<(String, String)>,
-    channels: HashMap<String, mpsc::Sender<DataFrame>>,
-    handles: Vec<JoinHandle<Result<()>>>,
+    connections: Vec<Connection>,
+    /// Task of each running node, by node id
+    handles: Vec<(String, JoinHandle<Result<()>>)>,
     source_node_id: Option<String>,
     channel_capacity: usize,
     metrics_collector: Option<MetricsCollector>,
+    /// Channels watched for `resource_usage`, shared with the collector
+    channels: ChannelRegistry,
     state: PipelineState,
     priority: Priority,
+    /// Policy of nodes whose config does not set one
+    default_policy: ErrorPolicy,
+    /// Backpressure of connections that do not set their own
+    default_backpressure: BackpressurePolicy,
+    /// Error policy of each node, applied when it is wrapped in a `ResilientNode`
+    error_policies: HashMap<String, ErrorPolicy>,
+    /// Node configs, replayed into `on_create` when a node is restarted
+    node_configs: HashMap<String, Value>,
+    node_ports: HashMap<String, NodePorts>,
+    /// Block size each node asks for on its inputs
+    input_block_sizes: HashMap<String, usize>,
+    /// Reshaping each node asks for on edges leaving its output ports
+    output_shapes: HashMap<String, HashMap<String, OutputShape>>,
+    supervisor: Supervisor,
+    dead_letters: DeadLetterQueue,
+    /// Input channel of every running node, for re-injecting dead letters
+    node_inputs: HashMap<String, mpsc::Sender<Delivery>>,
+    /// Rewires the outputs of each running node
+    fanout_controls: HashMap<String, mpsc::UnboundedSender<FanoutCommand>>,
+    /// Inbound connections of each running node, for counting markers
+    inbound_counts: HashMap<String, Arc<AtomicUsize>>,
+    circuit_events: tokio::sync::broadcast::Sender<CircuitEvent>,
+    /// Nodes fed by a hardware device, as (node id, device registration id)
+    device_bindings: Vec<(String, String)>,
+    events: tokio::sync::broadcast::Sender<PipelineEvent>,
+    metrics_interval: Duration,
+    /// Publishes metrics and fatal node errors while running
+    event_task: Option<(JoinHandle<()>, Arc<std::sync::Mutex<MetricsSampler>>)>,
+    /// Dedicated threads for realtime nodes, if enabled
+    realtime: Option<RealtimeConfig>,
+    /// Nodes marked `"realtime": true`
+    realtime_nodes: HashSet<String>,
+    /// Realtime threads started so far, for assigning cores
+    realtime_threads: usize,
+    alerts: AlertConfig,
+    /// Samples per frame of the pipeline's sources and devices, if set
+    frame_size: Option<usize>,
+    /// Packet buffering of the devices feeding bound nodes, by device id
+    device_buffers: HashMap<String, DeviceBuffer>,
 }
 
 impl AsyncPipeline {
-    pub async fn from_json(config: Value) -> Result<Self> {
+    pub async fn from_json(config: Value) -> Result<Self, AudiotabError> {
+        Self::from_json_with_presets(config, &PresetStore::with_builtins()).await
+    }
+
+    /// Build a pipeline, resolving `"preset"` references in node configs from `presets`
+    ///
+    /// Subgraph instances are expanded first (see `expand_subgraphs`).
+    pub async fn from_json_with_presets(config: Value, presets: &PresetStore) -> Result<Self, AudiotabError> {
+        let config = super::subgraph::expand_subgraphs(config).map_err(|e| AudiotabError::graph(format!("{:#}", e)))?;
+        let config_error = |e: anyhow::Error| AudiotabError::config(format!("{:#}", e));
+
         // Parse channel capacity from config
         let channel_capacity = config["pipeline_config"]["channel_capacity"]
             .as_u64()
@@ -42,72 +471,189 @@ impl AsyncPipeline {
             })
             .unwrap_or(Priority::Normal);
 
-        let mut nodes: HashMap<String, Box<dyn ProcessingNode>> = HashMap::new();
-        let mut connections = Vec::new();
+        // Error policy for nodes that do not set their own
+        let default_policy = match config["pipeline_config"].get("error_policy") {
+            Some(policy) if !policy.is_null() => ErrorPolicy::from_json(policy).map_err(config_error)?,
+            _ => ErrorPolicy::Propagate,
+        };
+
+        // Edges block their sender when full unless told otherwise
+        let default_backpressure = match config["pipeline_config"].get("backpressure") {
+            Some(policy) if !policy.is_null() => BackpressurePolicy::from_json(policy).map_err(config_error)?,
+            _ => BackpressurePolicy::Block,
+        };
+
+        // How crashed node tasks are restarted
+        let supervisor = Supervisor::new(RestartStrategy::from_json(&config["pipeline_config"]["restart"]));
+
+        let metrics_interval = config["pipeline_config"]["metrics_interval_ms"]
+            .as_u64()
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_METRICS_INTERVAL);
+        if metrics_interval.is_zero() {
+            return Err(AudiotabError::config("metrics_interval_ms must be positive"));
+        }
+
+        let realtime = RealtimeConfig::from_json(&config["pipeline_config"]["realtime"]).map_err(config_error)?;
+
+        // Samples per frame, for sources and devices that do not set their own
+        let frame_size = match config["pipeline_config"].get("frame_size") {
+            Some(size) if !size.is_null() => match size.as_u64() {
+                Some(size) if size > 0 => Some(size as usize),
+                _ => return Err(AudiotabError::config("frame_size must be a positive number of samples")),
+            },
+            _ => None,
+        };
+
+        // Where the alerts raised by nodes are routed
+        let alerts = AlertConfig::from_json(&config["pipeline_config"]["alerts"]).map_err(config_error)?;
+
+        // Frames that fail processing are kept for inspection
+        let dead_letters = DeadLetterQueue::new(
+            config["pipeline_config"]["dead_letter_capacity"]
+                .as_u64()
+                .map(|c| c as usize)
+                .unwrap_or(DEFAULT_DEAD_LETTER_CAPACITY),
+        );
+
+        let collector = MetricsCollector::new();
+        let mut pipeline = Self {
+            nodes: HashMap::new(),
+            connections: Vec::new(),
+            handles: Vec::new(),
+            source_node_id: None,
+            channel_capacity,
+            channels: collector.channels(),
+            metrics_collector: Some(collector),
+            state: PipelineState::Idle,
+            priority,
+            default_policy,
+            default_backpressure,
+            error_policies: HashMap::new(),
+            node_configs: HashMap::new(),
+            node_ports: HashMap::new(),
+            input_block_sizes: HashMap::new(),
+            output_shapes: HashMap::new(),
+            supervisor,
+            dead_letters,
+            node_inputs: HashMap::new(),
+            fanout_controls: HashMap::new(),
+            inbound_counts: HashMap::new(),
+            circuit_events: tokio::sync::broadcast::channel(64).0,
+            device_bindings: Vec::new(),
+            events: tokio::sync::broadcast::channel(256).0,
+            metrics_interval,
+            event_task: None,
+            realtime,
+            realtime_nodes: HashSet::new(),
+            realtime_threads: 0,
+            alerts,
+            frame_size,
+            device_buffers: HashMap::new(),
+        };
 
         // Parse nodes
         if let Some(nodes_array) = config["nodes"].as_array() {
             for node_config in nodes_array {
-                let id = node_config["id"]
-                    .as_str()
-                    .ok_or(anyhow!("Node missing id"))?
-                    .to_string();
-                let node_type = node_config["type"].as_str().ok_or(anyhow!("Node missing type"))?;
-                let node_cfg = node_config["config"].clone();
-
-                let mut node: Box<dyn ProcessingNode> = match node_type {
-                    "AudioSourceNode" | "SineGenerator" => Box::new(AudioSourceNode::default()),
-                    "GainNode" | "Gain" => Box::new(GainNode::default()),
-                    "DebugSinkNode" | "Print" => Box::new(DebugSinkNode::default()),
-                    "FFTNode" => Box::new(FFTNode::default()),
-                    "FilterNode" => Box::new(FilterNode::default()),
-                    "TriggerSourceNode" => Box::new(TriggerSourceNode::default()),
-                    _ => return Err(anyhow!("Unknown node type: {}", node_type)),
-                };
-
-                node.on_create(node_cfg).await?;
-                nodes.insert(id, node);
+                let spec = create_node(node_config, presets, &pipeline.default_policy, pipeline.frame_size).await?;
+                pipeline.insert_node(spec);
             }
         }
 
         // Parse connections
         if let Some(conns_array) = config["connections"].as_array() {
             for conn in conns_array {
-                let from = conn["from"]
-                    .as_str()
-                    .ok_or(anyhow!("Connection missing from"))?
-                    .to_string();
-                let to = conn["to"]
-                    .as_str()
-                    .ok_or(anyhow!("Connection missing to"))?
-                    .to_string();
-                connections.push((from, to));
+                let connection = pipeline.parse_connection(conn)?;
+                pipeline.connections.push(connection);
             }
         }
 
-        // Find source node (no incoming connections)
-        let source_node_id = nodes.keys().find(|id| {
-            !connections.iter().any(|(_, to)| to == *id)
-        }).map(|s| s.clone());
+        pipeline.update_source();
+        Ok(pipeline)
+    }
 
-        Ok(Self {
-            nodes,
-            connections,
-            channels: HashMap::new(),
-            handles: Vec::new(),
-            source_node_id,
-            channel_capacity,
-            metrics_collector: Some(MetricsCollector::new()),
-            state: PipelineState::Idle,
-            priority,
-        })
+    /// Parse a connection entry, checking the ports it names
+    fn parse_connection(&self, conn: &Value) -> Result<Connection, AudiotabError> {
+        let from = conn["from"]
+            .as_str()
+            .ok_or_else(|| AudiotabError::graph("Connection missing from"))?
+            .to_string();
+        let to = conn["to"]
+            .as_str()
+            .ok_or_else(|| AudiotabError::graph("Connection missing to"))?
+            .to_string();
+
+        let from_port = conn["from_port"].as_str();
+        if let Some(from_port) = from_port {
+            if let Some(ports) = self.node_ports.get(&from) {
+                if !ports.outputs.iter().any(|p| p == from_port) {
+                    return Err(AudiotabError::graph(format!("Node '{}' has no output port '{}'", from, from_port)));
+                }
+            }
+        }
+        let shape = from_port
+            .and_then(|port| self.output_shapes.get(&from)?.get(port).copied())
+            .unwrap_or_default();
+        let to_port = conn["to_port"].as_str().map(|s| s.to_string());
+        if let Some(to_port) = &to_port {
+            if let Some(ports) = self.node_ports.get(&to) {
+                if !ports.inputs.iter().any(|p| p == to_port) {
+                    return Err(AudiotabError::graph(format!("Node '{}' has no input port '{}'", to, to_port)));
+                }
+            }
+        }
+
+        // Edges take their own block size, else the one the source asks for on
+        // the output port, else the one the target asks for on its inputs
+        let block_size = conn["block_size"]
+            .as_u64()
+            .map(|b| b as usize)
+            .or(shape.block_size)
+            .or_else(|| self.input_block_sizes.get(&to).copied());
+        if block_size == Some(0) {
+            return Err(AudiotabError::config(format!("Connection {} -> {}: block_size must be positive", from, to)));
+        }
+        let decimation = conn["decimation"].as_u64().map(|d| d as usize).or(shape.decimation);
+        if decimation == Some(0) {
+            return Err(AudiotabError::config(format!("Connection {} -> {}: decimation must be positive", from, to)));
+        }
+
+        let backpressure = match conn.get("backpressure") {
+            Some(policy) if !policy.is_null() => BackpressurePolicy::from_json(policy)
+                .map_err(|e| AudiotabError::config(format!("Connection {} -> {}: {}", from, to, e)))?,
+            _ => self.default_backpressure,
+        };
+
+        Ok(Connection { from, to, to_port, block_size, decimation, backpressure })
+    }
+
+    /// Add a built node to the pipeline's definition
+    fn insert_node(&mut self, spec: NodeSpec) {
+        self.error_policies.insert(spec.id.clone(), spec.policy);
+        self.node_configs.insert(spec.id.clone(), spec.config);
+        if let Some(device_id) = spec.device_id {
+            self.device_bindings.push((spec.id.clone(), device_id));
+        }
+        if let Some(block_size) = spec.input_block_size {
+            self.input_block_sizes.insert(spec.id.clone(), block_size);
+        }
+        if !spec.output_shapes.is_empty() {
+            self.output_shapes.insert(spec.id.clone(), spec.output_shapes);
+        }
+        if spec.realtime {
+            self.realtime_nodes.insert(spec.id.clone());
+        }
+        if let Some(ports) = spec.ports {
+            self.node_ports.insert(spec.id.clone(), ports);
+        }
+        self.nodes.insert(spec.id, spec.node);
     }
 
     /// Inject RingBuffer into visualization-capable nodes
     ///
     /// This method sets up the RingBuffer for nodes that support visualization.
     /// Must be called after `from_json()` but before `start()`.
-    pub fn set_ring_buffer(&mut self, ring_buffer: Arc<std::sync::Mutex<crate::visualization::RingBufferWriter>>) {
+    pub fn set_ring_buffer(&mut self, ring_buffer: Arc<crate::visualization::RingBufferWriter>) {
         for (_id, node) in self.nodes.iter_mut() {
             // Try to downcast to AudioSourceNode
             if let Some(audio_source) = node.as_any_mut().downcast_mut::<AudioSourceNode>() {
@@ -134,127 +680,586 @@ impl AsyncPipeline {
         self.priority
     }
 
+    /// Dedicated-thread settings from `pipeline_config.realtime`, if enabled
+    pub fn realtime(&self) -> Option<&RealtimeConfig> {
+        self.realtime.as_ref()
+    }
+
+    /// Routing of the alerts nodes raise, see `AlertRouter`
+    pub fn alert_config(&self) -> &AlertConfig {
+        &self.alerts
+    }
+
     /// Set pipeline state directly (without validation)
     pub fn set_state(&mut self, new_state: PipelineState) {
         self.state = new_state;
     }
 
     /// Transition to a new state with validation
-    pub fn transition_to(&mut self, new_state: PipelineState) -> Result<()> {
+    pub fn transition_to(&mut self, new_state: PipelineState) -> Result<(), AudiotabError> {
         if !self.state.can_transition_to(&new_state) {
-            return Err(anyhow!(
+            return Err(AudiotabError::state(format!(
                 "Invalid state transition: {} -> {}",
                 self.state.name(),
                 new_state.name()
-            ));
+            )));
         }
+        let from = self.state.name().to_string();
         self.state = new_state;
+        tracing::debug!(from = %from, to = self.state.name(), "Pipeline state changed");
+        let _ = self.events.send(PipelineEvent::StateChanged {
+            from,
+            to: self.state.name().to_string(),
+        });
         Ok(())
     }
 
-    pub async fn start(&mut self) -> Result<()> {
+    pub async fn start(&mut self) -> Result<(), AudiotabError> {
         // Transition to Initializing state
         self.transition_to(PipelineState::Initializing { progress: 0 })?;
 
-        let channel_capacity = self.channel_capacity;
-        let mut node_channels: HashMap<String, (mpsc::Sender<DataFrame>, mpsc::Receiver<DataFrame>)> = HashMap::new();
-
         // Create channels for each node
+        let mut receivers = HashMap::new();
         for node_id in self.nodes.keys() {
-            let (tx, rx) = mpsc::channel(channel_capacity);
-            node_channels.insert(node_id.clone(), (tx, rx));
+            let (tx, rx) = mpsc::channel(self.channel_capacity);
+            self.node_inputs.insert(node_id.clone(), tx);
+            receivers.insert(node_id.clone(), rx);
         }
 
-        // Save source node channel before spawning
-        if let Some(source_id) = &self.source_node_id {
-            if let Some((tx, _)) = node_channels.get(source_id) {
-                self.channels.insert(source_id.clone(), tx.clone());
-            }
+        // Wrap nodes with ResilientNode and metrics
+        let mut collector = self.metrics_collector.take().unwrap();
+
+        // Pipeline frames are counted where they enter the graph
+        let sources: Vec<String> = self
+            .nodes
+            .keys()
+            .filter(|id| !self.connections.iter().any(|c| &c.to == *id))
+            .cloned()
+            .collect();
+
+        // Spawn task for each node
+        tracing::info!(nodes = self.nodes.len(), connections = self.connections.len(), "Starting pipeline");
+        let nodes: Vec<_> = self.nodes.drain().collect();
+        for (node_id, node) in nodes {
+            let rx = receivers.remove(&node_id).unwrap();
+            self.spawn_node(node_id, node, rx, &mut collector);
         }
 
-        // Build output channel map (which nodes send to which channels)
-        let mut output_channels: HashMap<String, Vec<mpsc::Sender<DataFrame>>> = HashMap::new();
-        for (from, to) in &self.connections {
-            output_channels
-                .entry(from.clone())
-                .or_insert_with(Vec::new)
-                .push(node_channels.get(to).unwrap().0.clone());
+        // Transition to Running state after all nodes spawned
+        self.transition_to(PipelineState::Running {
+            start_time: Some(std::time::Instant::now()),
+            frames_processed: 0,
+        })?;
+
+        self.spawn_event_task(MetricsSampler::new(collector.clone(), sources));
+        self.metrics_collector = Some(collector);
+        Ok(())
+    }
+
+    /// Run a node in its own task, sending to the inputs of its downstream nodes
+    fn spawn_node(
+        &mut self,
+        node_id: String,
+        node: Box<dyn ProcessingNode>,
+        rx: mpsc::Receiver<Delivery>,
+        collector: &mut MetricsCollector,
+    ) -> Arc<NodeMetrics> {
+        let channel_capacity = self.channel_capacity;
+        let outputs: Vec<Output> = self
+            .connections
+            .iter()
+            .filter(|c| c.from == node_id)
+            .filter_map(|c| self.output(c))
+            .collect();
+        let (control_tx, mut control_rx) = mpsc::unbounded_channel();
+        self.fanout_controls.insert(node_id.clone(), control_tx);
+
+        // Markers are acted on once every inbound connection has delivered one
+        let inbound = Arc::new(AtomicUsize::new(self.connections.iter().filter(|c| c.to == node_id).count()));
+        self.inbound_counts.insert(node_id.clone(), inbound.clone());
+
+        // Create metrics for this node
+        let metrics = Arc::new(NodeMetrics::new(&node_id));
+        collector.register(&node_id, metrics.clone());
+        if let Some(tx) = self.node_inputs.get(&node_id) {
+            let input = tx.downgrade();
+            let probe = move || {
+                let tx = input.upgrade().filter(|tx| !tx.is_closed())?;
+                Some((tx.max_capacity() - tx.capacity(), tx.max_capacity()))
+            };
+            collector.register_channel(&node_id, &node_id, Arc::new(probe));
         }
 
-        // Wrap nodes with ResilientNode and metrics
-        let mut collector = self.metrics_collector.take().unwrap();
+        // Wrap with ResilientNode under the node's error policy
+        let policy = self.error_policies.remove(&node_id).unwrap_or(ErrorPolicy::Propagate);
+        let resilient = ResilientNode::new(node, metrics.clone(), policy)
+            .with_config(self.node_configs.remove(&node_id).unwrap_or(Value::Null))
+            .with_dead_letters(self.dead_letters.clone())
+            .with_circuit_events(self.circuit_events.clone())
+            .with_events(self.events.clone());
 
-        // Spawn task for each node
-        for (node_id, node) in self.nodes.drain() {
-            let (_tx, rx) = node_channels.remove(&node_id).unwrap();
-            let outputs = output_channels.remove(&node_id).unwrap_or_default();
-
-            // Create metrics for this node
-            let metrics = Arc::new(NodeMetrics::new(&node_id));
-            collector.register(&node_id, metrics.clone());
-
-            // Wrap with ResilientNode
-            let mut resilient = ResilientNode::new(node, metrics, ErrorPolicy::Propagate);
-
-            let handle = tokio::spawn(async move {
-                let (fanout_tx, mut fanout_rx) = mpsc::channel(channel_capacity);
-
-                // Spawn node processing
-                let node_task = tokio::spawn(async move {
-                    let mut rx = rx;
-                    while let Some(frame) = rx.recv().await {
-                        match resilient.process(frame).await {
-                            Ok(output) => {
-                                if fanout_tx.send(output).await.is_err() {
+        // Node and input stay behind (non-poisoning) locks so a restarted
+        // run picks up the same node and the frames still queued for it
+        let resilient = Arc::new(tokio::sync::Mutex::new(resilient));
+        let rx = Arc::new(tokio::sync::Mutex::new(rx));
+        let supervisor = self.supervisor.clone();
+        let task_node_id = node_id.clone();
+        // Flush errors are reported rather than restarted: the input is already closed
+        let flush_error: Arc<std::sync::Mutex<Option<anyhow::Error>>> = Arc::default();
+        let task_flush_error = flush_error.clone();
+        let task_metrics = metrics.clone();
+        let fanout_metrics = metrics.clone();
+        let run_metrics = metrics.clone();
+
+        let task = async move {
+            let (fanout_tx, mut fanout_rx) = mpsc::channel(channel_capacity);
+
+            // Spawn node processing under the supervisor
+            let mut runs = 0;
+            let node_task = supervisor.supervise(task_node_id, task_metrics, move || {
+                runs += 1;
+                let restarted = runs > 1;
+                let resilient = resilient.clone();
+                let rx = rx.clone();
+                let inbound = inbound.clone();
+                let fanout_tx = fanout_tx.clone();
+                let flush_error = task_flush_error.clone();
+                let sequence_metrics = run_metrics.clone();
+                async move {
+                    let mut resilient = resilient.lock().await;
+                    let mut rx = rx.lock().await;
+                    if restarted {
+                        resilient.reinitialize().await?;
+                    }
+                    let (mut flushes, mut ends) = (0, 0);
+                    // A closed input counts as end of stream too
+                    let mut ended = true;
+                    let mut sequences = SequenceTracker::new();
+                    while let Some(Delivery { message, port, from }) = rx.recv().await {
+                        match message {
+                            Message::Frame(shared) => {
+                                sequence_metrics.record_frame_bytes(shared.sample_bytes() as u64);
+                                let mut frame = DataFrame::from_shared(shared);
+                                // Note frames lost or reordered upstream on this edge
+                                if let Some(from) = from {
+                                    match sequences.check((from, port.clone()), frame.sequence_id) {
+                                        SequenceCheck::InOrder => {}
+                                        SequenceCheck::Gap { missing } => {
+                                            sequence_metrics.record_frames_missing(missing);
+                                            frame.metadata.insert("sequence_gap", missing);
+                                        }
+                                        SequenceCheck::OutOfOrder => {
+                                            sequence_metrics.record_out_of_order();
+                                            frame.metadata.insert("out_of_order", true);
+                                        }
+                                    }
+                                }
+                                // The port tag describes this edge only; it is not passed on
+                                match port {
+                                    Some(port) => frame.metadata.insert("input_port", port),
+                                    None => frame.metadata.remove("input_port"),
+                                };
+                                let span = tracing::debug_span!("process", sequence_id = frame.sequence_id);
+                                match resilient.process(frame).instrument(span).await {
+                                    Ok(mut output) => {
+                                        output.metadata.remove("input_port");
+                                        if fanout_tx.send(Message::Frame(Arc::new(output))).await.is_err() {
+                                            break;
+                                        }
+                                    }
+                                    Err(_) => {
+                                        // Error handled by ResilientNode
+                                        ended = false;
+                                        break;
+                                    }
+                                }
+                            }
+                            Message::Flush => {
+                                flushes += 1;
+                                if flushes == inbound.load(Ordering::Relaxed).max(1) {
+                                    flushes = 0;
+                                    match resilient.on_flush().await {
+                                        Ok(Some(mut held)) => {
+                                            held.metadata.remove("input_port");
+                                            let _ = fanout_tx.send(Message::Frame(Arc::new(held))).await;
+                                        }
+                                        Ok(None) => {}
+                                        Err(e) => tracing::warn!("Node flush failed: {}", e),
+                                    }
+                                    let _ = fanout_tx.send(Message::Flush).await;
+                                }
+                            }
+                            Message::Configure(update) => {
+                                let result = resilient.reconfigure(&update.params).await;
+                                if let Some(reply) = update.reply.lock().unwrap().take() {
+                                    let _ = reply.send(result);
+                                }
+                            }
+                            Message::EndOfStream => {
+                                ends += 1;
+                                if ends == inbound.load(Ordering::Relaxed).max(1) {
                                     break;
                                 }
                             }
-                            Err(_) => {
-                                // Error handled by ResilientNode
-                                break;
+                        }
+                    }
+                    // Emit the tail, tell consumers the stream ended, then let sinks close their outputs
+                    if ended {
+                        match resilient.on_eos().await {
+                            Ok(Some(mut tail)) => {
+                                tail.metadata.remove("input_port");
+                                let _ = fanout_tx.send(Message::Frame(Arc::new(tail))).await;
                             }
+                            Ok(None) => {}
+                            Err(e) => tracing::warn!("Node end of stream failed: {}", e),
                         }
+                        let _ = fanout_tx.send(Message::EndOfStream).await;
+                    }
+                    if let Err(e) = resilient.on_destroy().await {
+                        *flush_error.lock().unwrap() = Some(e);
                     }
-                    Ok::<(), anyhow::Error>(())
-                });
-
-                // Spawn fanout (send to multiple outputs)
-                let fanout_task = tokio::spawn(async move {
-                    while let Some(frame) = fanout_rx.recv().await {
-                        for output in &outputs {
-                            let _ = output.send(frame.clone()).await;
+                    Ok(())
+                }
+            });
+
+            // Spawn fanout (send to multiple outputs); rewiring takes effect between messages
+            let fanout_task = tokio::spawn(async move {
+                let mut outputs = outputs;
+                loop {
+                    let message = tokio::select! {
+                        biased;
+                        Some(command) = control_rx.recv() => {
+                            match command {
+                                FanoutCommand::Connect(output) => outputs.push(*output),
+                                FanoutCommand::Disconnect { to, to_port } => {
+                                    outputs.retain(|o| o.to != to || o.to_port != to_port)
+                                }
+                            }
+                            continue;
+                        }
+                        message = fanout_rx.recv() => match message {
+                            Some(message) => message,
+                            None => break,
+                        },
+                    };
+                    for output in outputs.iter_mut() {
+                        let frame = match (&message, &mut output.decimator) {
+                            (Message::Frame(frame), Some(decimator)) => {
+                                Some(Arc::new(decimator.push(DataFrame::from_shared(frame.clone()))))
+                            }
+                            (Message::Frame(frame), None) => Some(frame.clone()),
+                            _ => None,
+                        };
+                        let Some(reblocker) = &mut output.reblocker else {
+                            let message = frame.map_or_else(|| message.clone(), Message::Frame);
+                            output.send(message, &fanout_metrics).await;
+                            continue;
+                        };
+                        let blocks = match (&message, frame) {
+                            (_, Some(frame)) => reblocker.push(&frame),
+                            // The stream ends with whatever is left as a shorter block
+                            (Message::EndOfStream, _) => reblocker.finish().into_iter().collect(),
+                            _ => Vec::new(),
+                        };
+                        for block in blocks {
+                            output.send(Message::Frame(Arc::new(block)), &fanout_metrics).await;
+                        }
+                        if !matches!(message, Message::Frame(_)) {
+                            output.send(message.clone(), &fanout_metrics).await;
                         }
                     }
-                });
+                }
+            }.in_current_span());
+
+            node_task.await??;
+            fanout_task.await?;
+            match flush_error.lock().unwrap().take() {
+                Some(e) => Err(e),
+                None => Ok(()),
+            }
+        };
 
-                node_task.await??;
-                fanout_task.await?;
-                Ok(())
+        // Everything the node's tasks log is attributed to it
+        let task = task.instrument(tracing::info_span!("node", node = %node_id));
+
+        // Realtime nodes, their supervisor and fanout share a dedicated thread
+        let handle = match &self.realtime {
+            Some(realtime) if self.realtime_nodes.contains(&node_id) => {
+                let core = realtime.core(self.realtime_threads);
+                self.realtime_threads += 1;
+                spawn_realtime(format!("audiotab-node-{}", node_id), self.priority, core, task)
+            }
+            _ => tokio::spawn(task),
+        };
+        self.handles.push((node_id, handle));
+        metrics
+    }
+
+    /// Fanout output for a connection, if its target is running
+    ///
+    /// Lossy edges get a queue of `channel_capacity` messages and a task
+    /// forwarding it to the target; the task ends once the output is dropped
+    /// and the queue is empty.
+    fn output(&self, conn: &Connection) -> Option<Output> {
+        let tx = self.node_inputs.get(&conn.to)?.clone();
+        let sink = if conn.backpressure.is_lossy() {
+            let (queue, rx) = edge_queue(conn.backpressure, self.channel_capacity);
+            let channel = match &conn.to_port {
+                Some(port) => format!("{} -> {}.{}", conn.from, conn.to, port),
+                None => format!("{} -> {}", conn.from, conn.to),
+            };
+            self.channels.register(channel, &conn.to, Arc::new(queue.probe()));
+            tokio::spawn(async move {
+                while let Some(delivery) = rx.recv().await {
+                    if tx.send(delivery).await.is_err() {
+                        break;
+                    }
+                }
             });
+            EdgeSink::Queued(queue)
+        } else {
+            EdgeSink::Direct(tx)
+        };
+        Some(Output {
+            from: conn.from.as_str().into(),
+            to: conn.to.clone(),
+            sink,
+            to_port: conn.to_port.clone(),
+            decimator: conn.decimation.filter(|&d| d > 1).map(|d| FrameDecimator::new(d, true)),
+            reblocker: conn.block_size.map(Reblocker::new),
+        })
+    }
 
-            self.handles.push(handle);
+    /// Entry node for `trigger`: a node without inbound connections,
+    /// keeping the current one while it still qualifies
+    fn update_source(&mut self) {
+        let is_source = |id: &String| {
+            (self.nodes.contains_key(id) || self.node_inputs.contains_key(id))
+                && !self.connections.iter().any(|c| &c.to == id)
+        };
+        if self.source_node_id.as_ref().is_some_and(is_source) {
+            return;
         }
+        self.source_node_id = self.nodes.keys().chain(self.node_inputs.keys()).find(|id| is_source(id)).cloned();
+    }
 
-        // Transition to Running state after all nodes spawned
-        self.transition_to(PipelineState::Running {
-            start_time: Some(std::time::Instant::now()),
-            frames_processed: 0,
-        })?;
+    /// Whether edits must be spliced into running tasks
+    fn editable(&self) -> Result<bool, AudiotabError> {
+        match self.state {
+            PipelineState::Idle => Ok(false),
+            PipelineState::Running { .. } => Ok(true),
+            _ => Err(AudiotabError::state(format!(
+                "Pipeline can only be edited while idle or running, not {}",
+                self.state.name()
+            ))),
+        }
+    }
 
-        self.metrics_collector = Some(collector);
+    /// Add a node, given in the pipeline JSON format
+    ///
+    /// On a running pipeline the node starts at once, without connections.
+    pub async fn add_node(&mut self, node_config: Value) -> Result<(), AudiotabError> {
+        let running = self.editable()?;
+        let spec = create_node(&node_config, &PresetStore::with_builtins(), &self.default_policy, self.frame_size).await?;
+        if self.nodes.contains_key(&spec.id) || self.node_inputs.contains_key(&spec.id) {
+            return Err(AudiotabError::graph(format!("Node '{}' already exists", spec.id)));
+        }
+        let node_id = spec.id.clone();
+        self.insert_node(spec);
+
+        if running {
+            let node = self.nodes.remove(&node_id).unwrap();
+            let (tx, rx) = mpsc::channel(self.channel_capacity);
+            self.node_inputs.insert(node_id.clone(), tx);
+            let mut collector = self.metrics_collector.take().unwrap_or_default();
+            let metrics = self.spawn_node(node_id.clone(), node, rx, &mut collector);
+            self.metrics_collector = Some(collector);
+            if let Some((_, sampler)) = &self.event_task {
+                sampler.lock().unwrap().register(&node_id, metrics);
+            }
+        }
+        self.update_source();
         Ok(())
     }
 
-    pub async fn trigger(&self, frame: DataFrame) -> Result<()> {
+    /// Remove a node and its connections
+    ///
+    /// A running node processes the frames already queued for it and is
+    /// joined; its downstream nodes do not see an end of stream.
+    pub async fn remove_node(&mut self, node_id: &str) -> Result<(), AudiotabError> {
+        let running = self.editable()?;
+        if !self.nodes.contains_key(node_id) && !self.node_inputs.contains_key(node_id) {
+            return Err(AudiotabError::graph(format!("Node '{}' not found", node_id)));
+        }
+        // One disconnect per neighbour removes every port connecting the pair
+        let edges: HashSet<(String, String)> = self
+            .connections
+            .iter()
+            .filter(|c| c.from == node_id || c.to == node_id)
+            .map(|c| (c.from.clone(), c.to.clone()))
+            .collect();
+        for (from, to) in edges {
+            self.disconnect(&from, &to)?;
+        }
+
+        self.nodes.remove(node_id);
+        self.error_policies.remove(node_id);
+        self.node_configs.remove(node_id);
+        self.node_ports.remove(node_id);
+        self.input_block_sizes.remove(node_id);
+        self.output_shapes.remove(node_id);
+        self.device_bindings.retain(|(id, _)| id != node_id);
+        self.realtime_nodes.remove(node_id);
+        self.fanout_controls.remove(node_id);
+        self.inbound_counts.remove(node_id);
+
+        if running {
+            if let Some(tx) = self.node_inputs.remove(node_id) {
+                let _ = tx.send(Delivery::external(Message::EndOfStream)).await;
+            }
+            if let Some(index) = self.handles.iter().position(|(id, _)| id == node_id) {
+                let (_, mut handle) = self.handles.remove(index);
+                match tokio::time::timeout(DEFAULT_DRAIN_TIMEOUT, &mut handle).await {
+                    Ok(Ok(result)) => result.map_err(|e| AudiotabError::node(node_id, format!("{:#}", e)))?,
+                    Ok(Err(e)) => return Err(AudiotabError::node(node_id, format!("Node '{}' task failed: {}", node_id, e))),
+                    Err(_) => {
+                        handle.abort();
+                        return Err(AudiotabError::node(
+                            node_id,
+                            format!("Node '{}' did not drain within {:?}", node_id, DEFAULT_DRAIN_TIMEOUT),
+                        ));
+                    }
+                }
+            }
+        }
+        self.update_source();
+        Ok(())
+    }
+
+    /// Add a connection, given in the pipeline JSON format
+    ///
+    /// On a running pipeline the source node sends to the target from its
+    /// next frame on.
+    pub fn connect(&mut self, connection: Value) -> Result<(), AudiotabError> {
+        let running = self.editable()?;
+        let conn = self.parse_connection(&connection)?;
+        for id in [&conn.from, &conn.to] {
+            if !self.nodes.contains_key(id) && !self.node_inputs.contains_key(id) {
+                return Err(AudiotabError::graph(format!("Connection {} -> {}: node '{}' not found", conn.from, conn.to, id)));
+            }
+        }
+        if self.connections.iter().any(|c| c.from == conn.from && c.to == conn.to && c.to_port == conn.to_port) {
+            return Err(AudiotabError::graph(format!("Nodes '{}' and '{}' are already connected", conn.from, conn.to)));
+        }
+
+        if running {
+            let output = self
+                .output(&conn)
+                .ok_or_else(|| AudiotabError::state(format!("Node '{}' is not running", conn.to)))?;
+            let control = self
+                .fanout_controls
+                .get(&conn.from)
+                .ok_or_else(|| AudiotabError::state(format!("Node '{}' is not running", conn.from)))?;
+            if let Some(inbound) = self.inbound_counts.get(&conn.to) {
+                inbound.fetch_add(1, Ordering::Relaxed);
+            }
+            control
+                .send(FanoutCommand::Connect(Box::new(output)))
+                .map_err(|_| AudiotabError::state(format!("Node '{}' has stopped", conn.from)))?;
+        }
+        self.connections.push(conn);
+        self.update_source();
+        Ok(())
+    }
+
+    /// Remove every connection from `from` to `to`
+    pub fn disconnect(&mut self, from: &str, to: &str) -> Result<(), AudiotabError> {
+        let running = self.editable()?;
+        let (removed, kept): (Vec<Connection>, Vec<Connection>) = std::mem::take(&mut self.connections)
+            .into_iter()
+            .partition(|c| c.from == from && c.to == to);
+        self.connections = kept;
+        if removed.is_empty() {
+            return Err(AudiotabError::graph(format!("Nodes '{}' and '{}' are not connected", from, to)));
+        }
+
+        if running {
+            for conn in removed {
+                if let Some(control) = self.fanout_controls.get(from) {
+                    let _ = control.send(FanoutCommand::Disconnect { to: conn.to, to_port: conn.to_port });
+                }
+                if let Some(inbound) = self.inbound_counts.get(to) {
+                    let _ = inbound.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1));
+                }
+            }
+        }
+        self.update_source();
+        Ok(())
+    }
+
+    /// Merge `params` into a node's config and re-run its `on_create`
+    ///
+    /// On a running pipeline the update is queued behind the frames already
+    /// waiting for the node and applied between frames; this returns once
+    /// the node has applied it. A rejected update leaves the node on its
+    /// previous config.
+    pub async fn update_node_params(&mut self, node_id: &str, params: Value) -> Result<(), AudiotabError> {
+        if !params.is_object() {
+            return Err(AudiotabError::node_config(node_id, "Node parameters must be a JSON object"));
+        }
+
+        if let Some(node) = self.nodes.get_mut(node_id) {
+            let previous = self.node_configs.get(node_id).cloned().unwrap_or(Value::Null);
+            let mut config = previous.clone();
+            merge_params(&mut config, &params);
+            if let Err(e) = node.on_create(config.clone()).await {
+                let _ = node.on_create(previous).await;
+                return Err(AudiotabError::from_node_config(node_id, e));
+            }
+            self.node_configs.insert(node_id.to_string(), config);
+            return Ok(());
+        }
+
+        let input = self
+            .node_inputs
+            .get(node_id)
+            .ok_or_else(|| AudiotabError::graph(format!("Node {} not found", node_id)))?;
+        let (reply, applied) = tokio::sync::oneshot::channel();
+        let update = ParamUpdate { params, reply: std::sync::Mutex::new(Some(reply)) };
+        input
+            .send(Delivery::external(Message::Configure(Arc::new(update))))
+            .await
+            .map_err(|_| AudiotabError::state(format!("Node {} has stopped", node_id)))?;
+        applied
+            .await
+            .map_err(|_| AudiotabError::state(format!("Node {} stopped before applying the update", node_id)))?
+            .map_err(|e| AudiotabError::from_node_config(node_id, e))
+    }
+
+    pub async fn trigger(&self, frame: DataFrame) -> Result<(), AudiotabError> {
         if let Some(source_id) = &self.source_node_id {
-            if let Some(tx) = self.channels.get(source_id) {
-                tx.send(frame).await.map_err(|_| anyhow!("Failed to send trigger frame"))?;
+            if let Some(tx) = self.node_inputs.get(source_id) {
+                tx.send(Delivery::external(Message::Frame(Arc::new(frame)))).await.map_err(|_| AudiotabError::state("Failed to send trigger frame"))?;
             }
         }
         Ok(())
     }
 
-    pub async fn stop(&mut self) -> Result<()> {
+    /// Stop gracefully, draining frames in flight (see `drain`)
+    pub async fn stop(&mut self) -> Result<(), AudiotabError> {
+        self.drain(DEFAULT_DRAIN_TIMEOUT).await
+    }
+
+    /// Stop the pipeline without losing frames already in flight
+    ///
+    /// Sources stop receiving new frames and an end-of-stream marker follows
+    /// their queued frames down the graph: each node processes what is queued
+    /// for it, emits its tail from `on_eos`, passes the marker on, and closes
+    /// in `on_destroy` (finishing files such as WAV recordings). A closed
+    /// input counts as end of stream as well. Every node task is joined,
+    /// even after one fails; tasks still running at `timeout` are aborted.
+    /// Returns the first node error.
+    pub async fn drain(&mut self, timeout: Duration) -> Result<(), AudiotabError> {
         // Transition to Completed state before stopping
         if let PipelineState::Running { start_time, frames_processed } = &self.state {
             let duration = start_time.map(|t| t.elapsed());
@@ -264,21 +1269,278 @@ impl AsyncPipeline {
             })?;
         }
 
-        // Take ownership of channels and drop to signal nodes to shut down
-        let channels = std::mem::take(&mut self.channels);
-        drop(channels);
+        // Sources stop producing; end of stream follows their queued frames
+        let deadline = tokio::time::Instant::now() + timeout;
+        for tx in self.root_inputs() {
+            let _ = tokio::time::timeout_at(deadline, tx.send(Delivery::external(Message::EndOfStream))).await;
+        }
+        self.node_inputs.clear();
+        self.fanout_controls.clear();
+        self.inbound_counts.clear();
+
+        // Join every task so no sink is left unflushed
+        let mut first_error = None;
+        for (node_id, mut handle) in std::mem::take(&mut self.handles) {
+            let error = match tokio::time::timeout_at(deadline, &mut handle).await {
+                Ok(Ok(Ok(()))) => continue,
+                Ok(Ok(Err(e))) => format!("Node '{}' failed while draining: {}", node_id, e),
+                Ok(Err(e)) => format!("Node '{}' task failed: {}", node_id, e),
+                Err(_) => {
+                    handle.abort();
+                    format!("Node '{}' did not drain within {:?}", node_id, timeout)
+                }
+            };
+            tracing::warn!(node = %node_id, "{}", error);
+            first_error.get_or_insert(AudiotabError::node(node_id, error));
+        }
+
+        // Final counters, now that every frame is through
+        if let Some((task, sampler)) = self.event_task.take() {
+            task.abort();
+            let metrics = sampler.lock().unwrap().sample();
+            let _ = self.events.send(PipelineEvent::Metrics(metrics));
+        }
+
+        match first_error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Subscribe to state changes, periodic metrics and node errors
+    ///
+    /// Metrics are published every `pipeline_config.metrics_interval_ms`
+    /// (default one second) while running, and once more after draining.
+    pub fn subscribe_events(&self) -> tokio::sync::broadcast::Receiver<PipelineEvent> {
+        self.events.subscribe()
+    }
+
+    /// Publish metrics on a timer, and node tasks the supervisor gave up on
+    fn spawn_event_task(&mut self, sampler: MetricsSampler) {
+        let sampler = Arc::new(std::sync::Mutex::new(sampler));
+        let task_sampler = sampler.clone();
+        let events = self.events.clone();
+        let mut restarts = self.supervisor.subscribe();
+        let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + self.metrics_interval, self.metrics_interval);
+
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {
+                        let metrics = task_sampler.lock().unwrap().sample();
+                        let _ = events.send(PipelineEvent::Metrics(metrics));
+                    }
+                    restart = restarts.recv() => match restart {
+                        Ok(event) if event.gave_up => {
+                            let _ = events.send(PipelineEvent::NodeError {
+                                node_id: event.node_id,
+                                error: event.error,
+                                fatal: true,
+                            });
+                        }
+                        Ok(_) | Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {}
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    },
+                }
+            }
+        });
+        self.event_task = Some((task, sampler));
+    }
+
+    /// Subscribe to node restarts made by the pipeline's supervisor
+    pub fn subscribe_restarts(&self) -> tokio::sync::broadcast::Receiver<RestartEvent> {
+        self.supervisor.subscribe()
+    }
+
+    /// Subscribe to circuit breaker trips and recoveries of nodes in this pipeline
+    pub fn subscribe_circuit_events(&self) -> tokio::sync::broadcast::Receiver<CircuitEvent> {
+        self.circuit_events.subscribe()
+    }
+
+    /// Frames that failed processing in this pipeline
+    pub fn dead_letters(&self) -> &DeadLetterQueue {
+        &self.dead_letters
+    }
+
+    /// Have every node emit the data it is holding back, in graph order
+    ///
+    /// A flush marker follows the frames already queued at the sources; each
+    /// node runs `on_flush` once the marker has arrived on all its inputs,
+    /// sends what it returns, and passes the marker on.
+    pub async fn flush(&self) -> Result<(), AudiotabError> {
+        for tx in self.root_inputs() {
+            tx.send(Delivery::external(Message::Flush))
+                .await
+                .map_err(|_| AudiotabError::state("Failed to send flush marker"))?;
+        }
+        Ok(())
+    }
+
+    /// Nodes that declare a device (`device_profile_id`), as (node id, device registration id)
+    pub fn device_bindings(&self) -> &[(String, String)] {
+        &self.device_bindings
+    }
+
+    /// Samples per frame set by `pipeline_config.frame_size`
+    pub fn frame_size(&self) -> Option<usize> {
+        self.frame_size
+    }
+
+    /// Record the packet buffering of a device feeding this pipeline, for `latency_budget`
+    pub fn set_device_buffer(&mut self, device_id: impl Into<String>, buffer: DeviceBuffer) {
+        self.device_buffers.insert(device_id.into(), buffer);
+    }
 
-        // Take ownership of handles and wait for completion
-        let handles = std::mem::take(&mut self.handles);
-        for handle in handles {
-            handle.await??;
+    /// End-to-end latency of the slowest path from a source to a sink
+    ///
+    /// Device-bound sources start with their device's buffering (see
+    /// `set_device_buffer`; `attach_devices` records it), other sources with
+    /// one frame of their `buffer_size` and `sample_rate`. Re-blocking edges
+    /// and node input channels at `channel_capacity` frames add to it.
+    pub fn latency_budget(&self) -> LatencyBudget {
+        let frame_size = self.frame_size.unwrap_or(DEFAULT_FRAME_SIZE);
+        let mut sources: Vec<_> = self
+            .node_configs
+            .iter()
+            .filter(|(id, _)| !self.connections.iter().any(|c| &c.to == *id))
+            .map(|(id, config)| {
+                let device = self.device_bindings.iter().find(|(node, _)| node == id).map(|(_, device)| device);
+                let source = match device.and_then(|d| self.device_buffers.get(d)) {
+                    Some(buffer) => LatencySource::from(*buffer),
+                    None => LatencySource::new(
+                        config["buffer_size"].as_u64().map_or(frame_size, |b| b as usize),
+                        config["sample_rate"].as_f64(),
+                        if device.is_some() { DEFAULT_BUFFER_COUNT } else { 1 },
+                    ),
+                };
+                (id.as_str(), source)
+            })
+            .collect();
+        sources.sort_by_key(|(id, _)| *id);
+        let edges = self
+            .connections
+            .iter()
+            .map(|c| LatencyEdge {
+                from: &c.from,
+                to: &c.to,
+                block_size: c.block_size,
+                decimation: c.decimation.unwrap_or(1),
+            })
+            .collect();
+        LatencyGraph {
+            sources,
+            edges,
+            channel_capacity: self.channel_capacity,
+            queued: self.channels.queued(),
         }
+        .slowest_path()
+    }
 
+    /// Start the devices requested by AudioSourceNodes, MidiTriggerNodes,
+    /// TriggerSourceNodes and RawBytesNodes and inject their channels
+    ///
+    /// Nodes reading the same device share it. Returns (profile, node) leases,
+    /// to be given back with `DeviceManager::release_device`.
+    pub async fn attach_devices(&mut self, manager: &DeviceManager) -> Result<Vec<(String, String)>, AudiotabError> {
+        let mut started: Vec<(String, String)> = Vec::new();
+        for (node_id, node) in self.nodes.iter_mut() {
+            let profile_id = if let Some(source) = node.as_any_mut().downcast_mut::<AudioSourceNode>() {
+                source.device_profile_id.clone()
+            } else if let Some(midi) = node.as_any_mut().downcast_mut::<MidiTriggerNode>() {
+                midi.device_profile_id.clone()
+            } else if let Some(trigger) = node.as_any_mut().downcast_mut::<TriggerSourceNode>() {
+                trigger.device_profile_id.clone()
+            } else if let Some(raw) = node.as_any_mut().downcast_mut::<RawBytesNode>() {
+                raw.device_profile_id.clone()
+            } else {
+                continue;
+            };
+            if profile_id.is_empty() {
+                continue;
+            }
+
+            let channels = manager
+                .acquire_device(&profile_id, node_id, DeviceAccess::Shared)
+                .await
+                .map_err(|e| e.context(format!("Failed to start device '{}' for node '{}'", profile_id, node_id)))?;
+            let channels = Some(channels);
+            if let Some(source) = node.as_any_mut().downcast_mut::<AudioSourceNode>() {
+                source.set_device_channels(channels);
+            } else if let Some(midi) = node.as_any_mut().downcast_mut::<MidiTriggerNode>() {
+                midi.set_device_channels(channels);
+            } else if let Some(trigger) = node.as_any_mut().downcast_mut::<TriggerSourceNode>() {
+                trigger.set_device_channels(channels);
+            } else if let Some(raw) = node.as_any_mut().downcast_mut::<RawBytesNode>() {
+                raw.set_device_channels(channels);
+            }
+            started.push((profile_id, node_id.clone()));
+        }
+        for (profile_id, _) in &started {
+            if let Some(profile) = manager.get_profile(profile_id) {
+                self.device_buffers.insert(profile_id.clone(), DeviceBuffer::from(&profile.config));
+            }
+        }
+        Ok(started)
+    }
+
+    /// Inputs of the running device-bound nodes, as (device registration id, input)
+    pub fn device_inputs(&self) -> Vec<(String, DeviceInput)> {
+        self.device_bindings
+            .iter()
+            .filter_map(|(node_id, device_id)| {
+                let tx = self.node_inputs.get(node_id)?.clone();
+                Some((device_id.clone(), DeviceInput { node_id: node_id.clone(), device_id: device_id.as_str().into(), tx }))
+            })
+            .collect()
+    }
+
+    /// Inputs of running nodes without inbound connections
+    fn root_inputs(&self) -> Vec<mpsc::Sender<Delivery>> {
+        self.node_inputs
+            .iter()
+            .filter(|(id, _)| !self.connections.iter().any(|c| &c.to == *id))
+            .map(|(_, tx)| tx.clone())
+            .collect()
+    }
+
+    /// Send a dead letter's frame back to the node that failed it
+    ///
+    /// The letter is removed from the queue; if the node fails again it is
+    /// stored under a new id.
+    pub async fn reinject(&self, letter_id: u64) -> Result<(), AudiotabError> {
+        let letter = self
+            .dead_letters
+            .get(letter_id)
+            .ok_or_else(|| AudiotabError::other(format!("No dead letter with id {}", letter_id)))?;
+        let tx = self
+            .node_inputs
+            .get(&letter.node_id)
+            .ok_or_else(|| AudiotabError::state(format!("Node '{}' is not running", letter.node_id)))?;
+        tx.send(Delivery::external(Message::Frame(Arc::new(letter.frame))))
+            .await
+            .map_err(|_| AudiotabError::state(format!("Node '{}' no longer accepts frames", letter.node_id)))?;
+        self.dead_letters.take(letter_id);
         Ok(())
     }
 
     pub fn get_monitor(&self) -> Option<PipelineMonitor> {
-        self.metrics_collector.as_ref().map(|c| PipelineMonitor::new(c.clone()))
+        let elapsed = match &self.state {
+            PipelineState::Running { start_time, .. } => start_time.map(|t| t.elapsed()),
+            PipelineState::Completed { duration, .. } => *duration,
+            _ => None,
+        };
+        self.metrics_collector.as_ref().map(|c| {
+            let monitor = PipelineMonitor::new(c.clone());
+            match elapsed {
+                Some(elapsed) => monitor.with_elapsed(elapsed),
+                None => monitor,
+            }
+        })
+    }
+
+    /// Frames queued in node inputs and lossy edges, with memory usage
+    pub fn resource_usage(&self) -> Option<ResourceUsage> {
+        self.metrics_collector.as_ref().map(MetricsCollector::resource_usage)
     }
 
     /// Get the current state of the pipeline
@@ -293,7 +1555,7 @@ impl AsyncPipeline {
     /// This will require adding a broadcast channel to AsyncPipeline
     /// For now, errors are propagated through the ResilientNode wrapper
     /// and can be monitored via the metrics collector
-    pub fn subscribe_errors(&self) -> Result<tokio::sync::broadcast::Receiver<String>> {
-        Err(anyhow!("Error subscription not yet implemented. Use metrics collector for error monitoring."))
+    pub fn subscribe_errors(&self) -> Result<tokio::sync::broadcast::Receiver<String>, AudiotabError> {
+        Err(AudiotabError::other("Error subscription not yet implemented. Use metrics collector for error monitoring."))
     }
 }
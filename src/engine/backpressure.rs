@@ -0,0 +1,202 @@
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, Weak};
+use tokio::sync::Notify;
+
+/// What an edge does with a frame when its target cannot keep up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackpressurePolicy {
+    /// Wait for room, slowing the sending node down (never loses frames)
+    #[default]
+    Block,
+    /// Discard the oldest queued frame to make room
+    DropOldest,
+    /// Discard the frame being sent
+    DropNewest,
+    /// Keep only the latest frame waiting on the edge
+    Coalesce,
+}
+
+impl BackpressurePolicy {
+    /// Parse a policy name (`"block"`, `"drop_oldest"`, `"drop_newest"`,
+    /// `"coalesce"`); hyphenated names are accepted as well
+    pub fn from_json(value: &Value) -> Result<Self> {
+        let name = value
+            .as_str()
+            .ok_or_else(|| anyhow!("Backpressure policy must be a name"))?;
+        match name.replace('-', "_").as_str() {
+            "block" => Ok(BackpressurePolicy::Block),
+            "drop_oldest" => Ok(BackpressurePolicy::DropOldest),
+            "drop_newest" => Ok(BackpressurePolicy::DropNewest),
+            "coalesce" => Ok(BackpressurePolicy::Coalesce),
+            other => Err(anyhow!("Unknown backpressure policy: {}", other)),
+        }
+    }
+
+    /// Whether the policy may discard frames
+    pub fn is_lossy(&self) -> bool {
+        *self != BackpressurePolicy::Block
+    }
+}
+
+/// Bounded queue of a lossy edge, applying its policy to droppable items
+///
+/// Items that are not droppable (control markers) are always queued.
+/// The queue closes when the sender is dropped; the receiver still gets
+/// what was queued before.
+pub(crate) fn edge_queue<T>(policy: BackpressurePolicy, capacity: usize) -> (EdgeSender<T>, EdgeReceiver<T>) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(QueueState { items: VecDeque::new(), closed: false }),
+        notify: Notify::new(),
+    });
+    let sender = EdgeSender { shared: shared.clone(), policy, capacity: capacity.max(1) };
+    (sender, EdgeReceiver { shared })
+}
+
+struct Shared<T> {
+    state: Mutex<QueueState<T>>,
+    notify: Notify,
+}
+
+struct QueueState<T> {
+    /// Items with whether each may be dropped
+    items: VecDeque<(T, bool)>,
+    closed: bool,
+}
+
+pub(crate) struct EdgeSender<T> {
+    shared: Arc<Shared<T>>,
+    policy: BackpressurePolicy,
+    capacity: usize,
+}
+
+impl<T> EdgeSender<T> {
+    /// Queue an item without waiting; returns the number of items dropped
+    pub fn push(&self, item: T, droppable: bool) -> u64 {
+        let mut state = self.shared.state.lock().unwrap_or_else(|p| p.into_inner());
+        let items = &mut state.items;
+        let mut dropped = 0;
+        if droppable {
+            match self.policy {
+                BackpressurePolicy::Block => {}
+                BackpressurePolicy::DropNewest => {
+                    if items.len() >= self.capacity {
+                        return 1;
+                    }
+                }
+                BackpressurePolicy::DropOldest => {
+                    if items.len() >= self.capacity {
+                        if let Some(oldest) = items.iter().position(|(_, droppable)| *droppable) {
+                            items.remove(oldest);
+                            dropped += 1;
+                        }
+                    }
+                }
+                BackpressurePolicy::Coalesce => {
+                    // Frames queued since the last marker are superseded
+                    while items.back().is_some_and(|(_, droppable)| *droppable) {
+                        items.pop_back();
+                        dropped += 1;
+                    }
+                }
+            }
+        }
+        items.push_back((item, droppable));
+        drop(state);
+        self.shared.notify.notify_one();
+        dropped
+    }
+
+    /// Reads `(queued, capacity)` without keeping the queue alive; `None`
+    /// once the queue is closed
+    pub fn probe(&self) -> impl Fn() -> Option<(usize, usize)> + Send + Sync + 'static
+    where
+        T: Send + 'static,
+    {
+        let shared: Weak<Shared<T>> = Arc::downgrade(&self.shared);
+        let capacity = self.capacity;
+        move || {
+            let shared = shared.upgrade()?;
+            let state = shared.state.lock().unwrap_or_else(|p| p.into_inner());
+            (!state.closed).then_some((state.items.len(), capacity))
+        }
+    }
+}
+
+impl<T> Drop for EdgeSender<T> {
+    fn drop(&mut self) {
+        self.shared.state.lock().unwrap_or_else(|p| p.into_inner()).closed = true;
+        self.shared.notify.notify_one();
+    }
+}
+
+pub(crate) struct EdgeReceiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> EdgeReceiver<T> {
+    /// Next queued item, or `None` once the sender is gone and the queue is empty
+    pub async fn recv(&self) -> Option<T> {
+        loop {
+            {
+                let mut state = self.shared.state.lock().unwrap_or_else(|p| p.into_inner());
+                if let Some((item, _)) = state.items.pop_front() {
+                    return Some(item);
+                }
+                if state.closed {
+                    return None;
+                }
+            }
+            self.shared.notify.notified().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn drain(rx: EdgeReceiver<u32>) -> Vec<u32> {
+        let mut items = Vec::new();
+        while let Some(item) = rx.recv().await {
+            items.push(item);
+        }
+        items
+    }
+
+    #[tokio::test]
+    async fn test_drop_policies() {
+        let (tx, rx) = edge_queue(BackpressurePolicy::DropNewest, 2);
+        let dropped: u64 = (1..=4).map(|i| tx.push(i, true)).sum();
+        drop(tx);
+        assert_eq!(dropped, 2);
+        assert_eq!(drain(rx).await, vec![1, 2]);
+
+        let (tx, rx) = edge_queue(BackpressurePolicy::DropOldest, 2);
+        let dropped: u64 = (1..=4).map(|i| tx.push(i, true)).sum();
+        drop(tx);
+        assert_eq!(dropped, 2);
+        assert_eq!(drain(rx).await, vec![3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_coalesce_keeps_markers() {
+        let (tx, rx) = edge_queue(BackpressurePolicy::Coalesce, 8);
+        tx.push(1, true);
+        tx.push(2, true);
+        tx.push(0, false);
+        tx.push(3, true);
+        assert_eq!(tx.push(4, true), 1);
+        drop(tx);
+        assert_eq!(drain(rx).await, vec![2, 0, 4]);
+    }
+
+    #[test]
+    fn test_parses_policy_names() {
+        assert_eq!(BackpressurePolicy::from_json(&Value::from("drop-oldest")).unwrap(), BackpressurePolicy::DropOldest);
+        assert_eq!(BackpressurePolicy::from_json(&Value::from("coalesce")).unwrap(), BackpressurePolicy::Coalesce);
+        assert!(BackpressurePolicy::from_json(&Value::from("spill")).is_err());
+        assert!(!BackpressurePolicy::Block.is_lossy());
+    }
+}
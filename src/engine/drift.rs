@@ -0,0 +1,130 @@
+use crate::core::DataFrame;
+use crate::observability::DriftMetrics;
+use std::sync::Arc;
+
+/// Observation time before a device's rate estimate is trusted
+pub const DEFAULT_DRIFT_WINDOW_NS: u64 = 2_000_000_000;
+
+/// Estimates a device's true sample rate from packet timestamps
+///
+/// The rate is the number of samples delivered divided by the elapsed
+/// time since the first packet, so timestamp jitter averages out as the
+/// observation window grows.
+#[derive(Debug, Clone)]
+pub struct DriftEstimator {
+    nominal_rate: f64,
+    min_window_ns: u64,
+    start_ns: Option<u64>,
+    samples: u64,
+    ratio: Option<f64>,
+}
+
+impl DriftEstimator {
+    pub fn new(nominal_rate: f64) -> Self {
+        Self::with_window(nominal_rate, DEFAULT_DRIFT_WINDOW_NS)
+    }
+
+    pub fn with_window(nominal_rate: f64, min_window_ns: u64) -> Self {
+        Self {
+            nominal_rate,
+            min_window_ns,
+            start_ns: None,
+            samples: 0,
+            ratio: None,
+        }
+    }
+
+    /// Record a packet of `num_samples` (per channel) starting at `timestamp_ns`
+    pub fn observe(&mut self, timestamp_ns: u64, num_samples: usize) {
+        match self.start_ns {
+            None => self.start_ns = Some(timestamp_ns),
+            Some(start) => {
+                let elapsed = timestamp_ns.saturating_sub(start);
+                if elapsed >= self.min_window_ns && self.samples > 0 {
+                    let measured = self.samples as f64 / (elapsed as f64 * 1e-9);
+                    self.ratio = Some(measured / self.nominal_rate);
+                }
+            }
+        }
+        self.samples += num_samples as u64;
+    }
+
+    /// Measured / nominal sample rate, once the observation window is full
+    pub fn rate_ratio(&self) -> Option<f64> {
+        self.ratio
+    }
+}
+
+/// Keeps one device's stream aligned with a reference device
+///
+/// Drift against the reference is accumulated as a fractional sample
+/// count; whole samples are dropped from (device running fast) or
+/// duplicated at (device running slow) the end of each frame.
+pub struct DriftCompensator {
+    estimator: DriftEstimator,
+    reference: Option<Arc<DriftMetrics>>,
+    metrics: Arc<DriftMetrics>,
+    pending: f64,
+}
+
+impl DriftCompensator {
+    /// Compensator for a device; `reference` is `None` for the reference device itself
+    pub fn new(
+        estimator: DriftEstimator,
+        metrics: Arc<DriftMetrics>,
+        reference: Option<Arc<DriftMetrics>>,
+    ) -> Self {
+        Self {
+            estimator,
+            reference,
+            metrics,
+            pending: 0.0,
+        }
+    }
+
+    pub fn metrics(&self) -> &Arc<DriftMetrics> {
+        &self.metrics
+    }
+
+    /// Update the estimate with `frame` and correct its length against the reference
+    pub fn process(&mut self, frame: &mut DataFrame, timestamp_ns: u64) {
+        let num_samples = frame.payload.values().map(|c| c.len()).max().unwrap_or(0);
+        self.estimator.observe(timestamp_ns, num_samples);
+
+        let Some(ratio) = self.estimator.rate_ratio() else {
+            return;
+        };
+        self.metrics.set_rate_ratio(ratio);
+
+        let Some(reference) = self.reference.as_ref().and_then(|r| r.rate_ratio()) else {
+            return;
+        };
+        let relative = ratio / reference;
+        self.metrics.set_drift_ppm((relative - 1.0) * 1e6);
+
+        // Samples this frame produced beyond what the reference clock did
+        self.pending += num_samples as f64 * (1.0 - 1.0 / relative);
+        let correction = self.pending.trunc();
+        if correction == 0.0 || num_samples == 0 {
+            return;
+        }
+        self.pending -= correction;
+
+        let count = (correction.abs() as usize).min(num_samples - 1);
+        for channel in frame.payload.values_mut() {
+            let samples = channel.samples_mut();
+            if correction > 0.0 {
+                let len = samples.len().saturating_sub(count);
+                samples.truncate(len);
+            } else if let Some(&last) = samples.last() {
+                samples.resize(samples.len() + count, last);
+            }
+        }
+
+        if correction > 0.0 {
+            self.metrics.record_dropped(count as u64);
+        } else {
+            self.metrics.record_inserted(count as u64);
+        }
+    }
+}
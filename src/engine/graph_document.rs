@@ -0,0 +1,224 @@
+use super::async_pipeline::node_metadata;
+use super::subgraph::{SubgraphDefinition, SUBGRAPH_NODE_TYPE};
+use crate::registry::NodeMetadata;
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Current project file schema version
+pub const GRAPH_DOCUMENT_VERSION: u32 = 1;
+
+/// File extension used for saved projects
+pub const PROJECT_EXTENSION: &str = "atproj";
+
+/// Upgrades a raw document from version N to N + 1
+pub type Migration = fn(&mut Value) -> Result<()>;
+
+/// Migrations indexed by the version they upgrade from
+const MIGRATIONS: &[Migration] = &[migrate_v0_to_v1];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodePosition {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraphNode {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub node_type: String,
+    #[serde(default)]
+    pub parameters: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<NodePosition>,
+    /// UI fields the engine does not interpret, kept for round-tripping
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphEdge {
+    #[serde(default)]
+    pub id: String,
+    pub source: String,
+    pub target: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_handle: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_handle: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Serializable pipeline graph stored in `.atproj` project files
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraphDocument {
+    pub schema_version: u32,
+    #[serde(default)]
+    pub name: String,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    #[serde(default)]
+    pub pipeline_config: Value,
+    /// Reusable node groups, instantiated by `Subgraph` nodes
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub subgraphs: BTreeMap<String, SubgraphDefinition>,
+}
+
+impl GraphDocument {
+    pub fn new(name: impl Into<String>, nodes: Vec<GraphNode>, edges: Vec<GraphEdge>) -> Self {
+        Self {
+            schema_version: GRAPH_DOCUMENT_VERSION,
+            name: name.into(),
+            nodes,
+            edges,
+            pipeline_config: Value::Null,
+            subgraphs: BTreeMap::new(),
+        }
+    }
+
+    /// Parse a document of any supported version, applying migrations
+    pub fn from_value(mut value: Value) -> Result<Self> {
+        let version = value
+            .get("schema_version")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0) as u32;
+        if version > GRAPH_DOCUMENT_VERSION {
+            return Err(anyhow!(
+                "Project schema version {} is newer than supported version {}",
+                version,
+                GRAPH_DOCUMENT_VERSION
+            ));
+        }
+
+        for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+            migration(&mut value).with_context(|| format!("Migration from schema version {} failed", from))?;
+            value["schema_version"] = json!(from + 1);
+        }
+
+        serde_json::from_value(value).context("Invalid project document")
+    }
+
+    pub fn to_value(&self) -> Result<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read project {}", path.display()))?;
+        let value: Value = serde_json::from_str(&contents)
+            .with_context(|| format!("Invalid JSON in project {}", path.display()))?;
+        Self::from_value(value)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let contents = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, contents)
+            .with_context(|| format!("Failed to write project {}", path.display()))
+    }
+
+    /// Record the registered version of each node that does not carry one yet,
+    /// so later releases can migrate its parameters
+    pub fn stamp_node_versions(&mut self) {
+        for node in &mut self.nodes {
+            if node.extra.contains_key("version") {
+                continue;
+            }
+            if let Some(meta) = NodeMetadata::find(&node.node_type) {
+                node.extra.insert("version".to_string(), json!(meta.version));
+            }
+        }
+    }
+
+    /// Convert to the `AsyncPipeline::from_json` format
+    ///
+    /// Node types are passed through unchanged; UI-level names must be
+    /// mapped by the caller if they differ from engine node names. Edge
+    /// handles become `from_port`/`to_port` on subgraph instances and on
+    /// nodes with several or variadic ports on that side.
+    pub fn to_pipeline_json(&self) -> Value {
+        let nodes: Vec<Value> = self
+            .nodes
+            .iter()
+            .map(|n| {
+                let mut node = json!({"id": n.id, "type": n.node_type, "config": n.parameters});
+                for key in ["version", "subgraph", "port_counts"] {
+                    if let Some(value) = n.extra.get(key) {
+                        node[key] = value.clone();
+                    }
+                }
+                node
+            })
+            .collect();
+
+        let names_port = |id: &str, output: bool| {
+            self.nodes.iter().any(|n| {
+                n.id == id
+                    && (n.node_type == SUBGRAPH_NODE_TYPE
+                        || node_metadata(&n.node_type)
+                            .is_some_and(|meta| if output { meta.names_outputs() } else { meta.names_inputs() }))
+            })
+        };
+        let connections: Vec<Value> = self
+            .edges
+            .iter()
+            .map(|e| {
+                let mut conn = json!({"from": e.source, "to": e.target});
+                if let Some(handle) = e.source_handle.as_ref().filter(|_| names_port(&e.source, true)) {
+                    conn["from_port"] = json!(handle);
+                }
+                if let Some(handle) = e.target_handle.as_ref().filter(|_| names_port(&e.target, false)) {
+                    conn["to_port"] = json!(handle);
+                }
+                conn
+            })
+            .collect();
+
+        let mut pipeline = json!({"nodes": nodes, "connections": connections});
+        if !self.pipeline_config.is_null() {
+            pipeline["pipeline_config"] = self.pipeline_config.clone();
+        }
+        if !self.subgraphs.is_empty() {
+            pipeline["subgraphs"] = json!(self.subgraphs);
+        }
+        pipeline
+    }
+}
+
+/// v0 documents were unversioned backend pipelines (`config`, `connections`)
+fn migrate_v0_to_v1(doc: &mut Value) -> Result<()> {
+    let obj = doc
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("Project document must be a JSON object"))?;
+
+    if let Some(nodes) = obj.get_mut("nodes").and_then(|n| n.as_array_mut()) {
+        for node in nodes {
+            if let Some(node) = node.as_object_mut() {
+                if !node.contains_key("parameters") {
+                    let config = node.remove("config").unwrap_or(json!({}));
+                    node.insert("parameters".to_string(), config);
+                }
+            }
+        }
+    }
+
+    if !obj.contains_key("edges") {
+        let connections = obj.remove("connections").unwrap_or(json!([]));
+        let edges: Vec<Value> = connections
+            .as_array()
+            .map(|c| c.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .enumerate()
+            .map(|(i, c)| json!({"id": format!("e{}", i), "source": c["from"], "target": c["to"]}))
+            .collect();
+        obj.insert("edges".to_string(), Value::Array(edges));
+    }
+    Ok(())
+}
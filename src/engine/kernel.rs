@@ -4,11 +4,16 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::{broadcast, RwLock};
 use tokio::task::JoinHandle;
+use tracing::Instrument;
 
-use crate::hal::{Device, DeviceChannels, HardwareRegistry, DeviceConfig};
+use crate::hal::{BytesDecoder, Device, DeviceChannels, HardwareRegistry, PacketBuffer, SampleData, TimeBase};
+use crate::hal::calibration::{calibration_status, unix_now, DEFAULT_MAX_AGE};
 use crate::hal::registered::HardwareConfig;
 use crate::hal::format_converter;
-use crate::engine::AsyncPipeline;
+use crate::engine::{AsyncPipeline, DeviceBuffer, DeviceInput, Priority};
+use crate::engine::realtime::{spawn_realtime, RealtimeConfig};
+use crate::engine::drift::{DriftCompensator, DriftEstimator};
+use crate::observability::{DeviceHealthSnapshot, DriftMetrics, DriftSnapshot, EventKind, EventLog, LoggedEvent};
 
 /// Kernel status enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -21,8 +26,9 @@ pub enum KernelStatus {
 
 /// AudioKernelRuntime orchestrates the connection between HAL and Pipeline
 pub struct AudioKernelRuntime {
-    /// Active device instances
-    active_devices: HashMap<String, Box<dyn Device>>,
+    /// Active device instances, behind a lock so the runtime can be shared
+    /// between threads (devices themselves are only `Send`)
+    active_devices: HashMap<String, std::sync::Mutex<Box<dyn Device>>>,
 
     /// Device channels for buffer ping-pong
     device_channels: HashMap<String, DeviceChannels>,
@@ -44,8 +50,41 @@ pub struct AudioKernelRuntime {
 
     /// Hardware configuration
     hardware_config: HardwareConfig,
+
+    /// Common clock that all device packets are stamped against
+    time_base: TimeBase,
+
+    /// Sample clock drift per device, measured against the first started device
+    drift_metrics: HashMap<String, Arc<DriftMetrics>>,
+
+    /// Pipeline inputs each device reader sends its frames to
+    frame_routes: Routes<DeviceInput>,
+
+    /// Copies of the raw packets of each device, for nodes that parse them (MIDI)
+    packet_taps: Routes<crossbeam_channel::Sender<PacketBuffer>>,
+
+    /// Run device readers on dedicated threads, if set
+    realtime: Option<RealtimeConfig>,
+
+    /// Audit trail for device starts, stops and failures, if set
+    event_log: Option<EventLog>,
+
+    /// Samples per packet of the devices started next, overriding their registrations
+    frame_size: Option<usize>,
+
+    /// Packet buffering of each device started by the last `start()`
+    device_buffers: HashMap<String, DeviceBuffer>,
 }
 
+/// Per-device subscribers, shared with the reader tasks
+type Routes<T> = Arc<std::sync::RwLock<HashMap<String, Vec<T>>>>;
+
+/// Packets queued per tap before further packets are dropped
+const TAP_CAPACITY: usize = 256;
+
+/// How long a reader on a dedicated thread blocks for a packet before checking for shutdown
+const REALTIME_POLL: std::time::Duration = std::time::Duration::from_millis(5);
+
 impl AudioKernelRuntime {
     /// Create new AudioKernelRuntime with owned registry (for backward compatibility)
     pub fn new(registry: HardwareRegistry, hardware_config: HardwareConfig) -> Self {
@@ -67,6 +106,14 @@ impl AudioKernelRuntime {
             reader_handles: Vec::new(),
             registry,
             hardware_config,
+            time_base: TimeBase::global(),
+            drift_metrics: HashMap::new(),
+            frame_routes: Arc::default(),
+            packet_taps: Arc::default(),
+            realtime: None,
+            event_log: None,
+            frame_size: None,
+            device_buffers: HashMap::new(),
         }
     }
 
@@ -80,11 +127,71 @@ impl AudioKernelRuntime {
         self.active_devices.len()
     }
 
+    /// Clock shared by all devices; frame timestamps are nanoseconds since its origin
+    pub fn time_base(&self) -> TimeBase {
+        self.time_base
+    }
+
+    /// Drift statistics for each device started by the last `start()`
+    pub fn drift_stats(&self) -> HashMap<String, DriftSnapshot> {
+        self.drift_metrics
+            .iter()
+            .map(|(id, metrics)| (id.clone(), metrics.snapshot()))
+            .collect()
+    }
+
+    /// Health of active devices whose backend reports xruns or shutdowns
+    pub fn device_health(&self) -> HashMap<String, DeviceHealthSnapshot> {
+        self.active_devices
+            .iter()
+            .filter_map(|(id, device)| {
+                let device = device.lock().unwrap_or_else(|p| p.into_inner());
+                device.health().map(|health| (id.clone(), health))
+            })
+            .collect()
+    }
+
+    /// Run device readers on dedicated threads from the next `start()`
+    ///
+    /// Readers block on their device instead of polling the tokio pool, at
+    /// the thread priority of the kernel's pipeline (`Critical` without one).
+    pub fn set_realtime(&mut self, realtime: Option<RealtimeConfig>) {
+        self.realtime = realtime;
+    }
+
+    /// Record device starts, stops, calibrations and failures in `event_log`
+    pub fn set_event_log(&mut self, event_log: Option<EventLog>) {
+        self.event_log = event_log;
+    }
+
+    fn record(&self, event: LoggedEvent) {
+        if let Some(log) = &self.event_log {
+            log.record(event);
+        }
+    }
+
     /// Set pipeline (optional)
+    ///
+    /// A pipeline with a `frame_size` sets the frame size of the devices
+    /// started next (see `set_frame_size`).
     pub fn set_pipeline(&mut self, pipeline: AsyncPipeline) {
+        if let Some(frame_size) = pipeline.frame_size() {
+            self.frame_size = Some(frame_size);
+        }
         self.pipeline = Some(pipeline);
     }
 
+    /// Open devices with `frame_size` samples per packet from the next
+    /// `start()`, instead of the default of their registration
+    pub fn set_frame_size(&mut self, frame_size: Option<usize>) {
+        self.frame_size = frame_size;
+    }
+
+    /// Packet buffering of a running device
+    pub fn device_buffer(&self, device_id: &str) -> Option<DeviceBuffer> {
+        self.device_buffers.get(device_id).copied()
+    }
+
     /// Start the kernel - creates and starts all enabled devices
     pub async fn start(&mut self) -> Result<()> {
         if self.status == KernelStatus::Running {
@@ -100,21 +207,29 @@ impl AudioKernelRuntime {
         // Create devices from registered hardware
         let registered_devices = self.hardware_config.registered_devices.clone();
         let num_registered = registered_devices.len();
+        self.drift_metrics.clear();
+        self.device_buffers.clear();
+        let mut reference_drift: Option<Arc<DriftMetrics>> = None;
 
         for registered in registered_devices {
             if !registered.enabled {
                 continue;
             }
 
-            // Create device config from registered hardware
-            let device_config = DeviceConfig {
-                name: registered.user_name.clone(),
-                sample_rate: registered.sample_rate,
-                format: crate::hal::SampleFormat::F32, // Default to F32
-                buffer_size: 1024, // Default buffer size
-                channel_mapping: registered.channel_mapping.clone(),
-                calibration: registered.calibration,
-            };
+            let status = calibration_status(&registered.calibration, unix_now(), DEFAULT_MAX_AGE);
+            if status.stale && status.calibrated_at.is_some() {
+                let message = format!(
+                    "Calibration is {} days old; recalibrate before measuring",
+                    status.age_secs.unwrap_or(0) / 86400
+                );
+                tracing::warn!(device = %registered.registration_id, "{}", message);
+                self.record(LoggedEvent::new(EventKind::Warning, message).device(&registered.registration_id));
+            }
+            let mut device_config = registered.device_config();
+            if let Some(frame_size) = self.frame_size {
+                device_config.buffer_size = frame_size;
+            }
+            let buffer = DeviceBuffer::from(&device_config);
 
             // Create device from registry (read lock)
             match {
@@ -128,6 +243,17 @@ impl AudioKernelRuntime {
                 Ok(mut device) => {
                     // Start the device
                     device.start().await?;
+                    tracing::info!(device = %registered.registration_id, driver = %registered.driver_id, "Device started");
+                    self.record(
+                        LoggedEvent::new(EventKind::DeviceStarted, "Device started")
+                            .device(&registered.registration_id)
+                            .data(serde_json::json!({
+                                "driver": registered.driver_id,
+                                "sample_rate": registered.sample_rate,
+                                "calibration": registered.calibration,
+                                "calibration_stale": status.stale,
+                            })),
+                    );
 
                     // Get device channels
                     let channels = device.get_channels();
@@ -135,20 +261,38 @@ impl AudioKernelRuntime {
                     // Store channels
                     self.device_channels.insert(registered.registration_id.clone(), channels.clone());
 
+                    // The first started device is the clock reference for the others
+                    let metrics = Arc::new(DriftMetrics::new(&registered.registration_id));
+                    let reference = reference_drift.clone();
+                    if reference.is_none() {
+                        reference_drift = Some(metrics.clone());
+                    }
+                    self.drift_metrics.insert(registered.registration_id.clone(), metrics.clone());
+                    let compensator = DriftCompensator::new(
+                        DriftEstimator::new(registered.sample_rate as f64),
+                        metrics,
+                        reference,
+                    );
+
                     // Spawn device reader task
+                    let decoder = device.bytes_decoder();
                     self.spawn_device_reader_task(
                         registered.registration_id.clone(),
                         channels,
+                        compensator,
+                        decoder,
                         shutdown_tx.subscribe(),
                     );
 
                     // Store device
-                    self.active_devices.insert(registered.registration_id.clone(), device);
+                    self.device_buffers.insert(registered.registration_id.clone(), buffer);
+                    self.active_devices.insert(registered.registration_id.clone(), std::sync::Mutex::new(device));
                 }
                 Err(e) => {
-                    eprintln!(
-                        "Failed to create device {}: {}",
-                        registered.registration_id, e
+                    tracing::error!(device = %registered.registration_id, "Failed to create device: {}", e);
+                    self.record(
+                        LoggedEvent::new(EventKind::Error, format!("Failed to create device: {}", e))
+                            .device(&registered.registration_id),
                     );
                     // Continue with other devices
                 }
@@ -163,8 +307,14 @@ impl AudioKernelRuntime {
 
         // Start pipeline if available
         if let Some(ref mut pipeline) = self.pipeline {
+            for (device_id, buffer) in &self.device_buffers {
+                pipeline.set_device_buffer(device_id.clone(), *buffer);
+            }
             pipeline.start().await?;
         }
+        if let Some(ref pipeline) = self.pipeline {
+            self.bind_pipeline(pipeline)?;
+        }
 
         self.status = KernelStatus::Running;
         Ok(())
@@ -181,91 +331,232 @@ impl AudioKernelRuntime {
             return Ok(());
         }
 
-        // Send shutdown signal to all reader tasks
+        // Stop all devices first so no new packets are produced
+        for (device_id, device) in self.active_devices.iter_mut() {
+            let device = device.get_mut().unwrap_or_else(|p| p.into_inner());
+            if let Err(e) = device.stop().await {
+                tracing::warn!(device = %device_id, "Failed to stop device: {}", e);
+            }
+            if let Some(log) = &self.event_log {
+                // Xrun counts cover the whole run, so they go with the stop
+                log.record(
+                    LoggedEvent::new(EventKind::DeviceStopped, "Device stopped")
+                        .device(device_id.as_str())
+                        .data(serde_json::json!({ "health": device.health() })),
+                );
+            }
+        }
+
+        // Reader tasks convert the packets still queued, then exit
         if let Some(tx) = &self.shutdown_tx {
             let _ = tx.send(());
         }
-
-        // Wait for all reader tasks to complete
         while let Some(handle) = self.reader_handles.pop() {
             let _ = handle.await;
         }
 
-        // Stop all devices
-        for (device_id, device) in self.active_devices.iter_mut() {
-            if let Err(e) = device.stop().await {
-                eprintln!("Failed to stop device {}: {}", device_id, e);
-            }
-        }
-
-        // Stop pipeline if available
+        // Drain the pipeline if available
         if let Some(ref mut pipeline) = self.pipeline {
             pipeline.stop().await?;
         }
 
         // Clear all state
+        self.frame_routes.write().unwrap_or_else(|p| p.into_inner()).clear();
+        self.packet_taps.write().unwrap_or_else(|p| p.into_inner()).clear();
         self.active_devices.clear();
         self.device_channels.clear();
+        self.device_buffers.clear();
         self.shutdown_tx = None;
         self.status = KernelStatus::Stopped;
 
         Ok(())
     }
 
+    /// Send the frames of every device a started pipeline declares to the bound nodes
+    ///
+    /// Nodes bind with `device_profile_id` set to a registration id. Fails
+    /// without binding anything if one of those devices is not running.
+    /// Returns the number of bound nodes; bindings end when the pipeline stops.
+    pub fn bind_pipeline(&self, pipeline: &AsyncPipeline) -> Result<usize> {
+        for (node_id, device_id) in pipeline.device_bindings() {
+            if !self.active_devices.contains_key(device_id) {
+                return Err(anyhow!("Node '{}' is bound to device '{}', which is not running", node_id, device_id));
+            }
+        }
+        let inputs = pipeline.device_inputs();
+        let count = inputs.len();
+        let mut routes = self.frame_routes.write().unwrap_or_else(|p| p.into_inner());
+        for (device_id, input) in inputs {
+            routes.entry(device_id).or_default().push(input);
+        }
+        Ok(count)
+    }
+
+    /// Drop the bindings of pipelines that have stopped
+    ///
+    /// Readers also drop them on their next frame; this releases them
+    /// without waiting for one. Returns the number of bindings removed.
+    pub fn unbind_stopped(&self) -> usize {
+        let mut routes = self.frame_routes.write().unwrap_or_else(|p| p.into_inner());
+        let mut removed = 0;
+        for inputs in routes.values_mut() {
+            let before = inputs.len();
+            inputs.retain(|input| !input.is_closed());
+            removed += before - inputs.len();
+        }
+        routes.retain(|_, inputs| !inputs.is_empty());
+        removed
+    }
+
+    /// Receive copies of a running device's raw packets
+    ///
+    /// For nodes that read packets themselves, such as `MidiTriggerNode`.
+    /// Buffers sent back on `empty_tx` are discarded; packets are dropped
+    /// while the tap is full, and the tap closes when the receiver is dropped.
+    pub fn tap_packets(&self, device_id: &str) -> Result<DeviceChannels> {
+        if !self.active_devices.contains_key(device_id) {
+            return Err(anyhow!("Device '{}' is not running", device_id));
+        }
+        let (filled_tx, filled_rx) = crossbeam_channel::bounded(TAP_CAPACITY);
+        let (empty_tx, _) = crossbeam_channel::bounded(1);
+        self.packet_taps
+            .write()
+            .unwrap_or_else(|p| p.into_inner())
+            .entry(device_id.to_string())
+            .or_default()
+            .push(filled_tx);
+        Ok(DeviceChannels { filled_rx, empty_tx })
+    }
+
     /// Spawn a task to read from device and convert to DataFrame
     fn spawn_device_reader_task(
         &mut self,
         device_id: String,
         channels: DeviceChannels,
+        mut compensator: DriftCompensator,
+        mut decoder: Option<Box<dyn BytesDecoder>>,
         mut shutdown_rx: broadcast::Receiver<()>,
     ) {
-        let handle = tokio::spawn(async move {
+        let time_base = self.time_base;
+        let frame_routes = self.frame_routes.clone();
+        let packet_taps = self.packet_taps.clone();
+        let dedicated = self.realtime.is_some();
+        let thread_name = format!("audiotab-reader-{}", device_id);
+        let span = tracing::info_span!("device", device = %device_id);
+        let event_log = self.event_log.clone();
+        let task = async move {
             let mut sequence_id = 0u64;
+            let mut draining = false;
+            let mut converter = format_converter::FrameConverter::new();
 
             loop {
-                // Check for shutdown signal
-                if shutdown_rx.try_recv().is_ok() {
-                    break;
+                // On shutdown, finish the packets already queued
+                if !draining && shutdown_rx.try_recv().is_ok() {
+                    draining = true;
                 }
 
-                // Try to receive filled buffer from device
-                match channels.filled_rx.try_recv() {
-                    Ok(packet) => {
-                        // Convert PacketBuffer to DataFrame
-                        match format_converter::packet_to_frame(&packet, sequence_id) {
-                            Ok(_frame) => {
-                                // TODO: Send frame to pipeline or RingBufferWriter
-                                // This will be implemented in Phase 3 when AudioInputNode is created
+                // Try to receive filled buffer from device; a dedicated thread can block for it
+                let received = if dedicated {
+                    channels.filled_rx.recv_timeout(REALTIME_POLL).map_err(|e| match e {
+                        crossbeam_channel::RecvTimeoutError::Timeout => crossbeam_channel::TryRecvError::Empty,
+                        crossbeam_channel::RecvTimeoutError::Disconnected => crossbeam_channel::TryRecvError::Disconnected,
+                    })
+                } else {
+                    channels.filled_rx.try_recv()
+                };
+                match received {
+                    Ok(mut packet) => {
+                        let _span = tracing::debug_span!("packet", sequence_id).entered();
+
+                        // Device capture time, or arrival time when the driver has none
+                        let timestamp_ns = *packet.timestamp.get_or_insert_with(|| time_base.now_ns());
+
+                        route(&packet_taps, &device_id, |tap| {
+                            !matches!(tap.try_send(packet.clone()), Err(crossbeam_channel::TrySendError::Disconnected(_)))
+                        });
+
+                        // Convert PacketBuffer to DataFrame; byte packets go through the device's decoder,
+                        // else the packet's codec (without either they are raw messages, for packet taps only)
+                        let converted = match (&packet.data, decoder.as_mut()) {
+                            (SampleData::Bytes(_), Some(decoder)) => {
+                                Some(format_converter::bytes_to_frame(&packet, sequence_id, decoder.as_mut()))
+                            }
+                            (SampleData::Bytes(_), None) if packet.codec.is_none() => None,
+                            _ => Some(converter.convert(&packet, sequence_id)),
+                        };
+                        match converted {
+                            Some(Ok(mut frame)) => {
+                                compensator.process(&mut frame, timestamp_ns);
+                                frame.metadata.insert("device", device_id.as_str());
+
+                                // Every bound node shares the frame; a full input drops it
+                                let frame = Arc::new(frame);
+                                route(&frame_routes, &device_id, |input| match input.try_send(frame.clone()) {
+                                    Ok(true) => true,
+                                    Ok(false) => {
+                                        tracing::warn!(node = %input.node_id(), "Node is not keeping up with the device; frame dropped");
+                                        true
+                                    }
+                                    Err(_) => false,
+                                });
                                 sequence_id += 1;
                             }
-                            Err(e) => {
-                                eprintln!("Failed to convert packet to frame: {}", e);
+                            Some(Err(e)) => {
+                                tracing::warn!("Failed to convert packet to frame: {}", e);
                             }
+                            None => {}
                         }
 
                         // Return buffer to device
                         if let Err(e) = channels.empty_tx.try_send(packet) {
-                            eprintln!("Failed to return buffer to device: {}", e);
+                            tracing::warn!("Failed to return buffer to device: {}", e);
                         }
                     }
                     Err(crossbeam_channel::TryRecvError::Empty) => {
+                        if draining {
+                            break;
+                        }
                         // No data available, yield
-                        tokio::task::yield_now().await;
+                        if !dedicated {
+                            tokio::task::yield_now().await;
+                        }
                     }
                     Err(crossbeam_channel::TryRecvError::Disconnected) => {
-                        eprintln!("Device {} disconnected", device_id);
+                        if !draining {
+                            tracing::error!("Device disconnected");
+                            if let Some(log) = &event_log {
+                                log.record(LoggedEvent::new(EventKind::Error, "Device disconnected").device(device_id.as_str()));
+                            }
+                        }
                         break;
                     }
                 }
             }
 
             Ok(())
-        });
+        }
+        .instrument(span);
 
+        let handle = match &self.realtime {
+            Some(realtime) => {
+                let priority = self.pipeline.as_ref().map(|p| p.priority()).unwrap_or(Priority::Critical);
+                let core = realtime.core(self.reader_handles.len());
+                spawn_realtime(thread_name, priority, core, task)
+            }
+            None => tokio::spawn(task),
+        };
         self.reader_handles.push(handle);
     }
 }
 
+/// Call `send` for each subscriber of `device_id`, removing those for which it returns false
+fn route<T>(routes: &Routes<T>, device_id: &str, send: impl FnMut(&T) -> bool) {
+    let mut routes = routes.write().unwrap_or_else(|p| p.into_inner());
+    if let Some(subscribers) = routes.get_mut(device_id) {
+        subscribers.retain(send);
+    }
+}
+
 // Implement Drop to ensure clean shutdown
 /// Note: This struct should be properly shut down via `shutdown()` before dropping.
 /// The Drop implementation only sends a shutdown signal but cannot await cleanup.
@@ -300,4 +591,10 @@ mod tests {
 
         assert_eq!(kernel.active_device_count(), 0);
     }
+
+    #[test]
+    fn test_kernel_can_be_shared_between_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<AudioKernelRuntime>();
+    }
 }
@@ -0,0 +1,180 @@
+//! End-to-end latency budget of a pipeline
+//!
+//! A sample reaching the end of a pipeline has waited in three places: the
+//! packet buffers of the device that captured it, re-blocking edges that
+//! hold samples until a block is complete, and the input channels of the
+//! nodes along the way. `LatencyBudget` adds these up along the slowest
+//! path from a source to a sink, with channels counted at full depth, and
+//! separately reports how much of that depth is queued right now.
+
+use crate::hal::DeviceConfig;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Sample rate assumed for sources that do not set one
+const DEFAULT_SAMPLE_RATE: f64 = 48000.0;
+
+/// Packet buffering of a device feeding a pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct DeviceBuffer {
+    /// Samples per packet
+    pub frame_size: usize,
+    /// Packets cycled between the driver and the engine
+    pub buffer_count: usize,
+    pub sample_rate: f64,
+}
+
+impl DeviceBuffer {
+    /// Time the packet buffers hold, in milliseconds
+    pub fn latency_ms(&self) -> f64 {
+        (self.frame_size * self.buffer_count) as f64 / self.sample_rate * 1000.0
+    }
+}
+
+impl From<&DeviceConfig> for DeviceBuffer {
+    fn from(config: &DeviceConfig) -> Self {
+        Self {
+            frame_size: config.buffer_size,
+            buffer_count: config.buffer_count.max(1),
+            sample_rate: config.sample_rate as f64,
+        }
+    }
+}
+
+/// Latency of a pipeline's slowest source-to-sink path, by contributor
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LatencyBudget {
+    /// Node ids along the path, source first
+    pub path: Vec<String>,
+    /// Samples per frame leaving the source
+    pub frame_size: usize,
+    pub sample_rate: f64,
+    /// Device packet buffers, or the one frame a source without a device generates
+    pub device_ms: f64,
+    /// Samples held by re-blocking edges until their blocks are complete
+    pub block_ms: f64,
+    /// Frames the input channels along the path hold when full
+    pub channel_ms: f64,
+    /// Frames queued on those channels at the time of the report
+    pub queued_ms: f64,
+    /// Worst case: device, block and full channel latency
+    pub total_ms: f64,
+}
+
+/// Where frames enter a pipeline
+pub(crate) struct LatencySource {
+    pub frame_size: usize,
+    pub sample_rate: f64,
+    pub device_ms: f64,
+}
+
+impl LatencySource {
+    /// Source of `frame_size`-sample frames, held in `buffers` packets before
+    /// they reach the pipeline
+    pub fn new(frame_size: usize, sample_rate: Option<f64>, buffers: usize) -> Self {
+        let sample_rate = sample_rate.filter(|r| *r > 0.0).unwrap_or(DEFAULT_SAMPLE_RATE);
+        Self {
+            frame_size,
+            sample_rate,
+            device_ms: (frame_size * buffers) as f64 / sample_rate * 1000.0,
+        }
+    }
+}
+
+impl From<DeviceBuffer> for LatencySource {
+    fn from(buffer: DeviceBuffer) -> Self {
+        Self {
+            frame_size: buffer.frame_size,
+            sample_rate: buffer.sample_rate,
+            device_ms: buffer.latency_ms(),
+        }
+    }
+}
+
+/// An edge as the budget sees it
+pub(crate) struct LatencyEdge<'a> {
+    pub from: &'a str,
+    pub to: &'a str,
+    pub block_size: Option<usize>,
+    pub decimation: usize,
+}
+
+/// The parts of a pipeline that add latency
+pub(crate) struct LatencyGraph<'a> {
+    pub sources: Vec<(&'a str, LatencySource)>,
+    pub edges: Vec<LatencyEdge<'a>>,
+    /// Frames each node's input channel holds
+    pub channel_capacity: usize,
+    /// Frames currently queued for each node
+    pub queued: HashMap<String, usize>,
+}
+
+/// State of a path being followed
+#[derive(Clone)]
+struct Walk<'a> {
+    path: Vec<&'a str>,
+    /// Samples per frame and their rate at the current node
+    frame_len: f64,
+    rate: f64,
+    block_ms: f64,
+    channel_ms: f64,
+    queued_ms: f64,
+}
+
+impl<'a> LatencyGraph<'a> {
+    /// Budget of the path with the largest total; an empty budget if the
+    /// graph has no nodes
+    pub fn slowest_path(&self) -> LatencyBudget {
+        let mut slowest = LatencyBudget::default();
+        for (id, source) in &self.sources {
+            let walk = Walk {
+                path: Vec::new(),
+                frame_len: source.frame_size as f64,
+                rate: source.sample_rate,
+                block_ms: 0.0,
+                channel_ms: 0.0,
+                queued_ms: 0.0,
+            };
+            self.visit(id, walk, source, &mut slowest);
+        }
+        slowest
+    }
+
+    fn visit(&self, node: &'a str, mut walk: Walk<'a>, source: &LatencySource, slowest: &mut LatencyBudget) {
+        let frame_ms = walk.frame_len / walk.rate * 1000.0;
+        walk.channel_ms += self.channel_capacity as f64 * frame_ms;
+        walk.queued_ms += self.queued.get(node).copied().unwrap_or(0) as f64 * frame_ms;
+        walk.path.push(node);
+
+        let mut sink = true;
+        for edge in self.edges.iter().filter(|e| e.from == node && !walk.path.contains(&e.to)) {
+            sink = false;
+            let mut next = walk.clone();
+            let decimation = edge.decimation.max(1) as f64;
+            next.rate /= decimation;
+            next.frame_len /= decimation;
+            if let Some(block_size) = edge.block_size {
+                next.block_ms += (block_size as f64 - next.frame_len).max(0.0) / next.rate * 1000.0;
+                next.frame_len = block_size as f64;
+            }
+            self.visit(edge.to, next, source, slowest);
+        }
+        if !sink {
+            return;
+        }
+
+        let total_ms = source.device_ms + walk.block_ms + walk.channel_ms;
+        if slowest.path.is_empty() || total_ms > slowest.total_ms {
+            *slowest = LatencyBudget {
+                path: walk.path.iter().map(|id| id.to_string()).collect(),
+                frame_size: source.frame_size,
+                sample_rate: source.sample_rate,
+                device_ms: source.device_ms,
+                block_ms: walk.block_ms,
+                channel_ms: walk.channel_ms,
+                queued_ms: walk.queued_ms,
+                total_ms,
+            };
+        }
+    }
+}
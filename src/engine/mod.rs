@@ -5,11 +5,33 @@ pub mod priority;
 pub mod scheduler;
 pub mod state;
 pub mod kernel;
+pub mod graph_document;
+pub mod subgraph;
+pub mod drift;
+pub mod realtime;
+pub mod backpressure;
+pub mod sequence;
+pub mod session;
+pub mod validate;
+pub mod latency;
+#[cfg(feature = "remote")]
+pub mod remote;
 
 pub use pipeline::Pipeline;
-pub use async_pipeline::AsyncPipeline;
+pub use async_pipeline::{AsyncPipeline, DeviceInput};
 pub use pipeline_pool::PipelinePool;
 pub use priority::Priority;
 pub use scheduler::PipelineScheduler;
 pub use state::PipelineState;
 pub use kernel::{AudioKernelRuntime, KernelStatus};
+pub use drift::{DriftCompensator, DriftEstimator};
+pub use graph_document::{GraphDocument, GraphEdge, GraphNode, NodePosition};
+pub use backpressure::BackpressurePolicy;
+pub use realtime::{spawn_realtime, RealtimeConfig};
+pub use sequence::{SequenceCheck, SequenceTracker};
+pub use session::{CaptureSession, SessionEvent, SessionPlan, SessionTarget};
+pub use subgraph::{expand_subgraphs, PortTarget, SubgraphDefinition};
+pub use validate::{validate_graph, FieldError};
+pub use latency::{DeviceBuffer, LatencyBudget};
+#[cfg(feature = "remote")]
+pub use remote::{RemoteControl, RemoteError, RemoteServer};
@@ -1,17 +1,20 @@
+use super::async_pipeline::{instantiate_node, node_metadata};
 use crate::core::{DataFrame, ProcessingNode};
-use crate::nodes::{GainNode, AudioSourceNode, DebugSinkNode};
 use anyhow::{Result, anyhow};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 
 pub struct Pipeline {
     nodes: HashMap<String, Box<dyn ProcessingNode>>,
     connections: Vec<(String, String)>,
+    /// Node ids in topological order, ties broken by declaration order
+    execution_order: Vec<String>,
 }
 
 impl Pipeline {
     pub async fn from_json(config: Value) -> Result<Self> {
         let mut nodes: HashMap<String, Box<dyn ProcessingNode>> = HashMap::new();
+        let mut node_ids = Vec::new();
         let mut connections = Vec::new();
 
         // Parse nodes
@@ -26,15 +29,13 @@ impl Pipeline {
                     .ok_or(anyhow!("Node missing type"))?;
                 let node_cfg = node_config["config"].clone();
 
-                let mut node: Box<dyn ProcessingNode> = match node_type {
-                    "AudioSourceNode" => Box::new(AudioSourceNode::default()),
-                    "GainNode" | "Gain" => Box::new(GainNode::default()),
-                    "DebugSinkNode" | "Print" => Box::new(DebugSinkNode::default()),
-                    _ => return Err(anyhow!("Unknown node type: {}", node_type)),
-                };
+                let mut node = instantiate_node(node_type, node_metadata(node_type).as_ref())?;
 
                 node.on_create(node_cfg).await?;
-                nodes.insert(id, node);
+                if nodes.insert(id.clone(), node).is_some() {
+                    return Err(anyhow!("Duplicate node id: {}", id));
+                }
+                node_ids.push(id);
             }
         }
 
@@ -49,52 +50,110 @@ impl Pipeline {
                     .as_str()
                     .ok_or(anyhow!("Connection missing to"))?
                     .to_string();
+                for id in [&from, &to] {
+                    if !nodes.contains_key(id) {
+                        return Err(anyhow!("Connection {} -> {}: node '{}' not found", from, to, id));
+                    }
+                }
                 connections.push((from, to));
             }
         }
 
-        Ok(Self { nodes, connections })
+        let execution_order = topological_order(&node_ids, &connections)?;
+        Ok(Self { nodes, connections, execution_order })
     }
 
-    pub async fn execute_once(&mut self) -> Result<()> {
-        // Simple linear execution for now (no parallelism)
-        // Start with empty frame
-        let mut current_frame = DataFrame::new(0, 0);
+    /// Mutable access to nodes, e.g. to wrap or inject resources before running
+    pub fn nodes_mut(&mut self) -> &mut HashMap<String, Box<dyn ProcessingNode>> {
+        &mut self.nodes
+    }
 
-        // Build execution order (simple topological sort for linear pipeline)
-        let mut executed = std::collections::HashSet::new();
-        let mut execution_order = Vec::new();
+    /// Node ids in the order `execute_once` runs them
+    pub fn execution_order(&self) -> &[String] {
+        &self.execution_order
+    }
 
-        // Find source node (no incoming connections)
-        for id in self.nodes.keys() {
-            let has_incoming = self.connections.iter().any(|(_, to)| to == id);
-            if !has_incoming {
-                execution_order.push(id.clone());
-                executed.insert(id.clone());
-                break;
-            }
-        }
+    /// Run every node once, in topological order
+    ///
+    /// Sources process an empty frame. Each node processes the frames of its
+    /// inbound connections one at a time, in connection order, and every
+    /// output goes to each of its downstream nodes.
+    pub async fn execute_once(&mut self) -> Result<()> {
+        let mut inputs: HashMap<&str, Vec<DataFrame>> = HashMap::new();
 
-        // Follow connections to build order
-        while execution_order.len() < self.nodes.len() {
-            let last = execution_order.last().unwrap();
-            if let Some((_, next)) = self.connections.iter().find(|(from, _)| from == last) {
-                if !executed.contains(next) {
-                    execution_order.push(next.clone());
-                    executed.insert(next.clone());
+        for node_id in &self.execution_order {
+            let frames = match inputs.remove(node_id.as_str()) {
+                Some(frames) => frames,
+                None if self.connections.iter().any(|(_, to)| to == node_id) => continue,
+                None => vec![DataFrame::new(0, 0)],
+            };
+            let node = self.nodes.get_mut(node_id).unwrap();
+            for frame in frames {
+                let output = node.process(frame).await?;
+                for (_, to) in self.connections.iter().filter(|(from, _)| from == node_id) {
+                    inputs.entry(to.as_str()).or_default().push(output.clone());
                 }
-            } else {
-                break;
             }
         }
 
-        // Execute in order
-        for node_id in execution_order {
-            if let Some(node) = self.nodes.get_mut(&node_id) {
-                current_frame = node.process(current_frame).await?;
+        Ok(())
+    }
+}
+
+/// Order nodes so each runs after everything upstream of it (Kahn's algorithm)
+///
+/// Ready nodes run in declaration order, so the result is deterministic.
+/// A cycle is an error naming the nodes on it.
+fn topological_order(node_ids: &[String], connections: &[(String, String)]) -> Result<Vec<String>> {
+    let mut in_degree: HashMap<&str, usize> = node_ids.iter().map(|id| (id.as_str(), 0)).collect();
+    for (_, to) in connections {
+        *in_degree.get_mut(to.as_str()).unwrap() += 1;
+    }
+
+    let mut ready: VecDeque<&str> = node_ids
+        .iter()
+        .map(|id| id.as_str())
+        .filter(|id| in_degree[id] == 0)
+        .collect();
+    let mut order = Vec::with_capacity(node_ids.len());
+    while let Some(id) = ready.pop_front() {
+        order.push(id.to_string());
+        for (_, to) in connections.iter().filter(|(from, _)| from == id) {
+            let degree = in_degree.get_mut(to.as_str()).unwrap();
+            *degree -= 1;
+            if *degree == 0 {
+                ready.push_back(to);
             }
         }
+    }
+    if order.len() == node_ids.len() {
+        return Ok(order);
+    }
 
-        Ok(())
+    // Unordered nodes are on a cycle or downstream of one; peel off the
+    // downstream ones, which have no outbound edge left inside the set
+    let mut involved: HashSet<&str> = node_ids
+        .iter()
+        .map(|id| id.as_str())
+        .filter(|id| in_degree[id] > 0)
+        .collect();
+    loop {
+        let leaves: Vec<&str> = involved
+            .iter()
+            .copied()
+            .filter(|id| !connections.iter().any(|(from, to)| from == id && involved.contains(to.as_str())))
+            .collect();
+        if leaves.is_empty() {
+            break;
+        }
+        for leaf in leaves {
+            involved.remove(leaf);
+        }
     }
+    let cycle: Vec<&str> = node_ids
+        .iter()
+        .map(|id| id.as_str())
+        .filter(|id| involved.contains(id))
+        .collect();
+    Err(anyhow!("Pipeline contains a cycle through nodes: {}", cycle.join(", ")))
 }
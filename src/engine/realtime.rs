@@ -0,0 +1,249 @@
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use std::future::Future;
+use tokio::task::JoinHandle;
+use crate::engine::Priority;
+
+/// Run latency-sensitive tasks on dedicated OS threads with elevated priority
+///
+/// Enabled with `pipeline_config.realtime` (`true`, or `{"cores": [2, 3]}` to
+/// pin threads to cores, assigned round-robin), or on the kernel with
+/// `AudioKernelRuntime::set_realtime` for device readers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RealtimeConfig {
+    /// Cores to pin realtime threads to; empty leaves them unpinned
+    pub cores: Vec<usize>,
+}
+
+impl RealtimeConfig {
+    /// Parse the `realtime` setting; absent, `null` or `false` disable it
+    pub fn from_json(value: &Value) -> Result<Option<Self>> {
+        match value {
+            Value::Null | Value::Bool(false) => Ok(None),
+            Value::Bool(true) => Ok(Some(Self::default())),
+            Value::Object(_) => {
+                let cores = match &value["cores"] {
+                    Value::Null => Vec::new(),
+                    Value::Array(cores) => cores
+                        .iter()
+                        .map(|c| c.as_u64().map(|c| c as usize).ok_or_else(|| anyhow!("Realtime cores must be core indices")))
+                        .collect::<Result<_>>()?,
+                    _ => return Err(anyhow!("Realtime cores must be an array")),
+                };
+                Ok(Some(Self { cores }))
+            }
+            _ => Err(anyhow!("realtime must be a boolean or an object")),
+        }
+    }
+
+    /// Core for the `index`-th realtime thread, if threads are pinned
+    pub fn core(&self, index: usize) -> Option<usize> {
+        (!self.cores.is_empty()).then(|| self.cores[index % self.cores.len()])
+    }
+}
+
+/// Run `task` on its own OS thread, with a single-threaded runtime
+///
+/// The thread is raised to the scheduling class for `priority` (SCHED_FIFO
+/// on Linux, the time-constraint audio class on macOS, MMCSS "Pro Audio" on
+/// Windows) and pinned to `core` if given. If the OS refuses, for lack of
+/// privileges say, the thread warns and runs at normal priority. Tasks the
+/// future spawns run on the same thread.
+pub fn spawn_realtime<F>(name: impl Into<String>, priority: Priority, core: Option<usize>, task: F) -> JoinHandle<Result<()>>
+where
+    F: Future<Output = Result<()>> + Send + 'static,
+{
+    let name = name.into();
+    let (result_tx, result_rx) = tokio::sync::oneshot::channel();
+    let thread_name = name.clone();
+    // The thread logs within the caller's span, such as the node it runs
+    let span = tracing::Span::current();
+    let spawned = std::thread::Builder::new().name(name.clone()).spawn(move || {
+        let _span = span.enter();
+        if let Some(core) = core {
+            if let Err(e) = platform::pin_to_core(core) {
+                tracing::warn!(thread = %thread_name, "Could not pin thread to core {}: {}", core, e);
+            }
+        }
+        if let Err(e) = platform::elevate(priority) {
+            tracing::warn!(thread = %thread_name, "Thread runs at normal priority: {}", e);
+        }
+        let result = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| anyhow!("Failed to build runtime for thread '{}': {}", thread_name, e))
+            .and_then(|runtime| runtime.block_on(task));
+        let _ = result_tx.send(result);
+    });
+
+    tokio::spawn(async move {
+        spawned.map_err(|e| anyhow!("Failed to spawn thread '{}': {}", name, e))?;
+        result_rx
+            .await
+            .unwrap_or_else(|_| Err(anyhow!("Thread '{}' panicked", name)))
+    })
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::Priority;
+    use anyhow::{anyhow, Result};
+
+    /// SCHED_FIFO priority (1-99) for each pipeline priority
+    fn fifo_priority(priority: Priority) -> libc::c_int {
+        match priority {
+            Priority::Critical => 80,
+            Priority::High => 60,
+            Priority::Normal => 40,
+            Priority::Low => 20,
+        }
+    }
+
+    pub fn elevate(priority: Priority) -> Result<()> {
+        let param = libc::sched_param { sched_priority: fifo_priority(priority) };
+        // SAFETY: sets the policy of the calling thread; `param` outlives the call
+        let code = unsafe { libc::pthread_setschedparam(libc::pthread_self(), libc::SCHED_FIFO, &param) };
+        match code {
+            0 => Ok(()),
+            code => Err(anyhow!("SCHED_FIFO refused: {}", std::io::Error::from_raw_os_error(code))),
+        }
+    }
+
+    pub fn pin_to_core(core: usize) -> Result<()> {
+        // SAFETY: the set is zeroed before use and only read by the call; pid 0 is the calling thread
+        unsafe {
+            let mut set: libc::cpu_set_t = std::mem::zeroed();
+            libc::CPU_ZERO(&mut set);
+            libc::CPU_SET(core, &mut set);
+            if libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) != 0 {
+                return Err(std::io::Error::last_os_error().into());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::Priority;
+    use anyhow::{anyhow, Result};
+
+    /// Critical and High pipelines get the time-constraint (audio) class,
+    /// with a period of their target latency; the others a QoS class
+    pub fn elevate(priority: Priority) -> Result<()> {
+        if priority < Priority::High {
+            let class = match priority {
+                Priority::Normal => libc::qos_class_t::QOS_CLASS_USER_INTERACTIVE,
+                _ => libc::qos_class_t::QOS_CLASS_USER_INITIATED,
+            };
+            // SAFETY: applies to the calling thread only
+            let code = unsafe { libc::pthread_set_qos_class_self_np(class, 0) };
+            return match code {
+                0 => Ok(()),
+                code => Err(anyhow!("QoS class refused: {}", std::io::Error::from_raw_os_error(code))),
+            };
+        }
+
+        let mut timebase = libc::mach_timebase_info { numer: 0, denom: 0 };
+        // SAFETY: `timebase` is a valid out pointer for the call
+        #[allow(deprecated)]
+        unsafe {
+            libc::mach_timebase_info(&mut timebase);
+        }
+        let period_ns = priority.target_latency_ms() * 1_000_000;
+        let to_abs = |ns: u64| (ns * timebase.denom as u64 / timebase.numer.max(1) as u64) as u32;
+        let mut policy = libc::thread_time_constraint_policy {
+            period: to_abs(period_ns),
+            computation: to_abs(period_ns / 4),
+            constraint: to_abs(period_ns),
+            preemptible: 1,
+        };
+        // SAFETY: `policy` matches the flavor and count passed, and outlives the call
+        let code = unsafe {
+            libc::thread_policy_set(
+                libc::pthread_mach_thread_np(libc::pthread_self()),
+                libc::THREAD_TIME_CONSTRAINT_POLICY as libc::thread_policy_flavor_t,
+                &mut policy as *mut _ as libc::thread_policy_t,
+                libc::THREAD_TIME_CONSTRAINT_POLICY_COUNT,
+            )
+        };
+        match code {
+            0 => Ok(()),
+            code => Err(anyhow!("time-constraint policy refused (kern_return {})", code)),
+        }
+    }
+
+    pub fn pin_to_core(_core: usize) -> Result<()> {
+        Err(anyhow!("macOS does not support pinning threads to cores"))
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::Priority;
+    use anyhow::{anyhow, Result};
+    use std::ffi::c_void;
+
+    #[link(name = "avrt")]
+    extern "system" {
+        fn AvSetMmThreadCharacteristicsW(task_name: *const u16, task_index: *mut u32) -> *mut c_void;
+        fn AvSetMmThreadPriority(handle: *mut c_void, priority: i32) -> i32;
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetCurrentThread() -> *mut c_void;
+        fn SetThreadAffinityMask(thread: *mut c_void, mask: usize) -> usize;
+    }
+
+    /// MMCSS (AVRT_PRIORITY) level within the "Pro Audio" task
+    fn avrt_priority(priority: Priority) -> i32 {
+        match priority {
+            Priority::Critical => 2,
+            Priority::High => 1,
+            Priority::Normal => 0,
+            Priority::Low => -1,
+        }
+    }
+
+    pub fn elevate(priority: Priority) -> Result<()> {
+        let task: Vec<u16> = "Pro Audio".encode_utf16().chain(std::iter::once(0)).collect();
+        let mut index = 0u32;
+        // SAFETY: `task` is NUL-terminated and both pointers outlive the calls
+        unsafe {
+            let handle = AvSetMmThreadCharacteristicsW(task.as_ptr(), &mut index);
+            if handle.is_null() {
+                return Err(anyhow!("MMCSS refused: {}", std::io::Error::last_os_error()));
+            }
+            if AvSetMmThreadPriority(handle, avrt_priority(priority)) == 0 {
+                return Err(anyhow!("MMCSS priority refused: {}", std::io::Error::last_os_error()));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn pin_to_core(core: usize) -> Result<()> {
+        if core >= usize::BITS as usize {
+            return Err(anyhow!("core {} is outside the thread's processor group", core));
+        }
+        // SAFETY: applies to the calling thread's pseudo handle
+        if unsafe { SetThreadAffinityMask(GetCurrentThread(), 1 << core) } == 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    use super::Priority;
+    use anyhow::{anyhow, Result};
+
+    pub fn elevate(_priority: Priority) -> Result<()> {
+        Err(anyhow!("thread priorities are not supported on this platform"))
+    }
+
+    pub fn pin_to_core(_core: usize) -> Result<()> {
+        Err(anyhow!("core pinning is not supported on this platform"))
+    }
+}
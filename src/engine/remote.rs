@@ -0,0 +1,357 @@
+use super::AsyncPipeline;
+use crate::core::{AudiotabError, DataFrame};
+use crate::hal::{DeviceInfo, DeviceManager};
+use crate::observability::NodeThroughput;
+use crate::registry::PresetStore;
+use anyhow::{Context, Result};
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, patch, post, put};
+use axum::{Json, Router};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::net::{TcpListener, ToSocketAddrs};
+use tokio::task::JoinHandle;
+
+/// Why a remote command failed
+#[derive(Debug)]
+pub enum RemoteError {
+    /// No pipeline is deployed under this ID (HTTP 404)
+    NotFound(String),
+    /// The command was rejected or failed (HTTP 400)
+    Failed(AudiotabError),
+}
+
+impl std::fmt::Display for RemoteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RemoteError::NotFound(id) => write!(f, "Pipeline {} not found", id),
+            RemoteError::Failed(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for RemoteError {}
+
+impl From<AudiotabError> for RemoteError {
+    fn from(e: AudiotabError) -> Self {
+        RemoteError::Failed(e)
+    }
+}
+
+impl IntoResponse for RemoteError {
+    fn into_response(self) -> Response {
+        let (status, kind) = match &self {
+            RemoteError::NotFound(_) => (StatusCode::NOT_FOUND, "not_found"),
+            RemoteError::Failed(e) => (StatusCode::BAD_REQUEST, e.kind()),
+        };
+        (status, Json(json!({ "error": self.to_string(), "kind": kind }))).into_response()
+    }
+}
+
+/// A deployed pipeline and its state
+#[derive(Debug, Clone, Serialize)]
+pub struct PipelineStatus {
+    pub id: String,
+    pub state: String,
+}
+
+/// A pipeline and the devices it holds while running
+struct Deployment {
+    pipeline: AsyncPipeline,
+    /// (profile, node) leases taken by `start`
+    leases: Vec<(String, String)>,
+}
+
+/// Pipelines driven by remote clients, by ID
+///
+/// Pipelines are deployed in the backend JSON format read by
+/// `AsyncPipeline::from_json`. Starting one attaches the devices its source
+/// nodes name (when a device manager is set) and stopping it releases them.
+pub struct RemoteControl {
+    pipelines: Mutex<HashMap<String, Arc<tokio::sync::Mutex<Deployment>>>>,
+    devices: Option<Arc<DeviceManager>>,
+    presets: PresetStore,
+}
+
+impl Default for RemoteControl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RemoteControl {
+    pub fn new() -> Self {
+        Self {
+            pipelines: Mutex::new(HashMap::new()),
+            devices: None,
+            presets: PresetStore::with_builtins(),
+        }
+    }
+
+    /// Device manager used for device listing and by device-bound source nodes
+    pub fn with_devices(mut self, manager: Arc<DeviceManager>) -> Self {
+        self.devices = Some(manager);
+        self
+    }
+
+    /// Presets that deployed pipelines may reference (built-in ones by default)
+    pub fn with_presets(mut self, presets: PresetStore) -> Self {
+        self.presets = presets;
+        self
+    }
+
+    /// The lock is released before returning, so callers can await on the deployment
+    fn deployment(&self, id: &str) -> Result<Arc<tokio::sync::Mutex<Deployment>>, RemoteError> {
+        self.pipelines
+            .lock()
+            .unwrap()
+            .get(id)
+            .cloned()
+            .ok_or_else(|| RemoteError::NotFound(id.to_string()))
+    }
+
+    /// Build a pipeline and store it under `id`, replacing the one there
+    pub async fn deploy(&self, id: &str, config: Value) -> Result<(), RemoteError> {
+        let pipeline = AsyncPipeline::from_json_with_presets(config, &self.presets)
+            .await
+            .map_err(|e| e.context("Pipeline creation failed"))?;
+        let deployment = Arc::new(tokio::sync::Mutex::new(Deployment { pipeline, leases: Vec::new() }));
+
+        let replaced = self.pipelines.lock().unwrap().insert(id.to_string(), deployment);
+        if let Some(old) = replaced {
+            self.shut_down(&mut *old.lock().await)
+                .await
+                .map_err(|e| e.context(format!("Replaced pipeline {} failed to stop cleanly", id)))?;
+        }
+        Ok(())
+    }
+
+    /// Stop a pipeline and forget it
+    pub async fn delete(&self, id: &str) -> Result<(), RemoteError> {
+        let deployment = self
+            .pipelines
+            .lock()
+            .unwrap()
+            .remove(id)
+            .ok_or_else(|| RemoteError::NotFound(id.to_string()))?;
+        self.shut_down(&mut *deployment.lock().await)
+            .await
+            .map_err(|e| e.context(format!("Pipeline {} failed to stop cleanly", id)))?;
+        Ok(())
+    }
+
+    /// Attach the pipeline's devices and start it
+    pub async fn start(&self, id: &str) -> Result<(), RemoteError> {
+        let deployment = self.deployment(id)?;
+        let mut deployment = deployment.lock().await;
+        if let Some(manager) = &self.devices {
+            deployment.leases = deployment.pipeline.attach_devices(manager).await?;
+        }
+        if let Err(e) = deployment.pipeline.start().await {
+            let _ = self.release_devices(&mut deployment).await;
+            return Err(e.context("Failed to start pipeline").into());
+        }
+        Ok(())
+    }
+
+    /// Drain and stop the pipeline, then release its devices
+    pub async fn stop(&self, id: &str) -> Result<(), RemoteError> {
+        let deployment = self.deployment(id)?;
+        self.shut_down(&mut *deployment.lock().await)
+            .await
+            .map_err(|e| e.context("Failed to stop pipeline"))?;
+        Ok(())
+    }
+
+    /// Send a frame marked `manual_trigger`, firing TriggerSourceNodes in manual mode
+    pub async fn trigger(&self, id: &str) -> Result<(), RemoteError> {
+        let deployment = self.deployment(id)?;
+        let mut frame = DataFrame::new(0, 0);
+        frame.metadata.insert("manual_trigger", true);
+        deployment
+            .lock()
+            .await
+            .pipeline
+            .trigger(frame)
+            .await
+            .map_err(|e| e.context("Failed to trigger pipeline"))?;
+        Ok(())
+    }
+
+    /// Merge `params` into a node's config (see `AsyncPipeline::update_node_params`)
+    pub async fn update_node_params(&self, id: &str, node_id: &str, params: Value) -> Result<(), RemoteError> {
+        let deployment = self.deployment(id)?;
+        deployment.lock().await.pipeline.update_node_params(node_id, params).await?;
+        Ok(())
+    }
+
+    /// States of all deployed pipelines, ordered by ID
+    pub async fn statuses(&self) -> Vec<PipelineStatus> {
+        let deployments: Vec<_> = self
+            .pipelines
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(id, deployment)| (id.clone(), deployment.clone()))
+            .collect();
+
+        let mut statuses = Vec::with_capacity(deployments.len());
+        for (id, deployment) in deployments {
+            let state = deployment.lock().await.pipeline.state().name().to_string();
+            statuses.push(PipelineStatus { id, state });
+        }
+        statuses.sort_by(|a, b| a.id.cmp(&b.id));
+        statuses
+    }
+
+    /// Per-node latency, throughput and error counts
+    pub async fn metrics(&self, id: &str) -> Result<Vec<NodeThroughput>, RemoteError> {
+        let deployment = self.deployment(id)?;
+        let deployment = deployment.lock().await;
+        let monitor = deployment
+            .pipeline
+            .get_monitor()
+            .ok_or_else(|| AudiotabError::state(format!("Pipeline {} is starting; metrics are not available yet", id)))?;
+        Ok(monitor.node_metrics())
+    }
+
+    /// Devices found by the device manager's drivers
+    pub async fn discover_devices(&self) -> Result<Vec<DeviceInfo>, RemoteError> {
+        let manager = self
+            .devices
+            .as_ref()
+            .ok_or_else(|| AudiotabError::config("No device manager configured"))?;
+        Ok(manager.discover_all().await.map_err(|e| e.context("Device discovery failed"))?)
+    }
+
+    async fn shut_down(&self, deployment: &mut Deployment) -> Result<(), AudiotabError> {
+        let stopped = deployment.pipeline.stop().await;
+        let released = self.release_devices(deployment).await;
+        stopped.and(released)
+    }
+
+    async fn release_devices(&self, deployment: &mut Deployment) -> Result<(), AudiotabError> {
+        let Some(manager) = &self.devices else {
+            return Ok(());
+        };
+        let mut result = Ok(());
+        for (profile_id, node_id) in deployment.leases.drain(..) {
+            if let Err(e) = manager.release_device(&profile_id, &node_id).await {
+                result = result.and(Err(e));
+            }
+        }
+        result
+    }
+}
+
+/// HTTP routes for `control`
+///
+/// | Method | Path | Body | Action |
+/// |---|---|---|---|
+/// | GET | `/pipelines` | | States of all pipelines |
+/// | PUT | `/pipelines/{id}` | pipeline JSON | Deploy, replacing any pipeline under `id` |
+/// | DELETE | `/pipelines/{id}` | | Stop and remove |
+/// | POST | `/pipelines/{id}/start`, `/stop`, `/trigger` | | Control the pipeline |
+/// | GET | `/pipelines/{id}/metrics` | | Per-node metrics |
+/// | PATCH | `/pipelines/{id}/nodes/{node}` | parameter object | Update node parameters |
+/// | GET | `/devices` | | Discover devices |
+///
+/// Failures answer 404 for unknown pipelines and 400 otherwise, with a
+/// `{"error": "..."}` body.
+pub fn router(control: Arc<RemoteControl>) -> Router {
+    Router::new()
+        .route("/pipelines", get(list_pipelines))
+        .route("/pipelines/:id", put(deploy_pipeline).delete(delete_pipeline))
+        .route("/pipelines/:id/start", post(start_pipeline))
+        .route("/pipelines/:id/stop", post(stop_pipeline))
+        .route("/pipelines/:id/trigger", post(trigger_pipeline))
+        .route("/pipelines/:id/metrics", get(pipeline_metrics))
+        .route("/pipelines/:id/nodes/:node", patch(update_node_params))
+        .route("/devices", get(discover_devices))
+        .with_state(control)
+}
+
+type Control = State<Arc<RemoteControl>>;
+
+async fn list_pipelines(State(control): Control) -> Json<Vec<PipelineStatus>> {
+    Json(control.statuses().await)
+}
+
+async fn deploy_pipeline(State(control): Control, Path(id): Path<String>, Json(config): Json<Value>) -> Result<StatusCode, RemoteError> {
+    control.deploy(&id, config).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn delete_pipeline(State(control): Control, Path(id): Path<String>) -> Result<StatusCode, RemoteError> {
+    control.delete(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn start_pipeline(State(control): Control, Path(id): Path<String>) -> Result<StatusCode, RemoteError> {
+    control.start(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn stop_pipeline(State(control): Control, Path(id): Path<String>) -> Result<StatusCode, RemoteError> {
+    control.stop(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn trigger_pipeline(State(control): Control, Path(id): Path<String>) -> Result<StatusCode, RemoteError> {
+    control.trigger(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn pipeline_metrics(State(control): Control, Path(id): Path<String>) -> Result<Json<Vec<NodeThroughput>>, RemoteError> {
+    Ok(Json(control.metrics(&id).await?))
+}
+
+async fn update_node_params(
+    State(control): Control,
+    Path((id, node)): Path<(String, String)>,
+    Json(params): Json<Value>,
+) -> Result<StatusCode, RemoteError> {
+    control.update_node_params(&id, &node, params).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn discover_devices(State(control): Control) -> Result<Json<Vec<DeviceInfo>>, RemoteError> {
+    Ok(Json(control.discover_devices().await?))
+}
+
+/// HTTP server for remote control of the engine, e.g. from lab automation scripts
+///
+/// See `router` for the API. The server stops when dropped; pipelines live
+/// in the `RemoteControl` and outlast it.
+pub struct RemoteServer {
+    local_addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl RemoteServer {
+    /// Listen on `addr` and serve `control`
+    pub async fn bind(addr: impl ToSocketAddrs, control: Arc<RemoteControl>) -> Result<Self> {
+        let listener = TcpListener::bind(addr).await.context("Failed to bind remote control server")?;
+        let local_addr = listener.local_addr()?;
+        let app = router(control);
+        let task = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+        Ok(Self { local_addr, task })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+impl Drop for RemoteServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
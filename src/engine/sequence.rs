@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// What a frame's sequence id says about the frames before it on its edge
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceCheck {
+    /// Next in sequence, the first seen, or a repeat of the previous id
+    /// (unsequenced sources such as manual triggers reuse one id)
+    InOrder,
+    /// `missing` frames were lost between the previous frame and this one
+    Gap { missing: u64 },
+    /// Earlier than the previous frame, e.g. from a restarted source;
+    /// the edge continues from this id
+    OutOfOrder,
+}
+
+/// Last sequence id seen on each edge, to notice dropped and reordered frames
+#[derive(Debug)]
+pub struct SequenceTracker<K> {
+    last: HashMap<K, u64>,
+}
+
+impl<K: Eq + Hash> SequenceTracker<K> {
+    pub fn new() -> Self {
+        Self { last: HashMap::new() }
+    }
+
+    /// Record a frame arriving on `edge` and compare it with the previous one
+    pub fn check(&mut self, edge: K, sequence_id: u64) -> SequenceCheck {
+        match self.last.insert(edge, sequence_id) {
+            Some(last) if sequence_id > last + 1 => SequenceCheck::Gap { missing: sequence_id - last - 1 },
+            Some(last) if sequence_id < last => SequenceCheck::OutOfOrder,
+            _ => SequenceCheck::InOrder,
+        }
+    }
+}
+
+impl<K: Eq + Hash> Default for SequenceTracker<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,208 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::{Mutex, Notify};
+use tokio::task::JoinHandle;
+use tracing::Instrument;
+use crate::engine::{AsyncPipeline, PipelineState};
+
+/// When a capture session starts and what ends it
+///
+/// The session ends at whichever limit is reached first; at least one of
+/// `duration_ms` and `max_triggers` must be given.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionPlan {
+    /// Wall-clock start in milliseconds since the Unix epoch; absent or
+    /// past starts at once
+    #[serde(default)]
+    pub start_at_ms: Option<u64>,
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+    /// Trigger events to capture, see `PipelineMonitor::triggers`
+    #[serde(default)]
+    pub max_triggers: Option<u64>,
+}
+
+impl SessionPlan {
+    pub fn validate(&self) -> Result<()> {
+        match (self.duration_ms, self.max_triggers) {
+            (None, None) => Err(anyhow!("A capture session needs a duration or a trigger count")),
+            (Some(0), _) => Err(anyhow!("Session duration must be positive")),
+            (_, Some(0)) => Err(anyhow!("Session trigger count must be positive")),
+            _ => Ok(()),
+        }
+    }
+
+    /// Time left until the planned start
+    fn delay(&self) -> Duration {
+        let Some(start_at_ms) = self.start_at_ms else { return Duration::ZERO };
+        let start = UNIX_EPOCH + Duration::from_millis(start_at_ms);
+        start.duration_since(SystemTime::now()).unwrap_or(Duration::ZERO)
+    }
+}
+
+/// Progress of a capture session
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SessionEvent {
+    /// Waiting for the planned start
+    Countdown { remaining_ms: u64 },
+    Started,
+    /// `progress` runs from 0.0 to 1.0 towards the nearest limit
+    Progress { elapsed_ms: u64, triggers: u64, progress: f64 },
+    /// A limit was reached and the pipeline stopped
+    Completed { elapsed_ms: u64, triggers: u64 },
+    /// Cancelled before or during the capture
+    Cancelled { elapsed_ms: u64, triggers: u64 },
+    Failed { error: String },
+}
+
+/// What a capture session runs
+#[async_trait]
+pub trait SessionTarget: Send + 'static {
+    async fn start(&mut self) -> Result<()>;
+
+    /// Stop the capture, letting sinks finalize their output
+    async fn finish(&mut self) -> Result<()>;
+
+    /// Trigger events since the start
+    async fn triggers(&mut self) -> u64;
+}
+
+#[async_trait]
+impl SessionTarget for Arc<Mutex<AsyncPipeline>> {
+    async fn start(&mut self) -> Result<()> {
+        Ok(self.lock().await.start().await?)
+    }
+
+    async fn finish(&mut self) -> Result<()> {
+        let mut pipeline = self.lock().await;
+        if matches!(pipeline.state(), PipelineState::Running { .. }) {
+            Ok(pipeline.stop().await?)
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn triggers(&mut self) -> u64 {
+        self.lock().await.get_monitor().map_or(0, |monitor| monitor.triggers())
+    }
+}
+
+/// A scheduled capture running in the background
+///
+/// Waits for the plan's start time, starts the target, and finishes it once
+/// the duration or trigger count is reached. Countdown and progress events
+/// are reported every `tick`.
+pub struct CaptureSession {
+    cancel: Arc<Notify>,
+    task: JoinHandle<SessionEvent>,
+}
+
+impl CaptureSession {
+    pub fn spawn<T, F>(plan: SessionPlan, mut target: T, tick: Duration, on_event: F) -> Result<Self>
+    where
+        T: SessionTarget,
+        F: Fn(SessionEvent) + Send + Sync + 'static,
+    {
+        plan.validate()?;
+        let cancel = Arc::new(Notify::new());
+        let cancelled = cancel.clone();
+        let task = tokio::spawn(async move {
+            let outcome = run(&plan, &mut target, tick, &cancelled, &on_event).await;
+            on_event(outcome.clone());
+            outcome
+        }.in_current_span());
+        Ok(Self { cancel, task })
+    }
+
+    /// Stop the session; a running capture is finished as on completion
+    pub fn cancel(&self) {
+        self.cancel.notify_one();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    /// Wait for the session to end, returning its final event
+    pub async fn wait(self) -> Result<SessionEvent> {
+        self.task.await.map_err(|e| anyhow!("Capture session task failed: {}", e))
+    }
+}
+
+async fn run<T: SessionTarget>(
+    plan: &SessionPlan,
+    target: &mut T,
+    tick: Duration,
+    cancel: &Notify,
+    on_event: &(impl Fn(SessionEvent) + Sync),
+) -> SessionEvent {
+    let tick = tick.max(Duration::from_millis(1));
+
+    // Countdown to the planned start
+    let start_at = Instant::now() + plan.delay();
+    loop {
+        let remaining = start_at.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        on_event(SessionEvent::Countdown { remaining_ms: remaining.as_millis() as u64 });
+        tokio::select! {
+            _ = tokio::time::sleep(remaining.min(tick)) => {}
+            _ = cancel.notified() => return SessionEvent::Cancelled { elapsed_ms: 0, triggers: 0 },
+        }
+    }
+
+    if let Err(e) = target.start().await {
+        return SessionEvent::Failed { error: format!("Failed to start capture: {}", e) };
+    }
+    on_event(SessionEvent::Started);
+    let started = Instant::now();
+    let duration = plan.duration_ms.map(Duration::from_millis);
+
+    let mut cancelled = false;
+    let triggers = loop {
+        let elapsed = started.elapsed();
+        let triggers = target.triggers().await;
+        let time_done = duration.is_some_and(|d| elapsed >= d);
+        let triggers_done = plan.max_triggers.is_some_and(|max| triggers >= max);
+        if time_done || triggers_done {
+            break triggers;
+        }
+
+        let progress = [
+            duration.map(|d| elapsed.as_secs_f64() / d.as_secs_f64()),
+            plan.max_triggers.map(|max| triggers as f64 / max as f64),
+        ]
+        .into_iter()
+        .flatten()
+        .fold(0.0, f64::max);
+        on_event(SessionEvent::Progress {
+            elapsed_ms: elapsed.as_millis() as u64,
+            triggers,
+            progress: progress.min(1.0),
+        });
+
+        let wait = duration.map_or(tick, |d| d.saturating_sub(elapsed).min(tick));
+        tokio::select! {
+            _ = tokio::time::sleep(wait) => {}
+            _ = cancel.notified() => {
+                cancelled = true;
+                break target.triggers().await;
+            }
+        }
+    };
+
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+    if let Err(e) = target.finish().await {
+        return SessionEvent::Failed { error: format!("Failed to finish capture: {}", e) };
+    }
+    if cancelled {
+        SessionEvent::Cancelled { elapsed_ms, triggers }
+    } else {
+        SessionEvent::Completed { elapsed_ms, triggers }
+    }
+}
@@ -0,0 +1,196 @@
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+/// Node type of a subgraph instance; its `subgraph` field names the definition
+pub const SUBGRAPH_NODE_TYPE: &str = "Subgraph";
+
+/// Instances nested deeper than this are taken to instantiate themselves
+const MAX_DEPTH: usize = 16;
+
+/// Reusable group of nodes, instantiated as a single node in other graphs
+///
+/// Nodes and connections use the pipeline JSON format and may themselves
+/// be subgraph instances. Exposed ports name the inner node (and port)
+/// that frames enter or leave through.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubgraphDefinition {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    pub nodes: Vec<Value>,
+    #[serde(default)]
+    pub connections: Vec<Value>,
+    #[serde(default)]
+    pub inputs: BTreeMap<String, PortTarget>,
+    #[serde(default)]
+    pub outputs: BTreeMap<String, PortTarget>,
+}
+
+/// Inner node behind an exposed subgraph port
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PortTarget {
+    pub node: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<String>,
+}
+
+type Definitions = HashMap<String, SubgraphDefinition>;
+
+/// Replace the subgraph instances of a pipeline config with their nodes
+///
+/// Definitions come from the config's `subgraphs` map. An instance `mix`
+/// contributes its inner node `gain` as `mix/gain`; connections to or from
+/// the instance are rewired to the inner nodes behind the exposed port given
+/// as `to_port`/`from_port` (optional when only one is exposed). The
+/// instance's `config` may override inner node configs, keyed by inner id.
+pub fn expand_subgraphs(mut config: Value) -> Result<Value> {
+    let definitions: Definitions = match config.as_object_mut().and_then(|c| c.remove("subgraphs")) {
+        Some(Value::Null) | None => HashMap::new(),
+        Some(subgraphs) => serde_json::from_value(subgraphs).context("Invalid subgraph definitions")?,
+    };
+
+    let nodes = config["nodes"].as_array().cloned().unwrap_or_default();
+    if !nodes.iter().any(is_instance) {
+        return Ok(config);
+    }
+    let connections = config["connections"].as_array().cloned().unwrap_or_default();
+    let (nodes, connections) = expand(&definitions, nodes, connections, 0)?;
+    config["nodes"] = Value::Array(nodes);
+    config["connections"] = Value::Array(connections);
+    Ok(config)
+}
+
+fn is_instance(node: &Value) -> bool {
+    node["type"].as_str() == Some(SUBGRAPH_NODE_TYPE)
+}
+
+fn node_id(node: &Value) -> Result<&str> {
+    node["id"].as_str().ok_or_else(|| anyhow!("Node missing id"))
+}
+
+fn definition<'a>(definitions: &'a Definitions, instance: &Value) -> Result<&'a SubgraphDefinition> {
+    let id = node_id(instance)?;
+    let name = instance["subgraph"]
+        .as_str()
+        .ok_or_else(|| anyhow!("Subgraph node '{}' is missing its subgraph name", id))?;
+    definitions
+        .get(name)
+        .ok_or_else(|| anyhow!("Node '{}' uses unknown subgraph '{}'", id, name))
+}
+
+fn expand(
+    definitions: &Definitions,
+    nodes: Vec<Value>,
+    connections: Vec<Value>,
+    depth: usize,
+) -> Result<(Vec<Value>, Vec<Value>)> {
+    let mut expanded_nodes = Vec::new();
+    let mut expanded_connections = Vec::new();
+    let mut instances = HashMap::new();
+
+    for node in nodes {
+        if !is_instance(&node) {
+            expanded_nodes.push(node);
+            continue;
+        }
+        if depth >= MAX_DEPTH {
+            return Err(anyhow!("Subgraphs are nested more than {} levels deep; does one contain itself?", MAX_DEPTH));
+        }
+        let id = node_id(&node)?.to_string();
+        let def = definition(definitions, &node)?;
+
+        let inner_nodes = def
+            .nodes
+            .iter()
+            .map(|inner| {
+                let mut inner = inner.clone();
+                let inner_id = node_id(&inner)?.to_string();
+                if let Some(overrides) = node["config"].get(&inner_id).and_then(|o| o.as_object()) {
+                    if inner["config"].is_null() {
+                        inner["config"] = serde_json::json!({});
+                    }
+                    let config = inner["config"]
+                        .as_object_mut()
+                        .ok_or_else(|| anyhow!("Subgraph node '{}/{}' has a non-object config", id, inner_id))?;
+                    config.extend(overrides.iter().map(|(k, v)| (k.clone(), v.clone())));
+                }
+                Ok(inner)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let (inner_nodes, inner_connections) = expand(definitions, inner_nodes, def.connections.clone(), depth + 1)
+            .with_context(|| format!("In subgraph node '{}'", id))?;
+
+        for mut inner in inner_nodes {
+            inner["id"] = Value::from(format!("{}/{}", id, node_id(&inner)?));
+            expanded_nodes.push(inner);
+        }
+        for mut conn in inner_connections {
+            for end in ["from", "to"] {
+                let inner = conn[end].as_str().ok_or_else(|| anyhow!("Connection missing {}", end))?;
+                conn[end] = Value::from(format!("{}/{}", id, inner));
+            }
+            expanded_connections.push(conn);
+        }
+        instances.insert(id, def);
+    }
+
+    for mut conn in connections {
+        for (end, port_key, outputs) in [("to", "to_port", false), ("from", "from_port", true)] {
+            let Some(def) = conn[end].as_str().and_then(|n| instances.get(n)) else {
+                continue;
+            };
+            let instance = conn[end].as_str().unwrap_or_default().to_string();
+            let (inner, port) = resolve_port(definitions, def, conn[port_key].as_str(), outputs, 0)
+                .map_err(|e| anyhow!("Subgraph node '{}' {}", instance, e))?;
+            conn[end] = Value::from(format!("{}/{}", instance, inner));
+            match port {
+                Some(port) => conn[port_key] = Value::from(port),
+                None => {
+                    if let Some(conn) = conn.as_object_mut() {
+                        conn.remove(port_key);
+                    }
+                }
+            }
+        }
+        expanded_connections.push(conn);
+    }
+
+    Ok((expanded_nodes, expanded_connections))
+}
+
+/// Inner node path and port behind an exposed port, following nested instances
+fn resolve_port(
+    definitions: &Definitions,
+    def: &SubgraphDefinition,
+    port: Option<&str>,
+    outputs: bool,
+    depth: usize,
+) -> Result<(String, Option<String>)> {
+    if depth >= MAX_DEPTH {
+        return Err(anyhow!("Subgraphs are nested more than {} levels deep; does one contain itself?", MAX_DEPTH));
+    }
+    let (kind, exposed) = if outputs { ("output", &def.outputs) } else { ("input", &def.inputs) };
+    let target = match port {
+        Some(port) => exposed.get(port).ok_or_else(|| anyhow!("has no {} port '{}'", kind, port))?,
+        None => {
+            let mut targets = exposed.values();
+            match (targets.next(), targets.next()) {
+                (Some(only), None) => only,
+                _ => return Err(anyhow!("has {} {} ports; connect to one by name", exposed.len(), kind)),
+            }
+        }
+    };
+
+    let inner = def
+        .nodes
+        .iter()
+        .find(|n| n["id"].as_str() == Some(target.node.as_str()))
+        .ok_or_else(|| anyhow!("exposes {} port through unknown node '{}'", kind, target.node))?;
+    if !is_instance(inner) {
+        return Ok((target.node.clone(), target.port.clone()));
+    }
+    let nested = definition(definitions, inner)?;
+    let (path, port) = resolve_port(definitions, nested, target.port.as_deref(), outputs, depth + 1)?;
+    Ok((format!("{}/{}", target.node, path), port))
+}
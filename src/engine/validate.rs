@@ -0,0 +1,267 @@
+//! Field-level checks of a pipeline config before it is deployed
+//!
+//! `AsyncPipeline::from_json` stops at the first problem; `validate_graph`
+//! reports all of them, each with the field it concerns, so editors can
+//! mark them in place.
+
+use crate::engine::async_pipeline::NODE_TYPE_ALIASES;
+use crate::engine::backpressure::BackpressurePolicy;
+use crate::engine::realtime::RealtimeConfig;
+use crate::engine::subgraph::expand_subgraphs;
+use crate::registry::{NodeMetadata, PresetStore};
+use crate::resilience::ErrorPolicy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// A problem with one field of a pipeline config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldError {
+    /// Dotted path of the field, e.g. `nodes.gain.config.gain_db`; nodes
+    /// are named by id (by index when they have none), connections by index
+    pub path: String,
+    /// Node the field belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node: Option<String>,
+    pub message: String,
+}
+
+/// Check a pipeline config (the `AsyncPipeline::from_json` format) and
+/// return every problem found; an empty list means it is valid
+///
+/// Subgraph instances are expanded first, so problems inside them are
+/// reported on the expanded nodes (`mix/gain`). Node configs are checked
+/// against their declared parameters after resolving `"preset"`
+/// references from `presets`; settings a node validates only in
+/// `on_create` are not covered.
+pub fn validate_graph(config: &Value, presets: &PresetStore) -> Vec<FieldError> {
+    let mut errors = Errors::default();
+    if !config.is_object() {
+        errors.push("", None, "Pipeline config must be a JSON object");
+        return errors.0;
+    }
+    let config = match expand_subgraphs(config.clone()) {
+        Ok(config) => config,
+        Err(e) => {
+            errors.push("subgraphs", None, format!("{:#}", e));
+            return errors.0;
+        }
+    };
+
+    check_pipeline_config(&config["pipeline_config"], &mut errors);
+    let ports = check_nodes(&config["nodes"], presets, &mut errors);
+    check_connections(&config["connections"], &ports, &mut errors);
+    errors.0
+}
+
+#[derive(Default)]
+struct Errors(Vec<FieldError>);
+
+impl Errors {
+    fn push(&mut self, path: impl Into<String>, node: Option<&str>, message: impl Into<String>) {
+        self.0.push(FieldError { path: path.into(), node: node.map(str::to_string), message: message.into() });
+    }
+}
+
+/// Port instance ids of a node, when its type is registered
+#[derive(Default)]
+struct Ports {
+    inputs: Option<Vec<String>>,
+    outputs: Option<Vec<String>>,
+}
+
+fn is_positive_integer(value: &Value) -> bool {
+    value.as_u64().is_some_and(|n| n > 0)
+}
+
+fn check_pipeline_config(config: &Value, errors: &mut Errors) {
+    if config.is_null() {
+        return;
+    }
+    if !config.is_object() {
+        errors.push("pipeline_config", None, "pipeline_config must be an object");
+        return;
+    }
+    let present = |key: &str| config.get(key).filter(|v| !v.is_null());
+
+    for key in ["channel_capacity", "metrics_interval_ms", "frame_size"] {
+        if let Some(value) = present(key) {
+            if !is_positive_integer(value) {
+                errors.push(format!("pipeline_config.{}", key), None, format!("{} must be a positive integer", key));
+            }
+        }
+    }
+    if let Some(value) = present("dead_letter_capacity") {
+        if value.as_u64().is_none() {
+            errors.push("pipeline_config.dead_letter_capacity", None, "dead_letter_capacity must be a non-negative integer");
+        }
+    }
+    if let Some(value) = present("priority") {
+        if !matches!(value.as_str(), Some("Critical" | "High" | "Normal" | "Low")) {
+            errors.push("pipeline_config.priority", None, "priority must be one of Critical, High, Normal, Low");
+        }
+    }
+    if let Some(value) = present("error_policy") {
+        if let Err(e) = ErrorPolicy::from_json(value) {
+            errors.push("pipeline_config.error_policy", None, e.to_string());
+        }
+    }
+    if let Some(value) = present("backpressure") {
+        if let Err(e) = BackpressurePolicy::from_json(value) {
+            errors.push("pipeline_config.backpressure", None, e.to_string());
+        }
+    }
+    if let Err(e) = RealtimeConfig::from_json(&config["realtime"]) {
+        errors.push("pipeline_config.realtime", None, e.to_string());
+    }
+    if let Some(value) = present("restart") {
+        if !value.is_object() {
+            errors.push("pipeline_config.restart", None, "restart must be an object");
+        }
+    }
+}
+
+/// Check every node, returning the ports of the valid ones by node id
+fn check_nodes(nodes: &Value, presets: &PresetStore, errors: &mut Errors) -> HashMap<String, Ports> {
+    let mut ports = HashMap::new();
+    let nodes = match nodes {
+        Value::Null => return ports,
+        Value::Array(nodes) => nodes,
+        _ => {
+            errors.push("nodes", None, "nodes must be an array");
+            return ports;
+        }
+    };
+
+    for (index, node) in nodes.iter().enumerate() {
+        let id = node["id"].as_str().filter(|id| !id.is_empty());
+        let key = id.map_or_else(|| index.to_string(), str::to_string);
+        let path = |field: &str| format!("nodes.{}.{}", key, field);
+        let Some(id) = id else {
+            errors.push(path("id"), None, "Node missing id");
+            continue;
+        };
+        if ports.contains_key(id) {
+            errors.push(path("id"), Some(id), format!("Duplicate node id '{}'", id));
+            continue;
+        }
+        let Some(node_type) = node["type"].as_str() else {
+            errors.push(path("type"), Some(id), "Node missing type");
+            continue;
+        };
+        let canonical = NODE_TYPE_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == node_type)
+            .map_or(node_type, |(_, canonical)| canonical);
+        let Some(meta) = NodeMetadata::find(canonical) else {
+            errors.push(path("type"), Some(id), format!("Unknown node type: {}", node_type));
+            ports.insert(id.to_string(), Ports::default());
+            continue;
+        };
+
+        // Parameters, with preset references resolved as on deployment
+        match &node["config"] {
+            Value::Null | Value::Object(_) => match presets.resolve_config(node_type, &node["config"]) {
+                Ok(config) => {
+                    for param in &meta.parameters {
+                        let Some(value) = config.get(&param.name).filter(|v| !v.is_null()) else {
+                            continue;
+                        };
+                        if let Err(e) = param.check(value) {
+                            errors.push(path(&format!("config.{}", param.name)), Some(id), format!("{} {}", param.name, e));
+                        }
+                    }
+                }
+                Err(e) => errors.push(path("config.preset"), Some(id), e.to_string()),
+            },
+            _ => errors.push(path("config"), Some(id), "config must be an object"),
+        }
+
+        let saved_version = &node["version"];
+        if !saved_version.is_null() {
+            match saved_version.as_u64() {
+                Some(v) if v > meta.version as u64 => errors.push(
+                    path("version"),
+                    Some(id),
+                    format!("Saved by {} version {}, but only version {} is available", node_type, v, meta.version),
+                ),
+                Some(v) if v >= 1 => {}
+                _ => errors.push(path("version"), Some(id), "version must be a positive integer"),
+            }
+        }
+        if let Some(policy) = node.get("error_policy").filter(|p| !p.is_null()) {
+            if let Err(e) = ErrorPolicy::from_json(policy) {
+                errors.push(path("error_policy"), Some(id), e.to_string());
+            }
+        }
+        if let Some(size) = node.get("input_block_size").filter(|s| !s.is_null()) {
+            if !is_positive_integer(size) {
+                errors.push(path("input_block_size"), Some(id), "input_block_size must be a positive integer");
+            }
+        }
+
+        let node_ports = match meta.instantiate_ports(&node["port_counts"]) {
+            Ok((inputs, outputs)) => Ports { inputs: Some(inputs), outputs: Some(outputs) },
+            Err(e) => {
+                errors.push(path("port_counts"), Some(id), e.to_string());
+                Ports::default()
+            }
+        };
+        ports.insert(id.to_string(), node_ports);
+    }
+    ports
+}
+
+fn check_connections(connections: &Value, ports: &HashMap<String, Ports>, errors: &mut Errors) {
+    let connections = match connections {
+        Value::Null => return,
+        Value::Array(connections) => connections,
+        _ => {
+            errors.push("connections", None, "connections must be an array");
+            return;
+        }
+    };
+
+    for (index, conn) in connections.iter().enumerate() {
+        let path = |field: &str| format!("connections.{}.{}", index, field);
+        let mut endpoint = |field: &str| -> Option<String> {
+            let Some(id) = conn[field].as_str() else {
+                errors.push(path(field), None, format!("Connection missing {}", field));
+                return None;
+            };
+            if !ports.contains_key(id) {
+                errors.push(path(field), None, format!("Node '{}' not found", id));
+                return None;
+            }
+            Some(id.to_string())
+        };
+        let from = endpoint("from");
+        let to = endpoint("to");
+
+        if let (Some(from), Some(port)) = (&from, conn["from_port"].as_str()) {
+            if ports[from].outputs.as_ref().is_some_and(|outputs| !outputs.iter().any(|p| p == port)) {
+                errors.push(path("from_port"), Some(from), format!("Node '{}' has no output port '{}'", from, port));
+            }
+        }
+        if let (Some(to), Some(port)) = (&to, conn["to_port"].as_str()) {
+            if ports[to].inputs.as_ref().is_some_and(|inputs| !inputs.iter().any(|p| p == port)) {
+                errors.push(path("to_port"), Some(to), format!("Node '{}' has no input port '{}'", to, port));
+            }
+        }
+        if let Some(size) = conn.get("block_size").filter(|s| !s.is_null()) {
+            if !is_positive_integer(size) {
+                errors.push(path("block_size"), None, "block_size must be a positive integer");
+            }
+        }
+        if let Some(factor) = conn.get("decimation").filter(|d| !d.is_null()) {
+            if !is_positive_integer(factor) {
+                errors.push(path("decimation"), None, "decimation must be a positive integer");
+            }
+        }
+        if let Some(policy) = conn.get("backpressure").filter(|p| !p.is_null()) {
+            if let Err(e) = BackpressurePolicy::from_json(policy) {
+                errors.push(path("backpressure"), None, e.to_string());
+            }
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use crate::hal::format_converter::packet_to_frame;
+use crate::hal::{Calibration, Device, RegisteredHardware};
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Reference sound pressure (20 µPa)
+const P_REF: f64 = 20e-6;
+
+/// Level of a standard 1 kHz acoustic calibrator (1 Pa RMS)
+pub const CALIBRATOR_LEVEL_DB: f64 = 94.0;
+
+/// Age after which a calibration should be repeated
+pub const DEFAULT_MAX_AGE: Duration = Duration::from_secs(365 * 24 * 3600);
+
+/// Calibration runs kept per device
+const MAX_HISTORY: usize = 20;
+
+/// Allowed level spread between capture blocks before the tone counts as unstable
+const MAX_INSTABILITY_DB: f64 = 0.5;
+
+/// Length of the blocks compared for stability
+const BLOCK_SECONDS: f64 = 0.1;
+
+/// Which value the calibration solves for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CalibrationTarget {
+    /// Solve the sensitivity gain (full scale to Pascal); offset is reset
+    #[default]
+    Gain,
+    /// Keep the current gain and solve the dB offset
+    Offset,
+}
+
+/// One calibration run of a device
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CalibrationRecord {
+    pub calibration: Calibration,
+    /// Calibrator level in dB SPL
+    pub reference_db: f64,
+    /// RMS of the captured tone relative to full scale
+    pub measured_rms: f64,
+}
+
+/// Age of a device's calibration
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CalibrationStatus {
+    pub calibrated_at: Option<u64>,
+    pub age_secs: Option<u64>,
+    /// Never calibrated, or older than the allowed age
+    pub stale: bool,
+}
+
+pub fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Solve a calibration from a captured calibrator tone (full-scale samples)
+pub fn compute_calibration(
+    samples: &[f64],
+    sample_rate: f64,
+    reference_db: f64,
+    target: CalibrationTarget,
+    current: &Calibration,
+    now: u64,
+) -> Result<CalibrationRecord> {
+    let block = (sample_rate * BLOCK_SECONDS) as usize;
+    if block == 0 || samples.len() < 5 * block {
+        anyhow::bail!("Calibration needs at least {:.1} s of signal", 5.0 * BLOCK_SECONDS);
+    }
+    if samples.iter().any(|s| s.abs() >= 0.999) {
+        anyhow::bail!("Calibrator tone is clipping; reduce the input gain");
+    }
+
+    let rms = |s: &[f64]| (s.iter().map(|x| x * x).sum::<f64>() / s.len() as f64).sqrt();
+    let measured_rms = rms(samples);
+    if measured_rms < 1e-6 {
+        anyhow::bail!("No calibrator signal detected");
+    }
+
+    // A seated calibrator gives a steady level; handling noise does not
+    let block_levels: Vec<f64> = samples
+        .chunks_exact(block)
+        .map(|b| 20.0 * rms(b).max(1e-12).log10())
+        .collect();
+    let spread = block_levels.iter().cloned().fold(f64::NEG_INFINITY, f64::max)
+        - block_levels.iter().cloned().fold(f64::INFINITY, f64::min);
+    if spread > MAX_INSTABILITY_DB {
+        anyhow::bail!("Calibrator level varied by {:.1} dB; check that the calibrator is seated", spread);
+    }
+
+    let reference_pa = P_REF * 10f64.powf(reference_db / 20.0);
+    let (gain, offset) = match target {
+        CalibrationTarget::Gain => (reference_pa / measured_rms, 0.0),
+        CalibrationTarget::Offset => {
+            let measured_db = 20.0 * (measured_rms * current.gain / P_REF).max(1e-30).log10();
+            (current.gain, reference_db - measured_db)
+        }
+    };
+
+    Ok(CalibrationRecord {
+        calibration: Calibration {
+            gain,
+            offset,
+            calibrated_at: Some(now),
+        },
+        reference_db,
+        measured_rms,
+    })
+}
+
+/// Capture `duration` of one channel from an input device
+///
+/// The device is started and stopped here.
+pub async fn capture_channel(device: &mut dyn Device, channel: usize, duration: Duration) -> Result<(Vec<f64>, f64)> {
+    device.start().await?;
+    let channels = device.get_channels();
+    let deadline = Instant::now() + duration + Duration::from_secs(2);
+    let mut samples = Vec::new();
+    let mut sample_rate = 0.0;
+
+    let result = loop {
+        if sample_rate > 0.0 && samples.len() as f64 >= duration.as_secs_f64() * sample_rate {
+            break Ok(());
+        }
+        if Instant::now() > deadline {
+            break Err(anyhow::anyhow!("Timed out waiting for input samples"));
+        }
+        let Ok(packet) = channels.filled_rx.try_recv() else {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            continue;
+        };
+        let frame = packet_to_frame(&packet, 0);
+        sample_rate = packet.sample_rate as f64;
+        let _ = channels.empty_tx.try_send(packet);
+        let frame = match frame {
+            Ok(frame) => frame,
+            Err(e) => break Err(e),
+        };
+        match frame.payload.get(&format!("ch{}", channel)) {
+            Some(data) => samples.extend_from_slice(data),
+            None => break Err(anyhow::anyhow!("Input has no channel {}", channel)),
+        }
+    };
+    device.stop().await?;
+    result?;
+    Ok((samples, sample_rate))
+}
+
+/// Make `record` the device's calibration, keeping it in the history
+pub fn apply_calibration(registered: &mut RegisteredHardware, record: CalibrationRecord) {
+    registered.calibration = record.calibration;
+    registered.calibration_history.push(record);
+    let excess = registered.calibration_history.len().saturating_sub(MAX_HISTORY);
+    registered.calibration_history.drain(..excess);
+}
+
+pub fn calibration_status(calibration: &Calibration, now: u64, max_age: Duration) -> CalibrationStatus {
+    let age_secs = calibration.calibrated_at.map(|at| now.saturating_sub(at));
+    CalibrationStatus {
+        calibrated_at: calibration.calibrated_at,
+        age_secs,
+        stale: age_secs.is_none_or(|age| age > max_age.as_secs()),
+    }
+}
@@ -0,0 +1,215 @@
+use anyhow::{anyhow, bail, Result};
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+use crate::hal::types::SampleFormat;
+
+/// Parses raw byte packets from special hardware into numeric channels
+///
+/// Byte streams have no packet boundaries, so decoders are stateful:
+/// bytes of an incomplete frame are kept until the next call.
+pub trait BytesDecoder: Send {
+    /// Channel names, in the order `decode` returns them
+    fn channel_names(&self) -> Vec<String>;
+
+    /// Feed raw bytes; returns per-channel samples of every complete frame
+    fn decode(&mut self, bytes: &[u8]) -> Result<Vec<Vec<f64>>>;
+}
+
+pub type DecoderFactory = Arc<dyn Fn() -> Box<dyn BytesDecoder> + Send + Sync>;
+
+/// Named decoder factories, so devices can be configured with a decoder id
+pub struct DecoderRegistry {
+    factories: RwLock<HashMap<String, DecoderFactory>>,
+}
+
+impl DecoderRegistry {
+    /// Registry with the built-in decoders: `csv` (one comma-separated line per frame)
+    pub fn new() -> Self {
+        let registry = Self {
+            factories: RwLock::new(HashMap::new()),
+        };
+        registry.register("csv", || Box::new(LineDecoder::new(',')));
+        registry
+    }
+
+    /// Process-wide registry used by drivers
+    pub fn global() -> &'static DecoderRegistry {
+        static REGISTRY: OnceLock<DecoderRegistry> = OnceLock::new();
+        REGISTRY.get_or_init(DecoderRegistry::new)
+    }
+
+    pub fn register<F>(&self, name: impl Into<String>, factory: F)
+    where
+        F: Fn() -> Box<dyn BytesDecoder> + Send + Sync + 'static,
+    {
+        self.factories
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(name.into(), Arc::new(factory));
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .contains_key(name)
+    }
+
+    pub fn create(&self, name: &str) -> Result<Box<dyn BytesDecoder>> {
+        let factory = self
+            .factories
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(name)
+            .cloned()
+            .ok_or_else(|| anyhow!("Unknown bytes decoder '{}'", name))?;
+        Ok(factory())
+    }
+}
+
+impl Default for DecoderRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Text decoder: one line of `separator`-separated numbers per frame
+///
+/// The channel count is taken from the first complete line; lines that
+/// fail to parse or have a different width are skipped.
+pub struct LineDecoder {
+    separator: char,
+    channels: Option<usize>,
+    pending: Vec<u8>,
+}
+
+impl LineDecoder {
+    pub fn new(separator: char) -> Self {
+        Self {
+            separator,
+            channels: None,
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl BytesDecoder for LineDecoder {
+    fn channel_names(&self) -> Vec<String> {
+        (0..self.channels.unwrap_or(0)).map(|ch| format!("ch{}", ch)).collect()
+    }
+
+    fn decode(&mut self, bytes: &[u8]) -> Result<Vec<Vec<f64>>> {
+        self.pending.extend_from_slice(bytes);
+        let Some(last_newline) = self.pending.iter().rposition(|&b| b == b'\n') else {
+            return Ok(vec![Vec::new(); self.channels.unwrap_or(0)]);
+        };
+        let complete: Vec<u8> = self.pending.drain(..=last_newline).collect();
+
+        let mut output: Vec<Vec<f64>> = vec![Vec::new(); self.channels.unwrap_or(0)];
+        for line in String::from_utf8_lossy(&complete).lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Ok(values) = line
+                .split(self.separator)
+                .map(|v| v.trim().parse::<f64>())
+                .collect::<Result<Vec<f64>, _>>()
+            else {
+                continue;
+            };
+            let channels = *self.channels.get_or_insert(values.len());
+            if output.len() < channels {
+                output.resize(channels, Vec::new());
+            }
+            if values.len() != channels {
+                continue;
+            }
+            for (out, value) in output.iter_mut().zip(values) {
+                out.push(value);
+            }
+        }
+        Ok(output)
+    }
+}
+
+/// Binary decoder for fixed-size frames starting with a sync pattern
+///
+/// Each frame is `sync` followed by `channels` little- or big-endian
+/// samples in `format`. Bytes before the next sync pattern are discarded,
+/// so the decoder resynchronizes after corrupted data.
+pub struct BinaryFrameDecoder {
+    sync: Vec<u8>,
+    channels: usize,
+    format: SampleFormat,
+    big_endian: bool,
+    pending: Vec<u8>,
+}
+
+impl BinaryFrameDecoder {
+    pub fn new(sync: &[u8], channels: usize, format: SampleFormat, big_endian: bool) -> Result<Self> {
+        if channels == 0 {
+            bail!("Binary frames must have at least one channel");
+        }
+        Ok(Self {
+            sync: sync.to_vec(),
+            channels,
+            format,
+            big_endian,
+            pending: Vec::new(),
+        })
+    }
+
+    fn sample_width(&self) -> usize {
+        match self.format {
+            SampleFormat::U8 => 1,
+            SampleFormat::I16 => 2,
+            SampleFormat::I24 => 3,
+            SampleFormat::I32 | SampleFormat::F32 => 4,
+            SampleFormat::F64 => 8,
+        }
+    }
+
+    fn read_sample(&self, bytes: &[u8]) -> f64 {
+        let mut b = bytes.to_vec();
+        if self.big_endian {
+            b.reverse();
+        }
+        match self.format {
+            SampleFormat::U8 => b[0] as f64,
+            SampleFormat::I16 => i16::from_le_bytes([b[0], b[1]]) as f64,
+            SampleFormat::I24 => (i32::from_le_bytes([0, b[0], b[1], b[2]]) >> 8) as f64,
+            SampleFormat::I32 => i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            SampleFormat::F32 => f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            SampleFormat::F64 => f64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]),
+        }
+    }
+}
+
+impl BytesDecoder for BinaryFrameDecoder {
+    fn channel_names(&self) -> Vec<String> {
+        (0..self.channels).map(|ch| format!("ch{}", ch)).collect()
+    }
+
+    fn decode(&mut self, bytes: &[u8]) -> Result<Vec<Vec<f64>>> {
+        self.pending.extend_from_slice(bytes);
+        let width = self.sample_width();
+        let frame_len = self.sync.len() + width * self.channels;
+        let mut output = vec![Vec::new(); self.channels];
+
+        let mut pos = 0;
+        while self.pending.len() - pos >= frame_len {
+            if !self.pending[pos..].starts_with(&self.sync) {
+                pos += 1;
+                continue;
+            }
+            let body = &self.pending[pos + self.sync.len()..pos + frame_len];
+            for (ch, out) in output.iter_mut().enumerate() {
+                out.push(self.read_sample(&body[ch * width..(ch + 1) * width]));
+            }
+            pos += frame_len;
+        }
+        self.pending.drain(..pos);
+        Ok(output)
+    }
+}
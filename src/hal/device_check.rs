@@ -0,0 +1,152 @@
+use crate::core::{Channel, DataFrame};
+use crate::hal::format_converter::{frame_to_packet, packet_to_frame};
+use crate::hal::{Device, DeviceConfig, PacketBuffer};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+/// Sine tone used to check output wiring
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TestTone {
+    pub frequency: f64,
+    /// Peak level in dBFS; positive values are clamped to 0
+    pub level_db: f64,
+    pub duration: Duration,
+}
+
+impl TestTone {
+    /// Build the packet starting at `start_frame`, same tone on every channel
+    pub fn packet(&self, config: &DeviceConfig, start_frame: u64, frames: usize) -> Result<PacketBuffer> {
+        let amplitude = 10f64.powf(self.level_db.min(0.0) / 20.0);
+        let step = 2.0 * std::f64::consts::PI * self.frequency / config.sample_rate as f64;
+        let samples: Vec<f64> = (0..frames as u64)
+            .map(|n| amplitude * (step * (start_frame + n) as f64).sin())
+            .collect();
+
+        let mut frame = DataFrame::new(0, 0);
+        for ch in 0..config.channel_mapping.physical_channels.max(1) {
+            frame.insert_channel(format!("ch{}", ch), Channel::from(samples.clone()));
+        }
+        frame_to_packet(&frame, config.format, config.sample_rate)
+    }
+}
+
+/// Play `tone` through an output device, paced in real time
+///
+/// The device is started and stopped here. Returns the number of packets sent.
+pub async fn play_test_tone(device: &mut dyn Device, config: &DeviceConfig, tone: &TestTone) -> Result<u64> {
+    if !device.capabilities().can_output {
+        anyhow::bail!("Device '{}' is not an output", config.name);
+    }
+    if config.sample_rate == 0 || config.buffer_size == 0 {
+        anyhow::bail!("Test tone needs a non-zero sample rate and buffer size");
+    }
+
+    let total_frames = (tone.duration.as_secs_f64() * config.sample_rate as f64) as u64;
+    device.start().await?;
+    // Output devices take filled packets on empty_tx (see AudioOutputNode)
+    let channels = device.get_channels();
+    let started = Instant::now();
+    let mut sent_frames = 0;
+    let mut packets = 0;
+    while sent_frames < total_frames {
+        let frames = (total_frames - sent_frames).min(config.buffer_size as u64) as usize;
+        let packet = tone.packet(config, sent_frames, frames)?;
+        if channels.empty_tx.try_send(packet).is_ok() {
+            packets += 1;
+        }
+        // Release buffers handed back by the device
+        while channels.filled_rx.try_recv().is_ok() {}
+        sent_frames += frames as u64;
+
+        let due = Duration::from_secs_f64(sent_frames as f64 / config.sample_rate as f64);
+        tokio::time::sleep(due.saturating_sub(started.elapsed())).await;
+    }
+    device.stop().await?;
+    Ok(packets)
+}
+
+/// RMS and peak level of one channel in dBFS
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ChannelLevel {
+    pub rms_db: f64,
+    pub peak_db: f64,
+}
+
+/// Levels of one input packet
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputLevels {
+    pub timestamp: u64,
+    pub channels: Vec<ChannelLevel>,
+}
+
+/// Measure per-channel levels of a sample packet
+pub fn measure_levels(packet: &PacketBuffer) -> Result<InputLevels> {
+    if packet.num_channels == 0 {
+        anyhow::bail!("Packet has no channels");
+    }
+    let frame = packet_to_frame(packet, 0)?;
+    let channels = (0..packet.num_channels)
+        .filter_map(|ch| frame.payload.get(&format!("ch{}", ch)))
+        .map(|samples| {
+            let mean_square = samples.iter().map(|s| s * s).sum::<f64>() / samples.len().max(1) as f64;
+            let peak = samples.iter().fold(0.0f64, |peak, s| peak.max(s.abs()));
+            ChannelLevel {
+                rms_db: 10.0 * mean_square.max(1e-30).log10(),
+                peak_db: 20.0 * peak.max(1e-15).log10(),
+            }
+        })
+        .collect();
+    Ok(InputLevels {
+        timestamp: frame.timestamp,
+        channels,
+    })
+}
+
+/// Streams level readings from an input device until stopped
+pub struct InputMonitor {
+    running: Arc<AtomicBool>,
+    task: JoinHandle<Result<()>>,
+}
+
+impl InputMonitor {
+    /// Start `device` and call `on_levels` for every packet it captures
+    pub async fn start<F>(mut device: Box<dyn Device>, on_levels: F) -> Result<Self>
+    where
+        F: Fn(InputLevels) + Send + 'static,
+    {
+        device.start().await?;
+        let channels = device.get_channels();
+        let running = Arc::new(AtomicBool::new(true));
+        let flag = running.clone();
+
+        let task = tokio::spawn(async move {
+            while flag.load(Ordering::Relaxed) {
+                let mut idle = true;
+                while let Ok(packet) = channels.filled_rx.try_recv() {
+                    idle = false;
+                    // Non-sample packets (e.g. Bytes) have no level
+                    if let Ok(levels) = measure_levels(&packet) {
+                        on_levels(levels);
+                    }
+                    let _ = channels.empty_tx.try_send(packet);
+                }
+                if idle {
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                }
+            }
+            device.stop().await
+        });
+
+        Ok(Self { running, task })
+    }
+
+    /// Stop monitoring and release the device
+    pub async fn stop(self) -> Result<()> {
+        self.running.store(false, Ordering::Relaxed);
+        self.task.await?
+    }
+}
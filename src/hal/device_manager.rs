@@ -1,12 +1,85 @@
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use anyhow::{Result, Context};
+use serde::{Deserialize, Serialize};
+use crate::core::AudiotabError;
 use super::{
-    HardwareRegistry, HardwareDriver, Device,
-    DeviceProfile, DeviceStorage, DeviceInfo,
+    HardwareRegistry, HardwareDriver, Device, DeviceChannels, PacketBuffer,
+    DeviceProfile, DeviceStorage, DeviceInfo, FrequencyResponse,
 };
 
+/// Packets queued per shared consumer before new ones are dropped
+const TEE_CAPACITY: usize = 256;
+
+/// How a consumer holds a started device
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceAccess {
+    /// Sole consumer, reading the device's own channels
+    #[default]
+    Exclusive,
+    /// Receives a copy of every packet alongside other shared consumers
+    Shared,
+}
+
+/// Consumers holding a started device
+struct DeviceLease {
+    access: DeviceAccess,
+    owners: Vec<String>,
+    tee: Option<PacketTee>,
+}
+
+/// Copies a device's packets to every shared consumer
+struct PacketTee {
+    subscribers: Arc<Mutex<Vec<crossbeam_channel::Sender<PacketBuffer>>>>,
+    running: Arc<AtomicBool>,
+}
+
+impl PacketTee {
+    fn spawn(profile_id: &str, channels: DeviceChannels) -> Result<Self> {
+        let subscribers: Arc<Mutex<Vec<crossbeam_channel::Sender<PacketBuffer>>>> = Arc::default();
+        let running = Arc::new(AtomicBool::new(true));
+        let tee_subscribers = subscribers.clone();
+        let tee_running = running.clone();
+        std::thread::Builder::new()
+            .name(format!("device-tee-{}", profile_id))
+            .spawn(move || {
+                while tee_running.load(Ordering::Relaxed) {
+                    let packet = match channels.filled_rx.recv_timeout(Duration::from_millis(50)) {
+                        Ok(packet) => packet,
+                        Err(crossbeam_channel::RecvTimeoutError::Timeout) => continue,
+                        Err(crossbeam_channel::RecvTimeoutError::Disconnected) => break,
+                    };
+                    // A full consumer misses the packet; a gone one is dropped
+                    tee_subscribers
+                        .lock()
+                        .unwrap_or_else(|p| p.into_inner())
+                        .retain(|tx| !matches!(tx.try_send(packet.clone()), Err(crossbeam_channel::TrySendError::Disconnected(_))));
+                    let _ = channels.empty_tx.try_send(packet);
+                }
+            })
+            .context("Failed to spawn device tee thread")?;
+        Ok(Self { subscribers, running })
+    }
+
+    /// Channels receiving copies of the packets; buffers sent back are discarded
+    fn subscribe(&self) -> DeviceChannels {
+        let (filled_tx, filled_rx) = crossbeam_channel::bounded(TEE_CAPACITY);
+        let (empty_tx, _) = crossbeam_channel::bounded(1);
+        self.subscribers.lock().unwrap_or_else(|p| p.into_inner()).push(filled_tx);
+        DeviceChannels { filled_rx, empty_tx }
+    }
+}
+
+impl Drop for PacketTee {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+}
+
 /// Manages hardware devices and their configurations
 pub struct DeviceManager {
     /// Hardware driver registry
@@ -20,11 +93,22 @@ pub struct DeviceManager {
 
     /// Active device instances
     active_devices: Arc<Mutex<HashMap<String, Box<dyn Device>>>>,
+
+    /// Consumers of devices started through `acquire_device`
+    leases: Mutex<HashMap<String, DeviceLease>>,
 }
 
 impl DeviceManager {
     /// Create new device manager
-    pub fn new(storage_dir: PathBuf) -> Result<Self> {
+    pub fn new(storage_dir: PathBuf) -> Result<Self, AudiotabError> {
+        Self::with_registry(storage_dir, HardwareRegistry::new())
+    }
+
+    /// Create a device manager using the drivers of `registry`
+    ///
+    /// Pass a clone of the kernel's registry so both open devices through
+    /// the same driver instances.
+    pub fn with_registry(storage_dir: PathBuf, registry: HardwareRegistry) -> Result<Self, AudiotabError> {
         let storage = DeviceStorage::new(storage_dir)?;
         let profiles = storage.list_all()
             .context("Failed to load device profiles")?
@@ -33,10 +117,11 @@ impl DeviceManager {
             .collect();
 
         Ok(Self {
-            registry: HardwareRegistry::new(),
+            registry,
             storage,
             profiles,
             active_devices: Arc::new(Mutex::new(HashMap::new())),
+            leases: Mutex::new(HashMap::new()),
         })
     }
 
@@ -46,21 +131,21 @@ impl DeviceManager {
     }
 
     /// Discover all available devices from all drivers
-    pub async fn discover_all(&self) -> Result<Vec<DeviceInfo>> {
-        self.registry.discover_all().await
+    pub async fn discover_all(&self) -> Result<Vec<DeviceInfo>, AudiotabError> {
+        Ok(self.registry.discover_all().await?)
     }
 
     /// Add a new device profile
-    pub fn add_profile(&mut self, profile: DeviceProfile) -> Result<()> {
+    pub fn add_profile(&mut self, profile: DeviceProfile) -> Result<(), AudiotabError> {
         self.storage.save(&profile)?;
         self.profiles.insert(profile.id.clone(), profile);
         Ok(())
     }
 
     /// Update an existing device profile
-    pub fn update_profile(&mut self, profile: DeviceProfile) -> Result<()> {
+    pub fn update_profile(&mut self, profile: DeviceProfile) -> Result<(), AudiotabError> {
         if !self.profiles.contains_key(&profile.id) {
-            anyhow::bail!("Profile {} not found", profile.id);
+            return Err(AudiotabError::device(&profile.id, format!("Profile {} not found", profile.id)));
         }
 
         self.storage.save(&profile)?;
@@ -69,12 +154,23 @@ impl DeviceManager {
     }
 
     /// Delete a device profile
-    pub fn delete_profile(&mut self, id: &str) -> Result<()> {
+    pub fn delete_profile(&mut self, id: &str) -> Result<(), AudiotabError> {
         self.storage.delete(id)?;
         self.profiles.remove(id);
         Ok(())
     }
 
+    /// Load a microphone calibration file into a profile
+    pub fn import_frequency_response(&mut self, profile_id: &str, path: &Path) -> Result<FrequencyResponse, AudiotabError> {
+        let mut profile = self.get_profile(profile_id)
+            .cloned()
+            .ok_or_else(|| AudiotabError::device(profile_id, format!("Profile {} not found", profile_id)))?;
+        let response = FrequencyResponse::load(path).map_err(|e| AudiotabError::from_device(profile_id, e))?;
+        profile.frequency_response = Some(response.clone());
+        self.update_profile(profile)?;
+        Ok(response)
+    }
+
     /// Get a device profile by ID
     pub fn get_profile(&self, id: &str) -> Option<&DeviceProfile> {
         self.profiles.get(id)
@@ -86,74 +182,158 @@ impl DeviceManager {
     }
 
     /// Create a device instance from a profile
-    pub fn create_device(&self, profile_id: &str) -> Result<Box<dyn Device>> {
+    pub fn create_device(&self, profile_id: &str) -> Result<Box<dyn Device>, AudiotabError> {
         let profile = self.get_profile(profile_id)
-            .ok_or_else(|| anyhow::anyhow!("Profile {} not found", profile_id))?;
+            .ok_or_else(|| AudiotabError::device(profile_id, format!("Profile {} not found", profile_id)))?;
 
-        self.registry.create_device(
-            &profile.driver_id,
-            &profile.device_id,
-            profile.config.clone(),
-        )
+        self.registry
+            .create_device(&profile.driver_id, &profile.device_id, profile.config.clone())
+            .map_err(|e| AudiotabError::from_device(profile_id, e))
     }
 
     /// Start a device and track it as active
-    pub async fn start_device(&self, profile_id: &str) -> Result<()> {
-        let device = self.create_device(profile_id)?;
-
-        // Insert device into active_devices BEFORE starting it
-        // This prevents resource leak if the function fails after device starts
-        {
-            let mut active = self.active_devices.lock()
-                .map_err(|e| anyhow::anyhow!("Failed to acquire active devices lock: {}", e))?;
-            active.insert(profile_id.to_string(), device);
+    ///
+    /// Fails if the device is already started; use `acquire_device` to
+    /// share one between consumers.
+    pub async fn start_device(&self, profile_id: &str) -> Result<(), AudiotabError> {
+        let busy = || AudiotabError::device(profile_id, format!("Device '{}' is busy: already started", profile_id));
+        let active = self.active_devices.lock()
+            .map_err(|e| AudiotabError::other(format!("Failed to acquire active devices lock: {}", e)))?
+            .contains_key(profile_id);
+        if active {
+            return Err(busy());
         }
 
-        // Start the device - if this fails, remove from active_devices
-        let start_result = {
-            let mut active = self.active_devices.lock()
-                .map_err(|e| anyhow::anyhow!("Failed to acquire active devices lock: {}", e))?;
-            let device = active.get_mut(profile_id)
-                .ok_or_else(|| anyhow::anyhow!("Device disappeared from active devices"))?;
-            device.start().await
-        };
+        // Start the device outside the lock, so this future stays Send
+        let mut device = self.create_device(profile_id)?;
+        device.start().await.map_err(|e| AudiotabError::from_device(profile_id, e))?;
 
-        if let Err(e) = start_result {
-            // Remove from active_devices on failure
+        // Another caller may have started the device meanwhile
+        let raced = {
             let mut active = self.active_devices.lock()
-                .map_err(|e| anyhow::anyhow!("Failed to acquire active devices lock: {}", e))?;
-            active.remove(profile_id);
-            return Err(e);
+                .map_err(|e| AudiotabError::other(format!("Failed to acquire active devices lock: {}", e)))?;
+            if active.contains_key(profile_id) {
+                Some(device)
+            } else {
+                active.insert(profile_id.to_string(), device);
+                None
+            }
+        };
+        if let Some(mut device) = raced {
+            device.stop().await.map_err(|e| AudiotabError::from_device(profile_id, e))?;
+            return Err(busy());
         }
 
         Ok(())
     }
 
-    /// Stop an active device
-    pub async fn stop_device(&self, profile_id: &str) -> Result<()> {
+    /// Stop an active device, ending every consumer's lease on it
+    pub async fn stop_device(&self, profile_id: &str) -> Result<(), AudiotabError> {
+        self.leases.lock()
+            .map_err(|e| AudiotabError::other(format!("Failed to acquire device leases lock: {}", e)))?
+            .remove(profile_id);
+
         // Remove device from HashMap BEFORE calling stop()
         // This releases the lock before the async operation
         let mut device = {
             let mut active = self.active_devices.lock()
-                .map_err(|e| anyhow::anyhow!("Failed to acquire active devices lock: {}", e))?;
+                .map_err(|e| AudiotabError::other(format!("Failed to acquire active devices lock: {}", e)))?;
             active.remove(profile_id)
         };
 
         // Call stop() outside the lock
         if let Some(ref mut device) = device {
-            device.stop().await?;
+            device.stop().await.map_err(|e| AudiotabError::from_device(profile_id, e))?;
         }
 
         Ok(())
     }
 
+    /// Start a device for `owner`, or join the consumers already using it
+    ///
+    /// The first consumer decides how the device is held: an exclusive
+    /// owner reads the device's own channels, while shared owners each get
+    /// a copy of every packet. Any other combination fails with a
+    /// "busy" error naming the current owners.
+    pub async fn acquire_device(&self, profile_id: &str, owner: &str, access: DeviceAccess) -> Result<DeviceChannels, AudiotabError> {
+        {
+            let mut leases = self.leases.lock()
+                .map_err(|e| AudiotabError::other(format!("Failed to acquire device leases lock: {}", e)))?;
+            if let Some(lease) = leases.get_mut(profile_id) {
+                if let (DeviceAccess::Shared, Some(tee)) = (access, &lease.tee) {
+                    lease.owners.push(owner.to_string());
+                    return Ok(tee.subscribe());
+                }
+                return Err(AudiotabError::device(
+                    profile_id,
+                    format!(
+                        "Device '{}' is busy: used {} by {}",
+                        profile_id,
+                        if lease.access == DeviceAccess::Shared { "shared" } else { "exclusively" },
+                        lease.owners.join(", ")
+                    ),
+                ));
+            }
+        }
+
+        self.start_device(profile_id).await?;
+        let channels = self.get_device_channels(profile_id)?;
+        let (tee, channels) = match access {
+            DeviceAccess::Exclusive => (None, channels),
+            DeviceAccess::Shared => match PacketTee::spawn(profile_id, channels) {
+                Ok(tee) => {
+                    let channels = tee.subscribe();
+                    (Some(tee), channels)
+                }
+                Err(e) => {
+                    self.stop_device(profile_id).await?;
+                    return Err(AudiotabError::from_device(profile_id, e));
+                }
+            },
+        };
+
+        self.leases.lock()
+            .map_err(|e| AudiotabError::other(format!("Failed to acquire device leases lock: {}", e)))?
+            .insert(profile_id.to_string(), DeviceLease {
+                access,
+                owners: vec![owner.to_string()],
+                tee,
+            });
+        Ok(channels)
+    }
+
+    /// Give up `owner`'s lease on a device, stopping it after the last owner
+    pub async fn release_device(&self, profile_id: &str, owner: &str) -> Result<(), AudiotabError> {
+        let last_owner = {
+            let mut leases = self.leases.lock()
+                .map_err(|e| AudiotabError::other(format!("Failed to acquire device leases lock: {}", e)))?;
+            let Some(lease) = leases.get_mut(profile_id) else {
+                return Ok(());
+            };
+            lease.owners.retain(|o| o != owner);
+            lease.owners.is_empty()
+        };
+
+        if last_owner {
+            self.stop_device(profile_id).await?;
+        }
+        Ok(())
+    }
+
+    /// Owners currently holding a device
+    pub fn device_owners(&self, profile_id: &str) -> Vec<String> {
+        self.leases.lock()
+            .map(|leases| leases.get(profile_id).map(|l| l.owners.clone()).unwrap_or_default())
+            .unwrap_or_default()
+    }
+
     /// Get device channels for a running device
-    pub fn get_device_channels(&mut self, profile_id: &str) -> Result<super::DeviceChannels> {
+    pub fn get_device_channels(&self, profile_id: &str) -> Result<super::DeviceChannels, AudiotabError> {
         let mut active = self.active_devices.lock()
-            .map_err(|e| anyhow::anyhow!("Failed to acquire device lock: {}", e))?;
+            .map_err(|e| AudiotabError::other(format!("Failed to acquire device lock: {}", e)))?;
 
         let device = active.get_mut(profile_id)
-            .ok_or_else(|| anyhow::anyhow!("Device '{}' not found or not started", profile_id))?;
+            .ok_or_else(|| AudiotabError::device(profile_id, format!("Device '{}' not found or not started", profile_id)))?;
 
         Ok(device.get_channels())
     }
@@ -171,7 +351,7 @@ mod tests {
     use super::*;
     use tempfile::tempdir;
     use crate::hal::drivers::AudioDriver;
-    use crate::hal::types::{DeviceConfig, SampleFormat, ChannelMapping, Calibration};
+    use crate::hal::types::{DeviceConfig, SampleFormat, ChannelMapping, Calibration, LatencyMode};
     use crate::hal::device_profile::{DeviceProfile, DeviceMetadata};
 
     #[tokio::test]
@@ -194,8 +374,11 @@ mod tests {
                 buffer_size: 1024,
                 channel_mapping: ChannelMapping::default(),
                 calibration: Calibration::default(),
+                buffer_count: 2,
+                latency_mode: LatencyMode::LowLatency,
             },
             metadata: DeviceMetadata::default(),
+            frequency_response: None,
         };
 
         manager.add_profile(profile.clone()).unwrap();
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use super::types::{DeviceConfig, SampleFormat, ChannelMapping, Calibration};
+use super::frequency_response::FrequencyResponse;
 
 /// Complete device profile with configuration and metadata
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -21,6 +22,10 @@ pub struct DeviceProfile {
 
     /// Additional metadata
     pub metadata: DeviceMetadata,
+
+    /// Measured microphone response used for input compensation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frequency_response: Option<FrequencyResponse>,
 }
 
 /// Device metadata for organization and tracking
@@ -51,6 +56,7 @@ impl Default for DeviceMetadata {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::super::types::LatencyMode;
     use serde_json;
 
     #[test]
@@ -67,6 +73,8 @@ mod tests {
                 buffer_size: 1024,
                 channel_mapping: ChannelMapping::default(),
                 calibration: Calibration::default(),
+                buffer_count: 2,
+                latency_mode: LatencyMode::LowLatency,
             },
             metadata: DeviceMetadata {
                 description: Some("Main recording mic".to_string()),
@@ -74,6 +82,7 @@ mod tests {
                 created_at: 1701436800,
                 modified_at: 1701436800,
             },
+            frequency_response: None,
         };
 
         let json = serde_json::to_string(&profile).unwrap();
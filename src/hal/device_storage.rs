@@ -70,7 +70,7 @@ impl DeviceStorage {
                 match serde_json::from_str::<DeviceProfile>(&json) {
                     Ok(profile) => profiles.push(profile),
                     Err(e) => {
-                        eprintln!("Warning: Failed to parse device profile at {:?}: {}", path, e);
+                        tracing::warn!(path = %path.display(), "Failed to parse device profile: {}", e);
                     }
                 }
             }
@@ -101,7 +101,7 @@ impl DeviceStorage {
 mod tests {
     use super::*;
     use tempfile::tempdir;
-    use crate::hal::types::{DeviceConfig, SampleFormat, ChannelMapping, Calibration};
+    use crate::hal::types::{DeviceConfig, SampleFormat, ChannelMapping, Calibration, LatencyMode};
     use crate::hal::device_profile::{DeviceProfile, DeviceMetadata};
 
     #[test]
@@ -121,8 +121,11 @@ mod tests {
                 buffer_size: 1024,
                 channel_mapping: ChannelMapping::default(),
                 calibration: Calibration::default(),
+                buffer_count: 2,
+                latency_mode: LatencyMode::LowLatency,
             },
             metadata: DeviceMetadata::default(),
+            frequency_response: None,
         };
 
         storage.save(&profile).unwrap();
@@ -150,8 +153,11 @@ mod tests {
                     buffer_size: 1024,
                     channel_mapping: ChannelMapping::default(),
                     calibration: Calibration::default(),
+                    buffer_count: 2,
+                    latency_mode: LatencyMode::LowLatency,
                 },
                 metadata: DeviceMetadata::default(),
+                frequency_response: None,
             };
             storage.save(&profile).unwrap();
         }
@@ -177,8 +183,11 @@ mod tests {
                 buffer_size: 1024,
                 channel_mapping: ChannelMapping::default(),
                 calibration: Calibration::default(),
+                buffer_count: 2,
+                latency_mode: LatencyMode::LowLatency,
             },
             metadata: DeviceMetadata::default(),
+            frequency_response: None,
         };
 
         // Save and verify it exists
@@ -6,14 +6,79 @@ use crate::hal::types::*;
 use crate::hal::Device;
 use super::audio_device::AudioDevice;
 
-pub struct AudioDriver;
+/// Rates probed against each supported config range
+const STANDARD_SAMPLE_RATES: [u64; 11] = [
+    8000, 11025, 16000, 22050, 32000, 44100, 48000, 88200, 96000, 176400, 192000,
+];
+
+pub struct AudioDriver {
+    auto_correct: bool,
+}
 
 impl AudioDriver {
+    /// Driver that corrects configs to the nearest supported rate and format
     pub fn new() -> Self {
-        Self
+        Self { auto_correct: true }
+    }
+
+    /// Driver that rejects configs the device cannot run with a `CapabilityError`
+    pub fn strict() -> Self {
+        Self { auto_correct: false }
     }
 }
 
+/// Resolve a discovered id ("input-N" / "output-N") to a cpal device
+///
+/// Unknown ids fall back to the default input device.
+fn resolve_device(host: &cpal::Host, device_id: &str) -> Option<(cpal::Device, bool, Option<usize>)> {
+    if let Some(index) = device_id.strip_prefix("input-").and_then(|i| i.parse().ok()) {
+        return host.input_devices().ok()?.nth(index).map(|d| (d, true, Some(index)));
+    }
+    if let Some(index) = device_id.strip_prefix("output-").and_then(|i| i.parse::<usize>().ok()) {
+        return host.output_devices().ok()?.nth(index).map(|d| (d, false, None));
+    }
+    host.default_input_device().map(|d| (d, true, None))
+}
+
+/// Query the rates, formats and channel counts a cpal device supports
+pub fn query_capabilities(device: &cpal::Device, input: bool) -> Result<DeviceCapabilities> {
+    let ranges: Vec<cpal::SupportedStreamConfigRange> = if input {
+        device.supported_input_configs()?.collect()
+    } else {
+        device.supported_output_configs()?.collect()
+    };
+
+    let mut capabilities = DeviceCapabilities {
+        can_input: input,
+        can_output: !input,
+        supported_formats: Vec::new(),
+        supported_sample_rates: Vec::new(),
+        max_channels: 0,
+    };
+    for range in &ranges {
+        let format = match range.sample_format() {
+            cpal::SampleFormat::U8 => Some(SampleFormat::U8),
+            cpal::SampleFormat::I16 => Some(SampleFormat::I16),
+            cpal::SampleFormat::I32 => Some(SampleFormat::I32),
+            cpal::SampleFormat::F32 => Some(SampleFormat::F32),
+            cpal::SampleFormat::F64 => Some(SampleFormat::F64),
+            _ => None,
+        };
+        if let Some(format) = format.filter(|f| !capabilities.supported_formats.contains(f)) {
+            capabilities.supported_formats.push(format);
+        }
+        let (min, max) = (range.min_sample_rate().0 as u64, range.max_sample_rate().0 as u64);
+        for rate in STANDARD_SAMPLE_RATES.into_iter().filter(|r| (min..=max).contains(r)) {
+            if !capabilities.supported_sample_rates.contains(&rate) {
+                capabilities.supported_sample_rates.push(rate);
+            }
+        }
+        capabilities.max_channels = capabilities.max_channels.max(range.channels() as usize);
+    }
+    capabilities.supported_sample_rates.sort_unstable();
+    Ok(capabilities)
+}
+
 #[async_trait]
 impl HardwareDriver for AudioDriver {
     fn driver_id(&self) -> &str {
@@ -63,14 +128,36 @@ impl HardwareDriver for AudioDriver {
         .await?
     }
 
-    fn create_device(&self, _device_id: &str, config: DeviceConfig) -> Result<Box<dyn Device>> {
-        let device = AudioDevice::new(
+    fn create_device(&self, device_id: &str, mut config: DeviceConfig) -> Result<Box<dyn Device>> {
+        let host = cpal::default_host();
+        let resolved = resolve_device(&host, device_id).and_then(|(device, input, index)| {
+            query_capabilities(&device, input).ok().map(|capabilities| (capabilities, index))
+        });
+
+        // Without a queryable device the config is used as-is and fails at start
+        if let Some((capabilities, _)) = &resolved {
+            if self.auto_correct {
+                config = capabilities.negotiate(&config)?;
+            } else {
+                capabilities.validate(&config)?;
+            }
+        }
+
+        let mut device = AudioDevice::new(
             config.name,
             config.sample_rate,
             config.format,
             config.buffer_size,
             config.channel_mapping.physical_channels,
+            config.buffer_count,
+            config.latency_mode,
         )?;
+        if let Some((capabilities, index)) = resolved {
+            device = device.with_capabilities(capabilities);
+            if let Some(index) = index {
+                device = device.with_input_index(index);
+            }
+        }
 
         Ok(Box::new(device))
     }
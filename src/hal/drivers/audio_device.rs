@@ -5,7 +5,7 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use cpal::{Stream, StreamConfig};
-use crate::hal::{Device, DeviceChannels, DeviceCapabilities, PacketBuffer, SampleData, SampleFormat};
+use crate::hal::{Device, DeviceChannels, DeviceCapabilities, LatencyMode, PacketBuffer, SampleData, SampleFormat, TimeBase};
 
 // Wrapper to make Stream Send (it's thread-safe, just not marked Send on all platforms)
 struct SendStream(Stream);
@@ -17,12 +17,14 @@ pub struct AudioDevice {
     format: SampleFormat,
     buffer_size: usize,
     num_channels: usize,
+    latency_mode: LatencyMode,
     filled_tx: Sender<PacketBuffer>,
     filled_rx: Receiver<PacketBuffer>,
     empty_tx: Sender<PacketBuffer>,
     empty_rx: Receiver<PacketBuffer>,
     is_streaming: Arc<AtomicBool>,
     capabilities: DeviceCapabilities,
+    input_index: Option<usize>,
     stream: Option<SendStream>,
 }
 
@@ -33,12 +35,17 @@ impl AudioDevice {
         format: SampleFormat,
         buffer_size: usize,
         num_channels: usize,
+        buffer_count: usize,
+        latency_mode: LatencyMode,
     ) -> Result<Self> {
-        let (filled_tx, filled_rx) = bounded(2);
-        let (empty_tx, empty_rx) = bounded(2);
+        if buffer_count < 2 {
+            anyhow::bail!("buffer_count must be at least 2, got {}", buffer_count);
+        }
+        let (filled_tx, filled_rx) = bounded(buffer_count);
+        let (empty_tx, empty_rx) = bounded(buffer_count);
 
         // Pre-allocate buffers
-        for _ in 0..2 {
+        for _ in 0..buffer_count {
             let buffer = PacketBuffer::new(format, buffer_size, num_channels);
             empty_tx.send(buffer)
                 .map_err(|e| anyhow::anyhow!("Failed to send buffer: {}", e))?;
@@ -58,36 +65,65 @@ impl AudioDevice {
             format,
             buffer_size,
             num_channels,
+            latency_mode,
             filled_tx,
             filled_rx,
             empty_tx,
             empty_rx,
             is_streaming: Arc::new(AtomicBool::new(false)),
             capabilities,
+            input_index: None,
             stream: None,
         })
     }
 
+    /// Replace the default capabilities with ones queried from the device
+    pub fn with_capabilities(mut self, capabilities: DeviceCapabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Open the input device at this enumeration index instead of the default one
+    pub fn with_input_index(mut self, index: usize) -> Self {
+        self.input_index = Some(index);
+        self
+    }
+
     fn start_cpal_stream(&mut self) -> Result<()> {
         let host = cpal::default_host();
-        let device = host.default_input_device()
-            .ok_or_else(|| anyhow::anyhow!("No default input device"))?;
+        let device = match self.input_index {
+            Some(index) => host.input_devices()?.nth(index)
+                .ok_or_else(|| anyhow::anyhow!("Input device {} not found", index))?,
+            None => host.default_input_device()
+                .ok_or_else(|| anyhow::anyhow!("No default input device"))?,
+        };
 
         let config = StreamConfig {
             channels: self.num_channels as u16,
             sample_rate: cpal::SampleRate(self.sample_rate as u32),
-            buffer_size: cpal::BufferSize::Fixed(self.buffer_size as u32),
+            buffer_size: match self.latency_mode {
+                LatencyMode::LowLatency => cpal::BufferSize::Fixed(self.buffer_size as u32),
+                LatencyMode::Safe => cpal::BufferSize::Default,
+            },
         };
 
         let empty_rx = self.empty_rx.clone();
         let filled_tx = self.filled_tx.clone();
         let num_channels = self.num_channels;
+        let time_base = TimeBase::global();
+        let device_name = self.device_name.clone();
 
         let stream = device.build_input_stream(
             &config,
-            move |data: &[f32], _: &cpal::InputCallbackInfo| {
+            move |data: &[f32], info: &cpal::InputCallbackInfo| {
+                // Stamp against the shared clock, backdated by the driver's capture latency
+                let stamps = info.timestamp();
+                let latency = stamps.callback.duration_since(&stamps.capture).unwrap_or_default();
+                let timestamp = Some(time_base.capture_ns(latency));
+
                 // Try to get empty buffer
                 if let Ok(mut buffer) = empty_rx.try_recv() {
+                    buffer.timestamp = timestamp;
                     // Copy audio data
                     if let SampleData::F32(ref mut samples) = buffer.data {
                         let copy_len = data.len().min(samples.len());
@@ -99,7 +135,7 @@ impl AudioDevice {
                     let _ = filled_tx.try_send(buffer);
                 }
             },
-            |err| eprintln!("Audio stream error: {}", err),
+            move |err| tracing::error!(device = %device_name, "Audio stream error: {}", err),
             None,
         )?;
 
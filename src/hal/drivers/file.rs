@@ -0,0 +1,548 @@
+use async_trait::async_trait;
+use anyhow::{bail, Context, Result};
+use crossbeam_channel::{bounded, Receiver, RecvTimeoutError, Sender};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use crate::hal::format_converter::packet_to_frame;
+use crate::hal::traits::HardwareDriver;
+use crate::hal::types::*;
+use crate::hal::{Device, TimeBase};
+use crate::visualization::ring_buffer::{
+    COMMIT_POSITION_OFFSET, HEADER_SIZE as RING_BUFFER_HEADER, MAGIC as RING_BUFFER_MAGIC,
+    WRITE_EPOCH_OFFSET, WRITE_SEQUENCE_OFFSET,
+};
+
+/// Samples per write assumed by version 1 dumps, which had no commit position
+const RING_BUFFER_V1_SAMPLES_PER_WRITE: u64 = 1024;
+
+/// How fast a recording is replayed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ReplaySpeed {
+    /// Packets are paced at the recording's sample rate
+    #[default]
+    Realtime,
+    /// Packets are produced as fast as the consumer returns buffers
+    MaxSpeed,
+}
+
+/// File format for saving a recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordingFormat {
+    /// 32-bit IEEE float WAV
+    Wav,
+    /// `time_s` column followed by one column per channel
+    Csv,
+}
+
+impl RecordingFormat {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "wav" | "wave" => Ok(RecordingFormat::Wav),
+            "csv" => Ok(RecordingFormat::Csv),
+            _ => bail!("Unknown recording format: {}", name),
+        }
+    }
+
+    /// Guess the format from a file extension
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let ext = path.as_ref().extension()?.to_str()?;
+        Self::parse(ext).ok()
+    }
+}
+
+/// A recorded capture held in memory as interleaved samples
+#[derive(Debug, Clone)]
+pub struct Recording {
+    pub sample_rate: u64,
+    pub num_channels: usize,
+    /// Interleaved samples in the file's native format
+    pub data: SampleData,
+}
+
+impl Recording {
+    /// Load a WAV file or a RingBufferWriter dump, detected from the file header
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Failed to read recording {}", path.display()))?;
+        if bytes.starts_with(b"RIFF") {
+            Self::from_wav_bytes(&bytes)
+        } else if bytes.starts_with(RING_BUFFER_MAGIC) {
+            Self::from_ring_buffer_bytes(&bytes)
+        } else {
+            bail!("Unrecognised recording format: {}", path.display())
+        }
+    }
+
+    /// Parse a PCM (8/16/24/32-bit) or IEEE float (32/64-bit) WAV file
+    pub fn from_wav_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+            bail!("Not a RIFF/WAVE file");
+        }
+
+        let mut format = None;
+        let mut data = None;
+        let mut pos = 12;
+        while pos + 8 <= bytes.len() {
+            let id = &bytes[pos..pos + 4];
+            let size = u32::from_le_bytes(bytes[pos + 4..pos + 8].try_into()?) as usize;
+            let body = &bytes[pos + 8..(pos + 8 + size).min(bytes.len())];
+            match id {
+                b"fmt " => format = Some(WavFormat::parse(body)?),
+                b"data" => data = Some(body),
+                _ => {}
+            }
+            pos += 8 + size + (size & 1);
+        }
+
+        let format = format.ok_or_else(|| anyhow::anyhow!("WAV file has no fmt chunk"))?;
+        let data = data.ok_or_else(|| anyhow::anyhow!("WAV file has no data chunk"))?;
+        let usable = data.len() - data.len() % format.block_align();
+        let data = &data[..usable];
+
+        let samples = match (format.audio_format, format.bits_per_sample) {
+            (1, 8) => SampleData::U8(data.to_vec()),
+            (1, 16) => SampleData::I16(data.chunks_exact(2).map(|b| i16::from_le_bytes([b[0], b[1]])).collect()),
+            (1, 24) => SampleData::I24(data.to_vec()),
+            (1, 32) => SampleData::I32(data.chunks_exact(4).map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect()),
+            (3, 32) => SampleData::F32(data.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect()),
+            (3, 64) => SampleData::F64(data.chunks_exact(8).map(|b| f64::from_le_bytes(b.try_into().unwrap())).collect()),
+            (fmt, bits) => bail!("Unsupported WAV encoding: format {} with {} bits", fmt, bits),
+        };
+
+        Ok(Self {
+            sample_rate: format.sample_rate as u64,
+            num_channels: format.channels as usize,
+            data: samples,
+        })
+    }
+
+    /// Parse a RingBufferWriter file, oldest sample first
+    pub fn from_ring_buffer_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < RING_BUFFER_HEADER || !bytes.starts_with(RING_BUFFER_MAGIC) {
+            bail!("Not a ring buffer dump");
+        }
+        let header = |offset: usize| u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap());
+        let version = header(8);
+        let sample_rate = header(16);
+        let num_channels = header(24) as usize;
+        let capacity = header(32) as usize;
+
+        if bytes.len() < RING_BUFFER_HEADER + num_channels * capacity * 8 {
+            bail!("Ring buffer dump is truncated");
+        }
+
+        let written = if version >= 2 {
+            if header(WRITE_EPOCH_OFFSET) % 2 == 1 {
+                bail!("Ring buffer dump was taken during a write");
+            }
+            header(COMMIT_POSITION_OFFSET) as usize
+        } else {
+            (header(WRITE_SEQUENCE_OFFSET) * RING_BUFFER_V1_SAMPLES_PER_WRITE) as usize
+        };
+        let (start, frames) = if written < capacity {
+            (0, written)
+        } else {
+            (written % capacity, capacity)
+        };
+
+        let mut interleaved = Vec::with_capacity(frames * num_channels);
+        for frame in 0..frames {
+            let idx = (start + frame) % capacity;
+            for ch in 0..num_channels {
+                let offset = RING_BUFFER_HEADER + (ch * capacity + idx) * 8;
+                interleaved.push(f64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap()));
+            }
+        }
+
+        Ok(Self {
+            sample_rate,
+            num_channels,
+            data: SampleData::F64(interleaved),
+        })
+    }
+
+    /// Number of sample frames (samples per channel)
+    pub fn frames(&self) -> usize {
+        if self.num_channels == 0 {
+            return 0;
+        }
+        let samples = match &self.data {
+            SampleData::I24(v) => v.len() / 3,
+            SampleData::I16(v) => v.len(),
+            SampleData::I32(v) => v.len(),
+            SampleData::F32(v) => v.len(),
+            SampleData::F64(v) => v.len(),
+            SampleData::U8(v) => v.len(),
+            SampleData::Bytes(v) => v.len(),
+        };
+        samples / self.num_channels
+    }
+
+    /// The last `seconds` of the recording, or all of it if shorter
+    pub fn tail(&self, seconds: f64) -> Self {
+        let count = (seconds.max(0.0) * self.sample_rate as f64).round() as usize;
+        let frames = self.frames();
+        Self {
+            sample_rate: self.sample_rate,
+            num_channels: self.num_channels,
+            data: self.slice(frames.saturating_sub(count), count),
+        }
+    }
+
+    /// Samples per channel, scaled to [-1, 1] like live device input
+    pub fn channels(&self) -> Result<Vec<Vec<f64>>> {
+        let packet = PacketBuffer {
+            data: self.data.clone(),
+            sample_rate: self.sample_rate,
+            num_channels: self.num_channels,
+            timestamp: None,
+            codec: None,
+        };
+        let frame = packet_to_frame(&packet, 0)?;
+        Ok((0..self.num_channels)
+            .map(|ch| frame.payload.get(&format!("ch{}", ch)).map(|v| v.to_vec()).unwrap_or_default())
+            .collect())
+    }
+
+    /// Encode as a 32-bit IEEE float WAV file
+    pub fn to_wav_bytes(&self) -> Result<Vec<u8>> {
+        let channels = self.channels()?;
+        let frames = self.frames();
+        let block_align = (self.num_channels * 4) as u16;
+        let data_len = (frames * block_align as usize) as u32;
+
+        let mut bytes = Vec::with_capacity(44 + data_len as usize);
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&3u16.to_le_bytes());
+        bytes.extend_from_slice(&(self.num_channels as u16).to_le_bytes());
+        bytes.extend_from_slice(&(self.sample_rate as u32).to_le_bytes());
+        bytes.extend_from_slice(&(self.sample_rate as u32 * block_align as u32).to_le_bytes());
+        bytes.extend_from_slice(&block_align.to_le_bytes());
+        bytes.extend_from_slice(&32u16.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_len.to_le_bytes());
+        for frame in 0..frames {
+            for channel in &channels {
+                bytes.extend_from_slice(&(channel[frame] as f32).to_le_bytes());
+            }
+        }
+        Ok(bytes)
+    }
+
+    /// CSV with a `time_s` column and one `ch<N>` column per channel
+    pub fn to_csv_string(&self) -> Result<String> {
+        let channels = self.channels()?;
+        let mut csv = String::from("time_s");
+        for ch in 0..self.num_channels {
+            csv.push_str(&format!(",ch{}", ch));
+        }
+        csv.push('\n');
+        for frame in 0..self.frames() {
+            csv.push_str(&(frame as f64 / self.sample_rate as f64).to_string());
+            for channel in &channels {
+                csv.push(',');
+                csv.push_str(&channel[frame].to_string());
+            }
+            csv.push('\n');
+        }
+        Ok(csv)
+    }
+
+    /// Write the recording to `path`, creating parent directories
+    pub fn save(&self, path: impl AsRef<Path>, format: RecordingFormat) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        let bytes = match format {
+            RecordingFormat::Wav => self.to_wav_bytes()?,
+            RecordingFormat::Csv => self.to_csv_string()?.into_bytes(),
+        };
+        std::fs::write(path, bytes)
+            .with_context(|| format!("Failed to write recording {}", path.display()))
+    }
+
+    /// Interleaved samples for `count` frames starting at `start`
+    pub fn slice(&self, start: usize, count: usize) -> SampleData {
+        let end = (start + count).min(self.frames());
+        let (a, b) = (start * self.num_channels, end * self.num_channels);
+        match &self.data {
+            SampleData::I16(v) => SampleData::I16(v[a..b].to_vec()),
+            SampleData::I24(v) => SampleData::I24(v[a * 3..b * 3].to_vec()),
+            SampleData::I32(v) => SampleData::I32(v[a..b].to_vec()),
+            SampleData::F32(v) => SampleData::F32(v[a..b].to_vec()),
+            SampleData::F64(v) => SampleData::F64(v[a..b].to_vec()),
+            SampleData::U8(v) => SampleData::U8(v[a..b].to_vec()),
+            SampleData::Bytes(v) => SampleData::Bytes(v[a..b].to_vec()),
+        }
+    }
+}
+
+struct WavFormat {
+    audio_format: u16,
+    channels: u16,
+    sample_rate: u32,
+    bits_per_sample: u16,
+}
+
+impl WavFormat {
+    fn parse(body: &[u8]) -> Result<Self> {
+        if body.len() < 16 {
+            bail!("WAV fmt chunk too short");
+        }
+        let u16_at = |offset: usize| u16::from_le_bytes([body[offset], body[offset + 1]]);
+        let mut audio_format = u16_at(0);
+        // WAVE_FORMAT_EXTENSIBLE stores the real format in the sub-format GUID
+        if audio_format == 0xFFFE && body.len() >= 26 {
+            audio_format = u16_at(24);
+        }
+        let format = Self {
+            audio_format,
+            channels: u16_at(2),
+            sample_rate: u32::from_le_bytes(body[4..8].try_into()?),
+            bits_per_sample: u16_at(14),
+        };
+        if format.channels == 0 {
+            bail!("WAV file declares zero channels");
+        }
+        Ok(format)
+    }
+
+    fn block_align(&self) -> usize {
+        self.channels as usize * (self.bits_per_sample as usize / 8).max(1)
+    }
+}
+
+/// A recording made available as a device
+#[derive(Debug, Clone)]
+struct FileSource {
+    path: PathBuf,
+    speed: ReplaySpeed,
+}
+
+/// Driver presenting recorded captures as input devices
+///
+/// Registered files are listed by `discover_devices`; any other device id
+/// is treated as a file path. Replayed packets go through the same
+/// kernel and pipeline paths as live hardware.
+pub struct FileDriver {
+    sources: Mutex<HashMap<String, FileSource>>,
+    default_speed: ReplaySpeed,
+}
+
+impl FileDriver {
+    pub fn new(default_speed: ReplaySpeed) -> Self {
+        Self {
+            sources: Mutex::new(HashMap::new()),
+            default_speed,
+        }
+    }
+
+    /// Register a recording under `device_id`
+    pub fn add_file(&self, device_id: impl Into<String>, path: impl Into<PathBuf>, speed: ReplaySpeed) {
+        self.sources
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(device_id.into(), FileSource { path: path.into(), speed });
+    }
+}
+
+impl Default for FileDriver {
+    fn default() -> Self {
+        Self::new(ReplaySpeed::Realtime)
+    }
+}
+
+#[async_trait]
+impl HardwareDriver for FileDriver {
+    fn driver_id(&self) -> &str {
+        "file"
+    }
+
+    fn hardware_type(&self) -> HardwareType {
+        HardwareType::Acoustic
+    }
+
+    async fn discover_devices(&self) -> Result<Vec<DeviceInfo>> {
+        let sources = self.sources.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut devices: Vec<DeviceInfo> = sources
+            .iter()
+            .map(|(id, source)| DeviceInfo {
+                id: id.clone(),
+                name: format!("{} (Replay)", source.path.display()),
+                hardware_type: HardwareType::Acoustic,
+                driver_id: "file".to_string(),
+            })
+            .collect();
+        devices.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(devices)
+    }
+
+    fn create_device(&self, device_id: &str, config: DeviceConfig) -> Result<Box<dyn Device>> {
+        let source = self
+            .sources
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(device_id)
+            .cloned()
+            .unwrap_or_else(|| FileSource {
+                path: PathBuf::from(device_id),
+                speed: self.default_speed,
+            });
+        let recording = Recording::open(&source.path)?;
+        Ok(Box::new(FileDevice::new(recording, source.speed, &config)?))
+    }
+}
+
+/// Input device replaying a Recording
+pub struct FileDevice {
+    recording: Arc<Recording>,
+    speed: ReplaySpeed,
+    buffer_size: usize,
+    filled_tx: Sender<PacketBuffer>,
+    filled_rx: Receiver<PacketBuffer>,
+    empty_tx: Sender<PacketBuffer>,
+    empty_rx: Receiver<PacketBuffer>,
+    is_streaming: Arc<AtomicBool>,
+    capabilities: DeviceCapabilities,
+    player: Option<JoinHandle<()>>,
+}
+
+impl FileDevice {
+    pub fn new(recording: Recording, speed: ReplaySpeed, config: &DeviceConfig) -> Result<Self> {
+        if config.buffer_count < 2 {
+            bail!("buffer_count must be at least 2, got {}", config.buffer_count);
+        }
+        let buffer_size = config.buffer_size.max(1);
+        let (filled_tx, filled_rx) = bounded(config.buffer_count);
+        let (empty_tx, empty_rx) = bounded(config.buffer_count);
+        for _ in 0..config.buffer_count {
+            empty_tx
+                .send(PacketBuffer::new(config.format, buffer_size, recording.num_channels))
+                .map_err(|e| anyhow::anyhow!("Failed to send buffer: {}", e))?;
+        }
+
+        let capabilities = DeviceCapabilities {
+            can_input: true,
+            can_output: false,
+            supported_formats: vec![config.format],
+            supported_sample_rates: vec![recording.sample_rate],
+            max_channels: recording.num_channels,
+        };
+
+        Ok(Self {
+            recording: Arc::new(recording),
+            speed,
+            buffer_size,
+            filled_tx,
+            filled_rx,
+            empty_tx,
+            empty_rx,
+            is_streaming: Arc::new(AtomicBool::new(false)),
+            capabilities,
+            player: None,
+        })
+    }
+
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+}
+
+/// Emit the recording in `buffer_size`-frame packets until done or stopped
+fn play(
+    recording: Arc<Recording>,
+    speed: ReplaySpeed,
+    buffer_size: usize,
+    empty_rx: Receiver<PacketBuffer>,
+    filled_tx: Sender<PacketBuffer>,
+    running: Arc<AtomicBool>,
+) {
+    let start_ns = TimeBase::global().now_ns();
+    let started = Instant::now();
+    let total = recording.frames();
+    let mut position = 0;
+
+    while position < total && running.load(Ordering::Relaxed) {
+        // Wait for the consumer to hand back a buffer (backpressure keeps replay lossless)
+        let mut packet = match empty_rx.recv_timeout(Duration::from_millis(10)) {
+            Ok(packet) => packet,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+
+        let offset = Duration::from_nanos(position as u64 * 1_000_000_000 / recording.sample_rate);
+        if speed == ReplaySpeed::Realtime {
+            if let Some(wait) = offset.checked_sub(started.elapsed()) {
+                std::thread::sleep(wait);
+            }
+        }
+
+        packet.data = recording.slice(position, buffer_size);
+        packet.sample_rate = recording.sample_rate;
+        packet.num_channels = recording.num_channels;
+        packet.timestamp = Some(start_ns + offset.as_nanos() as u64);
+        position += buffer_size;
+
+        if filled_tx.send(packet).is_err() {
+            break;
+        }
+    }
+
+    running.store(false, Ordering::Relaxed);
+}
+
+#[async_trait]
+impl Device for FileDevice {
+    async fn start(&mut self) -> Result<()> {
+        if self.player.is_some() {
+            return Ok(());
+        }
+        self.is_streaming.store(true, Ordering::Relaxed);
+
+        let recording = self.recording.clone();
+        let (speed, buffer_size) = (self.speed, self.buffer_size);
+        let empty_rx = self.empty_rx.clone();
+        let filled_tx = self.filled_tx.clone();
+        let running = self.is_streaming.clone();
+        self.player = Some(std::thread::spawn(move || {
+            play(recording, speed, buffer_size, empty_rx, filled_tx, running)
+        }));
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<()> {
+        self.is_streaming.store(false, Ordering::Relaxed);
+        if let Some(handle) = self.player.take() {
+            let _ = handle.join();
+        }
+        Ok(())
+    }
+
+    fn get_channels(&mut self) -> DeviceChannels {
+        DeviceChannels {
+            filled_rx: self.filled_rx.clone(),
+            empty_tx: self.empty_tx.clone(),
+        }
+    }
+
+    fn capabilities(&self) -> DeviceCapabilities {
+        self.capabilities.clone()
+    }
+
+    /// False once the whole recording has been delivered
+    fn is_streaming(&self) -> bool {
+        self.is_streaming.load(Ordering::Relaxed)
+    }
+}
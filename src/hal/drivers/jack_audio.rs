@@ -0,0 +1,462 @@
+use async_trait::async_trait;
+use anyhow::Result;
+use crossbeam_channel::{bounded, unbounded, Receiver, Sender};
+use jack::{AudioIn, AudioOut, Client, ClientOptions, ClientStatus, Control, Port, PortFlags, ProcessScope, TransportState};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use crate::hal::traits::HardwareDriver;
+use crate::hal::types::*;
+use crate::hal::{Device, TimeBase};
+use crate::observability::{DeviceHealth, DeviceHealthSnapshot};
+
+const CAPTURE_ID: &str = "capture";
+const PLAYBACK_ID: &str = "playback";
+const AUDIO_PORT_TYPE: &str = "32 bit float mono audio";
+/// Channel count used when the device config does not specify one
+const DEFAULT_JACK_CHANNELS: usize = 2;
+
+/// Driver registering audiotab as a client in the JACK graph
+///
+/// Exposes two devices: `capture` registers `in_N` ports whose audio
+/// feeds the pipeline, and `playback` registers `out_N` ports fed by
+/// AudioOutputNode. Ports are auto-connected to the physical system
+/// ports in order; other routing is done in the JACK graph.
+pub struct JackDriver {
+    client_name: String,
+    auto_connect: bool,
+}
+
+impl JackDriver {
+    pub fn new() -> Self {
+        Self::with_client_name("audiotab")
+    }
+
+    pub fn with_client_name(client_name: impl Into<String>) -> Self {
+        Self {
+            client_name: client_name.into(),
+            auto_connect: true,
+        }
+    }
+
+    /// Disable connecting ports to the physical system ports on start
+    pub fn without_auto_connect(mut self) -> Self {
+        self.auto_connect = false;
+        self
+    }
+}
+
+impl Default for JackDriver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl HardwareDriver for JackDriver {
+    fn driver_id(&self) -> &str {
+        "jack"
+    }
+
+    fn hardware_type(&self) -> HardwareType {
+        HardwareType::Acoustic
+    }
+
+    async fn discover_devices(&self) -> Result<Vec<DeviceInfo>> {
+        // No server (or no libjack) simply means no JACK devices
+        let Ok((client, _)) = Client::new(&format!("{}-probe", self.client_name), ClientOptions::NO_START_SERVER) else {
+            return Ok(Vec::new());
+        };
+        let physical = |flags: PortFlags| client.ports(None, Some(AUDIO_PORT_TYPE), flags | PortFlags::IS_PHYSICAL).len();
+        let sources = physical(PortFlags::IS_OUTPUT);
+        let sinks = physical(PortFlags::IS_INPUT);
+
+        Ok(vec![
+            DeviceInfo {
+                id: CAPTURE_ID.to_string(),
+                name: format!("JACK Capture ({} system ports)", sources),
+                hardware_type: HardwareType::Acoustic,
+                driver_id: "jack".to_string(),
+            },
+            DeviceInfo {
+                id: PLAYBACK_ID.to_string(),
+                name: format!("JACK Playback ({} system ports)", sinks),
+                hardware_type: HardwareType::Acoustic,
+                driver_id: "jack".to_string(),
+            },
+        ])
+    }
+
+    fn create_device(&self, device_id: &str, config: DeviceConfig) -> Result<Box<dyn Device>> {
+        let is_output = match device_id {
+            CAPTURE_ID => false,
+            PLAYBACK_ID => true,
+            _ => anyhow::bail!(
+                "Unknown JACK device '{}': expected '{}' or '{}'",
+                device_id, CAPTURE_ID, PLAYBACK_ID
+            ),
+        };
+        Ok(Box::new(JackDevice::new(&self.client_name, is_output, self.auto_connect, &config)?))
+    }
+}
+
+/// Forwards JACK notifications into device health
+struct JackNotifications {
+    health: Arc<DeviceHealth>,
+    running: Arc<AtomicBool>,
+}
+
+impl jack::NotificationHandler for JackNotifications {
+    fn shutdown(&mut self, status: ClientStatus, reason: &str) {
+        self.running.store(false, Ordering::Relaxed);
+        self.health.record_shutdown(format!("{} ({:?})", reason, status));
+    }
+
+    fn xrun(&mut self, _: &Client) -> Control {
+        self.health.record_xrun();
+        Control::Continue
+    }
+}
+
+/// Convert sample `index` of any packet format to f32
+fn sample_at(data: &SampleData, index: usize) -> f32 {
+    match data {
+        SampleData::I16(v) => v[index] as f32 / 32768.0,
+        SampleData::I24(v) => {
+            let bytes = &v[index * 3..index * 3 + 3];
+            (i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 8) as f32 / 8388608.0
+        }
+        SampleData::I32(v) => v[index] as f32 / 2147483648.0,
+        SampleData::F32(v) => v[index],
+        SampleData::F64(v) => v[index] as f32,
+        SampleData::U8(v) => (v[index] as f32 - 128.0) / 128.0,
+        SampleData::Bytes(_) => 0.0,
+    }
+}
+
+fn sample_len(data: &SampleData) -> usize {
+    match data {
+        SampleData::I24(v) => v.len() / 3,
+        SampleData::I16(v) => v.len(),
+        SampleData::I32(v) => v.len(),
+        SampleData::F32(v) => v.len(),
+        SampleData::F64(v) => v.len(),
+        SampleData::U8(v) | SampleData::Bytes(v) => v.len(),
+    }
+}
+
+fn update_transport(client: &Client, health: &DeviceHealth) {
+    if let Ok(transport) = client.transport().query() {
+        health.set_transport(transport.state == TransportState::Rolling, transport.pos.frame() as u64);
+    }
+}
+
+/// Real-time capture: interleaves port buffers into PacketBuffers
+struct CaptureProcess {
+    ports: Vec<Port<AudioIn>>,
+    buffer_size: usize,
+    sample_rate: u64,
+    empty_rx: Receiver<PacketBuffer>,
+    filled_tx: Sender<PacketBuffer>,
+    current: Option<PacketBuffer>,
+    filled: usize,
+    health: Arc<DeviceHealth>,
+}
+
+impl jack::ProcessHandler for CaptureProcess {
+    fn process(&mut self, client: &Client, ps: &ProcessScope) -> Control {
+        update_transport(client, &self.health);
+        let frames = ps.n_frames() as usize;
+        let channels = self.ports.len();
+        let ns_per_frame = 1e9 / self.sample_rate as f64;
+        let cycle_start_ns = TimeBase::global()
+            .now_ns()
+            .saturating_sub((ps.frames_since_cycle_start() as f64 * ns_per_frame) as u64);
+
+        let mut offset = 0;
+        while offset < frames {
+            if self.current.is_none() {
+                let Ok(mut packet) = self.empty_rx.try_recv() else {
+                    // Pipeline is behind; the rest of this cycle is lost
+                    self.health.record_dropped_cycle();
+                    return Control::Continue;
+                };
+                packet.sample_rate = self.sample_rate;
+                packet.num_channels = channels;
+                packet.timestamp = Some(cycle_start_ns + (offset as f64 * ns_per_frame) as u64);
+                if !matches!(&packet.data, SampleData::F32(v) if v.len() == self.buffer_size * channels) {
+                    packet.data = SampleData::F32(vec![0.0; self.buffer_size * channels]);
+                }
+                self.current = Some(packet);
+                self.filled = 0;
+            }
+
+            let take = (self.buffer_size - self.filled).min(frames - offset);
+            if let Some(SampleData::F32(data)) = self.current.as_mut().map(|p| &mut p.data) {
+                for (ch, port) in self.ports.iter().enumerate() {
+                    let src = &port.as_slice(ps)[offset..offset + take];
+                    for (i, &sample) in src.iter().enumerate() {
+                        data[(self.filled + i) * channels + ch] = sample;
+                    }
+                }
+            }
+            self.filled += take;
+            offset += take;
+
+            if self.filled == self.buffer_size {
+                if let Some(packet) = self.current.take() {
+                    let _ = self.filled_tx.try_send(packet);
+                }
+            }
+        }
+        Control::Continue
+    }
+}
+
+/// Real-time playback: de-interleaves queued packets into port buffers
+struct PlaybackProcess {
+    ports: Vec<Port<AudioOut>>,
+    write_rx: Receiver<PacketBuffer>,
+    returned_tx: Sender<PacketBuffer>,
+    current: Option<PacketBuffer>,
+    position: usize,
+    health: Arc<DeviceHealth>,
+}
+
+impl jack::ProcessHandler for PlaybackProcess {
+    fn process(&mut self, client: &Client, ps: &ProcessScope) -> Control {
+        update_transport(client, &self.health);
+        let frames = ps.n_frames() as usize;
+        let mut offset = 0;
+        while offset < frames {
+            if self.current.is_none() {
+                match self.write_rx.try_recv() {
+                    Ok(packet) => {
+                        self.current = Some(packet);
+                        self.position = 0;
+                    }
+                    Err(_) => {
+                        // Nothing queued: output silence for the rest of the cycle
+                        for port in self.ports.iter_mut() {
+                            port.as_mut_slice(ps)[offset..].fill(0.0);
+                        }
+                        self.health.record_dropped_cycle();
+                        return Control::Continue;
+                    }
+                }
+            }
+
+            let Some(packet) = self.current.as_ref() else {
+                continue;
+            };
+            let channels = packet.num_channels.max(1);
+            let available = sample_len(&packet.data) / channels - self.position;
+            let take = available.min(frames - offset);
+            for (ch, port) in self.ports.iter_mut().enumerate() {
+                let out = &mut port.as_mut_slice(ps)[offset..offset + take];
+                for (i, sample) in out.iter_mut().enumerate() {
+                    *sample = if ch < channels {
+                        sample_at(&packet.data, (self.position + i) * channels + ch)
+                    } else {
+                        0.0
+                    };
+                }
+            }
+            self.position += take;
+            offset += take;
+
+            if take == available {
+                if let Some(packet) = self.current.take() {
+                    let _ = self.returned_tx.try_send(packet);
+                }
+            }
+        }
+        Control::Continue
+    }
+}
+
+enum ActiveClient {
+    Capture(jack::AsyncClient<JackNotifications, CaptureProcess>),
+    Playback(jack::AsyncClient<JackNotifications, PlaybackProcess>),
+}
+
+/// One direction of the audiotab JACK client
+pub struct JackDevice {
+    client_name: String,
+    is_output: bool,
+    auto_connect: bool,
+    num_channels: usize,
+    buffer_size: usize,
+    /// Capture: packets filled by JACK. Playback: consumed packets handed back.
+    filled_tx: Sender<PacketBuffer>,
+    filled_rx: Receiver<PacketBuffer>,
+    /// Capture: empty buffers for JACK to fill. Playback: packets to play.
+    empty_tx: Sender<PacketBuffer>,
+    empty_rx: Receiver<PacketBuffer>,
+    health: Arc<DeviceHealth>,
+    is_streaming: Arc<AtomicBool>,
+    capabilities: DeviceCapabilities,
+    client: Option<ActiveClient>,
+}
+
+impl JackDevice {
+    pub fn new(client_name: &str, is_output: bool, auto_connect: bool, config: &DeviceConfig) -> Result<Self> {
+        if config.buffer_count < 2 {
+            anyhow::bail!("buffer_count must be at least 2, got {}", config.buffer_count);
+        }
+        let num_channels = match config.channel_mapping.physical_channels {
+            0 => DEFAULT_JACK_CHANNELS,
+            n => n,
+        };
+        let buffer_size = config.buffer_size.max(1);
+
+        let ((filled_tx, filled_rx), (empty_tx, empty_rx)) = if is_output {
+            (unbounded(), bounded(config.buffer_count))
+        } else {
+            (bounded(config.buffer_count), bounded(config.buffer_count))
+        };
+        if !is_output {
+            for _ in 0..config.buffer_count {
+                empty_tx
+                    .send(PacketBuffer::new(SampleFormat::F32, buffer_size, num_channels))
+                    .map_err(|e| anyhow::anyhow!("Failed to send buffer: {}", e))?;
+            }
+        }
+
+        let capabilities = DeviceCapabilities {
+            can_input: !is_output,
+            can_output: is_output,
+            supported_formats: vec![SampleFormat::F32],
+            supported_sample_rates: vec![config.sample_rate],
+            max_channels: num_channels,
+        };
+
+        Ok(Self {
+            client_name: client_name.to_string(),
+            is_output,
+            auto_connect,
+            num_channels,
+            buffer_size,
+            filled_tx,
+            filled_rx,
+            empty_tx,
+            empty_rx,
+            health: Arc::new(DeviceHealth::new()),
+            is_streaming: Arc::new(AtomicBool::new(false)),
+            capabilities,
+            client: None,
+        })
+    }
+
+    /// Connect our ports to the physical system ports, in order
+    fn connect_physical(&self, client: &Client, ours: &[String]) {
+        let flags = if self.is_output { PortFlags::IS_INPUT } else { PortFlags::IS_OUTPUT };
+        let system = client.ports(None, Some(AUDIO_PORT_TYPE), flags | PortFlags::IS_PHYSICAL);
+        for (own, other) in ours.iter().zip(system.iter()) {
+            let result = if self.is_output {
+                client.connect_ports_by_name(own, other)
+            } else {
+                client.connect_ports_by_name(other, own)
+            };
+            if let Err(e) = result {
+                tracing::warn!("JACK: failed to connect {} and {}: {}", own, other, e);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Device for JackDevice {
+    async fn start(&mut self) -> Result<()> {
+        if self.client.is_some() {
+            return Ok(());
+        }
+        let suffix = if self.is_output { PLAYBACK_ID } else { CAPTURE_ID };
+        let (client, _) = Client::new(&format!("{}-{}", self.client_name, suffix), ClientOptions::NO_START_SERVER)
+            .map_err(|e| anyhow::anyhow!("Failed to open JACK client: {}", e))?;
+        let sample_rate = client.sample_rate() as u64;
+        self.capabilities.supported_sample_rates = vec![sample_rate];
+
+        let notifications = JackNotifications {
+            health: self.health.clone(),
+            running: self.is_streaming.clone(),
+        };
+        self.is_streaming.store(true, Ordering::Relaxed);
+
+        let active = if self.is_output {
+            let ports = (1..=self.num_channels)
+                .map(|n| client.register_port(&format!("out_{}", n), AudioOut))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            let names: Vec<String> = ports.iter().filter_map(|p| p.name().ok()).collect();
+            let process = PlaybackProcess {
+                ports,
+                write_rx: self.empty_rx.clone(),
+                returned_tx: self.filled_tx.clone(),
+                current: None,
+                position: 0,
+                health: self.health.clone(),
+            };
+            let active = client.activate_async(notifications, process)?;
+            if self.auto_connect {
+                self.connect_physical(active.as_client(), &names);
+            }
+            ActiveClient::Playback(active)
+        } else {
+            let ports = (1..=self.num_channels)
+                .map(|n| client.register_port(&format!("in_{}", n), AudioIn))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            let names: Vec<String> = ports.iter().filter_map(|p| p.name().ok()).collect();
+            let process = CaptureProcess {
+                ports,
+                buffer_size: self.buffer_size,
+                sample_rate,
+                empty_rx: self.empty_rx.clone(),
+                filled_tx: self.filled_tx.clone(),
+                current: None,
+                filled: 0,
+                health: self.health.clone(),
+            };
+            let active = client.activate_async(notifications, process)?;
+            if self.auto_connect {
+                self.connect_physical(active.as_client(), &names);
+            }
+            ActiveClient::Capture(active)
+        };
+        self.client = Some(active);
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<()> {
+        self.is_streaming.store(false, Ordering::Relaxed);
+        match self.client.take() {
+            Some(ActiveClient::Capture(client)) => {
+                client.deactivate()?;
+            }
+            Some(ActiveClient::Playback(client)) => {
+                client.deactivate()?;
+            }
+            None => {}
+        }
+        Ok(())
+    }
+
+    /// Capture: receive packets on `filled_rx` and hand them back on `empty_tx`.
+    /// Playback: send filled packets on `empty_tx` (as with AudioOutputNode).
+    fn get_channels(&mut self) -> DeviceChannels {
+        DeviceChannels {
+            filled_rx: self.filled_rx.clone(),
+            empty_tx: self.empty_tx.clone(),
+        }
+    }
+
+    fn capabilities(&self) -> DeviceCapabilities {
+        self.capabilities.clone()
+    }
+
+    fn is_streaming(&self) -> bool {
+        self.is_streaming.load(Ordering::Relaxed)
+    }
+
+    fn health(&self) -> Option<DeviceHealthSnapshot> {
+        Some(self.health.snapshot())
+    }
+}
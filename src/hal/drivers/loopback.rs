@@ -0,0 +1,251 @@
+use async_trait::async_trait;
+use anyhow::Result;
+use crossbeam_channel::{bounded, unbounded, Receiver, RecvTimeoutError, Sender};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use crate::hal::traits::HardwareDriver;
+use crate::hal::types::*;
+use crate::hal::{Device, TimeBase};
+
+const OUTPUT_SUFFIX: &str = "-output";
+const INPUT_SUFFIX: &str = "-input";
+
+/// Virtual cable: packets written to the output side appear on the input side
+pub struct LoopbackCable {
+    name: String,
+    /// Writer side: filled packets from the output device
+    write_tx: Sender<PacketBuffer>,
+    write_rx: Receiver<PacketBuffer>,
+    /// Reader side: packets delivered to the input device
+    read_tx: Sender<PacketBuffer>,
+    read_rx: Receiver<PacketBuffer>,
+    /// Buffers handed back by the input reader
+    returned_tx: Sender<PacketBuffer>,
+    returned_rx: Receiver<PacketBuffer>,
+    dropped: AtomicU64,
+}
+
+impl LoopbackCable {
+    fn new(name: &str, capacity: usize) -> Self {
+        let (write_tx, write_rx) = unbounded();
+        let (read_tx, read_rx) = bounded(capacity.max(1));
+        let (returned_tx, returned_rx) = unbounded();
+        Self {
+            name: name.to_string(),
+            write_tx,
+            write_rx,
+            read_tx,
+            read_rx,
+            returned_tx,
+            returned_rx,
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Packets discarded because the input side was not keeping up
+    pub fn dropped_packets(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Move written packets to the input side until `running` is cleared
+    fn forward(self: Arc<Self>, running: Arc<AtomicBool>) {
+        let time_base = TimeBase::global();
+        while running.load(Ordering::Relaxed) {
+            // Returned buffers are not reused by writers; release them
+            while self.returned_rx.try_recv().is_ok() {}
+
+            match self.write_rx.recv_timeout(Duration::from_millis(10)) {
+                Ok(mut packet) => {
+                    packet.timestamp = Some(time_base.now_ns());
+                    if self.read_tx.try_send(packet).is_err() {
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    }
+}
+
+/// Driver exposing virtual loopback device pairs
+///
+/// Each cable `<name>` appears as two devices: `<name>-output`, which
+/// accepts packets like a hardware output (e.g. from AudioOutputNode),
+/// and `<name>-input`, which delivers them like a capture device. This
+/// routes one pipeline's output into another without physical hardware.
+pub struct LoopbackDriver {
+    cables: Mutex<HashMap<String, Arc<LoopbackCable>>>,
+}
+
+impl LoopbackDriver {
+    /// Driver with a single cable named "loopback"
+    pub fn new() -> Self {
+        Self::with_cables(&["loopback"])
+    }
+
+    pub fn with_cables(names: &[&str]) -> Self {
+        let driver = Self {
+            cables: Mutex::new(HashMap::new()),
+        };
+        for name in names {
+            driver.cable(name, DEFAULT_BUFFER_COUNT);
+        }
+        driver
+    }
+
+    /// Get or create the cable called `name`
+    pub fn cable(&self, name: &str, capacity: usize) -> Arc<LoopbackCable> {
+        self.cables
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .entry(name.to_string())
+            .or_insert_with(|| Arc::new(LoopbackCable::new(name, capacity)))
+            .clone()
+    }
+
+    fn cable_names(&self) -> Vec<String> {
+        let cables = self.cables.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut names: Vec<String> = cables.keys().cloned().collect();
+        names.sort();
+        names
+    }
+}
+
+impl Default for LoopbackDriver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl HardwareDriver for LoopbackDriver {
+    fn driver_id(&self) -> &str {
+        "loopback"
+    }
+
+    fn hardware_type(&self) -> HardwareType {
+        HardwareType::Acoustic
+    }
+
+    async fn discover_devices(&self) -> Result<Vec<DeviceInfo>> {
+        let mut devices = Vec::new();
+        for name in self.cable_names() {
+            for (suffix, label) in [(OUTPUT_SUFFIX, "Output"), (INPUT_SUFFIX, "Input")] {
+                devices.push(DeviceInfo {
+                    id: format!("{}{}", name, suffix),
+                    name: format!("{} ({})", name, label),
+                    hardware_type: HardwareType::Acoustic,
+                    driver_id: "loopback".to_string(),
+                });
+            }
+        }
+        Ok(devices)
+    }
+
+    fn create_device(&self, device_id: &str, config: DeviceConfig) -> Result<Box<dyn Device>> {
+        if let Some(name) = device_id.strip_suffix(OUTPUT_SUFFIX) {
+            let cable = self.cable(name, config.buffer_count);
+            Ok(Box::new(LoopbackDevice::new(cable, true, &config)))
+        } else if let Some(name) = device_id.strip_suffix(INPUT_SUFFIX) {
+            let cable = self.cable(name, config.buffer_count);
+            Ok(Box::new(LoopbackDevice::new(cable, false, &config)))
+        } else {
+            anyhow::bail!(
+                "Unknown loopback device '{}': expected '<cable>{}' or '<cable>{}'",
+                device_id, OUTPUT_SUFFIX, INPUT_SUFFIX
+            )
+        }
+    }
+}
+
+/// One side of a loopback cable
+pub struct LoopbackDevice {
+    cable: Arc<LoopbackCable>,
+    is_output: bool,
+    capabilities: DeviceCapabilities,
+    is_streaming: Arc<AtomicBool>,
+    forwarder: Option<JoinHandle<()>>,
+}
+
+impl LoopbackDevice {
+    fn new(cable: Arc<LoopbackCable>, is_output: bool, config: &DeviceConfig) -> Self {
+        let capabilities = DeviceCapabilities {
+            can_input: !is_output,
+            can_output: is_output,
+            supported_formats: vec![
+                SampleFormat::I16,
+                SampleFormat::I24,
+                SampleFormat::I32,
+                SampleFormat::F32,
+                SampleFormat::F64,
+                SampleFormat::U8,
+            ],
+            supported_sample_rates: vec![config.sample_rate],
+            max_channels: 64,
+        };
+        Self {
+            cable,
+            is_output,
+            capabilities,
+            is_streaming: Arc::new(AtomicBool::new(false)),
+            forwarder: None,
+        }
+    }
+
+    pub fn cable(&self) -> &Arc<LoopbackCable> {
+        &self.cable
+    }
+}
+
+#[async_trait]
+impl Device for LoopbackDevice {
+    async fn start(&mut self) -> Result<()> {
+        self.is_streaming.store(true, Ordering::Relaxed);
+        if self.is_output && self.forwarder.is_none() {
+            let cable = self.cable.clone();
+            let running = self.is_streaming.clone();
+            self.forwarder = Some(std::thread::spawn(move || cable.forward(running)));
+        }
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<()> {
+        self.is_streaming.store(false, Ordering::Relaxed);
+        if let Some(handle) = self.forwarder.take() {
+            let _ = handle.join();
+        }
+        Ok(())
+    }
+
+    /// Output side: send filled packets on `empty_tx` (as with AudioOutputNode).
+    /// Input side: receive packets on `filled_rx` and hand them back on `empty_tx`.
+    fn get_channels(&mut self) -> DeviceChannels {
+        if self.is_output {
+            DeviceChannels {
+                filled_rx: self.cable.returned_rx.clone(),
+                empty_tx: self.cable.write_tx.clone(),
+            }
+        } else {
+            DeviceChannels {
+                filled_rx: self.cable.read_rx.clone(),
+                empty_tx: self.cable.returned_tx.clone(),
+            }
+        }
+    }
+
+    fn capabilities(&self) -> DeviceCapabilities {
+        self.capabilities.clone()
+    }
+
+    fn is_streaming(&self) -> bool {
+        self.is_streaming.load(Ordering::Relaxed)
+    }
+}
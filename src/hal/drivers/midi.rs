@@ -0,0 +1,224 @@
+use async_trait::async_trait;
+use anyhow::Result;
+use crossbeam_channel::{bounded, Receiver, Sender};
+use midir::{MidiInput, MidiInputConnection};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use crate::hal::traits::HardwareDriver;
+use crate::hal::types::*;
+use crate::hal::{Device, TimeBase};
+
+/// Channel voice messages used by triggers; channels are numbered 1-16
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MidiMessage {
+    NoteOn { channel: u8, note: u8, velocity: u8 },
+    NoteOff { channel: u8, note: u8 },
+    ControlChange { channel: u8, controller: u8, value: u8 },
+    ProgramChange { channel: u8, program: u8 },
+}
+
+impl MidiMessage {
+    /// Parse one complete message; returns `None` for unsupported or truncated ones
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        let status = *bytes.first()?;
+        let channel = (status & 0x0F) + 1;
+        match (status & 0xF0, bytes.get(1), bytes.get(2)) {
+            (0x90, Some(&note), Some(&0)) | (0x80, Some(&note), Some(_)) => Some(MidiMessage::NoteOff { channel, note }),
+            (0x90, Some(&note), Some(&velocity)) => Some(MidiMessage::NoteOn { channel, note, velocity }),
+            (0xB0, Some(&controller), Some(&value)) => Some(MidiMessage::ControlChange { channel, controller, value }),
+            (0xC0, Some(&program), _) => Some(MidiMessage::ProgramChange { channel, program }),
+            _ => None,
+        }
+    }
+
+    pub fn channel(&self) -> u8 {
+        match *self {
+            MidiMessage::NoteOn { channel, .. }
+            | MidiMessage::NoteOff { channel, .. }
+            | MidiMessage::ControlChange { channel, .. }
+            | MidiMessage::ProgramChange { channel, .. } => channel,
+        }
+    }
+}
+
+/// Driver for MIDI input ports (controllers, footswitches, sequencers)
+///
+/// Devices are identified by port name. Each received message becomes one
+/// `SampleData::Bytes` packet holding the raw message bytes.
+pub struct MidiDriver {
+    client_name: String,
+}
+
+impl MidiDriver {
+    pub fn new() -> Self {
+        Self {
+            client_name: "audiotab".to_string(),
+        }
+    }
+}
+
+impl Default for MidiDriver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl HardwareDriver for MidiDriver {
+    fn driver_id(&self) -> &str {
+        "midi"
+    }
+
+    async fn discover_devices(&self) -> Result<Vec<DeviceInfo>> {
+        // No MIDI subsystem (e.g. no ALSA sequencer) means no devices
+        let Ok(input) = MidiInput::new(&format!("{}-probe", self.client_name)) else {
+            return Ok(Vec::new());
+        };
+        Ok(input
+            .ports()
+            .iter()
+            .filter_map(|port| input.port_name(port).ok())
+            .map(|name| DeviceInfo {
+                id: name.clone(),
+                name,
+                hardware_type: HardwareType::Special,
+                driver_id: "midi".to_string(),
+            })
+            .collect())
+    }
+
+    fn create_device(&self, device_id: &str, config: DeviceConfig) -> Result<Box<dyn Device>> {
+        Ok(Box::new(MidiDevice::new(&self.client_name, device_id, &config)?))
+    }
+}
+
+/// MIDI input port delivering one packet per message
+pub struct MidiDevice {
+    client_name: String,
+    port_name: String,
+    filled_tx: Sender<PacketBuffer>,
+    filled_rx: Receiver<PacketBuffer>,
+    empty_tx: Sender<PacketBuffer>,
+    empty_rx: Receiver<PacketBuffer>,
+    dropped_messages: Arc<AtomicU64>,
+    is_streaming: Arc<AtomicBool>,
+    capabilities: DeviceCapabilities,
+    connection: Option<MidiInputConnection<()>>,
+}
+
+impl MidiDevice {
+    pub fn new(client_name: &str, port_name: &str, config: &DeviceConfig) -> Result<Self> {
+        if config.buffer_count < 2 {
+            anyhow::bail!("buffer_count must be at least 2, got {}", config.buffer_count);
+        }
+        let (filled_tx, filled_rx) = bounded(config.buffer_count);
+        let (empty_tx, empty_rx) = bounded(config.buffer_count);
+        for _ in 0..config.buffer_count {
+            let packet = PacketBuffer {
+                data: SampleData::Bytes(Vec::with_capacity(3)),
+                sample_rate: config.sample_rate,
+                num_channels: 1,
+                timestamp: None,
+                codec: None,
+            };
+            empty_tx
+                .send(packet)
+                .map_err(|e| anyhow::anyhow!("Failed to send buffer: {}", e))?;
+        }
+
+        let capabilities = DeviceCapabilities {
+            can_input: true,
+            can_output: false,
+            supported_formats: Vec::new(),
+            supported_sample_rates: Vec::new(),
+            max_channels: 16,
+        };
+
+        Ok(Self {
+            client_name: client_name.to_string(),
+            port_name: port_name.to_string(),
+            filled_tx,
+            filled_rx,
+            empty_tx,
+            empty_rx,
+            dropped_messages: Arc::new(AtomicU64::new(0)),
+            is_streaming: Arc::new(AtomicBool::new(false)),
+            capabilities,
+            connection: None,
+        })
+    }
+
+    /// Messages discarded because no empty buffer was available
+    pub fn dropped_messages(&self) -> u64 {
+        self.dropped_messages.load(Ordering::Relaxed)
+    }
+}
+
+#[async_trait]
+impl Device for MidiDevice {
+    async fn start(&mut self) -> Result<()> {
+        if self.connection.is_some() {
+            return Ok(());
+        }
+        let input = MidiInput::new(&self.client_name)
+            .map_err(|e| anyhow::anyhow!("Failed to open MIDI input: {}", e))?;
+        let port = input
+            .ports()
+            .into_iter()
+            .find(|port| input.port_name(port).ok().as_deref() == Some(self.port_name.as_str()))
+            .ok_or_else(|| anyhow::anyhow!("MIDI port '{}' not found", self.port_name))?;
+
+        let empty_rx = self.empty_rx.clone();
+        let filled_tx = self.filled_tx.clone();
+        let dropped = self.dropped_messages.clone();
+        let connection = input
+            .connect(
+                &port,
+                "audiotab-in",
+                move |_, message, _| {
+                    let Ok(mut packet) = empty_rx.try_recv() else {
+                        dropped.fetch_add(1, Ordering::Relaxed);
+                        return;
+                    };
+                    match &mut packet.data {
+                        SampleData::Bytes(bytes) => {
+                            bytes.clear();
+                            bytes.extend_from_slice(message);
+                        }
+                        data => *data = SampleData::Bytes(message.to_vec()),
+                    }
+                    packet.timestamp = Some(TimeBase::global().now_ns());
+                    let _ = filled_tx.try_send(packet);
+                },
+                (),
+            )
+            .map_err(|e| anyhow::anyhow!("Failed to connect MIDI port '{}': {}", self.port_name, e))?;
+
+        self.connection = Some(connection);
+        self.is_streaming.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<()> {
+        self.is_streaming.store(false, Ordering::Relaxed);
+        if let Some(connection) = self.connection.take() {
+            connection.close();
+        }
+        Ok(())
+    }
+
+    fn get_channels(&mut self) -> DeviceChannels {
+        DeviceChannels {
+            filled_rx: self.filled_rx.clone(),
+            empty_tx: self.empty_tx.clone(),
+        }
+    }
+
+    fn capabilities(&self) -> DeviceCapabilities {
+        self.capabilities.clone()
+    }
+
+    fn is_streaming(&self) -> bool {
+        self.is_streaming.load(Ordering::Relaxed)
+    }
+}
@@ -1,5 +1,23 @@
 pub mod audio;
 pub mod audio_device;
+pub mod file;
+#[cfg(feature = "jack")]
+pub mod jack_audio;
+pub mod loopback;
+pub mod midi;
+pub mod network;
+pub mod serial;
+pub mod simulated;
 
 pub use audio::AudioDriver;
 pub use audio_device::AudioDevice;
+pub use file::{FileDevice, FileDriver, Recording, RecordingFormat, ReplaySpeed};
+#[cfg(feature = "jack")]
+pub use jack_audio::{JackDevice, JackDriver};
+pub use loopback::{LoopbackCable, LoopbackDevice, LoopbackDriver};
+pub use midi::{MidiDevice, MidiDriver, MidiMessage};
+pub use network::{
+    NetworkDevice, NetworkDriver, NetworkEncoding, NetworkPayload, NetworkStatsSnapshot, NetworkStreamConfig,
+};
+pub use serial::{SerialDevice, SerialDriver, SerialPortConfig};
+pub use simulated::{Fault, SimulatedDevice, SimulatedDriver, SimulatedSignal, SimulatedStream};
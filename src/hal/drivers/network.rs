@@ -0,0 +1,452 @@
+use async_trait::async_trait;
+use anyhow::{bail, Result};
+use crossbeam_channel::{bounded, Receiver, Sender};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use crate::hal::traits::HardwareDriver;
+use crate::hal::types::*;
+use crate::hal::{Device, TimeBase};
+
+/// Longest sequence gap that is concealed; larger gaps are treated as a stream restart
+const MAX_CONCEALED_PACKETS: u16 = 64;
+
+/// Framing of incoming datagrams
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum NetworkPayload {
+    /// RTP packets (AES67, RAVENNA, Dante AES67 mode)
+    #[default]
+    Rtp,
+    /// Bare PCM datagrams without a header
+    RawPcm,
+}
+
+/// Sample encoding inside the payload
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum NetworkEncoding {
+    /// 16-bit big-endian PCM
+    L16,
+    /// 24-bit big-endian PCM (AES67 default)
+    #[default]
+    L24,
+    /// 32-bit little-endian float
+    F32Le,
+}
+
+impl NetworkEncoding {
+    fn bytes_per_sample(self) -> usize {
+        match self {
+            NetworkEncoding::L16 => 2,
+            NetworkEncoding::L24 => 3,
+            NetworkEncoding::F32Le => 4,
+        }
+    }
+
+    fn decode(self, bytes: &[u8]) -> f32 {
+        match self {
+            NetworkEncoding::L16 => i16::from_be_bytes([bytes[0], bytes[1]]) as f32 / 32768.0,
+            NetworkEncoding::L24 => {
+                let value = i32::from_be_bytes([bytes[0], bytes[1], bytes[2], 0]) >> 8;
+                value as f32 / 8388608.0
+            }
+            NetworkEncoding::F32Le => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        }
+    }
+}
+
+/// A network stream exposed as a device
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkStreamConfig {
+    /// Local address to receive on, e.g. `0.0.0.0:5004`
+    pub bind: SocketAddr,
+    /// Multicast group to join (AES67 streams are usually multicast)
+    #[serde(default)]
+    pub multicast_group: Option<Ipv4Addr>,
+    #[serde(default)]
+    pub payload: NetworkPayload,
+    #[serde(default)]
+    pub encoding: NetworkEncoding,
+    pub sample_rate: u64,
+    pub channels: usize,
+}
+
+/// Parsed RTP packet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RtpPacket<'a> {
+    pub sequence: u16,
+    pub timestamp: u32,
+    pub ssrc: u32,
+    pub payload: &'a [u8],
+}
+
+/// Parse an RTP datagram (RFC 3550), skipping CSRCs, extensions and padding
+pub fn parse_rtp(datagram: &[u8]) -> Result<RtpPacket<'_>> {
+    if datagram.len() < 12 {
+        bail!("RTP packet too short: {} bytes", datagram.len());
+    }
+    if datagram[0] >> 6 != 2 {
+        bail!("Unsupported RTP version {}", datagram[0] >> 6);
+    }
+    let has_padding = datagram[0] & 0x20 != 0;
+    let has_extension = datagram[0] & 0x10 != 0;
+    let csrc_count = (datagram[0] & 0x0F) as usize;
+
+    let mut start = 12 + csrc_count * 4;
+    if has_extension {
+        if datagram.len() < start + 4 {
+            bail!("Truncated RTP header extension");
+        }
+        let words = u16::from_be_bytes([datagram[start + 2], datagram[start + 3]]) as usize;
+        start += 4 + words * 4;
+    }
+    let mut end = datagram.len();
+    if has_padding {
+        end = end.saturating_sub(*datagram.last().unwrap_or(&0) as usize);
+    }
+    if start > end {
+        bail!("Truncated RTP packet");
+    }
+
+    Ok(RtpPacket {
+        sequence: u16::from_be_bytes([datagram[2], datagram[3]]),
+        timestamp: u32::from_be_bytes([datagram[4], datagram[5], datagram[6], datagram[7]]),
+        ssrc: u32::from_be_bytes([datagram[8], datagram[9], datagram[10], datagram[11]]),
+        payload: &datagram[start..end],
+    })
+}
+
+/// Reception counters for one network stream
+#[derive(Debug, Default)]
+pub struct NetworkStats {
+    packets_received: AtomicU64,
+    packets_lost: AtomicU64,
+    packets_late: AtomicU64,
+    packets_malformed: AtomicU64,
+    samples_concealed: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkStatsSnapshot {
+    pub packets_received: u64,
+    /// Packets missing from the RTP sequence
+    pub packets_lost: u64,
+    /// Packets that arrived after a later packet and were discarded
+    pub packets_late: u64,
+    pub packets_malformed: u64,
+    /// Sample frames synthesized to cover lost packets
+    pub samples_concealed: u64,
+}
+
+impl NetworkStats {
+    pub fn snapshot(&self) -> NetworkStatsSnapshot {
+        NetworkStatsSnapshot {
+            packets_received: self.packets_received.load(Ordering::Relaxed),
+            packets_lost: self.packets_lost.load(Ordering::Relaxed),
+            packets_late: self.packets_late.load(Ordering::Relaxed),
+            packets_malformed: self.packets_malformed.load(Ordering::Relaxed),
+            samples_concealed: self.samples_concealed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Driver receiving RTP/AES67 or raw UDP PCM streams
+pub struct NetworkDriver {
+    streams: Mutex<HashMap<String, (NetworkStreamConfig, Arc<NetworkStats>)>>,
+}
+
+impl NetworkDriver {
+    pub fn new() -> Self {
+        Self {
+            streams: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Register a stream under `device_id`
+    pub fn add_stream(&self, device_id: impl Into<String>, config: NetworkStreamConfig) {
+        self.streams
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(device_id.into(), (config, Arc::new(NetworkStats::default())));
+    }
+
+    /// Reception counters for a registered stream
+    pub fn stats(&self, device_id: &str) -> Option<NetworkStatsSnapshot> {
+        self.streams
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(device_id)
+            .map(|(_, stats)| stats.snapshot())
+    }
+}
+
+impl Default for NetworkDriver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl HardwareDriver for NetworkDriver {
+    fn driver_id(&self) -> &str {
+        "network"
+    }
+
+    fn hardware_type(&self) -> HardwareType {
+        HardwareType::Acoustic
+    }
+
+    async fn discover_devices(&self) -> Result<Vec<DeviceInfo>> {
+        let streams = self.streams.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut devices: Vec<DeviceInfo> = streams
+            .iter()
+            .map(|(id, (config, _))| DeviceInfo {
+                id: id.clone(),
+                name: match config.multicast_group {
+                    Some(group) => format!("{}:{} ({:?})", group, config.bind.port(), config.payload),
+                    None => format!("{} ({:?})", config.bind, config.payload),
+                },
+                hardware_type: HardwareType::Acoustic,
+                driver_id: "network".to_string(),
+            })
+            .collect();
+        devices.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(devices)
+    }
+
+    fn create_device(&self, device_id: &str, config: DeviceConfig) -> Result<Box<dyn Device>> {
+        let (stream, stats) = self
+            .streams
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(device_id)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Unknown network stream '{}'", device_id))?;
+        Ok(Box::new(NetworkDevice::new(stream, stats, &config)?))
+    }
+}
+
+/// Input device fed by a network stream
+pub struct NetworkDevice {
+    stream: NetworkStreamConfig,
+    stats: Arc<NetworkStats>,
+    buffer_size: usize,
+    filled_tx: Sender<PacketBuffer>,
+    filled_rx: Receiver<PacketBuffer>,
+    empty_tx: Sender<PacketBuffer>,
+    empty_rx: Receiver<PacketBuffer>,
+    is_streaming: Arc<AtomicBool>,
+    capabilities: DeviceCapabilities,
+    receiver: Option<JoinHandle<()>>,
+}
+
+impl NetworkDevice {
+    pub fn new(stream: NetworkStreamConfig, stats: Arc<NetworkStats>, config: &DeviceConfig) -> Result<Self> {
+        if stream.channels == 0 {
+            bail!("Network stream must have at least one channel");
+        }
+        if config.buffer_count < 2 {
+            bail!("buffer_count must be at least 2, got {}", config.buffer_count);
+        }
+        let buffer_size = config.buffer_size.max(1);
+        let (filled_tx, filled_rx) = bounded(config.buffer_count);
+        let (empty_tx, empty_rx) = bounded(config.buffer_count);
+        for _ in 0..config.buffer_count {
+            empty_tx
+                .send(PacketBuffer::new(SampleFormat::F32, buffer_size, stream.channels))
+                .map_err(|e| anyhow::anyhow!("Failed to send buffer: {}", e))?;
+        }
+
+        let capabilities = DeviceCapabilities {
+            can_input: true,
+            can_output: false,
+            supported_formats: vec![SampleFormat::F32],
+            supported_sample_rates: vec![stream.sample_rate],
+            max_channels: stream.channels,
+        };
+
+        Ok(Self {
+            stream,
+            stats,
+            buffer_size,
+            filled_tx,
+            filled_rx,
+            empty_tx,
+            empty_rx,
+            is_streaming: Arc::new(AtomicBool::new(false)),
+            capabilities,
+            receiver: None,
+        })
+    }
+
+    pub fn stats(&self) -> NetworkStatsSnapshot {
+        self.stats.snapshot()
+    }
+
+    fn bind(&self) -> Result<UdpSocket> {
+        let socket = UdpSocket::bind(self.stream.bind)?;
+        if let Some(group) = self.stream.multicast_group {
+            let interface = match self.stream.bind.ip() {
+                std::net::IpAddr::V4(ip) => ip,
+                std::net::IpAddr::V6(_) => Ipv4Addr::UNSPECIFIED,
+            };
+            socket.join_multicast_v4(&group, &interface)?;
+        }
+        socket.set_read_timeout(Some(Duration::from_millis(20)))?;
+        Ok(socket)
+    }
+}
+
+/// Collects decoded frames into fixed-size PacketBuffers
+struct Assembler {
+    stream: NetworkStreamConfig,
+    buffer_size: usize,
+    empty_rx: Receiver<PacketBuffer>,
+    filled_tx: Sender<PacketBuffer>,
+    pending: Vec<f32>,
+    pending_timestamp: Option<u64>,
+    last_payload: Vec<f32>,
+    last_sequence: Option<u16>,
+    stats: Arc<NetworkStats>,
+}
+
+impl Assembler {
+    fn receive(&mut self, datagram: &[u8]) {
+        let payload = match self.stream.payload {
+            NetworkPayload::Rtp => match parse_rtp(datagram) {
+                Ok(rtp) => {
+                    if !self.check_sequence(rtp.sequence) {
+                        return;
+                    }
+                    rtp.payload
+                }
+                Err(_) => {
+                    self.stats.packets_malformed.fetch_add(1, Ordering::Relaxed);
+                    return;
+                }
+            },
+            NetworkPayload::RawPcm => datagram,
+        };
+        self.stats.packets_received.fetch_add(1, Ordering::Relaxed);
+
+        let width = self.stream.encoding.bytes_per_sample();
+        let usable = payload.len() - payload.len() % (width * self.stream.channels);
+        let samples: Vec<f32> = payload[..usable]
+            .chunks_exact(width)
+            .map(|bytes| self.stream.encoding.decode(bytes))
+            .collect();
+        self.push(&samples);
+        self.last_payload = samples;
+    }
+
+    /// Track RTP sequence numbers; conceals gaps and returns false for late packets
+    fn check_sequence(&mut self, sequence: u16) -> bool {
+        let Some(last) = self.last_sequence else {
+            self.last_sequence = Some(sequence);
+            return true;
+        };
+        let delta = sequence.wrapping_sub(last);
+        if delta == 0 || delta > u16::MAX / 2 {
+            self.stats.packets_late.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+
+        let missing = delta - 1;
+        if missing > 0 {
+            self.stats.packets_lost.fetch_add(missing as u64, Ordering::Relaxed);
+            if missing <= MAX_CONCEALED_PACKETS {
+                // Repeat the previous packet for each one lost
+                let previous = std::mem::take(&mut self.last_payload);
+                for _ in 0..missing {
+                    self.push(&previous);
+                }
+                let frames = previous.len() / self.stream.channels * missing as usize;
+                self.stats.samples_concealed.fetch_add(frames as u64, Ordering::Relaxed);
+                self.last_payload = previous;
+            }
+        }
+        self.last_sequence = Some(sequence);
+        true
+    }
+
+    fn push(&mut self, samples: &[f32]) {
+        if self.pending.is_empty() {
+            self.pending_timestamp = Some(TimeBase::global().now_ns());
+        }
+        self.pending.extend_from_slice(samples);
+
+        let packet_len = self.buffer_size * self.stream.channels;
+        while self.pending.len() >= packet_len {
+            let Ok(mut packet) = self.empty_rx.try_recv() else {
+                // Consumer is behind; drop the oldest audio rather than block the socket
+                self.pending.drain(..packet_len);
+                continue;
+            };
+            packet.data = SampleData::F32(self.pending.drain(..packet_len).collect());
+            packet.sample_rate = self.stream.sample_rate;
+            packet.num_channels = self.stream.channels;
+            packet.timestamp = self.pending_timestamp;
+            let _ = self.filled_tx.try_send(packet);
+            self.pending_timestamp = Some(TimeBase::global().now_ns());
+        }
+    }
+}
+
+#[async_trait]
+impl Device for NetworkDevice {
+    async fn start(&mut self) -> Result<()> {
+        if self.receiver.is_some() {
+            return Ok(());
+        }
+        let socket = self.bind()?;
+        self.is_streaming.store(true, Ordering::Relaxed);
+
+        let running = self.is_streaming.clone();
+        let mut assembler = Assembler {
+            stream: self.stream.clone(),
+            buffer_size: self.buffer_size,
+            empty_rx: self.empty_rx.clone(),
+            filled_tx: self.filled_tx.clone(),
+            pending: Vec::new(),
+            pending_timestamp: None,
+            last_payload: Vec::new(),
+            last_sequence: None,
+            stats: self.stats.clone(),
+        };
+        self.receiver = Some(std::thread::spawn(move || {
+            let mut datagram = [0u8; 65536];
+            while running.load(Ordering::Relaxed) {
+                if let Ok(len) = socket.recv(&mut datagram) {
+                    assembler.receive(&datagram[..len]);
+                }
+            }
+        }));
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<()> {
+        self.is_streaming.store(false, Ordering::Relaxed);
+        if let Some(handle) = self.receiver.take() {
+            let _ = handle.join();
+        }
+        Ok(())
+    }
+
+    fn get_channels(&mut self) -> DeviceChannels {
+        DeviceChannels {
+            filled_rx: self.filled_rx.clone(),
+            empty_tx: self.empty_tx.clone(),
+        }
+    }
+
+    fn capabilities(&self) -> DeviceCapabilities {
+        self.capabilities.clone()
+    }
+
+    fn is_streaming(&self) -> bool {
+        self.is_streaming.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,285 @@
+use async_trait::async_trait;
+use anyhow::Result;
+use crossbeam_channel::{bounded, Receiver, Sender};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tokio::task::JoinHandle;
+use tokio_serial::SerialPortBuilderExt;
+use crate::hal::decoder::{BytesDecoder, DecoderRegistry};
+use crate::hal::traits::HardwareDriver;
+use crate::hal::types::*;
+use crate::hal::{Device, TimeBase};
+
+pub const DEFAULT_BAUD_RATE: u32 = 115_200;
+/// Largest read handed to the pipeline as one packet
+const READ_CHUNK_SIZE: usize = 4096;
+
+/// A serial port exposed as a device
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SerialPortConfig {
+    /// OS path, e.g. `/dev/ttyUSB0` or `COM3`
+    pub path: String,
+    pub baud_rate: u32,
+    /// DecoderRegistry id turning the byte stream into channels
+    #[serde(default)]
+    pub decoder: Option<String>,
+}
+
+impl SerialPortConfig {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            baud_rate: DEFAULT_BAUD_RATE,
+            decoder: None,
+        }
+    }
+
+    pub fn with_baud_rate(mut self, baud_rate: u32) -> Self {
+        self.baud_rate = baud_rate;
+        self
+    }
+
+    pub fn with_decoder(mut self, decoder: impl Into<String>) -> Self {
+        self.decoder = Some(decoder.into());
+        self
+    }
+}
+
+/// Driver for serial-attached DAQ hardware (accelerometers, tachometers, ...)
+///
+/// Devices produce raw `SampleData::Bytes` packets. A port configured with
+/// a decoder id reports it through `Device::bytes_decoder`, which the
+/// kernel uses to turn the bytes into numeric channels, and names it as
+/// the `codec` of every packet for consumers reading packets directly.
+pub struct SerialDriver {
+    ports: Mutex<HashMap<String, SerialPortConfig>>,
+}
+
+impl SerialDriver {
+    pub fn new() -> Self {
+        Self {
+            ports: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Register a port under `device_id`
+    pub fn add_port(&self, device_id: impl Into<String>, config: SerialPortConfig) {
+        self.ports
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(device_id.into(), config);
+    }
+}
+
+impl Default for SerialDriver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl HardwareDriver for SerialDriver {
+    fn driver_id(&self) -> &str {
+        "serial"
+    }
+
+    fn hardware_type(&self) -> HardwareType {
+        HardwareType::Special
+    }
+
+    async fn discover_devices(&self) -> Result<Vec<DeviceInfo>> {
+        let mut devices: Vec<DeviceInfo> = {
+            let ports = self.ports.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            ports
+                .iter()
+                .map(|(id, config)| DeviceInfo {
+                    id: id.clone(),
+                    name: format!("{} @ {} baud", config.path, config.baud_rate),
+                    hardware_type: HardwareType::Special,
+                    driver_id: "serial".to_string(),
+                })
+                .collect()
+        };
+
+        // Unregistered system ports are listed by path
+        for port in tokio_serial::available_ports().unwrap_or_default() {
+            if devices.iter().all(|d| d.id != port.port_name) {
+                devices.push(DeviceInfo {
+                    id: port.port_name.clone(),
+                    name: port.port_name,
+                    hardware_type: HardwareType::Special,
+                    driver_id: "serial".to_string(),
+                });
+            }
+        }
+        devices.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(devices)
+    }
+
+    /// Unregistered `device_id`s are opened as paths at the default baud rate
+    fn create_device(&self, device_id: &str, config: DeviceConfig) -> Result<Box<dyn Device>> {
+        let port = self
+            .ports
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(device_id)
+            .cloned()
+            .unwrap_or_else(|| SerialPortConfig::new(device_id));
+        Ok(Box::new(SerialDevice::new(port, &config)?))
+    }
+}
+
+/// Serial port producing raw byte packets
+pub struct SerialDevice {
+    port: SerialPortConfig,
+    sample_rate: u64,
+    filled_tx: Sender<PacketBuffer>,
+    filled_rx: Receiver<PacketBuffer>,
+    empty_tx: Sender<PacketBuffer>,
+    empty_rx: Receiver<PacketBuffer>,
+    dropped_bytes: Arc<AtomicU64>,
+    is_streaming: Arc<AtomicBool>,
+    capabilities: DeviceCapabilities,
+    reader: Option<JoinHandle<()>>,
+}
+
+impl SerialDevice {
+    pub fn new(port: SerialPortConfig, config: &DeviceConfig) -> Result<Self> {
+        if config.buffer_count < 2 {
+            anyhow::bail!("buffer_count must be at least 2, got {}", config.buffer_count);
+        }
+        if let Some(decoder) = &port.decoder {
+            if !DecoderRegistry::global().contains(decoder) {
+                anyhow::bail!("Unknown bytes decoder '{}' for serial port {}", decoder, port.path);
+            }
+        }
+
+        let (filled_tx, filled_rx) = bounded(config.buffer_count);
+        let (empty_tx, empty_rx) = bounded(config.buffer_count);
+        for _ in 0..config.buffer_count {
+            let packet = PacketBuffer {
+                data: SampleData::Bytes(Vec::with_capacity(READ_CHUNK_SIZE)),
+                sample_rate: config.sample_rate,
+                num_channels: 1,
+                timestamp: None,
+                codec: port.decoder.clone(),
+            };
+            empty_tx
+                .send(packet)
+                .map_err(|e| anyhow::anyhow!("Failed to send buffer: {}", e))?;
+        }
+
+        let capabilities = DeviceCapabilities {
+            can_input: true,
+            can_output: false,
+            supported_formats: Vec::new(),
+            supported_sample_rates: vec![config.sample_rate],
+            max_channels: 1,
+        };
+
+        Ok(Self {
+            port,
+            sample_rate: config.sample_rate,
+            filled_tx,
+            filled_rx,
+            empty_tx,
+            empty_rx,
+            dropped_bytes: Arc::new(AtomicU64::new(0)),
+            is_streaming: Arc::new(AtomicBool::new(false)),
+            capabilities,
+            reader: None,
+        })
+    }
+
+    pub fn port(&self) -> &SerialPortConfig {
+        &self.port
+    }
+
+    /// Bytes discarded because no empty buffer was available
+    pub fn dropped_bytes(&self) -> u64 {
+        self.dropped_bytes.load(Ordering::Relaxed)
+    }
+}
+
+#[async_trait]
+impl Device for SerialDevice {
+    async fn start(&mut self) -> Result<()> {
+        if self.reader.is_some() {
+            return Ok(());
+        }
+        let mut stream = tokio_serial::new(&self.port.path, self.port.baud_rate)
+            .open_native_async()
+            .map_err(|e| anyhow::anyhow!("Failed to open serial port {}: {}", self.port.path, e))?;
+        self.is_streaming.store(true, Ordering::Relaxed);
+
+        let running = self.is_streaming.clone();
+        let empty_rx = self.empty_rx.clone();
+        let filled_tx = self.filled_tx.clone();
+        let dropped_bytes = self.dropped_bytes.clone();
+        let sample_rate = self.sample_rate;
+        let path = self.port.path.clone();
+        self.reader = Some(tokio::spawn(async move {
+            let mut chunk = vec![0u8; READ_CHUNK_SIZE];
+            while running.load(Ordering::Relaxed) {
+                let len = match tokio::time::timeout(Duration::from_millis(50), stream.read(&mut chunk)).await {
+                    Ok(Ok(0)) => break,
+                    Ok(Ok(len)) => len,
+                    Ok(Err(e)) => {
+                        tracing::error!(device = %path, "Serial port read failed: {}", e);
+                        break;
+                    }
+                    Err(_) => continue,
+                };
+
+                let Ok(mut packet) = empty_rx.try_recv() else {
+                    dropped_bytes.fetch_add(len as u64, Ordering::Relaxed);
+                    continue;
+                };
+                match &mut packet.data {
+                    SampleData::Bytes(bytes) => {
+                        bytes.clear();
+                        bytes.extend_from_slice(&chunk[..len]);
+                    }
+                    data => *data = SampleData::Bytes(chunk[..len].to_vec()),
+                }
+                packet.sample_rate = sample_rate;
+                packet.timestamp = Some(TimeBase::global().now_ns());
+                let _ = filled_tx.try_send(packet);
+            }
+            running.store(false, Ordering::Relaxed);
+        }));
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<()> {
+        self.is_streaming.store(false, Ordering::Relaxed);
+        if let Some(handle) = self.reader.take() {
+            let _ = handle.await;
+        }
+        Ok(())
+    }
+
+    fn get_channels(&mut self) -> DeviceChannels {
+        DeviceChannels {
+            filled_rx: self.filled_rx.clone(),
+            empty_tx: self.empty_tx.clone(),
+        }
+    }
+
+    fn capabilities(&self) -> DeviceCapabilities {
+        self.capabilities.clone()
+    }
+
+    fn is_streaming(&self) -> bool {
+        self.is_streaming.load(Ordering::Relaxed)
+    }
+
+    fn bytes_decoder(&self) -> Option<Box<dyn BytesDecoder>> {
+        let name = self.port.decoder.as_deref()?;
+        DecoderRegistry::global().create(name).ok()
+    }
+}
@@ -0,0 +1,429 @@
+use async_trait::async_trait;
+use anyhow::Result;
+use crossbeam_channel::{bounded, unbounded, Receiver, SendTimeoutError, Sender};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use crate::core::DataFrame;
+use crate::hal::format_converter::frame_to_packet;
+use crate::hal::traits::HardwareDriver;
+use crate::hal::types::*;
+use crate::hal::{Device, TimeBase};
+use crate::observability::{DeviceHealth, DeviceHealthSnapshot};
+
+/// How long `emit` waits for the reader before counting a packet as overrun
+const SEND_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Fault injected into a simulated stream
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fault {
+    /// Lose the next `count` packets, as a capture overrun would; each is
+    /// reported as an xrun and its samples never arrive
+    DropPackets(usize),
+    /// Deliver packets in another sample format from here on
+    FormatChange(SampleFormat),
+    /// Close the stream, as an unplugged device does
+    Disconnect,
+    /// Shift the timestamps of this and later packets by this many nanoseconds
+    TimestampJump(i64),
+}
+
+/// Deterministic test signal: channel `c` is a sine at `frequency_hz * (c + 1)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulatedSignal {
+    pub channels: usize,
+    pub frequency_hz: f64,
+    pub amplitude: f64,
+}
+
+impl Default for SimulatedSignal {
+    fn default() -> Self {
+        Self { channels: 1, frequency_hz: 997.0, amplitude: 0.5 }
+    }
+}
+
+impl SimulatedSignal {
+    /// Value of `channel` at absolute sample `index`
+    pub fn sample(&self, channel: usize, index: u64, sample_rate: u64) -> f64 {
+        let phase = 2.0 * std::f64::consts::PI * self.frequency_hz * (channel + 1) as f64 * index as f64
+            / sample_rate.max(1) as f64;
+        self.amplitude * phase.sin()
+    }
+}
+
+struct StreamState {
+    config: DeviceConfig,
+    /// Index of the next packet; lost packets advance it too
+    next_packet: u64,
+    format: SampleFormat,
+    timestamp_offset_ns: i64,
+    /// Packets still to lose from an earlier `DropPackets`
+    dropping: usize,
+    /// Faults waiting for their packet index
+    scheduled: BTreeMap<u64, Vec<Fault>>,
+    /// Clock time of packet 0
+    start_ns: Option<u64>,
+    /// `None` once disconnected
+    filled_tx: Option<Sender<PacketBuffer>>,
+    filled_rx: Receiver<PacketBuffer>,
+    /// Buffers handed back by the reader, discarded
+    returned: (Sender<PacketBuffer>, Receiver<PacketBuffer>),
+    /// Renewed when a disconnected stream reconnects
+    health: Arc<DeviceHealth>,
+}
+
+/// One packet of a simulated stream
+enum Slot {
+    Packet { packet: PacketBuffer, filled_tx: Sender<PacketBuffer>, health: Arc<DeviceHealth> },
+    /// Lost to an injected fault
+    Lost,
+}
+
+/// Stream of one simulated device, shared by the driver, the device and the test
+///
+/// Packets are produced by `emit`, or in real time while the device runs
+/// if `set_realtime` was enabled. Faults are applied at a packet index, so
+/// a test can reproduce exactly the same stream on every run.
+pub struct SimulatedStream {
+    name: String,
+    signal: SimulatedSignal,
+    realtime: AtomicBool,
+    state: Mutex<StreamState>,
+}
+
+impl SimulatedStream {
+    fn new(name: &str, signal: SimulatedSignal) -> Self {
+        let config = DeviceConfig {
+            name: name.to_string(),
+            sample_rate: 48000,
+            format: SampleFormat::F32,
+            buffer_size: DEFAULT_FRAME_SIZE,
+            channel_mapping: ChannelMapping::default(),
+            calibration: Calibration::default(),
+            buffer_count: DEFAULT_BUFFER_COUNT,
+            latency_mode: LatencyMode::default(),
+        };
+        let (filled_tx, filled_rx) = bounded(config.buffer_count);
+        Self {
+            name: name.to_string(),
+            signal,
+            realtime: AtomicBool::new(false),
+            state: Mutex::new(StreamState {
+                format: config.format,
+                config,
+                next_packet: 0,
+                timestamp_offset_ns: 0,
+                dropping: 0,
+                scheduled: BTreeMap::new(),
+                start_ns: None,
+                filled_tx: Some(filled_tx),
+                filled_rx,
+                returned: unbounded(),
+                health: Arc::new(DeviceHealth::new()),
+            }),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn signal(&self) -> &SimulatedSignal {
+        &self.signal
+    }
+
+    /// Produce packets at the configured sample rate while the device runs
+    pub fn set_realtime(&self, realtime: bool) {
+        self.realtime.store(realtime, Ordering::Relaxed);
+    }
+
+    /// Apply `fault` when packet `packet_index` is due
+    pub fn schedule(&self, packet_index: u64, fault: Fault) {
+        self.lock().scheduled.entry(packet_index).or_default().push(fault);
+    }
+
+    /// Apply `fault` to the next packet
+    pub fn inject(&self, fault: Fault) {
+        let next = self.lock().next_packet;
+        self.schedule(next, fault);
+    }
+
+    /// Index of the next packet to be produced
+    pub fn next_packet(&self) -> u64 {
+        self.lock().next_packet
+    }
+
+    /// Samples per channel in each packet
+    pub fn buffer_size(&self) -> usize {
+        self.lock().config.buffer_size
+    }
+
+    /// Value of `channel` at absolute sample `index`, as sent before format conversion
+    pub fn sample(&self, channel: usize, index: u64) -> f64 {
+        let sample_rate = self.lock().config.sample_rate;
+        self.signal.sample(channel, index, sample_rate)
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.lock().filled_tx.is_some()
+    }
+
+    pub fn health(&self) -> DeviceHealthSnapshot {
+        self.lock().health.snapshot()
+    }
+
+    /// Produce the next `count` packets, waiting for the reader to make room
+    ///
+    /// Packets lost to `DropPackets`, or not taken by the reader within a
+    /// second, count as xruns. Returns the number delivered, which stops
+    /// short once the stream disconnects.
+    pub fn emit(&self, count: usize) -> usize {
+        let mut delivered = 0;
+        for _ in 0..count {
+            let Some(slot) = self.next() else {
+                break;
+            };
+            let Slot::Packet { packet, filled_tx, health } = slot else {
+                continue;
+            };
+            match filled_tx.send_timeout(packet, SEND_TIMEOUT) {
+                Ok(()) => delivered += 1,
+                Err(SendTimeoutError::Timeout(_)) => health.record_xrun(),
+                Err(SendTimeoutError::Disconnected(_)) => break,
+            }
+        }
+        delivered
+    }
+
+    /// Apply the faults due and build the next packet; `None` once disconnected
+    fn next(&self) -> Option<Slot> {
+        let mut state = self.lock();
+        while state.returned.1.try_recv().is_ok() {}
+        let index = state.next_packet;
+        for fault in state.scheduled.remove(&index).unwrap_or_default() {
+            match fault {
+                Fault::DropPackets(count) => state.dropping += count,
+                Fault::FormatChange(format) => state.format = format,
+                Fault::TimestampJump(offset) => state.timestamp_offset_ns += offset,
+                Fault::Disconnect => {
+                    state.filled_tx = None;
+                    state.health.record_shutdown("Device disconnected");
+                }
+            }
+        }
+        let filled_tx = state.filled_tx.clone()?;
+        let health = state.health.clone();
+        state.next_packet += 1;
+
+        if state.dropping > 0 {
+            state.dropping -= 1;
+            health.record_xrun();
+            return Some(Slot::Lost);
+        }
+
+        let DeviceConfig { sample_rate, buffer_size, .. } = state.config;
+        let first_sample = index * buffer_size as u64;
+        let mut frame = DataFrame::new(0, index);
+        for ch in 0..self.signal.channels.max(1) {
+            let samples = (0..buffer_size as u64)
+                .map(|i| self.signal.sample(ch, first_sample + i, sample_rate))
+                .collect::<Vec<f64>>();
+            frame.insert_channel(format!("ch{}", ch), samples);
+        }
+        let mut packet = match frame_to_packet(&frame, state.format, sample_rate) {
+            Ok(packet) => packet,
+            Err(e) => {
+                tracing::warn!(stream = %self.name, "Failed to build simulated packet: {}", e);
+                return Some(Slot::Lost);
+            }
+        };
+        let start_ns = *state.start_ns.get_or_insert_with(|| TimeBase::global().now_ns());
+        let nominal_ns = (first_sample as u128 * 1_000_000_000 / sample_rate.max(1) as u128) as u64;
+        packet.timestamp = Some((start_ns + nominal_ns).saturating_add_signed(state.timestamp_offset_ns));
+        Some(Slot::Packet { packet, filled_tx, health })
+    }
+
+    /// Take the device's settings; a disconnected stream reconnects with a fresh channel
+    fn configure(&self, config: DeviceConfig) {
+        let mut state = self.lock();
+        if state.filled_tx.is_none() || state.config.buffer_count != config.buffer_count {
+            let (filled_tx, filled_rx) = bounded(config.buffer_count.max(1));
+            state.filled_tx = Some(filled_tx);
+            state.filled_rx = filled_rx;
+            state.health = Arc::new(DeviceHealth::new());
+        }
+        state.format = config.format;
+        state.config = config;
+    }
+
+    /// Emit packets at the stream's sample rate until `running` is cleared
+    fn run(self: Arc<Self>, running: Arc<AtomicBool>) {
+        let period = {
+            let state = self.lock();
+            Duration::from_secs_f64(state.config.buffer_size as f64 / state.config.sample_rate.max(1) as f64)
+        };
+        let mut deadline = Instant::now();
+        while running.load(Ordering::Relaxed) && self.is_connected() {
+            self.emit(1);
+            deadline += period;
+            std::thread::sleep(deadline.saturating_duration_since(Instant::now()));
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, StreamState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Driver exposing simulated devices with injectable faults
+///
+/// Each stream `<name>` is a device of that id producing a deterministic
+/// `SimulatedSignal`. Tests hold the stream to emit packets and inject
+/// dropped packets, format changes, disconnects and timestamp jumps, so
+/// the kernel and pipelines can be exercised without hardware.
+pub struct SimulatedDriver {
+    streams: Mutex<HashMap<String, Arc<SimulatedStream>>>,
+}
+
+impl SimulatedDriver {
+    /// Driver with a single one-channel stream named "sim"
+    pub fn new() -> Self {
+        let driver = Self { streams: Mutex::new(HashMap::new()) };
+        driver.stream("sim", SimulatedSignal::default());
+        driver
+    }
+
+    /// Get or create the stream called `name`; `signal` applies to new streams only
+    pub fn stream(&self, name: &str, signal: SimulatedSignal) -> Arc<SimulatedStream> {
+        self.streams
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .entry(name.to_string())
+            .or_insert_with(|| Arc::new(SimulatedStream::new(name, signal)))
+            .clone()
+    }
+
+    fn get(&self, name: &str) -> Option<Arc<SimulatedStream>> {
+        self.streams.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).get(name).cloned()
+    }
+}
+
+impl Default for SimulatedDriver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl HardwareDriver for SimulatedDriver {
+    fn driver_id(&self) -> &str {
+        "simulated"
+    }
+
+    fn hardware_type(&self) -> HardwareType {
+        HardwareType::Acoustic
+    }
+
+    async fn discover_devices(&self) -> Result<Vec<DeviceInfo>> {
+        let streams = self.streams.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut names: Vec<&String> = streams.keys().collect();
+        names.sort();
+        Ok(names
+            .into_iter()
+            .map(|name| DeviceInfo {
+                id: name.clone(),
+                name: format!("{} (Simulated)", name),
+                hardware_type: HardwareType::Acoustic,
+                driver_id: "simulated".to_string(),
+            })
+            .collect())
+    }
+
+    fn create_device(&self, device_id: &str, config: DeviceConfig) -> Result<Box<dyn Device>> {
+        let stream = self
+            .get(device_id)
+            .ok_or_else(|| anyhow::anyhow!("Unknown simulated device '{}'", device_id))?;
+        Ok(Box::new(SimulatedDevice::new(stream, config)))
+    }
+}
+
+/// Capture device reading a simulated stream
+pub struct SimulatedDevice {
+    stream: Arc<SimulatedStream>,
+    capabilities: DeviceCapabilities,
+    is_streaming: Arc<AtomicBool>,
+    producer: Option<JoinHandle<()>>,
+}
+
+impl SimulatedDevice {
+    fn new(stream: Arc<SimulatedStream>, config: DeviceConfig) -> Self {
+        let capabilities = DeviceCapabilities {
+            can_input: true,
+            can_output: false,
+            supported_formats: vec![
+                SampleFormat::I16,
+                SampleFormat::I24,
+                SampleFormat::I32,
+                SampleFormat::F32,
+                SampleFormat::F64,
+                SampleFormat::U8,
+            ],
+            supported_sample_rates: vec![config.sample_rate],
+            max_channels: stream.signal.channels.max(1),
+        };
+        stream.configure(config);
+        Self {
+            stream,
+            capabilities,
+            is_streaming: Arc::new(AtomicBool::new(false)),
+            producer: None,
+        }
+    }
+
+    pub fn stream(&self) -> &Arc<SimulatedStream> {
+        &self.stream
+    }
+}
+
+#[async_trait]
+impl Device for SimulatedDevice {
+    async fn start(&mut self) -> Result<()> {
+        self.is_streaming.store(true, Ordering::Relaxed);
+        if self.stream.realtime.load(Ordering::Relaxed) && self.producer.is_none() {
+            let stream = self.stream.clone();
+            let running = self.is_streaming.clone();
+            self.producer = Some(std::thread::spawn(move || stream.run(running)));
+        }
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<()> {
+        self.is_streaming.store(false, Ordering::Relaxed);
+        if let Some(handle) = self.producer.take() {
+            let _ = handle.join();
+        }
+        Ok(())
+    }
+
+    fn get_channels(&mut self) -> DeviceChannels {
+        let state = self.stream.lock();
+        DeviceChannels {
+            filled_rx: state.filled_rx.clone(),
+            empty_tx: state.returned.0.clone(),
+        }
+    }
+
+    fn capabilities(&self) -> DeviceCapabilities {
+        self.capabilities.clone()
+    }
+
+    fn is_streaming(&self) -> bool {
+        self.is_streaming.load(Ordering::Relaxed)
+    }
+
+    fn health(&self) -> Option<DeviceHealthSnapshot> {
+        Some(self.stream.health())
+    }
+}
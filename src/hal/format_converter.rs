@@ -1,11 +1,88 @@
+use crate::buffers::FramePool;
 use crate::core::DataFrame;
+use crate::dsp::{Dither, DitherMode};
+use crate::hal::decoder::{BytesDecoder, DecoderRegistry};
 use crate::hal::types::{PacketBuffer, SampleData, SampleFormat};
 use anyhow::Result;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::Mutex;
 
 /// Convert PacketBuffer (native format) to DataFrame (f64)
+///
+/// `SampleData::Bytes` packets are decoded with a fresh decoder for their
+/// `codec`, so records split across packets are lost; use `FrameConverter`
+/// for byte streams.
 pub fn packet_to_frame(packet: &PacketBuffer, sequence_id: u64) -> Result<DataFrame> {
+    packet_to_frame_with_pool(packet, sequence_id, FramePool::global())
+}
+
+/// Convert a `SampleData::Bytes` packet to a DataFrame using `decoder`
+///
+/// Frames may hold fewer (or zero) samples than the packet's byte count
+/// suggests; partial records stay buffered in the decoder.
+pub fn bytes_to_frame(packet: &PacketBuffer, sequence_id: u64, decoder: &mut dyn BytesDecoder) -> Result<DataFrame> {
+    let SampleData::Bytes(bytes) = &packet.data else {
+        anyhow::bail!("Expected Bytes packet for decoding");
+    };
+    let channels = decoder.decode(bytes)?;
+    let names = decoder.channel_names();
+
+    let mut output = DataFrame::new(packet.derive_timestamp(sequence_id), sequence_id);
+    let sample_rate = packet.sample_rate as f64;
+    for (ch, samples) in channels.into_iter().enumerate() {
+        let name = names.get(ch).cloned().unwrap_or_else(|| format!("ch{}", ch));
+        output.insert_channel(name, crate::core::Channel::from(samples).with_sample_rate(sample_rate));
+    }
+    output.metadata.insert("sample_rate", packet.sample_rate);
+
+    Ok(output)
+}
+
+/// Stateful PacketBuffer to DataFrame conversion for one packet stream
+///
+/// Keeps one decoder per codec named by the stream's `SampleData::Bytes`
+/// packets, so partial records carry over to the next packet. Other
+/// packets convert as with `packet_to_frame`.
+#[derive(Default)]
+pub struct FrameConverter {
+    // Decoders are only `Send`; the mutex makes the converter `Sync` for nodes
+    decoders: HashMap<String, Mutex<Box<dyn BytesDecoder>>>,
+}
+
+impl FrameConverter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn convert(&mut self, packet: &PacketBuffer, sequence_id: u64) -> Result<DataFrame> {
+        let (SampleData::Bytes(_), Some(codec)) = (&packet.data, &packet.codec) else {
+            return packet_to_frame(packet, sequence_id);
+        };
+        if !self.decoders.contains_key(codec) {
+            self.decoders.insert(codec.clone(), Mutex::new(DecoderRegistry::global().create(codec)?));
+        }
+        let decoder = self.decoders.get_mut(codec).expect("decoder inserted above");
+        let decoder = decoder.get_mut().unwrap_or_else(|poisoned| poisoned.into_inner());
+        bytes_to_frame(packet, sequence_id, decoder.as_mut())
+    }
+}
+
+impl std::fmt::Debug for FrameConverter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FrameConverter")
+            .field("codecs", &self.decoders.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Convert PacketBuffer to DataFrame, borrowing channel buffers from `pool`
+pub fn packet_to_frame_with_pool(packet: &PacketBuffer, sequence_id: u64, pool: &FramePool) -> Result<DataFrame> {
+    if let SampleData::Bytes(_) = &packet.data {
+        let Some(codec) = &packet.codec else {
+            anyhow::bail!("Cannot convert Bytes to DataFrame without a codec");
+        };
+        return bytes_to_frame(packet, sequence_id, DecoderRegistry::global().create(codec)?.as_mut());
+    }
     let timestamp = packet.derive_timestamp(sequence_id);
 
     // Get total samples and samples per channel
@@ -16,16 +93,34 @@ pub fn packet_to_frame(packet: &PacketBuffer, sequence_id: u64) -> Result<DataFr
         SampleData::F32(v) => v.len(),
         SampleData::F64(v) => v.len(),
         SampleData::U8(v) => v.len(),
-        SampleData::Bytes(_) => anyhow::bail!("Cannot convert Bytes to DataFrame"),
+        SampleData::Bytes(_) => unreachable!(),
     };
 
+    // Reject malformed packets rather than index past their data
+    if packet.num_channels == 0 {
+        anyhow::bail!("Packet has no channels");
+    }
+    if let SampleData::I24(bytes) = &packet.data {
+        if bytes.len() % 3 != 0 {
+            anyhow::bail!("I24 packet has {} bytes, not a whole number of 3-byte samples", bytes.len());
+        }
+    }
+    if total_samples % packet.num_channels != 0 {
+        anyhow::bail!(
+            "Packet has {} samples, not a whole number of frames of {} channels",
+            total_samples,
+            packet.num_channels
+        );
+    }
+
     let samples_per_channel = total_samples / packet.num_channels;
 
     // Convert and de-interleave samples
-    let mut payload: HashMap<String, Arc<Vec<f64>>> = HashMap::new();
+    let mut output = DataFrame::new(timestamp, sequence_id);
+    let sample_rate = packet.sample_rate as f64;
 
     for ch in 0..packet.num_channels {
-        let mut channel_data = Vec::with_capacity(samples_per_channel);
+        let mut channel_data = pool.take(samples_per_channel);
 
         for frame in 0..samples_per_channel {
             let index = frame * packet.num_channels + ch;
@@ -51,33 +146,50 @@ pub fn packet_to_frame(packet: &PacketBuffer, sequence_id: u64) -> Result<DataFr
             channel_data.push(value);
         }
 
-        payload.insert(format!("ch{}", ch), Arc::new(channel_data));
+        output.insert_channel(format!("ch{}", ch), pool.channel(channel_data).with_sample_rate(sample_rate));
     }
 
-    let mut metadata = HashMap::new();
-    metadata.insert("sample_rate".to_string(), packet.sample_rate.to_string());
+    output.metadata.insert("sample_rate", packet.sample_rate);
 
-    Ok(DataFrame {
-        timestamp,
-        sequence_id,
-        payload,
-        metadata,
-    })
+    Ok(output)
 }
 
 /// Convert DataFrame (f64) back to PacketBuffer (native format)
+///
+/// Integer formats are truncated; see `frame_to_packet_dithered`.
 pub fn frame_to_packet(frame: &DataFrame, format: SampleFormat, sample_rate: u64) -> Result<PacketBuffer> {
+    frame_to_packet_dithered(frame, format, sample_rate, &mut Dither::new(DitherMode::None))
+}
+
+/// Convert DataFrame (f64) to PacketBuffer, requantizing integer formats with `dither`
+///
+/// Keep the same `dither` across consecutive frames of a stream so noise
+/// shaping carries over packet boundaries. Float formats are not dithered.
+pub fn frame_to_packet_dithered(
+    frame: &DataFrame,
+    format: SampleFormat,
+    sample_rate: u64,
+    dither: &mut Dither,
+) -> Result<PacketBuffer> {
     // Get channels from payload
     let num_channels = frame.payload.len();
     if num_channels == 0 {
         anyhow::bail!("DataFrame has no channels");
     }
 
-    // Get samples per channel (assume all channels have same length)
-    let samples_per_channel = frame.payload.values()
-        .next()
-        .ok_or_else(|| anyhow::anyhow!("No channels in DataFrame"))?
-        .len();
+    // Channels are interleaved in `ch0`, `ch1`, ... order and must be the same length
+    let channels = (0..num_channels)
+        .map(|ch| frame.payload.get(&format!("ch{}", ch)).ok_or_else(|| anyhow::anyhow!("Missing channel ch{}", ch)))
+        .collect::<Result<Vec<_>>>()?;
+    let samples_per_channel = channels[0].len();
+    if let Some((ch, channel)) = channels.iter().enumerate().find(|(_, c)| c.len() != samples_per_channel) {
+        anyhow::bail!(
+            "Channel ch{} has {} samples but ch0 has {}",
+            ch,
+            channel.len(),
+            samples_per_channel
+        );
+    }
 
     // Interleave channels back
     let total_samples = samples_per_channel * num_channels;
@@ -86,11 +198,9 @@ pub fn frame_to_packet(frame: &DataFrame, format: SampleFormat, sample_rate: u64
         SampleFormat::I16 => {
             let mut samples = Vec::with_capacity(total_samples);
             for frame_idx in 0..samples_per_channel {
-                for ch in 0..num_channels {
-                    let channel_data = frame.payload.get(&format!("ch{}", ch))
-                        .ok_or_else(|| anyhow::anyhow!("Missing channel ch{}", ch))?;
+                for (ch, channel_data) in channels.iter().enumerate() {
                     let f64_value = channel_data[frame_idx];
-                    let i16_value = (f64_value * 32768.0).clamp(-32768.0, 32767.0) as i16;
+                    let i16_value = dither.quantize(f64_value * 32768.0, ch, -32768.0, 32767.0) as i16;
                     samples.push(i16_value);
                 }
             }
@@ -99,11 +209,9 @@ pub fn frame_to_packet(frame: &DataFrame, format: SampleFormat, sample_rate: u64
         SampleFormat::I24 => {
             let mut bytes = Vec::with_capacity(total_samples * 3);
             for frame_idx in 0..samples_per_channel {
-                for ch in 0..num_channels {
-                    let channel_data = frame.payload.get(&format!("ch{}", ch))
-                        .ok_or_else(|| anyhow::anyhow!("Missing channel ch{}", ch))?;
+                for (ch, channel_data) in channels.iter().enumerate() {
                     let f64_value = channel_data[frame_idx];
-                    let i24_value = (f64_value * 8388608.0).clamp(-8388608.0, 8388607.0) as i32;
+                    let i24_value = dither.quantize(f64_value * 8388608.0, ch, -8388608.0, 8388607.0) as i32;
 
                     // Store as 3 bytes (little-endian)
                     bytes.push((i24_value & 0xFF) as u8);
@@ -116,11 +224,9 @@ pub fn frame_to_packet(frame: &DataFrame, format: SampleFormat, sample_rate: u64
         SampleFormat::I32 => {
             let mut samples = Vec::with_capacity(total_samples);
             for frame_idx in 0..samples_per_channel {
-                for ch in 0..num_channels {
-                    let channel_data = frame.payload.get(&format!("ch{}", ch))
-                        .ok_or_else(|| anyhow::anyhow!("Missing channel ch{}", ch))?;
+                for (ch, channel_data) in channels.iter().enumerate() {
                     let f64_value = channel_data[frame_idx];
-                    let i32_value = (f64_value * 2147483648.0).clamp(-2147483648.0, 2147483647.0) as i32;
+                    let i32_value = dither.quantize(f64_value * 2147483648.0, ch, -2147483648.0, 2147483647.0) as i32;
                     samples.push(i32_value);
                 }
             }
@@ -129,9 +235,7 @@ pub fn frame_to_packet(frame: &DataFrame, format: SampleFormat, sample_rate: u64
         SampleFormat::F32 => {
             let mut samples = Vec::with_capacity(total_samples);
             for frame_idx in 0..samples_per_channel {
-                for ch in 0..num_channels {
-                    let channel_data = frame.payload.get(&format!("ch{}", ch))
-                        .ok_or_else(|| anyhow::anyhow!("Missing channel ch{}", ch))?;
+                for channel_data in &channels {
                     samples.push(channel_data[frame_idx] as f32);
                 }
             }
@@ -140,9 +244,7 @@ pub fn frame_to_packet(frame: &DataFrame, format: SampleFormat, sample_rate: u64
         SampleFormat::F64 => {
             let mut samples = Vec::with_capacity(total_samples);
             for frame_idx in 0..samples_per_channel {
-                for ch in 0..num_channels {
-                    let channel_data = frame.payload.get(&format!("ch{}", ch))
-                        .ok_or_else(|| anyhow::anyhow!("Missing channel ch{}", ch))?;
+                for channel_data in &channels {
                     samples.push(channel_data[frame_idx]);
                 }
             }
@@ -151,11 +253,9 @@ pub fn frame_to_packet(frame: &DataFrame, format: SampleFormat, sample_rate: u64
         SampleFormat::U8 => {
             let mut samples = Vec::with_capacity(total_samples);
             for frame_idx in 0..samples_per_channel {
-                for ch in 0..num_channels {
-                    let channel_data = frame.payload.get(&format!("ch{}", ch))
-                        .ok_or_else(|| anyhow::anyhow!("Missing channel ch{}", ch))?;
+                for (ch, channel_data) in channels.iter().enumerate() {
                     let f64_value = channel_data[frame_idx];
-                    let u8_value = ((f64_value * 128.0) + 128.0).clamp(0.0, 255.0) as u8;
+                    let u8_value = dither.quantize((f64_value * 128.0) + 128.0, ch, 0.0, 255.0) as u8;
                     samples.push(u8_value);
                 }
             }
@@ -168,6 +268,7 @@ pub fn frame_to_packet(frame: &DataFrame, format: SampleFormat, sample_rate: u64
         sample_rate,
         num_channels,
         timestamp: Some(frame.timestamp),
+        codec: None,
     })
 }
 
@@ -193,6 +294,7 @@ mod tests {
             sample_rate: 48000,
             num_channels: 1,
             timestamp: Some(1000000),
+            codec: None,
         };
 
         let frame = packet_to_frame(&packet, 1).unwrap();
@@ -222,6 +324,7 @@ mod tests {
             sample_rate: 48000,
             num_channels: 1,
             timestamp: Some(1000000),
+            codec: None,
         };
 
         let frame = packet_to_frame(&packet, 1).unwrap();
@@ -249,6 +352,7 @@ mod tests {
             sample_rate: 48000,
             num_channels: 2,
             timestamp: Some(1000000),
+            codec: None,
         };
 
         let frame = packet_to_frame(&packet, 1).unwrap();
@@ -282,6 +386,7 @@ mod tests {
             sample_rate: 48000,
             num_channels: 1,
             timestamp: Some(1000000),
+            codec: None,
         };
 
         // Convert to frame
@@ -318,6 +423,7 @@ mod tests {
             sample_rate: 48000,
             num_channels: 1,
             timestamp: Some(1000000),
+            codec: None,
         };
 
         let frame = packet_to_frame(&packet, 1).unwrap();
@@ -341,6 +447,7 @@ mod tests {
             sample_rate: 48000,
             num_channels: 1,
             timestamp: Some(1000000),
+            codec: None,
         };
 
         let frame = packet_to_frame(&packet, 1).unwrap();
@@ -364,6 +471,7 @@ mod tests {
             sample_rate: 48000,
             num_channels: 1,
             timestamp: Some(1000000),
+            codec: None,
         };
 
         let frame = packet_to_frame(&packet, 1).unwrap();
@@ -383,6 +491,7 @@ mod tests {
             sample_rate: 48000,
             num_channels: 1,
             timestamp: Some(1000000),
+            codec: None,
         };
         let frame = packet_to_frame(&i16_packet, 1).unwrap();
         let _ = frame_to_packet(&frame, SampleFormat::I16, 48000).unwrap();
@@ -393,6 +502,7 @@ mod tests {
             sample_rate: 48000,
             num_channels: 1,
             timestamp: Some(1000000),
+            codec: None,
         };
         let frame = packet_to_frame(&i32_packet, 1).unwrap();
         let _ = frame_to_packet(&frame, SampleFormat::I32, 48000).unwrap();
@@ -403,6 +513,7 @@ mod tests {
             sample_rate: 48000,
             num_channels: 1,
             timestamp: Some(1000000),
+            codec: None,
         };
         let frame = packet_to_frame(&f32_packet, 1).unwrap();
         let _ = frame_to_packet(&frame, SampleFormat::F32, 48000).unwrap();
@@ -413,6 +524,7 @@ mod tests {
             sample_rate: 48000,
             num_channels: 1,
             timestamp: Some(1000000),
+            codec: None,
         };
         let frame = packet_to_frame(&f64_packet, 1).unwrap();
         let _ = frame_to_packet(&frame, SampleFormat::F64, 48000).unwrap();
@@ -423,6 +535,7 @@ mod tests {
             sample_rate: 48000,
             num_channels: 1,
             timestamp: Some(1000000),
+            codec: None,
         };
         let frame = packet_to_frame(&u8_packet, 1).unwrap();
         let _ = frame_to_packet(&frame, SampleFormat::U8, 48000).unwrap();
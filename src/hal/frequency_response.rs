@@ -0,0 +1,105 @@
+use crate::dsp::fir::design_linear_phase;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Largest boost or cut applied by compensation
+const MAX_CORRECTION_DB: f64 = 12.0;
+
+/// Compensation is normalized here, where calibrators operate
+const REFERENCE_HZ: f64 = 1000.0;
+
+/// Measured microphone response from a calibration file
+///
+/// Reads the common text formats: one `freq dB [phase]` point per line,
+/// separated by whitespace, commas or semicolons. Quoted or `*`/`#`
+/// comment lines are headers; miniDSP-style `Sens Factor =-1.2dB` and
+/// `SERNO: 1234` entries are picked up from them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FrequencyResponse {
+    /// (frequency in Hz, level in dB), sorted by frequency
+    pub points: Vec<(f64, f64)>,
+    pub sensitivity_db: Option<f64>,
+    pub serial: Option<String>,
+}
+
+impl FrequencyResponse {
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut points = Vec::new();
+        let mut sensitivity_db = None;
+        let mut serial = None;
+
+        for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let values: Vec<f64> = line
+                .split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+                .filter(|v| !v.is_empty())
+                .map_while(|v| v.parse().ok())
+                .collect();
+            if values.len() >= 2 && values[0] > 0.0 {
+                points.push((values[0], values[1]));
+                continue;
+            }
+
+            // Header line
+            if let Some(rest) = line.split("Sens Factor").nth(1) {
+                let number: String = rest
+                    .trim_start_matches([' ', '='])
+                    .chars()
+                    .take_while(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.'))
+                    .collect();
+                sensitivity_db = number.parse().ok();
+            }
+            if let Some(rest) = line.split("SERNO:").nth(1) {
+                let value = rest.trim().trim_end_matches('"');
+                serial = value.split([',', '"']).next().map(|s| s.trim().to_string());
+            }
+        }
+
+        if points.len() < 2 {
+            anyhow::bail!("Frequency response needs at least two points, found {}", points.len());
+        }
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        points.dedup_by(|a, b| a.0 == b.0);
+
+        Ok(Self {
+            points,
+            sensitivity_db,
+            serial,
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Invalid frequency response in {}", path.display()))
+    }
+
+    /// Response at `freq`, interpolated on a log-frequency axis and held flat past the ends
+    pub fn level_db(&self, freq: f64) -> f64 {
+        let (first, last) = (self.points[0], self.points[self.points.len() - 1]);
+        if freq <= first.0 {
+            return first.1;
+        }
+        if freq >= last.0 {
+            return last.1;
+        }
+        let upper = self.points.partition_point(|p| p.0 < freq);
+        let (f0, l0) = self.points[upper - 1];
+        let (f1, l1) = self.points[upper];
+        let t = (freq / f0).ln() / (f1 / f0).ln();
+        l0 + t * (l1 - l0)
+    }
+
+    /// Linear-phase FIR that flattens this response, unity gain at 1 kHz
+    ///
+    /// Corrections are limited to ±12 dB so deep notches and band edges
+    /// are not boosted into noise.
+    pub fn compensation_fir(&self, sample_rate: f64, num_taps: usize) -> Vec<f64> {
+        let reference = self.level_db(REFERENCE_HZ);
+        design_linear_phase(num_taps, sample_rate, |freq| {
+            let correction = (reference - self.level_db(freq)).clamp(-MAX_CORRECTION_DB, MAX_CORRECTION_DB);
+            10f64.powf(correction / 20.0)
+        })
+    }
+}
@@ -8,17 +8,29 @@ pub mod device_storage;
 pub mod device_manager;
 pub mod registered;
 pub mod format_converter;
+pub mod time_base;
+pub mod decoder;
+pub mod device_check;
+pub mod calibration;
+pub mod frequency_response;
 
 pub use traits::{HardwareDriver, Device};
 pub use types::{
-    HardwareType, DeviceInfo, DeviceConfig, DeviceCapabilities,
+    HardwareType, DeviceInfo, DeviceConfig, DeviceCapabilities, CapabilityError,
     DeviceChannels, PacketBuffer, SampleData, SampleFormat,
-    ChannelMapping, ChannelRoute, Calibration,
+    ChannelMapping, ChannelRoute, Calibration, LatencyMode, DEFAULT_BUFFER_COUNT, DEFAULT_FRAME_SIZE,
 };
 pub use registry::HardwareRegistry;
-pub use drivers::AudioDriver;
+pub use time_base::TimeBase;
+pub use calibration::{CalibrationRecord, CalibrationStatus, CalibrationTarget};
+pub use frequency_response::FrequencyResponse;
+pub use device_check::{InputLevels, InputMonitor, TestTone};
+pub use decoder::{BinaryFrameDecoder, BytesDecoder, DecoderRegistry, LineDecoder};
+pub use drivers::{AudioDriver, FileDriver, LoopbackDriver, MidiDriver, NetworkDriver, ReplaySpeed, SerialDriver, SimulatedDriver};
+#[cfg(feature = "jack")]
+pub use drivers::JackDriver;
 pub use channel_mapper::ChannelMapper;
 pub use device_profile::{DeviceProfile, DeviceMetadata};
 pub use device_storage::DeviceStorage;
-pub use device_manager::DeviceManager;
+pub use device_manager::{DeviceAccess, DeviceManager};
 pub use registered::*;
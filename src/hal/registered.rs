@@ -1,15 +1,18 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use super::{HardwareType, ChannelMapping, Calibration};
+use super::{HardwareType, ChannelMapping, Calibration, DeviceConfig, LatencyMode, SampleFormat};
+use super::types::{default_buffer_count, DEFAULT_FRAME_SIZE};
+use super::calibration::CalibrationRecord;
 
 /// Device direction (input or output)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum Direction {
     Input,
     Output,
 }
 
 /// Audio protocol type
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum AudioProtocol {
     ASIO,
     CoreAudio,
@@ -19,7 +22,7 @@ pub enum AudioProtocol {
 }
 
 /// Registered hardware device configuration (persistent)
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct RegisteredHardware {
     // Identity
     pub registration_id: String,
@@ -39,12 +42,38 @@ pub struct RegisteredHardware {
     pub channels: usize,
     pub channel_mapping: ChannelMapping,
     pub calibration: Calibration,
+    /// Calibration runs, oldest first
+    #[serde(default)]
+    pub calibration_history: Vec<CalibrationRecord>,
     pub max_voltage: f64,
+    #[serde(default = "default_buffer_count")]
+    pub buffer_count: usize,
+    #[serde(default)]
+    pub latency_mode: LatencyMode,
     pub notes: String,
 }
 
+impl RegisteredHardware {
+    /// Device config used when opening this registration
+    ///
+    /// Packets hold `DEFAULT_FRAME_SIZE` samples; the kernel overrides this
+    /// with the frame size of the pipeline it runs.
+    pub fn device_config(&self) -> DeviceConfig {
+        DeviceConfig {
+            name: self.user_name.clone(),
+            sample_rate: self.sample_rate,
+            format: SampleFormat::F32, // Default to F32
+            buffer_size: DEFAULT_FRAME_SIZE,
+            channel_mapping: self.channel_mapping.clone(),
+            calibration: self.calibration,
+            buffer_count: self.buffer_count,
+            latency_mode: self.latency_mode,
+        }
+    }
+}
+
 /// Hardware configuration file format
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct HardwareConfig {
     pub version: String,
     pub registered_devices: Vec<RegisteredHardware>,
@@ -86,8 +115,11 @@ mod tests {
                     ChannelRoute::Direct(1),
                 ],
             },
-            calibration: Calibration { gain: 1.0, offset: 0.0 },
+            calibration: Calibration { gain: 1.0, offset: 0.0, calibrated_at: None },
+            calibration_history: Vec::new(),
             max_voltage: 0.0,
+            buffer_count: 2,
+            latency_mode: LatencyMode::LowLatency,
             notes: "".to_string(),
         };
 
@@ -4,8 +4,13 @@ use anyhow::Result;
 use super::traits::HardwareDriver;
 use super::types::{DeviceInfo, DeviceConfig};
 use super::Device;
+use super::drivers::{AudioDriver, FileDriver, LoopbackDriver, MidiDriver, NetworkDriver, SerialDriver};
 
 /// Central registry for hardware drivers
+///
+/// Drivers are reference-counted, so clones share the same driver
+/// instances (and state such as loopback cables).
+#[derive(Clone)]
 pub struct HardwareRegistry {
     drivers: HashMap<String, Arc<dyn HardwareDriver>>,
 }
@@ -17,10 +22,29 @@ impl HardwareRegistry {
         }
     }
 
+    /// Registry with every built-in driver
+    pub fn with_builtin_drivers() -> Self {
+        let mut registry = Self::new();
+        registry.register(AudioDriver::new());
+        registry.register(LoopbackDriver::new());
+        registry.register(FileDriver::default());
+        registry.register(NetworkDriver::default());
+        registry.register(SerialDriver::default());
+        registry.register(MidiDriver::default());
+        #[cfg(feature = "jack")]
+        registry.register(super::drivers::JackDriver::default());
+        registry
+    }
+
     /// Register a hardware driver
     pub fn register(&mut self, driver: impl HardwareDriver + 'static) {
+        self.register_shared(Arc::new(driver));
+    }
+
+    /// Register a driver instance that other registries may also hold
+    pub fn register_shared(&mut self, driver: Arc<dyn HardwareDriver>) {
         let driver_id = driver.driver_id().to_string();
-        self.drivers.insert(driver_id, Arc::new(driver));
+        self.drivers.insert(driver_id, driver);
     }
 
     /// List all registered drivers
@@ -40,7 +64,7 @@ impl HardwareRegistry {
         for driver in self.drivers.values() {
             match driver.discover_devices().await {
                 Ok(devices) => all_devices.extend(devices),
-                Err(e) => eprintln!("Driver {} discovery failed: {}", driver.driver_id(), e),
+                Err(e) => tracing::warn!(driver = driver.driver_id(), "Device discovery failed: {}", e),
             }
         }
 
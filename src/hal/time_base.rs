@@ -0,0 +1,50 @@
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Monotonic clock shared by every device
+///
+/// Drivers stamp packets with nanoseconds since the time base origin at
+/// callback time, so frames captured on different hardware can be
+/// correlated by `DataFrame::timestamp`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeBase {
+    origin: Instant,
+}
+
+impl TimeBase {
+    /// Time base starting now (mainly for tests; devices use `global()`)
+    pub fn new() -> Self {
+        Self { origin: Instant::now() }
+    }
+
+    /// Process-wide time base shared by all drivers and the kernel
+    pub fn global() -> TimeBase {
+        static TIME_BASE: OnceLock<TimeBase> = OnceLock::new();
+        *TIME_BASE.get_or_init(TimeBase::new)
+    }
+
+    pub fn origin(&self) -> Instant {
+        self.origin
+    }
+
+    /// Nanoseconds since the origin
+    pub fn now_ns(&self) -> u64 {
+        self.instant_ns(Instant::now())
+    }
+
+    /// Nanoseconds from the origin to `instant` (0 if it precedes the origin)
+    pub fn instant_ns(&self, instant: Instant) -> u64 {
+        instant.saturating_duration_since(self.origin).as_nanos() as u64
+    }
+
+    /// Timestamp of a capture that happened `latency` before now
+    pub fn capture_ns(&self, latency: Duration) -> u64 {
+        self.now_ns().saturating_sub(latency.as_nanos() as u64)
+    }
+}
+
+impl Default for TimeBase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
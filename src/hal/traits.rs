@@ -1,6 +1,8 @@
 use async_trait::async_trait;
 use anyhow::Result;
 use super::types::{DeviceInfo, DeviceConfig, DeviceCapabilities, DeviceChannels, HardwareType};
+use super::decoder::BytesDecoder;
+use crate::observability::DeviceHealthSnapshot;
 
 /// Trait implemented by hardware drivers for device discovery and creation
 #[async_trait]
@@ -41,4 +43,14 @@ pub trait Device: Send {
 
     /// Check if device is currently streaming
     fn is_streaming(&self) -> bool;
+
+    /// Xrun, transport and shutdown reporting, for backends that provide it
+    fn health(&self) -> Option<DeviceHealthSnapshot> {
+        None
+    }
+
+    /// Decoder for the `SampleData::Bytes` packets this device produces
+    fn bytes_decoder(&self) -> Option<Box<dyn BytesDecoder>> {
+        None
+    }
 }
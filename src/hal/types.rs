@@ -1,8 +1,9 @@
 use crossbeam_channel::{Receiver, Sender};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Hardware classification
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum HardwareType {
     /// Full framework support - time-series samples
     Acoustic,
@@ -28,6 +29,31 @@ pub struct DeviceConfig {
     pub buffer_size: usize,
     pub channel_mapping: ChannelMapping,
     pub calibration: Calibration,
+    /// Number of packet buffers cycled between the driver and the engine
+    #[serde(default = "default_buffer_count")]
+    pub buffer_count: usize,
+    #[serde(default)]
+    pub latency_mode: LatencyMode,
+}
+
+/// Default number of ping-pong packet buffers
+pub const DEFAULT_BUFFER_COUNT: usize = 2;
+
+/// Samples per packet when neither the device nor the pipeline sets a frame size
+pub const DEFAULT_FRAME_SIZE: usize = 1024;
+
+pub fn default_buffer_count() -> usize {
+    DEFAULT_BUFFER_COUNT
+}
+
+/// Trade-off between input latency and robustness against dropouts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+pub enum LatencyMode {
+    /// Fixed driver period of `buffer_size` frames
+    #[default]
+    LowLatency,
+    /// Host-chosen driver period, usually larger and more tolerant of scheduling jitter
+    Safe,
 }
 
 /// Sample data format
@@ -48,7 +74,7 @@ impl Default for SampleFormat {
 }
 
 /// Channel mapping configuration
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct ChannelMapping {
     pub physical_channels: usize,
     pub virtual_channels: usize,
@@ -66,7 +92,7 @@ impl Default for ChannelMapping {
 }
 
 /// Channel routing rule
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub enum ChannelRoute {
     Direct(usize),          // Phys[i] -> Virt[i]
     Reorder(Vec<usize>),    // Phys[1,2,3] -> Virt[3,2,1]
@@ -75,10 +101,12 @@ pub enum ChannelRoute {
 }
 
 /// Calibration settings
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct Calibration {
     pub gain: f64,    // Multiply for voltage
     pub offset: f64,  // Add for SPL
+    #[serde(default)]
+    pub calibrated_at: Option<u64>,  // Unix seconds
 }
 
 impl Default for Calibration {
@@ -86,6 +114,7 @@ impl Default for Calibration {
         Self {
             gain: 1.0,
             offset: 0.0,
+            calibrated_at: None,
         }
     }
 }
@@ -100,6 +129,81 @@ pub struct DeviceCapabilities {
     pub max_channels: usize,
 }
 
+impl DeviceCapabilities {
+    /// Check a config against these capabilities; empty lists accept anything
+    pub fn validate(&self, config: &DeviceConfig) -> Result<(), CapabilityError> {
+        if !self.supported_sample_rates.is_empty() && !self.supported_sample_rates.contains(&config.sample_rate) {
+            return Err(CapabilityError::UnsupportedSampleRate {
+                requested: config.sample_rate,
+                supported: self.supported_sample_rates.clone(),
+            });
+        }
+        if !self.supported_formats.is_empty() && !self.supported_formats.contains(&config.format) {
+            return Err(CapabilityError::UnsupportedFormat {
+                requested: config.format,
+                supported: self.supported_formats.clone(),
+            });
+        }
+        let channels = config.channel_mapping.physical_channels;
+        if self.max_channels > 0 && channels > self.max_channels {
+            return Err(CapabilityError::TooManyChannels {
+                requested: channels,
+                max: self.max_channels,
+            });
+        }
+        Ok(())
+    }
+
+    /// Correct a config to the nearest supported sample rate and a supported format
+    ///
+    /// Channel counts are never reduced, since routing depends on them.
+    pub fn negotiate(&self, config: &DeviceConfig) -> Result<DeviceConfig, CapabilityError> {
+        let mut corrected = config.clone();
+        if let Some(&rate) = self
+            .supported_sample_rates
+            .iter()
+            .min_by_key(|&&rate| rate.abs_diff(config.sample_rate))
+        {
+            corrected.sample_rate = rate;
+        }
+        if !self.supported_formats.is_empty() && !self.supported_formats.contains(&config.format) {
+            corrected.format = if self.supported_formats.contains(&SampleFormat::F32) {
+                SampleFormat::F32
+            } else {
+                self.supported_formats[0]
+            };
+        }
+        self.validate(&corrected)?;
+        Ok(corrected)
+    }
+}
+
+/// Why a device cannot run a requested config
+#[derive(Debug, Clone, PartialEq)]
+pub enum CapabilityError {
+    UnsupportedSampleRate { requested: u64, supported: Vec<u64> },
+    UnsupportedFormat { requested: SampleFormat, supported: Vec<SampleFormat> },
+    TooManyChannels { requested: usize, max: usize },
+}
+
+impl std::fmt::Display for CapabilityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CapabilityError::UnsupportedSampleRate { requested, supported } => {
+                write!(f, "Sample rate {} Hz not supported (supported: {:?})", requested, supported)
+            }
+            CapabilityError::UnsupportedFormat { requested, supported } => {
+                write!(f, "Sample format {:?} not supported (supported: {:?})", requested, supported)
+            }
+            CapabilityError::TooManyChannels { requested, max } => {
+                write!(f, "{} channels requested but the device has at most {}", requested, max)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CapabilityError {}
+
 /// Channels for buffer ping-pong pattern
 #[derive(Clone)]
 pub struct DeviceChannels {
@@ -116,10 +220,12 @@ pub struct PacketBuffer {
     pub sample_rate: u64,
     pub num_channels: usize,
     pub timestamp: Option<u64>,  // Nanoseconds
+    /// DecoderRegistry id of the codec for `SampleData::Bytes` data; ignored for other formats
+    pub codec: Option<String>,
 }
 
 /// Sample data in native format
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum SampleData {
     I16(Vec<i16>),
     I24(Vec<u8>),  // 3 bytes per sample
@@ -147,14 +253,20 @@ impl PacketBuffer {
             sample_rate: 48000,  // Default
             num_channels,
             timestamp: None,
+            codec: None,
         }
     }
 
     /// Derive timestamp from packet index if not provided
+    ///
+    /// Packets without channels or a sample rate have no timeline and get 0.
     pub fn derive_timestamp(&self, packet_index: u64) -> u64 {
         if let Some(ts) = self.timestamp {
             return ts;
         }
+        if self.num_channels == 0 || self.sample_rate == 0 {
+            return 0;
+        }
 
         let samples_per_packet = match &self.data {
             SampleData::I16(v) => v.len() / self.num_channels,
@@ -166,7 +278,7 @@ impl PacketBuffer {
             SampleData::Bytes(_) => 0,
         };
 
-        let samples_elapsed = packet_index * samples_per_packet as u64;
-        (samples_elapsed * 1_000_000_000) / self.sample_rate
+        let samples_elapsed = packet_index as u128 * samples_per_packet as u128;
+        (samples_elapsed * 1_000_000_000 / self.sample_rate as u128).min(u64::MAX as u128) as u64
     }
 }
@@ -1,12 +1,15 @@
 pub mod buffers;
 pub mod core;
+pub mod dsp;
 pub mod engine;
 pub mod hal;
 pub mod nodes;
 pub mod observability;
+#[cfg(feature = "plugins")]
+pub mod plugin;
 pub mod registry;
 pub mod resilience;
 pub mod visualization;
 
-pub use core::{ProcessingNode, NodeContext, DataFrame};
+pub use core::{AudiotabError, Channel, ChannelRole, DataFrame, EventPoint, EventSeries, Metadata, MetadataValue, NodeContext, ProcessingNode, SharedFrame};
 pub use registry::{NodeMetadata, PortMetadata, ParameterSchema};
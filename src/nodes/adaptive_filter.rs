@@ -0,0 +1,168 @@
+use crate::core::{Channel, ChannelRole, DataFrame, PortPairer, ProcessingNode};
+use crate::dsp::AdaptiveFilter;
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use serde::{Deserialize, Serialize};
+
+const PRIMARY_PORT: &str = "_primary";
+const REFERENCE_PORT: &str = "_reference";
+
+/// AdaptiveFilterNode cancels the part of a primary signal that a reference
+/// signal explains
+///
+/// `reference_channel` (e.g. a microphone next to a noise source, or the
+/// signal sent to a loudspeaker) is filtered by an adaptive FIR of `taps`
+/// coefficients to estimate its contribution to `primary_channel`. The
+/// coefficients follow the LMS or normalised LMS (`nlms`, the default)
+/// update with `step_size`; NLMS is stable between 0 and 2, larger steps
+/// converge faster but leave more residual. With `adapt` off the current
+/// coefficients are frozen.
+///
+/// Adds channels `error` (primary minus estimate: the cleaned signal),
+/// `estimate` and `coefficients` (the current filter, which describes the
+/// path from reference to primary). Input channels are kept unless
+/// `keep_inputs` is off. Both channels may come in one frame, or the two
+/// inputs may be wired to separate sources, whose frames are paired by
+/// sequence id; a frame waiting for its partner yields an empty payload.
+/// The filter adapts continuously across frames.
+#[derive(StreamNode, Debug, Clone, Serialize, Deserialize)]
+#[node_meta(name = "Adaptive Filter", category = "Processors")]
+#[preset(name = "Noise reference cancellation", params = r#"{"algorithm": "nlms", "taps": 256, "step_size": 0.05}"#)]
+pub struct AdaptiveFilterNode {
+    #[input(name = "Primary In", data_type = "audio_frame")]
+    _primary: (),
+
+    #[input(name = "Reference In", data_type = "audio_frame")]
+    _reference: (),
+
+    #[output(name = "Audio Out", data_type = "audio_frame")]
+    _output: (),
+
+    #[param(default = "\"ch0\"")]
+    pub primary_channel: String,
+
+    #[param(default = "\"ch1\"")]
+    pub reference_channel: String,
+
+    #[param(default = "\"nlms\"", choices = "nlms,lms")]
+    pub algorithm: String,
+
+    #[param(default = "64", min = 1.0, max = 8192.0, step = 1.0)]
+    pub taps: usize,
+
+    #[param(default = "0.1", min = 0.0, max = 2.0, log_scale)]
+    pub step_size: f64,
+
+    #[param(default = "true")]
+    pub adapt: bool,
+
+    #[param(default = "true")]
+    pub keep_inputs: bool,
+
+    #[serde(skip)]
+    filter: Option<AdaptiveFilter>,
+
+    #[serde(skip)]
+    pairs: PortPairer,
+}
+
+impl Default for AdaptiveFilterNode {
+    fn default() -> Self {
+        Self {
+            _primary: (),
+            _reference: (),
+            _output: (),
+            primary_channel: "ch0".to_string(),
+            reference_channel: "ch1".to_string(),
+            algorithm: "nlms".to_string(),
+            taps: 64,
+            step_size: 0.1,
+            adapt: true,
+            keep_inputs: true,
+            filter: None,
+            pairs: PortPairer::new(),
+        }
+    }
+}
+
+impl AdaptiveFilterNode {
+    /// Current filter coefficients, empty until configured
+    pub fn coefficients(&self) -> &[f64] {
+        self.filter.as_ref().map(|f| f.weights()).unwrap_or_default()
+    }
+
+    fn configure(&mut self) -> Result<()> {
+        let normalized = match self.algorithm.to_ascii_lowercase().as_str() {
+            "nlms" => true,
+            "lms" => false,
+            other => anyhow::bail!("Unknown adaptive algorithm: {}", other),
+        };
+        if self.taps == 0 {
+            anyhow::bail!("taps must be at least 1");
+        }
+        if self.step_size <= 0.0 || !self.step_size.is_finite() {
+            anyhow::bail!("step_size must be positive, got {}", self.step_size);
+        }
+        self.filter = Some(AdaptiveFilter::new(self.taps, self.step_size, normalized));
+        self.pairs.clear();
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for AdaptiveFilterNode {
+    async fn on_configured(&mut self, _config: &serde_json::Value) -> Result<()> {
+        self.configure()
+    }
+
+    async fn process(&mut self, frame: DataFrame) -> Result<DataFrame> {
+        if self.filter.is_none() {
+            self.configure()?;
+        }
+        let waiting = DataFrame::new(frame.timestamp, frame.sequence_id);
+        let ports = [(PRIMARY_PORT, self.primary_channel.as_str()), (REFERENCE_PORT, self.reference_channel.as_str())];
+        let Some(mut frame) = self.pairs.push(frame, ports)? else {
+            return Ok(waiting);
+        };
+        let primary = frame.payload.get(&self.primary_channel).ok_or_else(|| {
+            anyhow::anyhow!("Missing primary channel '{}'", self.primary_channel)
+        })?;
+        let reference = frame.payload.get(&self.reference_channel).ok_or_else(|| {
+            anyhow::anyhow!("Missing reference channel '{}'", self.reference_channel)
+        })?;
+        if primary.len() != reference.len() {
+            anyhow::bail!(
+                "Primary channel has {} samples but reference channel has {}",
+                primary.len(),
+                reference.len()
+            );
+        }
+
+        let filter = self.filter.as_mut().expect("filter configured above");
+        let (estimate, error): (Vec<f64>, Vec<f64>) = reference
+            .samples()
+            .iter()
+            .zip(primary.samples())
+            .map(|(&x, &d)| filter.process(x, d, self.adapt))
+            .unzip();
+        let estimate = primary.with_samples(estimate);
+        let error = primary.with_samples(error);
+        let coefficients = Channel::new(filter.weights().to_vec()).with_role(ChannelRole::Measurement);
+
+        if !self.keep_inputs {
+            frame.payload.clear();
+        }
+        frame.insert_channel("error", error);
+        frame.insert_channel("estimate", estimate);
+        frame.insert_channel("coefficients", coefficients);
+        frame.metadata.insert("adaptive_algorithm", self.algorithm.to_lowercase());
+        Ok(frame)
+    }
+
+    async fn on_destroy(&mut self) -> Result<()> {
+        self.filter = None;
+        self.pairs.clear();
+        Ok(())
+    }
+}
@@ -0,0 +1,152 @@
+use crate::core::{DataFrame, ProcessingNode};
+use crate::observability::Severity;
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use serde::{Deserialize, Serialize};
+
+/// Side of the threshold that raises an AlertNode's alert
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertCondition {
+    Above,
+    Below,
+}
+
+impl AlertCondition {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "above" => Ok(AlertCondition::Above),
+            "below" => Ok(AlertCondition::Below),
+            _ => anyhow::bail!("Unknown alert condition: {}", name),
+        }
+    }
+
+    fn met(self, value: f64, threshold: f64) -> bool {
+        match self {
+            AlertCondition::Above => value > threshold,
+            AlertCondition::Below => value < threshold,
+        }
+    }
+}
+
+/// AlertNode raises a named alert when a channel crosses a threshold
+///
+/// Frames pass through unchanged. When any value of `channel` (every
+/// channel of the frame when empty) is `above` or `below` `threshold`, the
+/// frame gets `alert` (`name`), `alert_severity`, `alert_message` and
+/// `alert_value` metadata, the value furthest past the threshold. The
+/// pipeline publishes the alert for an `AlertRouter` to debounce and
+/// deliver (see `observability::alerts`). Watching a label channel of an
+/// Inference node raises an alert when that event class is detected; a
+/// level or SPL channel, when a limit is exceeded.
+///
+/// `message` may use `{channel}`, `{value}` and `{threshold}`; when empty
+/// a message naming them is generated.
+#[derive(StreamNode, Debug, Clone, Serialize, Deserialize)]
+#[node_meta(name = "Alert", category = "Processors")]
+#[preset(name = "Level limit", params = r#"{"name": "level_exceeded", "channel": "ch0_spl", "threshold": 85.0, "severity": "warning"}"#)]
+#[preset(name = "Event class detected", params = r#"{"name": "siren_detected", "channel": "siren", "threshold": 0.8, "severity": "critical"}"#)]
+pub struct AlertNode {
+    #[input(name = "In", data_type = "any")]
+    _input: (),
+
+    #[output(name = "Out", data_type = "any")]
+    _output: (),
+
+    #[param(default = "\"alert\"")]
+    pub name: String,
+
+    #[param(default = "\"\"")]
+    pub channel: String,
+
+    #[param(default = "\"above\"", choices = "above,below")]
+    pub condition: String,
+
+    #[param(default = "0.5", min = -1000000.0, max = 1000000.0)]
+    pub threshold: f64,
+
+    #[param(default = "\"warning\"", choices = "info,warning,critical")]
+    pub severity: String,
+
+    #[param(default = "\"\"")]
+    pub message: String,
+}
+
+impl Default for AlertNode {
+    fn default() -> Self {
+        Self {
+            _input: (),
+            _output: (),
+            name: "alert".to_string(),
+            channel: String::new(),
+            condition: "above".to_string(),
+            threshold: 0.5,
+            severity: "warning".to_string(),
+            message: String::new(),
+        }
+    }
+}
+
+impl AlertNode {
+    /// The channel and value furthest past the threshold, if any is past it
+    fn worst(&self, frame: &DataFrame, condition: AlertCondition) -> Result<Option<(String, f64)>> {
+        let channels: Vec<(&String, &crate::core::Channel)> = if self.channel.is_empty() {
+            frame.payload.iter().collect()
+        } else {
+            let channel = frame
+                .payload
+                .get_key_value(&self.channel)
+                .ok_or_else(|| anyhow::anyhow!("Channel '{}' is not in the frame", self.channel))?;
+            vec![channel]
+        };
+        let mut worst: Option<(String, f64)> = None;
+        for (name, channel) in channels {
+            for &value in channel.samples() {
+                let further = worst.as_ref().is_none_or(|(_, w)| condition.met(value, *w));
+                if condition.met(value, self.threshold) && further {
+                    worst = Some((name.clone(), value));
+                }
+            }
+        }
+        Ok(worst)
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for AlertNode {
+    async fn on_configured(&mut self, _config: &serde_json::Value) -> Result<()> {
+        if self.name.trim().is_empty() {
+            anyhow::bail!("Alert name must not be empty");
+        }
+        AlertCondition::parse(&self.condition)?;
+        Severity::parse(&self.severity)?;
+        Ok(())
+    }
+
+    async fn process(&mut self, mut frame: DataFrame) -> Result<DataFrame> {
+        let condition = AlertCondition::parse(&self.condition)?;
+        let severity = Severity::parse(&self.severity)?;
+        let Some((channel, value)) = self.worst(&frame, condition)? else {
+            return Ok(frame);
+        };
+        let message = if self.message.is_empty() {
+            format!(
+                "'{}' at {} is {} the threshold of {}",
+                channel,
+                value,
+                if condition == AlertCondition::Above { "above" } else { "below" },
+                self.threshold
+            )
+        } else {
+            self.message
+                .replace("{channel}", &channel)
+                .replace("{value}", &value.to_string())
+                .replace("{threshold}", &self.threshold.to_string())
+        };
+        frame.metadata.insert("alert", self.name.clone());
+        frame.metadata.insert("alert_severity", severity.to_string());
+        frame.metadata.insert("alert_message", message);
+        frame.metadata.insert("alert_value", value);
+        Ok(frame)
+    }
+}
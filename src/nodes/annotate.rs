@@ -0,0 +1,130 @@
+use crate::core::{DataFrame, MetadataValue, ProcessingNode};
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use serde::{Deserialize, Serialize};
+
+/// Parse custom tags given as `"fixture=B2, run=3"`
+///
+/// Values are stored as text; a JSON object passed as `tags` keeps its types.
+fn parse_tags(tags: &str) -> Result<Vec<(String, MetadataValue)>> {
+    tags.split(',')
+        .filter(|tag| !tag.trim().is_empty())
+        .map(|tag| match tag.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                Ok((key.trim().to_string(), MetadataValue::Text(value.trim().to_string())))
+            }
+            _ => anyhow::bail!("Tag '{}' must be 'key=value'", tag.trim()),
+        })
+        .collect()
+}
+
+/// AnnotateNode stamps experiment context onto the metadata of passing frames
+///
+/// `test_name`, `operator` and `device_serial` are set under those keys when
+/// not empty, and `tags` adds custom keys, either as `"key=value, ..."` or
+/// as a JSON object whose numbers and booleans keep their type. Exporters
+/// write frame metadata along with the samples, so files and logged results
+/// downstream record which test produced them.
+///
+/// Keys already on a frame are overwritten unless `overwrite` is off, in
+/// which case only missing keys are added.
+#[derive(StreamNode, Debug, Clone, Serialize, Deserialize)]
+#[node_meta(name = "Annotate", category = "Utilities")]
+pub struct AnnotateNode {
+    #[input(name = "Audio In", data_type = "audio_frame")]
+    _input: (),
+
+    #[output(name = "Audio Out", data_type = "audio_frame")]
+    _output: (),
+
+    #[param(default = "\"\"")]
+    pub test_name: String,
+
+    #[param(default = "\"\"")]
+    pub operator: String,
+
+    #[param(default = "\"\"")]
+    pub device_serial: String,
+
+    #[param(default = "\"\"", skip_config)]
+    pub tags: String,
+
+    #[param(default = "true")]
+    pub overwrite: bool,
+
+    /// Every key and value to stamp, resolved from the parameters
+    #[serde(skip)]
+    annotations: Vec<(String, MetadataValue)>,
+}
+
+impl Default for AnnotateNode {
+    fn default() -> Self {
+        Self {
+            _input: (),
+            _output: (),
+            test_name: String::new(),
+            operator: String::new(),
+            device_serial: String::new(),
+            tags: String::new(),
+            overwrite: true,
+            annotations: Vec::new(),
+        }
+    }
+}
+
+impl AnnotateNode {
+    /// Keys and values stamped on each frame
+    pub fn annotations(&self) -> &[(String, MetadataValue)] {
+        &self.annotations
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for AnnotateNode {
+    async fn on_configured(&mut self, config: &serde_json::Value) -> Result<()> {
+        let mut tags = match config.get("tags") {
+            // Accept either "key=value, key=value" or {"key": value}
+            Some(serde_json::Value::String(s)) => {
+                self.tags = s.clone();
+                parse_tags(s)?
+            }
+            Some(serde_json::Value::Object(map)) => {
+                let tags = map
+                    .iter()
+                    .map(|(key, value)| {
+                        let value: MetadataValue = serde_json::from_value(value.clone())
+                            .map_err(|_| anyhow::anyhow!("Tag '{}' must be a string, number or boolean", key))?;
+                        Ok((key.clone(), value))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                self.tags = tags.iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join(",");
+                tags
+            }
+            _ => parse_tags(&self.tags)?,
+        };
+
+        let fields = [
+            ("test_name", &self.test_name),
+            ("operator", &self.operator),
+            ("device_serial", &self.device_serial),
+        ];
+        self.annotations = fields
+            .into_iter()
+            .filter(|(_, value)| !value.trim().is_empty())
+            .map(|(key, value)| (key.to_string(), MetadataValue::Text(value.trim().to_string())))
+            .collect();
+        // Custom tags come last so they win over the named fields
+        self.annotations.append(&mut tags);
+        Ok(())
+    }
+
+    async fn process(&mut self, mut frame: DataFrame) -> Result<DataFrame> {
+        for (key, value) in &self.annotations {
+            if self.overwrite || !frame.metadata.contains_key(key) {
+                frame.metadata.insert(key.clone(), value.clone());
+            }
+        }
+        Ok(frame)
+    }
+}
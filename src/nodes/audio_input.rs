@@ -1,12 +1,13 @@
 use crate::core::{DataFrame, ProcessingNode};
-use crate::hal::DeviceChannels;
-use crate::hal::format_converter::packet_to_frame;
+use crate::dsp::FirFilter;
+use crate::hal::{DeviceChannels, FrequencyResponse};
+use crate::hal::format_converter::FrameConverter;
 use crate::visualization::RingBufferWriter;
 use anyhow::Result;
 use async_trait::async_trait;
 use audiotab_macros::StreamNode;
 use serde::{Deserialize, Serialize};
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 /// AudioInputNode bridges hardware device to processing pipeline
 ///
@@ -15,18 +16,26 @@ use std::sync::{Arc, Mutex};
 /// - Converts PacketBuffer → DataFrame using format_converter
 /// - Returns buffers to device (ping-pong pattern)
 /// - Writes to RingBufferWriter for visualization
+/// - Flattens the microphone response when a `FrequencyResponse` is set
+///   (linear-phase FIR, delays by `(compensation_taps - 1) / 2` samples)
 #[derive(StreamNode, Serialize, Deserialize)]
 #[node_meta(name = "Audio Input", category = "Sources")]
 pub struct AudioInputNode {
     #[output(name = "Audio Out", data_type = "audio_frame")]
     _output: (),
 
-    #[param(default = "48000", min = 8000.0, max = 192000.0)]
+    #[param(default = "48000", min = 8000.0, max = 192000.0, unit = "Hz")]
     pub sample_rate: u64,
 
-    #[param(default = "1", min = 1.0, max = 32.0)]
+    #[param(default = "1", min = 1.0, max = 32.0, step = 1.0)]
     pub num_channels: usize,
 
+    #[param(default = "true")]
+    pub compensate: bool,
+
+    #[param(default = "511", min = 31.0, max = 4095.0, step = 2.0, unit = "taps")]
+    pub compensation_taps: usize,
+
     #[serde(skip)]
     format_str: String,
 
@@ -37,7 +46,17 @@ pub struct AudioInputNode {
     device_channels: Option<DeviceChannels>,
 
     #[serde(skip)]
-    ring_buffer: Option<Arc<Mutex<RingBufferWriter>>>,
+    converter: FrameConverter,
+
+    #[serde(skip)]
+    ring_buffer: Option<Arc<RingBufferWriter>>,
+
+    #[serde(skip)]
+    frequency_response: Option<FrequencyResponse>,
+
+    /// Per-channel compensation filters and the sample rate they were designed for
+    #[serde(skip)]
+    compensation: Option<(f64, Vec<FirFilter>)>,
 }
 
 impl std::fmt::Debug for AudioInputNode {
@@ -45,6 +64,8 @@ impl std::fmt::Debug for AudioInputNode {
         f.debug_struct("AudioInputNode")
             .field("sample_rate", &self.sample_rate)
             .field("num_channels", &self.num_channels)
+            .field("compensate", &self.compensate)
+            .field("has_frequency_response", &self.frequency_response.is_some())
             .field("format", &self.format_str)
             .field("sequence", &self.sequence)
             .finish()
@@ -57,10 +78,15 @@ impl Clone for AudioInputNode {
             _output: (),
             sample_rate: self.sample_rate,
             num_channels: self.num_channels,
+            compensate: self.compensate,
+            compensation_taps: self.compensation_taps,
             format_str: self.format_str.clone(),
             sequence: self.sequence,
             device_channels: None, // Don't clone channels
+            converter: FrameConverter::new(),
             ring_buffer: self.ring_buffer.clone(),
+            frequency_response: self.frequency_response.clone(),
+            compensation: None,
         }
     }
 }
@@ -73,16 +99,56 @@ impl AudioInputNode {
     /// * `ring_buffer` - Optional RingBufferWriter for visualization
     pub fn new(
         channels: DeviceChannels,
-        ring_buffer: Option<Arc<Mutex<RingBufferWriter>>>,
+        ring_buffer: Option<Arc<RingBufferWriter>>,
     ) -> Self {
         Self {
             _output: (),
             sample_rate: 48000,
             num_channels: 1,
+            compensate: true,
+            compensation_taps: 511,
             format_str: "F32".to_string(),
             sequence: 0,
             device_channels: Some(channels),
+            converter: FrameConverter::new(),
             ring_buffer,
+            frequency_response: None,
+            compensation: None,
+        }
+    }
+
+    /// Compensate for a measured microphone response (usually the device profile's)
+    pub fn with_frequency_response(mut self, response: Option<FrequencyResponse>) -> Self {
+        self.set_frequency_response(response);
+        self
+    }
+
+    pub fn set_frequency_response(&mut self, response: Option<FrequencyResponse>) {
+        self.frequency_response = response;
+        self.compensation = None;
+    }
+
+    /// Run every channel through the compensation filters, designing them on first use
+    fn compensate_frame(&mut self, frame: &mut DataFrame) {
+        let Some(response) = self.frequency_response.as_ref().filter(|_| self.compensate) else {
+            return;
+        };
+        let sample_rate = frame.sample_rate().unwrap_or(self.sample_rate as f64);
+        if self.compensation.as_ref().is_none_or(|(rate, _)| *rate != sample_rate) {
+            self.compensation = Some((sample_rate, Vec::new()));
+        }
+        let Some((_, filters)) = self.compensation.as_mut() else { return };
+
+        for ch in 0.. {
+            let Some(channel) = frame.payload.get_mut(&format!("ch{}", ch)) else { break };
+            if filters.len() <= ch {
+                let taps = match filters.first() {
+                    Some(filter) => filter.taps().to_vec(),
+                    None => response.compensation_fir(sample_rate, self.compensation_taps),
+                };
+                filters.push(FirFilter::new(taps));
+            }
+            filters[ch].process_block(channel.samples_mut());
         }
     }
 }
@@ -93,23 +159,22 @@ impl Default for AudioInputNode {
             _output: (),
             sample_rate: 48000,
             num_channels: 1,
+            compensate: true,
+            compensation_taps: 511,
             format_str: "F32".to_string(),
             sequence: 0,
             device_channels: None,
+            converter: FrameConverter::new(),
             ring_buffer: None,
+            frequency_response: None,
+            compensation: None,
         }
     }
 }
 
 #[async_trait]
 impl ProcessingNode for AudioInputNode {
-    async fn on_create(&mut self, config: serde_json::Value) -> Result<()> {
-        if let Some(sr) = config.get("sample_rate").and_then(|v| v.as_u64()) {
-            self.sample_rate = sr;
-        }
-        if let Some(nc) = config.get("num_channels").and_then(|v| v.as_u64()) {
-            self.num_channels = nc as usize;
-        }
+    async fn on_configured(&mut self, config: &serde_json::Value) -> Result<()> {
         if let Some(fmt) = config.get("format").and_then(|v| v.as_str()) {
             self.format_str = fmt.to_string();
         }
@@ -118,7 +183,7 @@ impl ProcessingNode for AudioInputNode {
 
     async fn process(&mut self, _input: DataFrame) -> Result<DataFrame> {
         // Try to receive a packet from the device
-        if let Some(ref channels) = self.device_channels {
+        if let Some(channels) = self.device_channels.clone() {
             // Use try_recv to avoid blocking (non-blocking receive)
             match channels.filled_rx.try_recv() {
                 Ok(packet) => {
@@ -138,26 +203,25 @@ impl ProcessingNode for AudioInputNode {
                     self.sequence += 1;
 
                     // Convert PacketBuffer to DataFrame
-                    let frame = packet_to_frame(&packet, self.sequence)
+                    let mut frame = self.converter.convert(&packet, self.sequence)
                         .map_err(|e| anyhow::anyhow!(
                             "Failed to convert packet to frame (format: {}, channels: {}): {}",
                             format_name, num_channels, e
                         ))?;
+                    self.compensate_frame(&mut frame);
 
                     // Write to ring buffer for visualization if available
-                    if let Some(ref rb) = self.ring_buffer {
-                        if let Ok(mut writer) = rb.lock() {
-                            // Extract channel data for ring buffer
-                            let mut channels_data = Vec::new();
-                            for ch in 0..self.num_channels {
-                                if let Some(ch_data) = frame.payload.get(&format!("ch{}", ch)) {
-                                    channels_data.push(ch_data.as_ref().clone());
-                                }
+                    if let Some(ref writer) = self.ring_buffer {
+                        // Extract channel data for ring buffer
+                        let mut channels_data = Vec::new();
+                        for ch in 0..self.num_channels {
+                            if let Some(ch_data) = frame.payload.get(&format!("ch{}", ch)) {
+                                channels_data.push(ch_data.samples());
                             }
-                            if !channels_data.is_empty() {
-                                if let Err(e) = writer.write(&channels_data) {
-                                    eprintln!("Ring buffer write failed: {}", e);
-                                }
+                        }
+                        if !channels_data.is_empty() {
+                            if let Err(e) = writer.write(&channels_data) {
+                                tracing::warn!("Ring buffer write failed: {}", e);
                             }
                         }
                     }
@@ -1,6 +1,7 @@
 use crate::core::{DataFrame, ProcessingNode};
+use crate::dsp::{Dither, DitherMode};
 use crate::hal::DeviceChannels;
-use crate::hal::format_converter::frame_to_packet;
+use crate::hal::format_converter::frame_to_packet_dithered;
 use crate::hal::types::SampleFormat;
 use anyhow::Result;
 use async_trait::async_trait;
@@ -22,21 +23,34 @@ use serde::{Deserialize, Serialize};
 ///
 /// This is the opposite of input nodes, where `empty_tx` sends empty buffers
 /// and `filled_rx` receives filled buffers.
+///
+/// # Dither
+///
+/// Conversion to an integer format truncates by default. `dither` set to
+/// `tpdf` adds triangular dither before rounding so quantization error is
+/// uncorrelated noise rather than distortion of the signal; `shaped` also
+/// feeds the error back to push that noise towards Nyquist.
 #[derive(StreamNode, Serialize, Deserialize)]
 #[node_meta(name = "Audio Output", category = "Sinks")]
 pub struct AudioOutputNode {
     #[input(name = "Audio In", data_type = "audio_frame")]
     _input: (),
 
-    #[param(default = "48000", min = 8000.0, max = 192000.0)]
+    #[param(default = "48000", min = 8000.0, max = 192000.0, unit = "Hz")]
     pub sample_rate: u64,
 
-    #[param(default = "1", min = 1.0, max = 32.0)]
+    #[param(default = "1", min = 1.0, max = 32.0, step = 1.0)]
     pub num_channels: usize,
 
+    #[param(default = "\"none\"", choices = "none,tpdf,shaped")]
+    pub dither: String,
+
     #[serde(skip)]
     format: SampleFormat,
 
+    #[serde(skip)]
+    ditherer: Dither,
+
     #[serde(skip)]
     device_channels: Option<DeviceChannels>,
 }
@@ -47,6 +61,7 @@ impl std::fmt::Debug for AudioOutputNode {
             .field("sample_rate", &self.sample_rate)
             .field("num_channels", &self.num_channels)
             .field("format", &self.format)
+            .field("dither", &self.dither)
             .finish()
     }
 }
@@ -57,7 +72,9 @@ impl Clone for AudioOutputNode {
             _input: (),
             sample_rate: self.sample_rate,
             num_channels: self.num_channels,
+            dither: self.dither.clone(),
             format: self.format,
+            ditherer: Dither::new(self.ditherer.mode()),
             device_channels: None, // Don't clone channels
         }
     }
@@ -74,7 +91,9 @@ impl AudioOutputNode {
             _input: (),
             sample_rate: 48000,
             num_channels: 1,
+            dither: "none".to_string(),
             format,
+            ditherer: Dither::new(DitherMode::None),
             device_channels: Some(channels),
         }
     }
@@ -86,7 +105,9 @@ impl Default for AudioOutputNode {
             _input: (),
             sample_rate: 48000,
             num_channels: 1,
+            dither: "none".to_string(),
             format: SampleFormat::F32,
+            ditherer: Dither::new(DitherMode::None),
             device_channels: None,
         }
     }
@@ -94,13 +115,7 @@ impl Default for AudioOutputNode {
 
 #[async_trait]
 impl ProcessingNode for AudioOutputNode {
-    async fn on_create(&mut self, config: serde_json::Value) -> Result<()> {
-        if let Some(sr) = config.get("sample_rate").and_then(|v| v.as_u64()) {
-            self.sample_rate = sr;
-        }
-        if let Some(nc) = config.get("num_channels").and_then(|v| v.as_u64()) {
-            self.num_channels = nc as usize;
-        }
+    async fn on_configured(&mut self, config: &serde_json::Value) -> Result<()> {
         if let Some(fmt) = config.get("format").and_then(|v| v.as_str()) {
             self.format = match fmt {
                 "I16" => SampleFormat::I16,
@@ -112,6 +127,7 @@ impl ProcessingNode for AudioOutputNode {
                 _ => SampleFormat::F32, // Default fallback
             };
         }
+        self.ditherer = Dither::new(DitherMode::parse(&self.dither)?);
         Ok(())
     }
 
@@ -129,7 +145,7 @@ impl ProcessingNode for AudioOutputNode {
         // Try to send the frame to the device
         if let Some(ref channels) = self.device_channels {
             // Convert DataFrame to PacketBuffer
-            let packet = frame_to_packet(&input, self.format, self.sample_rate)
+            let packet = frame_to_packet_dithered(&input, self.format, self.sample_rate, &mut self.ditherer)
                 .map_err(|e| anyhow::anyhow!(
                     "Failed to convert frame to packet (format: {:?}, sample_rate: {}): {}",
                     self.format, self.sample_rate, e
@@ -1,12 +1,13 @@
+use crate::buffers::FramePool;
 use crate::core::{ProcessingNode, DataFrame};
 use crate::hal::DeviceChannels;
-use crate::hal::format_converter::packet_to_frame;
+use crate::hal::format_converter::FrameConverter;
 use crate::visualization::RingBufferWriter;
 use anyhow::Result;
 use async_trait::async_trait;
 use audiotab_macros::StreamNode;
 use serde::{Deserialize, Serialize};
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 /// AudioSourceNode provides audio input from either a hardware device or silent fallback.
 ///
@@ -18,6 +19,9 @@ use std::sync::{Arc, Mutex};
 ///    - Outputs channels as `ch0`, `ch1`, `ch2`, etc.
 ///    - Uses the format from the original HAL implementation
 ///    - Supports multi-channel audio from the device
+///    - With a `device_profile_id`, the kernel's reader for that registered
+///      device sends frames (tagged with `device` metadata) to this node's
+///      input and they are passed through
 ///
 /// 2. **Silent Mode** (fallback when no device or no packet available):
 ///    - Outputs channel as `main_channel`
@@ -32,13 +36,13 @@ pub struct AudioSourceNode {
     #[output(name = "Audio Out", data_type = "audio_frame")]
     _output: (),
 
-    #[param(default = "48000", min = 8000.0, max = 192000.0)]
+    #[param(default = "48000", min = 8000.0, max = 192000.0, unit = "Hz")]
     pub sample_rate: u32,
 
-    #[param(default = "1024", min = 64.0, max = 8192.0)]
+    #[param(default = "1024", min = 1.0, max = 8192.0, unit = "samples", log_scale)]
     pub buffer_size: u32,
 
-    #[param(default = "1", min = 1.0, max = 32.0)]
+    #[param(default = "1", min = 1.0, max = 32.0, step = 1.0)]
     pub num_channels: usize,
 
     // NEW: Device selection parameter
@@ -49,10 +53,13 @@ pub struct AudioSourceNode {
     sequence: u64,
 
     #[serde(skip)]
-    ring_buffer: Option<Arc<Mutex<RingBufferWriter>>>,
+    ring_buffer: Option<Arc<RingBufferWriter>>,
 
     #[serde(skip)]
     device_channels: Option<DeviceChannels>,
+
+    #[serde(skip)]
+    converter: FrameConverter,
 }
 
 // Manual Debug implementation since DeviceChannels doesn't implement Debug
@@ -80,6 +87,7 @@ impl Clone for AudioSourceNode {
             sequence: self.sequence,
             ring_buffer: self.ring_buffer.clone(),
             device_channels: None, // Don't clone device channels
+            converter: FrameConverter::new(),
         }
     }
 }
@@ -95,6 +103,7 @@ impl Default for AudioSourceNode {
             sequence: 0,
             ring_buffer: None,
             device_channels: None,
+            converter: FrameConverter::new(),
         }
     }
 }
@@ -110,7 +119,7 @@ impl AudioSourceNode {
     /// If no device is available, the node falls back to silent audio.
     pub fn with_device(
         channels: DeviceChannels,
-        ring_buffer: Option<Arc<Mutex<RingBufferWriter>>>,
+        ring_buffer: Option<Arc<RingBufferWriter>>,
     ) -> Self {
         Self {
             _output: (),
@@ -121,6 +130,7 @@ impl AudioSourceNode {
             sequence: 0,
             ring_buffer,
             device_channels: Some(channels),
+            converter: FrameConverter::new(),
         }
     }
 
@@ -128,7 +138,7 @@ impl AudioSourceNode {
     ///
     /// # Arguments
     /// * `ring_buffer` - Optional RingBufferWriter for visualization
-    pub fn set_ring_buffer(&mut self, ring_buffer: Option<Arc<Mutex<RingBufferWriter>>>) {
+    pub fn set_ring_buffer(&mut self, ring_buffer: Option<Arc<RingBufferWriter>>) {
         self.ring_buffer = ring_buffer;
     }
 
@@ -139,34 +149,36 @@ impl AudioSourceNode {
     pub fn set_device_channels(&mut self, channels: Option<DeviceChannels>) {
         self.device_channels = channels;
     }
-}
 
-#[async_trait]
-impl ProcessingNode for AudioSourceNode {
-    async fn on_create(&mut self, config: serde_json::Value) -> Result<()> {
-        if let Some(sr) = config.get("sample_rate").and_then(|v| v.as_u64()) {
-            self.sample_rate = sr as u32;
-        }
-        if let Some(bs) = config.get("buffer_size").and_then(|v| v.as_u64()) {
-            self.buffer_size = bs as u32;
-        }
-        if let Some(nc) = config.get("num_channels").and_then(|v| v.as_u64()) {
-            let nc_usize = nc as usize;
-            if nc_usize < 1 || nc_usize > 32 {
-                anyhow::bail!("num_channels must be between 1 and 32, got {}", nc_usize);
+    /// Write the `ch0..chN` channels of a device frame to the ring buffer
+    fn write_device_frame(&self, frame: &DataFrame) {
+        if let Some(ref writer) = self.ring_buffer {
+            // Extract channel data for ring buffer
+            let mut channels_data = Vec::new();
+            for ch in 0..self.num_channels {
+                if let Some(ch_data) = frame.payload.get(&format!("ch{}", ch)) {
+                    channels_data.push(ch_data.samples());
+                }
+            }
+            if !channels_data.is_empty() {
+                if let Err(e) = writer.write(&channels_data) {
+                    tracing::warn!("Ring buffer write failed: {}", e);
+                }
             }
-            self.num_channels = nc_usize;
-        }
-
-        // NEW: Extract device_profile_id
-        if let Some(profile_id) = config.get("device_profile_id").and_then(|v| v.as_str()) {
-            self.device_profile_id = profile_id.to_string();
         }
-
-        Ok(())
     }
+}
 
+#[async_trait]
+impl ProcessingNode for AudioSourceNode {
     async fn process(&mut self, mut frame: DataFrame) -> Result<DataFrame> {
+        // Frames dispatched by the kernel's device reader already carry the audio
+        if frame.metadata.contains_key("device") {
+            self.write_device_frame(&frame);
+            self.sequence += 1;
+            return Ok(frame);
+        }
+
         // Try to read from device if available
         if let Some(ref channels) = self.device_channels {
             match channels.filled_rx.try_recv() {
@@ -186,7 +198,7 @@ impl ProcessingNode for AudioSourceNode {
                     let num_channels = packet.num_channels;
 
                     // Convert PacketBuffer to DataFrame
-                    let converted_frame = packet_to_frame(&packet, self.sequence)
+                    let converted_frame = self.converter.convert(&packet, self.sequence)
                         .map_err(|e| anyhow::anyhow!(
                             "Failed to convert packet to frame (format: {}, channels: {}): {}",
                             format_name, num_channels, e
@@ -196,22 +208,7 @@ impl ProcessingNode for AudioSourceNode {
                     self.sequence += 1;
 
                     // Write to ring buffer for visualization if available
-                    if let Some(ref rb) = self.ring_buffer {
-                        if let Ok(mut writer) = rb.lock() {
-                            // Extract channel data for ring buffer
-                            let mut channels_data = Vec::new();
-                            for ch in 0..self.num_channels {
-                                if let Some(ch_data) = converted_frame.payload.get(&format!("ch{}", ch)) {
-                                    channels_data.push(ch_data.as_ref().clone());
-                                }
-                            }
-                            if !channels_data.is_empty() {
-                                if let Err(e) = writer.write(&channels_data) {
-                                    eprintln!("Ring buffer write failed: {}", e);
-                                }
-                            }
-                        }
-                    }
+                    self.write_device_frame(&converted_frame);
 
                     // Return the buffer to the device (ping-pong pattern)
                     let _ = channels.empty_tx.send(packet);
@@ -225,18 +222,17 @@ impl ProcessingNode for AudioSourceNode {
         }
 
         // No device or no packet available - generate silent audio (backward compatible)
-        let samples = vec![0.0; self.buffer_size as usize];
+        let pool = FramePool::global();
+        let samples = pool.take_zeroed(self.buffer_size as usize);
 
         // Write to ring buffer
-        if let Some(rb) = &self.ring_buffer {
-            if let Ok(mut writer) = rb.lock() {
-                let _ = writer.write(&vec![samples.clone()]); // Single channel for now
-            }
+        if let Some(writer) = &self.ring_buffer {
+            let _ = writer.write(&[samples.as_slice()]); // Single channel for now
         }
 
-        frame.payload.insert(
-            "main_channel".to_string(),
-            std::sync::Arc::new(samples),
+        frame.insert_channel(
+            "main_channel",
+            pool.channel(samples).with_sample_rate(self.sample_rate as f64),
         );
 
         self.sequence += 1;
@@ -0,0 +1,114 @@
+use crate::core::{DataFrame, ProcessingNode};
+use crate::dsp::{AveragingMode, SpectrumAverager};
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// AveragingNode accumulates spectra or band levels across frames
+///
+/// Every channel is averaged bin by bin, per channel name: `linear` takes
+/// the mean of the last `num_averages` frames, `exponential` weights the
+/// previous average by `forgetting_factor`, and `peak_hold` keeps the
+/// maximum of each bin. A channel whose length changes starts over.
+///
+/// Frames arriving on the Reset input, or carrying `reset_average`
+/// metadata, clear the accumulated values; reset frames on the Reset input
+/// are emitted with an empty payload. Output frames carry the number of
+/// frames averaged so far as `averages`.
+#[derive(StreamNode, Debug, Clone, Serialize, Deserialize)]
+#[node_meta(name = "Averaging", category = "Processors")]
+#[preset(name = "Stable noise spectrum", params = r#"{"mode": "linear", "num_averages": 64}"#)]
+#[preset(name = "Max hold", params = r#"{"mode": "peak_hold"}"#)]
+pub struct AveragingNode {
+    #[input(name = "Spectrum In", data_type = "any")]
+    _input: (),
+
+    #[input(name = "Reset", data_type = "trigger")]
+    _reset: (),
+
+    #[output(name = "Average Out", data_type = "any")]
+    _output: (),
+
+    #[param(default = "\"linear\"", choices = "linear,exponential,peak_hold")]
+    pub mode: String,
+
+    #[param(default = "16", min = 1.0, max = 10000.0, step = 1.0)]
+    pub num_averages: usize,
+
+    #[param(default = "0.9", min = 0.0, max = 0.9999)]
+    pub forgetting_factor: f64,
+
+    #[serde(skip)]
+    averagers: HashMap<String, SpectrumAverager>,
+}
+
+impl Default for AveragingNode {
+    fn default() -> Self {
+        Self {
+            _input: (),
+            _reset: (),
+            _output: (),
+            mode: "linear".to_string(),
+            num_averages: 16,
+            forgetting_factor: 0.9,
+            averagers: HashMap::new(),
+        }
+    }
+}
+
+impl AveragingNode {
+    /// Discard the accumulated values of every channel
+    pub fn reset(&mut self) {
+        self.averagers.clear();
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for AveragingNode {
+    async fn on_configured(&mut self, _config: &serde_json::Value) -> Result<()> {
+        if AveragingMode::parse(&self.mode)? == AveragingMode::None {
+            anyhow::bail!("Averaging mode must be linear, exponential or peak_hold");
+        }
+        if !(0.0..1.0).contains(&self.forgetting_factor) {
+            anyhow::bail!("forgetting_factor must be in [0.0, 1.0), got {}", self.forgetting_factor);
+        }
+        self.reset();
+        Ok(())
+    }
+
+    async fn process(&mut self, mut frame: DataFrame) -> Result<DataFrame> {
+        let mode = AveragingMode::parse(&self.mode)?;
+
+        if frame.metadata.get_str("input_port") == Some("_reset") {
+            self.reset();
+            frame.payload.clear();
+            return Ok(frame);
+        }
+        if frame.metadata.get_bool("reset_average") == Some(true) {
+            self.reset();
+        }
+
+        let (num_averages, forgetting_factor) = (self.num_averages, self.forgetting_factor);
+        let input = std::mem::take(&mut frame.payload);
+        let mut averages = 0;
+        for (channel, samples) in input {
+            let averager = self.averagers.entry(channel.clone()).or_insert_with(|| {
+                SpectrumAverager::new(mode, num_averages).with_forgetting_factor(forgetting_factor)
+            });
+            let average = averager.push(samples.to_vec()).to_vec();
+            averages = averages.max(averager.count());
+            frame.payload.insert(channel, samples.with_samples(average));
+        }
+
+        frame.metadata.insert("averages", averages);
+        frame.metadata.insert("averaging", &self.mode);
+        Ok(frame)
+    }
+
+    async fn on_destroy(&mut self) -> Result<()> {
+        self.reset();
+        Ok(())
+    }
+}
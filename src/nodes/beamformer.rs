@@ -0,0 +1,364 @@
+use crate::core::{Channel, ChannelRole, DataFrame, ProcessingNode};
+use crate::dsp::{DelayLine, Interpolation};
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use serde::{Deserialize, Serialize};
+
+/// Microphone layout of the array, in metres in the horizontal plane
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArrayGeometry {
+    /// Equally spaced along the x axis, centred on the origin
+    Linear { spacing: f64 },
+    /// Equally spaced on a circle around the origin, first mic at 0 degrees
+    Circular { radius: f64 },
+    /// Explicit (x, y) position per microphone
+    Custom(Vec<(f64, f64)>),
+}
+
+impl ArrayGeometry {
+    /// Geometry from the node's `geometry` name and its size parameters
+    pub fn parse(name: &str, spacing: f64, radius: f64, positions: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "linear" => Ok(ArrayGeometry::Linear { spacing }),
+            "circular" => Ok(ArrayGeometry::Circular { radius }),
+            "custom" => {
+                let positions = positions
+                    .split(';')
+                    .filter(|p| !p.trim().is_empty())
+                    .map(|p| {
+                        let coords: Vec<f64> = p
+                            .split(',')
+                            .map(|c| c.trim().parse::<f64>())
+                            .collect::<std::result::Result<_, _>>()
+                            .map_err(|e| anyhow::anyhow!("Invalid mic position '{}': {}", p.trim(), e))?;
+                        match coords[..] {
+                            [x, y] => Ok((x, y)),
+                            _ => anyhow::bail!("Mic position '{}' must be 'x,y'", p.trim()),
+                        }
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(ArrayGeometry::Custom(positions))
+            }
+            _ => anyhow::bail!("Unknown array geometry: {}", name),
+        }
+    }
+
+    /// Positions of `count` microphones
+    pub fn positions(&self, count: usize) -> Result<Vec<(f64, f64)>> {
+        match self {
+            ArrayGeometry::Linear { spacing } => {
+                let centre = (count as f64 - 1.0) / 2.0;
+                Ok((0..count).map(|i| ((i as f64 - centre) * spacing, 0.0)).collect())
+            }
+            ArrayGeometry::Circular { radius } => Ok((0..count)
+                .map(|i| {
+                    let phi = 2.0 * std::f64::consts::PI * i as f64 / count as f64;
+                    (radius * phi.sin(), radius * phi.cos())
+                })
+                .collect()),
+            ArrayGeometry::Custom(positions) => {
+                if positions.len() != count {
+                    anyhow::bail!(
+                        "Array has {} mic positions but {} channels",
+                        positions.len(), count
+                    );
+                }
+                Ok(positions.clone())
+            }
+        }
+    }
+
+    /// Directions worth scanning: a line array cannot tell front from back
+    fn scan_range(&self) -> (f64, f64) {
+        match self {
+            ArrayGeometry::Linear { .. } => (-90.0, 90.0),
+            _ => (-180.0, 180.0 - f64::EPSILON),
+        }
+    }
+}
+
+/// Delay lines aligning every microphone for one look direction
+#[derive(Debug, Clone)]
+struct Beam {
+    angle_deg: f64,
+    lines: Vec<DelayLine>,
+}
+
+impl Beam {
+    /// Delay each mic so a plane wave from `angle_deg` adds up in phase
+    ///
+    /// Angles are measured in the horizontal plane from the +y axis
+    /// (broadside of a linear array) towards +x.
+    fn new(angle_deg: f64, positions: &[(f64, f64)], speed_of_sound: f64, sample_rate: f64) -> Self {
+        let (ux, uy) = (angle_deg.to_radians().sin(), angle_deg.to_radians().cos());
+        // Mics further along the look direction hear the wave first
+        let leads: Vec<f64> = positions.iter().map(|(x, y)| (x * ux + y * uy) / speed_of_sound).collect();
+        let earliest = leads.iter().cloned().fold(f64::INFINITY, f64::min);
+        let lines = leads
+            .iter()
+            .map(|lead| DelayLine::new((lead - earliest) * sample_rate, Interpolation::Linear))
+            .collect();
+        Self { angle_deg, lines }
+    }
+
+    /// Delay-and-sum of one frame of mic signals
+    fn steer(&mut self, mics: &[&Channel]) -> Vec<f64> {
+        let len = mics.first().map_or(0, |m| m.len());
+        let gain = 1.0 / mics.len() as f64;
+        (0..len)
+            .map(|i| {
+                mics.iter()
+                    .zip(self.lines.iter_mut())
+                    .map(|(mic, line)| line.process(mic[i]))
+                    .sum::<f64>()
+                    * gain
+            })
+            .collect()
+    }
+}
+
+/// BeamformerNode steers a microphone array by delay-and-sum
+///
+/// `channels` lists the synchronized mic channels in array order (comma
+/// separated; empty takes every channel sorted by name), and `geometry`
+/// places them: `linear` with `spacing_m`, `circular` with `radius_m`, or
+/// `custom` with `positions` given as `"x,y;x,y;..."` in metres.
+///
+/// In `beams` mode every angle of `steering_deg` (comma separated, degrees
+/// from broadside towards +x) becomes an output channel `beam_<angle>`.
+/// In `doa` mode the node scans directions every `scan_step_deg` and outputs
+/// the steered response power per direction as `steered_power` plus the
+/// loudest direction as `doa_deg` (also set as metadata). The resolution is
+/// coarse: it is limited by the array aperture and the signal's bandwidth.
+#[derive(StreamNode, Debug, Clone, Serialize, Deserialize)]
+#[node_meta(name = "Beamformer", category = "Processors")]
+#[preset(name = "Direction finder", params = r#"{"mode": "doa", "scan_step_deg": 2.0}"#)]
+pub struct BeamformerNode {
+    #[input(name = "Array In", data_type = "audio_frame")]
+    _input: (),
+
+    #[output(name = "Beams Out", data_type = "audio_frame")]
+    _output: (),
+
+    #[param(default = "\"\"", skip_config)]
+    pub channels: String,
+
+    #[param(default = "\"linear\"", choices = "linear,circular,custom")]
+    pub geometry: String,
+
+    #[param(default = "0.05", min = 0.001, max = 10.0, unit = "m")]
+    pub spacing_m: f64,
+
+    #[param(default = "0.05", min = 0.001, max = 10.0, unit = "m")]
+    pub radius_m: f64,
+
+    #[param(default = "\"\"", skip_config)]
+    pub positions: String,
+
+    #[param(default = "\"beams\"", choices = "beams,doa")]
+    pub mode: String,
+
+    #[param(default = "\"0\"")]
+    pub steering_deg: String,
+
+    #[param(default = "5.0", min = 0.1, max = 90.0, unit = "deg")]
+    pub scan_step_deg: f64,
+
+    #[param(default = "343.0", min = 100.0, max = 2000.0, unit = "m/s")]
+    pub speed_of_sound: f64,
+
+    /// Beams built for the sample rate they were planned at
+    #[serde(skip)]
+    beams: Vec<Beam>,
+
+    #[serde(skip)]
+    planned_rate: Option<f64>,
+}
+
+impl Default for BeamformerNode {
+    fn default() -> Self {
+        Self {
+            _input: (),
+            _output: (),
+            channels: String::new(),
+            geometry: "linear".to_string(),
+            spacing_m: 0.05,
+            radius_m: 0.05,
+            positions: String::new(),
+            mode: "beams".to_string(),
+            steering_deg: "0".to_string(),
+            scan_step_deg: 5.0,
+            speed_of_sound: 343.0,
+            beams: Vec::new(),
+            planned_rate: None,
+        }
+    }
+}
+
+impl BeamformerNode {
+    fn is_doa(&self) -> bool {
+        self.mode.eq_ignore_ascii_case("doa")
+    }
+
+    fn array_geometry(&self) -> Result<ArrayGeometry> {
+        ArrayGeometry::parse(&self.geometry, self.spacing_m, self.radius_m, &self.positions)
+    }
+
+    /// Look directions: the steering list, or the scan grid in `doa` mode
+    fn angles(&self, geometry: &ArrayGeometry) -> Result<Vec<f64>> {
+        if self.is_doa() {
+            let (start, end) = geometry.scan_range();
+            let steps = ((end - start) / self.scan_step_deg).floor() as usize;
+            return Ok((0..=steps).map(|i| start + i as f64 * self.scan_step_deg).collect());
+        }
+        let angles = self
+            .steering_deg
+            .split(',')
+            .filter(|a| !a.trim().is_empty())
+            .map(|a| {
+                a.trim()
+                    .parse::<f64>()
+                    .map_err(|e| anyhow::anyhow!("Invalid steering angle '{}': {}", a.trim(), e))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if angles.is_empty() {
+            anyhow::bail!("steering_deg must list at least one angle");
+        }
+        Ok(angles)
+    }
+
+    /// Mic channel names in array order
+    fn mic_channels(&self, frame: &DataFrame) -> Vec<String> {
+        if self.channels.trim().is_empty() {
+            let mut names: Vec<String> = frame.payload.keys().cloned().collect();
+            names.sort();
+            names
+        } else {
+            self.channels.split(',').map(|c| c.trim().to_string()).collect()
+        }
+    }
+
+    fn plan(&mut self, mics: usize, sample_rate: f64) -> Result<()> {
+        let geometry = self.array_geometry()?;
+        let positions = geometry.positions(mics)?;
+        self.beams = self
+            .angles(&geometry)?
+            .into_iter()
+            .map(|angle| Beam::new(angle, &positions, self.speed_of_sound, sample_rate))
+            .collect();
+        self.planned_rate = Some(sample_rate);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for BeamformerNode {
+    async fn on_configured(&mut self, config: &serde_json::Value) -> Result<()> {
+        if let Some(c) = config.get("channels") {
+            // Accept either "ch0,ch1" or ["ch0", "ch1"]
+            if let Some(s) = c.as_str() {
+                self.channels = s.to_string();
+            } else if let Some(list) = c.as_array() {
+                self.channels = list
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .collect::<Vec<_>>()
+                    .join(",");
+            }
+        }
+        if let Some(p) = config.get("positions") {
+            // Accept either "x,y;x,y" or [[x, y], [x, y]]
+            if let Some(s) = p.as_str() {
+                self.positions = s.to_string();
+            } else if let Some(list) = p.as_array() {
+                self.positions = list
+                    .iter()
+                    .filter_map(|v| v.as_array())
+                    .map(|xy| xy.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(","))
+                    .collect::<Vec<_>>()
+                    .join(";");
+            }
+        }
+
+        match self.mode.to_ascii_lowercase().as_str() {
+            "beams" | "doa" => {}
+            other => anyhow::bail!("Unknown beamformer mode: {}", other),
+        }
+        if self.speed_of_sound <= 0.0 || self.scan_step_deg <= 0.0 {
+            anyhow::bail!("speed_of_sound and scan_step_deg must be positive");
+        }
+        let geometry = self.array_geometry()?;
+        if let ArrayGeometry::Custom(positions) = &geometry {
+            if positions.len() < 2 {
+                anyhow::bail!("Custom geometry needs at least 2 mic positions");
+            }
+        }
+        self.angles(&geometry)?;
+
+        self.beams.clear();
+        self.planned_rate = None;
+        Ok(())
+    }
+
+    async fn process(&mut self, mut frame: DataFrame) -> Result<DataFrame> {
+        let sample_rate = frame.sample_rate().unwrap_or(48000.0);
+        let names = self.mic_channels(&frame);
+        if names.len() < 2 {
+            anyhow::bail!("Beamforming needs at least 2 mic channels, got {}", names.len());
+        }
+
+        let input = std::mem::take(&mut frame.payload);
+        let mics = names
+            .iter()
+            .map(|name| input.get(name).ok_or_else(|| anyhow::anyhow!("Missing mic channel '{}'", name)))
+            .collect::<Result<Vec<_>>>()?;
+        let len = mics[0].len();
+        if mics.iter().any(|m| m.len() != len) {
+            anyhow::bail!("Mic channels must have equal lengths");
+        }
+
+        let mics_changed = self.beams.first().is_some_and(|b| b.lines.len() != mics.len());
+        if self.planned_rate != Some(sample_rate) || mics_changed {
+            self.plan(mics.len(), sample_rate)?;
+        }
+
+        let outputs: Vec<(f64, Vec<f64>)> = self
+            .beams
+            .iter_mut()
+            .map(|beam| (beam.angle_deg, beam.steer(&mics)))
+            .collect();
+
+        if self.is_doa() {
+            let powers: Vec<f64> = outputs
+                .iter()
+                .map(|(_, beam)| beam.iter().map(|s| s * s).sum::<f64>() / len.max(1) as f64)
+                .collect();
+            let (best, _) = powers
+                .iter()
+                .enumerate()
+                .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+                .expect("scan grid is non-empty");
+            let doa = outputs[best].0;
+
+            frame.insert_channel("steered_power", Channel::new(powers).with_role(ChannelRole::Measurement));
+            frame.insert_channel("doa_deg", Channel::scalar(doa).with_unit("deg"));
+            frame.metadata.insert("doa_deg", doa);
+            frame.metadata.insert("scan_start_deg", outputs[0].0);
+            frame.metadata.insert("scan_step_deg", self.scan_step_deg);
+        } else {
+            for (angle, beam) in outputs {
+                frame.insert_channel(format!("beam_{}", angle), mics[0].with_samples(beam));
+            }
+        }
+
+        frame.metadata.insert("array_channels", mics.len());
+        Ok(frame)
+    }
+
+    async fn on_destroy(&mut self) -> Result<()> {
+        self.beams.clear();
+        self.planned_rate = None;
+        Ok(())
+    }
+}
@@ -0,0 +1,388 @@
+use crate::core::{ProcessingNode, DataFrame};
+use crate::resilience::checkpoint;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use parquet::basic::{Compression, ZstdLevel};
+use parquet::data_type::{DoubleType, Int64Type};
+use parquet::file::metadata::{FileMetaData, KeyValue, ParquetMetaData, ParquetMetaDataWriter};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use parquet::schema::types::SchemaDescriptor;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Column-buffered rows waiting for the next row group
+#[derive(Debug, Default)]
+struct PendingRows {
+    sequence_id: Vec<i64>,
+    timestamp: Vec<i64>,
+    sample_index: Vec<i64>,
+    channels: Vec<Vec<f64>>,
+}
+
+impl PendingRows {
+    fn len(&self) -> usize {
+        self.sample_index.len()
+    }
+}
+
+/// CaptureSinkNode writes raw sample streams to a Parquet file
+///
+/// Each sample becomes one row with `sequence_id`, `timestamp`,
+/// `sample_index` (running count since the capture started) and one DOUBLE
+/// column per channel, named after the channel with characters other than
+/// `[A-Za-z0-9_]` replaced by `_`; names that would clash get a `_2`, `_3`,
+/// ... suffix. Channels are fixed by the first frame; missing samples are
+/// stored as NaN. Rows are written in row groups of `chunk_rows` using
+/// `compression` ("zstd", "snappy" or "none"), and the first frame's
+/// metadata is stored as key/value metadata in the file footer.
+///
+/// The footer is written in `on_destroy`. So that a crash does not lose the
+/// recording, pending rows are also written at least every
+/// `checkpoint_interval_ms`, and each row group updates a checkpoint from
+/// which `resilience::recover_file` rebuilds a readable file (see
+/// `resilience::checkpoint`). With `segment_ms` set, the recording rotates
+/// to a new, finished file `<name>.0001.parquet`, `<name>.0002.parquet`, ...
+/// at that interval; `sample_index` keeps counting across segments, and
+/// each segment stores its `segment` number and `first_sample_index` in the
+/// footer. HDF5 output is not supported.
+#[derive(StreamNode, Clone, Serialize, Deserialize)]
+#[node_meta(name = "Capture Sink", category = "Sinks")]
+pub struct CaptureSinkNode {
+    #[input(name = "Audio In", data_type = "audio_frame")]
+    _input: (),
+
+    #[param(default = "\"capture.parquet\"")]
+    pub path: String,
+
+    #[param(default = "\"zstd\"", choices = "zstd,snappy,none")]
+    pub compression: String,
+
+    #[param(default = "65536", min = 1.0, max = 100000000.0, unit = "rows", log_scale)]
+    pub chunk_rows: usize,
+
+    /// Longest time rows wait in memory; 0 writes them only when a row group fills
+    #[param(default = "5000", min = 0.0, max = 3600000.0, unit = "ms")]
+    pub checkpoint_interval_ms: u64,
+
+    /// Length of each file of the recording; 0 writes a single file
+    #[param(default = "0", min = 0.0, max = 86400000.0, unit = "ms")]
+    pub segment_ms: u64,
+
+    #[serde(skip)]
+    writer: Option<Arc<Mutex<SerializedFileWriter<File>>>>,
+
+    #[serde(skip)]
+    channel_names: Vec<String>,
+
+    #[serde(skip)]
+    pending: Arc<Mutex<PendingRows>>,
+
+    #[serde(skip)]
+    samples_written: u64,
+
+    /// File being written, `path` itself unless segmented
+    #[serde(skip)]
+    file_path: Option<PathBuf>,
+
+    /// Number of the current segment, from 1
+    #[serde(skip)]
+    segment: u32,
+
+    #[serde(skip)]
+    segment_started: Option<Instant>,
+
+    #[serde(skip)]
+    last_checkpoint: Option<Instant>,
+}
+
+impl std::fmt::Debug for CaptureSinkNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CaptureSinkNode")
+            .field("path", &self.path)
+            .field("compression", &self.compression)
+            .field("chunk_rows", &self.chunk_rows)
+            .field("checkpoint_interval_ms", &self.checkpoint_interval_ms)
+            .field("segment_ms", &self.segment_ms)
+            .field("segment", &self.segment)
+            .field("channel_names", &self.channel_names)
+            .field("samples_written", &self.samples_written)
+            .finish()
+    }
+}
+
+impl Default for CaptureSinkNode {
+    fn default() -> Self {
+        Self {
+            _input: (),
+            path: "capture.parquet".to_string(),
+            compression: "zstd".to_string(),
+            chunk_rows: 65536,
+            checkpoint_interval_ms: 5000,
+            segment_ms: 0,
+            writer: None,
+            channel_names: Vec::new(),
+            pending: Arc::new(Mutex::new(PendingRows::default())),
+            samples_written: 0,
+            file_path: None,
+            segment: 0,
+            segment_started: None,
+            last_checkpoint: None,
+        }
+    }
+}
+
+fn parse_compression(name: &str) -> Result<Compression> {
+    match name.to_ascii_lowercase().as_str() {
+        "zstd" => Ok(Compression::ZSTD(ZstdLevel::default())),
+        "snappy" => Ok(Compression::SNAPPY),
+        "none" | "uncompressed" => Ok(Compression::UNCOMPRESSED),
+        _ => anyhow::bail!("Unsupported capture compression: {}", name),
+    }
+}
+
+/// Columns written ahead of the channels
+const FIXED_COLUMNS: [&str; 3] = ["sequence_id", "timestamp", "sample_index"];
+
+/// Parquet column names only allow a restricted character set in the schema DSL
+fn column_name(channel: &str) -> String {
+    channel
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+        .collect()
+}
+
+/// Column names for `channels`, in order, with no two alike
+///
+/// Channels that map to the same name (`mic-1` and `mic_1`) or to a fixed
+/// column get a `_2`, `_3`, ... suffix rather than sharing a column.
+fn column_names(channels: &[String]) -> Vec<String> {
+    let mut taken: std::collections::HashSet<String> = FIXED_COLUMNS.iter().map(|c| c.to_string()).collect();
+    channels
+        .iter()
+        .map(|channel| {
+            let base = column_name(channel);
+            let mut name = base.clone();
+            let mut suffix = 2;
+            while !taken.insert(name.clone()) {
+                name = format!("{}_{}", base, suffix);
+                suffix += 1;
+            }
+            if name != base {
+                tracing::warn!(channel = %channel, column = %name, "Capture column name already used; renamed");
+            }
+            name
+        })
+        .collect()
+}
+
+/// Footer for the row groups written so far, finishing the file if it is cut there
+fn footer(writer: &SerializedFileWriter<File>) -> Result<Vec<u8>> {
+    let props = writer.properties();
+    let row_groups = writer.flushed_row_groups().to_vec();
+    let file_metadata = FileMetaData::new(
+        props.writer_version().as_num(),
+        row_groups.iter().map(|rg| rg.num_rows()).sum(),
+        Some(props.created_by().to_string()),
+        props.key_value_metadata().cloned(),
+        Arc::new(SchemaDescriptor::new(writer.schema_descr().root_schema_ptr())),
+        None,
+    );
+    let mut footer = Vec::new();
+    ParquetMetaDataWriter::new(&mut footer, &ParquetMetaData::new(file_metadata, row_groups)).finish()?;
+    Ok(footer)
+}
+
+impl CaptureSinkNode {
+    /// File of segment `segment`, e.g. `capture.0001.parquet`
+    fn segment_path(&self, segment: u32) -> PathBuf {
+        let path = Path::new(&self.path);
+        if self.segment_ms == 0 {
+            return path.to_path_buf();
+        }
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("capture");
+        let name = match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) => format!("{}.{:04}.{}", stem, segment, ext),
+            None => format!("{}.{:04}", stem, segment),
+        };
+        path.with_file_name(name)
+    }
+
+    /// Create the next file, with its schema from `frame`
+    fn open(&mut self, frame: &DataFrame) -> Result<()> {
+        let mut channels: Vec<String> = frame.payload.keys().cloned().collect();
+        channels.sort();
+
+        let mut message = String::from(
+            "message capture { REQUIRED INT64 sequence_id; REQUIRED INT64 timestamp; REQUIRED INT64 sample_index;",
+        );
+        for column in column_names(&channels) {
+            message.push_str(&format!(" REQUIRED DOUBLE {};", column));
+        }
+        message.push_str(" }");
+        let schema = Arc::new(parse_message_type(&message)?);
+
+        let segment = self.segment + 1;
+        let mut metadata: Vec<KeyValue> = frame
+            .metadata
+            .iter()
+            .map(|(k, v)| KeyValue::new(k.clone(), v.to_string()))
+            .collect();
+        metadata.push(KeyValue::new("channels".to_string(), channels.join(",")));
+        if self.segment_ms > 0 {
+            metadata.push(KeyValue::new("segment".to_string(), segment.to_string()));
+            metadata.push(KeyValue::new("first_sample_index".to_string(), self.samples_written.to_string()));
+        }
+        for channel in &channels {
+            if let Some(unit) = &frame.payload[channel].unit {
+                metadata.push(KeyValue::new(format!("unit.{}", channel), unit.clone()));
+            }
+        }
+        let props = WriterProperties::builder()
+            .set_compression(parse_compression(&self.compression)?)
+            .set_key_value_metadata(Some(metadata))
+            .build();
+
+        let path = self.segment_path(segment);
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        // A checkpoint left by an earlier run no longer matches the file
+        checkpoint::clear_checkpoint(&path)?;
+        let file = File::create(&path)
+            .with_context(|| format!("Failed to create capture file {}", path.display()))?;
+        let writer = SerializedFileWriter::new(file, schema, Arc::new(props))?;
+
+        self.writer = Some(Arc::new(Mutex::new(writer)));
+        self.pending = Arc::new(Mutex::new(PendingRows {
+            channels: vec![Vec::new(); channels.len()],
+            ..Default::default()
+        }));
+        self.channel_names = channels;
+        self.file_path = Some(path);
+        self.segment = segment;
+        self.segment_started = Some(Instant::now());
+        self.last_checkpoint = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Write all pending rows as one row group and checkpoint the file
+    fn write_row_group(&mut self) -> Result<()> {
+        self.last_checkpoint = Some(Instant::now());
+        let Some(writer) = &self.writer else { return Ok(()) };
+        let mut pending = self.pending.lock().map_err(|_| anyhow::anyhow!("Capture buffer lock poisoned"))?;
+        if pending.len() == 0 {
+            return Ok(());
+        }
+        let mut writer = writer.lock().map_err(|_| anyhow::anyhow!("Capture writer lock poisoned"))?;
+        let mut row_group = writer.next_row_group()?;
+
+        let int_columns = [&pending.sequence_id, &pending.timestamp, &pending.sample_index];
+        for values in int_columns {
+            let mut column = row_group.next_column()?.context("Missing capture column")?;
+            column.typed::<Int64Type>().write_batch(values, None, None)?;
+            column.close()?;
+        }
+        for values in &pending.channels {
+            let mut column = row_group.next_column()?.context("Missing capture column")?;
+            column.typed::<DoubleType>().write_batch(values, None, None)?;
+            column.close()?;
+        }
+        row_group.close()?;
+        if let Some(path) = &self.file_path {
+            checkpoint::write_checkpoint(path, writer.inner(), writer.bytes_written() as u64, &footer(&writer)?)?;
+        }
+
+        let channel_count = pending.channels.len();
+        *pending = PendingRows {
+            channels: vec![Vec::new(); channel_count],
+            ..Default::default()
+        };
+        Ok(())
+    }
+
+    /// Write the remaining rows and the footer, then drop the checkpoint
+    fn finish_file(&mut self) -> Result<()> {
+        self.write_row_group()?;
+        if let Some(writer) = self.writer.take() {
+            let writer = Arc::try_unwrap(writer)
+                .map_err(|_| anyhow::anyhow!("Capture writer still shared"))?
+                .into_inner()
+                .map_err(|_| anyhow::anyhow!("Capture writer lock poisoned"))?;
+            writer.close()?;
+        }
+        if let Some(path) = self.file_path.take() {
+            checkpoint::clear_checkpoint(path)?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for CaptureSinkNode {
+    async fn on_configured(&mut self, config: &serde_json::Value) -> Result<()> {
+        if let Some(f) = config.get("format").and_then(|v| v.as_str()) {
+            if !f.eq_ignore_ascii_case("parquet") {
+                anyhow::bail!("Unsupported capture format: {} (only parquet is available)", f);
+            }
+        }
+
+        parse_compression(&self.compression)?;
+        Ok(())
+    }
+
+    async fn process(&mut self, frame: DataFrame) -> Result<DataFrame> {
+        if frame.payload.is_empty() {
+            return Ok(frame);
+        }
+        // A frame arriving after the segment's time starts the next one
+        let segment_due = self.segment_ms > 0
+            && self.writer.is_some()
+            && self.segment_started.is_some_and(|t| t.elapsed().as_millis() as u64 >= self.segment_ms);
+        if segment_due {
+            self.finish_file()?;
+        }
+        if self.writer.is_none() {
+            self.open(&frame)?;
+        }
+
+        let rows = frame.payload.values().map(|v| v.len()).max().unwrap_or(0);
+        let full = {
+            let mut pending = self.pending.lock().map_err(|_| anyhow::anyhow!("Capture buffer lock poisoned"))?;
+            for i in 0..rows {
+                pending.sequence_id.push(frame.sequence_id as i64);
+                pending.timestamp.push(frame.timestamp as i64);
+                pending.sample_index.push((self.samples_written + i as u64) as i64);
+            }
+            for (column, name) in pending.channels.iter_mut().zip(self.channel_names.iter()) {
+                let samples = frame.payload.get(name);
+                column.extend((0..rows).map(|i| {
+                    samples.and_then(|s| s.get(i)).copied().unwrap_or(f64::NAN)
+                }));
+            }
+            pending.len() >= self.chunk_rows
+        };
+        self.samples_written += rows as u64;
+
+        let checkpoint_due = self.checkpoint_interval_ms > 0
+            && self.last_checkpoint.is_some_and(|t| t.elapsed().as_millis() as u64 >= self.checkpoint_interval_ms);
+        if full || checkpoint_due {
+            self.write_row_group()?;
+        }
+        Ok(frame)
+    }
+
+    async fn on_destroy(&mut self) -> Result<()> {
+        self.finish_file()?;
+        self.segment = 0;
+        self.samples_written = 0;
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+use crate::core::{DataFrame, ProcessingNode};
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// One entry of the routing table: copy channel `from` to output `to`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelRoute {
+    pub from: String,
+    pub to: String,
+}
+
+impl ChannelRoute {
+    /// Parse a routing table given as `"ch3 -> reference, ch0, ch0 -> copy"`
+    ///
+    /// An entry without `->` keeps the channel under its own name.
+    pub fn parse_table(table: &str) -> Result<Vec<Self>> {
+        let routes = table
+            .split(',')
+            .filter(|entry| !entry.trim().is_empty())
+            .map(|entry| {
+                let (from, to) = match entry.split_once("->") {
+                    Some((from, to)) => (from.trim(), to.trim()),
+                    None => (entry.trim(), entry.trim()),
+                };
+                if from.is_empty() || to.is_empty() {
+                    anyhow::bail!("Route '{}' must be 'source -> name'", entry.trim());
+                }
+                Ok(Self { from: from.to_string(), to: to.to_string() })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut targets = HashSet::new();
+        if let Some(route) = routes.iter().find(|r| !targets.insert(r.to.as_str())) {
+            anyhow::bail!("Output channel '{}' is routed more than once", route.to);
+        }
+        Ok(routes)
+    }
+}
+
+/// ChannelRouterNode picks, renames and duplicates channels of a frame
+///
+/// `routes` is the routing table, comma separated entries of
+/// `source -> name` (or just `source` to keep its name), e.g.
+/// `"ch3 -> reference, ch0 -> left, ch0 -> right"` takes channel 3 of an
+/// 8-channel interface as `reference` and channel 0 twice. A JSON list of
+/// entries or of `{"from", "to"}` objects is accepted too. Swapping names
+/// (`ch0 -> ch1, ch1 -> ch0`) reorders channels for nodes that take them
+/// sorted by name.
+///
+/// Only routed channels are output unless `keep_unrouted` is set, in which
+/// case channels not used as a source pass through unchanged (a route to
+/// the same name takes precedence). A route whose source is missing from
+/// the frame is an error. Sample buffers are shared, not copied, so
+/// duplicating a channel is free.
+#[derive(StreamNode, Debug, Clone, Default, Serialize, Deserialize)]
+#[node_meta(name = "Channel Router", category = "Processors")]
+#[preset(name = "Swap stereo", params = r#"{"routes": "ch0 -> ch1, ch1 -> ch0", "keep_unrouted": true}"#)]
+pub struct ChannelRouterNode {
+    #[input(name = "Audio In", data_type = "audio_frame")]
+    _input: (),
+
+    #[output(name = "Audio Out", data_type = "audio_frame")]
+    _output: (),
+
+    #[param(default = "\"\"", skip_config)]
+    pub routes: String,
+
+    #[param(default = "false")]
+    pub keep_unrouted: bool,
+
+    #[serde(skip)]
+    table: Vec<ChannelRoute>,
+}
+
+#[async_trait]
+impl ProcessingNode for ChannelRouterNode {
+    async fn on_configured(&mut self, config: &serde_json::Value) -> Result<()> {
+        if let Some(r) = config.get("routes") {
+            // Accept "ch3 -> reference, ch0", ["ch3 -> reference", "ch0"]
+            // or [{"from": "ch3", "to": "reference"}, {"from": "ch0"}]
+            if let Some(s) = r.as_str() {
+                self.routes = s.to_string();
+            } else if let Some(list) = r.as_array() {
+                self.routes = list
+                    .iter()
+                    .filter_map(|v| match v {
+                        serde_json::Value::String(s) => Some(s.clone()),
+                        serde_json::Value::Object(route) => {
+                            let from = route.get("from")?.as_str()?;
+                            let to = route.get("to").and_then(|t| t.as_str()).unwrap_or(from);
+                            Some(format!("{} -> {}", from, to))
+                        }
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",");
+            }
+        }
+
+        self.table = ChannelRoute::parse_table(&self.routes)?;
+        if self.table.is_empty() && !self.keep_unrouted {
+            anyhow::bail!("routes must list at least one channel");
+        }
+        Ok(())
+    }
+
+    async fn process(&mut self, mut frame: DataFrame) -> Result<DataFrame> {
+        let mut input = std::mem::take(&mut frame.payload);
+        let mut output = HashMap::with_capacity(self.table.len());
+        for route in &self.table {
+            let channel = input
+                .get(&route.from)
+                .ok_or_else(|| anyhow::anyhow!("Channel '{}' is not in the frame", route.from))?;
+            output.insert(route.to.clone(), channel.clone());
+        }
+
+        if self.keep_unrouted {
+            for route in &self.table {
+                input.remove(&route.from);
+            }
+            for (name, channel) in input {
+                output.entry(name).or_insert(channel);
+            }
+        }
+        frame.payload = output;
+        Ok(frame)
+    }
+}
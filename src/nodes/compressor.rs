@@ -0,0 +1,95 @@
+use crate::core::{ProcessingNode, DataFrame};
+use crate::dsp::GainSmoother;
+use crate::dsp::dynamics::gain_reduction_db;
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// CompressorNode applies downward compression independently per channel
+///
+/// A peak detector feeds a hard-knee static curve (`threshold_db`,
+/// `ratio`); the resulting gain is smoothed with `attack_ms`/`release_ms`
+/// and `makeup_gain_db` is added. The largest gain reduction seen in the
+/// frame is reported as `gain_reduction_db` metadata.
+#[derive(StreamNode, Debug, Clone, Serialize, Deserialize)]
+#[node_meta(name = "Compressor", category = "Processors")]
+pub struct CompressorNode {
+    #[input(name = "Audio In", data_type = "audio_frame")]
+    _input: (),
+
+    #[output(name = "Audio Out", data_type = "audio_frame")]
+    _output: (),
+
+    #[param(default = "-20.0", min = -120.0, max = 0.0, unit = "dB", step = 0.5)]
+    pub threshold_db: f64,
+
+    #[param(default = "4.0", min = 1.0, max = 100.0, unit = ":1", step = 0.1, log_scale)]
+    pub ratio: f64,
+
+    #[param(default = "10.0", min = 0.0, max = 1000.0, unit = "ms", log_scale)]
+    pub attack_ms: f64,
+
+    #[param(default = "100.0", min = 0.0, max = 10000.0, unit = "ms", log_scale)]
+    pub release_ms: f64,
+
+    #[param(default = "0.0", min = -40.0, max = 40.0, unit = "dB", step = 0.5)]
+    pub makeup_gain_db: f64,
+
+    #[serde(skip)]
+    smoothers: HashMap<String, GainSmoother>,
+
+    #[serde(skip)]
+    sample_rate: f64,
+}
+
+impl Default for CompressorNode {
+    fn default() -> Self {
+        Self {
+            _input: (),
+            _output: (),
+            threshold_db: -20.0,
+            ratio: 4.0,
+            attack_ms: 10.0,
+            release_ms: 100.0,
+            makeup_gain_db: 0.0,
+            smoothers: HashMap::new(),
+            sample_rate: 0.0,
+        }
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for CompressorNode {
+    async fn on_configured(&mut self, _config: &serde_json::Value) -> Result<()> {
+        self.smoothers.clear();
+        Ok(())
+    }
+
+    async fn process(&mut self, mut frame: DataFrame) -> Result<DataFrame> {
+        let sample_rate = frame.sample_rate().unwrap_or(48000.0);
+        if sample_rate != self.sample_rate {
+            self.smoothers.clear();
+            self.sample_rate = sample_rate;
+        }
+
+        let mut max_reduction: f64 = 0.0;
+        for (channel, data) in frame.payload.iter_mut() {
+            let smoother = self
+                .smoothers
+                .entry(channel.clone())
+                .or_insert_with(|| GainSmoother::new(self.attack_ms, self.release_ms, sample_rate));
+
+            for sample in data.samples_mut().iter_mut() {
+                let level_db = 20.0 * sample.abs().max(1e-12).log10();
+                let gain_db = smoother.process(gain_reduction_db(level_db, self.threshold_db, self.ratio));
+                max_reduction = max_reduction.max(-gain_db);
+                *sample *= 10f64.powf((gain_db + self.makeup_gain_db) / 20.0);
+            }
+        }
+
+        frame.metadata.insert("gain_reduction_db", max_reduction);
+        Ok(frame)
+    }
+}
@@ -0,0 +1,278 @@
+use crate::core::{Channel, ChannelRole, DataFrame, PortPairer, ProcessingNode};
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use rustfft::{Fft, FftPlanner, num_complex::Complex};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+const REFERENCE_PORT: &str = "_reference";
+const MEASUREMENT_PORT: &str = "_measurement";
+
+/// CrossCorrelationNode estimates the delay between two channels
+///
+/// Samples of `reference_channel` and `measurement_channel` are buffered
+/// into blocks of `window_size`. Each block is cross-correlated in the
+/// frequency domain, optionally with PHAT weighting (GCC-PHAT), and the peak
+/// within `max_lag_ms` is refined with parabolic interpolation.
+///
+/// Output payload holds single-value channels `lag_samples`, `peak` and
+/// `delay_ms`, plus the `correlation` sequence ordered from `-max_lag` to
+/// `+max_lag`. A positive lag means the measurement arrives after the
+/// reference. Frames without a complete block get an empty payload, and
+/// frames with NaN or infinite samples are rejected.
+///
+/// Both channels may come in one frame, or the two inputs may be wired to
+/// separate sources; frames on the two ports are then paired by sequence id
+/// (see `PortPairer`) before they are buffered.
+#[derive(StreamNode, Clone, Serialize, Deserialize)]
+#[node_meta(name = "Cross Correlation", category = "Processors")]
+pub struct CrossCorrelationNode {
+    #[input(name = "Reference In", data_type = "audio_frame")]
+    _reference: (),
+
+    #[input(name = "Measurement In", data_type = "audio_frame")]
+    _measurement: (),
+
+    #[output(name = "Delay Out", data_type = "correlation")]
+    _output: (),
+
+    #[param(default = "\"ch0\"")]
+    pub reference_channel: String,
+
+    #[param(default = "\"ch1\"")]
+    pub measurement_channel: String,
+
+    #[param(default = "4096", min = 16.0, max = 1048576.0, unit = "samples", log_scale)]
+    pub window_size: usize,
+
+    #[param(default = "\"phat\"", choices = "none,phat")]
+    pub weighting: String,
+
+    #[param(default = "100.0", min = 0.0, max = 10000.0, unit = "ms")]
+    pub max_lag_ms: f64,
+
+    #[serde(skip)]
+    fft: Option<Arc<dyn Fft<f64>>>,
+
+    #[serde(skip)]
+    ifft: Option<Arc<dyn Fft<f64>>>,
+
+    #[serde(skip)]
+    reference_buffer: Vec<f64>,
+
+    #[serde(skip)]
+    measurement_buffer: Vec<f64>,
+
+    #[serde(skip)]
+    pairs: PortPairer,
+}
+
+impl std::fmt::Debug for CrossCorrelationNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CrossCorrelationNode")
+            .field("reference_channel", &self.reference_channel)
+            .field("measurement_channel", &self.measurement_channel)
+            .field("window_size", &self.window_size)
+            .field("weighting", &self.weighting)
+            .field("max_lag_ms", &self.max_lag_ms)
+            .finish()
+    }
+}
+
+impl Default for CrossCorrelationNode {
+    fn default() -> Self {
+        Self {
+            _reference: (),
+            _measurement: (),
+            _output: (),
+            reference_channel: "ch0".to_string(),
+            measurement_channel: "ch1".to_string(),
+            window_size: 4096,
+            weighting: "phat".to_string(),
+            max_lag_ms: 100.0,
+            fft: None,
+            ifft: None,
+            reference_buffer: Vec::new(),
+            measurement_buffer: Vec::new(),
+            pairs: PortPairer::new(),
+        }
+    }
+}
+
+/// Result of correlating one block
+#[derive(Debug, Clone, PartialEq)]
+struct Correlation {
+    lag: f64,
+    peak: f64,
+    sequence: Vec<f64>,
+}
+
+impl CrossCorrelationNode {
+    fn configure(&mut self) -> Result<()> {
+        if self.window_size < 2 {
+            anyhow::bail!("window_size must be at least 2, got {}", self.window_size);
+        }
+        if self.max_lag_ms < 0.0 {
+            anyhow::bail!("max_lag_ms must be non-negative, got {}", self.max_lag_ms);
+        }
+        match self.weighting.to_ascii_lowercase().as_str() {
+            "none" | "phat" => {}
+            other => anyhow::bail!("Unknown correlation weighting: {}", other),
+        }
+
+        // Zero-pad to 2N so the circular correlation equals the linear one
+        let mut planner = FftPlanner::new();
+        self.fft = Some(planner.plan_fft_forward(2 * self.window_size));
+        self.ifft = Some(planner.plan_fft_inverse(2 * self.window_size));
+        self.reference_buffer.clear();
+        self.measurement_buffer.clear();
+        self.pairs.clear();
+        Ok(())
+    }
+
+    fn correlate(&self, reference: &[f64], measurement: &[f64], max_lag: usize) -> Correlation {
+        let n = 2 * self.window_size;
+        let fft = self.fft.as_ref().expect("FFT plan configured");
+        let ifft = self.ifft.as_ref().expect("IFFT plan configured");
+
+        let to_complex = |block: &[f64]| {
+            let mut bins: Vec<Complex<f64>> = block.iter().map(|&s| Complex::new(s, 0.0)).collect();
+            bins.resize(n, Complex::new(0.0, 0.0));
+            bins
+        };
+        let mut x = to_complex(reference);
+        let mut y = to_complex(measurement);
+        fft.process(&mut x);
+        fft.process(&mut y);
+
+        let phat = self.weighting.eq_ignore_ascii_case("phat");
+        let mut spectrum: Vec<Complex<f64>> = x
+            .iter()
+            .zip(y.iter())
+            .map(|(a, b)| {
+                let cross = a.conj() * b;
+                if phat {
+                    let mag = cross.norm();
+                    if mag > 1e-12 { cross / mag } else { Complex::new(0.0, 0.0) }
+                } else {
+                    cross
+                }
+            })
+            .collect();
+        ifft.process(&mut spectrum);
+
+        // PHAT peaks are bounded by 1 after 1/N scaling; plain correlation is
+        // normalised to a correlation coefficient
+        let norm = if phat {
+            n as f64
+        } else {
+            let ex: f64 = reference.iter().map(|s| s * s).sum();
+            let ey: f64 = measurement.iter().map(|s| s * s).sum();
+            n as f64 * (ex * ey).sqrt().max(1e-30)
+        };
+
+        let max_lag = max_lag.min(self.window_size - 1);
+        let at = |lag: i64| spectrum[lag.rem_euclid(n as i64) as usize].re / norm;
+        let sequence: Vec<f64> = (-(max_lag as i64)..=max_lag as i64).map(at).collect();
+
+        let (best_idx, &best) = sequence
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))
+            .expect("non-empty correlation");
+
+        // Parabolic interpolation around the peak for sub-sample resolution
+        let mut offset = 0.0;
+        if best_idx > 0 && best_idx + 1 < sequence.len() {
+            let (l, c, r) = (sequence[best_idx - 1], best, sequence[best_idx + 1]);
+            let denom = l - 2.0 * c + r;
+            if denom.abs() > 1e-12 {
+                offset = (0.5 * (l - r) / denom).clamp(-0.5, 0.5);
+            }
+        }
+
+        Correlation {
+            lag: best_idx as f64 - max_lag as f64 + offset,
+            peak: best,
+            sequence,
+        }
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for CrossCorrelationNode {
+    async fn on_configured(&mut self, _config: &serde_json::Value) -> Result<()> {
+        self.configure()
+    }
+
+    async fn process(&mut self, frame: DataFrame) -> Result<DataFrame> {
+        if self.fft.is_none() {
+            self.configure()?;
+        }
+
+        let waiting = DataFrame::new(frame.timestamp, frame.sequence_id);
+        let ports = [
+            (REFERENCE_PORT, self.reference_channel.as_str()),
+            (MEASUREMENT_PORT, self.measurement_channel.as_str()),
+        ];
+        let Some(mut frame) = self.pairs.push(frame, ports)? else {
+            return Ok(waiting);
+        };
+
+        let sample_rate = frame.sample_rate().unwrap_or(48000.0);
+
+        let input = std::mem::take(&mut frame.payload);
+        let reference = input.get(&self.reference_channel).ok_or_else(|| {
+            anyhow::anyhow!("Missing reference channel '{}'", self.reference_channel)
+        })?;
+        let measurement = input.get(&self.measurement_channel).ok_or_else(|| {
+            anyhow::anyhow!("Missing measurement channel '{}'", self.measurement_channel)
+        })?;
+        // Keep NaN and infinities out of the buffers, where they would spoil a whole window
+        for (name, channel) in [(&self.reference_channel, reference), (&self.measurement_channel, measurement)] {
+            if channel.iter().any(|s| !s.is_finite()) {
+                anyhow::bail!("Channel '{}' contains non-finite samples", name);
+            }
+        }
+        self.reference_buffer.extend_from_slice(reference);
+        self.measurement_buffer.extend_from_slice(measurement);
+
+        let max_lag = (self.max_lag_ms * sample_rate / 1000.0).round() as usize;
+        let mut latest = None;
+        while self.reference_buffer.len() >= self.window_size
+            && self.measurement_buffer.len() >= self.window_size
+        {
+            latest = Some(self.correlate(
+                &self.reference_buffer[..self.window_size],
+                &self.measurement_buffer[..self.window_size],
+                max_lag,
+            ));
+            self.reference_buffer.drain(..self.window_size);
+            self.measurement_buffer.drain(..self.window_size);
+        }
+
+        if let Some(result) = latest {
+            frame.insert_channel("lag_samples", Channel::scalar(result.lag).with_unit("samples"));
+            frame.insert_channel("peak", Channel::scalar(result.peak));
+            frame.insert_channel(
+                "delay_ms",
+                Channel::scalar(result.lag * 1000.0 / sample_rate).with_unit("ms"),
+            );
+            frame.insert_channel(
+                "correlation",
+                Channel::new(result.sequence).with_role(ChannelRole::Measurement),
+            );
+        }
+        frame.metadata.insert("correlation_weighting", self.weighting.to_lowercase());
+
+        Ok(frame)
+    }
+
+    async fn on_destroy(&mut self) -> Result<()> {
+        self.reference_buffer.clear();
+        self.measurement_buffer.clear();
+        self.pairs.clear();
+        Ok(())
+    }
+}
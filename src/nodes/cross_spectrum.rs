@@ -0,0 +1,284 @@
+use crate::core::{ChannelRole, DataFrame, PortPairer, ProcessingNode};
+use crate::dsp::WindowType;
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use rustfft::{Fft, FftPlanner, num_complex::Complex};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+const INPUT_A_PORT: &str = "_input_a";
+const INPUT_B_PORT: &str = "_input_b";
+
+/// One-sided spectral densities of one windowed segment pair
+#[derive(Debug, Clone)]
+struct SegmentSpectra {
+    aa: Vec<f64>,
+    bb: Vec<f64>,
+    ab: Vec<Complex<f64>>,
+}
+
+/// CrossSpectrumNode estimates auto-spectra, cross-spectrum and coherence
+/// between two channels (Welch's method)
+///
+/// Samples of `channel_a` and `channel_b` are cut into windowed segments of
+/// `fft_size`, advancing by `hop_size`. Spectral densities of the last
+/// `num_averages` segments are averaged, and the magnitude-squared
+/// coherence `|Gab|^2 / (Gaa * Gbb)` is derived from the averages, so it
+/// only becomes meaningful once several segments have been averaged.
+///
+/// Output payload holds `fft_size / 2 + 1` bins in the channels `auto_a`
+/// and `auto_b` (power spectral densities, units^2/Hz), `cross_magnitude`,
+/// `cross_phase` (radians, positive when B lags A) and `coherence` (0-1).
+/// Frames without a new segment get an empty payload.
+///
+/// Both channels may come in one frame, or the two inputs may be wired to
+/// separate sources, whose frames are paired by sequence id.
+#[derive(StreamNode, Clone, Serialize, Deserialize)]
+#[node_meta(name = "Cross Spectrum", category = "Processors")]
+#[preset(name = "Transfer function", params = r#"{"fft_size": 4096, "hop_size": 2048, "num_averages": 32}"#)]
+pub struct CrossSpectrumNode {
+    #[input(name = "Input A", data_type = "audio_frame")]
+    _input_a: (),
+
+    #[input(name = "Input B", data_type = "audio_frame")]
+    _input_b: (),
+
+    #[output(name = "Spectra Out", data_type = "fft_result")]
+    _output: (),
+
+    #[param(default = "\"ch0\"")]
+    pub channel_a: String,
+
+    #[param(default = "\"ch1\"")]
+    pub channel_b: String,
+
+    #[param(default = "1024", min = 16.0, max = 65536.0, unit = "samples", log_scale)]
+    pub fft_size: usize,
+
+    #[param(default = "512", min = 1.0, max = 65536.0, unit = "samples", log_scale)]
+    pub hop_size: usize,
+
+    #[param(default = "\"hann\"", choices = "rectangular,hann,hamming,blackman,flattop")]
+    pub window_type: String,
+
+    #[param(default = "16", min = 1.0, max = 1000.0, step = 1.0)]
+    pub num_averages: usize,
+
+    #[serde(skip)]
+    window: Vec<f64>,
+
+    #[serde(skip)]
+    fft: Option<Arc<dyn Fft<f64>>>,
+
+    #[serde(skip)]
+    buffer_a: Vec<f64>,
+
+    #[serde(skip)]
+    buffer_b: Vec<f64>,
+
+    #[serde(skip)]
+    segments: VecDeque<SegmentSpectra>,
+
+    #[serde(skip)]
+    pairs: PortPairer,
+}
+
+impl std::fmt::Debug for CrossSpectrumNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CrossSpectrumNode")
+            .field("channel_a", &self.channel_a)
+            .field("channel_b", &self.channel_b)
+            .field("fft_size", &self.fft_size)
+            .field("hop_size", &self.hop_size)
+            .field("window_type", &self.window_type)
+            .field("num_averages", &self.num_averages)
+            .finish()
+    }
+}
+
+impl Default for CrossSpectrumNode {
+    fn default() -> Self {
+        Self {
+            _input_a: (),
+            _input_b: (),
+            _output: (),
+            channel_a: "ch0".to_string(),
+            channel_b: "ch1".to_string(),
+            fft_size: 1024,
+            hop_size: 512,
+            window_type: "hann".to_string(),
+            num_averages: 16,
+            window: Vec::new(),
+            fft: None,
+            buffer_a: Vec::new(),
+            buffer_b: Vec::new(),
+            segments: VecDeque::new(),
+            pairs: PortPairer::new(),
+        }
+    }
+}
+
+impl CrossSpectrumNode {
+    /// Validate parameters and (re)build the window and FFT plan
+    fn configure(&mut self) -> Result<()> {
+        if self.fft_size < 2 {
+            anyhow::bail!("fft_size must be at least 2, got {}", self.fft_size);
+        }
+        if self.hop_size == 0 || self.hop_size > self.fft_size {
+            anyhow::bail!(
+                "hop_size must be between 1 and fft_size ({}), got {}",
+                self.fft_size, self.hop_size
+            );
+        }
+        if self.num_averages == 0 {
+            anyhow::bail!("num_averages must be at least 1");
+        }
+
+        self.window = WindowType::parse(&self.window_type)?.coefficients(self.fft_size);
+        self.fft = Some(FftPlanner::new().plan_fft_forward(self.fft_size));
+        self.reset_state();
+        Ok(())
+    }
+
+    fn reset_state(&mut self) {
+        self.buffer_a.clear();
+        self.buffer_b.clear();
+        self.segments.clear();
+        self.pairs.clear();
+    }
+
+    /// Spectral densities of one segment pair
+    fn segment_spectra(&self, fft: &Arc<dyn Fft<f64>>, a: &[f64], b: &[f64], sample_rate: f64) -> SegmentSpectra {
+        let transform = |block: &[f64]| {
+            let mut bins: Vec<Complex<f64>> = block
+                .iter()
+                .zip(self.window.iter())
+                .map(|(&s, &w)| Complex::new(s * w, 0.0))
+                .collect();
+            fft.process(&mut bins);
+            bins.truncate(self.fft_size / 2 + 1);
+            bins
+        };
+        let x = transform(a);
+        let y = transform(b);
+
+        // Density scaling by the window's power, doubled for the folded
+        // negative frequencies except at DC and Nyquist
+        let power: f64 = self.window.iter().map(|w| w * w).sum();
+        let scale = |k: usize| {
+            let fold = if k == 0 || 2 * k == self.fft_size { 1.0 } else { 2.0 };
+            fold / (sample_rate * power)
+        };
+
+        SegmentSpectra {
+            aa: x.iter().enumerate().map(|(k, c)| scale(k) * c.norm_sqr()).collect(),
+            bb: y.iter().enumerate().map(|(k, c)| scale(k) * c.norm_sqr()).collect(),
+            ab: x.iter().zip(y.iter()).enumerate().map(|(k, (p, q))| p.conj() * q * scale(k)).collect(),
+        }
+    }
+
+    /// Mean of the stored segments
+    fn average(&self) -> SegmentSpectra {
+        let bins = self.fft_size / 2 + 1;
+        let mut sum = SegmentSpectra {
+            aa: vec![0.0; bins],
+            bb: vec![0.0; bins],
+            ab: vec![Complex::new(0.0, 0.0); bins],
+        };
+        for segment in &self.segments {
+            for k in 0..bins {
+                sum.aa[k] += segment.aa[k];
+                sum.bb[k] += segment.bb[k];
+                sum.ab[k] += segment.ab[k];
+            }
+        }
+
+        let n = self.segments.len().max(1) as f64;
+        sum.aa.iter_mut().for_each(|v| *v /= n);
+        sum.bb.iter_mut().for_each(|v| *v /= n);
+        sum.ab.iter_mut().for_each(|v| *v /= n);
+        sum
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for CrossSpectrumNode {
+    async fn on_configured(&mut self, _config: &serde_json::Value) -> Result<()> {
+        self.configure()
+    }
+
+    async fn process(&mut self, frame: DataFrame) -> Result<DataFrame> {
+        if self.fft.is_none() {
+            self.configure()?;
+        }
+        let waiting = DataFrame::new(frame.timestamp, frame.sequence_id);
+        let ports = [(INPUT_A_PORT, self.channel_a.as_str()), (INPUT_B_PORT, self.channel_b.as_str())];
+        let Some(mut frame) = self.pairs.push(frame, ports)? else {
+            return Ok(waiting);
+        };
+        let fft = self.fft.clone().expect("FFT plan configured");
+        let sample_rate = frame.sample_rate().unwrap_or(48000.0);
+
+        let input = std::mem::take(&mut frame.payload);
+        let a = input.get(&self.channel_a).ok_or_else(|| {
+            anyhow::anyhow!("Missing channel '{}'", self.channel_a)
+        })?;
+        let b = input.get(&self.channel_b).ok_or_else(|| {
+            anyhow::anyhow!("Missing channel '{}'", self.channel_b)
+        })?;
+        self.buffer_a.extend_from_slice(a);
+        self.buffer_b.extend_from_slice(b);
+
+        let mut updated = false;
+        while self.buffer_a.len() >= self.fft_size && self.buffer_b.len() >= self.fft_size {
+            let segment = self.segment_spectra(
+                &fft,
+                &self.buffer_a[..self.fft_size],
+                &self.buffer_b[..self.fft_size],
+                sample_rate,
+            );
+            self.segments.push_back(segment);
+            while self.segments.len() > self.num_averages {
+                self.segments.pop_front();
+            }
+            self.buffer_a.drain(..self.hop_size);
+            self.buffer_b.drain(..self.hop_size);
+            updated = true;
+        }
+
+        if updated {
+            let average = self.average();
+            let coherence: Vec<f64> = average
+                .ab
+                .iter()
+                .zip(average.aa.iter().zip(average.bb.iter()))
+                .map(|(ab, (aa, bb))| {
+                    let denom = aa * bb;
+                    if denom > 1e-300 { (ab.norm_sqr() / denom).min(1.0) } else { 0.0 }
+                })
+                .collect();
+
+            let spectrum = |samples: Vec<f64>| a.with_samples(samples).with_role(ChannelRole::Spectrum);
+            frame.insert_channel("cross_magnitude", spectrum(average.ab.iter().map(|c| c.norm()).collect()));
+            // conj(A) * B has the phase of B relative to A; report lag as positive
+            frame.insert_channel("cross_phase", spectrum(average.ab.iter().map(|c| -c.arg()).collect()));
+            frame.insert_channel("coherence", spectrum(coherence));
+            frame.insert_channel("auto_a", spectrum(average.aa));
+            frame.insert_channel("auto_b", spectrum(average.bb));
+            frame.metadata.insert("averages", self.segments.len());
+        }
+
+        frame.metadata.insert("fft_size", self.fft_size);
+        frame.metadata.insert("hop_size", self.hop_size);
+        frame.metadata.insert("window", &self.window_type);
+        frame.metadata.insert("bin_resolution_hz", sample_rate / self.fft_size as f64);
+        Ok(frame)
+    }
+
+    async fn on_destroy(&mut self) -> Result<()> {
+        self.reset_state();
+        Ok(())
+    }
+}
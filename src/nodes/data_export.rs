@@ -0,0 +1,267 @@
+use crate::core::{ProcessingNode, DataFrame};
+use crate::resilience::checkpoint;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Quote a CSV field per RFC 4180 when it holds a comma, quote or line break
+fn csv_field(field: &str) -> std::borrow::Cow<'_, str> {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\"")).into()
+    } else {
+        field.into()
+    }
+}
+
+fn csv_row(fields: &[String]) -> String {
+    fields.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(",")
+}
+
+/// Output format of DataExportNode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    JsonLines,
+}
+
+impl ExportFormat {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "jsonl" | "json_lines" | "ndjson" => Ok(ExportFormat::JsonLines),
+            _ => anyhow::bail!("Unknown export format: {}", name),
+        }
+    }
+
+    /// Guess the format from a file extension
+    pub fn from_path(path: &str) -> Option<Self> {
+        let ext = Path::new(path).extension()?.to_str()?;
+        Self::parse(ext).ok()
+    }
+}
+
+/// DataExportNode writes analysis results to CSV or JSON Lines
+///
+/// Each frame becomes one record with `timestamp` and `sequence_id`. In CSV,
+/// single-value channels map to one column and multi-value channels (e.g.
+/// spectra) expand to `<name>_<index>` columns; the columns are fixed by the
+/// first non-empty frame, and the header is only written to an empty file so
+/// appended runs share one. Fields are quoted per RFC 4180 where needed.
+/// JSON Lines records carry the full channel vectors and frame metadata.
+/// Writes are buffered and flushed every `flush_interval_ms` and on destroy;
+/// each flush checkpoints the file, so after a crash
+/// `resilience::recover_file` cuts it back to the last complete record.
+/// Appending to a file recovers it first. Frames pass through unchanged.
+#[derive(StreamNode, Debug, Clone, Serialize, Deserialize)]
+#[node_meta(name = "Data Export", category = "Sinks")]
+pub struct DataExportNode {
+    #[input(name = "Data In", data_type = "any")]
+    _input: (),
+
+    #[param(default = "\"export.csv\"")]
+    pub path: String,
+
+    /// "csv", "jsonl", or empty to infer from the file extension
+    #[param(default = "\"\"")]
+    pub format: String,
+
+    #[param(default = "1000", min = 0.0, max = 3600000.0, unit = "ms")]
+    pub flush_interval_ms: u64,
+
+    #[param(default = "false")]
+    pub append: bool,
+
+    #[serde(skip)]
+    writer: Option<Arc<Mutex<BufWriter<File>>>>,
+
+    #[serde(skip)]
+    columns: Option<Vec<(String, usize)>>,
+
+    #[serde(skip)]
+    last_flush: Option<Instant>,
+
+    /// Whether the file was empty when opened and still needs a CSV header
+    #[serde(skip)]
+    needs_header: bool,
+}
+
+impl Default for DataExportNode {
+    fn default() -> Self {
+        Self {
+            _input: (),
+            path: "export.csv".to_string(),
+            format: String::new(),
+            flush_interval_ms: 1000,
+            append: false,
+            writer: None,
+            columns: None,
+            last_flush: None,
+            needs_header: true,
+        }
+    }
+}
+
+impl DataExportNode {
+    fn resolve_format(&self) -> Result<ExportFormat> {
+        if self.format.is_empty() {
+            Ok(ExportFormat::from_path(&self.path).unwrap_or(ExportFormat::Csv))
+        } else {
+            ExportFormat::parse(&self.format)
+        }
+    }
+
+    fn open(&mut self) -> Result<()> {
+        if let Some(parent) = Path::new(&self.path).parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        // Records are appended after the last complete one of an interrupted run
+        if self.append {
+            checkpoint::recover_file(&self.path)?;
+        } else {
+            checkpoint::clear_checkpoint(&self.path)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(self.append)
+            .truncate(!self.append)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open export file {}", self.path))?;
+        self.needs_header = file.metadata()?.len() == 0;
+        self.writer = Some(Arc::new(Mutex::new(BufWriter::new(file))));
+        self.columns = None;
+        self.last_flush = Some(Instant::now());
+        Ok(())
+    }
+
+    fn csv_record(&mut self, frame: &DataFrame) -> Option<String> {
+        let mut lines = String::new();
+        if self.columns.is_none() {
+            let mut keys: Vec<&String> = frame.payload.keys().collect();
+            keys.sort();
+            let columns: Vec<(String, usize)> = keys
+                .into_iter()
+                .map(|k| (k.clone(), frame.payload[k].len()))
+                .collect();
+
+            if self.needs_header {
+                let mut header = vec!["timestamp".to_string(), "sequence_id".to_string()];
+                for (name, len) in &columns {
+                    if *len == 1 {
+                        header.push(name.clone());
+                    } else {
+                        header.extend((0..*len).map(|i| format!("{}_{}", name, i)));
+                    }
+                }
+                lines.push_str(&csv_row(&header));
+                lines.push('\n');
+                self.needs_header = false;
+            }
+            self.columns = Some(columns);
+        }
+
+        let mut row = vec![frame.timestamp.to_string(), frame.sequence_id.to_string()];
+        for (name, len) in self.columns.as_ref()? {
+            let values = frame.payload.get(name);
+            for i in 0..*len {
+                // Missing channels or short vectors leave the cell empty
+                row.push(
+                    values
+                        .and_then(|v| v.get(i))
+                        .map(|v| v.to_string())
+                        .unwrap_or_default(),
+                );
+            }
+        }
+        lines.push_str(&csv_row(&row));
+        lines.push('\n');
+        Some(lines)
+    }
+
+    fn jsonl_record(frame: &DataFrame) -> Result<String> {
+        let values: serde_json::Map<String, serde_json::Value> = frame
+            .payload
+            .iter()
+            .map(|(k, v)| {
+                let value = if v.len() == 1 {
+                    serde_json::json!(v[0])
+                } else {
+                    serde_json::json!(v.as_ref())
+                };
+                (k.clone(), value)
+            })
+            .collect();
+        let record = serde_json::json!({
+            "timestamp": frame.timestamp,
+            "sequence_id": frame.sequence_id,
+            "values": values,
+            "metadata": frame.metadata,
+        });
+        Ok(format!("{}\n", serde_json::to_string(&record)?))
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if let Some(writer) = &self.writer {
+            let mut writer = writer.lock().map_err(|_| anyhow::anyhow!("Export writer lock poisoned"))?;
+            writer.flush()?;
+            let file = writer.get_ref();
+            checkpoint::write_checkpoint(&self.path, file, file.metadata()?.len(), &[])?;
+        }
+        self.last_flush = Some(Instant::now());
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for DataExportNode {
+    async fn on_configured(&mut self, _config: &serde_json::Value) -> Result<()> {
+        self.resolve_format()?;
+        self.open()
+    }
+
+    async fn process(&mut self, frame: DataFrame) -> Result<DataFrame> {
+        if frame.payload.is_empty() {
+            return Ok(frame);
+        }
+        if self.writer.is_none() {
+            self.open()?;
+        }
+
+        let record = match self.resolve_format()? {
+            ExportFormat::Csv => self.csv_record(&frame).unwrap_or_default(),
+            ExportFormat::JsonLines => Self::jsonl_record(&frame)?,
+        };
+        if let Some(writer) = &self.writer {
+            writer
+                .lock()
+                .map_err(|_| anyhow::anyhow!("Export writer lock poisoned"))?
+                .write_all(record.as_bytes())?;
+        }
+
+        let due = self
+            .last_flush
+            .map(|t| t.elapsed().as_millis() as u64 >= self.flush_interval_ms)
+            .unwrap_or(true);
+        if due {
+            self.flush()?;
+        }
+
+        Ok(frame)
+    }
+
+    async fn on_destroy(&mut self) -> Result<()> {
+        self.flush()?;
+        if self.writer.take().is_some() {
+            checkpoint::clear_checkpoint(&self.path)?;
+        }
+        Ok(())
+    }
+}
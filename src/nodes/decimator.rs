@@ -0,0 +1,70 @@
+use crate::core::{DataFrame, FrameDecimator, ProcessingNode};
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use serde::{Deserialize, Serialize};
+
+/// DecimatorNode reduces the sample rate by an integer factor
+///
+/// Every signal channel is low-pass filtered below the new Nyquist frequency
+/// (at 0.4 of the output rate) and then only every `factor`-th sample is
+/// kept, e.g. 48 kHz to 1 kHz with a factor of 48. Decimation continues
+/// across frames, so frames of any length can be fed. With `anti_alias`
+/// off, samples are dropped unfiltered. Spectra, measurements and control
+/// channels pass through unchanged.
+///
+/// The `sample_rate` metadata, and the rate of channels that carry their
+/// own, are divided by `factor`.
+#[derive(StreamNode, Debug, Clone, Serialize, Deserialize)]
+#[node_meta(name = "Decimator", category = "Processors")]
+#[preset(name = "48 kHz to 1 kHz", params = r#"{"factor": 48}"#)]
+pub struct DecimatorNode {
+    #[input(name = "Audio In", data_type = "audio_frame")]
+    _input: (),
+
+    #[output(name = "Audio Out", data_type = "audio_frame")]
+    _output: (),
+
+    #[param(default = "48", min = 1.0, max = 1000.0, step = 1.0)]
+    pub factor: usize,
+
+    #[param(default = "true")]
+    pub anti_alias: bool,
+
+    #[serde(skip)]
+    decimator: Option<FrameDecimator>,
+}
+
+impl Default for DecimatorNode {
+    fn default() -> Self {
+        Self {
+            _input: (),
+            _output: (),
+            factor: 48,
+            anti_alias: true,
+            decimator: None,
+        }
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for DecimatorNode {
+    async fn on_configured(&mut self, _config: &serde_json::Value) -> Result<()> {
+        if self.factor == 0 {
+            anyhow::bail!("Decimation factor must be at least 1");
+        }
+        self.decimator = None;
+        Ok(())
+    }
+
+    async fn process(&mut self, frame: DataFrame) -> Result<DataFrame> {
+        let (factor, anti_alias) = (self.factor, self.anti_alias);
+        let decimator = self.decimator.get_or_insert_with(|| FrameDecimator::new(factor, anti_alias));
+        Ok(decimator.push(frame))
+    }
+
+    async fn on_destroy(&mut self) -> Result<()> {
+        self.decimator = None;
+        Ok(())
+    }
+}
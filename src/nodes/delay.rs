@@ -0,0 +1,156 @@
+use crate::core::{DataFrame, ProcessingNode};
+use crate::dsp::{DelayLine, Interpolation};
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// DelayNode delays channels by a fixed, possibly fractional, amount
+///
+/// The delay is given either in samples (`delay_samples`) or in
+/// milliseconds (`delay_ms`, takes precedence when non-zero and is converted
+/// using the frame's `sample_rate` metadata). Fractional delays are
+/// interpolated. `channels` is a comma-separated list of channel names to
+/// delay; when empty every channel is delayed.
+#[derive(StreamNode, Debug, Clone, Serialize, Deserialize)]
+#[node_meta(name = "Delay", category = "Processors")]
+pub struct DelayNode {
+    #[input(name = "Audio In", data_type = "audio_frame")]
+    _input: (),
+
+    #[output(name = "Audio Out", data_type = "audio_frame")]
+    _output: (),
+
+    #[param(default = "0.0", min = 0.0, max = 1000000.0, unit = "samples")]
+    pub delay_samples: f64,
+
+    #[param(default = "0.0", min = 0.0, max = 10000.0, unit = "ms", step = 0.1)]
+    pub delay_ms: f64,
+
+    #[param(default = "\"linear\"", choices = "none,linear,cubic")]
+    pub interpolation: String,
+
+    #[param(default = "\"\"", skip_config)]
+    pub channels: String,
+
+    #[serde(skip)]
+    lines: HashMap<String, DelayLine>,
+
+    /// Sequence id and metadata for the tail emitted at end of stream
+    #[serde(skip)]
+    next_frame: Option<DataFrame>,
+}
+
+impl Default for DelayNode {
+    fn default() -> Self {
+        Self {
+            _input: (),
+            _output: (),
+            delay_samples: 0.0,
+            delay_ms: 0.0,
+            interpolation: "linear".to_string(),
+            channels: String::new(),
+            lines: HashMap::new(),
+            next_frame: None,
+        }
+    }
+}
+
+impl DelayNode {
+    /// Change the delay in samples at runtime; delay lines are rebuilt on the next frame
+    pub fn set_delay_samples(&mut self, delay_samples: f64) {
+        self.delay_samples = delay_samples.max(0.0);
+        self.delay_ms = 0.0;
+    }
+
+    /// Change the delay in milliseconds at runtime
+    pub fn set_delay_ms(&mut self, delay_ms: f64) {
+        self.delay_ms = delay_ms.max(0.0);
+    }
+
+    fn effective_delay(&self, sample_rate: f64) -> f64 {
+        if self.delay_ms > 0.0 {
+            self.delay_ms * sample_rate / 1000.0
+        } else {
+            self.delay_samples
+        }
+    }
+
+    fn applies_to(&self, channel: &str) -> bool {
+        self.channels.trim().is_empty()
+            || self.channels.split(',').any(|c| c.trim() == channel)
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for DelayNode {
+    async fn on_configured(&mut self, config: &serde_json::Value) -> Result<()> {
+        if let Some(c) = config.get("channels") {
+            // Accept either "ch0,ch1" or ["ch0", "ch1"]
+            if let Some(s) = c.as_str() {
+                self.channels = s.to_string();
+            } else if let Some(list) = c.as_array() {
+                self.channels = list
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .collect::<Vec<_>>()
+                    .join(",");
+            }
+        }
+
+        Interpolation::parse(&self.interpolation)?;
+        self.lines.clear();
+        self.next_frame = None;
+        Ok(())
+    }
+
+    async fn process(&mut self, mut frame: DataFrame) -> Result<DataFrame> {
+        let sample_rate = frame.sample_rate().unwrap_or(48000.0);
+        let delay = self.effective_delay(sample_rate);
+        let interpolation = Interpolation::parse(&self.interpolation)?;
+
+        let channels: Vec<String> = frame
+            .payload
+            .keys()
+            .filter(|c| self.applies_to(c))
+            .cloned()
+            .collect();
+
+        for channel in channels {
+            let line = self
+                .lines
+                .entry(channel.clone())
+                .or_insert_with(|| DelayLine::new(delay, interpolation));
+            if line.delay() != delay {
+                *line = DelayLine::new(delay, interpolation);
+            }
+
+            if let Some(data) = frame.payload.get_mut(&channel) {
+                for sample in data.samples_mut().iter_mut() {
+                    *sample = line.process(*sample);
+                }
+            }
+        }
+
+        frame.metadata.insert("delay_samples", delay);
+
+        let mut next = DataFrame::new(frame.timestamp, frame.sequence_id + 1);
+        next.metadata = frame.metadata.clone();
+        self.next_frame = Some(next);
+        Ok(frame)
+    }
+
+    /// Emit the samples still inside the delay lines
+    async fn on_eos(&mut self) -> Result<Option<DataFrame>> {
+        let Some(mut tail) = self.next_frame.take() else {
+            return Ok(None);
+        };
+        for (channel, line) in self.lines.iter_mut() {
+            let samples: Vec<f64> = (0..line.delay().ceil() as usize).map(|_| line.process(0.0)).collect();
+            tail.insert_channel(channel.clone(), samples);
+        }
+        tail.metadata.insert("tail", true);
+        Ok(Some(tail))
+    }
+}
@@ -0,0 +1,127 @@
+use crate::core::{ProcessingNode, DataFrame};
+use crate::dsp::dynamics::time_coefficient;
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Level detector used by the envelope follower
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvelopeMode {
+    /// Smoothed absolute value
+    Peak,
+    /// Square root of the smoothed mean square
+    Rms,
+}
+
+impl EnvelopeMode {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "peak" => Ok(EnvelopeMode::Peak),
+            "rms" => Ok(EnvelopeMode::Rms),
+            _ => anyhow::bail!("Unknown envelope mode: {}", name),
+        }
+    }
+}
+
+/// Per-channel follower state
+#[derive(Debug, Clone, Default)]
+struct EnvelopeState {
+    level: f64,
+    counter: usize,
+}
+
+/// EnvelopeFollowerNode tracks the signal envelope with attack/release smoothing
+///
+/// Each channel is replaced by its envelope, decimated to one value every
+/// `output_interval_ms`. The frame's `sample_rate` metadata is rewritten to
+/// the envelope rate so downstream nodes see a regular low-rate signal.
+#[derive(StreamNode, Debug, Clone, Serialize, Deserialize)]
+#[node_meta(name = "Envelope Follower", category = "Processors")]
+pub struct EnvelopeFollowerNode {
+    #[input(name = "Audio In", data_type = "audio_frame")]
+    _input: (),
+
+    #[output(name = "Envelope Out", data_type = "audio_frame")]
+    _output: (),
+
+    #[param(default = "10.0", min = 0.0, max = 10000.0, unit = "ms", log_scale)]
+    pub attack_ms: f64,
+
+    #[param(default = "100.0", min = 0.0, max = 60000.0, unit = "ms", log_scale)]
+    pub release_ms: f64,
+
+    #[param(default = "\"peak\"", choices = "peak,rms")]
+    pub mode: String,
+
+    #[param(default = "10.0", min = 0.0, max = 60000.0, unit = "ms")]
+    pub output_interval_ms: f64,
+
+    #[serde(skip)]
+    states: HashMap<String, EnvelopeState>,
+}
+
+impl Default for EnvelopeFollowerNode {
+    fn default() -> Self {
+        Self {
+            _input: (),
+            _output: (),
+            attack_ms: 10.0,
+            release_ms: 100.0,
+            mode: "peak".to_string(),
+            output_interval_ms: 10.0,
+            states: HashMap::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for EnvelopeFollowerNode {
+    async fn on_configured(&mut self, _config: &serde_json::Value) -> Result<()> {
+        EnvelopeMode::parse(&self.mode)?;
+        self.states.clear();
+        Ok(())
+    }
+
+    async fn process(&mut self, mut frame: DataFrame) -> Result<DataFrame> {
+        let mode = EnvelopeMode::parse(&self.mode)?;
+        let sample_rate = frame.sample_rate().unwrap_or(48000.0);
+
+        let attack = time_coefficient(self.attack_ms, sample_rate);
+        let release = time_coefficient(self.release_ms, sample_rate);
+        let decimation = ((self.output_interval_ms * sample_rate / 1000.0).round() as usize).max(1);
+
+        for (channel, data) in frame.payload.iter_mut() {
+            let state = self.states.entry(channel.clone()).or_default();
+            let mut envelope = Vec::with_capacity(data.len() / decimation + 1);
+
+            for &sample in data.iter() {
+                let detected = match mode {
+                    EnvelopeMode::Peak => sample.abs(),
+                    EnvelopeMode::Rms => sample * sample,
+                };
+                let coeff = if detected > state.level { attack } else { release };
+                state.level += coeff * (detected - state.level);
+
+                state.counter += 1;
+                if state.counter >= decimation {
+                    state.counter = 0;
+                    envelope.push(match mode {
+                        EnvelopeMode::Peak => state.level,
+                        EnvelopeMode::Rms => state.level.sqrt(),
+                    });
+                }
+            }
+
+            *data = data
+                .with_samples(envelope)
+                .with_sample_rate(sample_rate / decimation as f64);
+        }
+
+        frame.metadata.insert("sample_rate", sample_rate / decimation as f64);
+        frame.metadata.insert("envelope_mode", self.mode.to_lowercase());
+
+        Ok(frame)
+    }
+}
@@ -0,0 +1,248 @@
+use crate::core::{Channel, ChannelRole, DataFrame, ProcessingNode};
+use crate::dsp::{FeatureExtractor, FeatureSettings, Features, WindowType};
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Features a FeatureExtractorNode can emit, in output order
+const FEATURES: [&str; 5] = ["mfcc", "centroid", "rolloff", "flux", "zcr"];
+
+/// Buffered samples and extractor of one channel
+#[derive(Debug, Clone)]
+struct ChannelFeatures {
+    extractor: FeatureExtractor,
+    buffer: Vec<f64>,
+}
+
+/// FeatureExtractorNode computes per-block audio features for classifiers
+///
+/// Samples are buffered into blocks of `window_size`, advancing by
+/// `hop_size`. Each block yields `mfcc_count` MFCCs from a mel filterbank
+/// of `mel_bands` bands between `min_frequency` and `max_frequency` (0 for
+/// Nyquist), the spectral centroid, the rolloff frequency below which
+/// `rolloff` of the energy lies, the spectral flux against the previous
+/// block and the zero-crossing rate (see `dsp::FeatureExtractor`).
+/// `features` limits the output to a comma separated subset of
+/// `mfcc,centroid,rolloff,flux,zcr`.
+///
+/// For every analysed channel `chN` (the comma separated `channels`, or
+/// every signal channel when empty) the output payload holds `chN_mfcc_0`
+/// to `chN_mfcc_<n-1>`, `chN_centroid` and `chN_rolloff` in Hz, `chN_flux`
+/// and `chN_zcr`, one value per block completed by the frame at a sample
+/// rate of `sample_rate / hop_size`, so a Data Export downstream writes one
+/// feature vector per row. Frames that complete no block get an empty
+/// payload.
+#[derive(StreamNode, Debug, Clone, Serialize, Deserialize)]
+#[node_meta(name = "Feature Extractor", category = "Processors")]
+#[preset(name = "Speech MFCC", params = r#"{"window_size": 512, "hop_size": 160, "mel_bands": 40, "mfcc_count": 13, "min_frequency": 20.0, "max_frequency": 8000.0, "features": "mfcc"}"#)]
+#[preset(name = "Spectral shape", params = r#"{"features": "centroid,rolloff,flux,zcr"}"#)]
+pub struct FeatureExtractorNode {
+    #[input(name = "Audio In", data_type = "audio_frame")]
+    _input: (),
+
+    #[output(name = "Features Out", data_type = "features")]
+    _output: (),
+
+    #[param(default = "\"\"", skip_config)]
+    pub channels: String,
+
+    #[param(default = "1024", min = 16.0, max = 65536.0, unit = "samples", log_scale)]
+    pub window_size: usize,
+
+    #[param(default = "512", min = 1.0, max = 65536.0, unit = "samples", log_scale)]
+    pub hop_size: usize,
+
+    #[param(default = "\"hann\"", choices = "rectangular,hann,hamming,blackman,flattop")]
+    pub window_type: String,
+
+    #[param(default = "26", min = 1.0, max = 256.0, step = 1.0)]
+    pub mel_bands: usize,
+
+    #[param(default = "13", min = 1.0, max = 256.0, step = 1.0)]
+    pub mfcc_count: usize,
+
+    #[param(default = "0.0", min = 0.0, max = 96000.0, unit = "Hz")]
+    pub min_frequency: f64,
+
+    #[param(default = "0.0", min = 0.0, max = 96000.0, unit = "Hz")]
+    pub max_frequency: f64,
+
+    #[param(default = "0.85", min = 0.01, max = 1.0, step = 0.01)]
+    pub rolloff: f64,
+
+    #[param(default = "\"\"", skip_config)]
+    pub features: String,
+
+    #[serde(skip)]
+    extractors: HashMap<String, ChannelFeatures>,
+}
+
+impl Default for FeatureExtractorNode {
+    fn default() -> Self {
+        Self {
+            _input: (),
+            _output: (),
+            channels: String::new(),
+            window_size: 1024,
+            hop_size: 512,
+            window_type: "hann".to_string(),
+            mel_bands: 26,
+            mfcc_count: 13,
+            min_frequency: 0.0,
+            max_frequency: 0.0,
+            rolloff: 0.85,
+            features: String::new(),
+            extractors: HashMap::new(),
+        }
+    }
+}
+
+/// Read a skip_config list given as "a,b" or ["a", "b"]
+fn list_param(config: &serde_json::Value, key: &str) -> Option<String> {
+    match config.get(key)? {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Array(list) => {
+            Some(list.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>().join(","))
+        }
+        _ => None,
+    }
+}
+
+impl FeatureExtractorNode {
+    /// Enabled features, in output order
+    fn enabled(&self) -> Result<Vec<&'static str>> {
+        let requested: Vec<String> = self
+            .features
+            .split(',')
+            .map(|f| f.trim().to_ascii_lowercase())
+            .filter(|f| !f.is_empty())
+            .collect();
+        if let Some(unknown) = requested.iter().find(|f| !FEATURES.contains(&f.as_str())) {
+            anyhow::bail!("Unknown feature '{}', expected one of {}", unknown, FEATURES.join(", "));
+        }
+        Ok(FEATURES
+            .into_iter()
+            .filter(|f| requested.is_empty() || requested.iter().any(|r| r == f))
+            .collect())
+    }
+
+    fn settings(&self) -> Result<FeatureSettings> {
+        Ok(FeatureSettings {
+            window: WindowType::parse(&self.window_type)?,
+            mel_bands: self.mel_bands,
+            mfcc_count: self.mfcc_count,
+            min_hz: self.min_frequency,
+            max_hz: self.max_frequency,
+            rolloff: self.rolloff,
+        })
+    }
+
+    fn channel_names(&self, frame: &DataFrame) -> Result<Vec<String>> {
+        if self.channels.trim().is_empty() {
+            let mut names: Vec<String> = frame
+                .payload
+                .iter()
+                .filter(|(_, c)| c.role == ChannelRole::Signal)
+                .map(|(name, _)| name.clone())
+                .collect();
+            names.sort();
+            return Ok(names);
+        }
+        let names: Vec<String> =
+            self.channels.split(',').map(|c| c.trim().to_string()).filter(|c| !c.is_empty()).collect();
+        if let Some(missing) = names.iter().find(|name| !frame.payload.contains_key(*name)) {
+            anyhow::bail!("Channel '{}' is not in the frame", missing);
+        }
+        Ok(names)
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for FeatureExtractorNode {
+    async fn on_configured(&mut self, config: &serde_json::Value) -> Result<()> {
+        if let Some(channels) = list_param(config, "channels") {
+            self.channels = channels;
+        }
+        if let Some(features) = list_param(config, "features") {
+            self.features = features;
+        }
+        if self.window_size < 16 {
+            anyhow::bail!("window_size must be at least 16, got {}", self.window_size);
+        }
+        if self.hop_size == 0 || self.hop_size > self.window_size {
+            anyhow::bail!("hop_size must be between 1 and window_size, got {}", self.hop_size);
+        }
+        if self.mel_bands == 0 || self.mfcc_count == 0 || self.mfcc_count > self.mel_bands {
+            anyhow::bail!("mfcc_count must be between 1 and mel_bands ({}), got {}", self.mel_bands, self.mfcc_count);
+        }
+        if self.max_frequency > 0.0 && self.max_frequency <= self.min_frequency {
+            anyhow::bail!("max_frequency must be above min_frequency");
+        }
+        self.settings()?;
+        self.enabled()?;
+        self.extractors.clear();
+        Ok(())
+    }
+
+    async fn process(&mut self, mut frame: DataFrame) -> Result<DataFrame> {
+        let names = self.channel_names(&frame)?;
+        let enabled = self.enabled()?;
+        let settings = self.settings()?;
+        let input = std::mem::take(&mut frame.payload);
+        for name in names {
+            let sample_rate = input[&name].sample_rate.or_else(|| frame.sample_rate()).unwrap_or(48000.0);
+            let window_size = self.window_size;
+            let state = self
+                .extractors
+                .entry(name.clone())
+                .and_modify(|s| {
+                    if s.extractor.sample_rate() != sample_rate {
+                        s.extractor = FeatureExtractor::new(window_size, sample_rate, &settings);
+                    }
+                })
+                .or_insert_with(|| ChannelFeatures {
+                    extractor: FeatureExtractor::new(window_size, sample_rate, &settings),
+                    buffer: Vec::new(),
+                });
+            state.buffer.extend_from_slice(input[&name].samples());
+
+            let mut blocks: Vec<Features> = Vec::new();
+            while state.buffer.len() >= self.window_size {
+                blocks.push(state.extractor.extract(&state.buffer[..self.window_size]));
+                state.buffer.drain(..self.hop_size);
+            }
+            if blocks.is_empty() {
+                continue;
+            }
+
+            let block_rate = Some(sample_rate / self.hop_size as f64);
+            let mut emit = |feature: String, values: Vec<f64>, unit: Option<&str>| {
+                let mut channel = Channel::new(values).with_role(ChannelRole::Measurement);
+                channel.sample_rate = block_rate;
+                channel.unit = unit.map(str::to_string);
+                frame.insert_channel(format!("{}_{}", name, feature), channel);
+            };
+            for feature in &enabled {
+                match *feature {
+                    "mfcc" => {
+                        for i in 0..self.mfcc_count {
+                            emit(format!("mfcc_{}", i), blocks.iter().map(|b| b.mfcc[i]).collect(), None);
+                        }
+                    }
+                    "centroid" => emit("centroid".into(), blocks.iter().map(|b| b.centroid).collect(), Some("Hz")),
+                    "rolloff" => emit("rolloff".into(), blocks.iter().map(|b| b.rolloff).collect(), Some("Hz")),
+                    "flux" => emit("flux".into(), blocks.iter().map(|b| b.flux).collect(), None),
+                    _ => emit("zcr".into(), blocks.iter().map(|b| b.zero_crossing_rate).collect(), None),
+                }
+            }
+        }
+        Ok(frame)
+    }
+
+    async fn on_destroy(&mut self) -> Result<()> {
+        self.extractors.clear();
+        Ok(())
+    }
+}
@@ -1,11 +1,35 @@
-use crate::core::{ProcessingNode, DataFrame};
+use crate::buffers::FramePool;
+use crate::core::{ChannelRole, DataFrame, ProcessingNode};
+use crate::dsp::{AveragingMode, SpectrumAverager, WindowType};
 use anyhow::Result;
 use async_trait::async_trait;
 use audiotab_macros::StreamNode;
+use rustfft::{Fft, FftPlanner, num_complex::Complex};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
 
-#[derive(StreamNode, Debug, Clone, Serialize, Deserialize)]
+/// FFTNode computes windowed, overlapped magnitude spectra (STFT framing)
+///
+/// Incoming samples are buffered per channel across DataFrames. Every time
+/// `fft_size` samples are available a window is applied, the spectrum is
+/// computed, and the buffer advances by `hop_size` samples. Spectra are then
+/// averaged according to `averaging`.
+///
+/// One output frame carries one spectrum per channel. When an input frame
+/// completes several hops, every spectrum is averaged, but with `averaging`
+/// off only the last is emitted and the number skipped is set as
+/// `spectra_skipped` metadata. To get a spectrum per hop, re-block the
+/// input edge to `hop_size` samples (`block_size` on the connection).
+///
+/// Output payload uses the same channel names as the input, each holding
+/// `fft_size / 2 + 1` single-sided amplitude bins. Frames where no channel
+/// has completed a window yet are emitted with an empty payload.
+#[derive(StreamNode, Clone, Serialize, Deserialize)]
 #[node_meta(name = "FFT", category = "Processors")]
+#[preset(name = "High resolution", params = r#"{"fft_size": 16384, "hop_size": 8192, "window_type": "hann"}"#)]
+#[preset(name = "Amplitude accurate", params = r#"{"window_type": "flattop"}"#)]
+#[preset(name = "Averaged noise spectrum", params = r#"{"averaging": "linear", "num_averages": 32}"#)]
 pub struct FFTNode {
     #[input(name = "Audio In", data_type = "audio_frame")]
     _input: (),
@@ -13,8 +37,44 @@ pub struct FFTNode {
     #[output(name = "FFT Out", data_type = "fft_result")]
     _output: (),
 
-    #[param(default = "\"hann\"")]
+    #[param(default = "1024", min = 16.0, max = 65536.0, unit = "samples", log_scale)]
+    pub fft_size: usize,
+
+    #[param(default = "512", min = 1.0, max = 65536.0, unit = "samples", log_scale)]
+    pub hop_size: usize,
+
+    #[param(default = "\"hann\"", choices = "rectangular,hann,hamming,blackman,flattop")]
     pub window_type: String,
+
+    #[param(default = "\"none\"", choices = "none,linear,exponential,peak_hold")]
+    pub averaging: String,
+
+    #[param(default = "8", min = 1.0, max = 1000.0, step = 1.0)]
+    pub num_averages: usize,
+
+    #[serde(skip)]
+    window: Vec<f64>,
+
+    #[serde(skip)]
+    fft: Option<Arc<dyn Fft<f64>>>,
+
+    #[serde(skip)]
+    buffers: HashMap<String, Vec<f64>>,
+
+    #[serde(skip)]
+    averagers: HashMap<String, SpectrumAverager>,
+}
+
+impl std::fmt::Debug for FFTNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FFTNode")
+            .field("fft_size", &self.fft_size)
+            .field("hop_size", &self.hop_size)
+            .field("window_type", &self.window_type)
+            .field("averaging", &self.averaging)
+            .field("num_averages", &self.num_averages)
+            .finish()
+    }
 }
 
 impl Default for FFTNode {
@@ -22,16 +82,136 @@ impl Default for FFTNode {
         Self {
             _input: (),
             _output: (),
+            fft_size: 1024,
+            hop_size: 512,
             window_type: "hann".to_string(),
+            averaging: "none".to_string(),
+            num_averages: 8,
+            window: Vec::new(),
+            fft: None,
+            buffers: HashMap::new(),
+            averagers: HashMap::new(),
+        }
+    }
+}
+
+impl FFTNode {
+    /// Validate parameters and (re)build the window and FFT plan
+    fn configure(&mut self) -> Result<()> {
+        if self.fft_size < 2 {
+            anyhow::bail!("fft_size must be at least 2, got {}", self.fft_size);
         }
+        if self.hop_size == 0 || self.hop_size > self.fft_size {
+            anyhow::bail!(
+                "hop_size must be between 1 and fft_size ({}), got {}",
+                self.fft_size, self.hop_size
+            );
+        }
+        let window = WindowType::parse(&self.window_type)?;
+        AveragingMode::parse(&self.averaging)?;
+
+        self.window = window.coefficients(self.fft_size);
+        self.fft = Some(FftPlanner::new().plan_fft_forward(self.fft_size));
+        self.buffers.clear();
+        self.averagers.clear();
+        Ok(())
+    }
+
+    /// Single-sided amplitude spectrum of one windowed block
+    fn compute_spectrum(&self, fft: &Arc<dyn Fft<f64>>, block: &[f64]) -> Vec<f64> {
+        let mut bins: Vec<Complex<f64>> = block
+            .iter()
+            .zip(self.window.iter())
+            .map(|(&s, &w)| Complex::new(s * w, 0.0))
+            .collect();
+        fft.process(&mut bins);
+
+        // Normalise by the window's coherent gain so a full-scale sine reads its amplitude
+        let coherent_gain: f64 = self.window.iter().sum();
+        let num_bins = self.fft_size / 2 + 1;
+        bins.iter()
+            .take(num_bins)
+            .enumerate()
+            .map(|(k, c)| {
+                let scale = if k == 0 || 2 * k == self.fft_size {
+                    1.0
+                } else {
+                    2.0
+                };
+                scale * c.norm() / coherent_gain
+            })
+            .collect()
     }
 }
 
 #[async_trait]
 impl ProcessingNode for FFTNode {
-    async fn process(&mut self, frame: DataFrame) -> Result<DataFrame> {
-        // Placeholder - just pass through
-        // Real FFT implementation will come in next phase
+    async fn on_configured(&mut self, config: &serde_json::Value) -> Result<()> {
+        if config.get("hop_size").is_none() {
+            if let Some(overlap) = config.get("overlap").and_then(|v| v.as_f64()) {
+                // Overlap given as a fraction (0.0 - <1.0) of the FFT size
+                if !(0.0..1.0).contains(&overlap) {
+                    anyhow::bail!("overlap must be in [0.0, 1.0), got {}", overlap);
+                }
+                self.hop_size = ((self.fft_size as f64) * (1.0 - overlap)).round().max(1.0) as usize;
+            }
+        }
+
+        self.configure()
+    }
+
+    async fn process(&mut self, mut frame: DataFrame) -> Result<DataFrame> {
+        if self.fft.is_none() {
+            self.configure()?;
+        }
+        let fft = self.fft.clone().expect("FFT plan configured");
+        let mode = AveragingMode::parse(&self.averaging)?;
+
+        let pool = FramePool::global();
+        let mut skipped = 0;
+        let input = std::mem::take(&mut frame.payload);
+        for (channel, samples) in input {
+            let mut buffer = self.buffers.remove(&channel).unwrap_or_default();
+            buffer.extend_from_slice(&samples);
+
+            let mut hops: usize = 0;
+            while buffer.len() >= self.fft_size {
+                let spectrum = self.compute_spectrum(&fft, &buffer[..self.fft_size]);
+                self.averagers
+                    .entry(channel.clone())
+                    .or_insert_with(|| SpectrumAverager::new(mode, self.num_averages))
+                    .push(spectrum);
+                buffer.drain(..self.hop_size);
+                hops += 1;
+            }
+            if mode == AveragingMode::None {
+                skipped = skipped.max(hops.saturating_sub(1));
+            }
+
+            if hops > 0 {
+                let average = self.averagers[&channel].current();
+                let mut spectrum = pool.take(average.len());
+                spectrum.extend_from_slice(average);
+                frame.insert_channel(channel.clone(), pool.channel(spectrum).with_role(ChannelRole::Spectrum));
+            }
+            self.buffers.insert(channel, buffer);
+        }
+
+        let sample_rate = frame.sample_rate().unwrap_or(48000.0);
+        frame.metadata.insert("fft_size", self.fft_size);
+        frame.metadata.insert("hop_size", self.hop_size);
+        frame.metadata.insert("window", &self.window_type);
+        frame.metadata.insert("bin_resolution_hz", sample_rate / self.fft_size as f64);
+        if skipped > 0 {
+            frame.metadata.insert("spectra_skipped", skipped);
+        }
+
         Ok(frame)
     }
+
+    async fn on_destroy(&mut self) -> Result<()> {
+        self.buffers.clear();
+        self.averagers.clear();
+        Ok(())
+    }
 }
@@ -13,10 +13,10 @@ pub struct FilterNode {
     #[output(name = "Audio Out", data_type = "audio_frame")]
     _output: (),
 
-    #[param(default = "\"lowpass\"")]
+    #[param(default = "\"lowpass\"", choices = "lowpass,highpass,bandpass,bandstop")]
     pub filter_type: String,
 
-    #[param(default = "1000.0", min = 20.0, max = 20000.0)]
+    #[param(default = "1000.0", min = 20.0, max = 20000.0, unit = "Hz", step = 1.0, log_scale)]
     pub cutoff_hz: f64,
 }
 
@@ -0,0 +1,301 @@
+use crate::core::{DataFrame, ProcessingNode};
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Id prefix of the instances of the variadic input port
+const INPUT_PORT: &str = "_inputs_";
+
+/// What a merged frame contains for an input with no frame close enough in time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillPolicy {
+    /// Discard frames that cannot be matched; only complete merges are output
+    Drop,
+    /// Output the input's channels as silence
+    Zeros,
+    /// Repeat the input's last frame
+    Hold,
+}
+
+impl FillPolicy {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "drop" => Ok(FillPolicy::Drop),
+            "zeros" => Ok(FillPolicy::Zeros),
+            "hold" => Ok(FillPolicy::Hold),
+            _ => anyhow::bail!("Unknown fill policy: {}", name),
+        }
+    }
+}
+
+/// FrameMergeNode time-aligns frames from several sources into one frame
+///
+/// Connect each source to its own instance of the variadic input
+/// (`_inputs_0`, `_inputs_1`, ...). Frames are queued per input and merged
+/// when every input has a frame whose timestamp lies within `tolerance_ms`
+/// of the oldest queued one. Channels are renamed `<prefix>_<channel>`, with
+/// prefixes from `prefixes` (comma separated, one per input, default
+/// `in0`, `in1`, ...), so two devices' `ch0` stay apart. Event series are
+/// renamed the same way.
+///
+/// An input whose matching frame is missing (a later frame arrived instead,
+/// or its queue stays empty while another input has more than `max_pending`
+/// frames waiting) is handled by `fill`: `drop` discards the incomplete set,
+/// `zeros` outputs silence for the missing input and `hold` repeats its last
+/// frame. Frames are matched by timestamp only, so sources should deliver
+/// blocks of the same length; set `block_size` on the edges if they do not.
+///
+/// Every input frame produces one output frame. While waiting for a match
+/// the output carries no channels and `merged` metadata is false. A flush
+/// outputs the oldest queued set, filled as if its missing frames were lost.
+#[derive(StreamNode, Debug, Clone, Serialize, Deserialize)]
+#[node_meta(name = "Frame Merge", category = "Processors")]
+#[preset(name = "Hold slow source", params = r#"{"fill": "hold", "tolerance_ms": 50.0}"#)]
+pub struct FrameMergeNode {
+    #[input(name = "Audio In", data_type = "audio_frame", variadic, min = 2, max = 16)]
+    _inputs: (),
+
+    #[output(name = "Merged Out", data_type = "audio_frame")]
+    _output: (),
+
+    #[param(default = "5.0", min = 0.0, max = 10000.0, unit = "ms")]
+    pub tolerance_ms: f64,
+
+    #[param(default = "\"drop\"", choices = "drop,zeros,hold")]
+    pub fill: String,
+
+    #[param(default = "8", min = 1.0, max = 1024.0, step = 1.0)]
+    pub max_pending: usize,
+
+    #[param(default = "\"\"", skip_config)]
+    pub prefixes: String,
+
+    #[serde(skip)]
+    pending: Vec<VecDeque<DataFrame>>,
+
+    /// Last frame merged from each input, for `hold` and `zeros`
+    #[serde(skip)]
+    last: Vec<Option<DataFrame>>,
+
+    #[serde(skip)]
+    next_sequence: u64,
+
+    #[serde(skip)]
+    dropped: u64,
+}
+
+impl Default for FrameMergeNode {
+    fn default() -> Self {
+        Self {
+            _inputs: (),
+            _output: (),
+            tolerance_ms: 5.0,
+            fill: "drop".to_string(),
+            max_pending: 8,
+            prefixes: String::new(),
+            pending: vec![VecDeque::new(); 2],
+            last: vec![None; 2],
+            next_sequence: 0,
+            dropped: 0,
+        }
+    }
+}
+
+impl FrameMergeNode {
+    fn inputs(&self) -> usize {
+        self.pending.len()
+    }
+
+    fn prefix(&self, input: usize) -> String {
+        self.prefixes
+            .split(',')
+            .nth(input)
+            .map(|p| p.trim())
+            .filter(|p| !p.is_empty())
+            .map_or_else(|| format!("in{}", input), |p| p.to_string())
+    }
+
+    fn tolerance_ns(&self) -> u64 {
+        (self.tolerance_ms * 1e6) as u64
+    }
+
+    /// Frames discarded because no matching frame arrived on another input
+    pub fn dropped_frames(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Next merged frame from the queues, if one can be completed
+    ///
+    /// With `force`, incomplete sets are resolved by the fill policy even
+    /// if a missing frame might still arrive.
+    fn next_merge(&mut self, force: bool) -> Result<Option<DataFrame>> {
+        let fill = FillPolicy::parse(&self.fill)?;
+        let tolerance = self.tolerance_ns();
+        loop {
+            let Some(oldest) = self.pending.iter().filter_map(|q| q.front()).map(|f| f.timestamp).min() else {
+                return Ok(None);
+            };
+            let matched: Vec<bool> = self
+                .pending
+                .iter()
+                .map(|q| q.front().is_some_and(|f| f.timestamp <= oldest.saturating_add(tolerance)))
+                .collect();
+            if matched.iter().all(|&m| m) {
+                return Ok(Some(self.merge(&matched, fill)));
+            }
+
+            // A missing frame may still arrive on an input with nothing queued;
+            // one with a later frame queued has skipped it
+            let may_arrive = self.pending.iter().any(|q| q.is_empty());
+            let overflowing = self.pending.iter().any(|q| q.len() > self.max_pending);
+            if may_arrive && !overflowing && !force {
+                return Ok(None);
+            }
+
+            if fill == FillPolicy::Drop {
+                for (queue, _) in self.pending.iter_mut().zip(&matched).filter(|(_, &m)| m) {
+                    queue.pop_front();
+                    self.dropped += 1;
+                }
+                continue;
+            }
+            return Ok(Some(self.merge(&matched, fill)));
+        }
+    }
+
+    /// Pop the matched inputs' frames and combine them, filling the others
+    fn merge(&mut self, matched: &[bool], fill: FillPolicy) -> DataFrame {
+        let frames: Vec<Option<DataFrame>> = self
+            .pending
+            .iter_mut()
+            .zip(matched)
+            .map(|(queue, &m)| if m { queue.pop_front() } else { None })
+            .collect();
+
+        let present = frames.iter().flatten();
+        let base = present.clone().min_by_key(|f| f.timestamp).expect("merge of no frames");
+        let newest = present.clone().map(|f| f.timestamp).max().unwrap_or(base.timestamp);
+        let block_len = present.clone().flat_map(|f| f.payload.values()).map(|c| c.len()).next().unwrap_or(0);
+
+        let mut merged = DataFrame::new(base.timestamp, self.next_sequence);
+        merged.metadata = base.metadata.clone();
+        merged.metadata.remove("input_port");
+
+        let mut filled = 0;
+        for (input, frame) in frames.iter().enumerate() {
+            let prefix = self.prefix(input);
+            let source = match frame {
+                Some(frame) => {
+                    self.last[input] = Some(frame.clone());
+                    frame
+                }
+                None => match &self.last[input] {
+                    Some(last) => {
+                        filled += 1;
+                        last
+                    }
+                    None => continue,
+                },
+            };
+            for (name, channel) in &source.payload {
+                let channel = match (frame, fill) {
+                    (None, FillPolicy::Zeros) => channel.with_samples(vec![0.0; block_len]),
+                    _ => channel.clone(),
+                };
+                merged.payload.insert(format!("{}_{}", prefix, name), channel);
+            }
+            // Events happened once; a filled input does not repeat them
+            if frame.is_some() {
+                for (name, events) in &source.events {
+                    merged.events.insert(format!("{}_{}", prefix, name), events.clone());
+                }
+            }
+        }
+
+        merged.metadata.insert("merged", true);
+        merged.metadata.insert("merged_inputs", matched.iter().filter(|&&m| m).count());
+        merged.metadata.insert("filled_inputs", filled);
+        merged.metadata.insert("timestamp_skew_ns", newest - base.timestamp);
+        merged
+    }
+
+    /// Empty output while the queues wait for a match
+    fn waiting(&self, timestamp: u64) -> DataFrame {
+        let mut frame = DataFrame::new(timestamp, self.next_sequence);
+        frame.metadata.insert("merged", false);
+        frame
+    }
+
+    fn reset(&mut self, inputs: usize) {
+        self.pending = vec![VecDeque::new(); inputs];
+        self.last = vec![None; inputs];
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for FrameMergeNode {
+    async fn on_configured(&mut self, config: &serde_json::Value) -> Result<()> {
+        if let Some(p) = config.get("prefixes") {
+            // Accept either "mic,accel" or ["mic", "accel"]
+            if let Some(s) = p.as_str() {
+                self.prefixes = s.to_string();
+            } else if let Some(list) = p.as_array() {
+                self.prefixes = list
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .collect::<Vec<_>>()
+                    .join(",");
+            }
+        }
+        FillPolicy::parse(&self.fill)?;
+
+        let inputs = config["port_counts"]["_inputs"].as_u64().unwrap_or(2) as usize;
+        if inputs < 2 {
+            anyhow::bail!("Frame merge needs at least 2 inputs, got {}", inputs);
+        }
+        self.reset(inputs);
+        self.dropped = 0;
+        Ok(())
+    }
+
+    async fn process(&mut self, frame: DataFrame) -> Result<DataFrame> {
+        let port = frame.metadata.get_str("input_port").unwrap_or_default();
+        let input = port
+            .strip_prefix(INPUT_PORT)
+            .and_then(|i| i.parse::<usize>().ok())
+            .filter(|&i| i < self.inputs())
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Frame arrived on port '{}'; connect each source to one of {}0..{}{}",
+                    port, INPUT_PORT, INPUT_PORT, self.inputs() - 1
+                )
+            })?;
+
+        let timestamp = frame.timestamp;
+        self.pending[input].push_back(frame);
+        let output = match self.next_merge(false)? {
+            Some(merged) => merged,
+            None => self.waiting(timestamp),
+        };
+        self.next_sequence += 1;
+        Ok(output)
+    }
+
+    async fn on_flush(&mut self) -> Result<Option<DataFrame>> {
+        // Resolve the oldest queued set; later ones would be stale after the flush
+        let merged = self.next_merge(true)?;
+        if merged.is_some() {
+            self.next_sequence += 1;
+        }
+        self.pending.iter_mut().for_each(VecDeque::clear);
+        Ok(merged)
+    }
+
+    async fn on_destroy(&mut self) -> Result<()> {
+        let inputs = self.inputs();
+        self.reset(inputs);
+        Ok(())
+    }
+}
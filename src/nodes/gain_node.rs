@@ -13,7 +13,7 @@ pub struct GainNode {
     #[output(name = "Audio Out", data_type = "audio_frame")]
     _output: (),
 
-    #[param(default = "0.0", min = 0.0, max = 80.0)]
+    #[param(default = "0.0", min = -80.0, max = 80.0, unit = "dB", step = 0.1)]
     pub gain_db: f64,
 
     #[serde(skip)]
@@ -33,11 +33,7 @@ impl Default for GainNode {
 
 #[async_trait]
 impl ProcessingNode for GainNode {
-    async fn on_create(&mut self, config: serde_json::Value) -> Result<()> {
-        if let Some(gain_db) = config.get("gain_db").and_then(|v| v.as_f64()) {
-            self.gain_db = gain_db;
-        }
-
+    async fn on_configured(&mut self, _config: &serde_json::Value) -> Result<()> {
         // Convert dB to linear
         self.gain_linear = 10_f64.powf(self.gain_db / 20.0);
 
@@ -47,11 +43,9 @@ impl ProcessingNode for GainNode {
     async fn process(&mut self, mut frame: DataFrame) -> Result<DataFrame> {
         // Apply gain to all payload channels
         for (_key, data) in frame.payload.iter_mut() {
-            let mut samples = data.as_ref().clone();
-            for sample in samples.iter_mut() {
+            for sample in data.samples_mut().iter_mut() {
                 *sample *= self.gain_linear;
             }
-            *data = std::sync::Arc::new(samples);
         }
 
         Ok(frame)
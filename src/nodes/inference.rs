@@ -0,0 +1,295 @@
+use crate::core::{Channel, ChannelRole, DataFrame, ProcessingNode};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tract_onnx::prelude::*;
+use tract_onnx::tract_hir::infer::Factoid;
+
+type OnnxPlan = TypedRunnableModel<TypedModel>;
+
+/// How an InferenceNode builds model inputs from a frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputLayout {
+    /// One input per sample index, taking one value from each input channel
+    Features,
+    /// One input per `window_size` samples of a single channel
+    Window,
+}
+
+impl InputLayout {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "features" => Ok(InputLayout::Features),
+            "window" => Ok(InputLayout::Window),
+            _ => anyhow::bail!("Unknown input layout: {}", name),
+        }
+    }
+}
+
+/// Parse a comma separated list, dropping empty entries
+fn split_list(list: &str) -> Vec<String> {
+    list.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
+}
+
+/// Sort key that orders a trailing number numerically, so `mfcc_2` comes
+/// before `mfcc_10`
+fn natural_key(name: &str) -> (&str, u64) {
+    let stem = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    (stem, name[stem.len()..].parse().unwrap_or(0))
+}
+
+/// InferenceNode runs an ONNX model on feature vectors or audio windows
+///
+/// The model at `model_path` is loaded with tract and given a single f32
+/// input. With `layout` `features`, every sample index of the `inputs`
+/// channels (in the given order, e.g. the outputs of a Feature Extractor)
+/// forms one input vector; with `window`, the single input channel is
+/// buffered into windows of `window_size` samples advancing by `hop_size`.
+/// The vector is fed with shape `input_shape` (comma separated, e.g.
+/// `"1,1,40,32"`), or `[1, n]` when empty.
+///
+/// The first model output is flattened into channels named after `labels`
+/// (comma separated), or `<output_prefix>_0`, `<output_prefix>_1`, ...,
+/// with one value per input vector. With `softmax` on, logits are turned
+/// into probabilities first. `top_class` and `top_score` hold the index and
+/// value of the largest output, for event detection downstream. Output
+/// channels replace the payload unless `keep_inputs` is on; frames that
+/// complete no input get an empty payload.
+#[derive(StreamNode, Clone, Serialize, Deserialize)]
+#[node_meta(name = "Inference", category = "Processors")]
+#[preset(name = "Event classifier on features", params = r#"{"model_path": "classifier.onnx", "layout": "features", "softmax": true}"#)]
+pub struct InferenceNode {
+    #[input(name = "Features In", data_type = "any")]
+    _input: (),
+
+    #[output(name = "Scores Out", data_type = "features")]
+    _output: (),
+
+    #[param(default = "\"\"")]
+    pub model_path: String,
+
+    #[param(default = "\"features\"", choices = "features,window")]
+    pub layout: String,
+
+    #[param(default = "\"\"", skip_config)]
+    pub inputs: String,
+
+    #[param(default = "16000", min = 1.0, max = 1048576.0, unit = "samples", log_scale)]
+    pub window_size: usize,
+
+    #[param(default = "16000", min = 1.0, max = 1048576.0, unit = "samples", log_scale)]
+    pub hop_size: usize,
+
+    #[param(default = "\"\"")]
+    pub input_shape: String,
+
+    #[param(default = "\"\"", skip_config)]
+    pub labels: String,
+
+    #[param(default = "\"score\"")]
+    pub output_prefix: String,
+
+    #[param(default = "false")]
+    pub softmax: bool,
+
+    #[param(default = "false")]
+    pub keep_inputs: bool,
+
+    #[serde(skip)]
+    model: Option<Arc<OnnxPlan>>,
+
+    #[serde(skip)]
+    shape: Vec<usize>,
+
+    #[serde(skip)]
+    buffer: Vec<f64>,
+}
+
+impl std::fmt::Debug for InferenceNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InferenceNode")
+            .field("model_path", &self.model_path)
+            .field("layout", &self.layout)
+            .field("inputs", &self.inputs)
+            .field("input_shape", &self.shape)
+            .field("labels", &self.labels)
+            .field("softmax", &self.softmax)
+            .finish()
+    }
+}
+
+impl Default for InferenceNode {
+    fn default() -> Self {
+        Self {
+            _input: (),
+            _output: (),
+            model_path: String::new(),
+            layout: "features".to_string(),
+            inputs: String::new(),
+            window_size: 16000,
+            hop_size: 16000,
+            input_shape: String::new(),
+            labels: String::new(),
+            output_prefix: "score".to_string(),
+            softmax: false,
+            keep_inputs: false,
+            model: None,
+            shape: Vec::new(),
+            buffer: Vec::new(),
+        }
+    }
+}
+
+impl InferenceNode {
+    /// Input vectors of one frame and the rate at which they occur
+    fn input_vectors(&mut self, frame: &DataFrame, layout: InputLayout) -> Result<(Vec<Vec<f32>>, Option<f64>)> {
+        let mut names = split_list(&self.inputs);
+        if names.is_empty() {
+            names = frame.payload.keys().cloned().collect();
+            names.sort_by(|a, b| natural_key(a).cmp(&natural_key(b)).then_with(|| a.cmp(b)));
+        }
+        let channels = names
+            .iter()
+            .map(|name| frame.payload.get(name).ok_or_else(|| anyhow::anyhow!("Channel '{}' is not in the frame", name)))
+            .collect::<Result<Vec<&Channel>>>()?;
+
+        match layout {
+            InputLayout::Features => {
+                let rows = channels.first().map_or(0, |c| c.len());
+                if let Some((name, other)) = names.iter().zip(&channels).find(|(_, c)| c.len() != rows) {
+                    anyhow::bail!("Channel '{}' has {} values but '{}' has {}", name, other.len(), names[0], rows);
+                }
+                let vectors = (0..rows).map(|i| channels.iter().map(|c| c[i] as f32).collect()).collect();
+                Ok((vectors, channels.first().and_then(|c| c.sample_rate).or_else(|| frame.sample_rate())))
+            }
+            InputLayout::Window => {
+                let [channel] = channels.as_slice() else {
+                    anyhow::bail!("Window layout takes one input channel, got {}", channels.len());
+                };
+                self.buffer.extend_from_slice(channel.samples());
+                let mut windows = Vec::new();
+                while self.buffer.len() >= self.window_size {
+                    windows.push(self.buffer[..self.window_size].iter().map(|&x| x as f32).collect());
+                    self.buffer.drain(..self.hop_size);
+                }
+                let rate = frame.channel_sample_rate(&names[0]).map(|r| r / self.hop_size as f64);
+                Ok((windows, rate))
+            }
+        }
+    }
+
+    fn run(&self, input: &[f32]) -> Result<Vec<f64>> {
+        let model = self.model.as_ref().ok_or_else(|| anyhow::anyhow!("No ONNX model loaded"))?;
+        let tensor = Tensor::from_shape(&self.shape, input)?;
+        let outputs = model.run(tvec!(tensor.into()))?;
+        let output = outputs.first().ok_or_else(|| anyhow::anyhow!("Model produced no output"))?;
+        let mut values: Vec<f64> = output.cast_to::<f32>()?.as_slice::<f32>()?.iter().map(|&v| v as f64).collect();
+        if self.softmax {
+            let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            values.iter_mut().for_each(|v| *v = (*v - max).exp());
+            let sum: f64 = values.iter().sum();
+            values.iter_mut().for_each(|v| *v /= sum);
+        }
+        Ok(values)
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for InferenceNode {
+    async fn on_configured(&mut self, config: &serde_json::Value) -> Result<()> {
+        for (key, target) in [("inputs", &mut self.inputs), ("labels", &mut self.labels)] {
+            // Accept either "a,b" or ["a", "b"]
+            if let Some(s) = config.get(key).and_then(|v| v.as_str()) {
+                *target = s.to_string();
+            } else if let Some(list) = config.get(key).and_then(|v| v.as_array()) {
+                *target = list.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>().join(",");
+            }
+        }
+
+        let layout = InputLayout::parse(&self.layout)?;
+        if layout == InputLayout::Window && (self.hop_size == 0 || self.window_size == 0) {
+            anyhow::bail!("window_size and hop_size must be at least 1");
+        }
+        if self.model_path.trim().is_empty() {
+            anyhow::bail!("model_path is required");
+        }
+
+        // Without an explicit shape, the vector length comes from the model
+        // or the window size
+        self.shape = split_list(&self.input_shape)
+            .iter()
+            .map(|d| d.parse::<usize>().map_err(|_| anyhow::anyhow!("Invalid input_shape dimension '{}'", d)))
+            .collect::<Result<_>>()?;
+        let model = tract_onnx::onnx()
+            .model_for_path(self.model_path.trim())
+            .with_context(|| format!("Failed to load ONNX model {}", self.model_path))?;
+        if self.shape.is_empty() {
+            let length = match layout {
+                InputLayout::Window => self.window_size,
+                InputLayout::Features => {
+                    let fact = model.input_fact(0)?;
+                    let last = fact.shape.dims().last().and_then(|d| d.concretize()).and_then(|d| d.to_i64().ok());
+                    match (last, split_list(&self.inputs).len()) {
+                        (Some(n), _) if n > 0 => n as usize,
+                        (_, n) if n > 0 => n,
+                        _ => anyhow::bail!("Set input_shape or inputs: the model does not fix its input length"),
+                    }
+                }
+            };
+            self.shape = vec![1, length];
+        }
+        let model = model
+            .with_input_fact(0, f32::fact(&self.shape).into())?
+            .into_optimized()?
+            .into_runnable()
+            .with_context(|| format!("Failed to prepare ONNX model {}", self.model_path))?;
+        self.model = Some(Arc::new(model));
+        self.buffer.clear();
+        Ok(())
+    }
+
+    async fn process(&mut self, mut frame: DataFrame) -> Result<DataFrame> {
+        let layout = InputLayout::parse(&self.layout)?;
+        let (vectors, rate) = self.input_vectors(&frame, layout)?;
+        let expected: usize = self.shape.iter().product();
+        if let Some(bad) = vectors.iter().find(|v| v.len() != expected) {
+            anyhow::bail!("Model input has {} values but input_shape {:?} takes {}", bad.len(), self.shape, expected);
+        }
+        let outputs = vectors.iter().map(|v| self.run(v)).collect::<Result<Vec<_>>>()?;
+
+        if !self.keep_inputs {
+            frame.payload.clear();
+        }
+        let Some(width) = outputs.first().map(|o| o.len()) else {
+            return Ok(frame);
+        };
+        let labels = split_list(&self.labels);
+        if !labels.is_empty() && labels.len() != width {
+            anyhow::bail!("{} labels given but the model outputs {} values", labels.len(), width);
+        }
+
+        let mut emit = |name: String, values: Vec<f64>| {
+            let mut channel = Channel::new(values).with_role(ChannelRole::Measurement);
+            channel.sample_rate = rate;
+            frame.insert_channel(name, channel);
+        };
+        for k in 0..width {
+            let name = labels.get(k).cloned().unwrap_or_else(|| format!("{}_{}", self.output_prefix, k));
+            emit(name, outputs.iter().map(|o| o[k]).collect());
+        }
+        let top: Vec<(usize, f64)> = outputs
+            .iter()
+            .map(|o| o.iter().copied().enumerate().fold((0, f64::NEG_INFINITY), |a, b| if b.1 > a.1 { b } else { a }))
+            .collect();
+        emit("top_class".to_string(), top.iter().map(|t| t.0 as f64).collect());
+        emit("top_score".to_string(), top.iter().map(|t| t.1).collect());
+        Ok(frame)
+    }
+
+    async fn on_destroy(&mut self) -> Result<()> {
+        self.buffer.clear();
+        Ok(())
+    }
+}
@@ -0,0 +1,272 @@
+use crate::core::{ChannelRole, DataFrame, ProcessingNode};
+use crate::dsp::biquad::Biquad;
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Conversion an IntegratorNode applies to each channel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IntegrationMode {
+    /// Acceleration to velocity, or velocity to displacement
+    Integrate,
+    /// Acceleration to displacement
+    DoubleIntegrate,
+    /// Displacement to velocity, or velocity to acceleration
+    Differentiate,
+}
+
+impl IntegrationMode {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "integrate" => Ok(IntegrationMode::Integrate),
+            "double_integrate" => Ok(IntegrationMode::DoubleIntegrate),
+            "differentiate" => Ok(IntegrationMode::Differentiate),
+            _ => anyhow::bail!("Unknown integration mode: {}", name),
+        }
+    }
+
+    /// Unit of the result for a known input unit, e.g. `m/s²` to `m/s`
+    pub fn output_unit(self, unit: &str) -> Option<&'static str> {
+        const CHAINS: [[&str; 3]; 2] = [["m/s²", "m/s", "m"], ["mm/s²", "mm/s", "mm"]];
+        let unit = unit.trim().replace("^2", "²");
+        let (chain, pos) = CHAINS
+            .iter()
+            .find_map(|chain| chain.iter().position(|u| *u == unit).map(|pos| (chain, pos)))?;
+        let pos = match self {
+            IntegrationMode::Integrate => pos + 1,
+            IntegrationMode::DoubleIntegrate => pos + 2,
+            IntegrationMode::Differentiate => pos.checked_sub(1)?,
+        };
+        chain.get(pos).copied()
+    }
+}
+
+/// Trapezoidal integrator followed by a high-pass that removes its drift
+#[derive(Debug, Clone)]
+struct Integrator {
+    previous: f64,
+    sum: f64,
+    highpass: Option<Biquad>,
+}
+
+impl Integrator {
+    fn process(&mut self, x: f64, dt: f64) -> f64 {
+        self.sum += 0.5 * (x + self.previous) * dt;
+        self.previous = x;
+        match &mut self.highpass {
+            Some(hp) => hp.process(self.sum),
+            None => self.sum,
+        }
+    }
+}
+
+/// Filter and integrator state of one channel, rebuilt when its rate changes
+#[derive(Debug, Clone)]
+struct ChannelState {
+    sample_rate: f64,
+    input_highpass: Option<Biquad>,
+    integrators: Vec<Integrator>,
+    /// Last input sample, for the differentiator
+    previous: Option<f64>,
+}
+
+impl ChannelState {
+    fn new(mode: IntegrationMode, highpass_hz: f64, sample_rate: f64) -> Self {
+        let highpass = (highpass_hz > 0.0).then(|| Biquad::highpass(highpass_hz, sample_rate));
+        let stages = match mode {
+            IntegrationMode::Integrate => 1,
+            IntegrationMode::DoubleIntegrate => 2,
+            IntegrationMode::Differentiate => 0,
+        };
+        Self {
+            sample_rate,
+            input_highpass: if stages > 0 { highpass } else { None },
+            integrators: vec![Integrator { previous: 0.0, sum: 0.0, highpass }; stages],
+            previous: None,
+        }
+    }
+
+    fn process(&mut self, mode: IntegrationMode, samples: &[f64]) -> Vec<f64> {
+        let dt = 1.0 / self.sample_rate;
+        samples
+            .iter()
+            .map(|&x| {
+                if mode == IntegrationMode::Differentiate {
+                    let dx = x - self.previous.unwrap_or(x);
+                    self.previous = Some(x);
+                    return dx * self.sample_rate;
+                }
+                let x = match &mut self.input_highpass {
+                    Some(hp) => hp.process(x),
+                    None => x,
+                };
+                self.integrators.iter_mut().fold(x, |acc, stage| stage.process(acc, dt))
+            })
+            .collect()
+    }
+}
+
+/// IntegratorNode converts vibration signals between acceleration, velocity
+/// and displacement
+///
+/// `mode` is `integrate` (acceleration to velocity, or velocity to
+/// displacement), `double_integrate` (acceleration to displacement) or
+/// `differentiate` (the reverse of `integrate`). Integration is trapezoidal;
+/// a second-order Butterworth high-pass at `highpass_hz` on the input and
+/// after each stage removes the DC offset and low-frequency drift an
+/// accelerometer would otherwise integrate into a ramp. A `highpass_hz` of 0
+/// disables it. Differentiation is a first difference.
+///
+/// Converts the comma separated `channels`, or every signal channel when
+/// empty, in place, or into `<channel><suffix>` when `suffix` is set. The
+/// result is multiplied by `output_scale` and gets unit `output_unit`; when
+/// that is empty, the unit follows from the input (`m/s²` to `m/s` to `m`,
+/// likewise for `mm`) unless a scale is applied. State carries across
+/// frames, so a stream integrates continuously.
+#[derive(StreamNode, Debug, Clone, Serialize, Deserialize)]
+#[node_meta(name = "Integrator", category = "Processors")]
+#[preset(name = "Acceleration to velocity (mm/s)", params = r#"{"mode": "integrate", "highpass_hz": 10.0, "output_scale": 1000.0, "output_unit": "mm/s"}"#)]
+#[preset(name = "Acceleration to displacement (µm)", params = r#"{"mode": "double_integrate", "highpass_hz": 10.0, "output_scale": 1000000.0, "output_unit": "µm"}"#)]
+pub struct IntegratorNode {
+    #[input(name = "Audio In", data_type = "audio_frame")]
+    _input: (),
+
+    #[output(name = "Audio Out", data_type = "audio_frame")]
+    _output: (),
+
+    #[param(default = "\"integrate\"", choices = "integrate,double_integrate,differentiate")]
+    pub mode: String,
+
+    #[param(default = "10.0", min = 0.0, max = 1000.0, unit = "Hz", log_scale)]
+    pub highpass_hz: f64,
+
+    #[param(default = "\"\"", skip_config)]
+    pub channels: String,
+
+    #[param(default = "\"\"")]
+    pub suffix: String,
+
+    #[param(default = "1.0")]
+    pub output_scale: f64,
+
+    #[param(default = "\"\"")]
+    pub output_unit: String,
+
+    #[serde(skip)]
+    parsed: Option<IntegrationMode>,
+
+    #[serde(skip)]
+    states: HashMap<String, ChannelState>,
+}
+
+impl Default for IntegratorNode {
+    fn default() -> Self {
+        Self {
+            _input: (),
+            _output: (),
+            mode: "integrate".to_string(),
+            highpass_hz: 10.0,
+            channels: String::new(),
+            suffix: String::new(),
+            output_scale: 1.0,
+            output_unit: String::new(),
+            parsed: None,
+            states: HashMap::new(),
+        }
+    }
+}
+
+impl IntegratorNode {
+    fn channel_names(&self, frame: &DataFrame) -> Result<Vec<String>> {
+        if self.channels.trim().is_empty() {
+            let mut names: Vec<String> = frame
+                .payload
+                .iter()
+                .filter(|(_, c)| c.role == ChannelRole::Signal)
+                .map(|(name, _)| name.clone())
+                .collect();
+            names.sort();
+            return Ok(names);
+        }
+        let names: Vec<String> =
+            self.channels.split(',').map(|c| c.trim().to_string()).filter(|c| !c.is_empty()).collect();
+        if let Some(missing) = names.iter().find(|name| !frame.payload.contains_key(*name)) {
+            anyhow::bail!("Channel '{}' is not in the frame", missing);
+        }
+        Ok(names)
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for IntegratorNode {
+    async fn on_configured(&mut self, config: &serde_json::Value) -> Result<()> {
+        if let Some(c) = config.get("channels") {
+            // Accept either "ch0,ch1" or ["ch0", "ch1"]
+            if let Some(s) = c.as_str() {
+                self.channels = s.to_string();
+            } else if let Some(list) = c.as_array() {
+                self.channels = list
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .collect::<Vec<_>>()
+                    .join(",");
+            }
+        }
+        if self.highpass_hz < 0.0 {
+            anyhow::bail!("highpass_hz must not be negative");
+        }
+        self.parsed = Some(IntegrationMode::parse(&self.mode)?);
+        self.states.clear();
+        Ok(())
+    }
+
+    async fn process(&mut self, mut frame: DataFrame) -> Result<DataFrame> {
+        let mode = match self.parsed {
+            Some(mode) => mode,
+            None => *self.parsed.insert(IntegrationMode::parse(&self.mode)?),
+        };
+        for name in self.channel_names(&frame)? {
+            let channel = &frame.payload[&name];
+            let sample_rate = frame.channel_sample_rate(&name).unwrap_or(48000.0);
+            if self.highpass_hz >= sample_rate / 2.0 {
+                anyhow::bail!(
+                    "highpass_hz {} is above the Nyquist frequency of channel '{}' ({} Hz)",
+                    self.highpass_hz,
+                    name,
+                    sample_rate / 2.0
+                );
+            }
+            let state = self
+                .states
+                .entry(name.clone())
+                .and_modify(|s| {
+                    if s.sample_rate != sample_rate {
+                        *s = ChannelState::new(mode, self.highpass_hz, sample_rate);
+                    }
+                })
+                .or_insert_with(|| ChannelState::new(mode, self.highpass_hz, sample_rate));
+
+            let mut samples = state.process(mode, channel.samples());
+            if self.output_scale != 1.0 {
+                samples.iter_mut().for_each(|x| *x *= self.output_scale);
+            }
+            let mut output = channel.with_samples(samples);
+            output.unit = if !self.output_unit.trim().is_empty() {
+                Some(self.output_unit.trim().to_string())
+            } else if self.output_scale == 1.0 {
+                channel.unit.as_deref().and_then(|u| mode.output_unit(u)).map(str::to_string)
+            } else {
+                None
+            };
+            frame.payload.insert(format!("{}{}", name, self.suffix.trim()), output);
+        }
+        Ok(frame)
+    }
+
+    async fn on_destroy(&mut self) -> Result<()> {
+        self.states.clear();
+        Ok(())
+    }
+}
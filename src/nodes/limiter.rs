@@ -0,0 +1,147 @@
+use crate::core::{ProcessingNode, DataFrame};
+use crate::dsp::dynamics::time_coefficient;
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// Per-channel lookahead state
+#[derive(Debug, Clone, Default)]
+struct LimiterChannel {
+    /// Monotonic deque of (sample index, required gain) for the sliding minimum
+    hold: VecDeque<(u64, f64)>,
+    /// Recent held gains averaged over the lookahead window
+    ramp: VecDeque<f64>,
+    ramp_sum: f64,
+    /// Input samples delayed by the lookahead
+    delay: VecDeque<f64>,
+    index: u64,
+    gain: f64,
+}
+
+/// LimiterNode keeps every channel below `threshold_db` (dBFS)
+///
+/// The signal is delayed by `lookahead_ms` while the required gain is
+/// min-held and ramped over the same window, so reduction is already in
+/// place when a peak reaches the output. Recovery follows `release_ms`.
+/// `makeup_gain_db` is applied after limiting. With zero lookahead the
+/// limiter clamps instantaneously.
+#[derive(StreamNode, Debug, Clone, Serialize, Deserialize)]
+#[node_meta(name = "Limiter", category = "Processors")]
+pub struct LimiterNode {
+    #[input(name = "Audio In", data_type = "audio_frame")]
+    _input: (),
+
+    #[output(name = "Audio Out", data_type = "audio_frame")]
+    _output: (),
+
+    #[param(default = "-1.0", min = -60.0, max = 0.0, unit = "dB", step = 0.1)]
+    pub threshold_db: f64,
+
+    #[param(default = "5.0", min = 0.0, max = 100.0, unit = "ms", step = 0.1)]
+    pub lookahead_ms: f64,
+
+    #[param(default = "50.0", min = 0.0, max = 10000.0, unit = "ms", log_scale)]
+    pub release_ms: f64,
+
+    #[param(default = "0.0", min = -40.0, max = 40.0, unit = "dB", step = 0.5)]
+    pub makeup_gain_db: f64,
+
+    #[serde(skip)]
+    channels: HashMap<String, LimiterChannel>,
+
+    #[serde(skip)]
+    sample_rate: f64,
+}
+
+impl Default for LimiterNode {
+    fn default() -> Self {
+        Self {
+            _input: (),
+            _output: (),
+            threshold_db: -1.0,
+            lookahead_ms: 5.0,
+            release_ms: 50.0,
+            makeup_gain_db: 0.0,
+            channels: HashMap::new(),
+            sample_rate: 0.0,
+        }
+    }
+}
+
+impl LimiterNode {
+    /// Lookahead in whole samples at the given rate
+    pub fn latency_samples(&self, sample_rate: f64) -> usize {
+        (self.lookahead_ms * sample_rate / 1000.0).round() as usize
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for LimiterNode {
+    async fn on_configured(&mut self, _config: &serde_json::Value) -> Result<()> {
+        self.channels.clear();
+        Ok(())
+    }
+
+    async fn process(&mut self, mut frame: DataFrame) -> Result<DataFrame> {
+        let sample_rate = frame.sample_rate().unwrap_or(48000.0);
+        if sample_rate != self.sample_rate {
+            self.channels.clear();
+            self.sample_rate = sample_rate;
+        }
+
+        let lookahead = self.latency_samples(sample_rate);
+        let ceiling = 10f64.powf(self.threshold_db / 20.0);
+        let makeup = 10f64.powf(self.makeup_gain_db / 20.0);
+        let release = time_coefficient(self.release_ms, sample_rate);
+
+        let mut max_reduction: f64 = 0.0;
+        for (channel, data) in frame.payload.iter_mut() {
+            let state = self.channels.entry(channel.clone()).or_insert_with(|| LimiterChannel {
+                delay: std::iter::repeat_n(0.0, lookahead).collect(),
+                gain: 1.0,
+                ..Default::default()
+            });
+
+            for sample in data.samples_mut().iter_mut() {
+                let x = *sample;
+                let required = if x.abs() > ceiling { ceiling / x.abs() } else { 1.0 };
+
+                // Minimum required gain over the last lookahead + 1 samples
+                while state.hold.back().is_some_and(|&(_, g)| g >= required) {
+                    state.hold.pop_back();
+                }
+                state.hold.push_back((state.index, required));
+                while state.hold.front().is_some_and(|&(i, _)| i + (lookahead as u64) < state.index) {
+                    state.hold.pop_front();
+                }
+                let held = state.hold.front().map(|&(_, g)| g).unwrap_or(1.0);
+                state.index += 1;
+
+                // Box-average the held gain so reduction ramps in over the lookahead
+                state.ramp.push_back(held);
+                state.ramp_sum += held;
+                if state.ramp.len() > lookahead.max(1) {
+                    state.ramp_sum -= state.ramp.pop_front().unwrap_or(0.0);
+                }
+                let target = (state.ramp_sum / state.ramp.len() as f64).min(1.0);
+
+                state.gain = if target < state.gain {
+                    target
+                } else {
+                    state.gain + release * (target - state.gain)
+                };
+
+                state.delay.push_back(x);
+                let delayed = state.delay.pop_front().unwrap_or(0.0);
+                max_reduction = max_reduction.max(-20.0 * state.gain.log10());
+                *sample = delayed * state.gain * makeup;
+            }
+        }
+
+        frame.metadata.insert("gain_reduction_db", max_reduction);
+        frame.metadata.insert("limiter_latency_samples", lookahead);
+        Ok(frame)
+    }
+}
@@ -0,0 +1,199 @@
+use crate::core::{Channel, DataFrame, ProcessingNode};
+use crate::dsp::Expression;
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use serde::{Deserialize, Serialize};
+
+/// Operation a MathNode computes per sample
+#[derive(Debug, Clone, PartialEq)]
+pub enum MathOperation {
+    /// Sum of the input channels
+    Sum,
+    /// First input minus the others
+    Difference,
+    /// Product of the input channels
+    Product,
+    /// Absolute value of one channel
+    Abs,
+    /// 20 * log10(|x| / db_reference) of one channel
+    Db,
+    /// User expression over channels named in it
+    Expression(Expression),
+}
+
+impl MathOperation {
+    pub fn parse(name: &str, expression: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "sum" => Ok(MathOperation::Sum),
+            "difference" => Ok(MathOperation::Difference),
+            "product" => Ok(MathOperation::Product),
+            "abs" => Ok(MathOperation::Abs),
+            "db" => Ok(MathOperation::Db),
+            "expression" => Ok(MathOperation::Expression(Expression::parse(expression)?)),
+            _ => anyhow::bail!("Unknown math operation: {}", name),
+        }
+    }
+}
+
+/// MathNode computes a channel from others, sample by sample
+///
+/// `operation` is one of `sum`, `difference` (first input minus the rest),
+/// `product`, `abs`, `db` (`20 * log10(|x| / db_reference)`) over the
+/// comma separated `inputs` channels, or `expression`, which evaluates the
+/// `expression` string with channel names as variables, e.g.
+/// `"(mic - ref) / 0.05"` for reference subtraction and sensitivity
+/// scaling (see `dsp::Expression` for the syntax). Without `inputs`, the
+/// arithmetic operations take every channel sorted by name and `abs`/`db`
+/// the only channel of the frame.
+///
+/// The result is multiplied by `scale` and written to channel `output`,
+/// which takes its sample rate and role from the first input; `db` output
+/// has unit `dB`. Input channels are kept unless `keep_inputs` is off.
+/// Inputs must have the same length.
+#[derive(StreamNode, Debug, Clone, Serialize, Deserialize)]
+#[node_meta(name = "Math", category = "Processors")]
+#[preset(name = "Reference subtraction", params = r#"{"operation": "difference", "inputs": "ch0,ch1", "output": "diff"}"#)]
+#[preset(name = "Level in dB SPL", params = r#"{"operation": "db", "db_reference": 0.00002, "output": "spl"}"#)]
+pub struct MathNode {
+    #[input(name = "Audio In", data_type = "audio_frame")]
+    _input: (),
+
+    #[output(name = "Audio Out", data_type = "audio_frame")]
+    _output: (),
+
+    #[param(default = "\"sum\"", choices = "sum,difference,product,abs,db,expression")]
+    pub operation: String,
+
+    #[param(default = "\"\"", skip_config)]
+    pub inputs: String,
+
+    #[param(default = "\"\"")]
+    pub expression: String,
+
+    #[param(default = "\"math\"")]
+    pub output: String,
+
+    #[param(default = "1.0")]
+    pub scale: f64,
+
+    #[param(default = "1.0", min = 1e-12, max = 1e6)]
+    pub db_reference: f64,
+
+    #[param(default = "true")]
+    pub keep_inputs: bool,
+
+    #[serde(skip)]
+    parsed: Option<MathOperation>,
+}
+
+impl Default for MathNode {
+    fn default() -> Self {
+        Self {
+            _input: (),
+            _output: (),
+            operation: "sum".to_string(),
+            inputs: String::new(),
+            expression: String::new(),
+            output: "math".to_string(),
+            scale: 1.0,
+            db_reference: 1.0,
+            keep_inputs: true,
+            parsed: None,
+        }
+    }
+}
+
+impl MathNode {
+    fn input_names(&self, frame: &DataFrame, operation: &MathOperation) -> Result<Vec<String>> {
+        if let MathOperation::Expression(expr) = operation {
+            if expr.variables().is_empty() {
+                anyhow::bail!("Expression '{}' reads no channels", self.expression);
+            }
+            return Ok(expr.variables().to_vec());
+        }
+        let names: Vec<String> = if self.inputs.trim().is_empty() {
+            let mut names: Vec<String> = frame.payload.keys().cloned().collect();
+            names.sort();
+            names
+        } else {
+            self.inputs.split(',').map(|c| c.trim().to_string()).filter(|c| !c.is_empty()).collect()
+        };
+        match operation {
+            MathOperation::Abs | MathOperation::Db if names.len() != 1 => {
+                anyhow::bail!("{} takes one channel, got {}", self.operation, names.len())
+            }
+            MathOperation::Difference if names.len() < 2 => {
+                anyhow::bail!("difference takes at least two channels, got {}", names.len())
+            }
+            _ if names.is_empty() => anyhow::bail!("No input channels"),
+            _ => Ok(names),
+        }
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for MathNode {
+    async fn on_configured(&mut self, config: &serde_json::Value) -> Result<()> {
+        if let Some(c) = config.get("inputs") {
+            // Accept either "ch0,ch1" or ["ch0", "ch1"]
+            if let Some(s) = c.as_str() {
+                self.inputs = s.to_string();
+            } else if let Some(list) = c.as_array() {
+                self.inputs = list
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .collect::<Vec<_>>()
+                    .join(",");
+            }
+        }
+        if self.output.trim().is_empty() {
+            anyhow::bail!("output channel name must not be empty");
+        }
+        self.parsed = Some(MathOperation::parse(&self.operation, &self.expression)?);
+        Ok(())
+    }
+
+    async fn process(&mut self, mut frame: DataFrame) -> Result<DataFrame> {
+        if self.parsed.is_none() {
+            self.parsed = Some(MathOperation::parse(&self.operation, &self.expression)?);
+        }
+        let operation = self.parsed.as_ref().expect("operation parsed above");
+        let names = self.input_names(&frame, operation)?;
+        let channels = names
+            .iter()
+            .map(|name| {
+                frame.payload.get(name).ok_or_else(|| anyhow::anyhow!("Channel '{}' is not in the frame", name))
+            })
+            .collect::<Result<Vec<&Channel>>>()?;
+        let len = channels[0].len();
+        if let Some(other) = names.iter().zip(&channels).find(|(_, c)| c.len() != len) {
+            anyhow::bail!("Channel '{}' has {} samples but '{}' has {}", other.0, other.1.len(), names[0], len);
+        }
+
+        let columns: Vec<&[f64]> = channels.iter().map(|c| c.samples()).collect();
+        let mut result: Vec<f64> = match operation {
+            MathOperation::Sum => (0..len).map(|i| columns.iter().map(|c| c[i]).sum()).collect(),
+            MathOperation::Difference => {
+                (0..len).map(|i| columns[0][i] - columns[1..].iter().map(|c| c[i]).sum::<f64>()).collect()
+            }
+            MathOperation::Product => (0..len).map(|i| columns.iter().map(|c| c[i]).product()).collect(),
+            MathOperation::Abs => columns[0].iter().map(|x| x.abs()).collect(),
+            MathOperation::Db => columns[0].iter().map(|x| 20.0 * (x.abs() / self.db_reference).log10()).collect(),
+            MathOperation::Expression(expr) => expr.eval_samples(&columns),
+        };
+        if self.scale != 1.0 {
+            result.iter_mut().for_each(|x| *x *= self.scale);
+        }
+
+        let mut output = channels[0].with_samples(result);
+        if *operation == MathOperation::Db {
+            output.unit = Some("dB".to_string());
+        }
+        if !self.keep_inputs {
+            frame.payload.clear();
+        }
+        frame.payload.insert(self.output.trim().to_string(), output);
+        Ok(frame)
+    }
+}
@@ -0,0 +1,159 @@
+use crate::core::{DataFrame, ProcessingNode};
+use crate::hal::drivers::MidiMessage;
+use crate::hal::{DeviceChannels, SampleData};
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use serde::{Deserialize, Serialize};
+
+/// MidiTriggerNode fires captures from an external MIDI controller
+///
+/// MIDI messages from the injected device (see `device_profile_id`) are
+/// drained on every frame. A note-on matching `midi_channel` (0 = any)
+/// and `note` (-1 = any) marks the frame with `midi_trigger`,
+/// `midi_note` and `midi_velocity` metadata. With `gate` enabled, frames
+/// between notes are emitted with an empty payload, so only triggered
+/// frames reach downstream captures.
+///
+/// The latest value of controller `cc_number` is attached to every frame
+/// as `midi_cc` (0.0-1.0) for parameter control.
+#[derive(StreamNode, Serialize, Deserialize)]
+#[node_meta(name = "MIDI Trigger", category = "Sources")]
+pub struct MidiTriggerNode {
+    #[input(name = "Audio In", data_type = "audio_frame")]
+    _input: (),
+
+    #[output(name = "Audio Out", data_type = "audio_frame")]
+    _output: (),
+
+    #[param(default = "\"\"")]
+    pub device_profile_id: String,
+
+    #[param(default = "0", min = 0.0, max = 16.0, step = 1.0)]
+    pub midi_channel: u32,
+
+    #[param(default = "-1", min = -1.0, max = 127.0, step = 1.0)]
+    pub note: i32,
+
+    #[param(default = "-1", min = -1.0, max = 127.0, step = 1.0)]
+    pub cc_number: i32,
+
+    #[param(default = "true")]
+    pub gate: bool,
+
+    #[serde(skip)]
+    device_channels: Option<DeviceChannels>,
+
+    #[serde(skip)]
+    cc_value: Option<f64>,
+
+    #[serde(skip)]
+    trigger_count: u64,
+}
+
+impl std::fmt::Debug for MidiTriggerNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MidiTriggerNode")
+            .field("device_profile_id", &self.device_profile_id)
+            .field("midi_channel", &self.midi_channel)
+            .field("note", &self.note)
+            .field("cc_number", &self.cc_number)
+            .field("gate", &self.gate)
+            .field("has_device", &self.device_channels.is_some())
+            .finish()
+    }
+}
+
+impl Clone for MidiTriggerNode {
+    fn clone(&self) -> Self {
+        Self {
+            _input: (),
+            _output: (),
+            device_profile_id: self.device_profile_id.clone(),
+            midi_channel: self.midi_channel,
+            note: self.note,
+            cc_number: self.cc_number,
+            gate: self.gate,
+            device_channels: None, // Don't clone device channels
+            cc_value: self.cc_value,
+            trigger_count: self.trigger_count,
+        }
+    }
+}
+
+impl Default for MidiTriggerNode {
+    fn default() -> Self {
+        Self {
+            _input: (),
+            _output: (),
+            device_profile_id: String::new(),
+            midi_channel: 0,
+            note: -1,
+            cc_number: -1,
+            gate: true,
+            device_channels: None,
+            cc_value: None,
+            trigger_count: 0,
+        }
+    }
+}
+
+impl MidiTriggerNode {
+    /// Set device channels of the MIDI input device
+    pub fn set_device_channels(&mut self, channels: Option<DeviceChannels>) {
+        self.device_channels = channels;
+    }
+
+    /// Note-on events that have fired a trigger
+    pub fn trigger_count(&self) -> u64 {
+        self.trigger_count
+    }
+
+    /// Drain pending MIDI packets, returning the last matching note-on
+    fn poll_messages(&mut self) -> Option<(u8, u8)> {
+        let channels = self.device_channels.as_ref()?;
+        let mut fired = None;
+        while let Ok(packet) = channels.filled_rx.try_recv() {
+            let message = match &packet.data {
+                SampleData::Bytes(bytes) => MidiMessage::parse(bytes),
+                _ => None,
+            };
+            let _ = channels.empty_tx.try_send(packet);
+
+            let Some(message) = message else { continue };
+            if self.midi_channel != 0 && message.channel() as u32 != self.midi_channel {
+                continue;
+            }
+            match message {
+                MidiMessage::NoteOn { note, velocity, .. } if self.note < 0 || note as i32 == self.note => {
+                    fired = Some((note, velocity));
+                }
+                MidiMessage::ControlChange { controller, value, .. } if controller as i32 == self.cc_number => {
+                    self.cc_value = Some(value as f64 / 127.0);
+                }
+                _ => {}
+            }
+        }
+        fired
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for MidiTriggerNode {
+    async fn process(&mut self, mut frame: DataFrame) -> Result<DataFrame> {
+        let fired = self.poll_messages();
+
+        frame.metadata.insert("midi_trigger", fired.is_some());
+        if let Some((note, velocity)) = fired {
+            self.trigger_count += 1;
+            frame.metadata.insert("midi_note", note as i64);
+            frame.metadata.insert("midi_velocity", velocity as f64 / 127.0);
+        } else if self.gate {
+            frame.payload.clear();
+        }
+        if let Some(value) = self.cc_value {
+            frame.metadata.insert("midi_cc", value);
+        }
+        Ok(frame)
+    }
+}
@@ -6,6 +6,42 @@ pub mod trigger_source;
 pub mod debug_sink;
 pub mod fft;
 pub mod filter;
+pub mod spl_meter;
+pub mod delay;
+pub mod cross_correlation;
+pub mod trigger_gate;
+pub mod envelope_follower;
+pub mod compressor;
+pub mod limiter;
+pub mod signal_detector;
+pub mod data_export;
+pub mod midi_trigger;
+pub mod averaging;
+pub mod decimator;
+pub mod order_analysis;
+pub mod cross_spectrum;
+pub mod beamformer;
+pub mod signal_generator;
+pub mod script;
+pub mod channel_router;
+pub mod frame_merge;
+pub mod splitter;
+pub mod annotate;
+pub mod math;
+pub mod integrator;
+pub mod convolution;
+pub mod adaptive_filter;
+pub mod pitch_tracker;
+pub mod feature_extractor;
+pub mod alert;
+pub mod signal_quality;
+pub mod raw_bytes;
+#[cfg(feature = "onnx")]
+pub mod inference;
+#[cfg(feature = "parquet")]
+pub mod capture_sink;
+#[cfg(feature = "plugin-host")]
+pub mod plugin_host;
 
 pub use gain_node::GainNode;
 pub use audio_source::AudioSourceNode;
@@ -15,3 +51,39 @@ pub use trigger_source::TriggerSourceNode;
 pub use debug_sink::DebugSinkNode;
 pub use fft::FFTNode;
 pub use filter::FilterNode;
+pub use spl_meter::SplMeterNode;
+pub use delay::DelayNode;
+pub use cross_correlation::CrossCorrelationNode;
+pub use trigger_gate::TriggerGateNode;
+pub use envelope_follower::EnvelopeFollowerNode;
+pub use compressor::CompressorNode;
+pub use limiter::LimiterNode;
+pub use signal_detector::{SignalDetectorNode, SignalEvent, SignalEventKind};
+pub use data_export::DataExportNode;
+pub use midi_trigger::MidiTriggerNode;
+pub use averaging::AveragingNode;
+pub use decimator::DecimatorNode;
+pub use order_analysis::OrderAnalysisNode;
+pub use cross_spectrum::CrossSpectrumNode;
+pub use beamformer::{ArrayGeometry, BeamformerNode};
+pub use signal_generator::SignalGeneratorNode;
+pub use script::ScriptNode;
+pub use channel_router::{ChannelRoute, ChannelRouterNode};
+pub use frame_merge::{FillPolicy, FrameMergeNode};
+pub use splitter::SplitterNode;
+pub use annotate::AnnotateNode;
+pub use math::{MathNode, MathOperation};
+pub use integrator::{IntegrationMode, IntegratorNode};
+pub use convolution::ConvolutionNode;
+pub use adaptive_filter::AdaptiveFilterNode;
+pub use pitch_tracker::PitchTrackerNode;
+pub use feature_extractor::FeatureExtractorNode;
+pub use alert::{AlertCondition, AlertNode};
+pub use signal_quality::{ChannelQuality, SignalQualityNode};
+pub use raw_bytes::RawBytesNode;
+#[cfg(feature = "onnx")]
+pub use inference::{InferenceNode, InputLayout};
+#[cfg(feature = "parquet")]
+pub use capture_sink::CaptureSinkNode;
+#[cfg(feature = "plugin-host")]
+pub use plugin_host::PluginHostNode;
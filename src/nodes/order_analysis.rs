@@ -0,0 +1,338 @@
+use crate::core::{ChannelRole, DataFrame, PortPairer, ProcessingNode};
+use crate::dsp::WindowType;
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use rustfft::{Fft, FftPlanner, num_complex::Complex};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+const SIGNAL_PORT: &str = "_signal";
+const TACHO_PORT: &str = "_tacho";
+
+/// How the tacho channel encodes shaft speed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TachoType {
+    /// Speed in revolutions per minute, at any rate (held between values)
+    Rpm,
+    /// Pulse train at the signal rate, `pulses_per_rev` rising edges per revolution
+    Pulse,
+}
+
+impl TachoType {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "rpm" => Ok(TachoType::Rpm),
+            "pulse" => Ok(TachoType::Pulse),
+            _ => anyhow::bail!("Unknown tacho type: {}", name),
+        }
+    }
+}
+
+/// OrderAnalysisNode computes order spectra of rotating machinery
+///
+/// The shaft angle is integrated from `tacho_channel`, and
+/// `signal_channel` is resampled by linear interpolation at
+/// `samples_per_rev` equally spaced angles (synchronous resampling), so
+/// components locked to the shaft stay in fixed bins as speed varies.
+/// Every `revolutions` revolutions the angle-domain block is windowed and
+/// transformed into single-sided amplitudes.
+///
+/// Output payload holds the spectrum under the signal channel's name, bin
+/// `k` being order `k / revolutions`, up to order `samples_per_rev / 2`.
+/// `order_resolution`, `max_order` and the block's mean `rpm` are set as
+/// metadata. Samples are skipped while the shaft turns slower than
+/// `min_rpm`, and frames without a complete block get an empty payload.
+///
+/// Signal and tacho may come in one frame, or the two inputs may be wired
+/// to separate sources, whose frames are paired by sequence id.
+#[derive(StreamNode, Clone, Serialize, Deserialize)]
+#[node_meta(name = "Order Analysis", category = "Processors")]
+pub struct OrderAnalysisNode {
+    #[input(name = "Signal In", data_type = "audio_frame")]
+    _signal: (),
+
+    #[input(name = "Tacho In", data_type = "audio_frame")]
+    _tacho: (),
+
+    #[output(name = "Orders Out", data_type = "fft_result")]
+    _output: (),
+
+    #[param(default = "\"ch0\"")]
+    pub signal_channel: String,
+
+    #[param(default = "\"ch1\"")]
+    pub tacho_channel: String,
+
+    #[param(default = "\"rpm\"", choices = "rpm,pulse")]
+    pub tacho_type: String,
+
+    #[param(default = "1", min = 1.0, max = 1000.0, step = 1.0)]
+    pub pulses_per_rev: u32,
+
+    #[param(default = "0.5", min = -1000000.0, max = 1000000.0)]
+    pub pulse_threshold: f64,
+
+    #[param(default = "64", min = 4.0, max = 4096.0, log_scale)]
+    pub samples_per_rev: usize,
+
+    #[param(default = "16", min = 1.0, max = 1024.0, step = 1.0)]
+    pub revolutions: usize,
+
+    #[param(default = "10.0", min = 0.0, max = 100000.0, unit = "rpm")]
+    pub min_rpm: f64,
+
+    #[param(default = "\"hann\"", choices = "rectangular,hann,hamming,blackman,flattop")]
+    pub window_type: String,
+
+    #[serde(skip)]
+    fft: Option<Arc<dyn Fft<f64>>>,
+
+    #[serde(skip)]
+    window: Vec<f64>,
+
+    /// Fraction of a revolution turned at the last sample
+    #[serde(skip)]
+    angle: f64,
+
+    /// Angle of the next resampled point, relative to `angle`'s revolution
+    #[serde(skip)]
+    next_angle: f64,
+
+    /// Signal value at the last sample
+    #[serde(skip)]
+    previous: Option<f64>,
+
+    /// Angle-domain samples not yet transformed
+    #[serde(skip)]
+    resampled: Vec<f64>,
+
+    /// Sum and count of the speeds behind `resampled`
+    #[serde(skip)]
+    rpm_total: (f64, u64),
+
+    /// Pulse edge detection: armed below threshold, samples since the
+    /// last edge, and the speed it gave
+    #[serde(skip)]
+    pulse_armed: bool,
+
+    #[serde(skip)]
+    since_pulse: Option<u64>,
+
+    #[serde(skip)]
+    pulse_rpm: f64,
+
+    #[serde(skip)]
+    pairs: PortPairer,
+}
+
+impl std::fmt::Debug for OrderAnalysisNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OrderAnalysisNode")
+            .field("signal_channel", &self.signal_channel)
+            .field("tacho_channel", &self.tacho_channel)
+            .field("tacho_type", &self.tacho_type)
+            .field("pulses_per_rev", &self.pulses_per_rev)
+            .field("samples_per_rev", &self.samples_per_rev)
+            .field("revolutions", &self.revolutions)
+            .field("window_type", &self.window_type)
+            .finish()
+    }
+}
+
+impl Default for OrderAnalysisNode {
+    fn default() -> Self {
+        Self {
+            _signal: (),
+            _tacho: (),
+            _output: (),
+            signal_channel: "ch0".to_string(),
+            tacho_channel: "ch1".to_string(),
+            tacho_type: "rpm".to_string(),
+            pulses_per_rev: 1,
+            pulse_threshold: 0.5,
+            samples_per_rev: 64,
+            revolutions: 16,
+            min_rpm: 10.0,
+            window_type: "hann".to_string(),
+            fft: None,
+            window: Vec::new(),
+            angle: 0.0,
+            next_angle: 0.0,
+            previous: None,
+            resampled: Vec::new(),
+            rpm_total: (0.0, 0),
+            pulse_armed: false,
+            since_pulse: None,
+            pulse_rpm: 0.0,
+            pairs: PortPairer::new(),
+        }
+    }
+}
+
+impl OrderAnalysisNode {
+    /// Validate parameters, plan the FFT and clear the resampling state
+    fn configure(&mut self) -> Result<()> {
+        TachoType::parse(&self.tacho_type)?;
+        if self.samples_per_rev < 2 {
+            anyhow::bail!("samples_per_rev must be at least 2, got {}", self.samples_per_rev);
+        }
+        if self.revolutions == 0 {
+            anyhow::bail!("revolutions must be at least 1");
+        }
+        let block = self.samples_per_rev * self.revolutions;
+        self.window = WindowType::parse(&self.window_type)?.coefficients(block);
+        self.fft = Some(FftPlanner::new().plan_fft_forward(block));
+        self.reset_state();
+        Ok(())
+    }
+
+    fn reset_state(&mut self) {
+        self.angle = 0.0;
+        self.next_angle = 0.0;
+        self.previous = None;
+        self.resampled.clear();
+        self.rpm_total = (0.0, 0);
+        self.pulse_armed = false;
+        self.since_pulse = None;
+        self.pulse_rpm = 0.0;
+        self.pairs.clear();
+    }
+
+    /// Shaft speed from one tacho pulse-train sample
+    fn pulse_speed(&mut self, level: f64, sample_rate: f64) -> f64 {
+        if let Some(since) = self.since_pulse.as_mut() {
+            *since += 1;
+        }
+        if self.pulse_armed && level >= self.pulse_threshold {
+            self.pulse_armed = false;
+            if let Some(period) = self.since_pulse {
+                self.pulse_rpm = 60.0 * sample_rate / (period as f64 * self.pulses_per_rev as f64);
+            }
+            self.since_pulse = Some(0);
+        } else if level < self.pulse_threshold {
+            self.pulse_armed = true;
+        }
+        // A shaft that stops sends no more edges: decay to the speed the
+        // current gap allows at most
+        match self.since_pulse {
+            Some(since) if since > 0 => {
+                let bound = 60.0 * sample_rate / (since as f64 * self.pulses_per_rev as f64);
+                self.pulse_rpm.min(bound)
+            }
+            _ => self.pulse_rpm,
+        }
+    }
+
+    /// Advance the shaft by one sample, emitting the angle-domain points it passes
+    fn advance(&mut self, x: f64, rpm: f64, sample_rate: f64) {
+        let Some(previous) = self.previous.replace(x) else { return };
+        if rpm < self.min_rpm.max(f64::MIN_POSITIVE) {
+            return;
+        }
+
+        let step = 1.0 / self.samples_per_rev as f64;
+        let new_angle = self.angle + rpm / 60.0 / sample_rate;
+        while self.next_angle <= new_angle {
+            let frac = (self.next_angle - self.angle) / (new_angle - self.angle);
+            self.resampled.push(previous + frac * (x - previous));
+            self.rpm_total.0 += rpm;
+            self.rpm_total.1 += 1;
+            self.next_angle += step;
+        }
+        self.angle = new_angle;
+
+        // Keep angles small so precision does not degrade over long runs
+        let whole = self.angle.floor();
+        self.angle -= whole;
+        self.next_angle -= whole;
+    }
+
+    /// Single-sided amplitude spectrum of one angle-domain block
+    fn order_spectrum(&self, fft: &Arc<dyn Fft<f64>>, block: &[f64]) -> Vec<f64> {
+        let mut bins: Vec<Complex<f64>> = block
+            .iter()
+            .zip(self.window.iter())
+            .map(|(&s, &w)| Complex::new(s * w, 0.0))
+            .collect();
+        fft.process(&mut bins);
+
+        let coherent_gain: f64 = self.window.iter().sum();
+        let n = block.len();
+        bins.iter()
+            .take(n / 2 + 1)
+            .enumerate()
+            .map(|(k, c)| {
+                let scale = if k == 0 || 2 * k == n { 1.0 } else { 2.0 };
+                scale * c.norm() / coherent_gain
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for OrderAnalysisNode {
+    async fn on_configured(&mut self, _config: &serde_json::Value) -> Result<()> {
+        self.configure()
+    }
+
+    async fn process(&mut self, frame: DataFrame) -> Result<DataFrame> {
+        if self.fft.is_none() {
+            self.configure()?;
+        }
+        let waiting = DataFrame::new(frame.timestamp, frame.sequence_id);
+        let ports = [(SIGNAL_PORT, self.signal_channel.as_str()), (TACHO_PORT, self.tacho_channel.as_str())];
+        let Some(mut frame) = self.pairs.push(frame, ports)? else {
+            return Ok(waiting);
+        };
+        let fft = self.fft.clone().expect("FFT plan configured");
+        let tacho_type = TachoType::parse(&self.tacho_type)?;
+        let sample_rate = frame.sample_rate().unwrap_or(48000.0);
+
+        let input = std::mem::take(&mut frame.payload);
+        let signal = input.get(&self.signal_channel).cloned().ok_or_else(|| {
+            anyhow::anyhow!("Missing signal channel '{}'", self.signal_channel)
+        })?;
+        let tacho = input.get(&self.tacho_channel).cloned().ok_or_else(|| {
+            anyhow::anyhow!("Missing tacho channel '{}'", self.tacho_channel)
+        })?;
+        if tacho_type == TachoType::Pulse && tacho.len() != signal.len() {
+            anyhow::bail!(
+                "Pulse tacho channel must match the signal length ({} samples), got {}",
+                signal.len(), tacho.len()
+            );
+        }
+
+        for (i, &x) in signal.iter().enumerate() {
+            let rpm = match tacho_type {
+                TachoType::Rpm if tacho.is_empty() => 0.0,
+                // Hold each speed value over its share of the frame
+                TachoType::Rpm => tacho[i * tacho.len() / signal.len()],
+                TachoType::Pulse => self.pulse_speed(tacho[i], sample_rate),
+            };
+            self.advance(x, rpm, sample_rate);
+        }
+
+        let block = self.samples_per_rev * self.revolutions;
+        let mut spectrum = None;
+        while self.resampled.len() >= block {
+            let samples: Vec<f64> = self.resampled.drain(..block).collect();
+            spectrum = Some(self.order_spectrum(&fft, &samples));
+            let (total, count) = std::mem::take(&mut self.rpm_total);
+            frame.metadata.insert("rpm", total / count.max(1) as f64);
+        }
+
+        if let Some(spectrum) = spectrum {
+            let channel = signal.with_samples(spectrum).with_role(ChannelRole::Spectrum);
+            frame.payload.insert(self.signal_channel.clone(), channel);
+        }
+        frame.metadata.insert("order_resolution", 1.0 / self.revolutions as f64);
+        frame.metadata.insert("max_order", self.samples_per_rev as f64 / 2.0);
+        Ok(frame)
+    }
+
+    async fn on_destroy(&mut self) -> Result<()> {
+        self.reset_state();
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+use crate::core::{Channel, ChannelRole, DataFrame, ProcessingNode};
+use crate::dsp::PitchTracker;
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Buffered samples and estimator of one channel
+#[derive(Debug, Clone)]
+struct ChannelTracker {
+    tracker: PitchTracker,
+    buffer: Vec<f64>,
+}
+
+/// PitchTrackerNode follows the fundamental frequency of each channel
+///
+/// Samples are buffered into blocks of `window_size`, advancing by
+/// `hop_size`, and each block is analysed with the YIN estimator (see
+/// `dsp::PitchTracker`) for a fundamental between `min_frequency` and
+/// `max_frequency`; the lower bound is also limited to two periods per
+/// block. `threshold` is the YIN dip threshold: lower values reject more
+/// noisy blocks, higher values accept weaker periodicity.
+///
+/// For every analysed channel `chN` (the comma separated `channels`, or
+/// every signal channel when empty) the output payload holds `chN_pitch`
+/// in Hz and `chN_confidence` (0-1), one value per block completed by the
+/// frame at a sample rate of `sample_rate / hop_size`. Blocks without a
+/// clear pitch report 0 Hz. Frames that complete no block get an empty
+/// payload.
+#[derive(StreamNode, Debug, Clone, Serialize, Deserialize)]
+#[node_meta(name = "Pitch Tracker", category = "Processors")]
+#[preset(name = "Voice and instruments", params = r#"{"min_frequency": 60.0, "max_frequency": 1500.0, "window_size": 2048}"#)]
+#[preset(name = "Machinery fundamental", params = r#"{"min_frequency": 10.0, "max_frequency": 500.0, "window_size": 16384, "hop_size": 4096}"#)]
+pub struct PitchTrackerNode {
+    #[input(name = "Audio In", data_type = "audio_frame")]
+    _input: (),
+
+    #[output(name = "Pitch Out", data_type = "pitch")]
+    _output: (),
+
+    #[param(default = "\"\"", skip_config)]
+    pub channels: String,
+
+    #[param(default = "2048", min = 64.0, max = 65536.0, unit = "samples", log_scale)]
+    pub window_size: usize,
+
+    #[param(default = "512", min = 1.0, max = 65536.0, unit = "samples", log_scale)]
+    pub hop_size: usize,
+
+    #[param(default = "50.0", min = 1.0, max = 20000.0, unit = "Hz", log_scale)]
+    pub min_frequency: f64,
+
+    #[param(default = "2000.0", min = 1.0, max = 20000.0, unit = "Hz", log_scale)]
+    pub max_frequency: f64,
+
+    #[param(default = "0.15", min = 0.01, max = 1.0, step = 0.01)]
+    pub threshold: f64,
+
+    #[serde(skip)]
+    trackers: HashMap<String, ChannelTracker>,
+}
+
+impl Default for PitchTrackerNode {
+    fn default() -> Self {
+        Self {
+            _input: (),
+            _output: (),
+            channels: String::new(),
+            window_size: 2048,
+            hop_size: 512,
+            min_frequency: 50.0,
+            max_frequency: 2000.0,
+            threshold: 0.15,
+            trackers: HashMap::new(),
+        }
+    }
+}
+
+impl PitchTrackerNode {
+    fn channel_names(&self, frame: &DataFrame) -> Result<Vec<String>> {
+        if self.channels.trim().is_empty() {
+            let mut names: Vec<String> = frame
+                .payload
+                .iter()
+                .filter(|(_, c)| c.role == ChannelRole::Signal)
+                .map(|(name, _)| name.clone())
+                .collect();
+            names.sort();
+            return Ok(names);
+        }
+        let names: Vec<String> =
+            self.channels.split(',').map(|c| c.trim().to_string()).filter(|c| !c.is_empty()).collect();
+        if let Some(missing) = names.iter().find(|name| !frame.payload.contains_key(*name)) {
+            anyhow::bail!("Channel '{}' is not in the frame", missing);
+        }
+        Ok(names)
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for PitchTrackerNode {
+    async fn on_configured(&mut self, config: &serde_json::Value) -> Result<()> {
+        if let Some(c) = config.get("channels") {
+            // Accept either "ch0,ch1" or ["ch0", "ch1"]
+            if let Some(s) = c.as_str() {
+                self.channels = s.to_string();
+            } else if let Some(list) = c.as_array() {
+                self.channels = list
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .collect::<Vec<_>>()
+                    .join(",");
+            }
+        }
+        if self.window_size < 4 {
+            anyhow::bail!("window_size must be at least 4, got {}", self.window_size);
+        }
+        if self.hop_size == 0 || self.hop_size > self.window_size {
+            anyhow::bail!("hop_size must be between 1 and window_size, got {}", self.hop_size);
+        }
+        if !(self.min_frequency > 0.0 && self.min_frequency < self.max_frequency) {
+            anyhow::bail!(
+                "Frequency range {} - {} Hz is empty",
+                self.min_frequency,
+                self.max_frequency
+            );
+        }
+        self.trackers.clear();
+        Ok(())
+    }
+
+    async fn process(&mut self, mut frame: DataFrame) -> Result<DataFrame> {
+        let names = self.channel_names(&frame)?;
+        let input = std::mem::take(&mut frame.payload);
+        for name in names {
+            let sample_rate = input[&name].sample_rate.or_else(|| frame.sample_rate()).unwrap_or(48000.0);
+            let (min_frequency, max_frequency, threshold) = (self.min_frequency, self.max_frequency, self.threshold);
+            let state = self.trackers.entry(name.clone()).or_insert_with(|| ChannelTracker {
+                tracker: PitchTracker::new(min_frequency, max_frequency, threshold),
+                buffer: Vec::new(),
+            });
+            state.buffer.extend_from_slice(input[&name].samples());
+
+            let (mut pitch, mut confidence) = (Vec::new(), Vec::new());
+            while state.buffer.len() >= self.window_size {
+                let estimate = state.tracker.detect(&state.buffer[..self.window_size], sample_rate);
+                pitch.push(estimate.frequency);
+                confidence.push(estimate.confidence);
+                state.buffer.drain(..self.hop_size);
+            }
+            if pitch.is_empty() {
+                continue;
+            }
+
+            let block_rate = Some(sample_rate / self.hop_size as f64);
+            let mut pitch = Channel::new(pitch).with_role(ChannelRole::Measurement).with_unit("Hz");
+            pitch.sample_rate = block_rate;
+            let mut confidence = Channel::new(confidence).with_role(ChannelRole::Measurement);
+            confidence.sample_rate = block_rate;
+            frame.insert_channel(format!("{}_pitch", name), pitch);
+            frame.insert_channel(format!("{}_confidence", name), confidence);
+        }
+        Ok(frame)
+    }
+
+    async fn on_destroy(&mut self) -> Result<()> {
+        self.trackers.clear();
+        Ok(())
+    }
+}
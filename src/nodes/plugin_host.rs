@@ -0,0 +1,836 @@
+use crate::core::{DataFrame, ProcessingNode};
+use crate::registry::ParameterSchema;
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use clap_sys::entry::clap_plugin_entry;
+use clap_sys::events::{
+    clap_event_header, clap_event_param_value, clap_input_events, clap_output_events, CLAP_CORE_EVENT_SPACE_ID,
+    CLAP_EVENT_PARAM_VALUE,
+};
+use clap_sys::ext::audio_ports::{clap_audio_port_info, clap_plugin_audio_ports, CLAP_AUDIO_PORT_SUPPORTS_64BITS, CLAP_EXT_AUDIO_PORTS};
+use clap_sys::ext::params::{clap_param_info, clap_plugin_params, CLAP_EXT_PARAMS, CLAP_PARAM_IS_HIDDEN, CLAP_PARAM_IS_READONLY, CLAP_PARAM_IS_STEPPED};
+use clap_sys::factory::plugin_factory::{clap_plugin_factory, CLAP_PLUGIN_FACTORY_ID};
+use clap_sys::host::clap_host;
+use clap_sys::id::clap_id;
+use clap_sys::plugin::clap_plugin;
+use clap_sys::process::{clap_process, CLAP_PROCESS_ERROR};
+use clap_sys::audio_buffer::clap_audio_buffer;
+use clap_sys::version::{clap_version_is_compatible, CLAP_VERSION};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::path::{Path, PathBuf};
+
+/// Sample rate used when neither the channels nor the frame carry one
+const DEFAULT_SAMPLE_RATE: f64 = 48000.0;
+
+/// Smallest block size a plugin is activated for; longer frames reactivate it
+const MIN_BLOCK_SIZE: u32 = 4096;
+
+unsafe extern "C" fn host_get_extension(_host: *const clap_host, _id: *const c_char) -> *const c_void {
+    std::ptr::null()
+}
+
+// The node drives activation and processing itself, so requests are ignored
+unsafe extern "C" fn host_request(_host: *const clap_host) {}
+
+fn new_host() -> Box<clap_host> {
+    Box::new(clap_host {
+        clap_version: CLAP_VERSION,
+        host_data: std::ptr::null_mut(),
+        name: c"audiotab".as_ptr(),
+        vendor: c"audiotab".as_ptr(),
+        url: c"".as_ptr(),
+        version: c"0.1.0".as_ptr(),
+        get_extension: Some(host_get_extension),
+        request_restart: Some(host_request),
+        request_process: Some(host_request),
+        request_callback: Some(host_request),
+    })
+}
+
+unsafe extern "C" fn input_events_size(list: *const clap_input_events) -> u32 {
+    (*((*list).ctx as *const Vec<clap_event_param_value>)).len() as u32
+}
+
+unsafe extern "C" fn input_events_get(list: *const clap_input_events, index: u32) -> *const clap_event_header {
+    let events = &*((*list).ctx as *const Vec<clap_event_param_value>);
+    events.get(index as usize).map_or(std::ptr::null(), |e| &e.header)
+}
+
+// Output events (parameter changes made by the plugin itself) are dropped
+unsafe extern "C" fn output_events_push(_list: *const clap_output_events, _event: *const clap_event_header) -> bool {
+    true
+}
+
+fn c_text(chars: &[c_char]) -> String {
+    let bytes: Vec<u8> = chars.iter().take_while(|&&c| c != 0).map(|&c| c as u8).collect();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// A loaded CLAP library, initialized through its entry point
+struct ClapLibrary {
+    entry: *const clap_plugin_entry,
+    /// Keeps the code behind `entry` mapped; `None` for plugins linked into the host
+    _library: Option<libloading::Library>,
+}
+
+impl ClapLibrary {
+    fn load(path: &Path) -> Result<Self> {
+        if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("vst3")) {
+            bail!("VST3 plugins are not supported, use the CLAP version of {}", path.display());
+        }
+        // macOS bundles keep the binary under Contents/MacOS
+        let binary = if path.is_dir() {
+            let stem = path.file_stem().ok_or_else(|| anyhow!("Invalid plugin path {}", path.display()))?;
+            path.join("Contents").join("MacOS").join(stem)
+        } else {
+            path.to_path_buf()
+        };
+        // Loading runs the library's initializers; plugins are trusted code
+        let library = unsafe { libloading::Library::new(&binary) }
+            .with_context(|| format!("Failed to load plugin {}", path.display()))?;
+        let entry = unsafe {
+            *library
+                .get::<*const clap_plugin_entry>(b"clap_entry\0")
+                .with_context(|| format!("{} is not a CLAP plugin", path.display()))?
+        };
+        unsafe { Self::from_entry(entry, path, Some(library)) }
+    }
+
+    unsafe fn from_entry(entry: *const clap_plugin_entry, path: &Path, library: Option<libloading::Library>) -> Result<Self> {
+        if entry.is_null() || !clap_version_is_compatible((*entry).clap_version) {
+            bail!("{} was built for an incompatible CLAP version", path.display());
+        }
+        let init = (*entry).init.ok_or_else(|| anyhow!("{} has no CLAP entry point", path.display()))?;
+        let c_path = CString::new(path.to_string_lossy().as_bytes())?;
+        if !init(c_path.as_ptr()) {
+            bail!("{} failed to initialize", path.display());
+        }
+        Ok(Self { entry, _library: library })
+    }
+
+    fn factory(&self) -> Result<&clap_plugin_factory> {
+        let factory = unsafe { (*self.entry).get_factory.map(|get| get(CLAP_PLUGIN_FACTORY_ID.as_ptr())) }
+            .unwrap_or(std::ptr::null());
+        if factory.is_null() {
+            bail!("Plugin library has no plugin factory");
+        }
+        Ok(unsafe { &*(factory as *const clap_plugin_factory) })
+    }
+}
+
+impl Drop for ClapLibrary {
+    fn drop(&mut self) {
+        if let Some(deinit) = unsafe { (*self.entry).deinit } {
+            unsafe { deinit() };
+        }
+    }
+}
+
+/// An automatable plugin parameter
+#[derive(Debug, Clone)]
+struct ClapParam {
+    id: clap_id,
+    name: String,
+    min: f64,
+    max: f64,
+    default: f64,
+    stepped: bool,
+}
+
+/// An audio bus: its channel count and whether it takes 64-bit samples
+#[derive(Debug, Clone, Copy)]
+struct ClapPort {
+    channels: usize,
+    double: bool,
+}
+
+/// Sample storage for one bus during a process call
+struct PortBuffers {
+    single: Vec<Vec<f32>>,
+    double: Vec<Vec<f64>>,
+    pointers32: Vec<*mut f32>,
+    pointers64: Vec<*mut f64>,
+}
+
+impl PortBuffers {
+    fn new(port: ClapPort, len: usize) -> Self {
+        let (mut single, mut double) = (Vec::new(), Vec::new());
+        if port.double {
+            double = vec![vec![0.0; len]; port.channels];
+        } else {
+            single = vec![vec![0.0; len]; port.channels];
+        }
+        let pointers32 = single.iter_mut().map(|c| c.as_mut_ptr()).collect();
+        let pointers64 = double.iter_mut().map(|c| c.as_mut_ptr()).collect();
+        Self { single, double, pointers32, pointers64 }
+    }
+
+    fn write(&mut self, channel: usize, samples: &[f64]) {
+        if let Some(c) = self.double.get_mut(channel) {
+            c[..samples.len()].copy_from_slice(samples);
+        } else if let Some(c) = self.single.get_mut(channel) {
+            c.iter_mut().zip(samples).for_each(|(d, &s)| *d = s as f32);
+        }
+    }
+
+    fn read(&self, channel: usize) -> Vec<f64> {
+        match self.double.get(channel) {
+            Some(c) => c.clone(),
+            None => self.single[channel].iter().map(|&s| s as f64).collect(),
+        }
+    }
+
+    fn buffer(&mut self) -> clap_audio_buffer {
+        clap_audio_buffer {
+            data32: if self.pointers32.is_empty() { std::ptr::null_mut() } else { self.pointers32.as_mut_ptr() },
+            data64: if self.pointers64.is_empty() { std::ptr::null_mut() } else { self.pointers64.as_mut_ptr() },
+            channel_count: (self.single.len() + self.double.len()) as u32,
+            latency: 0,
+            constant_mask: 0,
+        }
+    }
+}
+
+/// A created plugin instance with its buses and parameters
+struct ClapInstance {
+    plugin: *const clap_plugin,
+    /// Must outlive `plugin`, which keeps a pointer to it
+    _host: Box<clap_host>,
+    name: String,
+    inputs: Vec<ClapPort>,
+    outputs: Vec<ClapPort>,
+    params: Vec<ClapParam>,
+    /// Sample rate and largest block the plugin is activated for
+    active: Option<(f64, u32)>,
+    /// Parameter changes delivered with the next process call
+    pending: Vec<clap_event_param_value>,
+    steady_time: i64,
+    /// Dropped last, after the plugin is destroyed
+    _library: ClapLibrary,
+}
+
+// A plugin instance is only used by the node that owns it, one call at a time
+unsafe impl Send for ClapInstance {}
+unsafe impl Sync for ClapInstance {}
+
+impl ClapInstance {
+    /// Create `plugin_id` from the library, or its first plugin when `plugin_id` is empty
+    fn new(library: ClapLibrary, plugin_id: &str) -> Result<Self> {
+        let factory = library.factory()?;
+        let count = unsafe { factory.get_plugin_count.map_or(0, |f| f(factory)) };
+        let descriptors: Vec<_> = (0..count)
+            .filter_map(|i| unsafe { factory.get_plugin_descriptor.map(|f| f(factory, i)) })
+            .filter(|d| !d.is_null())
+            .collect();
+        let id_of = |d: &*const clap_sys::plugin::clap_plugin_descriptor| unsafe { CStr::from_ptr((**d).id) }.to_string_lossy().into_owned();
+        let descriptor = match plugin_id {
+            "" => descriptors.first(),
+            id => descriptors.iter().find(|d| id_of(d) == id),
+        }
+        .ok_or_else(|| {
+            let ids: Vec<String> = descriptors.iter().map(id_of).collect();
+            anyhow!("Plugin '{}' not found, available: {}", plugin_id, ids.join(", "))
+        })?;
+        let name = unsafe { CStr::from_ptr((**descriptor).name) }.to_string_lossy().into_owned();
+
+        let host = new_host();
+        let create = factory.create_plugin.ok_or_else(|| anyhow!("Plugin factory cannot create plugins"))?;
+        let plugin = unsafe { create(factory, &*host, (**descriptor).id) };
+        if plugin.is_null() {
+            bail!("Failed to create plugin '{}'", name);
+        }
+        let mut instance = Self {
+            plugin,
+            _host: host,
+            name,
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            params: Vec::new(),
+            active: None,
+            pending: Vec::new(),
+            steady_time: 0,
+            _library: library,
+        };
+        if !unsafe { instance.call(|p| p.init.is_some_and(|f| f(p))) } {
+            bail!("Plugin '{}' failed to initialize", instance.name);
+        }
+        instance.inputs = instance.ports(true);
+        instance.outputs = instance.ports(false);
+        if instance.outputs.is_empty() {
+            bail!("Plugin '{}' has no audio outputs", instance.name);
+        }
+        instance.params = instance.scan_params();
+        Ok(instance)
+    }
+
+    unsafe fn call<T>(&self, f: impl FnOnce(&clap_plugin) -> T) -> T {
+        f(&*self.plugin)
+    }
+
+    fn extension<T>(&self, id: &CStr) -> Option<&T> {
+        let ext = unsafe { self.call(|p| p.get_extension.map(|f| f(p, id.as_ptr()))) }?;
+        (!ext.is_null()).then(|| unsafe { &*(ext as *const T) })
+    }
+
+    fn ports(&self, is_input: bool) -> Vec<ClapPort> {
+        let Some(ext) = self.extension::<clap_plugin_audio_ports>(CLAP_EXT_AUDIO_PORTS) else {
+            return Vec::new();
+        };
+        let (Some(count), Some(get)) = (ext.count, ext.get) else {
+            return Vec::new();
+        };
+        (0..unsafe { count(self.plugin, is_input) })
+            .filter_map(|i| {
+                let mut info: clap_audio_port_info = unsafe { std::mem::zeroed() };
+                unsafe { get(self.plugin, i, is_input, &mut info) }.then_some(ClapPort {
+                    channels: info.channel_count as usize,
+                    double: info.flags & CLAP_AUDIO_PORT_SUPPORTS_64BITS != 0,
+                })
+            })
+            .collect()
+    }
+
+    fn scan_params(&self) -> Vec<ClapParam> {
+        let Some(ext) = self.extension::<clap_plugin_params>(CLAP_EXT_PARAMS) else {
+            return Vec::new();
+        };
+        let (Some(count), Some(get_info)) = (ext.count, ext.get_info) else {
+            return Vec::new();
+        };
+        (0..unsafe { count(self.plugin) })
+            .filter_map(|i| {
+                let mut info: clap_param_info = unsafe { std::mem::zeroed() };
+                unsafe { get_info(self.plugin, i, &mut info) }.then_some(info)
+            })
+            .filter(|info| info.flags & (CLAP_PARAM_IS_HIDDEN | CLAP_PARAM_IS_READONLY) == 0)
+            .map(|info| ClapParam {
+                id: info.id,
+                name: c_text(&info.name),
+                min: info.min_value,
+                max: info.max_value,
+                default: info.default_value,
+                stepped: info.flags & CLAP_PARAM_IS_STEPPED != 0,
+            })
+            .collect()
+    }
+
+    /// Queue a parameter change, by name (case-insensitive) or numeric id
+    fn set_param(&mut self, key: &str, value: f64) -> Result<()> {
+        let param = self
+            .params
+            .iter()
+            .find(|p| p.name.eq_ignore_ascii_case(key) || p.id.to_string() == key)
+            .ok_or_else(|| anyhow!("Plugin '{}' has no parameter '{}'", self.name, key))?;
+        if !(param.min..=param.max).contains(&value) {
+            bail!("Parameter '{}' must be between {} and {}, got {}", param.name, param.min, param.max, value);
+        }
+        self.pending.retain(|e| e.param_id != param.id);
+        self.pending.push(clap_event_param_value {
+            header: clap_event_header {
+                size: std::mem::size_of::<clap_event_param_value>() as u32,
+                time: 0,
+                space_id: CLAP_CORE_EVENT_SPACE_ID,
+                type_: CLAP_EVENT_PARAM_VALUE,
+                flags: 0,
+            },
+            param_id: param.id,
+            cookie: std::ptr::null_mut(),
+            note_id: -1,
+            port_index: -1,
+            channel: -1,
+            key: -1,
+            value,
+        });
+        Ok(())
+    }
+
+    fn deactivate(&mut self) {
+        if self.active.take().is_some() {
+            unsafe {
+                self.call(|p| p.stop_processing.map(|f| f(p)));
+                self.call(|p| p.deactivate.map(|f| f(p)));
+            }
+        }
+    }
+
+    /// (Re)activate when the sample rate changes or a frame exceeds the block size
+    fn activate(&mut self, sample_rate: f64, len: usize) -> Result<()> {
+        if let Some((rate, max_frames)) = self.active {
+            if rate == sample_rate && len <= max_frames as usize {
+                return Ok(());
+            }
+        }
+        self.deactivate();
+        let max_frames = (len as u32).max(MIN_BLOCK_SIZE);
+        let activated = unsafe { self.call(|p| p.activate.is_some_and(|f| f(p, sample_rate, 1, max_frames))) };
+        if !activated {
+            bail!("Plugin '{}' failed to activate at {} Hz", self.name, sample_rate);
+        }
+        if !unsafe { self.call(|p| p.start_processing.is_none_or(|f| f(p))) } {
+            unsafe { self.call(|p| p.deactivate.map(|f| f(p))) };
+            bail!("Plugin '{}' failed to start processing", self.name);
+        }
+        self.active = Some((sample_rate, max_frames));
+        Ok(())
+    }
+
+    /// Run one block: `inputs` fill the input buses' channels in order,
+    /// and every output bus channel is returned in order
+    fn process(&mut self, inputs: &[&[f64]], len: usize, sample_rate: f64) -> Result<Vec<Vec<f64>>> {
+        self.activate(sample_rate, len)?;
+
+        let mut input_buffers: Vec<PortBuffers> = self.inputs.iter().map(|&p| PortBuffers::new(p, len)).collect();
+        let mut output_buffers: Vec<PortBuffers> = self.outputs.iter().map(|&p| PortBuffers::new(p, len)).collect();
+        let slots = self.inputs.iter().enumerate().flat_map(|(i, port)| (0..port.channels).map(move |c| (i, c)));
+        for ((port, channel), samples) in slots.zip(inputs) {
+            input_buffers[port].write(channel, samples);
+        }
+        let audio_inputs: Vec<clap_audio_buffer> = input_buffers.iter_mut().map(|p| p.buffer()).collect();
+        let mut audio_outputs: Vec<clap_audio_buffer> = output_buffers.iter_mut().map(|p| p.buffer()).collect();
+
+        let in_events = clap_input_events {
+            ctx: &self.pending as *const Vec<clap_event_param_value> as *mut c_void,
+            size: Some(input_events_size),
+            get: Some(input_events_get),
+        };
+        let out_events = clap_output_events { ctx: std::ptr::null_mut(), try_push: Some(output_events_push) };
+        let process = clap_process {
+            steady_time: self.steady_time,
+            frames_count: len as u32,
+            transport: std::ptr::null(),
+            audio_inputs: audio_inputs.as_ptr(),
+            audio_outputs: audio_outputs.as_mut_ptr(),
+            audio_inputs_count: audio_inputs.len() as u32,
+            audio_outputs_count: audio_outputs.len() as u32,
+            in_events: &in_events,
+            out_events: &out_events,
+        };
+        let status = unsafe { self.call(|p| p.process.map_or(CLAP_PROCESS_ERROR, |f| f(p, &process))) };
+        if status == CLAP_PROCESS_ERROR {
+            bail!("Plugin '{}' failed to process a block", self.name);
+        }
+        self.pending.clear();
+        self.steady_time += len as i64;
+
+        Ok(output_buffers
+            .iter()
+            .zip(&self.outputs)
+            .flat_map(|(buffers, port)| (0..port.channels).map(move |c| buffers.read(c)))
+            .collect())
+    }
+}
+
+impl Drop for ClapInstance {
+    fn drop(&mut self) {
+        self.deactivate();
+        unsafe { self.call(|p| p.destroy.map(|f| f(p))) };
+    }
+}
+
+/// PluginHostNode runs frames through a CLAP effect or analysis plugin
+///
+/// `path` names a .clap file (or macOS .clap bundle) and `plugin_id` one of
+/// its plugins, by default the first. `channels` lists the frame channels fed to the plugin's input buses, in
+/// bus order (default: all channels, sorted by name); buses with more
+/// channels than given receive silence. Output bus channels are written back
+/// under the names in `outputs`, defaulting to the input channel names, with
+/// further outputs named `out<n>`. The plugin is (re)activated at the first
+/// channel's sample rate.
+///
+/// Plugin parameters are set by name or id in a `plugin_params` config
+/// object, e.g. `{"plugin_params": {"Gain": -6.0}}`; `parameters()` lists
+/// them once the plugin is loaded. VST3 plugins are not supported.
+#[derive(StreamNode, Serialize, Deserialize, Default)]
+#[node_meta(name = "Plugin Host", category = "Processors")]
+pub struct PluginHostNode {
+    #[input(name = "In", data_type = "audio_frame")]
+    _input: (),
+
+    #[output(name = "Out", data_type = "audio_frame")]
+    _output: (),
+
+    #[param(default = "\"\"")]
+    pub path: String,
+
+    #[param(default = "\"\"")]
+    pub plugin_id: String,
+
+    #[param(default = "\"\"")]
+    pub channels: String,
+
+    #[param(default = "\"\"")]
+    pub outputs: String,
+
+    #[serde(default)]
+    pub plugin_params: HashMap<String, f64>,
+
+    #[serde(skip)]
+    instance: Option<ClapInstance>,
+
+    /// Path and plugin id `instance` was created from
+    #[serde(skip)]
+    loaded: Option<(PathBuf, String)>,
+}
+
+impl PluginHostNode {
+    /// Parameters of the loaded plugin, empty before `on_create` loads one
+    pub fn parameters(&self) -> Vec<ParameterSchema> {
+        let Some(instance) = &self.instance else {
+            return Vec::new();
+        };
+        instance
+            .params
+            .iter()
+            .map(|p| ParameterSchema {
+                name: p.name.clone(),
+                param_type: "number".to_string(),
+                default: serde_json::json!(p.default),
+                min: Some(p.min),
+                max: Some(p.max),
+                choices: None,
+                unit: None,
+                step: p.stepped.then_some(1.0),
+                scale: None,
+            })
+            .collect()
+    }
+
+    /// Load the plugin unless it is already loaded, then queue `plugin_params`
+    fn load(&mut self) -> Result<()> {
+        if self.path.is_empty() {
+            bail!("PluginHostNode requires a plugin path");
+        }
+        let source = (PathBuf::from(&self.path), self.plugin_id.clone());
+        if self.loaded.as_ref() != Some(&source) {
+            self.instance = None;
+            let library = ClapLibrary::load(&source.0)?;
+            self.instance = Some(ClapInstance::new(library, &source.1)?);
+            self.loaded = Some(source);
+        }
+        let instance = self.instance.as_mut().expect("plugin loaded");
+        for (key, value) in &self.plugin_params {
+            instance.set_param(key, *value)?;
+        }
+        Ok(())
+    }
+
+    fn names(list: &str) -> Vec<String> {
+        list.split(',').map(str::trim).filter(|s| !s.is_empty()).map(String::from).collect()
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for PluginHostNode {
+    async fn on_configured(&mut self, config: &serde_json::Value) -> Result<()> {
+        if let Some(params) = config.get("plugin_params").filter(|v| !v.is_null()) {
+            let params = params.as_object().ok_or_else(|| anyhow!("plugin_params must be an object"))?;
+            self.plugin_params = params
+                .iter()
+                .map(|(k, v)| {
+                    let value = v.as_f64().ok_or_else(|| anyhow!("Plugin parameter '{}' must be a number", k))?;
+                    Ok((k.clone(), value))
+                })
+                .collect::<Result<_>>()?;
+        }
+        if self.path.is_empty() {
+            return Ok(());
+        }
+        self.load()
+    }
+
+    async fn process(&mut self, mut frame: DataFrame) -> Result<DataFrame> {
+        if self.instance.is_none() {
+            self.load()?;
+        }
+
+        let mut inputs = Self::names(&self.channels);
+        if inputs.is_empty() {
+            inputs = frame.payload.keys().cloned().collect();
+            inputs.sort();
+        }
+        let mut samples = Vec::with_capacity(inputs.len());
+        for name in &inputs {
+            let channel = frame.payload.get(name).ok_or_else(|| anyhow!("Channel '{}' not found", name))?;
+            samples.push(channel.samples());
+        }
+        let len = samples.first().map_or(0, |s| s.len());
+        if samples.iter().any(|s| s.len() != len) {
+            bail!("PluginHostNode channels must have the same length");
+        }
+        if len == 0 {
+            return Ok(frame);
+        }
+        let sample_rate = inputs
+            .first()
+            .and_then(|name| frame.channel_sample_rate(name))
+            .unwrap_or(DEFAULT_SAMPLE_RATE);
+
+        let instance = self.instance.as_mut().expect("plugin loaded");
+        let outputs = instance.process(&samples, len, sample_rate)?;
+
+        let mut names = Self::names(&self.outputs);
+        if names.is_empty() {
+            names = inputs;
+        }
+        for (i, out) in outputs.into_iter().enumerate() {
+            let name = names.get(i).cloned().unwrap_or_else(|| format!("out{}", i));
+            let channel = match frame.payload.get(&name) {
+                Some(existing) => existing.with_samples(out),
+                None => crate::core::Channel::new(out).with_sample_rate(sample_rate),
+            };
+            frame.insert_channel(name, channel);
+        }
+        Ok(frame)
+    }
+
+    async fn on_destroy(&mut self) -> Result<()> {
+        self.instance = None;
+        self.loaded = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap_sys::ext::audio_ports::CLAP_AUDIO_PORT_IS_MAIN;
+    use clap_sys::plugin::clap_plugin_descriptor;
+    use clap_sys::process::{clap_process_status, CLAP_PROCESS_CONTINUE};
+    use serde_json::json;
+
+    /// Fake plugin: stereo in, a stereo output scaled by "Gain" and a mono output with their sum
+    struct FakeState {
+        gain: f64,
+        sample_rate: f64,
+    }
+
+    const GAIN_ID: clap_id = 7;
+
+    static DESCRIPTOR: clap_plugin_descriptor = clap_plugin_descriptor {
+        clap_version: CLAP_VERSION,
+        id: c"com.example.gain".as_ptr(),
+        name: c"Fake Gain".as_ptr(),
+        vendor: c"".as_ptr(),
+        url: c"".as_ptr(),
+        manual_url: c"".as_ptr(),
+        support_url: c"".as_ptr(),
+        version: c"1.0".as_ptr(),
+        description: c"".as_ptr(),
+        features: std::ptr::null(),
+    };
+
+    unsafe fn state<'a>(plugin: *const clap_plugin) -> &'a mut FakeState {
+        &mut *((*plugin).plugin_data as *mut FakeState)
+    }
+
+    unsafe extern "C" fn ports_count(_plugin: *const clap_plugin, is_input: bool) -> u32 {
+        if is_input { 1 } else { 2 }
+    }
+
+    unsafe extern "C" fn ports_get(_plugin: *const clap_plugin, index: u32, is_input: bool, info: *mut clap_audio_port_info) -> bool {
+        (*info).id = index;
+        (*info).flags = if index == 0 { CLAP_AUDIO_PORT_IS_MAIN } else { 0 };
+        (*info).channel_count = if is_input || index == 0 { 2 } else { 1 };
+        (*info).in_place_pair = u32::MAX;
+        true
+    }
+
+    static AUDIO_PORTS: clap_plugin_audio_ports = clap_plugin_audio_ports { count: Some(ports_count), get: Some(ports_get) };
+
+    unsafe extern "C" fn params_count(_plugin: *const clap_plugin) -> u32 {
+        1
+    }
+
+    unsafe extern "C" fn params_get_info(_plugin: *const clap_plugin, _index: u32, info: *mut clap_param_info) -> bool {
+        (*info).id = GAIN_ID;
+        for (d, s) in (*info).name.iter_mut().zip(b"Gain\0") {
+            *d = *s as c_char;
+        }
+        (*info).min_value = -1.0;
+        (*info).max_value = 4.0;
+        (*info).default_value = 1.0;
+        true
+    }
+
+    static PARAMS: clap_plugin_params = clap_plugin_params {
+        count: Some(params_count),
+        get_info: Some(params_get_info),
+        get_value: None,
+        value_to_text: None,
+        text_to_value: None,
+        flush: None,
+    };
+
+    unsafe extern "C" fn get_extension(_plugin: *const clap_plugin, id: *const c_char) -> *const c_void {
+        match CStr::from_ptr(id) {
+            id if id == CLAP_EXT_AUDIO_PORTS => &AUDIO_PORTS as *const _ as *const c_void,
+            id if id == CLAP_EXT_PARAMS => &PARAMS as *const _ as *const c_void,
+            _ => std::ptr::null(),
+        }
+    }
+
+    unsafe extern "C" fn init(_plugin: *const clap_plugin) -> bool {
+        true
+    }
+
+    unsafe extern "C" fn destroy(plugin: *const clap_plugin) {
+        drop(Box::from_raw((*plugin).plugin_data as *mut FakeState));
+        drop(Box::from_raw(plugin as *mut clap_plugin));
+    }
+
+    unsafe extern "C" fn activate(plugin: *const clap_plugin, sample_rate: f64, _min: u32, _max: u32) -> bool {
+        state(plugin).sample_rate = sample_rate;
+        true
+    }
+
+    unsafe extern "C" fn process(plugin: *const clap_plugin, process: *const clap_process) -> clap_process_status {
+        let process = &*process;
+        let state = state(plugin);
+        let events = &*process.in_events;
+        for i in 0..events.size.unwrap()(events) {
+            let header = events.get.unwrap()(events, i);
+            if (*header).type_ == CLAP_EVENT_PARAM_VALUE {
+                let event = &*(header as *const clap_event_param_value);
+                assert_eq!(event.param_id, GAIN_ID);
+                state.gain = event.value;
+            }
+        }
+
+        let len = process.frames_count as usize;
+        let input = &*process.audio_inputs;
+        let outputs = std::slice::from_raw_parts(process.audio_outputs, 2);
+        let left = std::slice::from_raw_parts(*input.data32, len);
+        let right = std::slice::from_raw_parts(*input.data32.add(1), len);
+        for (c, samples) in [left, right].into_iter().enumerate() {
+            let out = std::slice::from_raw_parts_mut(*outputs[0].data32.add(c), len);
+            out.iter_mut().zip(samples).for_each(|(o, &s)| *o = s * state.gain as f32);
+        }
+        let sum = std::slice::from_raw_parts_mut(*outputs[1].data32, len);
+        for i in 0..len {
+            sum[i] = left[i] + right[i];
+        }
+        CLAP_PROCESS_CONTINUE
+    }
+
+    unsafe extern "C" fn create_plugin(_factory: *const clap_plugin_factory, _host: *const clap_host, id: *const c_char) -> *const clap_plugin {
+        assert_eq!(CStr::from_ptr(id), c"com.example.gain");
+        let data = Box::into_raw(Box::new(FakeState { gain: 1.0, sample_rate: 0.0 }));
+        Box::into_raw(Box::new(clap_plugin {
+            desc: &DESCRIPTOR,
+            plugin_data: data as *mut c_void,
+            init: Some(init),
+            destroy: Some(destroy),
+            activate: Some(activate),
+            deactivate: None,
+            start_processing: None,
+            stop_processing: None,
+            reset: None,
+            process: Some(process),
+            get_extension: Some(get_extension),
+            on_main_thread: None,
+        }))
+    }
+
+    unsafe extern "C" fn plugin_count(_factory: *const clap_plugin_factory) -> u32 {
+        1
+    }
+
+    unsafe extern "C" fn plugin_descriptor(_factory: *const clap_plugin_factory, _index: u32) -> *const clap_plugin_descriptor {
+        &DESCRIPTOR
+    }
+
+    static FACTORY: clap_plugin_factory = clap_plugin_factory {
+        get_plugin_count: Some(plugin_count),
+        get_plugin_descriptor: Some(plugin_descriptor),
+        create_plugin: Some(create_plugin),
+    };
+
+    unsafe extern "C" fn entry_init(_path: *const c_char) -> bool {
+        true
+    }
+
+    unsafe extern "C" fn get_factory(id: *const c_char) -> *const c_void {
+        if CStr::from_ptr(id) == CLAP_PLUGIN_FACTORY_ID {
+            &FACTORY as *const _ as *const c_void
+        } else {
+            std::ptr::null()
+        }
+    }
+
+    static ENTRY: clap_plugin_entry = clap_plugin_entry {
+        clap_version: CLAP_VERSION,
+        init: Some(entry_init),
+        deinit: None,
+        get_factory: Some(get_factory),
+    };
+
+    fn instance(plugin_id: &str) -> Result<ClapInstance> {
+        let library = unsafe { ClapLibrary::from_entry(&ENTRY, Path::new("fake.clap"), None)? };
+        ClapInstance::new(library, plugin_id)
+    }
+
+    fn sample_rate(node: &PluginHostNode) -> f64 {
+        let instance = node.instance.as_ref().unwrap();
+        unsafe { state(instance.plugin).sample_rate }
+    }
+
+    /// Node with the fake plugin already loaded from "fake.clap"
+    fn node() -> PluginHostNode {
+        PluginHostNode {
+            instance: Some(instance("").unwrap()),
+            loaded: Some((PathBuf::from("fake.clap"), String::new())),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_maps_channels_to_buses_and_sets_parameters() {
+        let mut node = node();
+        node.on_create(json!({"path": "fake.clap", "plugin_params": {"gain": 2.0}})).await.unwrap();
+
+        let params = node.parameters();
+        assert_eq!(params.len(), 1);
+        assert_eq!(params[0].name, "Gain");
+        assert_eq!((params[0].min, params[0].max), (Some(-1.0), Some(4.0)));
+
+        let mut frame = DataFrame::new(0, 0);
+        frame.insert_channel("left", crate::core::Channel::new(vec![1.0, 2.0]).with_unit("V").with_sample_rate(44100.0));
+        frame.insert_channel("right", vec![3.0, 4.0]);
+        let out = node.process(frame).await.unwrap();
+
+        assert_eq!(sample_rate(&node), 44100.0);
+        assert_eq!(out.payload["left"].samples(), &[2.0, 4.0]);
+        assert_eq!(out.payload["left"].unit.as_deref(), Some("V"));
+        assert_eq!(out.payload["right"].samples(), &[6.0, 8.0]);
+        // The second output bus has no input counterpart
+        assert_eq!(out.payload["out2"].samples(), &[4.0, 6.0]);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unknown_plugins_and_parameters() {
+        let err = instance("com.example.missing").err().unwrap();
+        assert!(err.to_string().contains("available: com.example.gain"), "{}", err);
+
+        let mut node = node();
+        let err = node.on_create(json!({"path": "fake.clap", "plugin_params": {"Gain": 10.0}})).await.unwrap_err();
+        assert!(err.to_string().contains("between -1 and 4"), "{}", err);
+        let err = node.on_create(json!({"path": "fake.clap", "plugin_params": {"Mix": 0.5}})).await.unwrap_err();
+        assert!(err.to_string().contains("no parameter 'Mix'"), "{}", err);
+
+        // Parameters can also be addressed by id, and the channel list selects the inputs
+        node.on_create(json!({"path": "fake.clap", "channels": "b,a", "outputs": "x,y,sum", "plugin_params": {"7": 0.5}}))
+            .await
+            .unwrap();
+        let mut frame = DataFrame::new(0, 0);
+        frame.insert_channel("a", vec![1.0]);
+        frame.insert_channel("b", vec![3.0]);
+        let out = node.process(frame).await.unwrap();
+        assert_eq!(out.payload["x"].samples(), &[1.5]);
+        assert_eq!(out.payload["y"].samples(), &[0.5]);
+        assert_eq!(out.payload["sum"].samples(), &[4.0]);
+        assert_eq!(sample_rate(&node), DEFAULT_SAMPLE_RATE);
+    }
+}
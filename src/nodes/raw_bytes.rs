@@ -0,0 +1,141 @@
+use crate::core::{Channel, ChannelRole, DataFrame, ProcessingNode};
+use crate::hal::{DeviceChannels, SampleData};
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use serde::{Deserialize, Serialize};
+
+/// RawBytesNode passes a device's undecoded byte stream into the pipeline
+///
+/// Packets from the injected device (see `device_profile_id`) are drained
+/// on every frame, whether or not the device has a codec. Their
+/// `SampleData::Bytes` data is appended to the frame, in arrival order, as
+/// the `channel` channel with one control sample (0.0-255.0) per byte, and
+/// the count is set as `byte_count` metadata. Packets of other formats are
+/// skipped.
+///
+/// At most `max_bytes` are emitted per frame; older bytes beyond that are
+/// dropped and counted in `dropped_bytes` metadata.
+#[derive(StreamNode, Serialize, Deserialize)]
+#[node_meta(name = "Raw Bytes", category = "Sources")]
+pub struct RawBytesNode {
+    #[input(name = "Audio In", data_type = "audio_frame")]
+    _input: (),
+
+    #[output(name = "Audio Out", data_type = "audio_frame")]
+    _output: (),
+
+    #[param(default = "\"\"")]
+    pub device_profile_id: String,
+
+    #[param(default = "\"bytes\"")]
+    pub channel: String,
+
+    #[param(default = "65536", min = 1.0, max = 16777216.0, unit = "bytes")]
+    pub max_bytes: usize,
+
+    #[serde(skip)]
+    device_channels: Option<DeviceChannels>,
+
+    #[serde(skip)]
+    total_bytes: u64,
+}
+
+impl std::fmt::Debug for RawBytesNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RawBytesNode")
+            .field("device_profile_id", &self.device_profile_id)
+            .field("channel", &self.channel)
+            .field("max_bytes", &self.max_bytes)
+            .field("has_device", &self.device_channels.is_some())
+            .finish()
+    }
+}
+
+impl Clone for RawBytesNode {
+    fn clone(&self) -> Self {
+        Self {
+            _input: (),
+            _output: (),
+            device_profile_id: self.device_profile_id.clone(),
+            channel: self.channel.clone(),
+            max_bytes: self.max_bytes,
+            device_channels: None, // Don't clone device channels
+            total_bytes: self.total_bytes,
+        }
+    }
+}
+
+impl Default for RawBytesNode {
+    fn default() -> Self {
+        Self {
+            _input: (),
+            _output: (),
+            device_profile_id: String::new(),
+            channel: "bytes".to_string(),
+            max_bytes: 65536,
+            device_channels: None,
+            total_bytes: 0,
+        }
+    }
+}
+
+impl RawBytesNode {
+    /// Set device channels of the byte-producing device
+    pub fn set_device_channels(&mut self, channels: Option<DeviceChannels>) {
+        self.device_channels = channels;
+    }
+
+    /// Bytes received since the node was created
+    pub fn total_bytes(&self) -> u64 {
+        self.total_bytes
+    }
+
+    /// Drain pending packets, returning their bytes in arrival order
+    fn poll_bytes(&mut self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let Some(channels) = self.device_channels.as_ref() else {
+            return bytes;
+        };
+        while let Ok(packet) = channels.filled_rx.try_recv() {
+            if let SampleData::Bytes(data) = &packet.data {
+                bytes.extend_from_slice(data);
+            }
+            let _ = channels.empty_tx.try_send(packet);
+        }
+        self.total_bytes += bytes.len() as u64;
+        bytes
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for RawBytesNode {
+    async fn on_configured(&mut self, _config: &serde_json::Value) -> Result<()> {
+        if self.channel.trim().is_empty() {
+            anyhow::bail!("channel must not be empty");
+        }
+        if self.max_bytes == 0 {
+            anyhow::bail!("max_bytes must be at least 1");
+        }
+        Ok(())
+    }
+
+    async fn process(&mut self, mut frame: DataFrame) -> Result<DataFrame> {
+        let mut bytes = self.poll_bytes();
+        let dropped = bytes.len().saturating_sub(self.max_bytes);
+        if dropped > 0 {
+            bytes.drain(..dropped);
+            frame.metadata.insert("dropped_bytes", dropped as i64);
+        }
+
+        frame.metadata.insert("byte_count", bytes.len() as i64);
+        let samples: Vec<f64> = bytes.into_iter().map(f64::from).collect();
+        frame.insert_channel(self.channel.clone(), Channel::from(samples).with_role(ChannelRole::Control));
+        Ok(frame)
+    }
+
+    async fn on_destroy(&mut self) -> Result<()> {
+        self.device_channels = None;
+        Ok(())
+    }
+}
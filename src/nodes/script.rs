@@ -0,0 +1,256 @@
+use crate::core::{DataFrame, MetadataValue, ProcessingNode};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Map, Scope, AST};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Longest string a script may build, in bytes
+const MAX_STRING_SIZE: usize = 1 << 20;
+
+/// Most elements a script array or map may hold
+const MAX_COLLECTION_SIZE: usize = 1 << 22;
+
+/// Deepest function call nesting a script may reach
+const MAX_CALL_LEVELS: usize = 32;
+
+/// The frame as seen by a script, bound to the `frame` variable
+#[derive(Clone)]
+struct ScriptFrame(DataFrame);
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+impl ScriptFrame {
+    fn channel(&mut self, name: &str) -> ScriptResult<Array> {
+        let channel = self.0.payload.get(name).ok_or_else(|| format!("No channel '{}'", name))?;
+        Ok(channel.samples().iter().map(|&s| Dynamic::from_float(s)).collect())
+    }
+
+    /// Replace or add a channel; a replaced channel keeps its sample rate, unit and role
+    fn set_channel(&mut self, name: &str, samples: Array) -> ScriptResult<()> {
+        let samples = samples
+            .into_iter()
+            .map(|value| {
+                value
+                    .as_float()
+                    .or_else(|_| value.as_int().map(|i| i as f64))
+                    .map_err(|t| format!("Channel '{}' must hold numbers, found {}", name, t).into())
+            })
+            .collect::<ScriptResult<Vec<f64>>>()?;
+        let channel = match self.0.payload.get(name) {
+            Some(existing) => existing.with_samples(samples),
+            None => samples.into(),
+        };
+        self.0.insert_channel(name, channel);
+        Ok(())
+    }
+
+    fn meta(&mut self, key: &str) -> Dynamic {
+        match self.0.metadata.get(key) {
+            Some(MetadataValue::Bool(v)) => Dynamic::from_bool(*v),
+            Some(MetadataValue::Int(v)) => Dynamic::from_int(*v),
+            Some(MetadataValue::Float(v)) => Dynamic::from_float(*v),
+            Some(MetadataValue::Text(v)) => Dynamic::from(v.clone()),
+            None => Dynamic::UNIT,
+        }
+    }
+
+    fn set_meta(&mut self, key: &str, value: Dynamic) -> ScriptResult<()> {
+        let value: MetadataValue = if let Ok(v) = value.as_bool() {
+            v.into()
+        } else if let Ok(v) = value.as_int() {
+            v.into()
+        } else if let Ok(v) = value.as_float() {
+            v.into()
+        } else if let Ok(v) = value.into_immutable_string() {
+            v.as_str().into()
+        } else {
+            return Err(format!("Metadata '{}' must be a bool, number or string", key).into());
+        };
+        self.0.metadata.insert(key, value);
+        Ok(())
+    }
+}
+
+fn numbers(samples: &Array) -> impl Iterator<Item = f64> + '_ {
+    samples
+        .iter()
+        .filter_map(|v| v.as_float().ok().or_else(|| v.as_int().ok().map(|i| i as f64)))
+}
+
+/// Sandboxed Rhai engine with per-frame operation and time limits
+fn script_engine(max_operations: u64, deadline: Arc<AtomicU64>, epoch: Instant) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(max_operations);
+    engine.set_max_string_size(MAX_STRING_SIZE);
+    engine.set_max_array_size(MAX_COLLECTION_SIZE);
+    engine.set_max_map_size(MAX_COLLECTION_SIZE);
+    engine.set_max_call_levels(MAX_CALL_LEVELS);
+    engine.disable_symbol("eval");
+    engine.on_print(|_| {});
+    engine.on_debug(|text, _, _| tracing::info!("Script debug: {}", text));
+    engine.on_progress(move |_| {
+        let elapsed = epoch.elapsed().as_nanos() as u64;
+        (elapsed > deadline.load(Ordering::Relaxed)).then(|| Dynamic::from("time budget exceeded"))
+    });
+
+    engine
+        .register_type_with_name::<ScriptFrame>("Frame")
+        .register_fn("channel", ScriptFrame::channel)
+        .register_fn("set_channel", ScriptFrame::set_channel)
+        .register_fn("has_channel", |f: &mut ScriptFrame, name: &str| f.0.payload.contains_key(name))
+        .register_fn("remove_channel", |f: &mut ScriptFrame, name: &str| {
+            f.0.payload.remove(name);
+        })
+        .register_fn("meta", ScriptFrame::meta)
+        .register_fn("set_meta", ScriptFrame::set_meta)
+        .register_get("channels", |f: &mut ScriptFrame| {
+            let mut names: Vec<&String> = f.0.payload.keys().collect();
+            names.sort();
+            names.into_iter().map(|n| Dynamic::from(n.clone())).collect::<Array>()
+        })
+        .register_get("sample_rate", |f: &mut ScriptFrame| f.0.sample_rate().map_or(Dynamic::UNIT, Dynamic::from_float))
+        .register_get("sequence_id", |f: &mut ScriptFrame| f.0.sequence_id as i64)
+        .register_get("timestamp", |f: &mut ScriptFrame| f.0.timestamp as i64);
+
+    engine
+        .register_fn("mean", |samples: Array| {
+            if samples.is_empty() { 0.0 } else { numbers(&samples).sum::<f64>() / samples.len() as f64 }
+        })
+        .register_fn("rms", |samples: Array| {
+            if samples.is_empty() { 0.0 } else { (numbers(&samples).map(|s| s * s).sum::<f64>() / samples.len() as f64).sqrt() }
+        })
+        .register_fn("peak", |samples: Array| numbers(&samples).fold(0.0, |peak: f64, s| peak.max(s.abs())));
+
+    engine
+}
+
+/// Compiled script and the state it keeps between frames
+struct ScriptRuntime {
+    engine: Engine,
+    ast: AST,
+    /// Variables the script leaves at top level, plus `state`, persist across frames
+    scope: Scope<'static>,
+    /// Deadline of the current run, in nanoseconds since `epoch`
+    deadline: Arc<AtomicU64>,
+    epoch: Instant,
+}
+
+/// ScriptNode runs a user-written Rhai script on every frame
+///
+/// The script sees the frame as `frame`:
+/// `frame.channel("ch0")` returns a channel's samples as an array,
+/// `frame.set_channel(name, samples)` writes one (keeping an existing
+/// channel's sample rate and unit), and `frame.meta(key)` /
+/// `frame.set_meta(key, value)` read and write metadata. `frame.channels`,
+/// `frame.sample_rate`, `frame.sequence_id` and `frame.timestamp` are
+/// read-only. `mean`, `rms` and `peak` summarize an array, and the Rhai
+/// standard library (math, strings, arrays) is available.
+///
+/// `state` is a map that persists between frames, e.g. for running totals.
+/// A script returning `false` drops the frame's channels, so downstream
+/// nodes receive an empty frame.
+///
+/// Scripts are sandboxed: no file, network or process access, and no
+/// `eval`. Each frame may take at most `max_operations` script operations
+/// and `time_budget_ms` of wall time; a script over budget fails the frame.
+#[derive(StreamNode, Serialize, Deserialize)]
+#[node_meta(name = "Script", category = "Processors")]
+#[preset(name = "Invert polarity", params = r#"{"script": "for name in frame.channels { frame.set_channel(name, frame.channel(name).map(|s| -s)); }"}"#)]
+#[preset(name = "Channel RMS", params = r#"{"script": "for name in frame.channels { frame.set_meta(name + \"_rms\", rms(frame.channel(name))); }"}"#)]
+pub struct ScriptNode {
+    #[input(name = "In", data_type = "audio_frame")]
+    _input: (),
+
+    #[output(name = "Out", data_type = "audio_frame")]
+    _output: (),
+
+    #[param(default = "\"\"")]
+    pub script: String,
+
+    #[param(default = "1000000", min = 1000.0, max = 1000000000.0, log_scale)]
+    pub max_operations: u64,
+
+    #[param(default = "10.0", min = 0.1, max = 10000.0, unit = "ms", log_scale)]
+    pub time_budget_ms: f64,
+
+    #[serde(skip)]
+    runtime: Option<ScriptRuntime>,
+}
+
+impl Default for ScriptNode {
+    fn default() -> Self {
+        Self {
+            _input: (),
+            _output: (),
+            script: String::new(),
+            max_operations: 1_000_000,
+            time_budget_ms: 10.0,
+            runtime: None,
+        }
+    }
+}
+
+impl ScriptNode {
+    /// Compile the script; syntax errors fail here rather than on the first frame
+    fn compile(&mut self) -> Result<()> {
+        if self.max_operations == 0 {
+            anyhow::bail!("max_operations must be positive");
+        }
+        if self.time_budget_ms.is_nan() || self.time_budget_ms <= 0.0 {
+            anyhow::bail!("time_budget_ms must be positive, got {}", self.time_budget_ms);
+        }
+
+        let deadline = Arc::new(AtomicU64::new(u64::MAX));
+        let epoch = Instant::now();
+        let engine = script_engine(self.max_operations, deadline.clone(), epoch);
+        let ast = engine.compile(&self.script).map_err(|e| anyhow!("Script error: {}", e))?;
+
+        let mut scope = Scope::new();
+        scope.push("state", Map::new());
+        self.runtime = Some(ScriptRuntime { engine, ast, scope, deadline, epoch });
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for ScriptNode {
+    async fn on_configured(&mut self, _config: &serde_json::Value) -> Result<()> {
+        self.compile()
+    }
+
+    async fn process(&mut self, frame: DataFrame) -> Result<DataFrame> {
+        if self.runtime.is_none() {
+            self.compile()?;
+        }
+        let budget = Duration::from_secs_f64(self.time_budget_ms / 1000.0);
+        let runtime = self.runtime.as_mut().expect("script compiled");
+
+        let deadline = runtime.epoch.elapsed() + budget;
+        runtime.deadline.store(deadline.as_nanos() as u64, Ordering::Relaxed);
+        runtime.scope.set_value("frame", ScriptFrame(frame));
+        let result = runtime.engine.eval_ast_with_scope::<Dynamic>(&mut runtime.scope, &runtime.ast);
+
+        let ScriptFrame(mut frame) = runtime
+            .scope
+            .remove::<ScriptFrame>("frame")
+            .expect("frame stays in scope");
+        let keep = result.map_err(|e| match *e {
+            // Stopped by the time budget
+            EvalAltResult::ErrorTerminated(reason, _) => anyhow!("Script error: {}", reason),
+            e => anyhow!("Script error: {}", e),
+        })?;
+        if keep.as_bool() == Ok(false) {
+            frame.payload.clear();
+        }
+        Ok(frame)
+    }
+
+    async fn on_destroy(&mut self) -> Result<()> {
+        self.runtime = None;
+        Ok(())
+    }
+}
@@ -0,0 +1,152 @@
+use crate::core::{ProcessingNode, DataFrame};
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+/// Transition reported by SignalDetectorNode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalEventKind {
+    SignalStart,
+    SignalStop,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignalEvent {
+    pub kind: SignalEventKind,
+    pub timestamp: u64,
+    pub sequence_id: u64,
+    pub rms_db: f64,
+}
+
+/// SignalDetectorNode classifies frames as signal or silence
+///
+/// The frame level is the highest per-channel RMS in dBFS. The state only
+/// flips to "signal" after the level stays at or above `threshold_db` for
+/// `min_signal_ms`, and back to "silence" after it stays below for
+/// `min_silence_ms`. Every frame is annotated with `signal_present` and
+/// `signal_rms_db` metadata; transitions also set `signal_event` and are
+/// published to subscribers.
+#[derive(StreamNode, Debug, Clone, Serialize, Deserialize)]
+#[node_meta(name = "Signal Detector", category = "Processors")]
+pub struct SignalDetectorNode {
+    #[input(name = "Audio In", data_type = "audio_frame")]
+    _input: (),
+
+    #[output(name = "Audio Out", data_type = "audio_frame")]
+    _output: (),
+
+    #[param(default = "-50.0", min = -200.0, max = 0.0, unit = "dB", step = 1.0)]
+    pub threshold_db: f64,
+
+    #[param(default = "50.0", min = 0.0, max = 60000.0, unit = "ms")]
+    pub min_signal_ms: f64,
+
+    #[param(default = "500.0", min = 0.0, max = 600000.0, unit = "ms")]
+    pub min_silence_ms: f64,
+
+    #[serde(skip)]
+    present: bool,
+
+    #[serde(skip)]
+    pending_ms: f64,
+
+    #[serde(skip)]
+    events: Option<broadcast::Sender<SignalEvent>>,
+}
+
+impl Default for SignalDetectorNode {
+    fn default() -> Self {
+        Self {
+            _input: (),
+            _output: (),
+            threshold_db: -50.0,
+            min_signal_ms: 50.0,
+            min_silence_ms: 500.0,
+            present: false,
+            pending_ms: 0.0,
+            events: None,
+        }
+    }
+}
+
+impl SignalDetectorNode {
+    /// Subscribe to signal start/stop transitions
+    pub fn subscribe(&mut self) -> broadcast::Receiver<SignalEvent> {
+        self.events
+            .get_or_insert_with(|| broadcast::channel(64).0)
+            .subscribe()
+    }
+
+    /// Whether the detector currently reports signal
+    pub fn is_signal_present(&self) -> bool {
+        self.present
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for SignalDetectorNode {
+    async fn on_configured(&mut self, _config: &serde_json::Value) -> Result<()> {
+        self.present = false;
+        self.pending_ms = 0.0;
+        Ok(())
+    }
+
+    async fn process(&mut self, mut frame: DataFrame) -> Result<DataFrame> {
+        let sample_rate = frame.sample_rate().unwrap_or(48000.0);
+
+        let mut frame_len = 0;
+        let mut max_ms: f64 = 0.0;
+        for samples in frame.payload.values() {
+            if samples.is_empty() {
+                continue;
+            }
+            frame_len = frame_len.max(samples.len());
+            let ms = samples.iter().map(|s| s * s).sum::<f64>() / samples.len() as f64;
+            max_ms = max_ms.max(ms);
+        }
+        let rms_db = 10.0 * max_ms.max(1e-30).log10();
+        let duration_ms = frame_len as f64 * 1000.0 / sample_rate;
+
+        let above = rms_db >= self.threshold_db;
+        let mut event = None;
+        if above != self.present {
+            self.pending_ms += duration_ms;
+            let required = if above { self.min_signal_ms } else { self.min_silence_ms };
+            if self.pending_ms >= required {
+                self.present = above;
+                self.pending_ms = 0.0;
+                event = Some(if above {
+                    SignalEventKind::SignalStart
+                } else {
+                    SignalEventKind::SignalStop
+                });
+            }
+        } else {
+            self.pending_ms = 0.0;
+        }
+
+        if let Some(kind) = event {
+            let name = match kind {
+                SignalEventKind::SignalStart => "start",
+                SignalEventKind::SignalStop => "stop",
+            };
+            frame.metadata.insert("signal_event", name);
+            if let Some(tx) = &self.events {
+                // No subscribers is not an error
+                let _ = tx.send(SignalEvent {
+                    kind,
+                    timestamp: frame.timestamp,
+                    sequence_id: frame.sequence_id,
+                    rms_db,
+                });
+            }
+        }
+        frame.metadata.insert("signal_present", self.present);
+        frame.metadata.insert("signal_rms_db", rms_db);
+
+        Ok(frame)
+    }
+}
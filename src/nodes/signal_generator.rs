@@ -0,0 +1,227 @@
+use crate::buffers::FramePool;
+use crate::core::{DataFrame, ProcessingNode};
+use crate::dsp::{Multitone, Oscillator, ToneSpacing, Waveform};
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use serde::{Deserialize, Serialize};
+
+/// Samples per period of the multitone table (0.73 Hz tone spacing at 48 kHz)
+const MULTITONE_PERIOD: usize = 1 << 16;
+
+/// Running waveform source
+#[derive(Debug, Clone)]
+enum Generator {
+    Tone(Oscillator),
+    Multitone(Multitone),
+}
+
+impl Generator {
+    fn next_sample(&mut self) -> f64 {
+        match self {
+            Generator::Tone(osc) => osc.next_sample(),
+            Generator::Multitone(multi) => multi.next_sample(),
+        }
+    }
+}
+
+/// SignalGeneratorNode synthesizes test signals sample by sample
+///
+/// `waveform` selects a single tone at `frequency` (`sine`, `square` with
+/// `duty_cycle`, `triangle`, `saw`) or a `multitone` of `tone_count`
+/// sines between `start_frequency` and `stop_frequency` (`tone_spacing`
+/// log or linear), whose phases are chosen to approach `crest_factor`
+/// (peak/RMS; 0 gives the lowest achievable, under 2 only with linear
+/// spacing). Peaks reach `amplitude`.
+///
+/// With both `burst_on_ms` and `burst_off_ms` set, output is gated on and
+/// off in a repeating burst pattern. The waveform keeps running while gated
+/// off, so phase and burst timing are continuous across frames.
+///
+/// Each frame carries `buffer_size` samples in channels `ch0..chN`.
+#[derive(StreamNode, Debug, Clone, Serialize, Deserialize)]
+#[node_meta(name = "Signal Generator", category = "Sources")]
+#[preset(name = "1 kHz sine", params = r#"{"waveform": "sine", "frequency": 1000.0}"#)]
+#[preset(name = "Low crest multitone", params = r#"{"waveform": "multitone", "tone_count": 31, "tone_spacing": "linear", "crest_factor": 0.0}"#)]
+#[preset(name = "Tone burst", params = r#"{"waveform": "sine", "burst_on_ms": 100.0, "burst_off_ms": 900.0}"#)]
+pub struct SignalGeneratorNode {
+    #[output(name = "Audio Out", data_type = "audio_frame")]
+    _output: (),
+
+    #[param(default = "\"sine\"", choices = "sine,square,triangle,saw,multitone")]
+    pub waveform: String,
+
+    #[param(default = "1000.0", min = 0.1, max = 96000.0, unit = "Hz", log_scale)]
+    pub frequency: f64,
+
+    #[param(default = "1.0", min = 0.0, max = 10.0)]
+    pub amplitude: f64,
+
+    #[param(default = "0.5", min = 0.0, max = 1.0, step = 0.01)]
+    pub duty_cycle: f64,
+
+    #[param(default = "16", min = 1.0, max = 1000.0, step = 1.0)]
+    pub tone_count: usize,
+
+    #[param(default = "20.0", min = 0.1, max = 96000.0, unit = "Hz", log_scale)]
+    pub start_frequency: f64,
+
+    #[param(default = "20000.0", min = 0.1, max = 96000.0, unit = "Hz", log_scale)]
+    pub stop_frequency: f64,
+
+    #[param(default = "\"log\"", choices = "log,linear")]
+    pub tone_spacing: String,
+
+    #[param(default = "0.0", min = 0.0, max = 50.0, step = 0.1)]
+    pub crest_factor: f64,
+
+    #[param(default = "0.0", min = 0.0, max = 60000.0, unit = "ms")]
+    pub burst_on_ms: f64,
+
+    #[param(default = "0.0", min = 0.0, max = 60000.0, unit = "ms")]
+    pub burst_off_ms: f64,
+
+    #[param(default = "48000", min = 8000.0, max = 192000.0, unit = "Hz")]
+    pub sample_rate: u32,
+
+    #[param(default = "1024", min = 1.0, max = 8192.0, unit = "samples", log_scale)]
+    pub buffer_size: u32,
+
+    #[param(default = "1", min = 1.0, max = 32.0, step = 1.0)]
+    pub num_channels: usize,
+
+    #[serde(skip)]
+    generator: Option<Generator>,
+
+    /// Samples into the current burst cycle
+    #[serde(skip)]
+    burst_position: u64,
+
+    #[serde(skip)]
+    sequence: u64,
+}
+
+impl Default for SignalGeneratorNode {
+    fn default() -> Self {
+        Self {
+            _output: (),
+            waveform: "sine".to_string(),
+            frequency: 1000.0,
+            amplitude: 1.0,
+            duty_cycle: 0.5,
+            tone_count: 16,
+            start_frequency: 20.0,
+            stop_frequency: 20000.0,
+            tone_spacing: "log".to_string(),
+            crest_factor: 0.0,
+            burst_on_ms: 0.0,
+            burst_off_ms: 0.0,
+            sample_rate: 48000,
+            buffer_size: 1024,
+            num_channels: 1,
+            generator: None,
+            burst_position: 0,
+            sequence: 0,
+        }
+    }
+}
+
+impl SignalGeneratorNode {
+    /// Validate parameters and restart the waveform at phase zero
+    fn configure(&mut self) -> Result<()> {
+        let sample_rate = self.sample_rate as f64;
+        if self.sample_rate == 0 || self.buffer_size == 0 {
+            anyhow::bail!("sample_rate and buffer_size must be positive");
+        }
+        if !(1..=32).contains(&self.num_channels) {
+            anyhow::bail!("num_channels must be between 1 and 32, got {}", self.num_channels);
+        }
+        if self.burst_on_ms < 0.0 || self.burst_off_ms < 0.0 {
+            anyhow::bail!("Burst times must be non-negative");
+        }
+
+        let generator = if self.waveform.eq_ignore_ascii_case("multitone") {
+            Generator::Multitone(Multitone::new(
+                self.tone_count,
+                self.start_frequency,
+                self.stop_frequency,
+                ToneSpacing::parse(&self.tone_spacing)?,
+                self.crest_factor,
+                sample_rate,
+                MULTITONE_PERIOD,
+            )?)
+        } else {
+            if !(self.frequency > 0.0 && self.frequency < sample_rate / 2.0) {
+                anyhow::bail!(
+                    "frequency must be between 0 and {} Hz, got {}",
+                    sample_rate / 2.0, self.frequency
+                );
+            }
+            let waveform = Waveform::parse(&self.waveform, self.duty_cycle)?;
+            Generator::Tone(Oscillator::new(waveform, self.frequency, sample_rate))
+        };
+        self.generator = Some(generator);
+        self.burst_position = 0;
+        Ok(())
+    }
+
+    /// Burst on and off lengths in samples, `None` when not bursting
+    fn burst_samples(&self) -> Option<(u64, u64)> {
+        let to_samples = |ms: f64| (ms * self.sample_rate as f64 / 1000.0).round() as u64;
+        let (on, off) = (to_samples(self.burst_on_ms), to_samples(self.burst_off_ms));
+        (on > 0 && off > 0).then_some((on, off))
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for SignalGeneratorNode {
+    async fn on_configured(&mut self, _config: &serde_json::Value) -> Result<()> {
+        self.configure()
+    }
+
+    async fn process(&mut self, mut frame: DataFrame) -> Result<DataFrame> {
+        if self.generator.is_none() {
+            self.configure()?;
+        }
+        let burst = self.burst_samples();
+        let generator = self.generator.as_mut().expect("generator configured");
+
+        let pool = FramePool::global();
+        let mut samples = pool.take(self.buffer_size as usize);
+        for _ in 0..self.buffer_size {
+            let value = generator.next_sample() * self.amplitude;
+            let gated = match burst {
+                Some((on, off)) => {
+                    let active = self.burst_position < on;
+                    self.burst_position = (self.burst_position + 1) % (on + off);
+                    active
+                }
+                None => true,
+            };
+            samples.push(if gated { value } else { 0.0 });
+        }
+
+        let sample_rate = self.sample_rate as f64;
+        for ch in 1..self.num_channels {
+            let mut copy = pool.take(samples.len());
+            copy.extend_from_slice(&samples);
+            frame.insert_channel(format!("ch{}", ch), pool.channel(copy).with_sample_rate(sample_rate));
+        }
+        frame.insert_channel("ch0", pool.channel(samples).with_sample_rate(sample_rate));
+
+        frame.metadata.insert("sample_rate", sample_rate);
+        frame.metadata.insert("waveform", self.waveform.to_lowercase());
+        if let Some(Generator::Multitone(multi)) = &self.generator {
+            frame.metadata.insert("crest_factor", multi.crest_factor());
+        }
+
+        frame.sequence_id = self.sequence;
+        self.sequence += 1;
+        Ok(frame)
+    }
+
+    async fn on_destroy(&mut self) -> Result<()> {
+        self.generator = None;
+        Ok(())
+    }
+}
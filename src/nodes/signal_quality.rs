@@ -0,0 +1,249 @@
+use crate::core::{ChannelRole, DataFrame, ProcessingNode};
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Issues found on one channel since the node was created
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ChannelQuality {
+    /// Runs of at least `clip_samples` samples at or above `clip_level`
+    pub clip_events: u64,
+    /// Samples within those runs
+    pub clipped_samples: u64,
+    /// Runs of silence of at least `dropout_ms` after signal
+    pub dropouts: u64,
+    /// Current running mean of the channel
+    pub dc_offset: f64,
+    /// Largest absolute sample seen
+    pub peak: f64,
+}
+
+/// Detection state of one channel, carried across frames
+#[derive(Debug, Clone, Default)]
+struct ChannelState {
+    report: ChannelQuality,
+    /// Consecutive samples at clip level so far
+    clip_run: usize,
+    /// Consecutive silent samples so far
+    silent_run: usize,
+    /// Whether the current silent run follows signal
+    after_signal: bool,
+    /// Running mean initialised from the first frame
+    dc_initialised: bool,
+}
+
+/// SignalQualityNode flags clipping, DC offset and dropouts per channel
+///
+/// Clipping is a run of at least `clip_samples` consecutive samples whose
+/// magnitude is at or above `clip_level` (full scale is 1.0). DC offset is
+/// an exponential running mean with time constant `dc_time_ms` whose
+/// magnitude exceeds `dc_threshold`. A dropout is a run of samples no
+/// louder than `dropout_level` lasting `dropout_ms` that follows signal, so
+/// silence before the first sound is not reported.
+///
+/// Frames pass through unchanged. A frame on which an issue is detected
+/// gets `quality_issue` metadata set, which the node's metrics count, with
+/// the comma separated affected channels in `clipping_channels`,
+/// `dc_offset_channels` and `dropout_channels`. With `raise_alert` on, the
+/// frame also raises a `signal_quality` alert. `channels` selects the
+/// analysed channels, every signal channel when empty.
+#[derive(StreamNode, Debug, Clone, Serialize, Deserialize)]
+#[node_meta(name = "Signal Quality", category = "Processors")]
+pub struct SignalQualityNode {
+    #[input(name = "Audio In", data_type = "audio_frame")]
+    _input: (),
+
+    #[output(name = "Audio Out", data_type = "audio_frame")]
+    _output: (),
+
+    #[param(default = "\"\"", skip_config)]
+    pub channels: String,
+
+    #[param(default = "0.999", min = 0.0, max = 1000000.0)]
+    pub clip_level: f64,
+
+    #[param(default = "3", min = 1.0, max = 100000.0, unit = "samples")]
+    pub clip_samples: usize,
+
+    #[param(default = "0.01", min = 0.0, max = 1000000.0)]
+    pub dc_threshold: f64,
+
+    #[param(default = "1000.0", min = 1.0, max = 600000.0, unit = "ms")]
+    pub dc_time_ms: f64,
+
+    #[param(default = "0.0", min = 0.0, max = 1000000.0)]
+    pub dropout_level: f64,
+
+    #[param(default = "10.0", min = 0.1, max = 600000.0, unit = "ms")]
+    pub dropout_ms: f64,
+
+    #[param(default = "false")]
+    pub raise_alert: bool,
+
+    #[serde(skip)]
+    states: HashMap<String, ChannelState>,
+}
+
+impl Default for SignalQualityNode {
+    fn default() -> Self {
+        Self {
+            _input: (),
+            _output: (),
+            channels: String::new(),
+            clip_level: 0.999,
+            clip_samples: 3,
+            dc_threshold: 0.01,
+            dc_time_ms: 1000.0,
+            dropout_level: 0.0,
+            dropout_ms: 10.0,
+            raise_alert: false,
+            states: HashMap::new(),
+        }
+    }
+}
+
+/// What one frame revealed on one channel
+#[derive(Debug, Default)]
+struct Findings {
+    clipping: bool,
+    dc_offset: bool,
+    dropout: bool,
+}
+
+impl SignalQualityNode {
+    /// Issues found so far, by channel
+    pub fn report(&self) -> HashMap<String, ChannelQuality> {
+        self.states.iter().map(|(name, state)| (name.clone(), state.report.clone())).collect()
+    }
+
+    fn channel_names(&self, frame: &DataFrame) -> Result<Vec<String>> {
+        if self.channels.trim().is_empty() {
+            let mut names: Vec<String> = frame
+                .payload
+                .iter()
+                .filter(|(_, c)| c.role == ChannelRole::Signal)
+                .map(|(name, _)| name.clone())
+                .collect();
+            names.sort();
+            return Ok(names);
+        }
+        let names: Vec<String> =
+            self.channels.split(',').map(|c| c.trim().to_string()).filter(|c| !c.is_empty()).collect();
+        if let Some(missing) = names.iter().find(|name| !frame.payload.contains_key(*name)) {
+            anyhow::bail!("Channel '{}' is not in the frame", missing);
+        }
+        Ok(names)
+    }
+
+    fn analyse(&self, state: &mut ChannelState, samples: &[f64], sample_rate: f64) -> Findings {
+        let mut findings = Findings::default();
+        let dropout_samples = ((self.dropout_ms * sample_rate / 1000.0).round() as usize).max(1);
+        let alpha = 1.0 - (-1000.0 / (self.dc_time_ms * sample_rate)).exp();
+        if !state.dc_initialised && !samples.is_empty() {
+            state.report.dc_offset = samples.iter().sum::<f64>() / samples.len() as f64;
+            state.dc_initialised = true;
+        }
+
+        for &x in samples {
+            let level = x.abs();
+            state.report.peak = state.report.peak.max(level);
+            state.report.dc_offset += alpha * (x - state.report.dc_offset);
+
+            if level >= self.clip_level {
+                state.clip_run += 1;
+                if state.clip_run == self.clip_samples {
+                    state.report.clip_events += 1;
+                    state.report.clipped_samples += self.clip_samples as u64;
+                    findings.clipping = true;
+                } else if state.clip_run > self.clip_samples {
+                    state.report.clipped_samples += 1;
+                }
+            } else {
+                state.clip_run = 0;
+            }
+
+            if level <= self.dropout_level {
+                state.silent_run += 1;
+                if state.silent_run == dropout_samples && state.after_signal {
+                    state.report.dropouts += 1;
+                    findings.dropout = true;
+                }
+            } else {
+                state.silent_run = 0;
+                state.after_signal = true;
+            }
+        }
+        findings.dc_offset = state.dc_initialised && state.report.dc_offset.abs() > self.dc_threshold;
+        findings
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for SignalQualityNode {
+    async fn on_configured(&mut self, config: &serde_json::Value) -> Result<()> {
+        // Accept either "ch0,ch1" or ["ch0", "ch1"]
+        if let Some(s) = config.get("channels").and_then(|v| v.as_str()) {
+            self.channels = s.to_string();
+        } else if let Some(list) = config.get("channels").and_then(|v| v.as_array()) {
+            self.channels = list.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>().join(",");
+        }
+        if self.clip_samples == 0 {
+            anyhow::bail!("clip_samples must be at least 1");
+        }
+        if self.dc_time_ms <= 0.0 || self.dropout_ms <= 0.0 {
+            anyhow::bail!("dc_time_ms and dropout_ms must be positive");
+        }
+        self.states.clear();
+        Ok(())
+    }
+
+    async fn process(&mut self, mut frame: DataFrame) -> Result<DataFrame> {
+        let mut clipping = Vec::new();
+        let mut dc_offset = Vec::new();
+        let mut dropout = Vec::new();
+        for name in self.channel_names(&frame)? {
+            let sample_rate = frame.channel_sample_rate(&name).unwrap_or(48000.0);
+            let mut state = self.states.remove(&name).unwrap_or_default();
+            let findings = self.analyse(&mut state, frame.payload[&name].samples(), sample_rate);
+            self.states.insert(name.clone(), state);
+            if findings.clipping {
+                clipping.push(name.clone());
+            }
+            if findings.dc_offset {
+                dc_offset.push(name.clone());
+            }
+            if findings.dropout {
+                dropout.push(name);
+            }
+        }
+
+        let mut issues = Vec::new();
+        for (key, label, channels) in [
+            ("clipping_channels", "clipping", &clipping),
+            ("dc_offset_channels", "DC offset", &dc_offset),
+            ("dropout_channels", "dropout", &dropout),
+        ] {
+            if !channels.is_empty() {
+                frame.metadata.insert(key, channels.join(","));
+                issues.push(format!("{} on {}", label, channels.join(", ")));
+            }
+        }
+        if issues.is_empty() {
+            return Ok(frame);
+        }
+        frame.metadata.insert("quality_issue", true);
+        if self.raise_alert {
+            frame.metadata.insert("alert", "signal_quality");
+            frame.metadata.insert("alert_severity", "warning");
+            frame.metadata.insert("alert_message", format!("Signal quality: {}", issues.join("; ")));
+        }
+        Ok(frame)
+    }
+
+    async fn on_destroy(&mut self) -> Result<()> {
+        self.states.clear();
+        Ok(())
+    }
+}
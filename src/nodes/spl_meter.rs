@@ -0,0 +1,211 @@
+use crate::core::{Channel, DataFrame, ProcessingNode};
+use crate::dsp::{Biquad, FrequencyWeighting};
+use crate::dsp::biquad::process_cascade;
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Reference sound pressure (20 µPa)
+const P_REF: f64 = 20e-6;
+
+/// Exponential time weighting per IEC 61672-1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeWeighting {
+    /// 125 ms
+    Fast,
+    /// 1 s
+    Slow,
+    /// 35 ms rise, 1.5 s decay
+    Impulse,
+}
+
+impl TimeWeighting {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "fast" | "f" => Ok(TimeWeighting::Fast),
+            "slow" | "s" => Ok(TimeWeighting::Slow),
+            "impulse" | "i" => Ok(TimeWeighting::Impulse),
+            _ => anyhow::bail!("Unknown time weighting: {}", name),
+        }
+    }
+
+    /// (rise, decay) time constants in seconds
+    fn time_constants(&self) -> (f64, f64) {
+        match self {
+            TimeWeighting::Fast => (0.125, 0.125),
+            TimeWeighting::Slow => (1.0, 1.0),
+            TimeWeighting::Impulse => (0.035, 1.5),
+        }
+    }
+}
+
+/// Per-channel meter state
+#[derive(Debug, Clone)]
+struct ChannelMeter {
+    filters: Vec<Biquad>,
+    mean_square: f64,
+    energy_sum: f64,
+    sample_count: u64,
+    lmax: f64,
+    lmin: f64,
+}
+
+impl ChannelMeter {
+    fn new(filters: Vec<Biquad>) -> Self {
+        Self {
+            filters,
+            mean_square: 0.0,
+            energy_sum: 0.0,
+            sample_count: 0,
+            lmax: f64::NEG_INFINITY,
+            lmin: f64::INFINITY,
+        }
+    }
+}
+
+fn to_db(mean_square_pa: f64) -> f64 {
+    10.0 * (mean_square_pa.max(1e-30) / (P_REF * P_REF)).log10()
+}
+
+/// SplMeterNode measures sound pressure level per channel
+///
+/// Samples are converted to Pascal with `calibration_gain` (or the
+/// `calibration_gain` frame metadata when present), frequency weighted
+/// (A/C/Z), and exponentially time weighted (Fast/Slow/Impulse).
+///
+/// For every input channel `chN` the output payload contains single-value
+/// channels `chN_spl` (running time-weighted level), `chN_leq`, `chN_lmax`
+/// and `chN_lmin`, all in dB re 20 µPa. Lmax/Lmin are the extremes of the
+/// running level over every sample, not just at frame boundaries. The
+/// running level starts at the mean square of the first frame of a channel.
+#[derive(StreamNode, Debug, Clone, Serialize, Deserialize)]
+#[node_meta(name = "SPL Meter", category = "Processors")]
+#[preset(name = "A-weighted Fast", params = r#"{"frequency_weighting": "A", "time_weighting": "fast"}"#)]
+#[preset(name = "A-weighted Slow", params = r#"{"frequency_weighting": "A", "time_weighting": "slow"}"#)]
+#[preset(name = "C-weighted Peak", params = r#"{"frequency_weighting": "C", "time_weighting": "impulse"}"#)]
+pub struct SplMeterNode {
+    #[input(name = "Audio In", data_type = "audio_frame")]
+    _input: (),
+
+    #[output(name = "Levels Out", data_type = "level")]
+    _output: (),
+
+    #[param(default = "\"A\"", choices = "A,C,Z")]
+    pub frequency_weighting: String,
+
+    #[param(default = "\"fast\"", choices = "fast,slow,impulse")]
+    pub time_weighting: String,
+
+    #[param(default = "1.0", min = 0.0, max = 1000000.0, log_scale)]
+    pub calibration_gain: f64,
+
+    #[param(default = "0.0", min = -200.0, max = 200.0, unit = "dB", step = 0.1)]
+    pub calibration_offset_db: f64,
+
+    #[serde(skip)]
+    meters: HashMap<String, ChannelMeter>,
+
+    #[serde(skip)]
+    sample_rate: f64,
+}
+
+impl Default for SplMeterNode {
+    fn default() -> Self {
+        Self {
+            _input: (),
+            _output: (),
+            frequency_weighting: "A".to_string(),
+            time_weighting: "fast".to_string(),
+            calibration_gain: 1.0,
+            calibration_offset_db: 0.0,
+            meters: HashMap::new(),
+            sample_rate: 0.0,
+        }
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for SplMeterNode {
+    async fn on_configured(&mut self, _config: &serde_json::Value) -> Result<()> {
+        FrequencyWeighting::parse(&self.frequency_weighting)?;
+        TimeWeighting::parse(&self.time_weighting)?;
+        self.meters.clear();
+        Ok(())
+    }
+
+    async fn process(&mut self, mut frame: DataFrame) -> Result<DataFrame> {
+        let sample_rate = frame.sample_rate().unwrap_or(48000.0);
+        if sample_rate != self.sample_rate {
+            // Filters and time constants depend on the sample rate
+            self.meters.clear();
+            self.sample_rate = sample_rate;
+        }
+
+        let gain = frame
+            .metadata
+            .get_f64("calibration_gain")
+            .unwrap_or(self.calibration_gain);
+        let offset_db = frame
+            .metadata
+            .get_f64("calibration_offset_db")
+            .unwrap_or(self.calibration_offset_db);
+
+        let weighting = FrequencyWeighting::parse(&self.frequency_weighting)?;
+        let (rise, decay) = TimeWeighting::parse(&self.time_weighting)?.time_constants();
+        let alpha_rise = 1.0 - (-1.0 / (rise * sample_rate)).exp();
+        let alpha_decay = 1.0 - (-1.0 / (decay * sample_rate)).exp();
+
+        let input = std::mem::take(&mut frame.payload);
+        for (channel, samples) in input {
+            let meter = self
+                .meters
+                .entry(channel.clone())
+                .or_insert_with(|| ChannelMeter::new(weighting.design(sample_rate)));
+
+            let squares: Vec<f64> = samples
+                .iter()
+                .map(|&sample| process_cascade(&mut meter.filters, sample * gain).powi(2))
+                .collect();
+            // Start the running level at the first block's mean square, so
+            // Lmin does not record the time weighting settling from silence
+            if meter.sample_count == 0 && !squares.is_empty() {
+                meter.mean_square = squares.iter().sum::<f64>() / squares.len() as f64;
+            }
+
+            // Extremes of the time-weighted level within this frame
+            let mut highest = f64::NEG_INFINITY;
+            let mut lowest = f64::INFINITY;
+            for sq in squares {
+                let alpha = if sq > meter.mean_square { alpha_rise } else { alpha_decay };
+                meter.mean_square += alpha * (sq - meter.mean_square);
+                meter.energy_sum += sq;
+                meter.sample_count += 1;
+                highest = highest.max(meter.mean_square);
+                lowest = lowest.min(meter.mean_square);
+            }
+
+            if meter.sample_count == 0 {
+                continue;
+            }
+
+            let spl = to_db(meter.mean_square) + offset_db;
+            let leq = to_db(meter.energy_sum / meter.sample_count as f64) + offset_db;
+            if !samples.is_empty() {
+                meter.lmax = meter.lmax.max(to_db(highest) + offset_db);
+                meter.lmin = meter.lmin.min(to_db(lowest) + offset_db);
+            }
+
+            frame.insert_channel(format!("{}_spl", channel), Channel::scalar(spl).with_unit("dB"));
+            frame.insert_channel(format!("{}_leq", channel), Channel::scalar(leq).with_unit("dB"));
+            frame.insert_channel(format!("{}_lmax", channel), Channel::scalar(meter.lmax).with_unit("dB"));
+            frame.insert_channel(format!("{}_lmin", channel), Channel::scalar(meter.lmin).with_unit("dB"));
+        }
+
+        frame.metadata.insert("frequency_weighting", weighting.name());
+        frame.metadata.insert("time_weighting", self.time_weighting.to_lowercase());
+
+        Ok(frame)
+    }
+}
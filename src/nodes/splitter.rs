@@ -0,0 +1,110 @@
+use crate::core::{DataFrame, OutputShape, ProcessingNode};
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use serde::{Deserialize, Serialize};
+
+/// Id prefix of the instances of the variadic output port
+const OUTPUT_PORT: &str = "_outputs_";
+
+/// Parse per-branch settings given as `"4096, 64/4"`
+///
+/// Each comma separated entry is `<block_size>`, `<block_size>/<decimation>`
+/// or `/<decimation>`; an empty entry leaves its branch unchanged.
+fn parse_branches(branches: &str) -> Result<Vec<OutputShape>> {
+    if branches.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let positive = |value: &str, what: &str| -> Result<Option<usize>> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Ok(None);
+        }
+        match value.parse::<usize>() {
+            Ok(n) if n > 0 => Ok(Some(n)),
+            _ => anyhow::bail!("Invalid {} '{}': must be a positive integer", what, value),
+        }
+    };
+    branches
+        .split(',')
+        .map(|entry| {
+            let (block_size, decimation) = entry.split_once('/').unwrap_or((entry, ""));
+            Ok(OutputShape {
+                block_size: positive(block_size, "block size")?,
+                decimation: positive(decimation, "decimation")?,
+            })
+        })
+        .collect()
+}
+
+/// SplitterNode feeds one input to several branches shaped independently
+///
+/// Connect each branch to its own instance of the variadic output
+/// (`_outputs_0`, `_outputs_1`, ...) with `from_port`. `branches` sets the
+/// re-blocking and decimation of each output in order, e.g. `"4096, 64/4"`
+/// sends 4096-sample blocks to an FFT on the first branch and 64-sample
+/// blocks at a quarter of the rate to a level meter on the second. A JSON
+/// list of `{"block_size", "decimation"}` objects is accepted too.
+///
+/// Frames pass through unchanged and are shared between branches; the edges
+/// do the shaping, and a connection's own `block_size` or `decimation`
+/// overrides its branch. Branch settings apply to edges connected after the
+/// node is configured.
+#[derive(StreamNode, Debug, Clone, Default, Serialize, Deserialize)]
+#[node_meta(name = "Splitter", category = "Processors")]
+#[preset(name = "FFT and level meter", params = r#"{"branches": "4096, 64"}"#)]
+pub struct SplitterNode {
+    #[input(name = "Audio In", data_type = "audio_frame")]
+    _input: (),
+
+    #[output(name = "Branch", data_type = "audio_frame", variadic, min = 2, max = 16)]
+    _outputs: (),
+
+    #[param(default = "\"\"", skip_config)]
+    pub branches: String,
+
+    #[serde(skip)]
+    shapes: Vec<OutputShape>,
+}
+
+#[async_trait]
+impl ProcessingNode for SplitterNode {
+    async fn on_configured(&mut self, config: &serde_json::Value) -> Result<()> {
+        if let Some(b) = config.get("branches") {
+            // Accept either "4096, 64/4" or [{"block_size": 4096}, {"block_size": 64, "decimation": 4}]
+            if let Some(s) = b.as_str() {
+                self.branches = s.to_string();
+            } else if let Some(list) = b.as_array() {
+                self.branches = list
+                    .iter()
+                    .map(|branch| {
+                        let field = |key: &str| branch[key].as_u64().map(|n| n.to_string()).unwrap_or_default();
+                        match field("decimation") {
+                            decimation if decimation.is_empty() => field("block_size"),
+                            decimation => format!("{}/{}", field("block_size"), decimation),
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",");
+            }
+        }
+
+        self.shapes = parse_branches(&self.branches)?;
+        let outputs = config["port_counts"]["_outputs"].as_u64().unwrap_or(2) as usize;
+        if self.shapes.len() > outputs {
+            anyhow::bail!("{} branches configured but the splitter has {} outputs", self.shapes.len(), outputs);
+        }
+        Ok(())
+    }
+
+    fn output_shape(&self, port: &str) -> OutputShape {
+        port.strip_prefix(OUTPUT_PORT)
+            .and_then(|i| i.parse::<usize>().ok())
+            .and_then(|i| self.shapes.get(i).copied())
+            .unwrap_or_default()
+    }
+
+    async fn process(&mut self, frame: DataFrame) -> Result<DataFrame> {
+        Ok(frame)
+    }
+}
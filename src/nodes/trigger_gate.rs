@@ -0,0 +1,261 @@
+use crate::core::{ProcessingNode, DataFrame};
+use anyhow::Result;
+use async_trait::async_trait;
+use audiotab_macros::StreamNode;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// Edge direction that fires the gate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerEdge {
+    Rising,
+    Falling,
+    Both,
+}
+
+impl TriggerEdge {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "rising" => Ok(TriggerEdge::Rising),
+            "falling" => Ok(TriggerEdge::Falling),
+            "both" => Ok(TriggerEdge::Both),
+            _ => anyhow::bail!("Unknown trigger edge: {}", name),
+        }
+    }
+}
+
+/// How `TriggerGateNode::gate_frame` finds trigger events
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Firing<'a> {
+    /// Level crossings of the trigger channel
+    Level,
+    /// Events at these sample indices of the frame
+    At(&'a [usize]),
+}
+
+/// TriggerGateNode passes signal only around level-crossing events
+///
+/// `trigger_channel` is compared against `threshold`. An edge re-arms only
+/// after the signal has moved `hysteresis` past the threshold in the opposite
+/// direction, and no new event can fire within `holdoff_ms` of the previous
+/// one. On each event the last `pre_trigger_samples` of every channel are
+/// emitted followed by `post_trigger_samples` from the trigger onwards.
+///
+/// Captures may span several input frames. Frames carrying no captured
+/// samples are emitted with an empty payload. When a capture starts,
+/// `trigger_offset` metadata gives the index of the trigger sample within
+/// the output.
+#[derive(StreamNode, Debug, Clone, Serialize, Deserialize)]
+#[node_meta(name = "Trigger Gate", category = "Processors")]
+pub struct TriggerGateNode {
+    #[input(name = "Audio In", data_type = "audio_frame")]
+    _input: (),
+
+    #[output(name = "Audio Out", data_type = "audio_frame")]
+    _output: (),
+
+    #[param(default = "\"ch0\"")]
+    pub trigger_channel: String,
+
+    #[param(default = "0.5", min = -1000000.0, max = 1000000.0)]
+    pub threshold: f64,
+
+    #[param(default = "\"rising\"", choices = "rising,falling,both")]
+    pub edge: String,
+
+    #[param(default = "0.0", min = 0.0, max = 1000000.0)]
+    pub hysteresis: f64,
+
+    #[param(default = "0.0", min = 0.0, max = 60000.0, unit = "ms")]
+    pub holdoff_ms: f64,
+
+    #[param(default = "0", min = 0.0, max = 10000000.0, unit = "samples")]
+    pub pre_trigger_samples: usize,
+
+    #[param(default = "4800", min = 1.0, max = 10000000.0, unit = "samples")]
+    pub post_trigger_samples: usize,
+
+    #[serde(skip)]
+    history: HashMap<String, VecDeque<f64>>,
+
+    #[serde(skip)]
+    armed_rising: bool,
+
+    #[serde(skip)]
+    armed_falling: bool,
+
+    #[serde(skip)]
+    capture_remaining: usize,
+
+    #[serde(skip)]
+    holdoff_remaining: usize,
+
+    #[serde(skip)]
+    event_count: u64,
+}
+
+impl Default for TriggerGateNode {
+    fn default() -> Self {
+        Self {
+            _input: (),
+            _output: (),
+            trigger_channel: "ch0".to_string(),
+            threshold: 0.5,
+            edge: "rising".to_string(),
+            hysteresis: 0.0,
+            holdoff_ms: 0.0,
+            pre_trigger_samples: 0,
+            post_trigger_samples: 4800,
+            history: HashMap::new(),
+            armed_rising: false,
+            armed_falling: false,
+            capture_remaining: 0,
+            holdoff_remaining: 0,
+            event_count: 0,
+        }
+    }
+}
+
+impl TriggerGateNode {
+    /// Update arming state with one sample and report whether an edge fired
+    fn detect_edge(&mut self, x: f64, edge: TriggerEdge) -> bool {
+        let mut fired = false;
+        if edge != TriggerEdge::Falling {
+            if self.armed_rising && x >= self.threshold {
+                fired = true;
+                self.armed_rising = false;
+            } else if x < self.threshold - self.hysteresis {
+                self.armed_rising = true;
+            }
+        }
+        if edge != TriggerEdge::Rising {
+            if self.armed_falling && x <= self.threshold {
+                fired = true;
+                self.armed_falling = false;
+            } else if x > self.threshold + self.hysteresis {
+                self.armed_falling = true;
+            }
+        }
+        fired
+    }
+
+    /// Capture around the events of one frame
+    ///
+    /// Shared with `TriggerSourceNode`, which fires events itself with
+    /// `Firing::At` instead of detecting levels.
+    pub(crate) fn gate_frame(&mut self, mut frame: DataFrame, firing: Firing<'_>) -> Result<DataFrame> {
+        let edge = TriggerEdge::parse(&self.edge)?;
+        let sample_rate = frame.sample_rate().unwrap_or(48000.0);
+        let holdoff_samples = (self.holdoff_ms * sample_rate / 1000.0).round() as usize;
+
+        let input = std::mem::take(&mut frame.payload);
+        let trigger = match firing {
+            Firing::Level => Some(input.get(&self.trigger_channel).cloned().ok_or_else(|| {
+                anyhow::anyhow!("Missing trigger channel '{}'", self.trigger_channel)
+            })?),
+            Firing::At(_) => None,
+        };
+        let len = match &trigger {
+            Some(trigger) => trigger.len(),
+            None => input.values().map(|samples| samples.len()).max().unwrap_or(0),
+        };
+
+        let mut outputs: HashMap<String, Vec<f64>> =
+            input.keys().map(|k| (k.clone(), Vec::new())).collect();
+        let mut trigger_offset = None;
+
+        for i in 0..len {
+            let fired = match (&trigger, firing) {
+                (Some(trigger), _) => self.detect_edge(trigger[i], edge),
+                (None, Firing::At(indices)) => indices.contains(&i),
+                (None, Firing::Level) => false,
+            };
+            if fired && self.capture_remaining == 0 && self.holdoff_remaining == 0 {
+                // Flush pre-trigger history so the capture includes the onset
+                for (channel, out) in outputs.iter_mut() {
+                    if let Some(history) = self.history.get_mut(channel) {
+                        out.extend(history.drain(..));
+                    }
+                }
+                if trigger_offset.is_none() {
+                    trigger_offset = outputs
+                        .get(&self.trigger_channel)
+                        .or_else(|| outputs.values().next())
+                        .map(|o| o.len());
+                }
+                self.capture_remaining = self.post_trigger_samples;
+                self.holdoff_remaining = holdoff_samples;
+                self.event_count += 1;
+            }
+
+            for (channel, samples) in input.iter() {
+                let Some(&sample) = samples.get(i) else { continue };
+                if self.capture_remaining > 0 {
+                    if let Some(out) = outputs.get_mut(channel) {
+                        out.push(sample);
+                    }
+                } else if self.pre_trigger_samples > 0 {
+                    let history = self.history.entry(channel.clone()).or_default();
+                    history.push_back(sample);
+                    if history.len() > self.pre_trigger_samples {
+                        history.pop_front();
+                    }
+                }
+            }
+
+            self.capture_remaining = self.capture_remaining.saturating_sub(1);
+            self.holdoff_remaining = self.holdoff_remaining.saturating_sub(1);
+        }
+
+        for (channel, samples) in outputs {
+            if !samples.is_empty() {
+                let captured = input[&channel].with_samples(samples);
+                frame.payload.insert(channel, captured);
+            }
+        }
+
+        if let Some(offset) = trigger_offset {
+            frame.metadata.insert("trigger_offset", offset);
+        }
+        frame.metadata.insert("triggered", !frame.payload.is_empty());
+        frame.metadata.insert("trigger_events", self.event_count);
+
+        Ok(frame)
+    }
+
+    /// Events fired so far
+    pub(crate) fn event_count(&self) -> u64 {
+        self.event_count
+    }
+
+    /// Count an event fired on a frame without samples to capture
+    pub(crate) fn record_event(&mut self) {
+        self.event_count += 1;
+    }
+
+    pub(crate) fn reset_state(&mut self) {
+        self.history.clear();
+        self.armed_rising = false;
+        self.armed_falling = false;
+        self.capture_remaining = 0;
+        self.holdoff_remaining = 0;
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for TriggerGateNode {
+    async fn on_configured(&mut self, _config: &serde_json::Value) -> Result<()> {
+        TriggerEdge::parse(&self.edge)?;
+        self.reset_state();
+        Ok(())
+    }
+
+    async fn process(&mut self, frame: DataFrame) -> Result<DataFrame> {
+        self.gate_frame(frame, Firing::Level)
+    }
+
+    async fn on_destroy(&mut self) -> Result<()> {
+        self.reset_state();
+        Ok(())
+    }
+}
@@ -1,36 +1,271 @@
 use crate::core::{ProcessingNode, DataFrame};
+use crate::hal::drivers::MidiMessage;
+use crate::hal::{DeviceChannels, SampleData};
+use crate::nodes::trigger_gate::{Firing, TriggerEdge, TriggerGateNode};
 use anyhow::Result;
 use async_trait::async_trait;
 use audiotab_macros::StreamNode;
 use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
 
-#[derive(StreamNode, Debug, Clone, Serialize, Deserialize)]
+/// What fires a `TriggerSourceNode`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerMode {
+    /// Every `interval_ms`
+    Periodic,
+    /// On frames sent by `trigger_pipeline` (metadata `manual_trigger`)
+    Manual,
+    /// On level crossings of the monitored channel
+    Level,
+    /// On a MIDI note-on or serial packet from the trigger device
+    External,
+}
+
+impl TriggerMode {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "periodic" => Ok(TriggerMode::Periodic),
+            "manual" => Ok(TriggerMode::Manual),
+            "level" => Ok(TriggerMode::Level),
+            "external" => Ok(TriggerMode::External),
+            _ => anyhow::bail!("Unknown trigger mode: {}", name),
+        }
+    }
+}
+
+/// TriggerSourceNode fires acquisitions and captures the signal around them
+///
+/// Events come from one of four modes (see `TriggerMode`). Periodic events
+/// are placed by sample count on incoming audio, or by wall clock on frames
+/// without samples. In level mode the node is armed on `trigger_channel`
+/// with `threshold`, `edge` and `hysteresis`, as for `TriggerGateNode`.
+/// External mode reads packets from the device in `device_profile_id`:
+/// note-ons of MIDI devices fire, as does any packet that is not a MIDI
+/// message (serial trigger lines).
+///
+/// Each event emits the last `pre_trigger_samples` and the following
+/// `post_trigger_samples` of every channel, with the same metadata as
+/// `TriggerGateNode`. Events on a frame without samples, such as a bare
+/// manual trigger, pass it on with `triggered` set.
+#[derive(StreamNode, Serialize, Deserialize)]
 #[node_meta(name = "Trigger Source", category = "Sources")]
 pub struct TriggerSourceNode {
+    #[input(name = "Audio In", data_type = "audio_frame")]
+    _input: (),
+
     #[output(name = "Trigger Out", data_type = "trigger")]
     _output: (),
 
-    #[param(default = "\"periodic\"")]
+    #[param(default = "\"periodic\"", choices = "periodic,manual,level,external")]
     pub mode: String,
 
-    #[param(default = "100", min = 1.0, max = 10000.0)]
+    #[param(default = "100", min = 1.0, max = 10000.0, unit = "ms")]
     pub interval_ms: u64,
+
+    #[param(default = "\"ch0\"")]
+    pub trigger_channel: String,
+
+    #[param(default = "0.5", min = -1000000.0, max = 1000000.0)]
+    pub threshold: f64,
+
+    #[param(default = "\"rising\"", choices = "rising,falling,both")]
+    pub edge: String,
+
+    #[param(default = "0.0", min = 0.0, max = 1000000.0)]
+    pub hysteresis: f64,
+
+    #[param(default = "0.0", min = 0.0, max = 60000.0, unit = "ms")]
+    pub holdoff_ms: f64,
+
+    #[param(default = "0", min = 0.0, max = 10000000.0, unit = "samples")]
+    pub pre_trigger_samples: usize,
+
+    #[param(default = "4800", min = 1.0, max = 10000000.0, unit = "samples")]
+    pub post_trigger_samples: usize,
+
+    #[param(default = "\"\"")]
+    pub device_profile_id: String,
+
+    #[serde(skip)]
+    gate: TriggerGateNode,
+
+    #[serde(skip)]
+    device_channels: Option<DeviceChannels>,
+
+    /// Samples until the next periodic event
+    #[serde(skip)]
+    samples_until_next: usize,
+
+    /// Last periodic event on frames without samples
+    #[serde(skip)]
+    last_fired: Option<Instant>,
+}
+
+impl std::fmt::Debug for TriggerSourceNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TriggerSourceNode")
+            .field("mode", &self.mode)
+            .field("interval_ms", &self.interval_ms)
+            .field("trigger_channel", &self.trigger_channel)
+            .field("threshold", &self.threshold)
+            .field("pre_trigger_samples", &self.pre_trigger_samples)
+            .field("post_trigger_samples", &self.post_trigger_samples)
+            .field("device_profile_id", &self.device_profile_id)
+            .field("has_device", &self.device_channels.is_some())
+            .finish()
+    }
+}
+
+impl Clone for TriggerSourceNode {
+    fn clone(&self) -> Self {
+        Self {
+            _input: (),
+            _output: (),
+            mode: self.mode.clone(),
+            interval_ms: self.interval_ms,
+            trigger_channel: self.trigger_channel.clone(),
+            threshold: self.threshold,
+            edge: self.edge.clone(),
+            hysteresis: self.hysteresis,
+            holdoff_ms: self.holdoff_ms,
+            pre_trigger_samples: self.pre_trigger_samples,
+            post_trigger_samples: self.post_trigger_samples,
+            device_profile_id: self.device_profile_id.clone(),
+            gate: self.gate.clone(),
+            device_channels: None, // Don't clone device channels
+            samples_until_next: self.samples_until_next,
+            last_fired: self.last_fired,
+        }
+    }
 }
 
 impl Default for TriggerSourceNode {
     fn default() -> Self {
         Self {
+            _input: (),
             _output: (),
             mode: "periodic".to_string(),
             interval_ms: 100,
+            trigger_channel: "ch0".to_string(),
+            threshold: 0.5,
+            edge: "rising".to_string(),
+            hysteresis: 0.0,
+            holdoff_ms: 0.0,
+            pre_trigger_samples: 0,
+            post_trigger_samples: 4800,
+            device_profile_id: String::new(),
+            gate: TriggerGateNode::default(),
+            device_channels: None,
+            samples_until_next: 0,
+            last_fired: None,
         }
     }
 }
 
+impl TriggerSourceNode {
+    /// Set device channels of the external trigger device
+    pub fn set_device_channels(&mut self, channels: Option<DeviceChannels>) {
+        self.device_channels = channels;
+    }
+
+    /// Trigger events fired so far
+    pub fn trigger_count(&self) -> u64 {
+        self.gate.event_count()
+    }
+
+    /// Configure the capture gate from this node's parameters
+    fn configure_gate(&mut self) {
+        self.gate.trigger_channel = self.trigger_channel.clone();
+        self.gate.threshold = self.threshold;
+        self.gate.edge = self.edge.clone();
+        self.gate.hysteresis = self.hysteresis;
+        self.gate.holdoff_ms = self.holdoff_ms;
+        self.gate.pre_trigger_samples = self.pre_trigger_samples;
+        self.gate.post_trigger_samples = self.post_trigger_samples;
+    }
+
+    /// Sample indices of the periodic events within a frame of `len` samples
+    fn periodic_events(&mut self, len: usize, sample_rate: f64) -> Vec<usize> {
+        if len == 0 {
+            let interval = Duration::from_millis(self.interval_ms);
+            if self.last_fired.is_some_and(|last| last.elapsed() < interval) {
+                return Vec::new();
+            }
+            self.last_fired = Some(Instant::now());
+            return vec![0];
+        }
+
+        let interval = ((self.interval_ms as f64 * sample_rate / 1000.0).round() as usize).max(1);
+        let mut events = Vec::new();
+        while self.samples_until_next < len {
+            events.push(self.samples_until_next);
+            self.samples_until_next += interval;
+        }
+        self.samples_until_next -= len;
+        events
+    }
+
+    /// Drain pending device packets, returning whether any fires a trigger
+    fn poll_device(&mut self) -> bool {
+        let Some(channels) = self.device_channels.as_ref() else { return false };
+        let mut fired = false;
+        while let Ok(packet) = channels.filled_rx.try_recv() {
+            if let SampleData::Bytes(bytes) = &packet.data {
+                fired |= match MidiMessage::parse(bytes) {
+                    Some(message) => matches!(message, MidiMessage::NoteOn { .. }),
+                    None => !bytes.is_empty(),
+                };
+            }
+            let _ = channels.empty_tx.try_send(packet);
+        }
+        fired
+    }
+}
+
 #[async_trait]
 impl ProcessingNode for TriggerSourceNode {
+    async fn on_configured(&mut self, _config: &serde_json::Value) -> Result<()> {
+        TriggerMode::parse(&self.mode)?;
+        TriggerEdge::parse(&self.edge)?;
+        self.configure_gate();
+        self.gate.reset_state();
+        self.samples_until_next = 0;
+        self.last_fired = None;
+        Ok(())
+    }
+
     async fn process(&mut self, frame: DataFrame) -> Result<DataFrame> {
-        // Placeholder - just pass through
-        Ok(frame)
+        let mode = TriggerMode::parse(&self.mode)?;
+        if mode == TriggerMode::Level {
+            return self.gate.gate_frame(frame, Firing::Level);
+        }
+
+        let len = frame.payload.values().map(|samples| samples.len()).max().unwrap_or(0);
+        let events = match mode {
+            TriggerMode::Periodic => {
+                let sample_rate = frame.sample_rate().unwrap_or(48000.0);
+                self.periodic_events(len, sample_rate)
+            }
+            TriggerMode::Manual if frame.metadata.get_bool("manual_trigger") == Some(true) => vec![0],
+            TriggerMode::External if self.poll_device() => vec![0],
+            _ => Vec::new(),
+        };
+
+        if len == 0 {
+            let mut frame = frame;
+            frame.metadata.insert("triggered", !events.is_empty());
+            if !events.is_empty() {
+                self.gate.record_event();
+                frame.metadata.insert("trigger_offset", 0usize);
+            }
+            frame.metadata.insert("trigger_events", self.gate.event_count());
+            return Ok(frame);
+        }
+        self.gate.gate_frame(frame, Firing::At(&events))
+    }
+
+    async fn on_destroy(&mut self) -> Result<()> {
+        self.gate.reset_state();
+        Ok(())
     }
 }
@@ -0,0 +1,364 @@
+//! Alerts raised by nodes and their delivery
+//!
+//! A node raises an alert by setting `alert` metadata, the alert name, on a
+//! frame it outputs, optionally with `alert_severity` (`info`, `warning` or
+//! `critical`; `warning` when absent) and `alert_message`. The pipeline
+//! publishes it as `PipelineEvent::Alert` when the output carries an alert
+//! the input did not, so nodes passing the frame along do not raise it
+//! again. An `AlertRouter` debounces the alerts of a pipeline and hands them
+//! to its sinks: the event log, webhooks, or the app's notifications.
+
+use super::{EventKind, EventLog, LoggedEvent, PipelineEvent};
+use crate::core::DataFrame;
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+/// How long a webhook may take to answer
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    #[default]
+    Info,
+    Warning,
+    Critical,
+}
+
+impl Severity {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "info" => Ok(Severity::Info),
+            "warning" => Ok(Severity::Warning),
+            "critical" => Ok(Severity::Critical),
+            _ => Err(anyhow!("Unknown alert severity: {}", name)),
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Critical => "critical",
+        })
+    }
+}
+
+/// A named condition reported by a node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Alert {
+    /// Milliseconds since the Unix epoch
+    pub timestamp_ms: u64,
+    pub name: String,
+    pub severity: Severity,
+    pub message: String,
+    pub node_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pipeline_id: Option<String>,
+    /// Repeats dropped by the debounce since this alert was last delivered
+    #[serde(default)]
+    pub suppressed: u64,
+}
+
+impl Alert {
+    /// Alert `name` of `node_id` stamped with the current time
+    pub fn new(node_id: impl Into<String>, name: impl Into<String>, severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            timestamp_ms: now_ms(),
+            name: name.into(),
+            severity,
+            message: message.into(),
+            node_id: node_id.into(),
+            pipeline_id: None,
+            suppressed: 0,
+        }
+    }
+
+    /// The alert `output` raises, if it carries one `input` did not
+    ///
+    /// An unknown `alert_severity` is treated as `warning`.
+    pub fn raised(node_id: &str, input: &DataFrame, output: &DataFrame) -> Option<Self> {
+        let name = output.metadata.get_str("alert").filter(|name| !name.is_empty())?;
+        if input.metadata.get_str("alert") == Some(name) {
+            return None;
+        }
+        let severity = output
+            .metadata
+            .get_str("alert_severity")
+            .and_then(|s| Severity::parse(s).ok())
+            .unwrap_or(Severity::Warning);
+        let message = output
+            .metadata
+            .get_str("alert_message")
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("Node '{}' raised '{}'", node_id, name));
+        Some(Self::new(node_id, name, severity, message))
+    }
+}
+
+/// Destination of routed alerts
+#[async_trait]
+pub trait AlertSink: Send + Sync {
+    /// Least severe alert this sink is given
+    fn min_severity(&self) -> Severity {
+        Severity::Info
+    }
+
+    async fn deliver(&self, alert: &Alert) -> Result<()>;
+}
+
+#[async_trait]
+impl AlertSink for EventLog {
+    async fn deliver(&self, alert: &Alert) -> Result<()> {
+        let mut event = LoggedEvent::new(EventKind::Alert, alert.message.clone()).data(serde_json::to_value(alert)?);
+        event.timestamp_ms = alert.timestamp_ms;
+        event.pipeline_id = alert.pipeline_id.clone();
+        self.record(event);
+        Ok(())
+    }
+}
+
+/// POSTs alerts as JSON to an `http://` URL
+///
+/// Any 2xx answer counts as delivered. HTTPS endpoints are not supported;
+/// point the webhook at a local relay instead.
+#[derive(Debug, Clone)]
+pub struct WebhookSink {
+    url: String,
+    host: String,
+    port: u16,
+    path: String,
+    min_severity: Severity,
+}
+
+impl WebhookSink {
+    pub fn new(url: &str, min_severity: Severity) -> Result<Self> {
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or_else(|| anyhow!("Webhook URL must start with http://, got '{}'", url))?;
+        let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => {
+                (host, port.parse().map_err(|_| anyhow!("Invalid port in webhook URL '{}'", url))?)
+            }
+            None => (authority, 80),
+        };
+        if host.is_empty() {
+            return Err(anyhow!("Webhook URL '{}' has no host", url));
+        }
+        Ok(Self {
+            url: url.to_string(),
+            host: host.to_string(),
+            port,
+            path: if path.is_empty() { "/".to_string() } else { path.to_string() },
+            min_severity,
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    async fn post(&self, body: &[u8]) -> Result<()> {
+        let mut stream = TcpStream::connect((self.host.as_str(), self.port)).await?;
+        let head = format!(
+            "POST {} HTTP/1.1\r\nHost: {}:{}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            self.path,
+            self.host,
+            self.port,
+            body.len()
+        );
+        stream.write_all(head.as_bytes()).await?;
+        stream.write_all(body).await?;
+
+        let mut status_line = String::new();
+        BufReader::new(stream).read_line(&mut status_line).await?;
+        let status: u16 = status_line
+            .split_whitespace()
+            .nth(1)
+            .and_then(|code| code.parse().ok())
+            .ok_or_else(|| anyhow!("Invalid HTTP response: {:?}", status_line.trim_end()))?;
+        if !(200..300).contains(&status) {
+            return Err(anyhow!("Webhook answered {}", status_line.trim_end()));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AlertSink for WebhookSink {
+    fn min_severity(&self) -> Severity {
+        self.min_severity
+    }
+
+    async fn deliver(&self, alert: &Alert) -> Result<()> {
+        let body = serde_json::to_vec(alert)?;
+        tokio::time::timeout(WEBHOOK_TIMEOUT, self.post(&body))
+            .await
+            .map_err(|_| anyhow!("Webhook timed out"))?
+            .with_context(|| format!("Failed to post alert to {}", self.url))
+    }
+}
+
+/// A webhook of `AlertConfig`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    #[serde(default)]
+    pub min_severity: Severity,
+}
+
+/// Alert routing of a pipeline, `pipeline_config.alerts` in a graph
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertConfig {
+    /// Repeats of an alert from the same node within this time are dropped,
+    /// unless they are more severe than the one last delivered
+    pub debounce_ms: u64,
+    /// Alerts below this severity are dropped
+    pub min_severity: Severity,
+    pub webhooks: Vec<WebhookConfig>,
+    /// Least severe alert shown as a desktop notification by the app
+    pub notify_severity: Severity,
+}
+
+impl Default for AlertConfig {
+    fn default() -> Self {
+        Self {
+            debounce_ms: 5000,
+            min_severity: Severity::Info,
+            webhooks: Vec::new(),
+            notify_severity: Severity::Warning,
+        }
+    }
+}
+
+impl AlertConfig {
+    /// Parse the `alerts` object of a pipeline config; null gives the defaults
+    pub fn from_json(value: &Value) -> Result<Self> {
+        if value.is_null() {
+            return Ok(Self::default());
+        }
+        let config: Self = serde_json::from_value(value.clone()).context("Invalid alerts config")?;
+        for webhook in &config.webhooks {
+            WebhookSink::new(&webhook.url, webhook.min_severity)?;
+        }
+        Ok(config)
+    }
+}
+
+/// Last delivery of an alert, for debouncing
+#[derive(Debug, Clone, Copy)]
+struct Delivered {
+    timestamp_ms: u64,
+    severity: Severity,
+    suppressed: u64,
+}
+
+/// Debounces alerts and hands them to sinks
+pub struct AlertRouter {
+    debounce_ms: u64,
+    min_severity: Severity,
+    sinks: Vec<Arc<dyn AlertSink>>,
+    /// Keyed by node and alert name
+    delivered: HashMap<(String, String), Delivered>,
+}
+
+impl AlertRouter {
+    /// Router for `config`, with its webhooks as sinks
+    pub fn new(config: &AlertConfig) -> Result<Self> {
+        let mut router = Self {
+            debounce_ms: config.debounce_ms,
+            min_severity: config.min_severity,
+            sinks: Vec::new(),
+            delivered: HashMap::new(),
+        };
+        for webhook in &config.webhooks {
+            router = router.with_sink(Arc::new(WebhookSink::new(&webhook.url, webhook.min_severity)?));
+        }
+        Ok(router)
+    }
+
+    pub fn with_sink(mut self, sink: Arc<dyn AlertSink>) -> Self {
+        self.sinks.push(sink);
+        self
+    }
+
+    /// Whether `alert` passes the severity filter and debounce
+    ///
+    /// A passing alert is counted as delivered and gets the number of
+    /// repeats dropped since the last delivery in `suppressed`.
+    pub fn admit(&mut self, alert: &mut Alert) -> bool {
+        if alert.severity < self.min_severity {
+            return false;
+        }
+        let key = (alert.node_id.clone(), alert.name.clone());
+        if let Some(last) = self.delivered.get_mut(&key) {
+            let within = alert.timestamp_ms.saturating_sub(last.timestamp_ms) < self.debounce_ms;
+            if within && alert.severity <= last.severity {
+                last.suppressed += 1;
+                return false;
+            }
+            alert.suppressed = last.suppressed;
+        }
+        self.delivered.insert(
+            key,
+            Delivered { timestamp_ms: alert.timestamp_ms, severity: alert.severity, suppressed: 0 },
+        );
+        true
+    }
+
+    /// Deliver `alert` to every sink that takes its severity, if admitted
+    ///
+    /// A failed delivery is logged rather than returned, so one unreachable
+    /// webhook does not keep the alert from the other sinks.
+    pub async fn route(&mut self, mut alert: Alert) -> bool {
+        if !self.admit(&mut alert) {
+            return false;
+        }
+        for sink in &self.sinks {
+            if alert.severity >= sink.min_severity() {
+                if let Err(e) = sink.deliver(&alert).await {
+                    tracing::warn!(alert = %alert.name, "Failed to deliver alert: {:#}", e);
+                }
+            }
+        }
+        true
+    }
+
+    /// Route the alerts of pipeline `pipeline_id` until its events close
+    pub fn follow(mut self, pipeline_id: &str, mut events: broadcast::Receiver<PipelineEvent>) -> JoinHandle<()> {
+        let pipeline_id = pipeline_id.to_string();
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(PipelineEvent::Alert(mut alert)) => {
+                        alert.pipeline_id = Some(pipeline_id.clone());
+                        self.route(alert).await;
+                    }
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
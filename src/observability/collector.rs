@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use super::resources::{ChannelProbe, ChannelRegistry, ResourceUsage};
 use super::NodeMetrics;
 
 #[derive(Debug, Clone)]
@@ -7,17 +8,25 @@ pub struct MetricsSnapshot {
     pub node_id: String,
     pub frames_processed: u64,
     pub errors_count: u64,
+    pub restarts_count: u64,
+    pub frames_dropped: u64,
+    pub frames_missing: u64,
+    pub frames_out_of_order: u64,
+    pub triggers: u64,
+    pub quality_issues: u64,
     pub avg_latency_us: u64,
 }
 
 pub struct MetricsCollector {
     metrics: HashMap<String, Arc<NodeMetrics>>,
+    channels: ChannelRegistry,
 }
 
 impl MetricsCollector {
     pub fn new() -> Self {
         Self {
             metrics: HashMap::new(),
+            channels: ChannelRegistry::default(),
         }
     }
 
@@ -35,6 +44,12 @@ impl MetricsCollector {
                         node_id: metrics.node_id().to_string(),
                         frames_processed: metrics.frames_processed(),
                         errors_count: metrics.errors_count(),
+                        restarts_count: metrics.restarts_count(),
+                        frames_dropped: metrics.frames_dropped(),
+                        frames_missing: metrics.frames_missing(),
+                        frames_out_of_order: metrics.frames_out_of_order(),
+                        triggers: metrics.triggers(),
+                        quality_issues: metrics.quality_issues(),
                         avg_latency_us: metrics.avg_latency_us(),
                     },
                 )
@@ -45,6 +60,21 @@ impl MetricsCollector {
     pub fn get_node_metrics(&self, node_id: &str) -> Option<Arc<NodeMetrics>> {
         self.metrics.get(node_id).cloned()
     }
+
+    /// Watch the fill level of `channel`, whose frames wait for `node_id`
+    pub fn register_channel(&self, channel: impl Into<String>, node_id: impl Into<String>, probe: ChannelProbe) {
+        self.channels.register(channel, node_id, probe);
+    }
+
+    /// Handle for registering channels while the collector is borrowed elsewhere
+    pub fn channels(&self) -> ChannelRegistry {
+        self.channels.clone()
+    }
+
+    /// Frames and bytes queued in the registered channels, with pool and memory state
+    pub fn resource_usage(&self) -> ResourceUsage {
+        ResourceUsage::new(self.channels.occupancy(&self.metrics))
+    }
 }
 
 impl Default for MetricsCollector {
@@ -57,6 +87,7 @@ impl Clone for MetricsCollector {
     fn clone(&self) -> Self {
         Self {
             metrics: self.metrics.clone(),
+            channels: self.channels.clone(),
         }
     }
 }
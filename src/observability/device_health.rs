@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// Runtime health of one device, updated from driver callbacks
+#[derive(Debug, Default)]
+pub struct DeviceHealth {
+    xruns: AtomicU64,
+    /// Cycles where no buffer was available (capture overrun / playback underrun)
+    dropped_cycles: AtomicU64,
+    transport_rolling: AtomicBool,
+    transport_frame: AtomicU64,
+    shutdown_reason: Mutex<Option<String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceHealthSnapshot {
+    pub xruns: u64,
+    pub dropped_cycles: u64,
+    pub transport_rolling: bool,
+    pub transport_frame: u64,
+    /// Set when the backend shut the device down
+    pub shutdown_reason: Option<String>,
+}
+
+impl DeviceHealthSnapshot {
+    pub fn is_healthy(&self) -> bool {
+        self.shutdown_reason.is_none()
+    }
+}
+
+impl DeviceHealth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_xrun(&self) {
+        self.xruns.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_dropped_cycle(&self) {
+        self.dropped_cycles.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn set_transport(&self, rolling: bool, frame: u64) {
+        self.transport_rolling.store(rolling, Ordering::Relaxed);
+        self.transport_frame.store(frame, Ordering::Relaxed);
+    }
+
+    pub fn record_shutdown(&self, reason: impl Into<String>) {
+        *self.shutdown_reason.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(reason.into());
+    }
+
+    pub fn snapshot(&self) -> DeviceHealthSnapshot {
+        DeviceHealthSnapshot {
+            xruns: self.xruns.load(Ordering::Relaxed),
+            dropped_cycles: self.dropped_cycles.load(Ordering::Relaxed),
+            transport_rolling: self.transport_rolling.load(Ordering::Relaxed),
+            transport_frame: self.transport_frame.load(Ordering::Relaxed),
+            shutdown_reason: self
+                .shutdown_reason
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .clone(),
+        }
+    }
+}
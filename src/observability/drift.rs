@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Sample clock drift of one device, updated by the kernel reader task
+pub struct DriftMetrics {
+    device_id: String,
+    /// Measured / nominal sample rate, stored as f64 bits (0 until estimated)
+    rate_ratio: AtomicU64,
+    /// Drift against the reference device in ppm, stored as f64 bits
+    drift_ppm: AtomicU64,
+    samples_inserted: AtomicU64,
+    samples_dropped: AtomicU64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DriftSnapshot {
+    pub device_id: String,
+    pub rate_ratio: Option<f64>,
+    pub drift_ppm: f64,
+    pub samples_inserted: u64,
+    pub samples_dropped: u64,
+}
+
+impl DriftMetrics {
+    pub fn new(device_id: impl Into<String>) -> Self {
+        Self {
+            device_id: device_id.into(),
+            rate_ratio: AtomicU64::new(0f64.to_bits()),
+            drift_ppm: AtomicU64::new(0f64.to_bits()),
+            samples_inserted: AtomicU64::new(0),
+            samples_dropped: AtomicU64::new(0),
+        }
+    }
+
+    pub fn device_id(&self) -> &str {
+        &self.device_id
+    }
+
+    /// Measured / nominal sample rate, once enough data has been observed
+    pub fn rate_ratio(&self) -> Option<f64> {
+        let ratio = f64::from_bits(self.rate_ratio.load(Ordering::Relaxed));
+        (ratio > 0.0).then_some(ratio)
+    }
+
+    pub fn set_rate_ratio(&self, ratio: f64) {
+        self.rate_ratio.store(ratio.to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn drift_ppm(&self) -> f64 {
+        f64::from_bits(self.drift_ppm.load(Ordering::Relaxed))
+    }
+
+    pub fn set_drift_ppm(&self, ppm: f64) {
+        self.drift_ppm.store(ppm.to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn samples_inserted(&self) -> u64 {
+        self.samples_inserted.load(Ordering::Relaxed)
+    }
+
+    pub fn samples_dropped(&self) -> u64 {
+        self.samples_dropped.load(Ordering::Relaxed)
+    }
+
+    pub fn record_inserted(&self, count: u64) {
+        self.samples_inserted.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn record_dropped(&self, count: u64) {
+        self.samples_dropped.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> DriftSnapshot {
+        DriftSnapshot {
+            device_id: self.device_id.clone(),
+            rate_ratio: self.rate_ratio(),
+            drift_ppm: self.drift_ppm(),
+            samples_inserted: self.samples_inserted(),
+            samples_dropped: self.samples_dropped(),
+        }
+    }
+}
@@ -0,0 +1,357 @@
+//! Persistent audit trail of a measurement session
+//!
+//! Events are appended as JSON lines to one file per session and flushed as
+//! they happen, so a measurement report can state exactly what happened
+//! during a capture: devices started and stopped, calibrations applied,
+//! pipelines deployed, overruns and errors. The files outlive the app and
+//! are read back with `read_session` and `list_sessions`.
+
+use super::PipelineEvent;
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+/// Extension of session files
+const EXTENSION: &str = "jsonl";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    SessionStarted,
+    SessionEnded,
+    PipelineDeployed,
+    /// Pipeline state transition, e.g. `Idle` to `Running`
+    PipelineState,
+    DeviceStarted,
+    DeviceStopped,
+    CalibrationApplied,
+    CaptureStarted,
+    /// A scheduled capture completed, was cancelled or failed
+    CaptureEnded,
+    /// Frames dropped or missing because a node or device fell behind
+    Overrun,
+    Warning,
+    Error,
+    /// An alert raised by a node, see `AlertRouter`
+    Alert,
+}
+
+/// One entry of the trail
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoggedEvent {
+    /// Milliseconds since the Unix epoch
+    pub timestamp_ms: u64,
+    pub kind: EventKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pipeline_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_id: Option<String>,
+    pub message: String,
+    /// Details that depend on the kind, such as calibration values
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub data: Value,
+}
+
+impl LoggedEvent {
+    /// Event of `kind` stamped with the current time
+    pub fn new(kind: EventKind, message: impl Into<String>) -> Self {
+        Self {
+            timestamp_ms: now_ms(),
+            kind,
+            pipeline_id: None,
+            device_id: None,
+            message: message.into(),
+            data: Value::Null,
+        }
+    }
+
+    pub fn pipeline(mut self, pipeline_id: impl Into<String>) -> Self {
+        self.pipeline_id = Some(pipeline_id.into());
+        self
+    }
+
+    pub fn device(mut self, device_id: impl Into<String>) -> Self {
+        self.device_id = Some(device_id.into());
+        self
+    }
+
+    pub fn data(mut self, data: Value) -> Self {
+        self.data = data;
+        self
+    }
+}
+
+/// Filter for reading events back; the default matches everything
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventQuery {
+    /// Kinds to keep; empty keeps all
+    pub kinds: Vec<EventKind>,
+    pub pipeline_id: Option<String>,
+    pub device_id: Option<String>,
+    /// Inclusive bounds in milliseconds since the Unix epoch
+    pub since_ms: Option<u64>,
+    pub until_ms: Option<u64>,
+    /// Keep only the latest this many matches
+    pub limit: Option<usize>,
+}
+
+impl EventQuery {
+    pub fn matches(&self, event: &LoggedEvent) -> bool {
+        (self.kinds.is_empty() || self.kinds.contains(&event.kind))
+            && (self.pipeline_id.is_none() || self.pipeline_id == event.pipeline_id)
+            && (self.device_id.is_none() || self.device_id == event.device_id)
+            && self.since_ms.is_none_or(|since| event.timestamp_ms >= since)
+            && self.until_ms.is_none_or(|until| event.timestamp_ms <= until)
+    }
+}
+
+/// A session file found by `list_sessions`
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionInfo {
+    pub session_id: String,
+    /// Time of the first event, if the file has any
+    pub started_ms: Option<u64>,
+    /// Time of the last event
+    pub ended_ms: Option<u64>,
+    pub event_count: usize,
+}
+
+/// Appends events to the session file; clones share the file
+#[derive(Clone)]
+pub struct EventLog {
+    session_id: String,
+    path: PathBuf,
+    file: Arc<Mutex<File>>,
+}
+
+impl EventLog {
+    /// Start session `session_id` in `dir`, appending to its file if it exists
+    ///
+    /// Session ids may contain letters, digits, `-` and `_` only.
+    pub fn create(dir: impl AsRef<Path>, session_id: &str) -> Result<Self> {
+        let path = session_path(dir.as_ref(), session_id)?;
+        std::fs::create_dir_all(dir.as_ref())
+            .with_context(|| format!("Failed to create event log directory {}", dir.as_ref().display()))?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open event log {}", path.display()))?;
+
+        let log = Self {
+            session_id: session_id.to_string(),
+            path,
+            file: Arc::new(Mutex::new(file)),
+        };
+        log.record(LoggedEvent::new(EventKind::SessionStarted, "Session started"));
+        Ok(log)
+    }
+
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append `event`; a failed write is logged rather than returned, so
+    /// recording never interrupts a measurement
+    pub fn record(&self, event: LoggedEvent) {
+        let mut line = match serde_json::to_string(&event) {
+            Ok(line) => line,
+            Err(e) => {
+                tracing::warn!("Failed to serialize event: {}", e);
+                return;
+            }
+        };
+        line.push('\n');
+        let mut file = self.file.lock().unwrap_or_else(|p| p.into_inner());
+        if let Err(e) = file.write_all(line.as_bytes()) {
+            tracing::warn!(path = %self.path.display(), "Failed to write event log: {}", e);
+        }
+    }
+
+    /// Record the end of the session; the log can still be written afterwards
+    pub fn end(&self) {
+        self.record(LoggedEvent::new(EventKind::SessionEnded, "Session ended"));
+    }
+
+    /// Events of this session matching `query`, oldest first
+    pub fn query(&self, query: &EventQuery) -> Result<Vec<LoggedEvent>> {
+        read_events(&self.path, query)
+    }
+
+    /// Record the events of pipeline `pipeline_id` until it is dropped
+    ///
+    /// State changes and fatal node errors are recorded as they arrive.
+    /// Dropped and missing frames, frames a node failed and frames flagged
+    /// with signal quality issues are recorded when a metrics sample shows
+    /// their counters went up.
+    pub fn follow(&self, pipeline_id: &str, mut events: broadcast::Receiver<PipelineEvent>) -> JoinHandle<()> {
+        let log = self.clone();
+        let pipeline_id = pipeline_id.to_string();
+        tokio::spawn(async move {
+            // Counters of each node at the previous sample: (dropped + missing, errors, quality issues)
+            let mut last: HashMap<String, (u64, u64, u64)> = HashMap::new();
+            loop {
+                match events.recv().await {
+                    Ok(PipelineEvent::StateChanged { from, to }) => {
+                        log.record(
+                            LoggedEvent::new(EventKind::PipelineState, format!("{} -> {}", from, to))
+                                .pipeline(&pipeline_id)
+                                .data(serde_json::json!({ "from": from, "to": to })),
+                        );
+                    }
+                    Ok(PipelineEvent::NodeError { node_id, error, fatal: true }) => {
+                        log.record(
+                            LoggedEvent::new(EventKind::Error, format!("Node '{}' failed: {}", node_id, error))
+                                .pipeline(&pipeline_id)
+                                .data(serde_json::json!({ "node": node_id })),
+                        );
+                    }
+                    Ok(PipelineEvent::NodeError { .. }) => {}
+                    // Alerts are recorded by an AlertRouter, after the debounce
+                    Ok(PipelineEvent::Alert(_)) => {}
+                    Ok(PipelineEvent::Metrics(metrics)) => {
+                        for node in metrics.nodes {
+                            let lost = node.frames_dropped + node.frames_missing;
+                            let (last_lost, last_errors, last_issues) = last
+                                .insert(node.node_id.clone(), (lost, node.errors_count, node.quality_issues))
+                                .unwrap_or((0, 0, 0));
+                            if lost > last_lost {
+                                log.record(
+                                    LoggedEvent::new(
+                                        EventKind::Overrun,
+                                        format!("Node '{}' lost {} frames", node.node_id, lost - last_lost),
+                                    )
+                                    .pipeline(&pipeline_id)
+                                    .data(serde_json::json!({
+                                        "node": node.node_id,
+                                        "frames_dropped": node.frames_dropped,
+                                        "frames_missing": node.frames_missing,
+                                    })),
+                                );
+                            }
+                            if node.errors_count > last_errors {
+                                log.record(
+                                    LoggedEvent::new(
+                                        EventKind::Warning,
+                                        format!("Node '{}' failed {} frames", node.node_id, node.errors_count - last_errors),
+                                    )
+                                    .pipeline(&pipeline_id)
+                                    .data(serde_json::json!({
+                                        "node": node.node_id,
+                                        "errors_count": node.errors_count,
+                                    })),
+                                );
+                            }
+                            if node.quality_issues > last_issues {
+                                log.record(
+                                    LoggedEvent::new(
+                                        EventKind::Warning,
+                                        format!(
+                                            "Node '{}' flagged {} frames with signal quality issues",
+                                            node.node_id,
+                                            node.quality_issues - last_issues
+                                        ),
+                                    )
+                                    .pipeline(&pipeline_id)
+                                    .data(serde_json::json!({
+                                        "node": node.node_id,
+                                        "quality_issues": node.quality_issues,
+                                    })),
+                                );
+                            }
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+}
+
+/// Events of session `session_id` in `dir` matching `query`, oldest first
+pub fn read_session(dir: impl AsRef<Path>, session_id: &str, query: &EventQuery) -> Result<Vec<LoggedEvent>> {
+    read_events(&session_path(dir.as_ref(), session_id)?, query)
+}
+
+/// Events of the session file at `path` matching `query`, oldest first
+///
+/// Lines that do not parse, such as one cut short by a crash, are skipped.
+pub fn read_events(path: impl AsRef<Path>, query: &EventQuery) -> Result<Vec<LoggedEvent>> {
+    let path = path.as_ref();
+    let file = File::open(path).with_context(|| format!("Failed to open event log {}", path.display()))?;
+    let mut events = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line.with_context(|| format!("Failed to read event log {}", path.display()))?;
+        if let Ok(event) = serde_json::from_str::<LoggedEvent>(&line) {
+            if query.matches(&event) {
+                events.push(event);
+            }
+        }
+    }
+    if let Some(limit) = query.limit {
+        let skip = events.len().saturating_sub(limit);
+        events.drain(..skip);
+    }
+    Ok(events)
+}
+
+/// Sessions recorded in `dir`, newest first; a missing directory has none
+pub fn list_sessions(dir: impl AsRef<Path>) -> Result<Vec<SessionInfo>> {
+    let dir = dir.as_ref();
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to list {}", dir.display())),
+    };
+
+    let mut sessions = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some(EXTENSION) {
+            continue;
+        }
+        let Some(session_id) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let events = read_events(&path, &EventQuery::default())?;
+        sessions.push(SessionInfo {
+            session_id: session_id.to_string(),
+            started_ms: events.first().map(|e| e.timestamp_ms),
+            ended_ms: events.last().map(|e| e.timestamp_ms),
+            event_count: events.len(),
+        });
+    }
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.started_ms));
+    Ok(sessions)
+}
+
+/// File of session `session_id`, refusing ids that could leave `dir`
+fn session_path(dir: &Path, session_id: &str) -> Result<PathBuf> {
+    let valid = !session_id.is_empty()
+        && session_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(anyhow!("Invalid session id '{}'", session_id));
+    }
+    Ok(dir.join(format!("{}.{}", session_id, EXTENSION)))
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
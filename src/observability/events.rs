@@ -0,0 +1,125 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+use super::{Alert, MetricsCollector, MetricsSnapshot, NodeMetrics};
+
+/// Lifecycle and progress of a running pipeline
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PipelineEvent {
+    StateChanged { from: String, to: String },
+    /// Periodic counters, see `MetricsSampler`
+    Metrics(PipelineMetrics),
+    /// A node failed a frame; `fatal` once its task was given up on
+    NodeError { node_id: String, error: String, fatal: bool },
+    /// A node raised an alert, see `AlertRouter`
+    Alert(Alert),
+}
+
+/// Counters of a pipeline at one point in time
+#[derive(Debug, Clone, Serialize)]
+pub struct PipelineMetrics {
+    /// Frames taken in by the source nodes
+    pub frames_processed: u64,
+    pub elapsed_ms: u64,
+    pub nodes: Vec<NodeThroughput>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeThroughput {
+    pub node_id: String,
+    pub frames_processed: u64,
+    /// Rate since the previous sample
+    pub frames_per_sec: f64,
+    pub errors_count: u64,
+    pub restarts_count: u64,
+    /// Frames dropped on the node's lossy output edges
+    pub frames_dropped: u64,
+    /// Gaps in the sequence ids arriving on the node's inputs
+    pub frames_missing: u64,
+    pub frames_out_of_order: u64,
+    /// Frames emitted with `triggered` set, e.g. by trigger sources and gates
+    pub triggers: u64,
+    /// Frames flagged with `quality_issue`, e.g. by a Signal Quality node
+    pub quality_issues: u64,
+    pub avg_latency_us: u64,
+}
+
+impl NodeThroughput {
+    pub(super) fn new(snapshot: MetricsSnapshot, frames_per_sec: f64) -> Self {
+        Self {
+            node_id: snapshot.node_id,
+            frames_processed: snapshot.frames_processed,
+            frames_per_sec,
+            errors_count: snapshot.errors_count,
+            restarts_count: snapshot.restarts_count,
+            frames_dropped: snapshot.frames_dropped,
+            frames_missing: snapshot.frames_missing,
+            frames_out_of_order: snapshot.frames_out_of_order,
+            triggers: snapshot.triggers,
+            quality_issues: snapshot.quality_issues,
+            avg_latency_us: snapshot.avg_latency_us,
+        }
+    }
+}
+
+/// Turns successive collector snapshots into throughput
+pub struct MetricsSampler {
+    collector: MetricsCollector,
+    sources: Vec<String>,
+    started: Instant,
+    last: Instant,
+    last_frames: HashMap<String, u64>,
+}
+
+impl MetricsSampler {
+    /// Sample `collector`, counting pipeline frames at the `sources` nodes
+    pub fn new(collector: MetricsCollector, sources: Vec<String>) -> Self {
+        let now = Instant::now();
+        Self {
+            collector,
+            sources,
+            started: now,
+            last: now,
+            last_frames: HashMap::new(),
+        }
+    }
+
+    /// Include a node added after sampling started
+    pub fn register(&mut self, node_id: impl Into<String>, metrics: Arc<NodeMetrics>) {
+        self.collector.register(node_id, metrics);
+    }
+
+    pub fn sample(&mut self) -> PipelineMetrics {
+        let now = Instant::now();
+        let interval = now.duration_since(self.last).as_secs_f64();
+        self.last = now;
+
+        let mut nodes: Vec<NodeThroughput> = self
+            .collector
+            .snapshot()
+            .into_values()
+            .map(|snapshot| {
+                let previous = self
+                    .last_frames
+                    .insert(snapshot.node_id.clone(), snapshot.frames_processed)
+                    .unwrap_or(0);
+                let frames = snapshot.frames_processed.saturating_sub(previous);
+                let rate = if interval > 0.0 { frames as f64 / interval } else { 0.0 };
+                NodeThroughput::new(snapshot, rate)
+            })
+            .collect();
+        nodes.sort_by(|a, b| a.node_id.cmp(&b.node_id));
+
+        PipelineMetrics {
+            frames_processed: nodes
+                .iter()
+                .filter(|n| self.sources.contains(&n.node_id))
+                .map(|n| n.frames_processed)
+                .sum(),
+            elapsed_ms: now.duration_since(self.started).as_millis() as u64,
+            nodes,
+        }
+    }
+}
@@ -5,8 +5,15 @@ pub struct NodeMetrics {
     node_id: String,
     frames_processed: AtomicU64,
     errors_count: AtomicU64,
+    restarts_count: AtomicU64,
+    frames_dropped: AtomicU64,
+    frames_missing: AtomicU64,
+    frames_out_of_order: AtomicU64,
+    triggers: AtomicU64,
+    quality_issues: AtomicU64,
     total_latency_us: AtomicU64,
     latency_samples: AtomicU64,
+    frame_bytes: AtomicU64,
 }
 
 impl NodeMetrics {
@@ -15,8 +22,15 @@ impl NodeMetrics {
             node_id: node_id.into(),
             frames_processed: AtomicU64::new(0),
             errors_count: AtomicU64::new(0),
+            restarts_count: AtomicU64::new(0),
+            frames_dropped: AtomicU64::new(0),
+            frames_missing: AtomicU64::new(0),
+            frames_out_of_order: AtomicU64::new(0),
+            triggers: AtomicU64::new(0),
+            quality_issues: AtomicU64::new(0),
             total_latency_us: AtomicU64::new(0),
             latency_samples: AtomicU64::new(0),
+            frame_bytes: AtomicU64::new(0),
         }
     }
 
@@ -32,6 +46,42 @@ impl NodeMetrics {
         self.errors_count.load(Ordering::Relaxed)
     }
 
+    /// Times the node's task was restarted by a supervisor
+    pub fn restarts_count(&self) -> u64 {
+        self.restarts_count.load(Ordering::Relaxed)
+    }
+
+    /// Frames the node's lossy output edges discarded under backpressure
+    pub fn frames_dropped(&self) -> u64 {
+        self.frames_dropped.load(Ordering::Relaxed)
+    }
+
+    /// Frames missing from the sequences arriving on the node's inputs
+    pub fn frames_missing(&self) -> u64 {
+        self.frames_missing.load(Ordering::Relaxed)
+    }
+
+    /// Frames that arrived with an earlier sequence id than the one before them
+    pub fn frames_out_of_order(&self) -> u64 {
+        self.frames_out_of_order.load(Ordering::Relaxed)
+    }
+
+    /// Frames the node emitted with `triggered` metadata set
+    pub fn triggers(&self) -> u64 {
+        self.triggers.load(Ordering::Relaxed)
+    }
+
+    /// Frames the node flagged with `quality_issue` metadata, e.g. for
+    /// clipping or dropouts
+    pub fn quality_issues(&self) -> u64 {
+        self.quality_issues.load(Ordering::Relaxed)
+    }
+
+    /// Sample bytes of the last frame the node received
+    pub fn frame_bytes(&self) -> u64 {
+        self.frame_bytes.load(Ordering::Relaxed)
+    }
+
     pub fn record_frame_processed(&self) {
         self.frames_processed.fetch_add(1, Ordering::Relaxed);
     }
@@ -40,6 +90,34 @@ impl NodeMetrics {
         self.errors_count.fetch_add(1, Ordering::Relaxed);
     }
 
+    pub fn record_restart(&self) {
+        self.restarts_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_frames_dropped(&self, count: u64) {
+        self.frames_dropped.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn record_frames_missing(&self, count: u64) {
+        self.frames_missing.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn record_out_of_order(&self) {
+        self.frames_out_of_order.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_trigger(&self) {
+        self.triggers.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_quality_issue(&self) {
+        self.quality_issues.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_frame_bytes(&self, bytes: u64) {
+        self.frame_bytes.store(bytes, Ordering::Relaxed);
+    }
+
     pub fn start_processing(&self) -> Instant {
         Instant::now()
     }
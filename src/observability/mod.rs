@@ -1,7 +1,27 @@
 pub mod metrics;
 pub mod collector;
 pub mod monitor;
+pub mod drift;
+pub mod device_health;
+pub mod events;
+pub mod event_log;
+pub mod alerts;
+pub mod resources;
+#[cfg(feature = "streaming")]
+pub mod streaming;
+#[cfg(feature = "otel")]
+pub mod otel;
 
 pub use metrics::NodeMetrics;
-pub use collector::MetricsCollector;
+pub use collector::{MetricsCollector, MetricsSnapshot};
 pub use monitor::PipelineMonitor;
+pub use drift::{DriftMetrics, DriftSnapshot};
+pub use device_health::{DeviceHealth, DeviceHealthSnapshot};
+pub use events::{MetricsSampler, NodeThroughput, PipelineEvent, PipelineMetrics};
+pub use event_log::{list_sessions, read_events, read_session, EventKind, EventLog, EventQuery, LoggedEvent, SessionInfo};
+pub use alerts::{Alert, AlertConfig, AlertRouter, AlertSink, Severity, WebhookConfig, WebhookSink};
+pub use resources::{ChannelOccupancy, ChannelProbe, ChannelRegistry, PoolUsage, ResourceUsage};
+#[cfg(feature = "streaming")]
+pub use streaming::{StreamMessageKind, StreamingConfig, StreamingServer};
+#[cfg(feature = "otel")]
+pub use otel::OtlpExporter;
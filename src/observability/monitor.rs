@@ -1,12 +1,46 @@
-use super::MetricsCollector;
+use super::{MetricsCollector, NodeThroughput};
+use std::time::Duration;
 
 pub struct PipelineMonitor {
     collector: MetricsCollector,
+    elapsed: Option<Duration>,
 }
 
 impl PipelineMonitor {
     pub fn new(collector: MetricsCollector) -> Self {
-        Self { collector }
+        Self { collector, elapsed: None }
+    }
+
+    /// How long the pipeline has been running, used to average throughput
+    pub fn with_elapsed(mut self, elapsed: Duration) -> Self {
+        self.elapsed = Some(elapsed);
+        self
+    }
+
+    /// Per-node counters sorted by node id
+    ///
+    /// `frames_per_sec` is averaged over the whole run, and 0 without an elapsed time.
+    pub fn node_metrics(&self) -> Vec<NodeThroughput> {
+        let seconds = self.elapsed.map_or(0.0, |e| e.as_secs_f64());
+        let mut nodes: Vec<NodeThroughput> = self
+            .collector
+            .snapshot()
+            .into_values()
+            .map(|snapshot| {
+                let rate = if seconds > 0.0 { snapshot.frames_processed as f64 / seconds } else { 0.0 };
+                NodeThroughput::new(snapshot, rate)
+            })
+            .collect();
+        nodes.sort_by(|a, b| a.node_id.cmp(&b.node_id));
+        nodes
+    }
+
+    /// Trigger events of the pipeline
+    ///
+    /// The most counted by any one node, so a trigger gate behind a trigger
+    /// source does not count its events twice.
+    pub fn triggers(&self) -> u64 {
+        self.collector.snapshot().values().map(|s| s.triggers).max().unwrap_or(0)
     }
 
     pub fn generate_report(&self) -> String {
@@ -30,6 +64,24 @@ impl PipelineMonitor {
                 },
                 metrics.avg_latency_us
             ));
+            if metrics.restarts_count > 0 {
+                report.push_str(&format!("  Restarts: {}\n", metrics.restarts_count));
+            }
+            if metrics.frames_dropped > 0 {
+                report.push_str(&format!("  Dropped: {} frames\n", metrics.frames_dropped));
+            }
+            if metrics.triggers > 0 {
+                report.push_str(&format!("  Triggers: {}\n", metrics.triggers));
+            }
+            if metrics.quality_issues > 0 {
+                report.push_str(&format!("  Signal quality issues: {} frames\n", metrics.quality_issues));
+            }
+            if metrics.frames_missing > 0 || metrics.frames_out_of_order > 0 {
+                report.push_str(&format!(
+                    "  Sequence: {} missing, {} out of order\n",
+                    metrics.frames_missing, metrics.frames_out_of_order
+                ));
+            }
         }
 
         report
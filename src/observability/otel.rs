@@ -0,0 +1,70 @@
+//! Export of tracing spans to an OpenTelemetry collector over OTLP/HTTP
+//!
+//! With the layer installed, the spans the engine records show up as
+//! traces in Jaeger, Grafana Tempo or any other OTLP backend: `pipeline`
+//! spans around commands, a `node` span per running node with a
+//! `process` span per frame, and a `device` span per kernel device reader
+//! with a `packet` span per packet. Per-frame and per-packet spans are at
+//! DEBUG level, so the layer's filter decides whether they are exported.
+
+use anyhow::{Context, Result};
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::trace::{SdkTracer, SdkTracerProvider};
+use opentelemetry_sdk::Resource;
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Environment variables that name an OTLP endpoint, as read by `from_env`
+const ENDPOINT_VARS: [&str; 2] = ["OTEL_EXPORTER_OTLP_TRACES_ENDPOINT", "OTEL_EXPORTER_OTLP_ENDPOINT"];
+
+/// Sends spans in batches from a background thread
+///
+/// Dropping it sends the spans still buffered, so keep it alive until
+/// the program exits.
+pub struct OtlpExporter {
+    provider: SdkTracerProvider,
+}
+
+impl OtlpExporter {
+    /// Export spans as `service_name` to `endpoint`, the collector's
+    /// OTLP/HTTP traces URL such as `http://localhost:4318/v1/traces`
+    ///
+    /// Without an endpoint, the standard `OTEL_EXPORTER_OTLP_*` variables
+    /// apply, then the default `http://localhost:4318`.
+    pub fn new(service_name: &str, endpoint: Option<&str>) -> Result<Self> {
+        let mut builder = SpanExporter::builder().with_http();
+        if let Some(endpoint) = endpoint {
+            builder = builder.with_endpoint(endpoint);
+        }
+        let exporter = builder.build().context("Failed to create OTLP exporter")?;
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(Resource::builder().with_service_name(service_name.to_string()).build())
+            .build();
+        Ok(Self { provider })
+    }
+
+    /// Exporter for `service_name` if an `OTEL_EXPORTER_OTLP_*` endpoint is set
+    pub fn from_env(service_name: &str) -> Result<Option<Self>> {
+        if !ENDPOINT_VARS.iter().any(|var| std::env::var_os(var).is_some()) {
+            return Ok(None);
+        }
+        Self::new(service_name, None).map(Some)
+    }
+
+    /// Layer turning the subscriber's spans into exported OpenTelemetry spans
+    pub fn layer<S>(&self) -> OpenTelemetryLayer<S, SdkTracer>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        tracing_opentelemetry::layer().with_tracer(self.provider.tracer("audiotab"))
+    }
+}
+
+impl Drop for OtlpExporter {
+    fn drop(&mut self) {
+        let _ = self.provider.shutdown();
+    }
+}
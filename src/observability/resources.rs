@@ -0,0 +1,182 @@
+//! Memory accounting of a running pipeline
+//!
+//! Frames waiting in a channel keep their sample buffers alive, so a node
+//! that falls behind is where memory goes when capturing many channels.
+//! `ResourceUsage` reports the fill level of every node input and lossy
+//! edge queue with an estimate of the bytes queued there, the shared frame
+//! pool, and process and system memory where the platform reports them.
+
+use super::NodeMetrics;
+use crate::buffers::FramePool;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Reads a channel's `(queued, capacity)`, or `None` once it is closed
+pub type ChannelProbe = Arc<dyn Fn() -> Option<(usize, usize)> + Send + Sync>;
+
+/// Channels of a pipeline being watched; clones share the list
+#[derive(Clone, Default)]
+pub struct ChannelRegistry {
+    channels: Arc<Mutex<Vec<(String, String, ChannelProbe)>>>,
+}
+
+impl ChannelRegistry {
+    /// Watch `channel`, whose frames wait for `node_id`
+    ///
+    /// The probe should not keep the channel open; closed channels are
+    /// forgotten the next time the registry is read.
+    pub fn register(&self, channel: impl Into<String>, node_id: impl Into<String>, probe: ChannelProbe) {
+        let channel = channel.into();
+        let mut channels = self.channels.lock().unwrap_or_else(|p| p.into_inner());
+        channels.retain(|(name, _, _)| *name != channel);
+        channels.push((channel, node_id.into(), probe));
+    }
+
+    /// Frames queued for each node, over all its open channels
+    pub fn queued(&self) -> HashMap<String, usize> {
+        let channels = self.channels.lock().unwrap_or_else(|p| p.into_inner());
+        let mut queued = HashMap::new();
+        for (_, node_id, probe) in channels.iter() {
+            if let Some((frames, _)) = probe() {
+                *queued.entry(node_id.clone()).or_insert(0) += frames;
+            }
+        }
+        queued
+    }
+
+    /// Occupancy of the open channels, sized by the frames each node last received
+    pub(super) fn occupancy(&self, metrics: &HashMap<String, Arc<NodeMetrics>>) -> Vec<ChannelOccupancy> {
+        let mut channels = self.channels.lock().unwrap_or_else(|p| p.into_inner());
+        let mut occupancy = Vec::with_capacity(channels.len());
+        channels.retain(|(channel, node_id, probe)| {
+            let Some((queued, capacity)) = probe() else {
+                return false;
+            };
+            let frame_bytes = metrics.get(node_id).map(|m| m.frame_bytes()).unwrap_or(0);
+            occupancy.push(ChannelOccupancy {
+                channel: channel.clone(),
+                node_id: node_id.clone(),
+                queued,
+                capacity,
+                queued_bytes: queued as u64 * frame_bytes,
+            });
+            true
+        });
+        occupancy
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelOccupancy {
+    /// `<node>` for a node's input, `<from> -> <to>` for a lossy edge queue
+    pub channel: String,
+    /// Node the queued frames wait for
+    pub node_id: String,
+    pub queued: usize,
+    pub capacity: usize,
+    /// Queued frames times the size of the last frame the node received
+    pub queued_bytes: u64,
+}
+
+impl ChannelOccupancy {
+    /// Fill level from 0.0 to 1.0
+    pub fn fill(&self) -> f64 {
+        if self.capacity == 0 {
+            0.0
+        } else {
+            self.queued as f64 / self.capacity as f64
+        }
+    }
+}
+
+/// State of the process-wide `FramePool`
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolUsage {
+    pub idle_buffers: usize,
+    pub max_idle_buffers: usize,
+    /// Buffers allocated because the pool was empty
+    pub allocations: usize,
+}
+
+impl PoolUsage {
+    pub fn global() -> Self {
+        let pool = FramePool::global();
+        Self {
+            idle_buffers: pool.available(),
+            max_idle_buffers: pool.capacity(),
+            allocations: pool.allocations(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceUsage {
+    /// Frames queued in all channels
+    pub frames_in_flight: usize,
+    /// Estimated sample bytes of those frames
+    pub bytes_in_flight: u64,
+    /// Open channels, fullest first
+    pub channels: Vec<ChannelOccupancy>,
+    pub frame_pool: PoolUsage,
+    pub process_rss_bytes: Option<u64>,
+    pub system_total_bytes: Option<u64>,
+    pub system_available_bytes: Option<u64>,
+}
+
+impl ResourceUsage {
+    pub(super) fn new(mut channels: Vec<ChannelOccupancy>) -> Self {
+        channels.sort_by(|a, b| b.fill().total_cmp(&a.fill()).then_with(|| a.channel.cmp(&b.channel)));
+        let memory = SystemMemory::read();
+        Self {
+            frames_in_flight: channels.iter().map(|c| c.queued).sum(),
+            bytes_in_flight: channels.iter().map(|c| c.queued_bytes).sum(),
+            channels,
+            frame_pool: PoolUsage::global(),
+            process_rss_bytes: memory.process_rss,
+            system_total_bytes: memory.total,
+            system_available_bytes: memory.available,
+        }
+    }
+
+    /// Share of system memory still available, if the platform reports it
+    pub fn memory_headroom(&self) -> Option<f64> {
+        match (self.system_available_bytes, self.system_total_bytes) {
+            (Some(available), Some(total)) if total > 0 => Some(available as f64 / total as f64),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Default)]
+struct SystemMemory {
+    process_rss: Option<u64>,
+    total: Option<u64>,
+    available: Option<u64>,
+}
+
+impl SystemMemory {
+    #[cfg(target_os = "linux")]
+    fn read() -> Self {
+        let status = std::fs::read_to_string("/proc/self/status").unwrap_or_default();
+        let meminfo = std::fs::read_to_string("/proc/meminfo").unwrap_or_default();
+        Self {
+            process_rss: kib_field(&status, "VmRSS:"),
+            total: kib_field(&meminfo, "MemTotal:"),
+            available: kib_field(&meminfo, "MemAvailable:"),
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn read() -> Self {
+        Self::default()
+    }
+}
+
+/// Bytes of a `<key> <n> kB` line in a /proc file
+#[cfg(target_os = "linux")]
+fn kib_field(text: &str, key: &str) -> Option<u64> {
+    let line = text.lines().find(|line| line.starts_with(key))?;
+    let kib: u64 = line[key.len()..].split_whitespace().next()?.parse().ok()?;
+    Some(kib * 1024)
+}
@@ -0,0 +1,235 @@
+use super::PipelineEvent;
+use crate::visualization::RingBufferWriter;
+use anyhow::{Context, Result};
+use futures_util::{SinkExt, StreamExt};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+
+/// First bytes of every streamed message
+pub const STREAM_MAGIC: &[u8; 4] = b"ATWS";
+
+/// Protocol version, byte 4 of every message
+pub const STREAM_PROTOCOL_VERSION: u8 = 1;
+
+/// Bytes before a waveform message's min/max data
+pub const WAVEFORM_HEADER_SIZE: usize = 40;
+
+/// Bytes before an event message's JSON body
+pub const EVENT_HEADER_SIZE: usize = 8;
+
+/// Messages buffered per client; slower clients skip the oldest
+const CLIENT_BACKLOG: usize = 16;
+
+/// Message type, byte 5 of every message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum StreamMessageKind {
+    /// Decimated waveform of the visualization ring buffer
+    Waveform = 1,
+    /// A `PipelineEvent` (metrics, state changes, node errors) as JSON
+    Event = 2,
+}
+
+/// What the streaming server sends and how often
+#[derive(Debug, Clone)]
+pub struct StreamingConfig {
+    /// Span of audio in each waveform message
+    pub window: Duration,
+    /// Min/max pairs per channel in each waveform message
+    pub points: usize,
+    /// Time between waveform messages
+    pub interval: Duration,
+}
+
+impl Default for StreamingConfig {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_secs(1),
+            points: 1000,
+            interval: Duration::from_millis(100),
+        }
+    }
+}
+
+fn message_header(kind: StreamMessageKind, capacity: usize) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(capacity);
+    bytes.extend_from_slice(STREAM_MAGIC);
+    bytes.push(STREAM_PROTOCOL_VERSION);
+    bytes.push(kind as u8);
+    bytes.extend_from_slice(&0u16.to_le_bytes());
+    bytes
+}
+
+/// Encode a waveform message
+///
+/// Little-endian layout after the common 8-byte header: sample rate (f64),
+/// commit position of the newest sample (u64), channels (u32), points
+/// (u32), samples per point (u32), reserved (u32); then for each channel
+/// `points` pairs of (min, max) as f32. Each point covers `samples per
+/// point` consecutive samples, oldest first; the last may cover fewer.
+pub fn encode_waveform(sample_rate: f64, position: u64, samples: &[Vec<f64>], points: usize) -> Vec<u8> {
+    let len = samples.iter().map(Vec::len).min().unwrap_or(0);
+    let per_point = len.div_ceil(points.max(1)).max(1);
+    let points = len.div_ceil(per_point);
+
+    let mut bytes = message_header(StreamMessageKind::Waveform, WAVEFORM_HEADER_SIZE + samples.len() * points * 8);
+    bytes.extend_from_slice(&sample_rate.to_le_bytes());
+    bytes.extend_from_slice(&position.to_le_bytes());
+    bytes.extend_from_slice(&(samples.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&(points as u32).to_le_bytes());
+    bytes.extend_from_slice(&(per_point as u32).to_le_bytes());
+    bytes.extend_from_slice(&0u32.to_le_bytes());
+    for channel in samples {
+        for chunk in channel[..len].chunks(per_point) {
+            let (min, max) = chunk
+                .iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &s| (lo.min(s), hi.max(s)));
+            bytes.extend_from_slice(&(min as f32).to_le_bytes());
+            bytes.extend_from_slice(&(max as f32).to_le_bytes());
+        }
+    }
+    bytes
+}
+
+/// Encode an event message: the common header followed by the event as JSON
+pub fn encode_event(event: &PipelineEvent) -> Result<Vec<u8>> {
+    let json = serde_json::to_vec(event)?;
+    let mut bytes = message_header(StreamMessageKind::Event, EVENT_HEADER_SIZE + json.len());
+    bytes.extend_from_slice(&json);
+    Ok(bytes)
+}
+
+/// WebSocket server pushing waveforms and pipeline events to remote viewers
+///
+/// Every client receives the same binary messages (see `encode_waveform`
+/// and `encode_event`); messages sent by clients are ignored. A client that
+/// falls behind skips messages rather than slowing the others. The server
+/// stops when dropped.
+pub struct StreamingServer {
+    local_addr: SocketAddr,
+    clients: Arc<AtomicUsize>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl StreamingServer {
+    /// Listen on `addr` and stream `ring` and `events`, either of which may be absent
+    pub async fn bind(
+        addr: impl ToSocketAddrs,
+        ring: Option<Arc<RingBufferWriter>>,
+        events: Option<broadcast::Receiver<PipelineEvent>>,
+        config: StreamingConfig,
+    ) -> Result<Self> {
+        let listener = TcpListener::bind(addr).await.context("Failed to bind streaming server")?;
+        let local_addr = listener.local_addr()?;
+        let (messages, _) = broadcast::channel::<Arc<Vec<u8>>>(CLIENT_BACKLOG);
+        let clients = Arc::new(AtomicUsize::new(0));
+
+        let mut tasks = vec![tokio::spawn(accept_clients(listener, messages.clone(), clients.clone()))];
+        if let Some(ring) = ring {
+            tasks.push(tokio::spawn(stream_waveforms(ring, messages.clone(), config)));
+        }
+        if let Some(events) = events {
+            tasks.push(tokio::spawn(stream_events(events, messages)));
+        }
+
+        Ok(Self { local_addr, clients, tasks })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Clients currently connected
+    pub fn client_count(&self) -> usize {
+        self.clients.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for StreamingServer {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+async fn accept_clients(listener: TcpListener, messages: broadcast::Sender<Arc<Vec<u8>>>, clients: Arc<AtomicUsize>) {
+    let mut connections = tokio::task::JoinSet::new();
+    loop {
+        let Ok((stream, _)) = listener.accept().await else {
+            continue;
+        };
+        let receiver = messages.subscribe();
+        let clients = clients.clone();
+        // Owned by the set, so connections close when the server stops
+        connections.spawn(async move {
+            clients.fetch_add(1, Ordering::Relaxed);
+            let _ = serve_client(stream, receiver).await;
+            clients.fetch_sub(1, Ordering::Relaxed);
+        });
+        while connections.try_join_next().is_some() {}
+    }
+}
+
+async fn serve_client(stream: TcpStream, mut messages: broadcast::Receiver<Arc<Vec<u8>>>) -> Result<()> {
+    let (mut sink, mut incoming) = tokio_tungstenite::accept_async(stream).await?.split();
+    loop {
+        tokio::select! {
+            message = messages.recv() => match message {
+                Ok(bytes) => sink.send(Message::Binary(bytes.as_ref().clone())).await?,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            // Reading also answers pings
+            message = incoming.next() => match message {
+                Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    let _ = sink.close().await;
+    Ok(())
+}
+
+async fn stream_waveforms(ring: Arc<RingBufferWriter>, messages: broadcast::Sender<Arc<Vec<u8>>>, config: StreamingConfig) {
+    let window = (config.window.as_secs_f64() * ring.sample_rate() as f64).round() as usize;
+    let mut ticker = tokio::time::interval(config.interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    let mut last_position = None;
+    loop {
+        ticker.tick().await;
+        if messages.receiver_count() == 0 {
+            continue;
+        }
+        let Ok((position, samples)) = ring.read_latest(window) else {
+            continue;
+        };
+        // Nothing new since the last message
+        if position == 0 || last_position == Some(position) {
+            continue;
+        }
+        last_position = Some(position);
+        let bytes = encode_waveform(ring.sample_rate() as f64, position, &samples, config.points);
+        let _ = messages.send(Arc::new(bytes));
+    }
+}
+
+async fn stream_events(mut events: broadcast::Receiver<PipelineEvent>, messages: broadcast::Sender<Arc<Vec<u8>>>) {
+    loop {
+        match events.recv().await {
+            Ok(event) => {
+                if let Ok(bytes) = encode_event(&event) {
+                    let _ = messages.send(Arc::new(bytes));
+                }
+            }
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
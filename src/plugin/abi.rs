@@ -0,0 +1,102 @@
+//! C ABI shared by the host and plugin libraries
+//!
+//! A plugin is a shared library exporting [`ENTRY_SYMBOL`]:
+//!
+//! ```c
+//! const AudiotabPlugin *audiotab_plugin_entry(void);
+//! ```
+//!
+//! The returned table must stay valid while the library is loaded. Strings
+//! are NUL-terminated UTF-8; pointers the host passes in are only valid for
+//! the duration of the call.
+
+use std::ffi::{c_char, c_void};
+
+/// Version of this ABI; plugins built against another version are rejected
+pub const ABI_VERSION: u32 = 1;
+
+/// Name of the function every plugin exports
+pub const ENTRY_SYMBOL: &str = "audiotab_plugin_entry";
+
+/// Return code of a successful call
+pub const STATUS_OK: i32 = 0;
+
+/// Signature of [`ENTRY_SYMBOL`]
+pub type PluginEntry = unsafe extern "C" fn() -> *const PluginVTable;
+
+/// Functions a plugin provides
+#[repr(C)]
+pub struct PluginVTable {
+    /// Must equal [`ABI_VERSION`]
+    pub abi_version: u32,
+    /// JSON array describing the plugin's node types, owned by the plugin
+    ///
+    /// Each entry has `id`, `name` and `category`, and optionally `version`,
+    /// `deprecated`, `inputs`, `outputs`, `parameters` (in the registry's
+    /// JSON form) and `presets` (`[{"name", "parameters"}]`).
+    pub describe: unsafe extern "C" fn() -> *const c_char,
+    /// Create an instance of `node_type` from a JSON config
+    ///
+    /// Returns null on failure, after reporting the reason with `host.set_error`.
+    pub create: unsafe extern "C" fn(
+        node_type: *const c_char,
+        config_json: *const c_char,
+        host: *const HostApi,
+        call: *mut HostCall,
+    ) -> *mut c_void,
+    /// Process one frame
+    ///
+    /// The output starts as a copy of `input`; the plugin edits it through
+    /// `host`. Returns [`STATUS_OK`], or any other value after reporting the
+    /// reason with `host.set_error`.
+    pub process: unsafe extern "C" fn(
+        instance: *mut c_void,
+        input: *const PluginFrame,
+        host: *const HostApi,
+        call: *mut HostCall,
+    ) -> i32,
+    /// Free an instance returned by `create`
+    pub destroy: unsafe extern "C" fn(instance: *mut c_void),
+}
+
+/// A channel of the input frame
+#[repr(C)]
+pub struct PluginChannel {
+    pub name: *const c_char,
+    pub samples: *const f64,
+    pub len: usize,
+    /// Sample rate in Hz, or 0 when the channel has none of its own
+    pub sample_rate: f64,
+}
+
+/// The frame passed to `process`
+#[repr(C)]
+pub struct PluginFrame {
+    pub timestamp: u64,
+    pub sequence_id: u64,
+    pub channels: *const PluginChannel,
+    pub channel_count: usize,
+    /// Frame metadata as a JSON object
+    pub metadata_json: *const c_char,
+}
+
+/// Host state of one plugin call; opaque to plugins
+pub struct HostCall {
+    pub(crate) frame: crate::core::DataFrame,
+    pub(crate) error: Option<String>,
+}
+
+/// Functions the host provides to plugins during a call
+#[repr(C)]
+pub struct HostApi {
+    /// Replace or add an output channel; samples are copied, and a replaced
+    /// channel keeps its sample rate and unit
+    pub set_channel: unsafe extern "C" fn(call: *mut HostCall, name: *const c_char, samples: *const f64, len: usize),
+    /// Remove an output channel if present
+    pub remove_channel: unsafe extern "C" fn(call: *mut HostCall, name: *const c_char),
+    /// Set output metadata to a JSON bool, number or string; returns
+    /// [`STATUS_OK`] on success
+    pub set_metadata: unsafe extern "C" fn(call: *mut HostCall, key: *const c_char, value_json: *const c_char) -> i32,
+    /// Report why the call failed
+    pub set_error: unsafe extern "C" fn(call: *mut HostCall, message: *const c_char),
+}
@@ -0,0 +1,331 @@
+//! Third-party node libraries loaded at startup through a stable C ABI
+//!
+//! A plugin describes its node types as JSON; loading it registers them
+//! with [`NodeMetadata::register`], after which pipelines instantiate them
+//! by id like built-in nodes. See [`abi`] for the interface a plugin exports.
+
+pub mod abi;
+
+use crate::core::{Channel, DataFrame, MetadataValue, ProcessingNode};
+use crate::registry::{NodeMetadata, NodePreset, ParameterSchema, PortMetadata};
+use abi::{HostApi, HostCall, PluginChannel, PluginEntry, PluginFrame, PluginVTable, ABI_VERSION, ENTRY_SYMBOL, STATUS_OK};
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// A node type as described by a plugin
+#[derive(Deserialize)]
+struct NodeDescriptor {
+    id: String,
+    name: String,
+    category: String,
+    #[serde(default = "default_version")]
+    version: u32,
+    #[serde(default)]
+    deprecated: bool,
+    #[serde(default)]
+    inputs: Vec<PortMetadata>,
+    #[serde(default)]
+    outputs: Vec<PortMetadata>,
+    #[serde(default)]
+    parameters: Vec<ParameterSchema>,
+    #[serde(default)]
+    presets: Vec<PresetDescriptor>,
+}
+
+#[derive(Deserialize)]
+struct PresetDescriptor {
+    name: String,
+    parameters: Value,
+}
+
+fn default_version() -> u32 {
+    1
+}
+
+/// A loaded plugin library
+pub struct Plugin {
+    name: String,
+    vtable: *const PluginVTable,
+    /// Keeps the code behind `vtable` mapped; `None` for statically linked plugins
+    _library: Option<libloading::Library>,
+}
+
+// Plugins must allow their functions to be called from any thread; each
+// instance is only used by one thread at a time.
+unsafe impl Send for Plugin {}
+unsafe impl Sync for Plugin {}
+
+impl Plugin {
+    /// Load a plugin library and check its ABI version
+    pub fn load(path: impl AsRef<Path>) -> Result<Arc<Plugin>> {
+        let path = path.as_ref();
+        // Loading runs the library's initializers; plugins are trusted code
+        let library = unsafe { libloading::Library::new(path) }
+            .with_context(|| format!("Failed to load plugin {}", path.display()))?;
+        let vtable = unsafe {
+            let entry = library
+                .get::<PluginEntry>(ENTRY_SYMBOL.as_bytes())
+                .with_context(|| format!("{} does not export {}", path.display(), ENTRY_SYMBOL))?;
+            entry()
+        };
+        let name = path.file_stem().map_or_else(|| path.display().to_string(), |s| s.to_string_lossy().into_owned());
+        unsafe { Self::new(name, vtable, Some(library)) }
+    }
+
+    /// Wrap a plugin linked into the host binary
+    ///
+    /// # Safety
+    /// `vtable` must point to a table that follows the [`abi`] contract and
+    /// stays valid for the rest of the process.
+    pub unsafe fn from_vtable(name: impl Into<String>, vtable: *const PluginVTable) -> Result<Arc<Plugin>> {
+        Self::new(name.into(), vtable, None)
+    }
+
+    unsafe fn new(name: String, vtable: *const PluginVTable, library: Option<libloading::Library>) -> Result<Arc<Plugin>> {
+        if vtable.is_null() {
+            bail!("Plugin '{}' returned no function table", name);
+        }
+        let version = (*vtable).abi_version;
+        if version != ABI_VERSION {
+            bail!("Plugin '{}' uses ABI version {}, but only version {} is supported", name, version, ABI_VERSION);
+        }
+        Ok(Arc::new(Plugin { name, vtable, _library: library }))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn vtable(&self) -> &PluginVTable {
+        unsafe { &*self.vtable }
+    }
+
+    /// Metadata of the node types this plugin provides
+    pub fn node_types(self: &Arc<Self>) -> Result<Vec<NodeMetadata>> {
+        let json = unsafe { (self.vtable().describe)() };
+        if json.is_null() {
+            bail!("Plugin '{}' returned no node descriptions", self.name);
+        }
+        let json = unsafe { CStr::from_ptr(json) }.to_string_lossy();
+        let descriptors: Vec<NodeDescriptor> = serde_json::from_str(&json)
+            .with_context(|| format!("Plugin '{}' returned invalid node descriptions", self.name))?;
+
+        Ok(descriptors
+            .into_iter()
+            .map(|d| {
+                let plugin = self.clone();
+                let node_type = d.id.clone();
+                NodeMetadata {
+                    presets: d
+                        .presets
+                        .into_iter()
+                        .map(|p| NodePreset { builtin: true, ..NodePreset::new(&d.id, p.name, p.parameters) })
+                        .collect(),
+                    id: d.id,
+                    name: d.name,
+                    category: d.category,
+                    version: d.version,
+                    deprecated: d.deprecated,
+                    inputs: d.inputs,
+                    outputs: d.outputs,
+                    parameters: d.parameters,
+                    factory: Arc::new(move || Box::new(PluginNode::new(plugin.clone(), &node_type))),
+                }
+            })
+            .collect())
+    }
+
+    /// Register this plugin's node types, returning their ids
+    pub fn register(self: &Arc<Self>) -> Result<Vec<String>> {
+        let mut ids = Vec::new();
+        for meta in self.node_types()? {
+            ids.push(meta.id.clone());
+            NodeMetadata::register(meta).with_context(|| format!("Plugin '{}'", self.name))?;
+        }
+        Ok(ids)
+    }
+}
+
+/// Load and register every plugin library in `dir`
+pub fn load_dir(dir: impl AsRef<Path>) -> Result<Vec<Arc<Plugin>>> {
+    let dir = dir.as_ref();
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read plugin directory {}", dir.display()))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<_>>()?;
+    paths.retain(|p| p.extension().is_some_and(|ext| ext == std::env::consts::DLL_EXTENSION));
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let plugin = Plugin::load(&path)?;
+            plugin.register()?;
+            Ok(plugin)
+        })
+        .collect()
+}
+
+static HOST_API: HostApi = HostApi {
+    set_channel: host_set_channel,
+    remove_channel: host_remove_channel,
+    set_metadata: host_set_metadata,
+    set_error: host_set_error,
+};
+
+unsafe fn host_str(s: *const c_char) -> String {
+    if s.is_null() {
+        String::new()
+    } else {
+        CStr::from_ptr(s).to_string_lossy().into_owned()
+    }
+}
+
+unsafe extern "C" fn host_set_channel(call: *mut HostCall, name: *const c_char, samples: *const f64, len: usize) {
+    let call = &mut *call;
+    let name = host_str(name);
+    let samples = if len == 0 || samples.is_null() {
+        Vec::new()
+    } else {
+        std::slice::from_raw_parts(samples, len).to_vec()
+    };
+    let channel = match call.frame.payload.get(&name) {
+        Some(existing) => existing.with_samples(samples),
+        None => Channel::new(samples),
+    };
+    call.frame.insert_channel(name, channel);
+}
+
+unsafe extern "C" fn host_remove_channel(call: *mut HostCall, name: *const c_char) {
+    (*call).frame.payload.remove(&host_str(name));
+}
+
+unsafe extern "C" fn host_set_metadata(call: *mut HostCall, key: *const c_char, value_json: *const c_char) -> i32 {
+    let call = &mut *call;
+    let key = host_str(key);
+    match serde_json::from_str::<MetadataValue>(&host_str(value_json)) {
+        Ok(value) => {
+            call.frame.metadata.insert(key, value);
+            STATUS_OK
+        }
+        Err(e) => {
+            call.error = Some(format!("Invalid value for metadata '{}': {}", key, e));
+            1
+        }
+    }
+}
+
+unsafe extern "C" fn host_set_error(call: *mut HostCall, message: *const c_char) {
+    (*call).error = Some(host_str(message));
+}
+
+/// Node backed by a plugin instance
+pub struct PluginNode {
+    plugin: Arc<Plugin>,
+    node_type: String,
+    instance: *mut c_void,
+}
+
+// The instance is only touched through `&mut self`
+unsafe impl Send for PluginNode {}
+unsafe impl Sync for PluginNode {}
+
+impl PluginNode {
+    fn new(plugin: Arc<Plugin>, node_type: &str) -> Self {
+        Self { plugin, node_type: node_type.to_string(), instance: std::ptr::null_mut() }
+    }
+
+    fn call_error(&self, call: HostCall, status: Option<i32>) -> anyhow::Error {
+        let reason = call.error.unwrap_or_else(|| match status {
+            Some(status) => format!("failed with status {}", status),
+            None => "failed".to_string(),
+        });
+        anyhow!("Plugin node {}: {}", self.node_type, reason)
+    }
+
+    fn create(&self, config: &Value) -> Result<*mut c_void> {
+        let node_type = CString::new(self.node_type.as_str())?;
+        let config = CString::new(config.to_string())?;
+        let mut call = HostCall { frame: DataFrame::new(0, 0), error: None };
+        let instance = unsafe { (self.plugin.vtable().create)(node_type.as_ptr(), config.as_ptr(), &HOST_API, &mut call) };
+        if instance.is_null() {
+            return Err(self.call_error(call, None));
+        }
+        Ok(instance)
+    }
+
+    fn destroy(&mut self) {
+        if !self.instance.is_null() {
+            unsafe { (self.plugin.vtable().destroy)(self.instance) };
+            self.instance = std::ptr::null_mut();
+        }
+    }
+}
+
+#[async_trait]
+impl ProcessingNode for PluginNode {
+    async fn on_create(&mut self, config: Value) -> Result<()> {
+        // Create first so a rejected config leaves the current instance running
+        let instance = self.create(&config)?;
+        self.destroy();
+        self.instance = instance;
+        Ok(())
+    }
+
+    async fn process(&mut self, frame: DataFrame) -> Result<DataFrame> {
+        if self.instance.is_null() {
+            self.instance = self.create(&Value::Object(Default::default()))?;
+        }
+
+        let named: Vec<(CString, &Channel)> = frame
+            .payload
+            .iter()
+            .map(|(name, channel)| Ok((CString::new(name.as_str())?, channel)))
+            .collect::<Result<_>>()?;
+        let channels: Vec<PluginChannel> = named
+            .iter()
+            .map(|(name, channel)| PluginChannel {
+                name: name.as_ptr(),
+                samples: channel.samples().as_ptr(),
+                len: channel.samples().len(),
+                sample_rate: channel.sample_rate.unwrap_or(0.0),
+            })
+            .collect();
+        let metadata: serde_json::Map<String, Value> = frame
+            .metadata
+            .iter()
+            .map(|(k, v)| Ok((k.clone(), serde_json::to_value(v)?)))
+            .collect::<Result<_>>()?;
+        let metadata = CString::new(Value::Object(metadata).to_string())?;
+        let input = PluginFrame {
+            timestamp: frame.timestamp,
+            sequence_id: frame.sequence_id,
+            channels: channels.as_ptr(),
+            channel_count: channels.len(),
+            metadata_json: metadata.as_ptr(),
+        };
+
+        let mut call = HostCall { frame: frame.clone(), error: None };
+        let status = unsafe { (self.plugin.vtable().process)(self.instance, &input, &HOST_API, &mut call) };
+        if status != STATUS_OK {
+            return Err(self.call_error(call, Some(status)));
+        }
+        Ok(call.frame)
+    }
+
+    async fn on_destroy(&mut self) -> Result<()> {
+        self.destroy();
+        Ok(())
+    }
+}
+
+impl Drop for PluginNode {
+    fn drop(&mut self) {
+        self.destroy();
+    }
+}
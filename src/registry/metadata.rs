@@ -1,5 +1,8 @@
 use crate::core::ProcessingNode;
+use super::NodePreset;
+use anyhow::{bail, Result};
 use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
 
 /// Metadata describing a port (input or output)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -7,6 +10,62 @@ pub struct PortMetadata {
     pub id: String,
     pub name: String,
     pub data_type: String,
+    #[serde(default)]
+    pub multiplicity: PortMultiplicity,
+}
+
+/// How many instances of a port a node exposes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum PortMultiplicity {
+    #[default]
+    Single,
+    /// Between `min` and `max` instances, chosen per node in the graph
+    Variadic { min: usize, max: usize },
+}
+
+impl PortMetadata {
+    pub fn is_variadic(&self) -> bool {
+        matches!(self.multiplicity, PortMultiplicity::Variadic { .. })
+    }
+
+    /// Ids of the concrete port instances: `id` for single ports,
+    /// `id_0`, `id_1`, ... for variadic ports (`min` instances when `count` is None)
+    pub fn instance_ids(&self, count: Option<usize>) -> Result<Vec<String>> {
+        match self.multiplicity {
+            PortMultiplicity::Single => {
+                if let Some(n) = count {
+                    if n != 1 {
+                        bail!("Port '{}' is not variadic, cannot instantiate {} ports", self.id, n);
+                    }
+                }
+                Ok(vec![self.id.clone()])
+            }
+            PortMultiplicity::Variadic { min, max } => {
+                let n = count.unwrap_or(min);
+                if n < min || n > max {
+                    bail!("Port '{}' must have between {} and {} instances, got {}", self.id, min, max, n);
+                }
+                Ok((0..n).map(|i| format!("{}_{}", self.id, i)).collect())
+            }
+        }
+    }
+}
+
+fn expand_ports(ports: &[PortMetadata], counts: &serde_json::Value) -> Result<Vec<String>> {
+    let mut ids = Vec::new();
+    for port in ports {
+        let count = match counts.get(&port.id) {
+            Some(v) => Some(
+                v.as_u64()
+                    .ok_or_else(|| anyhow::anyhow!("Port count for '{}' must be a non-negative integer", port.id))?
+                    as usize,
+            ),
+            None => None,
+        };
+        ids.extend(port.instance_ids(count)?);
+    }
+    Ok(ids)
 }
 
 /// Schema for a configurable parameter
@@ -20,10 +79,22 @@ pub struct ParameterSchema {
     pub min: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max: Option<f64>,
+    /// Allowed values for "enum" parameters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub choices: Option<Vec<String>>,
+    /// Display unit, e.g. "Hz" or "dB"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    /// Increment used by UI controls
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step: Option<f64>,
+    /// Control scale hint ("log" for logarithmic controls)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale: Option<String>,
 }
 
 /// Factory function type for creating node instances
-pub type NodeFactory = fn() -> Box<dyn ProcessingNode>;
+pub type NodeFactory = Arc<dyn Fn() -> Box<dyn ProcessingNode> + Send + Sync>;
 
 /// Complete metadata for a node type
 #[derive(Clone)]
@@ -31,9 +102,13 @@ pub struct NodeMetadata {
     pub id: String,
     pub name: String,
     pub category: String,
+    /// Implementation version; saved configs from older versions are migrated on load
+    pub version: u32,
+    pub deprecated: bool,
     pub inputs: Vec<PortMetadata>,
     pub outputs: Vec<PortMetadata>,
     pub parameters: Vec<ParameterSchema>,
+    pub presets: Vec<NodePreset>,
     pub factory: NodeFactory,
 }
 
@@ -47,15 +122,28 @@ impl NodeMetadata {
             id: id.into(),
             name: name.into(),
             category: category.into(),
+            version: 1,
+            deprecated: false,
             inputs: Vec::new(),
             outputs: Vec::new(),
             parameters: Vec::new(),
-            factory: || panic!("No factory set"),
+            presets: Vec::new(),
+            factory: Arc::new(|| panic!("No factory set")),
         }
     }
 
-    pub fn with_factory(mut self, factory: NodeFactory) -> Self {
-        self.factory = factory;
+    pub fn with_factory(mut self, factory: impl Fn() -> Box<dyn ProcessingNode> + Send + Sync + 'static) -> Self {
+        self.factory = Arc::new(factory);
+        self
+    }
+
+    pub fn with_version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    pub fn deprecated(mut self) -> Self {
+        self.deprecated = true;
         self
     }
 
@@ -64,6 +152,24 @@ impl NodeMetadata {
             id: id.into(),
             name: name.into(),
             data_type: data_type.into(),
+            multiplicity: PortMultiplicity::Single,
+        });
+        self
+    }
+
+    pub fn add_variadic_input(
+        mut self,
+        id: impl Into<String>,
+        name: impl Into<String>,
+        data_type: impl Into<String>,
+        min: usize,
+        max: usize,
+    ) -> Self {
+        self.inputs.push(PortMetadata {
+            id: id.into(),
+            name: name.into(),
+            data_type: data_type.into(),
+            multiplicity: PortMultiplicity::Variadic { min, max },
         });
         self
     }
@@ -73,6 +179,24 @@ impl NodeMetadata {
             id: id.into(),
             name: name.into(),
             data_type: data_type.into(),
+            multiplicity: PortMultiplicity::Single,
+        });
+        self
+    }
+
+    pub fn add_variadic_output(
+        mut self,
+        id: impl Into<String>,
+        name: impl Into<String>,
+        data_type: impl Into<String>,
+        min: usize,
+        max: usize,
+    ) -> Self {
+        self.outputs.push(PortMetadata {
+            id: id.into(),
+            name: name.into(),
+            data_type: data_type.into(),
+            multiplicity: PortMultiplicity::Variadic { min, max },
         });
         self
     }
@@ -82,12 +206,89 @@ impl NodeMetadata {
         self
     }
 
+    pub fn add_preset(mut self, name: impl Into<String>, parameters: serde_json::Value) -> Self {
+        self.presets.push(NodePreset {
+            node_type: self.id.clone(),
+            name: name.into(),
+            parameters,
+            builtin: true,
+        });
+        self
+    }
+
     /// Create a new instance of this node type
     pub fn create_instance(&self) -> Box<dyn ProcessingNode> {
         (self.factory)()
     }
+
+    /// Expand input and output ports into instance ids using per-port counts
+    /// from graph JSON (e.g. `{"inputs": 4}`); unknown port ids are rejected.
+    pub fn instantiate_ports(&self, counts: &serde_json::Value) -> Result<(Vec<String>, Vec<String>)> {
+        if let Some(map) = counts.as_object() {
+            let known = |k: &String| self.inputs.iter().chain(self.outputs.iter()).any(|p| &p.id == k);
+            if let Some(unknown) = map.keys().find(|k| !known(k)) {
+                bail!("Unknown port '{}'", unknown);
+            }
+        }
+        Ok((expand_ports(&self.inputs, counts)?, expand_ports(&self.outputs, counts)?))
+    }
+
+    /// Metadata of every registered node type, ordered by id
+    pub fn all() -> Vec<NodeMetadata> {
+        let mut all: Vec<NodeMetadata> = inventory::iter::<NodeMetadataFactoryWrapper>
+            .into_iter()
+            .map(|wrapper| (wrapper.0)())
+            .chain(RUNTIME_NODES.read().unwrap().iter().cloned())
+            .collect();
+        all.sort_by(|a, b| a.id.cmp(&b.id));
+        all
+    }
+
+    /// Whether edges into this node must name the input they feed: it has
+    /// several inputs or a variadic one
+    pub fn names_inputs(&self) -> bool {
+        self.inputs.len() > 1 || self.inputs.iter().any(PortMetadata::is_variadic)
+    }
+
+    /// Whether edges out of this node must name the output they leave from
+    pub fn names_outputs(&self) -> bool {
+        self.outputs.len() > 1 || self.outputs.iter().any(PortMetadata::is_variadic)
+    }
+
+    /// Look up registered metadata by node type name (case-insensitive, e.g. "GainNode")
+    pub fn find(node_type: &str) -> Option<NodeMetadata> {
+        inventory::iter::<NodeMetadataFactoryWrapper>
+            .into_iter()
+            .map(|wrapper| (wrapper.0)())
+            .find(|meta| meta.id.eq_ignore_ascii_case(node_type))
+            .or_else(|| {
+                RUNTIME_NODES
+                    .read()
+                    .unwrap()
+                    .iter()
+                    .find(|meta| meta.id.eq_ignore_ascii_case(node_type))
+                    .cloned()
+            })
+    }
+
+    /// Register a node type at runtime (e.g. from a plugin) so pipelines can
+    /// instantiate it through its factory; ids must not clash with existing types
+    pub fn register(meta: NodeMetadata) -> Result<()> {
+        let mut nodes = RUNTIME_NODES.write().unwrap();
+        let builtin = inventory::iter::<NodeMetadataFactoryWrapper>
+            .into_iter()
+            .any(|wrapper| (wrapper.0)().id.eq_ignore_ascii_case(&meta.id));
+        if builtin || nodes.iter().any(|n| n.id.eq_ignore_ascii_case(&meta.id)) {
+            bail!("Node type '{}' is already registered", meta.id);
+        }
+        nodes.push(meta);
+        Ok(())
+    }
 }
 
+/// Node types registered with `NodeMetadata::register`
+static RUNTIME_NODES: RwLock<Vec<NodeMetadata>> = RwLock::new(Vec::new());
+
 // Factory type for creating node metadata at runtime
 pub type NodeMetadataFactory = fn() -> NodeMetadata;
 
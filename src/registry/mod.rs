@@ -1,3 +1,9 @@
 pub mod metadata;
+pub mod params;
+pub mod preset;
+pub mod schema;
 
-pub use metadata::{NodeMetadata, PortMetadata, ParameterSchema, NodeFactory, NodeMetadataFactory, NodeMetadataFactoryWrapper};
+pub use metadata::{NodeMetadata, PortMetadata, PortMultiplicity, ParameterSchema, NodeFactory, NodeMetadataFactory, NodeMetadataFactoryWrapper};
+pub use params::ParamParser;
+pub use preset::{NodePreset, PresetStore};
+pub use schema::{hardware_config_schema, node_schemas, pipeline_schema};
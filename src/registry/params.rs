@@ -0,0 +1,98 @@
+use anyhow::Result;
+use serde_json::Value;
+use std::any::{Any, TypeId};
+
+/// Parameter parsing generated by `#[derive(StreamNode)]`, found by node type
+///
+/// The derive submits one for every node that does not opt out with
+/// `#[node_meta(manual_config)]`; `ProcessingNode::on_create` runs it by
+/// default.
+pub struct ParamParser {
+    pub type_id: fn() -> TypeId,
+    pub parse: fn(&mut dyn Any, &Value) -> Result<()>,
+}
+
+inventory::collect!(ParamParser);
+
+impl ParamParser {
+    /// Parse and validate the declared parameters of `node` from `config`
+    ///
+    /// Nodes without generated parsing are left unchanged.
+    pub fn apply(node: &mut dyn Any, config: &Value) -> Result<()> {
+        let type_id = (*node).type_id();
+        match inventory::iter::<ParamParser>.into_iter().find(|p| (p.type_id)() == type_id) {
+            Some(parser) => (parser.parse)(node, config),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::{DataFrame, ProcessingNode};
+    use async_trait::async_trait;
+    use audiotab_macros::StreamNode;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(StreamNode, Debug, Clone, Serialize, Deserialize)]
+    #[node_meta(name = "Derived Params", category = "Test")]
+    struct DerivedParamsNode {
+        #[param(default = "2.0", min = 0.0, max = 10.0)]
+        level: f64,
+
+        #[serde(skip)]
+        configured: bool,
+    }
+
+    impl Default for DerivedParamsNode {
+        fn default() -> Self {
+            Self { level: 2.0, configured: false }
+        }
+    }
+
+    #[async_trait]
+    impl ProcessingNode for DerivedParamsNode {
+        async fn on_configured(&mut self, _config: &serde_json::Value) -> anyhow::Result<()> {
+            self.configured = true;
+            Ok(())
+        }
+
+        async fn process(&mut self, frame: DataFrame) -> anyhow::Result<DataFrame> {
+            Ok(frame)
+        }
+    }
+
+    #[derive(StreamNode, Debug, Clone, Default, Serialize, Deserialize)]
+    #[node_meta(name = "Manual Params", category = "Test", manual_config)]
+    struct ManualParamsNode {
+        #[param(default = "0.0", min = 0.0, max = 10.0)]
+        level: f64,
+    }
+
+    #[async_trait]
+    impl ProcessingNode for ManualParamsNode {
+        async fn process(&mut self, frame: DataFrame) -> anyhow::Result<DataFrame> {
+            Ok(frame)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_default_on_create_parses_then_runs_setup() {
+        let mut node = DerivedParamsNode::default();
+        node.on_create(serde_json::json!({"level": 4.5})).await.unwrap();
+        assert_eq!(node.level, 4.5);
+        assert!(node.configured);
+
+        let mut node = DerivedParamsNode::default();
+        assert!(node.on_create(serde_json::json!({"level": 20.0})).await.is_err());
+        assert!(!node.configured);
+    }
+
+    #[tokio::test]
+    async fn test_manual_config_skips_generated_parsing() {
+        let mut node = ManualParamsNode::default();
+        node.on_create(serde_json::json!({"level": 20.0})).await.unwrap();
+        assert_eq!(node.level, 0.0);
+        assert!(node.apply_config(&serde_json::json!({"level": 20.0})).is_err());
+    }
+}
@@ -0,0 +1,179 @@
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use super::NodeMetadata;
+
+/// Named set of parameter values for one node type
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodePreset {
+    /// Node metadata id (lowercase struct name, e.g. "splmeternode")
+    pub node_type: String,
+    pub name: String,
+    pub parameters: Value,
+    /// Shipped with the node rather than saved by the user
+    #[serde(default)]
+    pub builtin: bool,
+}
+
+impl NodePreset {
+    pub fn new(node_type: impl Into<String>, name: impl Into<String>, parameters: Value) -> Self {
+        Self {
+            node_type: node_type.into().to_lowercase(),
+            name: name.into(),
+            parameters,
+            builtin: false,
+        }
+    }
+
+    /// Built-in preset declared with `#[preset(...)]` on a StreamNode
+    pub fn builtin(node_type: &str, name: &str, parameters_json: &str) -> Self {
+        Self {
+            node_type: node_type.to_lowercase(),
+            name: name.to_string(),
+            parameters: serde_json::from_str(parameters_json).unwrap_or(Value::Null),
+            builtin: true,
+        }
+    }
+}
+
+/// Stores built-in and user parameter presets
+///
+/// User presets are persisted as a single JSON file when the store is
+/// opened with a path. Built-in presets are never written to disk.
+#[derive(Debug, Default)]
+pub struct PresetStore {
+    path: Option<PathBuf>,
+    presets: HashMap<String, Vec<NodePreset>>,
+}
+
+impl PresetStore {
+    /// Empty in-memory store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// In-memory store seeded with the built-in presets of all registered nodes
+    pub fn with_builtins() -> Self {
+        let mut store = Self::new();
+        for meta in NodeMetadata::all() {
+            for preset in meta.presets {
+                store.insert(preset);
+            }
+        }
+        store
+    }
+
+    /// Built-in presets plus user presets loaded from `path` (if it exists)
+    pub fn open(path: PathBuf) -> Result<Self> {
+        let mut store = Self::with_builtins();
+        if path.exists() {
+            let json = fs::read_to_string(&path)
+                .context(format!("Failed to read presets from {:?}", path))?;
+            let user: Vec<NodePreset> = serde_json::from_str(&json)
+                .context("Failed to deserialize presets")?;
+            for preset in user {
+                store.insert(NodePreset { builtin: false, ..preset });
+            }
+        }
+        store.path = Some(path);
+        Ok(store)
+    }
+
+    fn insert(&mut self, preset: NodePreset) {
+        let list = self.presets.entry(preset.node_type.clone()).or_default();
+        list.retain(|p| p.name != preset.name);
+        list.push(preset);
+    }
+
+    /// Presets available for a node type
+    pub fn list(&self, node_type: &str) -> Vec<NodePreset> {
+        self.presets
+            .get(&node_type.to_lowercase())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn get(&self, node_type: &str, name: &str) -> Option<&NodePreset> {
+        self.presets
+            .get(&node_type.to_lowercase())?
+            .iter()
+            .find(|p| p.name == name)
+    }
+
+    /// Save a user preset, replacing any user preset with the same name
+    pub fn save_preset(&mut self, preset: NodePreset) -> Result<()> {
+        if !preset.parameters.is_object() {
+            return Err(anyhow!("Preset parameters must be a JSON object"));
+        }
+        if self.get(&preset.node_type, &preset.name).is_some_and(|p| p.builtin) {
+            return Err(anyhow!("Cannot overwrite built-in preset '{}'", preset.name));
+        }
+        self.insert(NodePreset { builtin: false, ..preset });
+        self.persist()
+    }
+
+    pub fn delete_preset(&mut self, node_type: &str, name: &str) -> Result<()> {
+        match self.get(node_type, name) {
+            None => return Err(anyhow!("Preset '{}' not found for {}", name, node_type)),
+            Some(p) if p.builtin => return Err(anyhow!("Cannot delete built-in preset '{}'", name)),
+            Some(_) => {}
+        }
+        if let Some(list) = self.presets.get_mut(&node_type.to_lowercase()) {
+            list.retain(|p| p.name != name);
+        }
+        self.persist()
+    }
+
+    /// Merge a `"preset"` reference in a node config with its explicit parameters
+    ///
+    /// Values set directly in `config` take precedence over the preset.
+    /// Configs without a `"preset"` key are returned unchanged.
+    pub fn resolve_config(&self, node_type: &str, config: &Value) -> Result<Value> {
+        let Some(name) = config.get("preset").and_then(|v| v.as_str()) else {
+            return Ok(config.clone());
+        };
+        let preset = self
+            .get(node_type, name)
+            .ok_or_else(|| anyhow!("Preset '{}' not found for {}", name, node_type))?;
+
+        let mut merged = preset.parameters.as_object().cloned().unwrap_or_default();
+        if let Some(overrides) = config.as_object() {
+            for (key, value) in overrides {
+                if key != "preset" {
+                    merged.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        Ok(Value::Object(merged))
+    }
+
+    /// Resolve preset references for every node of a backend pipeline config in place
+    pub fn resolve_pipeline(&self, pipeline: &mut Value) -> Result<()> {
+        if let Some(nodes) = pipeline.get_mut("nodes").and_then(|n| n.as_array_mut()) {
+            for node in nodes {
+                let node_type = node["type"].as_str().unwrap_or("").to_string();
+                node["config"] = self.resolve_config(&node_type, &node["config"])?;
+            }
+        }
+        Ok(())
+    }
+
+    fn persist(&self) -> Result<()> {
+        let Some(path) = &self.path else { return Ok(()) };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("Failed to create preset directory")?;
+        }
+        let user: Vec<&NodePreset> = self
+            .presets
+            .values()
+            .flatten()
+            .filter(|p| !p.builtin)
+            .collect();
+        let json = serde_json::to_string_pretty(&user).context("Failed to serialize presets")?;
+        fs::write(path, json).context(format!("Failed to write presets to {:?}", path))?;
+        Ok(())
+    }
+}
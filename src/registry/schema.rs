@@ -0,0 +1,278 @@
+//! JSON Schemas for node parameters and the config file formats
+//!
+//! Editors and external tools can use these to check configs before they
+//! reach the engine; `engine::validate_graph` applies the same rules and
+//! reports every problem with the field it concerns.
+
+use super::{NodeMetadata, ParameterSchema};
+use crate::engine::async_pipeline::NODE_TYPE_ALIASES;
+use serde_json::{json, Map, Value};
+
+const DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+impl ParameterSchema {
+    /// JSON Schema of the values this parameter accepts
+    pub fn json_schema(&self) -> Value {
+        let mut schema = Map::new();
+        if let Some(json_type) = self.json_type() {
+            schema.insert("type".to_string(), json!(json_type));
+        }
+        if let Some(choices) = &self.choices {
+            schema.insert("enum".to_string(), json!(choices));
+        }
+        if let Some(min) = self.min {
+            schema.insert("minimum".to_string(), json!(min));
+        }
+        if let Some(max) = self.max {
+            schema.insert("maximum".to_string(), json!(max));
+        }
+        if let Some(unit) = &self.unit {
+            schema.insert("description".to_string(), json!(format!("In {}", unit)));
+        }
+        if !self.default.is_null() {
+            schema.insert("default".to_string(), self.default.clone());
+        }
+        Value::Object(schema)
+    }
+
+    /// Check a config value against the parameter's type, choices and range
+    ///
+    /// Mirrors the checks nodes run in `apply_config`; choices match
+    /// case-insensitively as they do there.
+    pub fn check(&self, value: &Value) -> Result<(), String> {
+        let type_ok = match self.json_type() {
+            Some("number") => value.is_number(),
+            Some("integer") => value.is_i64() || value.is_u64(),
+            Some("string") => value.is_string(),
+            Some("boolean") => value.is_boolean(),
+            _ => true,
+        };
+        if !type_ok {
+            return Err(format!("expected a {}, got {}", self.json_type().unwrap_or("value"), value));
+        }
+
+        if let (Some(choices), Some(s)) = (&self.choices, value.as_str()) {
+            if !choices.iter().any(|c| c.eq_ignore_ascii_case(s)) {
+                return Err(format!("expected one of {}, got '{}'", choices.join(", "), s));
+            }
+        }
+        if let Some(n) = value.as_f64() {
+            let min = self.min.unwrap_or(f64::NEG_INFINITY);
+            let max = self.max.unwrap_or(f64::INFINITY);
+            if !(min..=max).contains(&n) {
+                return Err(format!("must be between {} and {}, got {}", min, max, n));
+            }
+        }
+        Ok(())
+    }
+
+    /// JSON type for `param_type`, or None when any value is accepted
+    fn json_type(&self) -> Option<&'static str> {
+        match self.param_type.as_str() {
+            "number" | "float" => Some("number"),
+            "integer" | "int" => Some("integer"),
+            "string" | "enum" => Some("string"),
+            "boolean" | "bool" => Some("boolean"),
+            _ => None,
+        }
+    }
+}
+
+impl NodeMetadata {
+    /// JSON Schema of this node type's `config` object
+    ///
+    /// Keys other than the declared parameters (such as `preset` or
+    /// `device_profile_id`) are allowed.
+    pub fn config_schema(&self) -> Value {
+        let properties: Map<String, Value> = self
+            .parameters
+            .iter()
+            .map(|p| (p.name.clone(), p.json_schema()))
+            .collect();
+        json!({
+            "$schema": DRAFT,
+            "title": format!("{} config", self.name),
+            "type": "object",
+            "properties": properties,
+        })
+    }
+}
+
+/// Config schemas of every registered node type, keyed by node type id
+pub fn node_schemas() -> Value {
+    Value::Object(NodeMetadata::all().iter().map(|meta| (meta.id.clone(), meta.config_schema())).collect())
+}
+
+/// JSON Schema of the pipeline file format read by `AsyncPipeline::from_json`
+///
+/// A node whose `type` names a registered node type has its `config`
+/// checked against that type's schema.
+pub fn pipeline_schema() -> Value {
+    let node_configs: Vec<Value> = NodeMetadata::all()
+        .iter()
+        .map(|meta| {
+            let mut config = meta.config_schema();
+            if let Some(config) = config.as_object_mut() {
+                config.remove("$schema");
+            }
+            json!({
+                "if": { "properties": { "type": { "pattern": type_pattern(&meta.id) } } },
+                "then": { "properties": { "config": config } },
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": DRAFT,
+        "title": "Audiotab pipeline",
+        "type": "object",
+        "properties": {
+            "nodes": { "type": "array", "items": { "$ref": "#/$defs/node" } },
+            "connections": { "type": "array", "items": { "$ref": "#/$defs/connection" } },
+            "pipeline_config": { "$ref": "#/$defs/pipeline_config" },
+            "subgraphs": {
+                "type": "object",
+                "additionalProperties": { "$ref": "#/$defs/subgraph" },
+            },
+        },
+        "$defs": {
+            "node": {
+                "type": "object",
+                "required": ["id", "type"],
+                "properties": {
+                    "id": { "type": "string", "minLength": 1 },
+                    "type": { "type": "string" },
+                    "config": { "type": "object" },
+                    "version": { "type": "integer", "minimum": 1 },
+                    "port_counts": { "type": "object", "additionalProperties": { "type": "integer", "minimum": 0 } },
+                    "error_policy": { "$ref": "#/$defs/error_policy" },
+                    "input_block_size": { "type": "integer", "minimum": 1 },
+                    "realtime": { "type": "boolean" },
+                },
+                "allOf": node_configs,
+            },
+            "connection": {
+                "type": "object",
+                "required": ["from", "to"],
+                "properties": {
+                    "from": { "type": "string" },
+                    "to": { "type": "string" },
+                    "from_port": { "type": "string" },
+                    "to_port": { "type": "string" },
+                    "block_size": { "type": "integer", "minimum": 1 },
+                    "backpressure": { "$ref": "#/$defs/backpressure" },
+                },
+            },
+            "pipeline_config": {
+                "type": "object",
+                "properties": {
+                    "channel_capacity": { "type": "integer", "minimum": 1 },
+                    "priority": { "enum": ["Critical", "High", "Normal", "Low"] },
+                    "error_policy": { "$ref": "#/$defs/error_policy" },
+                    "backpressure": { "$ref": "#/$defs/backpressure" },
+                    "restart": { "$ref": "#/$defs/restart" },
+                    "metrics_interval_ms": { "type": "integer", "minimum": 1 },
+                    "frame_size": { "type": "integer", "minimum": 1 },
+                    "dead_letter_capacity": { "type": "integer", "minimum": 0 },
+                    "realtime": {
+                        "oneOf": [
+                            { "type": "boolean" },
+                            {
+                                "type": "object",
+                                "properties": { "cores": { "type": "array", "items": { "type": "integer", "minimum": 0 } } },
+                            },
+                        ],
+                    },
+                },
+            },
+            "error_policy": {
+                "oneOf": [
+                    { "$ref": "#/$defs/error_policy_name" },
+                    {
+                        "allOf": [{ "$ref": "#/$defs/restart" }],
+                        "type": "object",
+                        "required": ["type"],
+                        "properties": {
+                            "type": { "$ref": "#/$defs/error_policy_name" },
+                            "error_threshold": { "type": "integer", "minimum": 1 },
+                            "open_ms": { "type": "integer", "minimum": 0 },
+                        },
+                    },
+                ],
+            },
+            "error_policy_name": {
+                "enum": [
+                    "propagate", "skip_frame", "substitute_silence", "restart", "circuit_breaker",
+                    "skip-frame", "substitute-silence", "circuit-breaker",
+                ],
+            },
+            "backpressure": {
+                "enum": ["block", "drop_oldest", "drop_newest", "coalesce", "drop-oldest", "drop-newest"],
+            },
+            "restart": {
+                "type": "object",
+                "properties": {
+                    "strategy": { "enum": ["never", "immediate", "exponential", "circuit_breaker", "circuit-breaker"] },
+                    "base_ms": { "type": "integer", "minimum": 0 },
+                    "max_ms": { "type": "integer", "minimum": 0 },
+                    "max_attempts": { "type": "integer", "minimum": 0 },
+                    "error_threshold": { "type": "integer", "minimum": 1 },
+                    "timeout_ms": { "type": "integer", "minimum": 0 },
+                },
+            },
+            "subgraph": {
+                "type": "object",
+                "required": ["nodes"],
+                "properties": {
+                    "description": { "type": "string" },
+                    "nodes": { "type": "array", "items": { "$ref": "#/$defs/node" } },
+                    "connections": { "type": "array", "items": { "$ref": "#/$defs/connection" } },
+                    "inputs": { "type": "object", "additionalProperties": { "$ref": "#/$defs/port_target" } },
+                    "outputs": { "type": "object", "additionalProperties": { "$ref": "#/$defs/port_target" } },
+                },
+            },
+            "port_target": {
+                "type": "object",
+                "required": ["node"],
+                "properties": {
+                    "node": { "type": "string" },
+                    "port": { "type": "string" },
+                },
+            },
+        },
+    })
+}
+
+/// Regex matching the names a node type is referred to by: its id in any
+/// case (as `NodeMetadata::find` accepts) and its aliases
+fn type_pattern(id: &str) -> String {
+    let case_insensitive: String = id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphabetic() {
+                format!("[{}{}]", c.to_ascii_lowercase(), c.to_ascii_uppercase())
+            } else {
+                regex_escape(c)
+            }
+        })
+        .collect();
+    let aliases = NODE_TYPE_ALIASES
+        .iter()
+        .filter(|(_, canonical)| canonical.eq_ignore_ascii_case(id))
+        .map(|(alias, _)| alias.chars().map(regex_escape).collect::<String>());
+    let names: Vec<String> = std::iter::once(case_insensitive).chain(aliases).collect();
+    format!("^(?:{})$", names.join("|"))
+}
+
+fn regex_escape(c: char) -> String {
+    if "\\^$.|?*+()[]{}/".contains(c) {
+        format!("\\{}", c)
+    } else {
+        c.to_string()
+    }
+}
+
+/// JSON Schema of the hardware config file (`HardwareConfig`)
+pub fn hardware_config_schema() -> Value {
+    serde_json::to_value(schemars::schema_for!(crate::hal::HardwareConfig)).unwrap_or_default()
+}
@@ -0,0 +1,153 @@
+//! Crash recovery for file sinks
+//!
+//! While a sink writes a file it keeps a `<file>.checkpoint` sidecar next
+//! to it. The sidecar holds the length of the output that was complete at
+//! the last checkpoint and the trailer that turns that prefix into a valid
+//! file: a Parquet footer, or nothing for line-based formats. Finishing
+//! cleanly removes the sidecar, so one left behind marks a file whose
+//! writer crashed; `recover_file` and `recover_dir` cut such a file back
+//! to its checkpoint and append the trailer.
+
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Extension appended to the output file name for its sidecar
+pub const CHECKPOINT_EXTENSION: &str = "checkpoint";
+
+/// First bytes of a sidecar
+const MAGIC: &[u8; 4] = b"ATCP";
+
+/// A file repaired by `recover_file`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RecoveredFile {
+    pub path: PathBuf,
+    /// Output kept up to the last checkpoint, without the trailer
+    pub kept_bytes: u64,
+    /// Output written after the last checkpoint, lost in the crash
+    pub discarded_bytes: u64,
+}
+
+/// Sidecar of the output file at `path`
+pub fn sidecar_path(path: impl AsRef<Path>) -> PathBuf {
+    let mut sidecar = path.as_ref().as_os_str().to_owned();
+    sidecar.push(".");
+    sidecar.push(CHECKPOINT_EXTENSION);
+    PathBuf::from(sidecar)
+}
+
+/// Record that the first `length` bytes of `path` are complete and that
+/// `trailer` finishes them
+///
+/// Call after the output is flushed; the output is synced to disk before
+/// the sidecar is replaced, so a crash leaves either checkpoint intact.
+pub fn write_checkpoint(path: impl AsRef<Path>, output: &File, length: u64, trailer: &[u8]) -> Result<()> {
+    let path = path.as_ref();
+    output.sync_data().with_context(|| format!("Failed to sync {}", path.display()))?;
+
+    let sidecar = sidecar_path(path);
+    let mut temp = sidecar.clone().into_os_string();
+    temp.push(".tmp");
+    let temp = PathBuf::from(temp);
+    {
+        let mut file = File::create(&temp).with_context(|| format!("Failed to create {}", temp.display()))?;
+        file.write_all(MAGIC)?;
+        file.write_all(&length.to_le_bytes())?;
+        file.write_all(trailer)?;
+        file.sync_data()?;
+    }
+    std::fs::rename(&temp, &sidecar).with_context(|| format!("Failed to write {}", sidecar.display()))?;
+    Ok(())
+}
+
+/// Remove the sidecar of `path` once the file is complete
+pub fn clear_checkpoint(path: impl AsRef<Path>) -> Result<()> {
+    let sidecar = sidecar_path(path);
+    match std::fs::remove_file(&sidecar) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e).with_context(|| format!("Failed to remove {}", sidecar.display())),
+    }
+}
+
+/// Repair the file at `path` if its writer left a checkpoint behind
+///
+/// Returns `None` when there is no sidecar, i.e. the file was finished.
+pub fn recover_file(path: impl AsRef<Path>) -> Result<Option<RecoveredFile>> {
+    let path = path.as_ref();
+    let sidecar = sidecar_path(path);
+    let mut contents = Vec::new();
+    match File::open(&sidecar) {
+        Ok(mut file) => file.read_to_end(&mut contents)?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("Failed to open {}", sidecar.display())),
+    };
+    if contents.len() < 12 || &contents[..4] != MAGIC {
+        return Err(anyhow!("{} is not a checkpoint", sidecar.display()));
+    }
+    let length = u64::from_le_bytes(contents[4..12].try_into().expect("eight bytes"));
+    let trailer = &contents[12..];
+
+    let mut output = OpenOptions::new()
+        .write(true)
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let written = output.metadata()?.len();
+    if written < length {
+        return Err(anyhow!(
+            "{} has {} bytes but its checkpoint covers {}",
+            path.display(),
+            written,
+            length
+        ));
+    }
+    output.set_len(length)?;
+    output.seek(SeekFrom::End(0))?;
+    output.write_all(trailer)?;
+    output.sync_data()?;
+    std::fs::remove_file(&sidecar).with_context(|| format!("Failed to remove {}", sidecar.display()))?;
+
+    Ok(Some(RecoveredFile {
+        path: path.to_path_buf(),
+        kept_bytes: length,
+        discarded_bytes: written - length,
+    }))
+}
+
+/// Repair every file in `dir` (not its subdirectories) that has a checkpoint
+///
+/// A file that cannot be repaired is logged and keeps its sidecar. A
+/// missing directory has nothing to recover.
+pub fn recover_dir(dir: impl AsRef<Path>) -> Result<Vec<RecoveredFile>> {
+    let dir = dir.as_ref();
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to list {}", dir.display())),
+    };
+
+    let mut recovered = Vec::new();
+    for entry in entries.flatten() {
+        let sidecar = entry.path();
+        if sidecar.extension().and_then(|e| e.to_str()) != Some(CHECKPOINT_EXTENSION) {
+            continue;
+        }
+        let output = sidecar.with_extension("");
+        match recover_file(&output) {
+            Ok(Some(file)) => {
+                tracing::warn!(
+                    path = %file.path.display(),
+                    discarded_bytes = file.discarded_bytes,
+                    "Recovered file of an interrupted recording"
+                );
+                recovered.push(file);
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!(path = %output.display(), "Failed to recover file: {:#}", e),
+        }
+    }
+    recovered.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(recovered)
+}
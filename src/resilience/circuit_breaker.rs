@@ -0,0 +1,96 @@
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// Circuit breaker state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum CircuitState {
+    /// Frames are processed normally
+    Closed,
+    /// Tripped; frames bypass the node until the open period ends
+    Open,
+    /// Open period ended; the next frame is a trial run
+    HalfOpen,
+}
+
+/// A node's circuit breaker changed state
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CircuitEvent {
+    pub node_id: String,
+    pub state: CircuitState,
+    pub consecutive_errors: usize,
+    /// Error that tripped or re-opened the breaker
+    pub error: Option<String>,
+}
+
+/// Trips open after consecutive failures and half-opens periodically to retry
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    error_threshold: usize,
+    open_for: Duration,
+    state: CircuitState,
+    consecutive_errors: usize,
+    opened_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub fn new(error_threshold: usize, open_for: Duration) -> Self {
+        Self {
+            error_threshold: error_threshold.max(1),
+            open_for,
+            state: CircuitState::Closed,
+            consecutive_errors: 0,
+            opened_at: None,
+        }
+    }
+
+    pub fn state(&self) -> CircuitState {
+        self.state
+    }
+
+    pub fn consecutive_errors(&self) -> usize {
+        self.consecutive_errors
+    }
+
+    /// Whether a frame may be processed now
+    ///
+    /// An open breaker whose period has ended moves to half-open and lets
+    /// one frame through.
+    pub fn allow(&mut self) -> bool {
+        match self.state {
+            CircuitState::Closed | CircuitState::HalfOpen => true,
+            CircuitState::Open => {
+                if self.opened_at.is_some_and(|at| at.elapsed() >= self.open_for) {
+                    self.state = CircuitState::HalfOpen;
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    /// Record a successful frame, returning the new state if it changed
+    pub fn record_success(&mut self) -> Option<CircuitState> {
+        self.consecutive_errors = 0;
+        self.transition(CircuitState::Closed)
+    }
+
+    /// Record a failed frame, returning the new state if it changed
+    pub fn record_failure(&mut self) -> Option<CircuitState> {
+        self.consecutive_errors += 1;
+        let trips = self.state == CircuitState::HalfOpen || self.consecutive_errors >= self.error_threshold;
+        if !trips {
+            return None;
+        }
+        self.opened_at = Some(Instant::now());
+        self.transition(CircuitState::Open)
+    }
+
+    fn transition(&mut self, state: CircuitState) -> Option<CircuitState> {
+        if self.state == state {
+            return None;
+        }
+        self.state = state;
+        Some(state)
+    }
+}
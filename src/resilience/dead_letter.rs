@@ -0,0 +1,150 @@
+use crate::core::DataFrame;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Failed frames kept when no capacity is configured
+pub const DEFAULT_DEAD_LETTER_CAPACITY: usize = 100;
+
+/// A frame that a node failed to process
+#[derive(Debug, Clone)]
+pub struct DeadLetter {
+    pub id: u64,
+    pub node_id: String,
+    pub error: String,
+    /// Unix milliseconds
+    pub failed_at: u64,
+    pub frame: DataFrame,
+}
+
+/// Summary of a dead letter without its samples
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DeadLetterInfo {
+    pub id: u64,
+    pub node_id: String,
+    pub error: String,
+    pub failed_at: u64,
+    pub timestamp: u64,
+    pub sequence_id: u64,
+    /// Channel names and their sample counts
+    pub channels: Vec<(String, usize)>,
+}
+
+impl DeadLetter {
+    pub fn info(&self) -> DeadLetterInfo {
+        let mut channels: Vec<(String, usize)> = self
+            .frame
+            .payload
+            .iter()
+            .map(|(name, channel)| (name.clone(), channel.len()))
+            .collect();
+        channels.sort();
+        DeadLetterInfo {
+            id: self.id,
+            node_id: self.node_id.clone(),
+            error: self.error.clone(),
+            failed_at: self.failed_at,
+            timestamp: self.frame.timestamp,
+            sequence_id: self.frame.sequence_id,
+            channels,
+        }
+    }
+}
+
+struct Letters {
+    letters: VecDeque<DeadLetter>,
+    next_id: u64,
+    dropped: u64,
+}
+
+/// Bounded store of frames that caused processing errors
+///
+/// Clones share the same queue. When full, the oldest letter is dropped.
+#[derive(Clone)]
+pub struct DeadLetterQueue {
+    capacity: usize,
+    inner: Arc<Mutex<Letters>>,
+}
+
+impl DeadLetterQueue {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Arc::new(Mutex::new(Letters {
+                letters: VecDeque::new(),
+                next_id: 0,
+                dropped: 0,
+            })),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Store a failed frame, returning its letter id
+    pub fn push(&self, node_id: impl Into<String>, error: impl std::fmt::Display, frame: DataFrame) -> u64 {
+        let failed_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let mut inner = self.inner.lock().unwrap();
+        let id = inner.next_id;
+        inner.next_id += 1;
+        if self.capacity == 0 {
+            inner.dropped += 1;
+            return id;
+        }
+        if inner.letters.len() >= self.capacity {
+            inner.letters.pop_front();
+            inner.dropped += 1;
+        }
+        inner.letters.push_back(DeadLetter {
+            id,
+            node_id: node_id.into(),
+            error: error.to_string(),
+            failed_at,
+            frame,
+        });
+        id
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().letters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Letters discarded because the queue was full
+    pub fn dropped(&self) -> u64 {
+        self.inner.lock().unwrap().dropped
+    }
+
+    /// Summaries of the stored letters, oldest first
+    pub fn list(&self) -> Vec<DeadLetterInfo> {
+        self.inner.lock().unwrap().letters.iter().map(DeadLetter::info).collect()
+    }
+
+    pub fn get(&self, id: u64) -> Option<DeadLetter> {
+        self.inner.lock().unwrap().letters.iter().find(|l| l.id == id).cloned()
+    }
+
+    /// Remove and return a letter, e.g. to re-inject it
+    pub fn take(&self, id: u64) -> Option<DeadLetter> {
+        let mut inner = self.inner.lock().unwrap();
+        let index = inner.letters.iter().position(|l| l.id == id)?;
+        inner.letters.remove(index)
+    }
+
+    pub fn clear(&self) {
+        self.inner.lock().unwrap().letters.clear();
+    }
+}
+
+impl Default for DeadLetterQueue {
+    fn default() -> Self {
+        Self::new(DEFAULT_DEAD_LETTER_CAPACITY)
+    }
+}
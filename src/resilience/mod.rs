@@ -1,5 +1,13 @@
+pub mod checkpoint;
+pub mod circuit_breaker;
+pub mod dead_letter;
 pub mod policy;
 pub mod resilient_node;
+pub mod supervisor;
 
+pub use checkpoint::{recover_dir, recover_file, RecoveredFile};
+pub use circuit_breaker::{CircuitBreaker, CircuitEvent, CircuitState};
+pub use dead_letter::{DeadLetter, DeadLetterInfo, DeadLetterQueue, DEFAULT_DEAD_LETTER_CAPACITY};
 pub use policy::{ErrorPolicy, RestartStrategy};
 pub use resilient_node::ResilientNode;
+pub use supervisor::{RestartEvent, Supervisor};
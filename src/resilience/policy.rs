@@ -1,4 +1,6 @@
 use crate::core::DataFrame;
+use anyhow::{anyhow, Result};
+use serde_json::Value;
 
 #[derive(Debug, Clone)]
 pub enum ErrorPolicy {
@@ -10,6 +12,47 @@ pub enum ErrorPolicy {
 
     /// Use a default/empty frame when error occurs
     UseDefault(DataFrame),
+
+    /// Replace the errored frame's channels with zeros of the same length
+    SubstituteSilence,
+
+    /// Re-run the node's `on_create` with its original config, skipping the errored frame
+    Restart(RestartStrategy),
+
+    /// Skip errored frames; after `error_threshold` consecutive errors stop
+    /// calling the node for `open_ms`, then retry it with a single frame
+    CircuitBreaker {
+        error_threshold: usize,
+        open_ms: u64,
+    },
+}
+
+impl ErrorPolicy {
+    /// Parse a policy from graph JSON
+    ///
+    /// Accepts a name (`"propagate"`, `"skip_frame"`, `"substitute_silence"`,
+    /// `"restart"`, `"circuit_breaker"`) or an object with a `"type"` name plus restart settings,
+    /// e.g. `{"type": "restart", "base_ms": 100, "max_ms": 5000, "max_attempts": 5}`
+    /// or `{"type": "circuit_breaker", "error_threshold": 5, "open_ms": 1000}`.
+    /// Hyphenated names are accepted as well.
+    pub fn from_json(value: &Value) -> Result<Self> {
+        let name = value
+            .as_str()
+            .or_else(|| value["type"].as_str())
+            .ok_or_else(|| anyhow!("Error policy must be a name or an object with a type"))?;
+
+        match name.replace('-', "_").as_str() {
+            "propagate" => Ok(ErrorPolicy::Propagate),
+            "skip_frame" => Ok(ErrorPolicy::SkipFrame),
+            "substitute_silence" => Ok(ErrorPolicy::SubstituteSilence),
+            "restart" => Ok(ErrorPolicy::Restart(RestartStrategy::from_json(value))),
+            "circuit_breaker" => Ok(ErrorPolicy::CircuitBreaker {
+                error_threshold: value["error_threshold"].as_u64().unwrap_or(5) as usize,
+                open_ms: value["open_ms"].as_u64().unwrap_or(1000),
+            }),
+            other => Err(anyhow!("Unknown error policy: {}", other)),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -33,3 +76,47 @@ pub enum RestartStrategy {
         timeout_ms: u64,
     },
 }
+
+impl RestartStrategy {
+    /// Parse restart settings, exponential backoff unless `"strategy"` says otherwise
+    pub fn from_json(value: &Value) -> Self {
+        match value["strategy"].as_str() {
+            Some("never") => RestartStrategy::Never,
+            Some("immediate") => RestartStrategy::Immediate,
+            Some("circuit_breaker") | Some("circuit-breaker") => RestartStrategy::CircuitBreaker {
+                error_threshold: value["error_threshold"].as_u64().unwrap_or(5) as usize,
+                timeout_ms: value["timeout_ms"].as_u64().unwrap_or(1000),
+            },
+            _ => RestartStrategy::Exponential {
+                base_ms: value["base_ms"].as_u64().unwrap_or(100),
+                max_ms: value["max_ms"].as_u64().unwrap_or(5000),
+                max_attempts: value["max_attempts"].as_u64().unwrap_or(5) as usize,
+            },
+        }
+    }
+
+    /// Delay before restart `attempt` (counted from 1), or `None` once restarts are exhausted
+    ///
+    /// The circuit breaker restarts immediately and pauses for its timeout
+    /// after every `error_threshold` failures.
+    pub fn backoff_ms(&self, attempt: usize) -> Option<u64> {
+        match self {
+            RestartStrategy::Never => None,
+            RestartStrategy::Immediate => Some(0),
+            RestartStrategy::Exponential { base_ms, max_ms, max_attempts } => {
+                if attempt == 0 || attempt > *max_attempts {
+                    return None;
+                }
+                let exponent = (attempt - 1).min(31) as u32;
+                Some(base_ms.saturating_mul(1 << exponent).min(*max_ms))
+            }
+            RestartStrategy::CircuitBreaker { error_threshold, timeout_ms } => {
+                if attempt.is_multiple_of((*error_threshold).max(1)) {
+                    Some(*timeout_ms)
+                } else {
+                    Some(0)
+                }
+            }
+        }
+    }
+}
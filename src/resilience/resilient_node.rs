@@ -1,15 +1,37 @@
 use crate::core::{ProcessingNode, DataFrame};
-use crate::observability::NodeMetrics;
-use super::ErrorPolicy;
+use crate::observability::{Alert, NodeMetrics, PipelineEvent};
+use super::{CircuitBreaker, CircuitEvent, DeadLetterQueue, ErrorPolicy, RestartStrategy};
 use anyhow::Result;
 use async_trait::async_trait;
 use serde_json::Value;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Overwrite the top-level keys of a node config with those of `params`
+pub fn merge_params(config: &mut Value, params: &Value) {
+    if !config.is_object() {
+        *config = Value::Object(Default::default());
+    }
+    if let (Some(config), Some(params)) = (config.as_object_mut(), params.as_object()) {
+        for (key, value) in params {
+            config.insert(key.clone(), value.clone());
+        }
+    }
+}
 
 pub struct ResilientNode {
     inner: Box<dyn ProcessingNode>,
     metrics: Arc<NodeMetrics>,
     error_policy: ErrorPolicy,
+    /// Config passed to `on_create` when the node is restarted
+    config: Value,
+    /// Errors since the last successful frame
+    consecutive_errors: usize,
+    dead_letters: Option<DeadLetterQueue>,
+    breaker: Option<CircuitBreaker>,
+    circuit_events: Option<broadcast::Sender<CircuitEvent>>,
+    events: Option<broadcast::Sender<PipelineEvent>>,
 }
 
 impl ResilientNode {
@@ -18,21 +40,134 @@ impl ResilientNode {
         metrics: Arc<NodeMetrics>,
         error_policy: ErrorPolicy,
     ) -> Self {
+        let breaker = match &error_policy {
+            ErrorPolicy::CircuitBreaker { error_threshold, open_ms } => {
+                Some(CircuitBreaker::new(*error_threshold, Duration::from_millis(*open_ms)))
+            }
+            _ => None,
+        };
         Self {
             inner,
             metrics,
             error_policy,
+            config: Value::Null,
+            consecutive_errors: 0,
+            dead_letters: None,
+            breaker,
+            circuit_events: None,
+            events: None,
+        }
+    }
+
+    /// Report circuit breaker state changes on `events`
+    pub fn with_circuit_events(mut self, events: broadcast::Sender<CircuitEvent>) -> Self {
+        self.circuit_events = Some(events);
+        self
+    }
+
+    fn emit_circuit_event(&self, error: Option<String>) {
+        if let (Some(events), Some(breaker)) = (&self.circuit_events, &self.breaker) {
+            let _ = events.send(CircuitEvent {
+                node_id: self.metrics.node_id().to_string(),
+                state: breaker.state(),
+                consecutive_errors: breaker.consecutive_errors(),
+                error,
+            });
+        }
+    }
+
+    /// Report every processing error on `events`, whatever the error policy,
+    /// and the alerts the node raises
+    pub fn with_events(mut self, events: broadcast::Sender<PipelineEvent>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Keep frames that fail processing in `queue`, whatever the error policy
+    pub fn with_dead_letters(mut self, queue: DeadLetterQueue) -> Self {
+        self.dead_letters = Some(queue);
+        self
+    }
+
+    /// Config the inner node was created with, replayed when it is restarted
+    pub fn with_config(mut self, config: Value) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Re-run the inner node's `on_create` with its original config
+    pub async fn reinitialize(&mut self) -> Result<()> {
+        self.consecutive_errors = 0;
+        self.inner.on_create(self.config.clone()).await
+    }
+
+    /// Merge `params` into the node's config and re-run `on_create` with it
+    ///
+    /// If the node rejects the new config it is re-created with the previous
+    /// one, which is also kept for restarts.
+    pub async fn reconfigure(&mut self, params: &Value) -> Result<()> {
+        let mut config = self.config.clone();
+        merge_params(&mut config, params);
+        if let Err(e) = self.inner.on_create(config.clone()).await {
+            let _ = self.inner.on_create(self.config.clone()).await;
+            return Err(e);
+        }
+        self.config = config;
+        Ok(())
+    }
+
+    /// Restart the inner node after a delay, or give up once the strategy is exhausted
+    async fn restart(&mut self, strategy: &RestartStrategy) -> Result<bool> {
+        let delay_ms = match strategy {
+            RestartStrategy::CircuitBreaker { error_threshold, timeout_ms } => {
+                // Skip frames until the breaker trips, then pause before restarting
+                if self.consecutive_errors < *error_threshold {
+                    return Ok(true);
+                }
+                *timeout_ms
+            }
+            _ => match strategy.backoff_ms(self.consecutive_errors) {
+                Some(delay_ms) => delay_ms,
+                None => return Ok(false),
+            },
+        };
+
+        if delay_ms > 0 {
+            tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+        }
+        self.inner.on_create(self.config.clone()).await?;
+        // The circuit breaker counts afresh; backoff keeps growing until a frame succeeds
+        if matches!(strategy, RestartStrategy::CircuitBreaker { .. }) {
+            self.consecutive_errors = 0;
         }
+        Ok(true)
     }
 }
 
 #[async_trait]
 impl ProcessingNode for ResilientNode {
     async fn on_create(&mut self, config: Value) -> Result<()> {
+        self.config = config.clone();
         self.inner.on_create(config).await
     }
 
+    fn migrate_config(&self, from_version: u32, config: Value) -> Result<Value> {
+        self.inner.migrate_config(from_version, config)
+    }
+
     async fn process(&mut self, input: DataFrame) -> Result<DataFrame> {
+        // An open breaker passes frames by without calling the node
+        if let Some(breaker) = &mut self.breaker {
+            let before = breaker.state();
+            let allowed = breaker.allow();
+            if breaker.state() != before {
+                self.emit_circuit_event(None);
+            }
+            if !allowed {
+                return Ok(input);
+            }
+        }
+
         let start = self.metrics.start_processing();
 
         // Try to process the frame using the inner node's process() method
@@ -43,11 +178,40 @@ impl ProcessingNode for ResilientNode {
                 // Success - forward output
                 self.metrics.finish_processing(start);
                 self.metrics.record_frame_processed();
+                if output.metadata.get_bool("triggered") == Some(true) {
+                    self.metrics.record_trigger();
+                }
+                // Counted at the node that flagged the frame, not the ones passing it on
+                if output.metadata.get_bool("quality_issue") == Some(true)
+                    && input.metadata.get_bool("quality_issue") != Some(true)
+                {
+                    self.metrics.record_quality_issue();
+                }
+                if let Some(events) = &self.events {
+                    if let Some(alert) = Alert::raised(self.metrics.node_id(), &input, &output) {
+                        let _ = events.send(PipelineEvent::Alert(alert));
+                    }
+                }
+                self.consecutive_errors = 0;
+                if self.breaker.as_mut().and_then(|b| b.record_success()).is_some() {
+                    self.emit_circuit_event(None);
+                }
                 Ok(output)
             }
             Err(e) => {
                 // Error occurred
                 self.metrics.record_error();
+                self.consecutive_errors += 1;
+                if let Some(queue) = &self.dead_letters {
+                    queue.push(self.metrics.node_id(), &e, input.clone());
+                }
+                if let Some(events) = &self.events {
+                    let _ = events.send(PipelineEvent::NodeError {
+                        node_id: self.metrics.node_id().to_string(),
+                        error: e.to_string(),
+                        fatal: false,
+                    });
+                }
 
                 match &self.error_policy {
                     ErrorPolicy::Propagate => {
@@ -60,11 +224,41 @@ impl ProcessingNode for ResilientNode {
                     ErrorPolicy::UseDefault(default_frame) => {
                         Ok(default_frame.clone())
                     }
+                    ErrorPolicy::SubstituteSilence => {
+                        let mut silent = input;
+                        for channel in silent.payload.values_mut() {
+                            *channel = channel.with_samples(vec![0.0; channel.len()]);
+                        }
+                        silent.events.clear();
+                        Ok(silent)
+                    }
+                    ErrorPolicy::Restart(strategy) => {
+                        let strategy = strategy.clone();
+                        if self.restart(&strategy).await? {
+                            Ok(input)
+                        } else {
+                            Err(e)
+                        }
+                    }
+                    ErrorPolicy::CircuitBreaker { .. } => {
+                        if self.breaker.as_mut().and_then(|b| b.record_failure()).is_some() {
+                            self.emit_circuit_event(Some(e.to_string()));
+                        }
+                        Ok(input)
+                    }
                 }
             }
         }
     }
 
+    async fn on_flush(&mut self) -> Result<Option<DataFrame>> {
+        self.inner.on_flush().await
+    }
+
+    async fn on_eos(&mut self) -> Result<Option<DataFrame>> {
+        self.inner.on_eos().await
+    }
+
     async fn on_destroy(&mut self) -> Result<()> {
         self.inner.on_destroy().await
     }
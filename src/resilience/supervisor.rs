@@ -0,0 +1,111 @@
+use super::RestartStrategy;
+use crate::observability::NodeMetrics;
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::Instrument;
+
+/// A run this long counts as recovered, resetting the crash count
+const STABLE_RUN: Duration = Duration::from_secs(10);
+
+/// Restart events buffered per subscriber
+const EVENT_CAPACITY: usize = 64;
+
+/// A supervised task crashed and was restarted, or given up on
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RestartEvent {
+    pub node_id: String,
+    /// Consecutive crashes, including this one
+    pub attempt: usize,
+    /// Error or panic message of the crashed run
+    pub error: String,
+    pub delay_ms: u64,
+    /// Restarts are exhausted and the task stays down
+    pub gave_up: bool,
+}
+
+/// Watches node tasks and restarts them when they panic or fail
+///
+/// Each run is spawned as its own task, so a panic is caught at the
+/// `JoinHandle` instead of taking the rest of the chain down with it.
+#[derive(Clone)]
+pub struct Supervisor {
+    strategy: RestartStrategy,
+    events: broadcast::Sender<RestartEvent>,
+}
+
+impl Supervisor {
+    pub fn new(strategy: RestartStrategy) -> Self {
+        let (events, _) = broadcast::channel(EVENT_CAPACITY);
+        Self { strategy, events }
+    }
+
+    pub fn strategy(&self) -> &RestartStrategy {
+        &self.strategy
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<RestartEvent> {
+        self.events.subscribe()
+    }
+
+    /// Run tasks built by `make_task` until one finishes cleanly
+    ///
+    /// A run that panics or returns an error is replaced by a new one after
+    /// the strategy's backoff; once restarts are exhausted the last error is
+    /// returned. Restarts are counted in `metrics`.
+    pub fn supervise<F, Fut>(&self, node_id: impl Into<String>, metrics: Arc<NodeMetrics>, mut make_task: F) -> JoinHandle<Result<()>>
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        let node_id = node_id.into();
+        let supervisor = self.clone();
+
+        tokio::spawn(async move {
+            let mut attempt = 0;
+            loop {
+                let started = Instant::now();
+                let error = match tokio::spawn(make_task().in_current_span()).await {
+                    Ok(Ok(())) => return Ok(()),
+                    Ok(Err(e)) => e.to_string(),
+                    Err(e) if e.is_panic() => panic_message(e.into_panic()),
+                    Err(e) => return Err(e.into()),
+                };
+
+                if started.elapsed() >= STABLE_RUN {
+                    attempt = 0;
+                }
+                attempt += 1;
+                let delay_ms = supervisor.strategy.backoff_ms(attempt);
+                let _ = supervisor.events.send(RestartEvent {
+                    node_id: node_id.clone(),
+                    attempt,
+                    error: error.clone(),
+                    delay_ms: delay_ms.unwrap_or(0),
+                    gave_up: delay_ms.is_none(),
+                });
+
+                let Some(delay_ms) = delay_ms else {
+                    return Err(anyhow!("Node '{}' crashed: {}", node_id, error));
+                };
+                tracing::warn!(node = %node_id, attempt, "Restarting node in {} ms after crash: {}", delay_ms, error);
+                metrics.record_restart();
+                tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+            }
+        }.in_current_span())
+    }
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        format!("panicked: {}", message)
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        format!("panicked: {}", message)
+    } else {
+        "panicked".to_string()
+    }
+}
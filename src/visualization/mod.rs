@@ -1,3 +1,3 @@
 pub mod ring_buffer;
 
-pub use ring_buffer::RingBufferWriter;
+pub use ring_buffer::{ChannelInfo, RingBufferWriter};
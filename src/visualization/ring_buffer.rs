@@ -2,21 +2,125 @@ use anyhow::Result;
 use memmap2::MmapMut;
 use std::fs::OpenOptions;
 use std::path::Path;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{fence, AtomicBool, AtomicU64, Ordering};
 
+/// File magic at offset 0
+pub const MAGIC: &[u8; 8] = b"AUDITAB!";
+
+/// Layout version at offset 8; version 1 had no commit position or epoch,
+/// version 2 no channel table
+pub const FORMAT_VERSION: u64 = 3;
+
+/// Bytes before the sample data
+pub const HEADER_SIZE: usize = 4096;
+
+/// Header offset of the number of committed writes
+pub const WRITE_SEQUENCE_OFFSET: usize = 40;
+
+/// Header offset of the committed samples per channel since creation; the
+/// newest sample sits just before `commit_position % capacity`
+pub const COMMIT_POSITION_OFFSET: usize = 48;
+
+/// Header offset of the seqlock epoch: odd while a write is in progress
+pub const WRITE_EPOCH_OFFSET: usize = 56;
+
+/// Header offset of the channel table: one `CHANNEL_ENTRY_SIZE` entry per
+/// channel (name, unit, calibration scale), for up to `MAX_LABELED_CHANNELS`
+pub const CHANNEL_TABLE_OFFSET: usize = 128;
+
+/// Bytes per channel table entry
+pub const CHANNEL_ENTRY_SIZE: usize = 64;
+
+/// Bytes of the channel name at the start of an entry, UTF-8 padded with NULs
+pub const CHANNEL_NAME_LEN: usize = 40;
+
+/// Bytes of the unit name following the channel name, UTF-8 padded with NULs
+pub const CHANNEL_UNIT_LEN: usize = 16;
+
+/// Channels that fit in the header's channel table
+pub const MAX_LABELED_CHANNELS: usize = (HEADER_SIZE - CHANNEL_TABLE_OFFSET) / CHANNEL_ENTRY_SIZE;
+
+/// Copy attempts before `snapshot` gives up on a busy writer
+const SNAPSHOT_RETRIES: usize = 100;
+
+/// Label and calibration of one ring buffer channel, stored in the header
+///
+/// Readers show `sample * scale` in `unit`; a scale of 0 means the samples
+/// are uncalibrated. Empty names fall back to `ch<N>`. Names longer than 40
+/// bytes and units longer than 16 bytes are truncated.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChannelInfo {
+    pub name: String,
+    pub unit: String,
+    pub scale: f64,
+}
+
+impl ChannelInfo {
+    pub fn new(name: impl Into<String>, unit: impl Into<String>, scale: f64) -> Self {
+        Self { name: name.into(), unit: unit.into(), scale }
+    }
+
+    /// Header entry bytes
+    fn encode(&self) -> [u8; CHANNEL_ENTRY_SIZE] {
+        let mut entry = [0u8; CHANNEL_ENTRY_SIZE];
+        copy_truncated(&mut entry[..CHANNEL_NAME_LEN], &self.name);
+        copy_truncated(&mut entry[CHANNEL_NAME_LEN..CHANNEL_NAME_LEN + CHANNEL_UNIT_LEN], &self.unit);
+        entry[CHANNEL_NAME_LEN + CHANNEL_UNIT_LEN..].copy_from_slice(&self.scale.to_le_bytes());
+        entry
+    }
+
+    /// Parse a header entry
+    pub fn decode(entry: &[u8]) -> Self {
+        let text = |bytes: &[u8]| {
+            let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+            String::from_utf8_lossy(&bytes[..len]).into_owned()
+        };
+        let scale_at = CHANNEL_NAME_LEN + CHANNEL_UNIT_LEN;
+        Self {
+            name: text(&entry[..CHANNEL_NAME_LEN]),
+            unit: text(&entry[CHANNEL_NAME_LEN..scale_at]),
+            scale: f64::from_le_bytes(entry[scale_at..scale_at + 8].try_into().unwrap()),
+        }
+    }
+}
+
+/// Copy as much of `text` as fits in `field`, cut at a character boundary
+fn copy_truncated(field: &mut [u8], text: &str) {
+    let mut end = text.len().min(field.len());
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    field[..end].copy_from_slice(&text.as_bytes()[..end]);
+}
+
+/// Lock-free single-producer ring of samples in a shared memory-mapped file
+///
+/// Layout: a 4096-byte header (magic, version, sample rate, channels,
+/// capacity, then the atomic write sequence, commit position and write
+/// epoch, then the channel table), followed by `capacity` f64 samples per
+/// channel, channel after channel.
+///
+/// Writes take `&self` and never block: the writer is shared as
+/// `Arc<RingBufferWriter>`, and a write racing another producer is refused
+/// instead of waiting. Readers copy the file and accept the copy only if
+/// the write epoch was even and unchanged across the copy (seqlock); see
+/// [`RingBufferWriter::snapshot`].
 pub struct RingBufferWriter {
-    _mmap: MmapMut,
+    mmap: MmapMut,
     sample_rate: u64,
     channels: usize,
     capacity: usize,
-    samples_per_write: usize,
-    write_sequence: *mut AtomicU64,
+    /// Start of the mapping; samples are written through it from `&self`
+    base: *mut u8,
+    /// Claimed by the producer for the duration of a write
+    producing: AtomicBool,
 }
 
-// SAFETY: RingBufferWriter is safe to send between threads because:
+// SAFETY: RingBufferWriter is safe to share between threads because:
 // - The memory-mapped file is valid for the lifetime of the writer
-// - The write_sequence pointer points to a valid AtomicU64 within the mmap
-// - All accesses to write_sequence use atomic operations
+// - Header fields are only accessed through atomic operations
+// - Sample data is only written by the producer holding `producing`, and
+//   concurrent readers detect torn copies through the write epoch
 unsafe impl Send for RingBufferWriter {}
 unsafe impl Sync for RingBufferWriter {}
 
@@ -26,7 +130,7 @@ impl std::fmt::Debug for RingBufferWriter {
             .field("sample_rate", &self.sample_rate)
             .field("channels", &self.channels)
             .field("capacity", &self.capacity)
-            .field("samples_per_write", &self.samples_per_write)
+            .field("commit_position", &self.get_commit_position())
             .finish()
     }
 }
@@ -61,7 +165,7 @@ mod tests {
         let path = "/tmp/test_ringbuf_write";
         let _ = fs::remove_file(path);
 
-        let mut writer = RingBufferWriter::new(path, 48000, 2, 1).unwrap();
+        let writer = RingBufferWriter::new(path, 48000, 2, 1).unwrap();
 
         // Write 1024 samples to each channel
         let samples = vec![
@@ -74,11 +178,107 @@ mod tests {
         // Verify write_sequence incremented
         let seq = writer.get_write_sequence();
         assert_eq!(seq, 1);
+        assert_eq!(writer.get_commit_position(), 1024);
 
         // Cleanup
         drop(writer);
         fs::remove_file(path).unwrap();
     }
+
+    #[test]
+    fn test_writes_of_any_length_wrap_around() {
+        let path = "/tmp/test_ringbuf_wrap";
+        let _ = fs::remove_file(path);
+
+        let writer = RingBufferWriter::new(path, 4, 1, 1).unwrap();
+        writer.write(&[vec![1.0, 2.0, 3.0]]).unwrap();
+        writer.write(&[vec![4.0, 5.0]]).unwrap();
+
+        let snapshot = writer.snapshot().unwrap();
+        let sample = |idx: usize| {
+            let offset = HEADER_SIZE + idx * 8;
+            f64::from_le_bytes(snapshot[offset..offset + 8].try_into().unwrap())
+        };
+        assert_eq!([sample(0), sample(1), sample(2), sample(3)], [5.0, 2.0, 3.0, 4.0]);
+        assert_eq!(writer.get_commit_position(), 5);
+        assert_eq!(writer.get_write_epoch() % 2, 0);
+
+        drop(writer);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_read_latest() {
+        let path = "/tmp/test_ringbuf_read_latest";
+        let _ = fs::remove_file(path);
+
+        let writer = RingBufferWriter::new(path, 4, 2, 1).unwrap();
+        writer.write(&[vec![1.0, 2.0, 3.0], vec![-1.0, -2.0, -3.0]]).unwrap();
+        assert_eq!(writer.read_latest(8).unwrap(), (3, vec![vec![1.0, 2.0, 3.0], vec![-1.0, -2.0, -3.0]]));
+
+        writer.write(&[vec![4.0, 5.0], vec![-4.0, -5.0]]).unwrap();
+        assert_eq!(writer.read_latest(3).unwrap(), (5, vec![vec![3.0, 4.0, 5.0], vec![-3.0, -4.0, -5.0]]));
+
+        drop(writer);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_channel_table() {
+        let path = "/tmp/test_ringbuf_channel_table";
+        let _ = fs::remove_file(path);
+
+        let writer = RingBufferWriter::new(path, 4, 2, 1).unwrap();
+        let mic = ChannelInfo::new("Mic 1", "Pa", 94.0);
+        writer.set_channel_info(1, &mic).unwrap();
+        assert!(writer.set_channel_info(2, &mic).is_err());
+        assert!(writer.set_channel_info(0, &ChannelInfo::new("x", "V", f64::NAN)).is_err());
+
+        assert_eq!(writer.channel_info(0).unwrap(), ChannelInfo::default());
+        assert_eq!(writer.channel_info(1).unwrap(), mic);
+        assert_eq!(writer.get_write_epoch(), 2);
+
+        // Truncated at a character boundary
+        let long = ChannelInfo::new("é".repeat(30), "", 0.0);
+        writer.set_channel_info(0, &long).unwrap();
+        assert_eq!(writer.channel_info(0).unwrap().name, "é".repeat(20));
+
+        let snapshot = writer.snapshot().unwrap();
+        let entry = CHANNEL_TABLE_OFFSET + CHANNEL_ENTRY_SIZE;
+        assert_eq!(ChannelInfo::decode(&snapshot[entry..entry + CHANNEL_ENTRY_SIZE]), mic);
+
+        drop(writer);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_channel_info_not_torn_by_relabeling() {
+        let path = "/tmp/test_ringbuf_channel_relabel";
+        let _ = fs::remove_file(path);
+
+        let writer = std::sync::Arc::new(RingBufferWriter::new(path, 4, 1, 1).unwrap());
+        let labels = [ChannelInfo::new("a".repeat(40), "Pa", 1.0), ChannelInfo::new("b".repeat(40), "V", 2.0)];
+        writer.set_channel_info(0, &labels[0]).unwrap();
+
+        let relabel = {
+            let writer = writer.clone();
+            let labels = labels.clone();
+            std::thread::spawn(move || {
+                for i in 0..20_000 {
+                    writer.set_channel_info(0, &labels[i % 2]).unwrap();
+                }
+            })
+        };
+        while !relabel.is_finished() {
+            if let Ok(info) = writer.channel_info(0) {
+                assert!(labels.contains(&info), "torn entry {:?}", info);
+            }
+        }
+        relabel.join().unwrap();
+
+        drop(writer);
+        fs::remove_file(path).unwrap();
+    }
 }
 
 impl RingBufferWriter {
@@ -89,47 +289,50 @@ impl RingBufferWriter {
         duration_secs: u64,
     ) -> Result<Self> {
         let capacity = (sample_rate * duration_secs) as usize;
-        let header_size = 4096;
         let data_size = channels * capacity * 8; // 8 bytes per f64
-        let total_size = header_size + data_size;
+        let total_size = HEADER_SIZE + data_size;
 
         // Create memory-mapped file
         let file = OpenOptions::new()
             .read(true)
             .write(true)
             .create(true)
+            .truncate(true)
             .open(path)?;
         file.set_len(total_size as u64)?;
 
         let mut mmap = unsafe { MmapMut::map_mut(&file)? };
 
-        // Write header
-        mmap[0..8].copy_from_slice(b"AUDITAB!");
-        mmap[8..16].copy_from_slice(&1u64.to_le_bytes()); // version
+        // Write header; the atomic fields start at zero
+        mmap[0..8].copy_from_slice(MAGIC);
+        mmap[8..16].copy_from_slice(&FORMAT_VERSION.to_le_bytes());
         mmap[16..24].copy_from_slice(&sample_rate.to_le_bytes());
         mmap[24..32].copy_from_slice(&(channels as u64).to_le_bytes());
         mmap[32..40].copy_from_slice(&(capacity as u64).to_le_bytes());
+        mmap[WRITE_SEQUENCE_OFFSET..HEADER_SIZE].fill(0);
 
-        // Initialize write_sequence to 0
-        let write_seq_ptr = &mut mmap[40..48];
-        write_seq_ptr.copy_from_slice(&0u64.to_le_bytes());
-
-        // Get pointer to write_sequence for atomic operations
-        let write_sequence = unsafe {
-            &mut *(mmap[40..48].as_mut_ptr() as *mut AtomicU64)
-        };
-
+        let base = mmap.as_mut_ptr();
         Ok(Self {
-            _mmap: mmap,
+            mmap,
             sample_rate,
             channels,
             capacity,
-            samples_per_write: 1024,
-            write_sequence,
+            base,
+            producing: AtomicBool::new(false),
         })
     }
 
-    pub fn write(&mut self, samples: &[Vec<f64>]) -> Result<()> {
+    /// Atomic header field at `offset` (8-byte aligned within the page-aligned mapping)
+    fn header_field(&self, offset: usize) -> &AtomicU64 {
+        unsafe { &*(self.base.add(offset) as *const AtomicU64) }
+    }
+
+    /// Append one block of samples per channel without blocking
+    ///
+    /// Every channel must hold the same number of samples. Blocks longer
+    /// than the capacity keep only their newest samples. Fails instead of
+    /// waiting when another producer is writing.
+    pub fn write<S: AsRef<[f64]>>(&self, samples: &[S]) -> Result<()> {
         use anyhow::ensure;
 
         ensure!(
@@ -138,30 +341,193 @@ impl RingBufferWriter {
             self.channels,
             samples.len()
         );
+        let len = samples.first().map_or(0, |s| s.as_ref().len());
+        ensure!(
+            samples.iter().all(|s| s.as_ref().len() == len),
+            "All channels must have the same number of samples"
+        );
+        ensure!(
+            !self.producing.swap(true, Ordering::Acquire),
+            "Ring buffer is being written by another producer"
+        );
 
-        let seq = unsafe { (*self.write_sequence).load(Ordering::Acquire) };
-        let start_idx = ((seq as usize) * self.samples_per_write) % self.capacity;
+        let epoch = self.header_field(WRITE_EPOCH_OFFSET);
+        let position = self.header_field(COMMIT_POSITION_OFFSET);
+        let committed = position.load(Ordering::Relaxed);
 
-        // Write each channel
-        for (ch_id, ch_samples) in samples.iter().enumerate() {
-            let ch_offset = 4096 + (ch_id * self.capacity * 8);
+        // Odd epoch: readers discard copies overlapping this write
+        epoch.fetch_add(1, Ordering::Relaxed);
+        fence(Ordering::Release);
 
-            for (i, &sample) in ch_samples.iter().enumerate() {
-                let idx = (start_idx + i) % self.capacity;
-                let offset = ch_offset + (idx * 8);
-                self._mmap[offset..offset + 8].copy_from_slice(&sample.to_le_bytes());
+        let skip = len.saturating_sub(self.capacity);
+        let start = (committed + skip as u64) as usize % self.capacity.max(1);
+        for (ch_id, ch_samples) in samples.iter().enumerate() {
+            let ch_offset = HEADER_SIZE + ch_id * self.capacity * 8;
+            for (i, &sample) in ch_samples.as_ref()[skip..].iter().enumerate() {
+                let offset = ch_offset + ((start + i) % self.capacity) * 8;
+                // SAFETY: offset is within the data region sized in `new`,
+                // and only the producer holding `producing` writes samples
+                unsafe {
+                    std::ptr::copy_nonoverlapping(sample.to_le_bytes().as_ptr(), self.base.add(offset), 8);
+                }
             }
         }
 
-        // Atomically increment sequence
+        position.store(committed + len as u64, Ordering::Relaxed);
+        self.header_field(WRITE_SEQUENCE_OFFSET).fetch_add(1, Ordering::Relaxed);
+        epoch.fetch_add(1, Ordering::Release);
+
+        self.producing.store(false, Ordering::Release);
+        Ok(())
+    }
+
+    /// Label a channel and record how its samples convert to physical units
+    ///
+    /// Channels beyond `MAX_LABELED_CHANNELS` have no header entry and are
+    /// rejected.
+    pub fn set_channel_info(&self, channel: usize, info: &ChannelInfo) -> Result<()> {
+        use anyhow::ensure;
+
+        ensure!(
+            channel < self.channels.min(MAX_LABELED_CHANNELS),
+            "Channel {} has no header entry ({} channels, {} labeled at most)",
+            channel,
+            self.channels,
+            MAX_LABELED_CHANNELS
+        );
+        ensure!(info.scale.is_finite() && info.scale >= 0.0, "Calibration scale must be finite and non-negative");
+        ensure!(
+            !self.producing.swap(true, Ordering::Acquire),
+            "Ring buffer is being written by another producer"
+        );
+
+        let entry = info.encode();
+        let epoch = self.header_field(WRITE_EPOCH_OFFSET);
+        epoch.fetch_add(1, Ordering::Relaxed);
+        fence(Ordering::Release);
+        // SAFETY: the entry lies within the header (checked against
+        // MAX_LABELED_CHANNELS), written only by the producer holding `producing`
         unsafe {
-            (*self.write_sequence).fetch_add(1, Ordering::Release);
+            let offset = CHANNEL_TABLE_OFFSET + channel * CHANNEL_ENTRY_SIZE;
+            std::ptr::copy_nonoverlapping(entry.as_ptr(), self.base.add(offset), CHANNEL_ENTRY_SIZE);
         }
+        epoch.fetch_add(1, Ordering::Release);
 
+        self.producing.store(false, Ordering::Release);
         Ok(())
     }
 
+    /// Header entry of a channel; default (unlabeled) beyond the table
+    ///
+    /// Retries like `snapshot` while the entry is being relabeled.
+    pub fn channel_info(&self, channel: usize) -> Result<ChannelInfo> {
+        if channel >= self.channels.min(MAX_LABELED_CHANNELS) {
+            return Ok(ChannelInfo::default());
+        }
+        let offset = CHANNEL_TABLE_OFFSET + channel * CHANNEL_ENTRY_SIZE;
+        let epoch = self.header_field(WRITE_EPOCH_OFFSET);
+        let mut entry = [0u8; CHANNEL_ENTRY_SIZE];
+        for _ in 0..SNAPSHOT_RETRIES {
+            let before = epoch.load(Ordering::Acquire);
+            if before % 2 == 1 {
+                std::thread::yield_now();
+                continue;
+            }
+            // SAFETY: the entry lies within the header; torn copies are
+            // detected by the epoch check below
+            unsafe {
+                std::ptr::copy_nonoverlapping(self.base.add(offset), entry.as_mut_ptr(), CHANNEL_ENTRY_SIZE);
+            }
+            fence(Ordering::Acquire);
+            if epoch.load(Ordering::Relaxed) == before {
+                return Ok(ChannelInfo::decode(&entry));
+            }
+        }
+        anyhow::bail!("Ring buffer is being written continuously; no consistent channel info")
+    }
+
+    /// Consistent copy of the whole file, taken while no write is in progress
+    ///
+    /// Retries while the writer is active; gives up if every attempt
+    /// overlaps a write.
+    pub fn snapshot(&self) -> Result<Vec<u8>> {
+        let epoch = self.header_field(WRITE_EPOCH_OFFSET);
+        let mut copy = vec![0u8; self.mmap.len()];
+        for _ in 0..SNAPSHOT_RETRIES {
+            let before = epoch.load(Ordering::Acquire);
+            if before % 2 == 1 {
+                std::thread::yield_now();
+                continue;
+            }
+            // SAFETY: the mapping is valid for `mmap.len()` bytes; torn
+            // copies are detected by the epoch check below
+            unsafe {
+                std::ptr::copy_nonoverlapping(self.base, copy.as_mut_ptr(), copy.len());
+            }
+            fence(Ordering::Acquire);
+            if epoch.load(Ordering::Relaxed) == before {
+                return Ok(copy);
+            }
+        }
+        anyhow::bail!("Ring buffer is being written continuously; no consistent snapshot")
+    }
+
+    /// The newest `count` samples of every channel, oldest first
+    ///
+    /// Returns the commit position of the newest sample with them. Fewer
+    /// samples are returned while the ring has not filled yet. Retries like
+    /// `snapshot` while a write is in progress.
+    pub fn read_latest(&self, count: usize) -> Result<(u64, Vec<Vec<f64>>)> {
+        let epoch = self.header_field(WRITE_EPOCH_OFFSET);
+        for _ in 0..SNAPSHOT_RETRIES {
+            let before = epoch.load(Ordering::Acquire);
+            if before % 2 == 1 {
+                std::thread::yield_now();
+                continue;
+            }
+            let position = self.header_field(COMMIT_POSITION_OFFSET).load(Ordering::Relaxed);
+            let count = count.min(self.capacity).min(position as usize);
+            let start = position - count as u64;
+            let samples: Vec<Vec<f64>> = (0..self.channels)
+                .map(|ch| {
+                    (start..position)
+                        .map(|p| {
+                            let offset = HEADER_SIZE + (ch * self.capacity + (p % self.capacity as u64) as usize) * 8;
+                            // SAFETY: offset is within the data region; torn
+                            // reads are detected by the epoch check below
+                            let bytes = unsafe { std::ptr::read_volatile(self.base.add(offset) as *const [u8; 8]) };
+                            f64::from_le_bytes(bytes)
+                        })
+                        .collect()
+                })
+                .collect();
+            fence(Ordering::Acquire);
+            if epoch.load(Ordering::Relaxed) == before {
+                return Ok((position, samples));
+            }
+        }
+        anyhow::bail!("Ring buffer is being written continuously; no consistent read")
+    }
+
+    pub fn sample_rate(&self) -> u64 {
+        self.sample_rate
+    }
+
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
     pub fn get_write_sequence(&self) -> u64 {
-        unsafe { (*self.write_sequence).load(Ordering::Acquire) }
+        self.header_field(WRITE_SEQUENCE_OFFSET).load(Ordering::Acquire)
+    }
+
+    /// Samples per channel committed since creation
+    pub fn get_commit_position(&self) -> u64 {
+        self.header_field(COMMIT_POSITION_OFFSET).load(Ordering::Acquire)
+    }
+
+    /// Seqlock epoch, odd while a write is in progress
+    pub fn get_write_epoch(&self) -> u64 {
+        self.header_field(WRITE_EPOCH_OFFSET).load(Ordering::Acquire)
     }
 }
@@ -0,0 +1,50 @@
+use std::process::Command;
+use tempfile::tempdir;
+
+#[test]
+fn test_run_pipeline_for_frame_count() {
+    let dir = tempdir().unwrap();
+    let export_path = dir.path().join("out.csv");
+    let pipeline_path = dir.path().join("pipeline.json");
+
+    let pipeline = serde_json::json!({
+        "nodes": [
+            {"id": "source", "type": "AudioSourceNode", "config": {"buffer_size": 16}},
+            {"id": "gain", "type": "GainNode", "config": {"gain_db": 6.0}},
+            {"id": "export", "type": "DataExportNode", "config": {
+                "path": export_path.to_str().unwrap(),
+                "flush_interval_ms": 60000
+            }}
+        ],
+        "connections": [
+            {"from": "source", "to": "gain"},
+            {"from": "gain", "to": "export"}
+        ]
+    });
+    std::fs::write(&pipeline_path, pipeline.to_string()).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_audiotab-run"))
+        .arg(&pipeline_path)
+        .args(["--frames", "5", "--interval-ms", "1", "--quiet"])
+        .output()
+        .unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("Triggered 5 frames"));
+    assert!(stdout.contains("=== Pipeline Metrics ==="));
+
+    // Sinks are destroyed on stop, so buffered rows reach the file
+    let csv = std::fs::read_to_string(&export_path).unwrap();
+    assert_eq!(csv.lines().count(), 6);
+}
+
+#[test]
+fn test_run_rejects_missing_pipeline() {
+    let output = Command::new(env!("CARGO_BIN_EXE_audiotab-run"))
+        .args(["--frames", "1"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Missing pipeline file"));
+}
@@ -0,0 +1,64 @@
+use audiotab::buffers::FramePool;
+use audiotab::hal::format_converter::packet_to_frame_with_pool;
+use audiotab::hal::{PacketBuffer, SampleData};
+
+fn packet(num_channels: usize, frames: usize) -> PacketBuffer {
+    PacketBuffer {
+        data: SampleData::F32(vec![0.25; num_channels * frames]),
+        sample_rate: 192000,
+        num_channels,
+        timestamp: None,
+        codec: None,
+    }
+}
+
+#[test]
+fn test_frame_pool_recycles_dropped_channels() {
+    let pool = FramePool::new(4);
+
+    let channel = pool.channel(pool.take_zeroed(1024));
+    assert_eq!(pool.allocations(), 1);
+    assert_eq!(pool.available(), 0);
+
+    // Buffer stays out while any clone still holds it
+    let clone = channel.clone();
+    drop(channel);
+    assert_eq!(pool.available(), 0);
+    drop(clone);
+    assert_eq!(pool.available(), 1);
+
+    let reused = pool.take(512);
+    assert!(reused.is_empty());
+    assert!(reused.capacity() >= 1024);
+    assert_eq!(pool.allocations(), 1);
+}
+
+#[test]
+fn test_frame_pool_is_bounded() {
+    let pool = FramePool::new(2);
+    for _ in 0..4 {
+        pool.recycle(vec![0.0; 16]);
+    }
+    assert_eq!(pool.available(), 2);
+
+    // Samples taken out of a pooled channel are no longer returned
+    let samples = pool.channel(pool.take_zeroed(8)).into_samples();
+    assert_eq!(samples.len(), 8);
+    assert_eq!(pool.available(), 1);
+}
+
+#[test]
+fn test_packet_to_frame_steady_state_does_not_allocate() {
+    let pool = FramePool::new(64);
+    let packet = packet(8, 1920);
+
+    for sequence_id in 0..100 {
+        let frame = packet_to_frame_with_pool(&packet, sequence_id, &pool).unwrap();
+        assert_eq!(frame.payload.len(), 8);
+        assert_eq!(frame.payload["ch7"].len(), 1920);
+    }
+
+    // Only the first frame's buffers were allocated
+    assert_eq!(pool.allocations(), 8);
+    assert_eq!(pool.available(), 8);
+}
@@ -1,4 +1,4 @@
-use audiotab::core::DataFrame;
+use audiotab::core::{Channel, ChannelRole, DataFrame, Metadata, SharedFrame};
 use std::sync::Arc;
 
 #[test]
@@ -13,8 +13,7 @@ fn test_dataframe_creation() {
 #[test]
 fn test_dataframe_with_data() {
     let mut df = DataFrame::new(2000, 2);
-    df.payload
-        .insert("channel1".to_string(), Arc::new(vec![1.0, 2.0, 3.0]));
+    df.insert_channel("channel1", vec![1.0, 2.0, 3.0]);
 
     assert_eq!(df.payload.get("channel1").unwrap().as_ref(), &vec![1.0, 2.0, 3.0]);
 }
@@ -22,13 +21,70 @@ fn test_dataframe_with_data() {
 #[test]
 fn test_dataframe_zero_copy_clone() {
     let mut frame = DataFrame::new(1000, 1);
-    frame.payload.insert("channel".to_string(), Arc::new(vec![1.0, 2.0, 3.0]));
+    frame.insert_channel("channel", vec![1.0, 2.0, 3.0]);
+    frame.metadata.insert("sample_rate", 48000.0);
 
-    let _cloned = frame.clone();
+    let cloned = frame.clone();
 
-    // Both should share the same Arc
-    assert_eq!(
-        Arc::strong_count(frame.payload.get("channel").unwrap()),
-        2
-    );
+    // Both frames share the same sample buffer and metadata
+    assert!(Channel::ptr_eq(&frame.payload["channel"], &cloned.payload["channel"]));
+    assert!(Metadata::ptr_eq(&frame.metadata, &cloned.metadata));
+}
+
+#[test]
+fn test_dataframe_copy_on_write() {
+    let mut frame = DataFrame::new(1000, 1);
+    frame.insert_channel("channel", vec![1.0, 2.0, 3.0]);
+    frame.metadata.insert("gain", 1.0);
+
+    let mut cloned = frame.clone();
+    cloned.payload.get_mut("channel").unwrap().samples_mut()[0] = 10.0;
+    cloned.metadata.insert("gain", 2.0);
+
+    assert_eq!(frame.payload["channel"].samples(), &[1.0, 2.0, 3.0]);
+    assert_eq!(cloned.payload["channel"].samples(), &[10.0, 2.0, 3.0]);
+    assert!(!Channel::ptr_eq(&frame.payload["channel"], &cloned.payload["channel"]));
+    assert_eq!(frame.metadata.get_f64("gain"), Some(1.0));
+    assert_eq!(cloned.metadata.get_f64("gain"), Some(2.0));
+}
+
+#[test]
+fn test_typed_channels_and_metadata() {
+    let mut frame = DataFrame::new(0, 0);
+    frame.metadata.insert("sample_rate", 48000u32);
+    frame.metadata.insert("triggered", true);
+    frame.metadata.insert("weighting", "A");
+    frame.insert_channel("mic", Channel::new(vec![0.5; 4]).with_unit("Pa"));
+    frame.insert_channel("env", Channel::new(vec![0.5]).with_sample_rate(100.0));
+    frame.insert_channel("spl", Channel::scalar(94.0).with_unit("dB"));
+
+    assert_eq!(frame.sample_rate(), Some(48000.0));
+    assert_eq!(frame.channel_sample_rate("mic"), Some(48000.0));
+    assert_eq!(frame.channel_sample_rate("env"), Some(100.0));
+    assert_eq!(frame.payload["mic"].unit.as_deref(), Some("Pa"));
+    assert_eq!(frame.payload["spl"].role, ChannelRole::Measurement);
+    assert_eq!(frame.metadata.get_bool("triggered"), Some(true));
+    assert_eq!(frame.metadata["weighting"], "A");
+
+    // Metadata serializes as plain JSON values
+    let json = serde_json::to_value(&frame.metadata).unwrap();
+    assert_eq!(json["sample_rate"], 48000);
+    assert_eq!(json["triggered"], true);
+    assert_eq!(json["weighting"], "A");
+}
+
+#[test]
+fn test_shared_frame_fan_out() {
+    let mut frame = DataFrame::new(1000, 1);
+    frame.insert_channel("channel", vec![1.0, 2.0, 3.0]);
+    let shared: SharedFrame = Arc::new(frame);
+
+    // Earlier consumers get a cheap clone that still shares samples
+    let first = DataFrame::from_shared(shared.clone());
+    assert!(Channel::ptr_eq(&first.payload["channel"], &shared.payload["channel"]));
+
+    // The last consumer takes the frame without copying
+    let samples = shared.payload["channel"].shared();
+    let last = DataFrame::from_shared(shared);
+    assert!(Arc::ptr_eq(&last.payload["channel"].shared(), &samples));
 }
@@ -0,0 +1,59 @@
+use anyhow::Context;
+use audiotab::core::AudiotabError;
+use audiotab::engine::AsyncPipeline;
+use serde_json::json;
+
+#[test]
+fn test_serializes_with_kind_tag() {
+    let err = AudiotabError::device("mic-1", "Device 'mic-1' is busy");
+    assert_eq!(
+        serde_json::to_value(&err).unwrap(),
+        json!({"kind": "device", "device": "mic-1", "message": "Device 'mic-1' is busy"})
+    );
+    assert_eq!(err.kind(), "device");
+    assert_eq!(err.to_string(), "Device 'mic-1' is busy");
+
+    let parsed: AudiotabError = serde_json::from_value(json!({"kind": "graph", "message": "bad edge"})).unwrap();
+    assert_eq!(parsed, AudiotabError::graph("bad edge"));
+}
+
+#[test]
+fn test_keeps_kind_through_anyhow() {
+    let inner: anyhow::Result<()> = Err(AudiotabError::node_config("gain", "gain_db must be finite").into());
+    let err = AudiotabError::from(inner.context("Failed to configure").unwrap_err());
+    assert_eq!(err, AudiotabError::node_config("gain", "Failed to configure: gain_db must be finite"));
+
+    let io = std::fs::read("/nonexistent/audiotab").context("Failed to read capture").unwrap_err();
+    assert_eq!(AudiotabError::from(io).kind(), "io");
+
+    let plain = AudiotabError::from(anyhow::anyhow!("something broke"));
+    assert_eq!(plain, AudiotabError::other("something broke"));
+    assert_eq!(AudiotabError::from_device("mic", anyhow::anyhow!("unplugged")), AudiotabError::device("mic", "unplugged"));
+    assert_eq!(plain.context("While starting").message(), "While starting: something broke");
+}
+
+#[tokio::test]
+async fn test_pipeline_errors_are_classified() {
+    let err = AsyncPipeline::from_json(json!({"nodes": [{"id": "x", "type": "NoSuchNode", "config": {}}]}))
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), "graph");
+    assert!(err.message().contains("Unknown node type"), "{}", err);
+
+    let err = AsyncPipeline::from_json(json!({"nodes": [{"id": "gain", "type": "Gain", "config": {"gain_db": "loud"}}]}))
+        .await
+        .err()
+        .unwrap();
+    assert!(matches!(err, AudiotabError::Config { node: Some(ref node), .. } if node == "gain"), "{:?}", err);
+
+    let mut pipeline = AsyncPipeline::from_json(json!({"nodes": [{"id": "gain", "type": "Gain", "config": {}}]}))
+        .await
+        .unwrap();
+    let err = pipeline.connect(json!({"from": "gain", "to": "missing"})).unwrap_err();
+    assert_eq!(err.kind(), "graph");
+
+    pipeline.start().await.unwrap();
+    assert_eq!(pipeline.start().await.unwrap_err().kind(), "state");
+    pipeline.stop().await.unwrap();
+}
@@ -0,0 +1,103 @@
+use audiotab::core::{DataFrame, EventPoint, EventSeries, ProcessingNode, Reblocker};
+use audiotab::engine::AsyncPipeline;
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+
+const MS: u64 = 1_000_000;
+
+/// 1 kHz frame of `len` samples starting at sample `start`, with tacho pulses at `pulses_ms`
+fn packet(sequence_id: u64, start: usize, len: usize, pulses_ms: &[u64]) -> DataFrame {
+    let mut frame = DataFrame::new(start as u64 * MS, sequence_id);
+    frame.insert_channel("ch0", vec![0.5; len]);
+    frame.insert_events("tacho", EventSeries::from_times(pulses_ms.iter().map(|t| t * MS)));
+    frame.metadata.insert("sample_rate", 1000.0);
+    frame
+}
+
+fn times_ms(series: &EventSeries) -> Vec<u64> {
+    series.iter().map(|p| p.time_ns / MS).collect()
+}
+
+#[test]
+fn test_series_stays_in_time_order() {
+    let mut series = EventSeries::new(vec![EventPoint::new(30, 3.0), EventPoint::new(10, 1.0)]).with_unit("V");
+    series.push(20, 2.0);
+    series.extend(&EventSeries::new(vec![EventPoint::new(5, 0.5), EventPoint::new(40, 4.0)]));
+    let values: Vec<f64> = series.iter().map(|p| p.value).collect();
+    assert_eq!(values, vec![0.5, 1.0, 2.0, 3.0, 4.0]);
+    assert_eq!(series.unit.as_deref(), Some("V"));
+
+    // Clones share points until one is changed
+    let copy = series.clone();
+    let head = series.split_before(20);
+    assert_eq!(head.len(), 2);
+    assert_eq!(series.len(), 3);
+    assert_eq!(copy.len(), 5);
+    assert_eq!(copy.window(10, 30).len(), 2);
+}
+
+#[test]
+fn test_intervals_and_rate() {
+    // Tacho pulses every 20 ms: 50 Hz
+    let series = EventSeries::from_times((0..6).map(|n| n * 20 * MS));
+    assert!(series.intervals().iter().all(|&dt| (dt - 0.02).abs() < 1e-12));
+    assert!((series.rate_hz().unwrap() - 50.0).abs() < 1e-9);
+    assert_eq!(EventSeries::from_times([MS]).rate_hz(), None);
+}
+
+#[test]
+fn test_frames_count_event_bytes() {
+    let frame = packet(0, 0, 4, &[1, 2]);
+    assert_eq!(frame.sample_bytes(), 4 * 8 + 2 * std::mem::size_of::<EventPoint>());
+}
+
+#[test]
+fn test_reblocker_sends_events_with_their_block() {
+    let mut reblocker = Reblocker::new(4);
+    assert!(reblocker.push(&packet(0, 0, 3, &[0, 2])).is_empty());
+    let blocks = reblocker.push(&packet(1, 3, 3, &[3, 5]));
+    assert_eq!(blocks.len(), 1);
+    // The first block spans 0-4 ms
+    assert_eq!(times_ms(&blocks[0].events["tacho"]), vec![0, 2, 3]);
+
+    let tail = reblocker.finish().unwrap();
+    assert_eq!(times_ms(&tail.events["tacho"]), vec![5]);
+}
+
+/// Records the tacho pulses of every frame it receives
+struct PulseRecorder {
+    pulses: Arc<Mutex<Vec<Vec<u64>>>>,
+}
+
+#[async_trait]
+impl ProcessingNode for PulseRecorder {
+    async fn process(&mut self, input: DataFrame) -> anyhow::Result<DataFrame> {
+        let pulses = input.events.get("tacho").map(times_ms).unwrap_or_default();
+        self.pulses.lock().unwrap().push(pulses);
+        Ok(input)
+    }
+}
+
+#[tokio::test]
+async fn test_events_flow_through_pipeline_alongside_audio() {
+    let graph = serde_json::json!({
+        "nodes": [
+            {"id": "gain", "type": "Gain", "config": {"gain": 2.0}},
+            {"id": "sink", "type": "Print", "config": {}}
+        ],
+        "connections": [{"from": "gain", "to": "sink", "decimation": 2, "block_size": 5}]
+    });
+    let pulses = Arc::new(Mutex::new(Vec::new()));
+    let mut pipeline = AsyncPipeline::from_json(graph).await.unwrap();
+    pipeline.nodes_mut().insert("sink".to_string(), Box::new(PulseRecorder { pulses: pulses.clone() }));
+
+    pipeline.start().await.unwrap();
+    for i in 0..4u64 {
+        let pulse = i * 10 + 4;
+        pipeline.trigger(packet(i, i as usize * 10, 10, &[pulse])).await.unwrap();
+    }
+    pipeline.stop().await.unwrap();
+
+    // Decimated to 500 Hz, 5-sample blocks span 10 ms each, one pulse per block
+    assert_eq!(*pulses.lock().unwrap(), vec![vec![4], vec![14], vec![24], vec![34]]);
+}
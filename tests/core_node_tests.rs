@@ -1,7 +1,6 @@
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use audiotab::core::{DataFrame, ProcessingNode};
-use std::sync::Arc;
 use tokio::sync::mpsc;
 
 struct DummyNode {
@@ -19,7 +18,7 @@ impl ProcessingNode for DummyNode {
         let mut output = input.clone();
         if let Some(data) = output.payload.get("test") {
             let multiplied: Vec<f64> = data.iter().map(|&x| x * self.multiplier).collect();
-            output.payload.insert("test".to_string(), Arc::new(multiplied));
+            output.insert_channel("test", multiplied);
         }
         Ok(output)
     }
@@ -33,7 +32,7 @@ async fn test_node_process() {
     node.on_create(config).await.unwrap();
 
     let mut df = DataFrame::new(0, 0);
-    df.payload.insert("test".to_string(), Arc::new(vec![1.0, 2.0, 3.0]));
+    df.insert_channel("test", vec![1.0, 2.0, 3.0]);
 
     let result = node.process(df).await.unwrap();
     assert_eq!(result.payload.get("test").unwrap().as_ref(), &vec![2.0, 4.0, 6.0]);
@@ -53,7 +52,7 @@ impl ProcessingNode for StreamingDummyNode {
     async fn process(&mut self, mut input: DataFrame) -> Result<DataFrame> {
         if let Some(data) = input.payload.get("test") {
             let multiplied: Vec<f64> = data.iter().map(|&x| x * self.multiplier).collect();
-            input.payload.insert("test".to_string(), Arc::new(multiplied));
+            input.insert_channel("test", multiplied);
         }
         Ok(input)
     }
@@ -83,11 +82,11 @@ async fn test_node_streaming() {
 
     // Send frames
     let mut df1 = DataFrame::new(0, 0);
-    df1.payload.insert("test".to_string(), Arc::new(vec![1.0, 2.0]));
+    df1.insert_channel("test", vec![1.0, 2.0]);
     tx_in.send(df1).await.unwrap();
 
     let mut df2 = DataFrame::new(1000, 1);
-    df2.payload.insert("test".to_string(), Arc::new(vec![3.0, 4.0]));
+    df2.insert_channel("test", vec![3.0, 4.0]);
     tx_in.send(df2).await.unwrap();
 
     drop(tx_in); // Close channel to terminate node
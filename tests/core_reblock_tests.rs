@@ -0,0 +1,117 @@
+use audiotab::core::{Channel, ChannelRole, DataFrame, ProcessingNode, Reblocker};
+use audiotab::engine::AsyncPipeline;
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+
+/// 1 kHz frame of `len` samples counting up from `start`, stamped at its first sample
+fn packet(sequence_id: u64, start: usize, len: usize) -> DataFrame {
+    let mut frame = DataFrame::new(start as u64 * 1_000_000, sequence_id);
+    frame.insert_channel("ch0", (start..start + len).map(|n| n as f64).collect::<Vec<_>>());
+    frame.metadata.insert("sample_rate", 1000.0);
+    frame
+}
+
+#[test]
+fn test_reblocker_accumulates_small_packets() {
+    let mut reblocker = Reblocker::new(4);
+    assert!(reblocker.push(&packet(0, 0, 3)).is_empty());
+    assert_eq!(reblocker.pending_len(), 3);
+
+    let blocks = reblocker.push(&packet(1, 3, 3));
+    assert_eq!(blocks.len(), 1);
+    assert_eq!(blocks[0].payload["ch0"].samples(), &[0.0, 1.0, 2.0, 3.0]);
+    assert_eq!(blocks[0].timestamp, 0);
+    assert_eq!(blocks[0].sequence_id, 0);
+    assert_eq!(blocks[0].metadata.get_i64("block_size"), Some(4));
+
+    // The remainder is stamped at its first sample (4 ms)
+    let tail = reblocker.finish().unwrap();
+    assert_eq!(tail.payload["ch0"].samples(), &[4.0, 5.0]);
+    assert_eq!(tail.timestamp, 4_000_000);
+    assert_eq!(tail.sequence_id, 1);
+    assert_eq!(tail.metadata["partial_block"], true);
+    assert!(reblocker.finish().is_none());
+}
+
+#[test]
+fn test_reblocker_splits_large_frames_and_carries_measurements() {
+    let mut reblocker = Reblocker::new(2);
+    let mut frame = packet(0, 0, 5);
+    frame.insert_channel("ch0", Channel::new((0..5).map(|n| n as f64).collect()).with_unit("Pa"));
+    frame.insert_channel("level", Channel::scalar(94.0));
+
+    let blocks = reblocker.push(&frame);
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks[1].payload["ch0"].samples(), &[2.0, 3.0]);
+    assert_eq!(blocks[1].payload["ch0"].unit.as_deref(), Some("Pa"));
+    assert_eq!(blocks[1].timestamp, 2_000_000);
+    assert!(blocks[0].payload.contains_key("level"));
+    assert!(!blocks[1].payload.contains_key("level"));
+    assert_eq!(reblocker.pending_len(), 1);
+}
+
+#[test]
+fn test_reblocker_passes_spectrum_frames_through() {
+    let mut reblocker = Reblocker::new(4);
+    for sequence_id in 0..3 {
+        let mut fft = DataFrame::new(sequence_id * 1_000_000, sequence_id);
+        fft.insert_channel("ch0", Channel::new(vec![1.0, 2.0, 3.0]).with_role(ChannelRole::Spectrum));
+
+        let blocks = reblocker.push(&fft);
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].sequence_id, sequence_id);
+        assert_eq!(blocks[0].payload["ch0"].samples(), &[1.0, 2.0, 3.0]);
+    }
+    assert!(reblocker.finish().is_none());
+}
+
+/// Records the length of every frame it receives
+struct LengthRecorder {
+    lengths: Arc<Mutex<Vec<usize>>>,
+}
+
+#[async_trait]
+impl ProcessingNode for LengthRecorder {
+    async fn process(&mut self, input: DataFrame) -> anyhow::Result<DataFrame> {
+        let len = input.payload.get("ch0").map(|c| c.len()).unwrap_or(0);
+        self.lengths.lock().unwrap().push(len);
+        Ok(input)
+    }
+}
+
+#[tokio::test]
+async fn test_pipeline_reblocks_on_node_input() {
+    let graph = serde_json::json!({
+        "nodes": [
+            {"id": "source", "type": "Gain", "config": {}},
+            {"id": "fft_in", "type": "Print", "config": {}, "input_block_size": 8}
+        ],
+        "connections": [{"from": "source", "to": "fft_in"}]
+    });
+    let lengths = Arc::new(Mutex::new(Vec::new()));
+    let mut pipeline = AsyncPipeline::from_json(graph).await.unwrap();
+    pipeline
+        .nodes_mut()
+        .insert("fft_in".to_string(), Box::new(LengthRecorder { lengths: lengths.clone() }));
+
+    pipeline.start().await.unwrap();
+    for i in 0..5 {
+        pipeline.trigger(packet(i, i as usize * 3, 3)).await.unwrap();
+    }
+    pipeline.stop().await.unwrap();
+
+    // 15 samples: one full block, then the remainder at end of stream
+    assert_eq!(*lengths.lock().unwrap(), vec![8, 7]);
+}
+
+#[tokio::test]
+async fn test_pipeline_rejects_zero_block_size() {
+    let graph = serde_json::json!({
+        "nodes": [
+            {"id": "a", "type": "Gain", "config": {}},
+            {"id": "b", "type": "Gain", "config": {}}
+        ],
+        "connections": [{"from": "a", "to": "b", "block_size": 0}]
+    });
+    assert!(AsyncPipeline::from_json(graph).await.is_err());
+}
@@ -0,0 +1,75 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::engine::AsyncPipeline;
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Sink that counts frames, taking `delay` for each
+struct CountingSink {
+    count: Arc<AtomicUsize>,
+    delay: Duration,
+}
+
+#[async_trait]
+impl ProcessingNode for CountingSink {
+    async fn process(&mut self, input: DataFrame) -> anyhow::Result<DataFrame> {
+        tokio::time::sleep(self.delay).await;
+        self.count.fetch_add(1, Ordering::SeqCst);
+        Ok(input)
+    }
+}
+
+#[tokio::test]
+async fn test_lossy_branch_drops_while_recording_branch_keeps_every_frame() {
+    let config = json!({
+        "nodes": [
+            {"id": "src", "type": "Gain", "config": {}},
+            {"id": "scope", "type": "Print", "config": {}},
+            {"id": "recorder", "type": "Print", "config": {}}
+        ],
+        "connections": [
+            {"from": "src", "to": "scope", "backpressure": "drop_newest"},
+            {"from": "src", "to": "recorder"}
+        ],
+        "pipeline_config": {"channel_capacity": 2}
+    });
+    let mut pipeline = AsyncPipeline::from_json(config).await.unwrap();
+
+    let scope = Arc::new(AtomicUsize::new(0));
+    let recorder = Arc::new(AtomicUsize::new(0));
+    let nodes = pipeline.nodes_mut();
+    nodes.insert("scope".to_string(), Box::new(CountingSink { count: scope.clone(), delay: Duration::from_millis(20) }));
+    nodes.insert("recorder".to_string(), Box::new(CountingSink { count: recorder.clone(), delay: Duration::ZERO }));
+    pipeline.start().await.unwrap();
+
+    for _ in 0..20 {
+        pipeline.trigger(DataFrame::new(0, 0)).await.unwrap();
+    }
+    pipeline.stop().await.unwrap();
+
+    let dropped = pipeline
+        .get_monitor()
+        .unwrap()
+        .node_metrics()
+        .into_iter()
+        .find(|m| m.node_id == "src")
+        .unwrap()
+        .frames_dropped;
+    assert_eq!(recorder.load(Ordering::SeqCst), 20);
+    assert!(dropped > 0);
+    assert_eq!(scope.load(Ordering::SeqCst) as u64 + dropped, 20);
+}
+
+#[tokio::test]
+async fn test_rejects_unknown_backpressure_policy() {
+    let config = json!({
+        "nodes": [
+            {"id": "src", "type": "Gain", "config": {}},
+            {"id": "sink", "type": "Print", "config": {}}
+        ],
+        "connections": [{"from": "src", "to": "sink", "backpressure": "spill"}]
+    });
+    assert!(AsyncPipeline::from_json(config).await.is_err());
+}
@@ -0,0 +1,91 @@
+use audiotab::core::DataFrame;
+use audiotab::engine::{DriftCompensator, DriftEstimator};
+use audiotab::observability::DriftMetrics;
+use std::sync::Arc;
+
+const FRAME: usize = 480;
+const WINDOW_NS: u64 = 1_000_000_000;
+
+fn frame(len: usize) -> DataFrame {
+    let mut frame = DataFrame::new(0, 0);
+    frame.insert_channel("ch0", vec![0.5; len]);
+    frame.insert_channel("ch1", vec![-0.5; len]);
+    frame
+}
+
+/// Feed `seconds` of frames from a device whose clock runs at `ratio` x nominal
+fn run(compensator: &mut DriftCompensator, ratio: f64, seconds: u64) -> usize {
+    let frames = seconds * 48000 / FRAME as u64;
+    let period_ns = FRAME as f64 / (48000.0 * ratio) * 1e9;
+    let mut output = 0;
+    for i in 0..frames {
+        let mut f = frame(FRAME);
+        compensator.process(&mut f, (i as f64 * period_ns) as u64);
+        assert_eq!(f.payload["ch0"].len(), f.payload["ch1"].len());
+        output += f.payload["ch0"].len();
+    }
+    output
+}
+
+#[test]
+fn test_drift_estimator_measures_rate() {
+    let mut estimator = DriftEstimator::with_window(48000.0, WINDOW_NS);
+    let period_ns = FRAME as f64 / 48004.8 * 1e9;
+    for i in 0..200 {
+        estimator.observe((i as f64 * period_ns) as u64, FRAME);
+    }
+
+    let ppm = (estimator.rate_ratio().unwrap() - 1.0) * 1e6;
+    assert!((ppm - 100.0).abs() < 1.0, "ppm = {}", ppm);
+
+    // No estimate before the window is full
+    let mut early = DriftEstimator::with_window(48000.0, WINDOW_NS);
+    early.observe(0, FRAME);
+    early.observe(10_000_000, FRAME);
+    assert!(early.rate_ratio().is_none());
+}
+
+#[test]
+fn test_drift_compensation_aligns_to_reference() {
+    let reference_metrics = Arc::new(DriftMetrics::new("ref"));
+    let mut reference = DriftCompensator::new(
+        DriftEstimator::with_window(48000.0, WINDOW_NS),
+        reference_metrics.clone(),
+        None,
+    );
+    let fast_metrics = Arc::new(DriftMetrics::new("fast"));
+    let mut fast = DriftCompensator::new(
+        DriftEstimator::with_window(48000.0, WINDOW_NS),
+        fast_metrics.clone(),
+        Some(reference_metrics.clone()),
+    );
+
+    // Reference is never corrected
+    assert_eq!(run(&mut reference, 1.0, 10), 480_000);
+    assert_eq!(reference_metrics.samples_dropped(), 0);
+
+    // A device 500 ppm fast delivers 24 extra samples per second of reference time
+    let output = run(&mut fast, 1.0005, 10);
+    let snapshot = fast_metrics.snapshot();
+    assert!((snapshot.drift_ppm - 500.0).abs() < 5.0, "ppm = {}", snapshot.drift_ppm);
+    assert!(snapshot.samples_dropped > 150, "dropped {}", snapshot.samples_dropped);
+    assert_eq!(snapshot.samples_inserted, 0);
+    assert_eq!(output, 480_000 - snapshot.samples_dropped as usize);
+}
+
+#[test]
+fn test_drift_compensation_duplicates_for_slow_device() {
+    let reference_metrics = Arc::new(DriftMetrics::new("ref"));
+    reference_metrics.set_rate_ratio(1.0);
+    let slow_metrics = Arc::new(DriftMetrics::new("slow"));
+    let mut slow = DriftCompensator::new(
+        DriftEstimator::with_window(48000.0, WINDOW_NS),
+        slow_metrics.clone(),
+        Some(reference_metrics),
+    );
+
+    run(&mut slow, 0.999, 5);
+    assert!(slow_metrics.drift_ppm() < -900.0);
+    assert!(slow_metrics.samples_inserted() > 100);
+    assert_eq!(slow_metrics.samples_dropped(), 0);
+}
@@ -0,0 +1,96 @@
+use audiotab::engine::{AsyncPipeline, GraphDocument};
+use audiotab::engine::graph_document::GRAPH_DOCUMENT_VERSION;
+use serde_json::json;
+use tempfile::tempdir;
+
+fn frontend_graph() -> serde_json::Value {
+    json!({
+        "schema_version": 1,
+        "name": "Mic check",
+        "nodes": [
+            {"id": "src", "type": "AudioSourceNode", "position": {"x": 10.0, "y": 20.0},
+             "parameters": {"buffer_size": 256}, "data": {"label": "Mic"}},
+            {"id": "gain", "type": "GainNode", "parameters": {"gain_db": 3.0}}
+        ],
+        "edges": [
+            {"id": "e1", "source": "src", "target": "gain", "sourceHandle": "out", "animated": true}
+        ],
+        "pipeline_config": {"channel_capacity": 8}
+    })
+}
+
+#[test]
+fn test_save_and_load_roundtrip() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("setup.atproj");
+
+    let doc = GraphDocument::from_value(frontend_graph()).unwrap();
+    doc.save(&path).unwrap();
+    let loaded = GraphDocument::load(&path).unwrap();
+
+    assert_eq!(loaded, doc);
+    assert_eq!(loaded.nodes[0].position.as_ref().unwrap().x, 10.0);
+    // Unknown UI fields survive the round trip
+    assert_eq!(loaded.nodes[0].extra["data"]["label"], "Mic");
+    assert_eq!(loaded.edges[0].extra["animated"], true);
+    assert_eq!(loaded.edges[0].source_handle.as_deref(), Some("out"));
+}
+
+#[test]
+fn test_migrates_unversioned_pipeline() {
+    let legacy = json!({
+        "nodes": [
+            {"id": "a", "type": "GainNode", "config": {"gain_db": 6.0}},
+            {"id": "b", "type": "DebugSinkNode", "config": {}}
+        ],
+        "connections": [{"from": "a", "to": "b"}]
+    });
+
+    let doc = GraphDocument::from_value(legacy).unwrap();
+    assert_eq!(doc.schema_version, GRAPH_DOCUMENT_VERSION);
+    assert_eq!(doc.nodes[0].parameters["gain_db"], 6.0);
+    assert_eq!(doc.edges[0].source, "a");
+    assert_eq!(doc.edges[0].target, "b");
+}
+
+#[test]
+fn test_rejects_newer_schema() {
+    let mut future = frontend_graph();
+    future["schema_version"] = json!(GRAPH_DOCUMENT_VERSION + 1);
+    assert!(GraphDocument::from_value(future).is_err());
+}
+
+#[tokio::test]
+async fn test_document_builds_pipeline() {
+    let doc = GraphDocument::from_value(frontend_graph()).unwrap();
+    let pipeline = AsyncPipeline::from_json(doc.to_pipeline_json()).await;
+    assert!(pipeline.is_ok());
+}
+
+#[tokio::test]
+async fn test_document_forwards_variadic_ports() {
+    let doc = json!({
+        "schema_version": 1,
+        "nodes": [
+            {"id": "a", "type": "AudioSourceNode", "parameters": {}},
+            {"id": "b", "type": "AudioSourceNode", "parameters": {}},
+            {"id": "c", "type": "AudioSourceNode", "parameters": {}},
+            {"id": "merge", "type": "FrameMerge", "parameters": {}, "port_counts": {"_inputs": 3}}
+        ],
+        "edges": [
+            {"id": "e0", "source": "a", "target": "merge", "sourceHandle": "out", "targetHandle": "_inputs_0"},
+            {"id": "e1", "source": "b", "target": "merge", "sourceHandle": "out", "targetHandle": "_inputs_1"},
+            {"id": "e2", "source": "c", "target": "merge", "sourceHandle": "out", "targetHandle": "_inputs_2"}
+        ]
+    });
+    let doc = GraphDocument::from_value(GraphDocument::from_value(doc).unwrap().to_value().unwrap()).unwrap();
+
+    let pipeline_json = doc.to_pipeline_json();
+    assert_eq!(pipeline_json["nodes"][3]["port_counts"], json!({"_inputs": 3}));
+    let conn = &pipeline_json["connections"][2];
+    assert_eq!(conn["to_port"], "_inputs_2");
+    // Single-output sources need no port name
+    assert!(conn.get("from_port").is_none());
+
+    assert!(AsyncPipeline::from_json(pipeline_json).await.is_ok());
+}
@@ -0,0 +1,160 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::engine::AsyncPipeline;
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Sink that keeps every frame it receives
+struct CollectSink(Arc<Mutex<Vec<DataFrame>>>);
+
+#[async_trait]
+impl ProcessingNode for CollectSink {
+    async fn process(&mut self, input: DataFrame) -> anyhow::Result<DataFrame> {
+        self.0.lock().unwrap().push(input.clone());
+        Ok(input)
+    }
+}
+
+fn frame(value: f64) -> DataFrame {
+    let mut frame = DataFrame::new(0, 0);
+    frame.insert_channel("main_channel", vec![value]);
+    frame
+}
+
+async fn wait_for(frames: &Arc<Mutex<Vec<DataFrame>>>, count: usize) {
+    for _ in 0..200 {
+        if frames.lock().unwrap().len() >= count {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+}
+
+async fn running_pipeline(sinks: &[&str]) -> (AsyncPipeline, Vec<Arc<Mutex<Vec<DataFrame>>>>) {
+    let mut nodes = vec![json!({"id": "src", "type": "Gain", "config": {}})];
+    let mut connections = Vec::new();
+    for sink in sinks {
+        nodes.push(json!({"id": sink, "type": "Print", "config": {}}));
+        connections.push(json!({"from": "src", "to": sink}));
+    }
+    let mut pipeline = AsyncPipeline::from_json(json!({"nodes": nodes, "connections": connections}))
+        .await
+        .unwrap();
+    let collected: Vec<_> = sinks.iter().map(|_| Arc::new(Mutex::new(Vec::new()))).collect();
+    for (sink, frames) in sinks.iter().zip(&collected) {
+        pipeline.nodes_mut().insert(sink.to_string(), Box::new(CollectSink(frames.clone())));
+    }
+    pipeline.start().await.unwrap();
+    (pipeline, collected)
+}
+
+#[tokio::test]
+async fn test_splices_node_into_running_pipeline() {
+    let (mut pipeline, collected) = running_pipeline(&["sink"]).await;
+    let frames = &collected[0];
+
+    pipeline.trigger(frame(1.0)).await.unwrap();
+    wait_for(frames, 1).await;
+
+    pipeline
+        .add_node(json!({"id": "boost", "type": "Gain", "config": {"gain_db": 20.0}}))
+        .await
+        .unwrap();
+    pipeline.disconnect("src", "sink").unwrap();
+    pipeline.connect(json!({"from": "src", "to": "boost"})).unwrap();
+    pipeline.connect(json!({"from": "boost", "to": "sink"})).unwrap();
+
+    pipeline.trigger(frame(1.0)).await.unwrap();
+    wait_for(frames, 2).await;
+    pipeline.stop().await.unwrap();
+
+    let frames = frames.lock().unwrap();
+    let values: Vec<f64> = frames.iter().map(|f| f.payload["main_channel"].samples()[0]).collect();
+    assert_eq!(values.len(), 2);
+    assert!((values[0] - 1.0).abs() < 1e-9 && (values[1] - 10.0).abs() < 1e-9, "{:?}", values);
+}
+
+#[tokio::test]
+async fn test_removes_running_node() {
+    let (mut pipeline, collected) = running_pipeline(&["kept", "removed"]).await;
+
+    pipeline.remove_node("removed").await.unwrap();
+    assert!(pipeline.remove_node("removed").await.is_err());
+
+    pipeline.trigger(frame(1.0)).await.unwrap();
+    wait_for(&collected[0], 1).await;
+    pipeline.stop().await.unwrap();
+
+    assert_eq!(collected[0].lock().unwrap().len(), 1);
+    assert!(collected[1].lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_removes_node_fed_twice_by_one_source() {
+    let mut pipeline = AsyncPipeline::from_json(json!({
+        "nodes": [
+            {"id": "src", "type": "Gain", "config": {}},
+            {"id": "xcorr", "type": "CrossCorrelationNode", "config": {}},
+            {"id": "sink", "type": "Print", "config": {}}
+        ],
+        "connections": [
+            {"from": "src", "to": "xcorr", "to_port": "_reference"},
+            {"from": "src", "to": "xcorr", "to_port": "_measurement"},
+            {"from": "src", "to": "sink"}
+        ]
+    }))
+    .await
+    .unwrap();
+    let frames = Arc::new(Mutex::new(Vec::new()));
+    pipeline.nodes_mut().insert("sink".to_string(), Box::new(CollectSink(frames.clone())));
+    pipeline.start().await.unwrap();
+
+    pipeline.remove_node("xcorr").await.unwrap();
+    assert!(pipeline.remove_node("xcorr").await.is_err());
+
+    pipeline.trigger(frame(1.0)).await.unwrap();
+    wait_for(&frames, 1).await;
+    pipeline.stop().await.unwrap();
+    assert_eq!(frames.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_disconnect_and_edit_errors() {
+    let (mut pipeline, collected) = running_pipeline(&["sink"]).await;
+
+    pipeline.disconnect("src", "sink").unwrap();
+    assert!(pipeline.disconnect("src", "sink").is_err());
+    assert!(pipeline.connect(json!({"from": "src", "to": "missing"})).is_err());
+
+    pipeline.trigger(frame(1.0)).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    pipeline.stop().await.unwrap();
+    assert!(collected[0].lock().unwrap().is_empty());
+
+    // Only idle and running pipelines can be edited
+    let err = pipeline.connect(json!({"from": "src", "to": "sink"})).unwrap_err();
+    assert!(err.to_string().contains("idle or running"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_updates_params_of_running_node() {
+    let (mut pipeline, collected) = running_pipeline(&["sink"]).await;
+    let frames = &collected[0];
+
+    pipeline.update_node_params("src", json!({"gain_db": 20.0})).await.unwrap();
+    pipeline.trigger(frame(1.0)).await.unwrap();
+    wait_for(frames, 1).await;
+
+    // A rejected update keeps the previous gain
+    assert!(pipeline.update_node_params("src", json!({"gain_db": "loud"})).await.is_err());
+    assert!(pipeline.update_node_params("missing", json!({"gain_db": 0.0})).await.is_err());
+    pipeline.trigger(frame(1.0)).await.unwrap();
+    wait_for(frames, 2).await;
+    pipeline.stop().await.unwrap();
+
+    let frames = frames.lock().unwrap();
+    let values: Vec<f64> = frames.iter().map(|f| f.payload["main_channel"].samples()[0]).collect();
+    assert_eq!(values.len(), 2);
+    assert!(values.iter().all(|v| (v - 10.0).abs() < 1e-9), "{:?}", values);
+}
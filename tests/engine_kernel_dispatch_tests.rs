@@ -0,0 +1,177 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::engine::{AsyncPipeline, AudioKernelRuntime};
+use audiotab::hal::format_converter::frame_to_packet;
+use audiotab::hal::*;
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+/// Sink that keeps every frame it receives
+struct CollectSink(Arc<Mutex<Vec<DataFrame>>>);
+
+#[async_trait]
+impl ProcessingNode for CollectSink {
+    async fn process(&mut self, input: DataFrame) -> anyhow::Result<DataFrame> {
+        self.0.lock().unwrap().push(input.clone());
+        Ok(input)
+    }
+}
+
+/// Kernel running the input side of a loopback cable as registration "mic",
+/// plus the cable's output side for the test to write into
+async fn kernel_with_loopback() -> (AudioKernelRuntime, Box<dyn Device>) {
+    let mut registry = HardwareRegistry::new();
+    registry.register(LoopbackDriver::new());
+    let registry = Arc::new(RwLock::new(registry));
+
+    let mic = RegisteredHardware {
+        registration_id: "mic".to_string(),
+        device_id: "loopback-input".to_string(),
+        hardware_name: "Loopback".to_string(),
+        driver_id: "loopback".to_string(),
+        hardware_type: HardwareType::Acoustic,
+        direction: Direction::Input,
+        user_name: "Mic".to_string(),
+        enabled: true,
+        protocol: None,
+        sample_rate: 48000,
+        channels: 1,
+        channel_mapping: ChannelMapping::default(),
+        calibration: Calibration::default(),
+        calibration_history: Vec::new(),
+        max_voltage: 0.0,
+        buffer_count: 4,
+        latency_mode: LatencyMode::LowLatency,
+        notes: String::new(),
+    };
+    let config = HardwareConfig {
+        version: "1.0".to_string(),
+        registered_devices: vec![mic.clone()],
+    };
+    let mut kernel = AudioKernelRuntime::with_shared_registry(registry.clone(), config);
+    kernel.start().await.unwrap();
+
+    let mut output = registry
+        .read()
+        .await
+        .create_device("loopback", "loopback-output", mic.device_config())
+        .unwrap();
+    output.start().await.unwrap();
+    (kernel, output)
+}
+
+async fn wait_for(mut ready: impl FnMut() -> bool) {
+    for _ in 0..200 {
+        if ready() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+}
+
+#[tokio::test]
+async fn test_kernel_dispatches_device_frames_to_bound_pipeline() {
+    let (mut kernel, mut output) = kernel_with_loopback().await;
+
+    let graph = serde_json::json!({
+        "nodes": [
+            {"id": "src", "type": "AudioSourceNode", "config": {"device_profile_id": "mic"}},
+            {"id": "sink", "type": "Print", "config": {}}
+        ],
+        "connections": [{"from": "src", "to": "sink"}]
+    });
+    let frames = Arc::new(Mutex::new(Vec::new()));
+    let mut pipeline = AsyncPipeline::from_json(graph).await.unwrap();
+    pipeline.nodes_mut().insert("sink".to_string(), Box::new(CollectSink(frames.clone())));
+    assert_eq!(pipeline.device_bindings(), &[("src".to_string(), "mic".to_string())]);
+
+    pipeline.start().await.unwrap();
+    assert_eq!(kernel.bind_pipeline(&pipeline).unwrap(), 1);
+
+    let mut frame = DataFrame::new(0, 0);
+    frame.insert_channel("ch0", vec![0.25, -0.5, 0.75]);
+    let packet = frame_to_packet(&frame, SampleFormat::F32, 48000).unwrap();
+    output.get_channels().empty_tx.send(packet).unwrap();
+
+    wait_for(|| !frames.lock().unwrap().is_empty()).await;
+    {
+        let frames = frames.lock().unwrap();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].metadata.get_str("device"), Some("mic"));
+        assert_eq!(frames[0].payload["ch0"].samples(), &[0.25, -0.5, 0.75]);
+    }
+
+    pipeline.stop().await.unwrap();
+    output.stop().await.unwrap();
+    kernel.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_bind_rejects_devices_the_kernel_does_not_run() {
+    let (mut kernel, mut output) = kernel_with_loopback().await;
+
+    let graph = serde_json::json!({
+        "nodes": [{"id": "src", "type": "AudioSourceNode", "config": {"device_profile_id": "missing"}}],
+        "connections": []
+    });
+    let mut pipeline = AsyncPipeline::from_json(graph).await.unwrap();
+    pipeline.start().await.unwrap();
+
+    let err = kernel.bind_pipeline(&pipeline).unwrap_err();
+    assert!(err.to_string().contains("'missing'"));
+    assert!(kernel.tap_packets("missing").is_err());
+
+    pipeline.stop().await.unwrap();
+    output.stop().await.unwrap();
+    kernel.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_packet_tap_receives_raw_bytes() {
+    let (mut kernel, mut output) = kernel_with_loopback().await;
+    let tap = kernel.tap_packets("mic").unwrap();
+
+    let packet = PacketBuffer {
+        data: SampleData::Bytes(vec![0x90, 60, 100]),
+        sample_rate: 48000,
+        num_channels: 1,
+        timestamp: None,
+        codec: None,
+    };
+    output.get_channels().empty_tx.send(packet).unwrap();
+
+    wait_for(|| !tap.filled_rx.is_empty()).await;
+    let received = tap.filled_rx.try_recv().unwrap();
+    assert!(matches!(received.data, SampleData::Bytes(ref bytes) if bytes == &[0x90, 60, 100]));
+    // Buffers handed back to a tap are discarded
+    let _ = tap.empty_tx.try_send(received);
+
+    output.stop().await.unwrap();
+    kernel.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_unbind_stopped_releases_stopped_pipelines() {
+    let (mut kernel, mut output) = kernel_with_loopback().await;
+
+    let graph = serde_json::json!({
+        "nodes": [{"id": "src", "type": "AudioSourceNode", "config": {"device_profile_id": "mic"}}],
+        "connections": []
+    });
+    let mut stopped = AsyncPipeline::from_json(graph.clone()).await.unwrap();
+    let mut running = AsyncPipeline::from_json(graph).await.unwrap();
+    stopped.start().await.unwrap();
+    running.start().await.unwrap();
+    kernel.bind_pipeline(&stopped).unwrap();
+    kernel.bind_pipeline(&running).unwrap();
+
+    assert_eq!(kernel.unbind_stopped(), 0);
+    stopped.stop().await.unwrap();
+    assert_eq!(kernel.unbind_stopped(), 1);
+    assert_eq!(kernel.unbind_stopped(), 0);
+
+    running.stop().await.unwrap();
+    output.stop().await.unwrap();
+    kernel.stop().await.unwrap();
+}
@@ -0,0 +1,150 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::engine::{AsyncPipeline, AudioKernelRuntime, DeviceBuffer};
+use audiotab::hal::*;
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use tokio::sync::RwLock;
+
+/// Sink that records the length of every frame it receives
+struct LengthSink(Arc<Mutex<Vec<usize>>>);
+
+#[async_trait]
+impl ProcessingNode for LengthSink {
+    async fn process(&mut self, input: DataFrame) -> anyhow::Result<DataFrame> {
+        self.0.lock().unwrap().push(input.payload["ch0"].len());
+        Ok(input)
+    }
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+#[tokio::test]
+async fn test_frame_size_sets_source_buffer_size() {
+    // A configured buffer size is kept
+    for (config, expected) in [(json!({}), 256), (json!({"buffer_size": 100}), 100)] {
+        let graph = json!({
+            "pipeline_config": {"frame_size": 256},
+            "nodes": [
+                {"id": "gen", "type": "SignalGenerator", "config": config},
+                {"id": "sink", "type": "Print", "config": {}}
+            ],
+            "connections": [{"from": "gen", "to": "sink"}]
+        });
+        let lengths = Arc::new(Mutex::new(Vec::new()));
+        let mut pipeline = AsyncPipeline::from_json(graph).await.unwrap();
+        assert_eq!(pipeline.frame_size(), Some(256));
+        pipeline.nodes_mut().insert("sink".to_string(), Box::new(LengthSink(lengths.clone())));
+
+        pipeline.start().await.unwrap();
+        pipeline.trigger(DataFrame::new(0, 0)).await.unwrap();
+        pipeline.stop().await.unwrap();
+        assert_eq!(*lengths.lock().unwrap(), vec![expected]);
+    }
+}
+
+#[tokio::test]
+async fn test_invalid_frame_size() {
+    for frame_size in [json!(0), json!("large"), json!(-64)] {
+        let graph = json!({"pipeline_config": {"frame_size": frame_size}, "nodes": []});
+        assert!(AsyncPipeline::from_json(graph).await.is_err(), "{}", frame_size);
+    }
+}
+
+#[tokio::test]
+async fn test_budget_follows_slowest_path() {
+    let graph = json!({
+        "pipeline_config": {"frame_size": 256, "channel_capacity": 4},
+        "nodes": [
+            {"id": "gen", "type": "SignalGenerator", "config": {"sample_rate": 48000}},
+            {"id": "gain", "type": "Gain", "config": {"gain": 1.0}},
+            {"id": "sink", "type": "Print", "config": {}},
+            {"id": "meter", "type": "Print", "config": {}}
+        ],
+        "connections": [
+            {"from": "gen", "to": "gain", "block_size": 1024},
+            {"from": "gain", "to": "sink"},
+            {"from": "gen", "to": "meter"}
+        ]
+    });
+    let pipeline = AsyncPipeline::from_json(graph).await.unwrap();
+    let budget = pipeline.latency_budget();
+
+    assert_eq!(budget.path, vec!["gen", "gain", "sink"]);
+    assert_eq!(budget.frame_size, 256);
+    // One generated frame of 256 samples at 48 kHz
+    assert!(close(budget.device_ms, 256.0 / 48.0));
+    // The re-blocking edge waits for 768 more samples
+    assert!(close(budget.block_ms, 16.0));
+    // Four frames per input channel: 256 samples at the source, 1024 after re-blocking
+    assert!(close(budget.channel_ms, 4.0 * (256.0 + 1024.0 + 1024.0) / 48.0));
+    assert!(close(budget.total_ms, budget.device_ms + budget.block_ms + budget.channel_ms));
+    assert_eq!(budget.queued_ms, 0.0);
+}
+
+#[tokio::test]
+async fn test_budget_uses_device_buffering() {
+    let graph = json!({
+        "pipeline_config": {"channel_capacity": 1},
+        "nodes": [{"id": "src", "type": "AudioSourceNode", "config": {"device_profile_id": "mic"}}],
+        "connections": []
+    });
+    let mut pipeline = AsyncPipeline::from_json(graph).await.unwrap();
+    // Before the device is known it counts as double-buffered at the node's frame size
+    assert!(close(pipeline.latency_budget().device_ms, 2.0 * 1024.0 / 48.0));
+
+    pipeline.set_device_buffer("mic", DeviceBuffer { frame_size: 480, buffer_count: 4, sample_rate: 96000.0 });
+    let budget = pipeline.latency_budget();
+    assert_eq!(budget.frame_size, 480);
+    assert!(close(budget.device_ms, 20.0));
+    assert!(close(budget.channel_ms, 5.0));
+}
+
+fn loopback_registration() -> RegisteredHardware {
+    RegisteredHardware {
+        registration_id: "mic".to_string(),
+        device_id: "loopback-input".to_string(),
+        hardware_name: "Loopback".to_string(),
+        driver_id: "loopback".to_string(),
+        hardware_type: HardwareType::Acoustic,
+        direction: Direction::Input,
+        user_name: "Mic".to_string(),
+        enabled: true,
+        protocol: None,
+        sample_rate: 48000,
+        channels: 1,
+        channel_mapping: ChannelMapping::default(),
+        calibration: Calibration::default(),
+        calibration_history: Vec::new(),
+        max_voltage: 0.0,
+        buffer_count: 4,
+        latency_mode: LatencyMode::LowLatency,
+        notes: String::new(),
+    }
+}
+
+#[tokio::test]
+async fn test_kernel_opens_devices_at_pipeline_frame_size() {
+    let mut registry = HardwareRegistry::new();
+    registry.register(LoopbackDriver::new());
+    let config = HardwareConfig {
+        version: "1.0".to_string(),
+        registered_devices: vec![loopback_registration()],
+    };
+    let mut kernel = AudioKernelRuntime::with_shared_registry(Arc::new(RwLock::new(registry)), config);
+    let graph = json!({
+        "pipeline_config": {"frame_size": 512},
+        "nodes": [{"id": "src", "type": "AudioSourceNode", "config": {"device_profile_id": "mic"}}],
+        "connections": []
+    });
+    kernel.set_pipeline(AsyncPipeline::from_json(graph).await.unwrap());
+    kernel.start().await.unwrap();
+
+    let buffer = kernel.device_buffer("mic").unwrap();
+    assert_eq!(buffer, DeviceBuffer { frame_size: 512, buffer_count: 4, sample_rate: 48000.0 });
+    assert!(close(buffer.latency_ms(), 2048.0 / 48.0));
+    kernel.stop().await.unwrap();
+    assert!(kernel.device_buffer("mic").is_none());
+}
@@ -34,4 +34,10 @@ async fn test_pipeline_with_metrics() {
     assert!(report.contains("print"));
 
     pipeline.stop().await.unwrap();
+
+    // Throughput is averaged over the completed run
+    let nodes = pipeline.get_monitor().unwrap().node_metrics();
+    let ids: Vec<&str> = nodes.iter().map(|n| n.node_id.as_str()).collect();
+    assert_eq!(ids, vec!["gain", "gen", "print"]);
+    assert!(nodes.iter().all(|n| n.frames_processed == 5 && n.frames_per_sec > 0.0));
 }
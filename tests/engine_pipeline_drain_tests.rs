@@ -0,0 +1,198 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::engine::AsyncPipeline;
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Slow sink that records frames and when it was flushed
+struct RecordingSink {
+    log: Arc<Mutex<Vec<String>>>,
+    delay: Duration,
+    fail_on_destroy: bool,
+}
+
+#[async_trait]
+impl ProcessingNode for RecordingSink {
+    async fn process(&mut self, input: DataFrame) -> anyhow::Result<DataFrame> {
+        tokio::time::sleep(self.delay).await;
+        self.log.lock().unwrap().push(format!("frame {}", input.sequence_id));
+        Ok(input)
+    }
+
+    async fn on_destroy(&mut self) -> anyhow::Result<()> {
+        if self.fail_on_destroy {
+            anyhow::bail!("disk full");
+        }
+        self.log.lock().unwrap().push("flushed".to_string());
+        Ok(())
+    }
+}
+
+fn sink(log: &Arc<Mutex<Vec<String>>>, delay_ms: u64, fail_on_destroy: bool) -> Box<RecordingSink> {
+    Box::new(RecordingSink {
+        log: log.clone(),
+        delay: Duration::from_millis(delay_ms),
+        fail_on_destroy,
+    })
+}
+
+async fn pipeline_with(graph: serde_json::Value, nodes: Vec<(&str, Box<RecordingSink>)>) -> AsyncPipeline {
+    let mut pipeline = AsyncPipeline::from_json(graph).await.unwrap();
+    for (id, node) in nodes {
+        pipeline.nodes_mut().insert(id.to_string(), node);
+    }
+    pipeline
+}
+
+#[tokio::test]
+async fn test_stop_drains_frames_in_flight() {
+    let graph = serde_json::json!({
+        "nodes": [
+            {"id": "gain", "type": "Gain", "config": {"gain": 1.0}},
+            {"id": "sink", "type": "Print", "config": {}}
+        ],
+        "connections": [{"from": "gain", "to": "sink"}]
+    });
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut pipeline = pipeline_with(graph, vec![("sink", sink(&log, 2, false))]).await;
+
+    pipeline.start().await.unwrap();
+    for i in 0..20 {
+        pipeline.trigger(DataFrame::new(0, i)).await.unwrap();
+    }
+    pipeline.stop().await.unwrap();
+
+    // Every queued frame reaches the sink before it flushes
+    let log = log.lock().unwrap();
+    assert_eq!(log.len(), 21);
+    assert_eq!(log[19], "frame 19");
+    assert_eq!(log[20], "flushed");
+}
+
+#[tokio::test]
+async fn test_drain_joins_all_nodes_after_a_failure() {
+    let graph = serde_json::json!({
+        "nodes": [
+            {"id": "source", "type": "Gain", "config": {}},
+            {"id": "bad", "type": "Print", "config": {}},
+            {"id": "good", "type": "Print", "config": {}}
+        ],
+        "connections": [{"from": "source", "to": "bad"}, {"from": "source", "to": "good"}]
+    });
+    let bad_log = Arc::new(Mutex::new(Vec::new()));
+    let good_log = Arc::new(Mutex::new(Vec::new()));
+    let mut pipeline = pipeline_with(
+        graph,
+        vec![("bad", sink(&bad_log, 0, true)), ("good", sink(&good_log, 5, false))],
+    )
+    .await;
+
+    pipeline.start().await.unwrap();
+    for i in 0..5 {
+        pipeline.trigger(DataFrame::new(0, i)).await.unwrap();
+    }
+    let err = pipeline.stop().await.unwrap_err();
+    assert!(err.to_string().contains("Node 'bad'"));
+    assert!(err.to_string().contains("disk full"));
+    assert_eq!(good_log.lock().unwrap().last().unwrap(), "flushed");
+}
+
+#[tokio::test]
+async fn test_drain_aborts_nodes_after_timeout() {
+    let graph = serde_json::json!({
+        "nodes": [{"id": "sink", "type": "Print", "config": {}}],
+        "connections": []
+    });
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut pipeline = pipeline_with(graph, vec![("sink", sink(&log, 10_000, false))]).await;
+
+    pipeline.start().await.unwrap();
+    pipeline.trigger(DataFrame::new(0, 0)).await.unwrap();
+    let err = pipeline.drain(Duration::from_millis(50)).await.unwrap_err();
+    assert!(err.to_string().contains("did not drain"));
+    assert!(log.lock().unwrap().is_empty());
+}
+
+/// Sums incoming samples and only emits the running total on flush
+struct Accumulator {
+    total: f64,
+}
+
+#[async_trait]
+impl ProcessingNode for Accumulator {
+    async fn process(&mut self, input: DataFrame) -> anyhow::Result<DataFrame> {
+        self.total += input.payload.values().flat_map(|c| c.iter()).sum::<f64>();
+        Ok(DataFrame::new(input.timestamp, input.sequence_id))
+    }
+
+    async fn on_flush(&mut self) -> anyhow::Result<Option<DataFrame>> {
+        let mut held = DataFrame::new(0, 1000 + self.total as u64);
+        held.insert_channel("total", vec![self.total]);
+        Ok(Some(held))
+    }
+}
+
+#[tokio::test]
+async fn test_flush_emits_held_data_through_the_graph() {
+    let graph = serde_json::json!({
+        "nodes": [
+            {"id": "sum", "type": "Gain", "config": {}},
+            {"id": "sink", "type": "Print", "config": {}}
+        ],
+        "connections": [{"from": "sum", "to": "sink"}]
+    });
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut pipeline = AsyncPipeline::from_json(graph).await.unwrap();
+    pipeline.nodes_mut().insert("sum".to_string(), Box::new(Accumulator { total: 0.0 }));
+    pipeline.nodes_mut().insert("sink".to_string(), sink(&log, 0, false));
+
+    pipeline.start().await.unwrap();
+    for i in 0..3 {
+        let mut frame = DataFrame::new(0, i);
+        frame.insert_channel("ch0", vec![1.0, 2.0]);
+        pipeline.trigger(frame).await.unwrap();
+    }
+    pipeline.flush().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(log.lock().unwrap().last().unwrap(), "frame 1009");
+
+    // End of stream flushes again before the sink closes
+    pipeline.stop().await.unwrap();
+    let log = log.lock().unwrap();
+    assert_eq!(log[log.len() - 2], "frame 1009");
+    assert_eq!(log[log.len() - 1], "flushed");
+}
+
+#[tokio::test]
+async fn test_end_of_stream_waits_for_every_input() {
+    let graph = serde_json::json!({
+        "nodes": [
+            {"id": "source", "type": "Gain", "config": {}},
+            {"id": "slow", "type": "Print", "config": {}},
+            {"id": "fast", "type": "Gain", "config": {}},
+            {"id": "merge", "type": "Print", "config": {}}
+        ],
+        "connections": [
+            {"from": "source", "to": "slow"},
+            {"from": "source", "to": "fast"},
+            {"from": "slow", "to": "merge"},
+            {"from": "fast", "to": "merge"}
+        ]
+    });
+    let slow_log = Arc::new(Mutex::new(Vec::new()));
+    let merge_log = Arc::new(Mutex::new(Vec::new()));
+    let mut pipeline = AsyncPipeline::from_json(graph).await.unwrap();
+    pipeline.nodes_mut().insert("slow".to_string(), sink(&slow_log, 10, false));
+    pipeline.nodes_mut().insert("merge".to_string(), sink(&merge_log, 0, false));
+
+    pipeline.start().await.unwrap();
+    for i in 0..3 {
+        pipeline.trigger(DataFrame::new(0, i)).await.unwrap();
+    }
+    pipeline.stop().await.unwrap();
+
+    // Frames from the slow branch still arrive before the merge node ends
+    let merge_log = merge_log.lock().unwrap();
+    assert_eq!(merge_log.len(), 7);
+    assert_eq!(merge_log.last().unwrap(), "flushed");
+}
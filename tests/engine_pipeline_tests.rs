@@ -36,3 +36,56 @@ async fn test_pipeline_execute() {
     let result = pipeline.execute_once().await;
     assert!(result.is_ok());
 }
+
+#[tokio::test]
+async fn test_orders_branches_and_merges() {
+    let config = serde_json::json!({
+        "nodes": [
+            {"id": "sink", "type": "Print", "config": {}},
+            {"id": "right", "type": "Gain", "config": {}},
+            {"id": "left", "type": "Gain", "config": {}},
+            {"id": "src", "type": "Gain", "config": {}}
+        ],
+        "connections": [
+            {"from": "src", "to": "right"},
+            {"from": "src", "to": "left"},
+            {"from": "left", "to": "sink"},
+            {"from": "right", "to": "sink"}
+        ]
+    });
+
+    let mut pipeline = Pipeline::from_json(config).await.unwrap();
+    assert_eq!(pipeline.execution_order(), ["src", "right", "left", "sink"]);
+    pipeline.execute_once().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_rejects_cycle_naming_its_nodes() {
+    let config = serde_json::json!({
+        "nodes": [
+            {"id": "src", "type": "Gain", "config": {}},
+            {"id": "a", "type": "Gain", "config": {}},
+            {"id": "b", "type": "Gain", "config": {}},
+            {"id": "after", "type": "Print", "config": {}}
+        ],
+        "connections": [
+            {"from": "src", "to": "a"},
+            {"from": "a", "to": "b"},
+            {"from": "b", "to": "a"},
+            {"from": "b", "to": "after"}
+        ]
+    });
+
+    let err = Pipeline::from_json(config).await.err().unwrap();
+    assert!(err.to_string().ends_with("cycle through nodes: a, b"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_rejects_connection_to_unknown_node() {
+    let config = serde_json::json!({
+        "nodes": [{"id": "src", "type": "Gain", "config": {}}],
+        "connections": [{"from": "src", "to": "missing"}]
+    });
+
+    assert!(Pipeline::from_json(config).await.is_err());
+}
@@ -0,0 +1,60 @@
+use audiotab::core::DataFrame;
+use audiotab::engine::{spawn_realtime, AsyncPipeline, Priority, RealtimeConfig};
+use serde_json::json;
+use std::time::Duration;
+
+#[test]
+fn test_parses_realtime_config() {
+    assert_eq!(RealtimeConfig::from_json(&serde_json::Value::Null).unwrap(), None);
+    assert_eq!(RealtimeConfig::from_json(&json!(false)).unwrap(), None);
+    assert_eq!(RealtimeConfig::from_json(&json!(true)).unwrap(), Some(RealtimeConfig::default()));
+
+    let config = RealtimeConfig::from_json(&json!({"cores": [2, 3]})).unwrap().unwrap();
+    assert_eq!(config.cores, vec![2, 3]);
+    assert_eq!(config.core(0), Some(2));
+    assert_eq!(config.core(3), Some(3));
+    assert_eq!(RealtimeConfig::default().core(0), None);
+
+    assert!(RealtimeConfig::from_json(&json!({"cores": ["a"]})).is_err());
+    assert!(RealtimeConfig::from_json(&json!(1)).is_err());
+}
+
+#[tokio::test]
+async fn test_runs_task_on_named_thread() {
+    let handle = spawn_realtime("audiotab-test", Priority::Critical, None, async {
+        assert_eq!(std::thread::current().name(), Some("audiotab-test"));
+        // Tasks spawned from the thread run on its runtime
+        tokio::spawn(async { 1 }).await?;
+        Ok::<(), anyhow::Error>(())
+    });
+    handle.await.unwrap().unwrap();
+
+    let failing = spawn_realtime("audiotab-failing", Priority::Low, None, async { Err::<(), _>(anyhow::anyhow!("boom")) });
+    assert_eq!(failing.await.unwrap().unwrap_err().to_string(), "boom");
+}
+
+#[tokio::test]
+async fn test_pipeline_runs_realtime_node() {
+    let config = json!({
+        "nodes": [
+            {"id": "src", "type": "Gain", "config": {}, "realtime": true},
+            {"id": "sink", "type": "Print", "config": {}}
+        ],
+        "connections": [{"from": "src", "to": "sink"}],
+        "pipeline_config": {"priority": "Critical", "realtime": {"cores": [0]}}
+    });
+
+    let mut pipeline = AsyncPipeline::from_json(config).await.unwrap();
+    assert_eq!(pipeline.realtime().unwrap().cores, vec![0]);
+    pipeline.start().await.unwrap();
+
+    let mut frame = DataFrame::new(0, 0);
+    frame.insert_channel("main_channel", vec![1.0]);
+    pipeline.trigger(frame).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    pipeline.stop().await.unwrap();
+
+    let monitor = pipeline.get_monitor().unwrap();
+    let src = monitor.node_metrics().into_iter().find(|m| m.node_id == "src").unwrap();
+    assert_eq!(src.frames_processed, 1);
+}
@@ -0,0 +1,95 @@
+#![cfg(feature = "remote")]
+
+use audiotab::engine::{RemoteControl, RemoteServer};
+use audiotab::hal::{DeviceManager, LoopbackDriver};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// Send one HTTP/1.1 request and return the status and JSON body (null if empty)
+async fn request(addr: SocketAddr, method: &str, path: &str, body: Option<Value>) -> (u16, Value) {
+    let body = body.map(|b| b.to_string()).unwrap_or_default();
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let head = format!(
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n",
+        method,
+        path,
+        body.len()
+    );
+    stream.write_all(head.as_bytes()).await.unwrap();
+    stream.write_all(body.as_bytes()).await.unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    let status = response[9..12].parse().unwrap();
+    let (_, body) = response.split_once("\r\n\r\n").unwrap();
+    let body = if body.is_empty() { Value::Null } else { serde_json::from_str(body).unwrap() };
+    (status, body)
+}
+
+fn gain_pipeline() -> Value {
+    json!({
+        "nodes": [
+            {"id": "src", "type": "Gain", "config": {}},
+            {"id": "sink", "type": "Print", "config": {}}
+        ],
+        "connections": [{"from": "src", "to": "sink"}]
+    })
+}
+
+#[tokio::test]
+async fn test_drives_pipeline_over_http() {
+    let server = RemoteServer::bind("127.0.0.1:0", Arc::new(RemoteControl::new())).await.unwrap();
+    let addr = server.local_addr();
+
+    assert_eq!(request(addr, "PUT", "/pipelines/bench", Some(gain_pipeline())).await.0, 204);
+    let (status, pipelines) = request(addr, "GET", "/pipelines", None).await;
+    assert_eq!(status, 200);
+    assert_eq!(pipelines, json!([{"id": "bench", "state": "Idle"}]));
+
+    assert_eq!(request(addr, "POST", "/pipelines/bench/start", None).await.0, 204);
+    assert_eq!(request(addr, "PATCH", "/pipelines/bench/nodes/src", Some(json!({"gain_db": 6.0}))).await.0, 204);
+    let (status, error) = request(addr, "PATCH", "/pipelines/bench/nodes/src", Some(json!({"gain_db": "loud"}))).await;
+    assert_eq!(status, 400);
+    assert!(error["error"].is_string(), "{}", error);
+    assert_eq!(request(addr, "POST", "/pipelines/bench/trigger", None).await.0, 204);
+
+    let (status, metrics) = request(addr, "GET", "/pipelines/bench/metrics", None).await;
+    assert_eq!(status, 200);
+    assert_eq!(metrics.as_array().unwrap().len(), 2);
+
+    assert_eq!(request(addr, "POST", "/pipelines/bench/stop", None).await.0, 204);
+    let (_, pipelines) = request(addr, "GET", "/pipelines", None).await;
+    assert_eq!(pipelines[0]["state"], "Completed");
+
+    assert_eq!(request(addr, "DELETE", "/pipelines/bench", None).await.0, 204);
+    let (status, error) = request(addr, "POST", "/pipelines/bench/start", None).await;
+    assert_eq!(status, 404);
+    assert_eq!(error["error"], "Pipeline bench not found");
+}
+
+#[tokio::test]
+async fn test_rejects_invalid_pipeline_and_lists_devices() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut manager = DeviceManager::new(dir.path().to_path_buf()).unwrap();
+    manager.register_driver(LoopbackDriver::new());
+    let control = Arc::new(RemoteControl::new().with_devices(Arc::new(manager)));
+    let server = RemoteServer::bind("127.0.0.1:0", control.clone()).await.unwrap();
+    let addr = server.local_addr();
+
+    let pipeline = json!({"nodes": [{"id": "x", "type": "NoSuchNode", "config": {}}], "connections": []});
+    let (status, error) = request(addr, "PUT", "/pipelines/bad", Some(pipeline)).await;
+    assert_eq!(status, 400);
+    assert!(error["error"].as_str().unwrap().starts_with("Pipeline creation failed"), "{}", error);
+    assert!(control.statuses().await.is_empty());
+
+    let (status, devices) = request(addr, "GET", "/devices", None).await;
+    assert_eq!(status, 200);
+    assert!(!devices.as_array().unwrap().is_empty());
+
+    // Without a device manager there is nothing to list
+    let bare = RemoteServer::bind("127.0.0.1:0", Arc::new(RemoteControl::new())).await.unwrap();
+    assert_eq!(request(bare.local_addr(), "GET", "/devices", None).await.0, 400);
+}
@@ -0,0 +1,65 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::engine::{AsyncPipeline, SequenceCheck, SequenceTracker};
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+
+/// Sink that keeps every frame it receives
+struct CollectSink(Arc<Mutex<Vec<DataFrame>>>);
+
+#[async_trait]
+impl ProcessingNode for CollectSink {
+    async fn process(&mut self, input: DataFrame) -> anyhow::Result<DataFrame> {
+        self.0.lock().unwrap().push(input.clone());
+        Ok(input)
+    }
+}
+
+#[test]
+fn test_tracks_each_edge_separately() {
+    let mut tracker = SequenceTracker::new();
+    assert_eq!(tracker.check("a", 7), SequenceCheck::InOrder);
+    assert_eq!(tracker.check("a", 8), SequenceCheck::InOrder);
+    assert_eq!(tracker.check("b", 0), SequenceCheck::InOrder);
+    assert_eq!(tracker.check("a", 11), SequenceCheck::Gap { missing: 2 });
+    assert_eq!(tracker.check("a", 11), SequenceCheck::InOrder);
+    assert_eq!(tracker.check("a", 4), SequenceCheck::OutOfOrder);
+    assert_eq!(tracker.check("a", 5), SequenceCheck::InOrder);
+    assert_eq!(tracker.check("b", 1), SequenceCheck::InOrder);
+}
+
+#[tokio::test]
+async fn test_annotates_and_counts_sequence_gaps() {
+    let config = json!({
+        "nodes": [
+            {"id": "src", "type": "Gain", "config": {}},
+            {"id": "sink", "type": "Print", "config": {}}
+        ],
+        "connections": [{"from": "src", "to": "sink"}]
+    });
+    let mut pipeline = AsyncPipeline::from_json(config).await.unwrap();
+    let frames = Arc::new(Mutex::new(Vec::new()));
+    pipeline.nodes_mut().insert("sink".to_string(), Box::new(CollectSink(frames.clone())));
+    pipeline.start().await.unwrap();
+
+    for sequence_id in [0, 1, 2, 5, 3] {
+        pipeline.trigger(DataFrame::new(0, sequence_id)).await.unwrap();
+    }
+    pipeline.stop().await.unwrap();
+
+    let frames = frames.lock().unwrap();
+    assert_eq!(frames.len(), 5);
+    assert_eq!(frames[3].metadata.get_i64("sequence_gap"), Some(2));
+    assert_eq!(frames[4].metadata.get_bool("out_of_order"), Some(true));
+    assert!(!frames[2].metadata.contains_key("sequence_gap"));
+
+    let sink = pipeline
+        .get_monitor()
+        .unwrap()
+        .node_metrics()
+        .into_iter()
+        .find(|m| m.node_id == "sink")
+        .unwrap();
+    assert_eq!(sink.frames_missing, 2);
+    assert_eq!(sink.frames_out_of_order, 1);
+}
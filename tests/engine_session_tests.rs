@@ -0,0 +1,137 @@
+use async_trait::async_trait;
+use audiotab::engine::{CaptureSession, SessionEvent, SessionPlan, SessionTarget};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Target counting its calls; each `triggers` poll fires one more trigger
+#[derive(Clone, Default)]
+struct FakeTarget {
+    started: Arc<AtomicU64>,
+    finished: Arc<AtomicU64>,
+    polls: Arc<AtomicU64>,
+}
+
+#[async_trait]
+impl SessionTarget for FakeTarget {
+    async fn start(&mut self) -> anyhow::Result<()> {
+        self.started.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn finish(&mut self) -> anyhow::Result<()> {
+        self.finished.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn triggers(&mut self) -> u64 {
+        self.polls.fetch_add(1, Ordering::SeqCst)
+    }
+}
+
+fn recorder() -> (Arc<Mutex<Vec<SessionEvent>>>, impl Fn(SessionEvent) + Send + Sync + 'static) {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = events.clone();
+    (events, move |event| sink.lock().unwrap().push(event))
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64
+}
+
+#[tokio::test]
+async fn test_session_stops_after_duration() {
+    let target = FakeTarget::default();
+    let (events, on_event) = recorder();
+    let plan = SessionPlan { duration_ms: Some(50), ..Default::default() };
+
+    let session = CaptureSession::spawn(plan, target.clone(), Duration::from_millis(10), on_event).unwrap();
+    let outcome = session.wait().await.unwrap();
+
+    assert!(matches!(outcome, SessionEvent::Completed { elapsed_ms, .. } if elapsed_ms >= 50));
+    assert_eq!(target.started.load(Ordering::SeqCst), 1);
+    assert_eq!(target.finished.load(Ordering::SeqCst), 1);
+
+    let events = events.lock().unwrap();
+    assert_eq!(events[0], SessionEvent::Started);
+    assert!(events.iter().any(|e| matches!(e, SessionEvent::Progress { .. })));
+    assert_eq!(events.last(), Some(&outcome));
+}
+
+#[tokio::test]
+async fn test_session_stops_after_trigger_count() {
+    let target = FakeTarget::default();
+    let (_, on_event) = recorder();
+    let plan = SessionPlan { max_triggers: Some(3), ..Default::default() };
+
+    let session = CaptureSession::spawn(plan, target.clone(), Duration::from_millis(1), on_event).unwrap();
+    let outcome = session.wait().await.unwrap();
+
+    assert!(matches!(outcome, SessionEvent::Completed { triggers: 3, .. }));
+    assert_eq!(target.finished.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_countdown_before_scheduled_start() {
+    let target = FakeTarget::default();
+    let (events, on_event) = recorder();
+    let plan = SessionPlan {
+        start_at_ms: Some(now_ms() + 60),
+        duration_ms: Some(10),
+        ..Default::default()
+    };
+
+    let session = CaptureSession::spawn(plan, target.clone(), Duration::from_millis(20), on_event).unwrap();
+    session.wait().await.unwrap();
+
+    let events = events.lock().unwrap();
+    let countdowns = events.iter().filter(|e| matches!(e, SessionEvent::Countdown { .. })).count();
+    assert!(countdowns >= 2, "expected countdown ticks, got {:?}", events);
+    let started = events.iter().position(|e| *e == SessionEvent::Started).unwrap();
+    assert!(events[..started].iter().all(|e| matches!(e, SessionEvent::Countdown { .. })));
+}
+
+#[tokio::test]
+async fn test_cancel_during_countdown_never_starts() {
+    let target = FakeTarget::default();
+    let (_, on_event) = recorder();
+    let plan = SessionPlan {
+        start_at_ms: Some(now_ms() + 60_000),
+        duration_ms: Some(1000),
+        ..Default::default()
+    };
+
+    let session = CaptureSession::spawn(plan, target.clone(), Duration::from_millis(10), on_event).unwrap();
+    session.cancel();
+    let outcome = session.wait().await.unwrap();
+
+    assert_eq!(outcome, SessionEvent::Cancelled { elapsed_ms: 0, triggers: 0 });
+    assert_eq!(target.started.load(Ordering::SeqCst), 0);
+    assert_eq!(target.finished.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn test_cancel_running_session_finishes_target() {
+    let target = FakeTarget::default();
+    let (_, on_event) = recorder();
+    let plan = SessionPlan { duration_ms: Some(60_000), ..Default::default() };
+
+    let session = CaptureSession::spawn(plan, target.clone(), Duration::from_millis(10), on_event).unwrap();
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    session.cancel();
+    let outcome = session.wait().await.unwrap();
+
+    assert!(matches!(outcome, SessionEvent::Cancelled { .. }));
+    assert_eq!(target.finished.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_plan_needs_a_limit() {
+    let (_, on_event) = recorder();
+    let result = CaptureSession::spawn(SessionPlan::default(), FakeTarget::default(), Duration::from_millis(10), on_event);
+    assert!(result.is_err());
+
+    let plan: SessionPlan = serde_json::from_value(serde_json::json!({"max_triggers": 10})).unwrap();
+    assert_eq!(plan.max_triggers, Some(10));
+    assert!(plan.validate().is_ok());
+}
@@ -0,0 +1,160 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::engine::{expand_subgraphs, AsyncPipeline, GraphDocument};
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Sink that keeps every frame it receives
+struct CollectSink(Arc<Mutex<Vec<DataFrame>>>);
+
+#[async_trait]
+impl ProcessingNode for CollectSink {
+    async fn process(&mut self, input: DataFrame) -> anyhow::Result<DataFrame> {
+        self.0.lock().unwrap().push(input.clone());
+        Ok(input)
+    }
+}
+
+/// `boost` amplifies by 40 dB; `stage` follows it with a 20 dB cut
+fn subgraphs() -> serde_json::Value {
+    json!({
+        "boost": {
+            "nodes": [
+                {"id": "first", "type": "Gain", "config": {"gain_db": 20.0}},
+                {"id": "second", "type": "Gain", "config": {"gain_db": 20.0}}
+            ],
+            "connections": [{"from": "first", "to": "second"}],
+            "inputs": {"in": {"node": "first"}},
+            "outputs": {"out": {"node": "second"}}
+        },
+        "stage": {
+            "nodes": [
+                {"id": "pre", "type": "Subgraph", "subgraph": "boost"},
+                {"id": "trim", "type": "Gain", "config": {"gain_db": -20.0}}
+            ],
+            "connections": [{"from": "pre", "to": "trim"}],
+            "inputs": {"in": {"node": "pre", "port": "in"}},
+            "outputs": {"out": {"node": "trim"}}
+        }
+    })
+}
+
+#[test]
+fn test_expands_nested_instances() {
+    let config = json!({
+        "subgraphs": subgraphs(),
+        "nodes": [
+            {"id": "src", "type": "Gain", "config": {}},
+            {"id": "chain", "type": "Subgraph", "subgraph": "stage", "config": {"trim": {"gain_db": -6.0}}},
+            {"id": "sink", "type": "Print", "config": {}}
+        ],
+        "connections": [
+            {"from": "src", "to": "chain", "to_port": "in"},
+            {"from": "chain", "to": "sink"}
+        ]
+    });
+    let expanded = expand_subgraphs(config).unwrap();
+
+    let ids: Vec<&str> = expanded["nodes"].as_array().unwrap().iter().map(|n| n["id"].as_str().unwrap()).collect();
+    assert_eq!(ids, vec!["src", "chain/pre/first", "chain/pre/second", "chain/trim", "sink"]);
+    assert_eq!(expanded["nodes"][3]["config"]["gain_db"], -6.0);
+    assert!(expanded.get("subgraphs").is_none());
+
+    let connections: Vec<(&str, &str)> = expanded["connections"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| (c["from"].as_str().unwrap(), c["to"].as_str().unwrap()))
+        .collect();
+    assert_eq!(
+        connections,
+        vec![
+            ("chain/pre/first", "chain/pre/second"),
+            ("chain/pre/second", "chain/trim"),
+            ("src", "chain/pre/first"),
+            ("chain/trim", "sink"),
+        ]
+    );
+    assert!(expanded["connections"][2].get("to_port").is_none());
+}
+
+#[test]
+fn test_rejects_invalid_instances() {
+    let graph = |node: serde_json::Value, connections: serde_json::Value| {
+        json!({"subgraphs": subgraphs(), "nodes": [{"id": "src", "type": "Gain"}, node], "connections": connections})
+    };
+
+    let err = expand_subgraphs(graph(json!({"id": "x", "type": "Subgraph", "subgraph": "missing"}), json!([]))).unwrap_err();
+    assert!(err.to_string().contains("unknown subgraph 'missing'"), "{}", err);
+
+    let err = expand_subgraphs(graph(
+        json!({"id": "x", "type": "Subgraph", "subgraph": "boost"}),
+        json!([{"from": "src", "to": "x", "to_port": "side"}]),
+    ))
+    .unwrap_err();
+    assert!(err.to_string().contains("no input port 'side'"), "{}", err);
+
+    let looping = json!({
+        "subgraphs": {"loop": {"nodes": [{"id": "again", "type": "Subgraph", "subgraph": "loop"}]}},
+        "nodes": [{"id": "x", "type": "Subgraph", "subgraph": "loop"}],
+        "connections": []
+    });
+    assert!(expand_subgraphs(looping).is_err());
+}
+
+#[tokio::test]
+async fn test_pipeline_runs_subgraph_instance() {
+    let config = json!({
+        "subgraphs": subgraphs(),
+        "nodes": [
+            {"id": "src", "type": "Gain", "config": {}},
+            {"id": "chain", "type": "Subgraph", "subgraph": "stage"},
+            {"id": "sink", "type": "Print", "config": {}}
+        ],
+        "connections": [{"from": "src", "to": "chain"}, {"from": "chain", "to": "sink"}]
+    });
+    let frames = Arc::new(Mutex::new(Vec::new()));
+    let mut pipeline = AsyncPipeline::from_json(config).await.unwrap();
+    pipeline.nodes_mut().insert("sink".to_string(), Box::new(CollectSink(frames.clone())));
+
+    pipeline.start().await.unwrap();
+    let mut frame = DataFrame::new(0, 0);
+    frame.insert_channel("main_channel", vec![1.0, -0.5]);
+    pipeline.trigger(frame).await.unwrap();
+    for _ in 0..200 {
+        if !frames.lock().unwrap().is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    pipeline.stop().await.unwrap();
+
+    let frames = frames.lock().unwrap();
+    assert_eq!(frames.len(), 1);
+    let samples = frames[0].payload["main_channel"].samples();
+    assert!((samples[0] - 10.0).abs() < 1e-9 && (samples[1] + 5.0).abs() < 1e-9, "{:?}", samples);
+}
+
+#[test]
+fn test_project_document_keeps_subgraphs() {
+    let doc = GraphDocument::from_value(json!({
+        "schema_version": 1,
+        "nodes": [
+            {"id": "src", "type": "Gain", "parameters": {}},
+            {"id": "chain", "type": "Subgraph", "subgraph": "boost", "parameters": {}}
+        ],
+        "edges": [{"id": "e1", "source": "src", "target": "chain", "targetHandle": "in"}],
+        "subgraphs": subgraphs()
+    }))
+    .unwrap();
+    assert_eq!(doc.subgraphs["stage"].outputs["out"].node, "trim");
+
+    let pipeline = doc.to_pipeline_json();
+    assert_eq!(pipeline["nodes"][1]["subgraph"], "boost");
+    assert_eq!(pipeline["connections"][0]["to_port"], "in");
+    assert_eq!(pipeline["subgraphs"]["boost"]["inputs"]["in"]["node"], "first");
+
+    let expanded = expand_subgraphs(pipeline).unwrap();
+    assert_eq!(expanded["connections"][1]["to"], "chain/first");
+}
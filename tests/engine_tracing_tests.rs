@@ -0,0 +1,88 @@
+use audiotab::core::DataFrame;
+use audiotab::engine::AsyncPipeline;
+use audiotab::observability::NodeMetrics;
+use audiotab::resilience::{RestartStrategy, Supervisor};
+use std::sync::{Arc, Mutex};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Instrument, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// An event's level and the names of its spans, outermost first
+type Recorded = (tracing::Level, Vec<String>);
+
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<Recorded>>>);
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for Recorder {
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let spans = ctx
+            .event_scope(event)
+            .map(|scope| scope.from_root().map(|span| span.name().to_string()).collect())
+            .unwrap_or_default();
+        self.0.lock().unwrap().push((*event.metadata().level(), spans));
+    }
+
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let spans = ctx
+            .span_scope(id)
+            .map(|scope| scope.from_root().map(|span| span.name().to_string()).collect())
+            .unwrap_or_default();
+        self.0.lock().unwrap().push((*attrs.metadata().level(), spans));
+    }
+}
+
+#[tokio::test]
+async fn test_supervisor_logs_within_node_span() {
+    let recorder = Recorder::default();
+    let _guard = tracing::subscriber::set_default(tracing_subscriber::registry().with(recorder.clone()));
+
+    let supervisor = Supervisor::new(RestartStrategy::Immediate);
+    let metrics = Arc::new(NodeMetrics::new("gain"));
+    let mut runs = 0;
+    let task = async move {
+        supervisor
+            .supervise("gain", metrics, move || {
+                runs += 1;
+                let crash = runs == 1;
+                async move {
+                    if crash {
+                        anyhow::bail!("device lost");
+                    }
+                    Ok(())
+                }
+            })
+            .await
+    };
+    let pipeline = tracing::info_span!("pipeline", pipeline = "p1");
+    task.instrument(tracing::info_span!(parent: &pipeline, "node", node = "gain"))
+        .await
+        .unwrap()
+        .unwrap();
+
+    let events = recorder.0.lock().unwrap();
+    let (_, spans) = events.iter().find(|(level, _)| *level == tracing::Level::WARN).expect("restart warning");
+    assert_eq!(spans, &["pipeline", "node"]);
+}
+
+#[tokio::test]
+async fn test_frames_are_processed_in_spans_under_their_node() {
+    let recorder = Recorder::default();
+    let _guard = tracing::subscriber::set_default(tracing_subscriber::registry().with(recorder.clone()));
+
+    let mut pipeline = AsyncPipeline::from_json(serde_json::json!({
+        "nodes": [{"id": "gain", "type": "Gain", "config": {}}]
+    }))
+    .await
+    .unwrap();
+    pipeline.start().await.unwrap();
+    for i in 0..3 {
+        pipeline.trigger(DataFrame::new(i * 1000, i)).await.unwrap();
+    }
+    pipeline.stop().await.unwrap();
+
+    let recorded = recorder.0.lock().unwrap();
+    let frames = recorded.iter().filter(|(_, spans)| spans == &["node", "process"]).count();
+    assert_eq!(frames, 3);
+}
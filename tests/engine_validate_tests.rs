@@ -0,0 +1,81 @@
+use audiotab::engine::{validate_graph, FieldError};
+use audiotab::registry::{NodePreset, PresetStore};
+use serde_json::json;
+
+fn paths(errors: &[FieldError]) -> Vec<&str> {
+    errors.iter().map(|e| e.path.as_str()).collect()
+}
+
+#[test]
+fn test_valid_graph_has_no_errors() {
+    let config = json!({
+        "nodes": [
+            {"id": "src", "type": "SineGenerator", "config": {}},
+            {"id": "gain", "type": "Gain", "config": {"gain_db": -6.0}},
+            {"id": "sink", "type": "DebugSinkNode", "config": {}}
+        ],
+        "connections": [
+            {"from": "src", "to": "gain"},
+            {"from": "gain", "to": "sink", "backpressure": "drop_oldest"}
+        ],
+        "pipeline_config": {"channel_capacity": 10, "priority": "High"}
+    });
+    assert_eq!(validate_graph(&config, &PresetStore::new()), vec![]);
+}
+
+#[test]
+fn test_reports_every_field_error() {
+    let config = json!({
+        "nodes": [
+            {"id": "gain", "type": "GainNode", "config": {"gain_db": 100.0}},
+            {"id": "loud", "type": "Gain", "config": {"gain_db": "loud"}},
+            {"id": "x", "type": "NoSuchNode"},
+            {"type": "Gain"}
+        ],
+        "connections": [
+            {"from": "gain", "to": "missing"},
+            {"from": "gain", "to": "loud", "to_port": "sidechain", "block_size": 0}
+        ],
+        "pipeline_config": {"channel_capacity": 0, "priority": "Urgent", "backpressure": "sometimes"}
+    });
+    let errors = validate_graph(&config, &PresetStore::new());
+    assert_eq!(
+        paths(&errors),
+        vec![
+            "pipeline_config.channel_capacity",
+            "pipeline_config.priority",
+            "pipeline_config.backpressure",
+            "nodes.gain.config.gain_db",
+            "nodes.loud.config.gain_db",
+            "nodes.x.type",
+            "nodes.3.id",
+            "connections.0.to",
+            "connections.1.to_port",
+            "connections.1.block_size",
+        ]
+    );
+    assert_eq!(errors[3].node.as_deref(), Some("gain"));
+    assert!(errors[3].message.contains("between -80 and 80"), "{}", errors[3].message);
+    assert!(errors[5].message.contains("Unknown node type"), "{}", errors[5].message);
+}
+
+#[test]
+fn test_checks_resolved_presets() {
+    let mut store = PresetStore::new();
+    store.save_preset(NodePreset::new("GainNode", "Too loud", json!({"gain_db": 120.0}))).unwrap();
+
+    let config = json!({"nodes": [
+        {"id": "a", "type": "GainNode", "config": {"preset": "Too loud"}},
+        {"id": "b", "type": "GainNode", "config": {"preset": "Missing"}}
+    ]});
+    let errors = validate_graph(&config, &store);
+    assert_eq!(paths(&errors), vec!["nodes.a.config.gain_db", "nodes.b.config.preset"]);
+}
+
+#[test]
+fn test_field_error_serializes_for_frontend() {
+    let errors = validate_graph(&json!({"nodes": [{"id": "g", "type": "Gain", "config": {"gain_db": 90}}]}), &PresetStore::new());
+    let value = serde_json::to_value(&errors[0]).unwrap();
+    assert_eq!(value["path"], "nodes.g.config.gain_db");
+    assert_eq!(value["node"], "g");
+}
@@ -30,6 +30,8 @@ async fn test_audio_device_creation() {
         buffer_size: 1024,
         channel_mapping: ChannelMapping::default(),
         calibration: Calibration::default(),
+        buffer_count: 2,
+        latency_mode: LatencyMode::LowLatency,
     };
 
     let mut device = driver.create_device("test-id", config).unwrap();
@@ -41,3 +43,105 @@ async fn test_audio_device_creation() {
     let channels = device.get_channels();
     assert!(channels.filled_rx.is_empty());
 }
+
+#[tokio::test]
+async fn test_audio_device_buffer_count() {
+    let driver = AudioDriver::new();
+    let config = |buffer_count| DeviceConfig {
+        name: "Test Device".to_string(),
+        sample_rate: 48000,
+        format: SampleFormat::F32,
+        buffer_size: 256,
+        channel_mapping: ChannelMapping::default(),
+        calibration: Calibration::default(),
+        buffer_count,
+        latency_mode: LatencyMode::Safe,
+    };
+
+    let mut device = driver.create_device("test-id", config(4)).unwrap();
+    let channels = device.get_channels();
+    assert_eq!(channels.empty_tx.capacity(), Some(4));
+    assert!(channels.empty_tx.is_full());
+
+    assert!(driver.create_device("test-id", config(1)).is_err());
+}
+
+#[test]
+fn test_device_config_buffering_defaults() {
+    // Configs saved before buffering options existed keep two low-latency buffers
+    let config: DeviceConfig = serde_json::from_value(serde_json::json!({
+        "name": "Legacy",
+        "sample_rate": 48000,
+        "format": "F32",
+        "buffer_size": 1024,
+        "channel_mapping": {"physical_channels": 2, "virtual_channels": 2, "routing": []},
+        "calibration": {"gain": 1.0, "offset": 0.0}
+    }))
+    .unwrap();
+
+    assert_eq!(config.buffer_count, DEFAULT_BUFFER_COUNT);
+    assert_eq!(config.latency_mode, LatencyMode::LowLatency);
+}
+
+fn capabilities() -> DeviceCapabilities {
+    DeviceCapabilities {
+        can_input: true,
+        can_output: false,
+        supported_formats: vec![SampleFormat::I16, SampleFormat::I32],
+        supported_sample_rates: vec![44100, 48000, 96000],
+        max_channels: 2,
+    }
+}
+
+fn requested(sample_rate: u64, format: SampleFormat, channels: usize) -> DeviceConfig {
+    DeviceConfig {
+        name: "Interface".to_string(),
+        sample_rate,
+        format,
+        buffer_size: 256,
+        channel_mapping: ChannelMapping {
+            physical_channels: channels,
+            virtual_channels: channels,
+            routing: Vec::new(),
+        },
+        calibration: Calibration::default(),
+        buffer_count: 2,
+        latency_mode: LatencyMode::LowLatency,
+    }
+}
+
+#[test]
+fn test_capabilities_validate_reports_mismatch() {
+    let caps = capabilities();
+    assert!(caps.validate(&requested(48000, SampleFormat::I16, 2)).is_ok());
+    assert_eq!(
+        caps.validate(&requested(50000, SampleFormat::I16, 2)),
+        Err(CapabilityError::UnsupportedSampleRate { requested: 50000, supported: vec![44100, 48000, 96000] })
+    );
+    assert!(matches!(
+        caps.validate(&requested(48000, SampleFormat::F32, 2)),
+        Err(CapabilityError::UnsupportedFormat { requested: SampleFormat::F32, .. })
+    ));
+    assert_eq!(
+        caps.validate(&requested(48000, SampleFormat::I16, 8)),
+        Err(CapabilityError::TooManyChannels { requested: 8, max: 2 })
+    );
+
+    // Unknown capabilities accept anything
+    let open = DeviceCapabilities { supported_formats: vec![], supported_sample_rates: vec![], max_channels: 0, ..caps };
+    assert!(open.validate(&requested(12345, SampleFormat::F64, 64)).is_ok());
+}
+
+#[test]
+fn test_capabilities_negotiate_corrects_config() {
+    let caps = capabilities();
+    let corrected = caps.negotiate(&requested(88200, SampleFormat::F32, 2)).unwrap();
+    assert_eq!(corrected.sample_rate, 96000);
+    assert_eq!(corrected.format, SampleFormat::I16);
+    assert_eq!(corrected.buffer_size, 256);
+
+    // Channel counts are never reduced
+    let err = caps.negotiate(&requested(48000, SampleFormat::I16, 4)).unwrap_err();
+    assert_eq!(err, CapabilityError::TooManyChannels { requested: 4, max: 2 });
+    assert!(err.to_string().contains("at most 2"));
+}
@@ -22,6 +22,8 @@ async fn test_audio_streaming_basic() {
             routing: vec![],
         },
         calibration: Calibration::default(),
+        buffer_count: 2,
+        latency_mode: LatencyMode::LowLatency,
     };
 
     let mut device = driver.create_device(&input_device.id, config).unwrap();
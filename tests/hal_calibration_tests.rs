@@ -0,0 +1,127 @@
+use audiotab::hal::calibration::*;
+use audiotab::hal::device_check::TestTone;
+use audiotab::hal::*;
+use std::time::Duration;
+
+const DAY: u64 = 86400;
+
+fn sine(rms: f64, seconds: f64) -> Vec<f64> {
+    let amplitude = rms * 2f64.sqrt();
+    (0..(48000.0 * seconds) as usize)
+        .map(|n| amplitude * (2.0 * std::f64::consts::PI * 1000.0 * n as f64 / 48000.0).sin())
+        .collect()
+}
+
+fn registered() -> RegisteredHardware {
+    RegisteredHardware {
+        registration_id: "mic".to_string(),
+        device_id: "loopback-input".to_string(),
+        hardware_name: "Measurement Mic".to_string(),
+        driver_id: "loopback".to_string(),
+        hardware_type: HardwareType::Acoustic,
+        direction: Direction::Input,
+        user_name: "Mic".to_string(),
+        enabled: true,
+        protocol: None,
+        sample_rate: 48000,
+        channels: 1,
+        channel_mapping: ChannelMapping::default(),
+        calibration: Calibration::default(),
+        calibration_history: Vec::new(),
+        max_voltage: 0.0,
+        buffer_count: 4,
+        latency_mode: LatencyMode::LowLatency,
+        notes: String::new(),
+    }
+}
+
+#[test]
+fn test_compute_calibration_gain_and_offset() {
+    let tone = sine(0.1, 1.0);
+    let record = compute_calibration(&tone, 48000.0, 94.0, CalibrationTarget::Gain, &Calibration::default(), 100).unwrap();
+    // 94 dB SPL is 1.00237 Pa RMS
+    assert!((record.calibration.gain - 10.0237).abs() < 1e-3, "gain {}", record.calibration.gain);
+    assert_eq!(record.calibration.offset, 0.0);
+    assert_eq!(record.calibration.calibrated_at, Some(100));
+    assert!((record.measured_rms - 0.1).abs() < 1e-6);
+
+    // Offset mode keeps the gain and corrects the remaining error
+    let current = Calibration { gain: 10.0, ..Calibration::default() };
+    let record = compute_calibration(&tone, 48000.0, 94.0, CalibrationTarget::Offset, &current, 100).unwrap();
+    assert_eq!(record.calibration.gain, 10.0);
+    assert!((record.calibration.offset - 0.0206).abs() < 1e-3, "offset {}", record.calibration.offset);
+}
+
+#[test]
+fn test_compute_calibration_rejects_bad_captures() {
+    let defaults = Calibration::default();
+    let compute = |samples: &[f64]| compute_calibration(samples, 48000.0, 94.0, CalibrationTarget::Gain, &defaults, 0);
+
+    assert!(compute(&sine(0.1, 0.2)).is_err(), "too short");
+    assert!(compute(&sine(0.8, 1.0)).is_err(), "clipping");
+    assert!(compute(&vec![0.0; 48000]).is_err(), "silence");
+
+    // Level drops by 6 dB halfway through, as when the calibrator slips
+    let mut unstable = sine(0.1, 0.5);
+    unstable.extend(sine(0.05, 0.5));
+    let err = compute(&unstable).unwrap_err();
+    assert!(err.to_string().contains("seated"));
+}
+
+#[test]
+fn test_apply_calibration_and_staleness() {
+    let mut hw = registered();
+    let status = calibration_status(&hw.calibration, 1000 * DAY, DEFAULT_MAX_AGE);
+    assert!(status.stale);
+    assert_eq!(status.age_secs, None);
+
+    for day in 0..25u64 {
+        let record = CalibrationRecord {
+            calibration: Calibration { gain: 10.0 + day as f64, offset: 0.0, calibrated_at: Some(day * DAY) },
+            reference_db: 94.0,
+            measured_rms: 0.1,
+        };
+        apply_calibration(&mut hw, record);
+    }
+    assert_eq!(hw.calibration.gain, 34.0);
+    assert_eq!(hw.calibration_history.len(), 20);
+    assert_eq!(hw.calibration_history[0].calibration.gain, 15.0);
+
+    let fresh = calibration_status(&hw.calibration, 30 * DAY, DEFAULT_MAX_AGE);
+    assert!(!fresh.stale);
+    assert_eq!(fresh.age_secs, Some(6 * DAY));
+    assert!(calibration_status(&hw.calibration, 400 * DAY, DEFAULT_MAX_AGE).stale);
+}
+
+#[tokio::test]
+async fn test_capture_channel_from_device() {
+    let mut registry = HardwareRegistry::new();
+    registry.register(LoopbackDriver::new());
+    let config = registered().device_config();
+
+    let mut output = registry.create_device("loopback", "loopback-output", config.clone()).unwrap();
+    output.start().await.unwrap();
+    let writer = output.get_channels();
+    let tone = TestTone { frequency: 1000.0, level_db: -17.0, duration: Duration::from_secs(1) };
+    let packets: Vec<PacketBuffer> = (0..10).map(|i| tone.packet(&config, i * 4800, 4800).unwrap()).collect();
+    let feeder = tokio::spawn(async move {
+        for packet in packets {
+            writer.empty_tx.send(packet).unwrap();
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    });
+
+    let mut input = registry.create_device("loopback", "loopback-input", config).unwrap();
+    let (samples, sample_rate) = capture_channel(input.as_mut(), 0, Duration::from_millis(500)).await.unwrap();
+    feeder.await.unwrap();
+    output.stop().await.unwrap();
+
+    assert_eq!(sample_rate, 48000.0);
+    assert!(samples.len() >= 24000);
+    let record = compute_calibration(&samples, sample_rate, 94.0, CalibrationTarget::Gain, &Calibration::default(), 0).unwrap();
+    assert!(record.measured_rms > 0.09 && record.measured_rms < 0.1);
+
+    // Missing channels are reported instead of hanging
+    let mut input = registry.create_device("loopback", "loopback-input", registered().device_config()).unwrap();
+    assert!(capture_channel(input.as_mut(), 3, Duration::from_millis(10)).await.is_err());
+}
@@ -0,0 +1,74 @@
+use audiotab::hal::device_check::{measure_levels, play_test_tone};
+use audiotab::hal::*;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+fn config(channels: usize) -> DeviceConfig {
+    DeviceConfig {
+        name: "Check".to_string(),
+        sample_rate: 48000,
+        format: SampleFormat::F32,
+        buffer_size: 480,
+        channel_mapping: ChannelMapping {
+            physical_channels: channels,
+            virtual_channels: channels,
+            routing: Vec::new(),
+        },
+        calibration: Calibration::default(),
+        buffer_count: 4,
+        latency_mode: LatencyMode::LowLatency,
+    }
+}
+
+fn tone(duration_ms: u64) -> TestTone {
+    TestTone {
+        frequency: 1000.0,
+        level_db: -6.0,
+        duration: Duration::from_millis(duration_ms),
+    }
+}
+
+#[test]
+fn test_tone_levels() {
+    // 480 frames at 48 kHz hold exactly ten cycles of 1 kHz
+    let packet = tone(10).packet(&config(2), 0, 480).unwrap();
+    assert_eq!(packet.num_channels, 2);
+
+    let levels = measure_levels(&packet).unwrap();
+    assert_eq!(levels.channels.len(), 2);
+    for level in &levels.channels {
+        assert!((level.peak_db + 6.0).abs() < 0.05, "peak {}", level.peak_db);
+        assert!((level.rms_db + 9.01).abs() < 0.05, "rms {}", level.rms_db);
+    }
+}
+
+#[tokio::test]
+async fn test_tone_reaches_monitored_input() {
+    let mut registry = HardwareRegistry::new();
+    registry.register(LoopbackDriver::new());
+
+    let readings = Arc::new(Mutex::new(Vec::new()));
+    let sink = readings.clone();
+    let input = registry.create_device("loopback", "loopback-input", config(1)).unwrap();
+    let monitor = InputMonitor::start(input, move |levels| sink.lock().unwrap().push(levels))
+        .await
+        .unwrap();
+
+    let mut output = registry.create_device("loopback", "loopback-output", config(1)).unwrap();
+    let sent = play_test_tone(output.as_mut(), &config(1), &tone(50)).await.unwrap();
+    assert_eq!(sent, 5);
+    assert!(!output.is_streaming());
+
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    monitor.stop().await.unwrap();
+
+    {
+        let readings = readings.lock().unwrap();
+        assert!(!readings.is_empty());
+        assert!(readings.iter().all(|r| (r.channels[0].peak_db + 6.0).abs() < 0.05));
+    }
+
+    // Inputs cannot play tones
+    let mut input = registry.create_device("loopback", "loopback-input", config(1)).unwrap();
+    assert!(play_test_tone(input.as_mut(), &config(1), &tone(10)).await.is_err());
+}
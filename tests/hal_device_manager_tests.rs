@@ -0,0 +1,101 @@
+use audiotab::core::AudiotabError;
+use audiotab::hal::*;
+use std::time::Duration;
+use tempfile::tempdir;
+
+fn profile(id: &str, device_id: &str) -> DeviceProfile {
+    DeviceProfile {
+        id: id.to_string(),
+        alias: id.to_string(),
+        driver_id: "loopback".to_string(),
+        device_id: device_id.to_string(),
+        config: DeviceConfig {
+            name: "Loopback".to_string(),
+            sample_rate: 48000,
+            format: SampleFormat::F32,
+            buffer_size: 4,
+            channel_mapping: ChannelMapping::default(),
+            calibration: Calibration::default(),
+            buffer_count: 4,
+            latency_mode: LatencyMode::LowLatency,
+        },
+        metadata: DeviceMetadata::default(),
+        frequency_response: None,
+    }
+}
+
+fn manager(dir: &std::path::Path) -> DeviceManager {
+    let mut manager = DeviceManager::new(dir.to_path_buf()).unwrap();
+    manager.register_driver(LoopbackDriver::new());
+    manager.add_profile(profile("mic", "loopback-input")).unwrap();
+    manager.add_profile(profile("cable", "loopback-output")).unwrap();
+    manager
+}
+
+fn busy_error(result: Result<DeviceChannels, AudiotabError>) -> String {
+    match result {
+        Ok(_) => panic!("device should be busy"),
+        Err(e) => {
+            assert_eq!(e, AudiotabError::device("mic", e.message()));
+            e.to_string()
+        }
+    }
+}
+
+fn packet(value: f32) -> PacketBuffer {
+    PacketBuffer {
+        data: SampleData::F32(vec![value]),
+        sample_rate: 48000,
+        num_channels: 1,
+        timestamp: None,
+        codec: None,
+    }
+}
+
+#[tokio::test]
+async fn test_shared_consumers_each_receive_every_packet() {
+    let dir = tempdir().unwrap();
+    let manager = manager(dir.path());
+
+    let first = manager.acquire_device("mic", "pipeline-a", DeviceAccess::Shared).await.unwrap();
+    let second = manager.acquire_device("mic", "pipeline-b", DeviceAccess::Shared).await.unwrap();
+    assert_eq!(manager.device_owners("mic"), vec!["pipeline-a", "pipeline-b"]);
+
+    let output = manager.acquire_device("cable", "test", DeviceAccess::Exclusive).await.unwrap();
+    output.empty_tx.send(packet(0.5)).unwrap();
+
+    for consumer in [&first, &second] {
+        let received = consumer.filled_rx.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert!(matches!(received.data, SampleData::F32(ref s) if s == &[0.5]));
+    }
+
+    // The device keeps running until its last owner lets go
+    manager.release_device("mic", "pipeline-a").await.unwrap();
+    assert!(manager.is_device_active("mic"));
+    manager.release_device("mic", "pipeline-b").await.unwrap();
+    assert!(!manager.is_device_active("mic"));
+    assert!(manager.device_owners("mic").is_empty());
+
+    manager.release_device("cable", "test").await.unwrap();
+}
+
+#[tokio::test]
+async fn test_exclusive_device_reports_busy() {
+    let dir = tempdir().unwrap();
+    let manager = manager(dir.path());
+
+    manager.acquire_device("mic", "recorder", DeviceAccess::Exclusive).await.unwrap();
+    for access in [DeviceAccess::Exclusive, DeviceAccess::Shared] {
+        let err = busy_error(manager.acquire_device("mic", "analyzer", access).await);
+        assert!(err.contains("busy") && err.contains("recorder"), "{}", err);
+    }
+    assert!(manager.start_device("mic").await.is_err());
+
+    manager.release_device("mic", "recorder").await.unwrap();
+    manager.acquire_device("mic", "analyzer", DeviceAccess::Shared).await.unwrap();
+    let err = busy_error(manager.acquire_device("mic", "recorder", DeviceAccess::Exclusive).await);
+    assert!(err.contains("busy"), "{}", err);
+
+    manager.stop_device("mic").await.unwrap();
+    assert!(manager.device_owners("mic").is_empty());
+}
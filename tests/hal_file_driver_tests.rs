@@ -0,0 +1,169 @@
+use audiotab::hal::drivers::{Recording, RecordingFormat};
+use audiotab::hal::format_converter::packet_to_frame;
+use audiotab::hal::*;
+use audiotab::visualization::RingBufferWriter;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+fn write_wav_i16(path: &Path, sample_rate: u32, channels: u16, samples: &[i16]) {
+    let data_len = (samples.len() * 2) as u32;
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&channels.to_le_bytes());
+    bytes.extend_from_slice(&sample_rate.to_le_bytes());
+    bytes.extend_from_slice(&(sample_rate * channels as u32 * 2).to_le_bytes());
+    bytes.extend_from_slice(&(channels * 2).to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    for s in samples {
+        bytes.extend_from_slice(&s.to_le_bytes());
+    }
+    std::fs::write(path, bytes).unwrap();
+}
+
+fn config(buffer_size: usize) -> DeviceConfig {
+    DeviceConfig {
+        name: "Replay".to_string(),
+        sample_rate: 48000,
+        format: SampleFormat::F32,
+        buffer_size,
+        channel_mapping: ChannelMapping::default(),
+        calibration: Calibration::default(),
+        buffer_count: 2,
+        latency_mode: LatencyMode::LowLatency,
+    }
+}
+
+/// Drain a started device, returning per-channel samples and packet timestamps
+fn drain(device: &mut Box<dyn Device>) -> (Vec<Vec<f64>>, Vec<u64>) {
+    let channels = device.get_channels();
+    let mut samples: Vec<Vec<f64>> = Vec::new();
+    let mut timestamps = Vec::new();
+    loop {
+        let packet = match channels.filled_rx.recv_timeout(Duration::from_millis(20)) {
+            Ok(packet) => packet,
+            Err(_) if device.is_streaming() => continue,
+            Err(_) => break,
+        };
+        let frame = packet_to_frame(&packet, 0).unwrap();
+        samples.resize(frame.payload.len(), Vec::new());
+        for (ch, out) in samples.iter_mut().enumerate() {
+            out.extend_from_slice(&frame.payload[&format!("ch{}", ch)]);
+        }
+        timestamps.push(packet.timestamp.unwrap());
+        channels.empty_tx.send(packet).unwrap();
+    }
+    (samples, timestamps)
+}
+
+#[tokio::test]
+async fn test_file_driver_replays_wav_at_max_speed() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("capture.wav");
+    // Interleaved stereo: left ramps up, right is its negation
+    let interleaved: Vec<i16> = (0..1000).flat_map(|n| [n * 16, -n * 16]).collect();
+    write_wav_i16(&path, 8000, 2, &interleaved);
+
+    let driver = FileDriver::new(ReplaySpeed::MaxSpeed);
+    driver.add_file("field-recording", &path, ReplaySpeed::MaxSpeed);
+    let devices = driver.discover_devices().await.unwrap();
+    assert_eq!(devices[0].id, "field-recording");
+
+    let mut device = driver.create_device("field-recording", config(256)).unwrap();
+    assert_eq!(device.capabilities().supported_sample_rates, vec![8000]);
+    device.start().await.unwrap();
+    let (samples, timestamps) = drain(&mut device);
+
+    assert_eq!(samples.len(), 2);
+    assert_eq!(samples[0].len(), 1000);
+    assert_eq!(samples[0][10], 160.0 / 32768.0);
+    assert_eq!(samples[1][10], -160.0 / 32768.0);
+
+    // Timestamps follow the recording's sample clock: 256 frames at 8 kHz = 32 ms
+    assert_eq!(timestamps.len(), 4);
+    assert_eq!(timestamps[1] - timestamps[0], 32_000_000);
+    assert!(!device.is_streaming());
+    device.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_file_driver_realtime_pacing() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("short.wav");
+    write_wav_i16(&path, 8000, 1, &vec![0i16; 800]); // 100 ms
+
+    // Unregistered device ids are treated as paths
+    let driver = FileDriver::default();
+    let mut device = driver.create_device(path.to_str().unwrap(), config(80)).unwrap();
+
+    let started = Instant::now();
+    device.start().await.unwrap();
+    let (samples, _) = drain(&mut device);
+    assert_eq!(samples[0].len(), 800);
+    assert!(started.elapsed() >= Duration::from_millis(85));
+}
+
+#[test]
+fn test_recording_from_ring_buffer_dump() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("ring.bin");
+    {
+        let writer = RingBufferWriter::new(&path, 4096, 2, 1).unwrap();
+        for block in 0..6 {
+            writer.write(&[vec![block as f64; 1024], vec![-(block as f64); 1024]]).unwrap();
+        }
+    }
+
+    // 6 writes into a 4096-frame ring: the oldest surviving block is #2
+    let recording = Recording::open(&path).unwrap();
+    assert_eq!(recording.sample_rate, 4096);
+    assert_eq!(recording.num_channels, 2);
+    assert_eq!(recording.frames(), 4096);
+    match recording.slice(0, 1) {
+        SampleData::F64(v) => assert_eq!(v, vec![2.0, -2.0]),
+        other => panic!("unexpected data {:?}", other),
+    }
+    match recording.slice(4095, 1) {
+        SampleData::F64(v) => assert_eq!(v, vec![5.0, -5.0]),
+        other => panic!("unexpected data {:?}", other),
+    }
+
+    assert!(Recording::open(dir.path().join("missing.wav")).is_err());
+}
+
+#[test]
+fn test_ring_buffer_snapshot_exports_to_wav_and_csv() {
+    let dir = tempfile::tempdir().unwrap();
+    let writer = RingBufferWriter::new(dir.path().join("ring.bin"), 1000, 2, 1).unwrap();
+    // Only 300 of 1000 frames written; unwritten space is not exported
+    let left: Vec<f64> = (0..300).map(|n| n as f64 / 1000.0).collect();
+    let right: Vec<f64> = left.iter().map(|s| -s).collect();
+    writer.write(&[left, right]).unwrap();
+
+    let recording = Recording::from_ring_buffer_bytes(&writer.snapshot().unwrap()).unwrap();
+    assert_eq!(recording.frames(), 300);
+
+    // Last 100 ms
+    let wav_path = dir.path().join("export/last.wav");
+    recording.tail(0.1).save(&wav_path, RecordingFormat::from_path(&wav_path).unwrap()).unwrap();
+    let wav = Recording::open(&wav_path).unwrap();
+    assert_eq!(wav.sample_rate, 1000);
+    assert_eq!(wav.num_channels, 2);
+    assert_eq!(wav.frames(), 100);
+    match wav.slice(0, 1) {
+        SampleData::F32(v) => assert_eq!(v, vec![0.2, -0.2]),
+        other => panic!("unexpected data {:?}", other),
+    }
+
+    let csv_path = dir.path().join("last.csv");
+    recording.tail(0.002).save(&csv_path, RecordingFormat::Csv).unwrap();
+    let csv = std::fs::read_to_string(&csv_path).unwrap();
+    assert_eq!(csv, "time_s,ch0,ch1\n0,0.298,-0.298\n0.001,0.299,-0.299\n");
+
+    assert!(RecordingFormat::parse("flac").is_err());
+}
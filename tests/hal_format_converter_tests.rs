@@ -0,0 +1,196 @@
+use audiotab::core::DataFrame;
+use audiotab::hal::format_converter::{frame_to_packet, packet_to_frame, FrameConverter};
+use audiotab::hal::{PacketBuffer, SampleData, SampleFormat};
+use proptest::prelude::*;
+
+const FORMATS: [SampleFormat; 6] = [
+    SampleFormat::I16,
+    SampleFormat::I24,
+    SampleFormat::I32,
+    SampleFormat::F32,
+    SampleFormat::F64,
+    SampleFormat::U8,
+];
+
+/// Smallest step each format can represent in the -1.0..1.0 range
+fn resolution(format: SampleFormat) -> f64 {
+    match format {
+        SampleFormat::I16 => 1.0 / 32768.0,
+        SampleFormat::I24 => 1.0 / 8388608.0,
+        SampleFormat::I32 => 1.0 / 2147483648.0,
+        SampleFormat::F32 => f32::EPSILON as f64,
+        SampleFormat::F64 => 0.0,
+        SampleFormat::U8 => 1.0 / 128.0,
+    }
+}
+
+/// Interleaved samples of `len` values in any of the formats
+fn sample_data(len: usize) -> impl Strategy<Value = SampleData> {
+    prop_oneof![
+        prop::collection::vec(any::<i16>(), len).prop_map(SampleData::I16),
+        prop::collection::vec(any::<u8>(), len * 3).prop_map(SampleData::I24),
+        prop::collection::vec(any::<i32>(), len).prop_map(SampleData::I32),
+        prop::collection::vec(-1.0f32..1.0, len).prop_map(SampleData::F32),
+        prop::collection::vec(-1.0f64..1.0, len).prop_map(SampleData::F64),
+        prop::collection::vec(any::<u8>(), len).prop_map(SampleData::U8),
+    ]
+}
+
+fn format_of(data: &SampleData) -> SampleFormat {
+    match data {
+        SampleData::I16(_) => SampleFormat::I16,
+        SampleData::I24(_) => SampleFormat::I24,
+        SampleData::I32(_) => SampleFormat::I32,
+        SampleData::F32(_) => SampleFormat::F32,
+        SampleData::F64(_) => SampleFormat::F64,
+        SampleData::U8(_) => SampleFormat::U8,
+        SampleData::Bytes(_) => unreachable!(),
+    }
+}
+
+/// Well-formed packets: 1-32 channels of 0-64 frames
+fn packet() -> impl Strategy<Value = PacketBuffer> {
+    (1usize..=32, 0usize..=64).prop_flat_map(|(num_channels, frames)| {
+        sample_data(num_channels * frames).prop_map(move |data| PacketBuffer {
+            data,
+            sample_rate: 48000,
+            num_channels,
+            timestamp: None,
+            codec: None,
+        })
+    })
+}
+
+/// Packets from a misbehaving device: any channel count, byte length and sample rate
+fn malformed_packet() -> impl Strategy<Value = PacketBuffer> {
+    (0usize..=8, 0usize..=64, prop_oneof![Just(0u64), any::<u64>()])
+        .prop_flat_map(|(num_channels, len, sample_rate)| {
+            (
+                Just(num_channels),
+                Just(sample_rate),
+                prop_oneof![
+                    prop::collection::vec(any::<u8>(), len).prop_map(SampleData::I24),
+                    sample_data(len),
+                ],
+            )
+        })
+        .prop_map(|(num_channels, sample_rate, data)| PacketBuffer { data, sample_rate, num_channels, timestamp: None, codec: None })
+}
+
+fn frame(channels: Vec<Vec<f64>>) -> DataFrame {
+    let mut frame = DataFrame::new(0, 0);
+    for (ch, samples) in channels.into_iter().enumerate() {
+        frame.insert_channel(format!("ch{}", ch), samples);
+    }
+    frame
+}
+
+proptest! {
+    #[test]
+    fn test_packet_round_trips_through_frame(packet in packet()) {
+        let frame = packet_to_frame(&packet, 0).unwrap();
+        prop_assert_eq!(frame.payload.len(), packet.num_channels);
+
+        let back = frame_to_packet(&frame, format_of(&packet.data), packet.sample_rate).unwrap();
+        prop_assert_eq!(back.num_channels, packet.num_channels);
+        prop_assert_eq!(back.data, packet.data);
+    }
+
+    #[test]
+    fn test_frame_round_trips_within_format_resolution(
+        format in prop::sample::select(&FORMATS[..]),
+        channels in (1usize..=16, 1usize..=64)
+            .prop_flat_map(|(n, len)| prop::collection::vec(prop::collection::vec(-1.0f64..1.0, len), n)),
+    ) {
+        let packet = frame_to_packet(&frame(channels.clone()), format, 48000).unwrap();
+        let back = packet_to_frame(&packet, 0).unwrap();
+        for (ch, original) in channels.iter().enumerate() {
+            let converted = back.payload.get(&format!("ch{}", ch)).unwrap();
+            prop_assert_eq!(converted.len(), original.len());
+            for (a, b) in original.iter().zip(converted.iter()) {
+                prop_assert!((a - b).abs() <= resolution(format), "{:?}: {} became {}", format, a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_malformed_packets_are_rejected_without_panicking(packet in malformed_packet(), sequence_id: u64) {
+        if let Ok(frame) = packet_to_frame(&packet, sequence_id) {
+            prop_assert!(packet.num_channels > 0);
+            prop_assert_eq!(frame.payload.len(), packet.num_channels);
+            let lengths: Vec<usize> = frame.payload.values().map(|c| c.len()).collect();
+            prop_assert!(lengths.windows(2).all(|w| w[0] == w[1]));
+        }
+    }
+
+    #[test]
+    fn test_ragged_frames_are_rejected(lengths in prop::collection::vec(0usize..=16, 2..=8)) {
+        let channels: Vec<Vec<f64>> = lengths.iter().map(|&len| vec![0.5; len]).collect();
+        let result = frame_to_packet(&frame(channels), SampleFormat::I24, 48000);
+        prop_assert_eq!(result.is_ok(), lengths.iter().all(|&len| len == lengths[0]));
+    }
+}
+
+#[test]
+fn test_i24_sign_extension() {
+    let packet = PacketBuffer {
+        // 0x7FFFFF, 0x800000, 0xFFFFFF, 0x000001
+        data: SampleData::I24(vec![0xFF, 0xFF, 0x7F, 0x00, 0x00, 0x80, 0xFF, 0xFF, 0xFF, 0x01, 0x00, 0x00]),
+        sample_rate: 48000,
+        num_channels: 1,
+        timestamp: None,
+        codec: None,
+    };
+    let frame = packet_to_frame(&packet, 0).unwrap();
+    let expected = [8388607.0, -8388608.0, -1.0, 1.0].map(|v| v / 8388608.0);
+    assert_eq!(frame.payload["ch0"].samples(), &expected[..]);
+}
+
+#[test]
+fn test_truncated_packets_are_errors() {
+    let packet = |data, num_channels| PacketBuffer { data, sample_rate: 48000, num_channels, timestamp: None, codec: None };
+    assert!(packet_to_frame(&packet(SampleData::I16(vec![0; 4]), 0), 0).is_err());
+    assert!(packet_to_frame(&packet(SampleData::I24(vec![0; 7]), 1), 0).is_err());
+    assert!(packet_to_frame(&packet(SampleData::F32(vec![0.0; 5]), 2), 0).is_err());
+}
+
+fn csv_packet(text: &str, codec: Option<&str>) -> PacketBuffer {
+    PacketBuffer {
+        data: SampleData::Bytes(text.as_bytes().to_vec()),
+        sample_rate: 100,
+        num_channels: 1,
+        timestamp: None,
+        codec: codec.map(str::to_string),
+    }
+}
+
+#[test]
+fn test_bytes_packets_decode_with_their_codec() {
+    let frame = packet_to_frame(&csv_packet("1,2\n3,4\n", Some("csv")), 0).unwrap();
+    assert_eq!(frame.payload["ch0"].samples(), &[1.0, 3.0]);
+    assert_eq!(frame.payload["ch1"].samples(), &[2.0, 4.0]);
+    assert_eq!(frame.metadata.get_f64("sample_rate"), Some(100.0));
+
+    assert!(packet_to_frame(&csv_packet("1,2\n", None), 0).is_err());
+    let error = packet_to_frame(&csv_packet("1,2\n", Some("morse")), 0).unwrap_err();
+    assert!(error.to_string().contains("morse"), "{}", error);
+}
+
+#[test]
+fn test_frame_converter_keeps_partial_records() {
+    let mut converter = FrameConverter::new();
+    let first = converter.convert(&csv_packet("1,2\n3,", Some("csv")), 0).unwrap();
+    assert_eq!(first.payload["ch0"].samples(), &[1.0]);
+    let second = converter.convert(&csv_packet("4\n5,6\n", Some("csv")), 1).unwrap();
+    assert_eq!(second.payload["ch0"].samples(), &[3.0, 5.0]);
+    assert_eq!(second.payload["ch1"].samples(), &[4.0, 6.0]);
+
+    // The stateless conversion misreads the tail of the split record
+    let split = packet_to_frame(&csv_packet("4\n5,6\n", Some("csv")), 1).unwrap();
+    assert_eq!(split.payload["ch0"].samples(), &[4.0]);
+    assert!(!split.payload.contains_key("ch1"));
+
+    // Other formats convert as usual
+    let packet = PacketBuffer { data: SampleData::I16(vec![16384]), sample_rate: 48000, num_channels: 1, timestamp: None, codec: None };
+    assert_eq!(converter.convert(&packet, 2).unwrap().payload["ch0"].samples(), &[0.5]);
+}
@@ -0,0 +1,67 @@
+use audiotab::dsp::fir::design_linear_phase;
+use audiotab::dsp::FirFilter;
+use audiotab::hal::FrequencyResponse;
+
+const MINIDSP: &str = "\"Sens Factor =-1.25dB, AGain =18dB, SERNO: 7001234\"
+20.0\t-2.0\t0.0
+100.0\t0.0
+1000.0\t0.0
+10000.0\t6.0
+20000.0\t-30.0
+";
+
+/// Magnitude of an FIR at `freq`
+fn gain_at(taps: &[f64], freq: f64, sample_rate: f64) -> f64 {
+    let w = 2.0 * std::f64::consts::PI * freq / sample_rate;
+    let (re, im) = taps.iter().enumerate().fold((0.0, 0.0), |(re, im), (n, h)| {
+        (re + h * (w * n as f64).cos(), im - h * (w * n as f64).sin())
+    });
+    (re * re + im * im).sqrt()
+}
+
+#[test]
+fn test_parse_minidsp_calibration_file() {
+    let response = FrequencyResponse::parse(MINIDSP).unwrap();
+    assert_eq!(response.points.len(), 5);
+    assert_eq!(response.sensitivity_db, Some(-1.25));
+    assert_eq!(response.serial.as_deref(), Some("7001234"));
+
+    // Log-frequency interpolation, held flat past the ends
+    assert!((response.level_db((20.0f64 * 100.0).sqrt()) + 1.0).abs() < 1e-9);
+    assert_eq!(response.level_db(10.0), -2.0);
+    assert_eq!(response.level_db(24000.0), -30.0);
+
+    // CSV with a comment header parses as well
+    let csv = FrequencyResponse::parse("* Earthworks M23\nHz,dB\n100,0.5\n1000,0\n").unwrap();
+    assert_eq!(csv.points, vec![(100.0, 0.5), (1000.0, 0.0)]);
+
+    assert!(FrequencyResponse::parse("\"header only\"\n1000 0\n").is_err());
+}
+
+#[test]
+fn test_compensation_fir_inverts_response() {
+    let response = FrequencyResponse::parse(MINIDSP).unwrap();
+    let taps = response.compensation_fir(48000.0, 1023);
+    assert_eq!(taps.len(), 1023);
+
+    let db = |freq| 20.0 * gain_at(&taps, freq, 48000.0).log10();
+    assert!(db(1000.0).abs() < 0.2, "1 kHz {}", db(1000.0));
+    assert!((db(10000.0) + 6.0).abs() < 0.3, "10 kHz {}", db(10000.0));
+    // The 30 dB roll-off is only boosted by the 12 dB limit
+    assert!((db(20000.0) - 12.0).abs() < 0.5, "20 kHz {}", db(20000.0));
+}
+
+#[test]
+fn test_fir_filter_streams_across_blocks() {
+    let taps = design_linear_phase(31, 48000.0, |_| 1.0);
+    // A flat response is a pure delay of (31 - 1) / 2 samples
+    let mut impulse = vec![0.0; 40];
+    impulse[0] = 1.0;
+    let mut filter = FirFilter::new(taps);
+    let (first, second) = impulse.split_at_mut(10);
+    filter.process_block(first);
+    filter.process_block(second);
+    let peak = impulse.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1)).unwrap().0;
+    assert_eq!(peak, 15);
+    assert!((impulse[15] - 1.0).abs() < 0.05);
+}
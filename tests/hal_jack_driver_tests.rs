@@ -0,0 +1,60 @@
+use audiotab::hal::*;
+use audiotab::observability::DeviceHealth;
+
+fn config() -> DeviceConfig {
+    DeviceConfig {
+        name: "JACK".to_string(),
+        sample_rate: 48000,
+        format: SampleFormat::F32,
+        buffer_size: 256,
+        channel_mapping: ChannelMapping::default(),
+        calibration: Calibration::default(),
+        buffer_count: 2,
+        latency_mode: LatencyMode::LowLatency,
+    }
+}
+
+#[test]
+fn test_device_health_snapshot() {
+    let health = DeviceHealth::new();
+    health.record_xrun();
+    health.record_xrun();
+    health.record_dropped_cycle();
+    health.set_transport(true, 96000);
+
+    let snapshot = health.snapshot();
+    assert_eq!(snapshot.xruns, 2);
+    assert_eq!(snapshot.dropped_cycles, 1);
+    assert!(snapshot.transport_rolling);
+    assert_eq!(snapshot.transport_frame, 96000);
+    assert!(snapshot.is_healthy());
+
+    health.record_shutdown("server stopped");
+    assert_eq!(health.snapshot().shutdown_reason.as_deref(), Some("server stopped"));
+    assert!(!health.snapshot().is_healthy());
+}
+
+#[test]
+fn test_devices_without_health_reporting() {
+    let driver = LoopbackDriver::new();
+    let device = driver.create_device("loopback-input", config()).unwrap();
+    assert!(device.health().is_none());
+}
+
+#[cfg(feature = "jack")]
+#[tokio::test]
+async fn test_jack_driver_devices() {
+    let driver = JackDriver::new();
+    assert_eq!(driver.driver_id(), "jack");
+    // Without a running server discovery is empty rather than an error
+    let devices = driver.discover_devices().await.unwrap();
+    assert!(devices.is_empty() || devices.len() == 2);
+
+    let mapping = ChannelMapping { physical_channels: 6, ..ChannelMapping::default() };
+    let device = driver
+        .create_device("capture", DeviceConfig { channel_mapping: mapping, ..config() })
+        .unwrap();
+    assert_eq!(device.capabilities().max_channels, 6);
+    assert_eq!(device.health().unwrap().xruns, 0);
+    assert!(driver.create_device("monitor", config()).is_err());
+}
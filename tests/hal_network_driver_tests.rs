@@ -0,0 +1,148 @@
+use audiotab::hal::drivers::{NetworkEncoding, NetworkPayload, NetworkStreamConfig};
+use audiotab::hal::drivers::network::parse_rtp;
+use audiotab::hal::*;
+use std::net::{SocketAddr, UdpSocket};
+use std::time::{Duration, Instant};
+
+fn config(buffer_size: usize) -> DeviceConfig {
+    DeviceConfig {
+        name: "Network".to_string(),
+        sample_rate: 48000,
+        format: SampleFormat::F32,
+        buffer_size,
+        channel_mapping: ChannelMapping::default(),
+        calibration: Calibration::default(),
+        buffer_count: 8,
+        latency_mode: LatencyMode::LowLatency,
+    }
+}
+
+fn free_local_addr() -> SocketAddr {
+    UdpSocket::bind("127.0.0.1:0").unwrap().local_addr().unwrap()
+}
+
+/// RTP packet carrying L16 samples, all equal to `value`
+fn rtp_l16(sequence: u16, value: i16, samples: usize) -> Vec<u8> {
+    let mut bytes = vec![0x80, 96];
+    bytes.extend_from_slice(&sequence.to_be_bytes());
+    bytes.extend_from_slice(&(sequence as u32 * samples as u32).to_be_bytes());
+    bytes.extend_from_slice(&0x1234_5678u32.to_be_bytes());
+    for _ in 0..samples {
+        bytes.extend_from_slice(&value.to_be_bytes());
+    }
+    bytes
+}
+
+/// Collect `count` mono samples from a started device
+fn collect(device: &mut Box<dyn Device>, count: usize) -> Vec<f32> {
+    let channels = device.get_channels();
+    let deadline = Instant::now() + Duration::from_secs(2);
+    let mut samples = Vec::new();
+    while samples.len() < count && Instant::now() < deadline {
+        if let Ok(packet) = channels.filled_rx.recv_timeout(Duration::from_millis(20)) {
+            assert!(packet.timestamp.is_some());
+            match &packet.data {
+                SampleData::F32(v) => samples.extend_from_slice(v),
+                other => panic!("unexpected data {:?}", other),
+            }
+            channels.empty_tx.send(packet).unwrap();
+        }
+    }
+    samples
+}
+
+#[test]
+fn test_parse_rtp_skips_csrcs_and_padding() {
+    // One CSRC, padding flag set with 2 padding bytes
+    let mut bytes = vec![0xA1, 97, 0x01, 0x02, 0, 0, 0, 48, 0, 0, 0, 7, 0, 0, 0, 9];
+    bytes.extend_from_slice(&[0x11, 0x22, 0x33, 0, 2]);
+    let packet = parse_rtp(&bytes).unwrap();
+    assert_eq!(packet.sequence, 0x0102);
+    assert_eq!(packet.timestamp, 48);
+    assert_eq!(packet.ssrc, 7);
+    assert_eq!(packet.payload, &[0x11, 0x22, 0x33]);
+
+    assert!(parse_rtp(&[0x80, 96, 0, 1]).is_err());
+    assert!(parse_rtp(&[0u8; 12]).is_err());
+}
+
+#[tokio::test]
+async fn test_network_driver_conceals_lost_rtp_packets() {
+    let addr = free_local_addr();
+    let driver = NetworkDriver::new();
+    driver.add_stream(
+        "mic-1",
+        NetworkStreamConfig {
+            bind: addr,
+            multicast_group: None,
+            payload: NetworkPayload::Rtp,
+            encoding: NetworkEncoding::L16,
+            sample_rate: 48000,
+            channels: 1,
+        },
+    );
+    assert_eq!(driver.discover_devices().await.unwrap()[0].id, "mic-1");
+    assert!(driver.create_device("mic-2", config(48)).is_err());
+
+    let mut device = driver.create_device("mic-1", config(48)).unwrap();
+    device.start().await.unwrap();
+
+    let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+    // Sequence 3 and 4 are lost; 2 arrives late after 5
+    for (sequence, value) in [(0u16, 1000i16), (1, 2000), (2, 3000), (5, 6000), (2, 3000), (6, 7000)] {
+        sender.send_to(&rtp_l16(sequence, value, 48), addr).unwrap();
+        std::thread::sleep(Duration::from_millis(2));
+    }
+
+    let samples = collect(&mut device, 48 * 7);
+    assert_eq!(samples.len(), 48 * 7);
+    let per_packet: Vec<f32> = samples.chunks(48).map(|c| c[0] * 32768.0).collect();
+    // Lost packets repeat the last packet received
+    assert_eq!(per_packet, vec![1000.0, 2000.0, 3000.0, 3000.0, 3000.0, 6000.0, 7000.0]);
+
+    let stats = driver.stats("mic-1").unwrap();
+    assert_eq!(stats.packets_received, 5);
+    assert_eq!(stats.packets_lost, 2);
+    assert_eq!(stats.packets_late, 1);
+    assert_eq!(stats.samples_concealed, 96);
+
+    device.stop().await.unwrap();
+    assert!(!device.is_streaming());
+}
+
+#[tokio::test]
+async fn test_network_driver_raw_pcm_stereo() {
+    let addr = free_local_addr();
+    let driver = NetworkDriver::new();
+    driver.add_stream(
+        "raw",
+        NetworkStreamConfig {
+            bind: addr,
+            multicast_group: None,
+            payload: NetworkPayload::RawPcm,
+            encoding: NetworkEncoding::F32Le,
+            sample_rate: 8000,
+            channels: 2,
+        },
+    );
+    let mut device = driver.create_device("raw", config(16)).unwrap();
+    assert_eq!(device.capabilities().max_channels, 2);
+    device.start().await.unwrap();
+
+    // 10 interleaved frames per datagram
+    let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+    for n in 0..4 {
+        let bytes: Vec<u8> = (0..10)
+            .flat_map(|i| [(n * 10 + i) as f32, -((n * 10 + i) as f32)])
+            .flat_map(f32::to_le_bytes)
+            .collect();
+        sender.send_to(&bytes, addr).unwrap();
+    }
+
+    let samples = collect(&mut device, 64);
+    assert_eq!(samples.len(), 64);
+    assert_eq!(&samples[..4], &[0.0, -0.0, 1.0, -1.0]);
+    assert_eq!(samples[62], 31.0);
+    assert_eq!(driver.stats("raw").unwrap().packets_received, 4);
+    device.stop().await.unwrap();
+}
@@ -107,6 +107,8 @@ async fn test_registry_create_device() {
         buffer_size: 1024,
         channel_mapping: ChannelMapping::default(),
         calibration: Calibration::default(),
+        buffer_count: 2,
+        latency_mode: LatencyMode::LowLatency,
     };
 
     let mut device = registry.create_device("mock-driver", "mock-device-1", config).unwrap();
@@ -118,3 +120,22 @@ async fn test_registry_create_device() {
     device.stop().await.unwrap();
     assert!(!device.is_streaming());
 }
+
+#[test]
+fn test_registry_clones_share_drivers() {
+    let mut registry = HardwareRegistry::with_builtin_drivers();
+    let custom: std::sync::Arc<dyn HardwareDriver> = std::sync::Arc::new(MockDriver);
+    registry.register_shared(custom.clone());
+
+    let kernel_registry = registry.clone();
+    let drivers = kernel_registry.list_drivers();
+    assert!(drivers.contains(&"mock-driver".to_string()));
+    assert!(drivers.contains(&"loopback".to_string()));
+
+    // Both registries hand out the same driver instances
+    assert!(std::sync::Arc::ptr_eq(&kernel_registry.get_driver("mock-driver").unwrap(), &custom));
+    assert!(std::sync::Arc::ptr_eq(
+        &kernel_registry.get_driver("loopback").unwrap(),
+        &registry.get_driver("loopback").unwrap()
+    ));
+}
@@ -0,0 +1,94 @@
+use audiotab::hal::drivers::SerialPortConfig;
+use audiotab::hal::format_converter::bytes_to_frame;
+use audiotab::hal::*;
+
+fn config() -> DeviceConfig {
+    DeviceConfig {
+        name: "Serial".to_string(),
+        sample_rate: 1000,
+        format: SampleFormat::F32,
+        buffer_size: 64,
+        channel_mapping: ChannelMapping::default(),
+        calibration: Calibration::default(),
+        buffer_count: 2,
+        latency_mode: LatencyMode::LowLatency,
+    }
+}
+
+fn bytes_packet(bytes: &[u8]) -> PacketBuffer {
+    PacketBuffer {
+        data: SampleData::Bytes(bytes.to_vec()),
+        sample_rate: 1000,
+        num_channels: 1,
+        timestamp: Some(42),
+        codec: None,
+    }
+}
+
+#[test]
+fn test_line_decoder_buffers_partial_lines() {
+    let mut decoder = LineDecoder::new(',');
+    let first = decoder.decode(b"0.5,1.5,-2\n0.25,").unwrap();
+    assert_eq!(first, vec![vec![0.5], vec![1.5], vec![-2.0]]);
+    assert_eq!(decoder.channel_names(), vec!["ch0", "ch1", "ch2"]);
+
+    // Completes the partial line; malformed and short lines are skipped
+    let second = decoder.decode(b"0.75,3\nnoise\n1,2\n9,8,7\n").unwrap();
+    assert_eq!(second, vec![vec![0.25, 9.0], vec![0.75, 8.0], vec![3.0, 7.0]]);
+}
+
+#[test]
+fn test_binary_frame_decoder_resyncs() {
+    let mut decoder = BinaryFrameDecoder::new(&[0xAA, 0x55], 2, SampleFormat::I16, false).unwrap();
+    let mut bytes = vec![0x01, 0x02]; // garbage before the first sync
+    bytes.extend_from_slice(&[0xAA, 0x55]);
+    bytes.extend_from_slice(&100i16.to_le_bytes());
+    bytes.extend_from_slice(&(-200i16).to_le_bytes());
+    bytes.extend_from_slice(&[0xAA, 0x55, 0x10]); // incomplete frame
+
+    let decoded = decoder.decode(&bytes).unwrap();
+    assert_eq!(decoded, vec![vec![100.0], vec![-200.0]]);
+
+    let decoded = decoder.decode(&[0x00, 0x20, 0x00]).unwrap();
+    assert_eq!(decoded, vec![vec![16.0], vec![32.0]]);
+
+    assert!(BinaryFrameDecoder::new(&[0xAA], 0, SampleFormat::I16, false).is_err());
+}
+
+#[test]
+fn test_bytes_to_frame_uses_decoder() {
+    let registry = DecoderRegistry::new();
+    registry.register("tacho", || {
+        Box::new(BinaryFrameDecoder::new(b"T", 1, SampleFormat::F32, true).unwrap())
+    });
+    let mut decoder = registry.create("tacho").unwrap();
+    assert!(registry.create("missing").is_err());
+
+    let mut bytes = b"T".to_vec();
+    bytes.extend_from_slice(&1500.0f32.to_be_bytes());
+    let frame = bytes_to_frame(&bytes_packet(&bytes), 7, decoder.as_mut()).unwrap();
+    assert_eq!(frame.timestamp, 42);
+    assert_eq!(frame.sequence_id, 7);
+    assert_eq!(&frame.payload["ch0"][..], &[1500.0]);
+    assert_eq!(frame.sample_rate(), Some(1000.0));
+
+    // The raw converter still refuses Bytes packets
+    assert!(format_converter::packet_to_frame(&bytes_packet(&bytes), 0).is_err());
+}
+
+#[tokio::test]
+async fn test_serial_driver_devices() {
+    let driver = SerialDriver::new();
+    driver.add_port("accel", SerialPortConfig::new("/dev/null-accel").with_decoder("csv"));
+    driver.add_port("bad", SerialPortConfig::new("/dev/null-bad").with_decoder("nope"));
+    assert_eq!(driver.hardware_type(), HardwareType::Special);
+
+    let devices = driver.discover_devices().await.unwrap();
+    assert!(devices.iter().any(|d| d.id == "accel" && d.name == "/dev/null-accel @ 115200 baud"));
+
+    let mut device = driver.create_device("accel", config()).unwrap();
+    assert!(device.bytes_decoder().is_some());
+    assert!(driver.create_device("bad", config()).is_err());
+    // Opening a missing port fails at start
+    assert!(device.start().await.is_err());
+}
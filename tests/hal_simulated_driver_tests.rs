@@ -0,0 +1,195 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::engine::{AsyncPipeline, AudioKernelRuntime, KernelStatus};
+use audiotab::hal::drivers::{Fault, SimulatedSignal, SimulatedStream};
+use audiotab::hal::*;
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+/// Sink that keeps every frame it receives
+struct CollectSink(Arc<Mutex<Vec<DataFrame>>>);
+
+#[async_trait]
+impl ProcessingNode for CollectSink {
+    async fn process(&mut self, input: DataFrame) -> anyhow::Result<DataFrame> {
+        self.0.lock().unwrap().push(input.clone());
+        Ok(input)
+    }
+}
+
+struct Simulation {
+    kernel: AudioKernelRuntime,
+    stream: Arc<SimulatedStream>,
+    frames: Arc<Mutex<Vec<DataFrame>>>,
+}
+
+impl Simulation {
+    /// Kernel running simulated device "sim" as registration "mic", with a
+    /// pipeline collecting the frames of an AudioSourceNode bound to it
+    async fn start(signal: SimulatedSignal) -> Self {
+        let driver = SimulatedDriver::new();
+        let stream = driver.stream("bench", signal);
+        let mut registry = HardwareRegistry::new();
+        registry.register(driver);
+
+        let config = HardwareConfig {
+            version: "1.0".to_string(),
+            registered_devices: vec![RegisteredHardware {
+                registration_id: "mic".to_string(),
+                device_id: "bench".to_string(),
+                hardware_name: "Simulated".to_string(),
+                driver_id: "simulated".to_string(),
+                hardware_type: HardwareType::Acoustic,
+                direction: Direction::Input,
+                user_name: "Mic".to_string(),
+                enabled: true,
+                protocol: None,
+                sample_rate: 48000,
+                channels: stream.signal().channels,
+                channel_mapping: ChannelMapping::default(),
+                calibration: Calibration::default(),
+                calibration_history: Vec::new(),
+                max_voltage: 0.0,
+                buffer_count: 4,
+                latency_mode: LatencyMode::LowLatency,
+                notes: String::new(),
+            }],
+        };
+
+        let frames = Arc::new(Mutex::new(Vec::new()));
+        let mut pipeline = AsyncPipeline::from_json(serde_json::json!({
+            "nodes": [
+                {"id": "src", "type": "AudioSourceNode", "config": {"device_profile_id": "mic"}},
+                {"id": "sink", "type": "Print", "config": {}}
+            ],
+            "connections": [{"from": "src", "to": "sink"}]
+        }))
+        .await
+        .unwrap();
+        pipeline.nodes_mut().insert("sink".to_string(), Box::new(CollectSink(frames.clone())));
+
+        let mut kernel = AudioKernelRuntime::with_shared_registry(Arc::new(RwLock::new(registry)), config);
+        kernel.set_pipeline(pipeline);
+        kernel.start().await.unwrap();
+        Self { kernel, stream, frames }
+    }
+
+    /// Emit `count` packets from a blocking thread, as a driver callback would
+    async fn emit(&self, count: usize) -> usize {
+        let stream = self.stream.clone();
+        tokio::task::spawn_blocking(move || stream.emit(count)).await.unwrap()
+    }
+
+    async fn wait_for_frames(&self, count: usize) -> Vec<DataFrame> {
+        for _ in 0..400 {
+            if self.frames.lock().unwrap().len() >= count {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        self.frames.lock().unwrap().clone()
+    }
+
+    /// Index of the first sample in `frame`, found by matching the signal
+    fn first_sample(&self, frame: &DataFrame, packets: u64) -> Option<u64> {
+        let size = self.stream.buffer_size() as u64;
+        let samples = frame.payload["ch0"].samples();
+        (0..packets).map(|p| p * size).find(|&start| (samples[1] - self.stream.sample(0, start + 1)).abs() < 1e-6)
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulated_signal_reaches_the_pipeline() {
+    let mut sim = Simulation::start(SimulatedSignal { channels: 2, ..Default::default() }).await;
+    assert_eq!(sim.emit(3).await, 3);
+
+    let frames = sim.wait_for_frames(3).await;
+    assert_eq!(frames.len(), 3);
+    let size = sim.stream.buffer_size();
+    for (packet, frame) in frames.iter().enumerate() {
+        assert_eq!(frame.metadata.get_str("device"), Some("mic"));
+        for ch in 0..2 {
+            let samples = frame.payload[&format!("ch{}", ch)].samples();
+            assert_eq!(samples.len(), size);
+            for (i, &value) in samples.iter().enumerate() {
+                let expected = sim.stream.sample(ch, (packet * size + i) as u64);
+                assert!((value - expected).abs() < 1e-6);
+            }
+        }
+    }
+
+    sim.kernel.shutdown().await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_dropped_packets_leave_a_gap_and_count_as_xruns() {
+    let mut sim = Simulation::start(SimulatedSignal::default()).await;
+    sim.stream.schedule(2, Fault::DropPackets(2));
+    assert_eq!(sim.emit(6).await, 4);
+
+    let frames = sim.wait_for_frames(4).await;
+    assert_eq!(frames.len(), 4);
+    let starts: Vec<Option<u64>> = frames.iter().map(|f| sim.first_sample(f, 6)).collect();
+    let size = sim.stream.buffer_size() as u64;
+    assert_eq!(starts, [Some(0), Some(size), Some(4 * size), Some(5 * size)]);
+    assert_eq!(sim.kernel.device_health()["mic"].xruns, 2);
+
+    sim.kernel.shutdown().await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_format_change_mid_stream_keeps_the_signal() {
+    let mut sim = Simulation::start(SimulatedSignal::default()).await;
+    sim.stream.schedule(1, Fault::FormatChange(SampleFormat::I16));
+    sim.stream.schedule(2, Fault::FormatChange(SampleFormat::I24));
+    assert_eq!(sim.emit(3).await, 3);
+
+    let frames = sim.wait_for_frames(3).await;
+    assert_eq!(frames.len(), 3);
+    let size = sim.stream.buffer_size();
+    for (packet, frame) in frames.iter().enumerate() {
+        let samples = frame.payload["ch0"].samples();
+        assert_eq!(samples.len(), size);
+        for (i, &value) in samples.iter().enumerate() {
+            let expected = sim.stream.sample(0, (packet * size + i) as u64);
+            assert!((value - expected).abs() <= 1.0 / 32768.0, "packet {}: {} vs {}", packet, value, expected);
+        }
+    }
+
+    sim.kernel.shutdown().await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_timestamp_jumps_pass_through_to_frames() {
+    let mut sim = Simulation::start(SimulatedSignal::default()).await;
+    sim.stream.schedule(2, Fault::TimestampJump(5_000_000_000));
+    sim.stream.schedule(3, Fault::TimestampJump(-3_000_000_000));
+    assert_eq!(sim.emit(4).await, 4);
+
+    let frames = sim.wait_for_frames(4).await;
+    // Offset of each frame from the first, against the nominal packet times
+    let nominal = |packet: i64| packet * 1024 * 1_000_000_000 / 48000;
+    let offset = |i: usize| frames[i].timestamp as i64 - frames[0].timestamp as i64 - nominal(i as i64);
+    assert_eq!(offset(1), 0);
+    assert_eq!(offset(2), 5_000_000_000);
+    assert_eq!(offset(3), 2_000_000_000);
+    assert!(sim.kernel.device_health()["mic"].is_healthy());
+
+    sim.kernel.shutdown().await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_disconnect_is_reported_and_the_pipeline_keeps_running() {
+    let mut sim = Simulation::start(SimulatedSignal::default()).await;
+    sim.stream.schedule(2, Fault::Disconnect);
+    assert_eq!(sim.emit(5).await, 2);
+    assert!(!sim.stream.is_connected());
+
+    assert_eq!(sim.wait_for_frames(2).await.len(), 2);
+    let health = &sim.kernel.device_health()["mic"];
+    assert_eq!(health.shutdown_reason.as_deref(), Some("Device disconnected"));
+    assert_eq!(sim.kernel.status(), KernelStatus::Running);
+
+    sim.kernel.shutdown().await.unwrap();
+}
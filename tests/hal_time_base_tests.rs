@@ -0,0 +1,47 @@
+use audiotab::hal::format_converter::packet_to_frame;
+use audiotab::hal::{PacketBuffer, SampleData, TimeBase};
+use std::time::Duration;
+
+fn packet(timestamp: Option<u64>) -> PacketBuffer {
+    PacketBuffer {
+        data: SampleData::F32(vec![0.0; 64]),
+        sample_rate: 48000,
+        num_channels: 1,
+        timestamp,
+        codec: None,
+    }
+}
+
+#[test]
+fn test_time_base_is_shared_and_monotonic() {
+    let a = TimeBase::global();
+    let b = TimeBase::global();
+    assert_eq!(a, b);
+
+    let first = a.now_ns();
+    std::thread::sleep(Duration::from_millis(2));
+    let second = b.now_ns();
+    assert!(second >= first + 2_000_000);
+
+    // Capture stamps are backdated by the driver latency, never below the origin
+    let capture = a.capture_ns(Duration::from_millis(1));
+    assert!(capture <= a.now_ns());
+    assert_eq!(TimeBase::new().capture_ns(Duration::from_secs(3600)), 0);
+}
+
+#[test]
+fn test_packets_from_different_devices_share_a_clock() {
+    let time_base = TimeBase::global();
+
+    // Two devices stamping their callbacks against the same clock
+    let mic = packet(Some(time_base.now_ns()));
+    std::thread::sleep(Duration::from_millis(1));
+    let accelerometer = packet(Some(time_base.now_ns()));
+
+    // Sequence numbers differ per device but timestamps stay comparable
+    let mic_frame = packet_to_frame(&mic, 500).unwrap();
+    let accel_frame = packet_to_frame(&accelerometer, 3).unwrap();
+    assert_eq!(mic_frame.timestamp, mic.timestamp.unwrap());
+    assert_eq!(accel_frame.timestamp, accelerometer.timestamp.unwrap());
+    assert!(accel_frame.timestamp > mic_frame.timestamp);
+}
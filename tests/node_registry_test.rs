@@ -56,7 +56,7 @@ fn test_node_metadata_has_correct_structure() {
         .expect("gain_db parameter not found");
 
     assert_eq!(gain_param.param_type, "number");
-    assert_eq!(gain_param.min, Some(0.0));
+    assert_eq!(gain_param.min, Some(-80.0));
     assert_eq!(gain_param.max, Some(80.0));
 }
 
@@ -80,3 +80,124 @@ fn test_node_factory_creates_instance() {
     // (Rust doesn't have null, so if we got here, creation succeeded)
     let _ = instance; // Just verify instance was created
 }
+
+#[test]
+fn test_choice_parameter_schema() {
+    use audiotab::nodes::FFTNode;
+    let _ = FFTNode::default();
+
+    let mut nodes: Vec<NodeMetadata> = Vec::new();
+    for wrapper in inventory::iter::<NodeMetadataFactoryWrapper> {
+        nodes.push((wrapper.0)());
+    }
+
+    let fft_node = nodes.iter().find(|n| n.id == "fftnode").expect("FFTNode not found");
+    let window_param = fft_node.parameters.iter()
+        .find(|p| p.name == "window_type")
+        .expect("window_type parameter not found");
+
+    assert_eq!(window_param.param_type, "enum");
+    let choices = window_param.choices.as_ref().expect("Expected choices");
+    assert!(choices.contains(&"hann".to_string()));
+    assert!(choices.contains(&"blackman".to_string()));
+
+    let json = serde_json::to_value(window_param).unwrap();
+    assert_eq!(json["type"], "enum");
+    assert!(json["choices"].is_array());
+}
+
+#[tokio::test]
+async fn test_invalid_choice_rejected_on_create() {
+    use audiotab::core::ProcessingNode;
+    use audiotab::nodes::FFTNode;
+
+    let mut node = FFTNode::default();
+    let err = node
+        .on_create(serde_json::json!({"window_type": "triangle"}))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("window_type"));
+
+    let mut node = FFTNode::default();
+    node.on_create(serde_json::json!({"window_type": "Blackman"}))
+        .await
+        .unwrap();
+}
+
+#[test]
+fn test_parameter_display_hints() {
+    use audiotab::nodes::FilterNode;
+    let _ = FilterNode::default();
+
+    let mut nodes: Vec<NodeMetadata> = Vec::new();
+    for wrapper in inventory::iter::<NodeMetadataFactoryWrapper> {
+        nodes.push((wrapper.0)());
+    }
+
+    let filter_node = nodes.iter().find(|n| n.id == "filternode").expect("FilterNode not found");
+    let cutoff = filter_node.parameters.iter()
+        .find(|p| p.name == "cutoff_hz")
+        .expect("cutoff_hz parameter not found");
+
+    assert_eq!(cutoff.unit.as_deref(), Some("Hz"));
+    assert_eq!(cutoff.step, Some(1.0));
+    assert_eq!(cutoff.scale.as_deref(), Some("log"));
+
+    let filter_type = filter_node.parameters.iter()
+        .find(|p| p.name == "filter_type")
+        .expect("filter_type parameter not found");
+    let json = serde_json::to_value(filter_type).unwrap();
+    assert!(json.get("unit").is_none());
+    assert!(json.get("scale").is_none());
+}
+
+#[tokio::test]
+async fn test_apply_config_parses_and_validates_params() {
+    use audiotab::core::ProcessingNode;
+    use audiotab::nodes::{FFTNode, FilterNode};
+
+    let mut filter = FilterNode::default();
+    filter
+        .on_create(serde_json::json!({"filter_type": "highpass", "cutoff_hz": 250.0}))
+        .await
+        .unwrap();
+    assert_eq!(filter.filter_type, "highpass");
+    assert_eq!(filter.cutoff_hz, 250.0);
+
+    // Missing and null keys keep their defaults
+    let mut filter = FilterNode::default();
+    filter.on_create(serde_json::json!({"cutoff_hz": null})).await.unwrap();
+    assert_eq!(filter.cutoff_hz, 1000.0);
+
+    // Out of range
+    let err = FilterNode::default()
+        .on_create(serde_json::json!({"cutoff_hz": 5.0}))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("cutoff_hz"));
+
+    // Wrong type
+    let err = FFTNode::default()
+        .on_create(serde_json::json!({"fft_size": "large"}))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("fft_size"));
+}
+
+#[test]
+fn test_negative_range_parameters_are_registered() {
+    use audiotab::nodes::CompressorNode;
+    let _ = CompressorNode::default();
+
+    let mut nodes: Vec<NodeMetadata> = Vec::new();
+    for wrapper in inventory::iter::<NodeMetadataFactoryWrapper> {
+        nodes.push((wrapper.0)());
+    }
+
+    let compressor = nodes.iter().find(|n| n.id == "compressornode").expect("CompressorNode not found");
+    let threshold = compressor.parameters.iter()
+        .find(|p| p.name == "threshold_db")
+        .expect("threshold_db parameter not found");
+    assert_eq!(threshold.min, Some(-120.0));
+    assert_eq!(threshold.max, Some(0.0));
+}
@@ -0,0 +1,162 @@
+use audiotab::core::{ChannelRole, DataFrame, ProcessingNode};
+use audiotab::engine::AsyncPipeline;
+use audiotab::nodes::AdaptiveFilterNode;
+use async_trait::async_trait;
+use serde_json::json;
+use tokio::sync::mpsc;
+
+/// Deterministic white noise in [-0.5, 0.5)
+fn noise(len: usize, seed: u64) -> Vec<f64> {
+    let mut state = seed;
+    (0..len)
+        .map(|_| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+        })
+        .collect()
+}
+
+fn rms(x: &[f64]) -> f64 {
+    (x.iter().map(|s| s * s).sum::<f64>() / x.len() as f64).sqrt()
+}
+
+/// Primary is a tone plus the reference noise through a short acoustic path
+fn field_recording(len: usize) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+    let path = [0.0, 0.8, -0.4, 0.2];
+    let reference = noise(len, 42);
+    let tone: Vec<f64> = (0..len).map(|n| 0.05 * (2.0 * std::f64::consts::PI * n as f64 / 48.0).sin()).collect();
+    let primary = (0..len)
+        .map(|n| {
+            let noise: f64 = path.iter().enumerate().filter(|(k, _)| *k <= n).map(|(k, h)| h * reference[n - k]).sum();
+            tone[n] + noise
+        })
+        .collect();
+    (primary, reference, tone)
+}
+
+#[tokio::test]
+async fn test_nlms_cancels_reference_noise() {
+    let (primary, reference, tone) = field_recording(24_000);
+    let mut node = AdaptiveFilterNode::default();
+    node.on_create(json!({"taps": 8, "step_size": 0.02})).await.unwrap();
+
+    let mut error = Vec::new();
+    let mut last = None;
+    for (seq, (p, r)) in primary.chunks(1000).zip(reference.chunks(1000)).enumerate() {
+        let mut frame = DataFrame::new(seq as u64 * 1_000_000, seq as u64);
+        frame.insert_channel("ch0", p.to_vec());
+        frame.insert_channel("ch1", r.to_vec());
+        let out = node.process(frame).await.unwrap();
+        error.extend_from_slice(out.payload["error"].samples());
+        last = Some(out);
+    }
+
+    // After convergence the error is the tone the noise was masking
+    let residual: Vec<f64> = error.iter().zip(&tone).map(|(e, t)| e - t).skip(20_000).collect();
+    let noise: Vec<f64> = primary.iter().zip(&tone).map(|(p, t)| p - t).skip(20_000).collect();
+    assert!(rms(&residual) < 0.02 * rms(&noise), "residual rms {}", rms(&residual));
+
+    let out = last.unwrap();
+    assert_eq!(out.payload.len(), 5);
+    let coefficients = &out.payload["coefficients"];
+    assert_eq!(coefficients.role, ChannelRole::Measurement);
+    assert_eq!(coefficients.len(), 8);
+    for (w, h) in coefficients.samples().iter().zip([0.0, 0.8, -0.4, 0.2, 0.0]) {
+        assert!((w - h).abs() < 0.02, "{:?}", coefficients.samples());
+    }
+    assert_eq!(node.coefficients(), coefficients.samples());
+    assert_eq!(out.metadata.get_str("adaptive_algorithm"), Some("nlms"));
+}
+
+#[tokio::test]
+async fn test_frozen_filter_and_dropped_inputs() {
+    let mut node = AdaptiveFilterNode::default();
+    let config = json!({
+        "algorithm": "lms",
+        "adapt": false,
+        "keep_inputs": false,
+        "primary_channel": "mic",
+        "reference_channel": "ref"
+    });
+    node.on_create(config).await.unwrap();
+    let mut frame = DataFrame::new(0, 0);
+    frame.insert_channel("mic", vec![1.0, 2.0]);
+    frame.insert_channel("ref", vec![3.0, 4.0]);
+    let out = node.process(frame).await.unwrap();
+    assert_eq!(out.payload.len(), 3);
+    assert_eq!(out.payload["error"].samples(), &[1.0, 2.0]);
+    assert!(node.coefficients().iter().all(|w| *w == 0.0));
+}
+
+#[tokio::test]
+async fn test_invalid_input() {
+    for config in [json!({"algorithm": "rls"}), json!({"step_size": 0.0}), json!({"taps": 0})] {
+        let mut node = AdaptiveFilterNode::default();
+        assert!(node.on_create(config.clone()).await.is_err(), "{}", config);
+    }
+
+    let mut node = AdaptiveFilterNode::default();
+    node.on_create(json!({})).await.unwrap();
+    let mut frame = DataFrame::new(0, 0);
+    frame.insert_channel("ch0", vec![0.0; 4]);
+    assert!(node.process(frame.clone()).await.is_err());
+    frame.insert_channel("ch1", vec![0.0; 3]);
+    assert!(node.process(frame).await.is_err());
+}
+
+/// Sink forwarding every filtered frame
+struct ForwardSink(mpsc::UnboundedSender<DataFrame>);
+
+#[async_trait]
+impl ProcessingNode for ForwardSink {
+    async fn process(&mut self, input: DataFrame) -> anyhow::Result<DataFrame> {
+        if input.payload.contains_key("error") {
+            let _ = self.0.send(input.clone());
+        }
+        Ok(input)
+    }
+}
+
+#[tokio::test]
+async fn test_primary_and_reference_from_separate_branches() {
+    let mut pipeline = AsyncPipeline::from_json(json!({
+        "nodes": [
+            {"id": "src", "type": "Gain", "config": {}},
+            {"id": "primary", "type": "ChannelRouter", "config": {"routes": "ch0"}},
+            {"id": "reference", "type": "ChannelRouter", "config": {"routes": "ch1 -> ch0"}},
+            {"id": "anc", "type": "AdaptiveFilter", "config": {"taps": 8, "step_size": 0.02}},
+            {"id": "sink", "type": "Print", "config": {}}
+        ],
+        "connections": [
+            {"from": "src", "to": "primary"},
+            {"from": "src", "to": "reference"},
+            {"from": "primary", "to": "anc", "to_port": "_primary"},
+            {"from": "reference", "to": "anc", "to_port": "_reference"},
+            {"from": "anc", "to": "sink"}
+        ]
+    }))
+    .await
+    .unwrap();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    pipeline.nodes_mut().insert("sink".to_string(), Box::new(ForwardSink(tx)));
+    pipeline.start().await.unwrap();
+
+    let (primary, reference, tone) = field_recording(24_000);
+    for (seq, (p, r)) in primary.chunks(1000).zip(reference.chunks(1000)).enumerate() {
+        let mut frame = DataFrame::new(seq as u64 * 1_000_000, seq as u64);
+        frame.insert_channel("ch0", p.to_vec());
+        frame.insert_channel("ch1", r.to_vec());
+        pipeline.trigger(frame).await.unwrap();
+    }
+
+    let mut error = Vec::new();
+    for _ in 0..24 {
+        let out = rx.recv().await.unwrap();
+        error.extend_from_slice(out.payload["error"].samples());
+    }
+    let residual: Vec<f64> = error.iter().zip(&tone).map(|(e, t)| e - t).skip(20_000).collect();
+    let noise: Vec<f64> = primary.iter().zip(&tone).map(|(p, t)| p - t).skip(20_000).collect();
+    assert!(rms(&residual) < 0.02 * rms(&noise), "residual rms {}", rms(&residual));
+
+    pipeline.stop().await.unwrap();
+}
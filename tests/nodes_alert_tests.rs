@@ -0,0 +1,60 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::nodes::AlertNode;
+use serde_json::json;
+
+fn frame(channels: &[(&str, Vec<f64>)]) -> DataFrame {
+    let mut frame = DataFrame::new(0, 0);
+    for (name, samples) in channels {
+        frame.insert_channel(*name, samples.clone());
+    }
+    frame
+}
+
+#[tokio::test]
+async fn test_raises_alert_above_threshold() {
+    let mut node = AlertNode::default();
+    node.on_create(json!({"name": "too_loud", "channel": "spl", "threshold": 85.0, "severity": "critical"}))
+        .await
+        .unwrap();
+
+    let quiet = node.process(frame(&[("spl", vec![60.0, 84.0])])).await.unwrap();
+    assert!(quiet.metadata.get("alert").is_none());
+
+    let loud = node.process(frame(&[("spl", vec![80.0, 92.0, 88.0]), ("other", vec![200.0])])).await.unwrap();
+    assert_eq!(loud.metadata.get_str("alert"), Some("too_loud"));
+    assert_eq!(loud.metadata.get_str("alert_severity"), Some("critical"));
+    assert_eq!(loud.metadata.get_f64("alert_value"), Some(92.0));
+    assert_eq!(loud.metadata.get_str("alert_message"), Some("'spl' at 92 is above the threshold of 85"));
+    // Frames pass through unchanged
+    assert_eq!(loud.payload["spl"].samples(), &[80.0, 92.0, 88.0]);
+}
+
+#[tokio::test]
+async fn test_below_condition_on_any_channel_with_message_template() {
+    let mut node = AlertNode::default();
+    node.on_create(json!({
+        "name": "dropout",
+        "condition": "below",
+        "threshold": 0.1,
+        "message": "{channel} fell to {value} (limit {threshold})"
+    }))
+    .await
+    .unwrap();
+
+    let out = node.process(frame(&[("a", vec![0.5, 0.05]), ("b", vec![0.02, 0.3])])).await.unwrap();
+    assert_eq!(out.metadata.get_str("alert"), Some("dropout"));
+    assert_eq!(out.metadata.get_str("alert_severity"), Some("warning"));
+    assert_eq!(out.metadata.get_str("alert_message"), Some("b fell to 0.02 (limit 0.1)"));
+}
+
+#[tokio::test]
+async fn test_missing_channel_and_invalid_configurations() {
+    let mut node = AlertNode::default();
+    node.on_create(json!({"channel": "siren"})).await.unwrap();
+    assert!(node.process(frame(&[("dog", vec![1.0])])).await.is_err());
+
+    for config in [json!({"name": ""}), json!({"condition": "equal"}), json!({"severity": "urgent"})] {
+        let mut node = AlertNode::default();
+        assert!(node.on_create(config.clone()).await.is_err(), "{}", config);
+    }
+}
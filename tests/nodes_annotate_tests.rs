@@ -0,0 +1,51 @@
+use audiotab::core::{DataFrame, MetadataValue, ProcessingNode};
+use audiotab::nodes::AnnotateNode;
+use serde_json::json;
+
+fn frame() -> DataFrame {
+    let mut frame = DataFrame::new(0, 0);
+    frame.insert_channel("ch0", vec![0.0; 4]);
+    frame.metadata.insert("operator", "previous");
+    frame
+}
+
+#[tokio::test]
+async fn test_stamps_named_fields_and_tags() {
+    let mut node = AnnotateNode::default();
+    node.on_create(json!({
+        "test_name": "Drop test",
+        "operator": "J. Doe",
+        "device_serial": "",
+        "tags": {"run": 3, "fixture": "B2", "warm": true}
+    }))
+    .await
+    .unwrap();
+
+    let out = node.process(frame()).await.unwrap();
+    assert_eq!(out.metadata.get_str("test_name"), Some("Drop test"));
+    assert_eq!(out.metadata.get_str("operator"), Some("J. Doe"));
+    assert!(!out.metadata.contains_key("device_serial"));
+    assert_eq!(out.metadata.get("run"), Some(&MetadataValue::Int(3)));
+    assert_eq!(out.metadata.get_str("fixture"), Some("B2"));
+    assert_eq!(out.metadata.get_bool("warm"), Some(true));
+    assert_eq!(out.payload["ch0"].len(), 4);
+}
+
+#[tokio::test]
+async fn test_without_overwrite_existing_keys_are_kept() {
+    let mut node = AnnotateNode::default();
+    node.on_create(json!({"tags": "operator=new, station=4", "overwrite": false})).await.unwrap();
+
+    let out = node.process(frame()).await.unwrap();
+    assert_eq!(out.metadata.get_str("operator"), Some("previous"));
+    assert_eq!(out.metadata.get_str("station"), Some("4"));
+    assert_eq!(out.metadata.get_i64("station"), Some(4));
+}
+
+#[tokio::test]
+async fn test_malformed_tags_are_rejected() {
+    for tags in [json!("station"), json!("=4"), json!({"nested": {"a": 1}})] {
+        let mut node = AnnotateNode::default();
+        assert!(node.on_create(json!({ "tags": tags })).await.is_err(), "{}", tags);
+    }
+}
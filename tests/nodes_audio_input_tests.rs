@@ -1,9 +1,9 @@
 use audiotab::core::{DataFrame, ProcessingNode};
 use audiotab::nodes::AudioInputNode;
-use audiotab::hal::{DeviceChannels, PacketBuffer, SampleData};
+use audiotab::hal::{DeviceChannels, FrequencyResponse, PacketBuffer, SampleData};
 use audiotab::visualization::RingBufferWriter;
 use crossbeam_channel::unbounded;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 #[tokio::test]
 async fn test_audio_input_node_creation() {
@@ -55,6 +55,7 @@ async fn test_audio_input_node_processes_packet() {
         sample_rate: 48000,
         num_channels: 1,
         timestamp: Some(1000000),
+        codec: None,
     };
 
     // Send packet to the node
@@ -110,6 +111,7 @@ async fn test_audio_input_node_multi_channel() {
         sample_rate: 48000,
         num_channels: 2,
         timestamp: Some(2000000),
+        codec: None,
     };
 
     filled_tx.send(packet).unwrap();
@@ -152,7 +154,7 @@ async fn test_audio_input_node_with_ring_buffer() {
     let ring_buffer_path = "/tmp/test_audio_input_ringbuf";
     let _ = std::fs::remove_file(ring_buffer_path);
     let ring_buffer = RingBufferWriter::new(ring_buffer_path, 48000, 2, 1).unwrap();
-    let ring_buffer_arc = Arc::new(Mutex::new(ring_buffer));
+    let ring_buffer_arc = Arc::new(ring_buffer);
 
     let config = serde_json::json!({
         "sample_rate": 48000,
@@ -175,6 +177,7 @@ async fn test_audio_input_node_with_ring_buffer() {
         sample_rate: 48000,
         num_channels: 2,
         timestamp: Some(3000000),
+        codec: None,
     };
 
     filled_tx.send(packet).unwrap();
@@ -183,12 +186,10 @@ async fn test_audio_input_node_with_ring_buffer() {
     let _output_frame = node.process(input_frame).await.unwrap();
 
     // Verify ring buffer was updated
-    let rb = ring_buffer_arc.lock().unwrap();
-    let seq = rb.get_write_sequence();
+    let seq = ring_buffer_arc.get_write_sequence();
     assert_eq!(seq, 1);
 
     // Cleanup
-    drop(rb);
     drop(ring_buffer_arc);
     std::fs::remove_file(ring_buffer_path).unwrap();
 }
@@ -248,6 +249,7 @@ async fn test_audio_input_node_sequence_increment() {
             sample_rate: 48000,
             num_channels: 1,
             timestamp: Some(i * 1000000),
+            codec: None,
         };
         filled_tx.send(packet).unwrap();
 
@@ -281,6 +283,7 @@ async fn test_audio_input_node_metadata() {
         sample_rate: 96000,
         num_channels: 1,
         timestamp: Some(5000000),
+        codec: None,
     };
 
     filled_tx.send(packet).unwrap();
@@ -290,5 +293,44 @@ async fn test_audio_input_node_metadata() {
 
     // Verify metadata contains sample rate
     assert!(output_frame.metadata.contains_key("sample_rate"));
-    assert_eq!(output_frame.metadata.get("sample_rate").unwrap(), "96000");
+    assert_eq!(output_frame.sample_rate(), Some(96000.0));
+}
+
+#[tokio::test]
+async fn test_audio_input_node_compensates_frequency_response() {
+    let (filled_tx, filled_rx) = unbounded();
+    let (empty_tx, _empty_rx) = unbounded();
+
+    // Mic is 6 dB hot at 8 kHz relative to 1 kHz
+    let response = FrequencyResponse::parse("100 0\n1000 0\n8000 6\n20000 6\n").unwrap();
+    let mut node = AudioInputNode::new(DeviceChannels { filled_rx, empty_tx }, None)
+        .with_frequency_response(Some(response));
+    node.on_create(serde_json::json!({"compensation_taps": 255})).await.unwrap();
+
+    let sine = |freq: f64| -> Vec<f32> {
+        (0..4096)
+            .map(|n| (0.5 * (2.0 * std::f64::consts::PI * freq * n as f64 / 48000.0).sin()) as f32)
+            .collect()
+    };
+    // RMS after the filter has settled; 0.5 amplitude is 0.354 RMS
+    let rms = |frame: &DataFrame| {
+        let settled = &frame.payload["ch0"][1024..];
+        (settled.iter().map(|s| s * s).sum::<f64>() / settled.len() as f64).sqrt()
+    };
+
+    for (freq, expected) in [(1000.0, 0.354), (8000.0, 0.177)] {
+        filled_tx
+            .send(PacketBuffer { data: SampleData::F32(sine(freq)), sample_rate: 48000, num_channels: 1, timestamp: None, codec: None })
+            .unwrap();
+        let frame = node.process(DataFrame::new(0, 0)).await.unwrap();
+        assert!((rms(&frame) - expected).abs() < 0.01, "{} Hz rms {}", freq, rms(&frame));
+    }
+
+    // Disabling compensation passes samples through untouched
+    node.compensate = false;
+    filled_tx
+        .send(PacketBuffer { data: SampleData::F32(sine(8000.0)), sample_rate: 48000, num_channels: 1, timestamp: None, codec: None })
+        .unwrap();
+    let frame = node.process(DataFrame::new(0, 0)).await.unwrap();
+    assert!((rms(&frame) - 0.354).abs() < 0.005);
 }
@@ -1,9 +1,8 @@
-use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::core::{Channel, DataFrame, Metadata, ProcessingNode};
 use audiotab::nodes::AudioOutputNode;
 use audiotab::hal::{DeviceChannels, SampleData, SampleFormat};
 use crossbeam_channel::unbounded;
 use std::collections::HashMap;
-use std::sync::Arc;
 
 #[tokio::test]
 async fn test_audio_output_node_creation() {
@@ -51,15 +50,16 @@ async fn test_audio_output_node_processes_frame() {
     // Create a test DataFrame with known data
     let mut payload = HashMap::new();
     let test_samples = vec![0.1f64, 0.2, 0.3, 0.4, 0.5];
-    payload.insert("ch0".to_string(), Arc::new(test_samples.clone()));
+    payload.insert("ch0".to_string(), Channel::new(test_samples.clone()));
 
-    let mut metadata = HashMap::new();
-    metadata.insert("sample_rate".to_string(), "48000".to_string());
+    let mut metadata = Metadata::new();
+    metadata.insert("sample_rate", 48000.0);
 
     let input_frame = DataFrame {
         timestamp: 1000000,
         sequence_id: 1,
         payload,
+        events: HashMap::new(),
         metadata,
     };
 
@@ -108,16 +108,17 @@ async fn test_audio_output_node_multi_channel() {
 
     // Create stereo DataFrame
     let mut payload = HashMap::new();
-    payload.insert("ch0".to_string(), Arc::new(vec![0.1f64, 0.2, 0.3]));
-    payload.insert("ch1".to_string(), Arc::new(vec![0.4f64, 0.5, 0.6]));
+    payload.insert("ch0".to_string(), Channel::new(vec![0.1f64, 0.2, 0.3]));
+    payload.insert("ch1".to_string(), Channel::new(vec![0.4f64, 0.5, 0.6]));
 
-    let mut metadata = HashMap::new();
-    metadata.insert("sample_rate".to_string(), "48000".to_string());
+    let mut metadata = Metadata::new();
+    metadata.insert("sample_rate", 48000.0);
 
     let input_frame = DataFrame {
         timestamp: 2000000,
         sequence_id: 2,
         payload,
+        events: HashMap::new(),
         metadata,
     };
 
@@ -196,10 +197,10 @@ async fn test_audio_output_node_different_formats() {
         node.on_create(config).await.unwrap();
 
         let mut payload = HashMap::new();
-        payload.insert("ch0".to_string(), Arc::new(vec![0.5f64, -0.5]));
-        let mut metadata = HashMap::new();
-        metadata.insert("sample_rate".to_string(), "48000".to_string());
-        let frame = DataFrame { timestamp: 0, sequence_id: 1, payload, metadata };
+        payload.insert("ch0".to_string(), Channel::new(vec![0.5f64, -0.5]));
+        let mut metadata = Metadata::new();
+        metadata.insert("sample_rate", 48000.0);
+        let frame = DataFrame { timestamp: 0, sequence_id: 1, payload, events: HashMap::new(), metadata };
 
         node.process(frame).await.unwrap();
         let packet = empty_rx.try_recv().unwrap();
@@ -230,10 +231,10 @@ async fn test_audio_output_node_different_formats() {
         node.on_create(config).await.unwrap();
 
         let mut payload = HashMap::new();
-        payload.insert("ch0".to_string(), Arc::new(vec![0.7f64, -0.3]));
-        let mut metadata = HashMap::new();
-        metadata.insert("sample_rate".to_string(), "48000".to_string());
-        let frame = DataFrame { timestamp: 0, sequence_id: 1, payload, metadata };
+        payload.insert("ch0".to_string(), Channel::new(vec![0.7f64, -0.3]));
+        let mut metadata = Metadata::new();
+        metadata.insert("sample_rate", 48000.0);
+        let frame = DataFrame { timestamp: 0, sequence_id: 1, payload, events: HashMap::new(), metadata };
 
         node.process(frame).await.unwrap();
         let packet = empty_rx.try_recv().unwrap();
@@ -266,10 +267,10 @@ async fn test_audio_output_node_different_formats() {
         node.on_create(config).await.unwrap();
 
         let mut payload = HashMap::new();
-        payload.insert("ch0".to_string(), Arc::new(vec![0.0f64, 0.5, -0.5]));
-        let mut metadata = HashMap::new();
-        metadata.insert("sample_rate".to_string(), "48000".to_string());
-        let frame = DataFrame { timestamp: 0, sequence_id: 1, payload, metadata };
+        payload.insert("ch0".to_string(), Channel::new(vec![0.0f64, 0.5, -0.5]));
+        let mut metadata = Metadata::new();
+        metadata.insert("sample_rate", 48000.0);
+        let frame = DataFrame { timestamp: 0, sequence_id: 1, payload, events: HashMap::new(), metadata };
 
         node.process(frame).await.unwrap();
         let packet = empty_rx.try_recv().unwrap();
@@ -306,13 +307,14 @@ async fn test_audio_output_node_sequence_passthrough() {
     // Process multiple frames with different sequence IDs
     for i in 1..=3 {
         let mut payload = HashMap::new();
-        payload.insert("ch0".to_string(), Arc::new(vec![i as f64]));
-        let mut metadata = HashMap::new();
-        metadata.insert("sample_rate".to_string(), "48000".to_string());
+        payload.insert("ch0".to_string(), Channel::new(vec![i as f64]));
+        let mut metadata = Metadata::new();
+        metadata.insert("sample_rate", 48000.0);
         let frame = DataFrame {
             timestamp: i * 1000000,
             sequence_id: i,
             payload,
+            events: HashMap::new(),
             metadata,
         };
 
@@ -342,13 +344,14 @@ async fn test_audio_output_node_timestamp_preservation() {
 
     let test_timestamp = 5000000u64;
     let mut payload = HashMap::new();
-    payload.insert("ch0".to_string(), Arc::new(vec![0.5f64]));
-    let mut metadata = HashMap::new();
-    metadata.insert("sample_rate".to_string(), "48000".to_string());
+    payload.insert("ch0".to_string(), Channel::new(vec![0.5f64]));
+    let mut metadata = Metadata::new();
+    metadata.insert("sample_rate", 48000.0);
     let frame = DataFrame {
         timestamp: test_timestamp,
         sequence_id: 1,
         payload,
+        events: HashMap::new(),
         metadata,
     };
 
@@ -356,3 +359,35 @@ async fn test_audio_output_node_timestamp_preservation() {
     let packet = empty_rx.try_recv().unwrap();
     assert_eq!(packet.timestamp, Some(test_timestamp));
 }
+
+#[tokio::test]
+async fn test_audio_output_node_dither() {
+    // 0.3 LSB of U8 is lost by truncation; dither keeps it in the average
+    let level = 0.3 / 128.0;
+    let mut means = Vec::new();
+    for dither in ["none", "tpdf", "shaped"] {
+        let (_filled_tx, filled_rx) = unbounded();
+        let (empty_tx, empty_rx) = unbounded();
+        let mut node = AudioOutputNode::new(DeviceChannels { filled_rx, empty_tx }, SampleFormat::U8);
+        node.on_create(serde_json::json!({"format": "U8", "dither": dither})).await.unwrap();
+
+        let mut codes = Vec::new();
+        for seq in 0..10 {
+            let mut frame = DataFrame::new(seq * 1000, seq);
+            frame.insert_channel("ch0", vec![level; 1000]);
+            node.process(frame).await.unwrap();
+            match empty_rx.try_recv().unwrap().data {
+                SampleData::U8(samples) => codes.extend(samples.into_iter().map(|s| s as f64 - 128.0)),
+                _ => panic!("Expected U8 data"),
+            }
+        }
+        assert!(codes.iter().all(|c| c.abs() <= 2.0), "{}", dither);
+        means.push(codes.iter().sum::<f64>() / codes.len() as f64);
+    }
+    assert_eq!(means[0], 0.0);
+    assert!((means[1] - 0.3).abs() < 0.05, "{}", means[1]);
+    assert!((means[2] - 0.3).abs() < 0.05, "{}", means[2]);
+
+    let mut node = AudioOutputNode::default();
+    assert!(node.on_create(serde_json::json!({"dither": "gaussian"})).await.is_err());
+}
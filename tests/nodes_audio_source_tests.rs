@@ -3,7 +3,7 @@ use audiotab::nodes::AudioSourceNode;
 use audiotab::hal::{DeviceChannels, PacketBuffer, SampleData};
 use audiotab::visualization::RingBufferWriter;
 use crossbeam_channel::unbounded;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 #[tokio::test]
 async fn test_audio_source_node_default_silent() {
@@ -48,6 +48,7 @@ async fn test_audio_source_node_with_device_channels() {
         sample_rate: 48000,
         num_channels: 1,
         timestamp: Some(1000000),
+        codec: None,
     };
 
     filled_tx.send(packet).unwrap();
@@ -135,6 +136,7 @@ async fn test_audio_source_node_multi_channel_device() {
         sample_rate: 48000,
         num_channels: 2,
         timestamp: Some(2000000),
+        codec: None,
     };
 
     filled_tx.send(packet).unwrap();
@@ -180,7 +182,7 @@ async fn test_audio_source_node_with_ring_buffer() {
     let ring_buffer_path = "/tmp/test_audio_source_ringbuf";
     let _ = std::fs::remove_file(ring_buffer_path);
     let ring_buffer = RingBufferWriter::new(ring_buffer_path, 48000, 1, 1).unwrap();
-    let ring_buffer_arc = Arc::new(Mutex::new(ring_buffer));
+    let ring_buffer_arc = Arc::new(ring_buffer);
 
     let test_samples = vec![0.1f32, 0.2, 0.3, 0.4, 0.5];
     let packet = PacketBuffer {
@@ -188,6 +190,7 @@ async fn test_audio_source_node_with_ring_buffer() {
         sample_rate: 48000,
         num_channels: 1,
         timestamp: Some(3000000),
+        codec: None,
     };
 
     filled_tx.send(packet).unwrap();
@@ -203,12 +206,10 @@ async fn test_audio_source_node_with_ring_buffer() {
     let _output_frame = node.process(input_frame).await.unwrap();
 
     // Verify ring buffer was updated
-    let rb = ring_buffer_arc.lock().unwrap();
-    let seq = rb.get_write_sequence();
+    let seq = ring_buffer_arc.get_write_sequence();
     assert_eq!(seq, 1);
 
     // Cleanup
-    drop(rb);
     drop(ring_buffer_arc);
     std::fs::remove_file(ring_buffer_path).unwrap();
 }
@@ -219,7 +220,7 @@ async fn test_audio_source_node_silent_writes_to_ring_buffer() {
     let ring_buffer_path = "/tmp/test_audio_source_silent_ringbuf";
     let _ = std::fs::remove_file(ring_buffer_path);
     let ring_buffer = RingBufferWriter::new(ring_buffer_path, 48000, 1, 1).unwrap();
-    let ring_buffer_arc = Arc::new(Mutex::new(ring_buffer));
+    let ring_buffer_arc = Arc::new(ring_buffer);
 
     let config = serde_json::json!({
         "sample_rate": 48000,
@@ -234,12 +235,10 @@ async fn test_audio_source_node_silent_writes_to_ring_buffer() {
     let _output_frame = node.process(input_frame).await.unwrap();
 
     // Verify ring buffer was updated
-    let rb = ring_buffer_arc.lock().unwrap();
-    let seq = rb.get_write_sequence();
+    let seq = ring_buffer_arc.get_write_sequence();
     assert_eq!(seq, 1);
 
     // Cleanup
-    drop(rb);
     drop(ring_buffer_arc);
     std::fs::remove_file(ring_buffer_path).unwrap();
 }
@@ -0,0 +1,69 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::nodes::AveragingNode;
+
+fn spectrum(sequence_id: u64, bins: Vec<f64>) -> DataFrame {
+    let mut frame = DataFrame::new(0, sequence_id);
+    frame.insert_channel("ch0", bins);
+    frame
+}
+
+async fn node(config: serde_json::Value) -> AveragingNode {
+    let mut node = AveragingNode::default();
+    node.on_create(config).await.unwrap();
+    node
+}
+
+#[tokio::test]
+async fn test_linear_average_over_last_n() {
+    let mut node = node(serde_json::json!({"mode": "linear", "num_averages": 2})).await;
+
+    node.process(spectrum(0, vec![1.0, 2.0])).await.unwrap();
+    let second = node.process(spectrum(1, vec![3.0, 4.0])).await.unwrap();
+    assert_eq!(second.payload.get("ch0").unwrap().as_ref(), &vec![2.0, 3.0]);
+    assert_eq!(second.metadata.get_i64("averages"), Some(2));
+
+    // Only the last two frames count
+    let third = node.process(spectrum(2, vec![5.0, 6.0])).await.unwrap();
+    assert_eq!(third.payload.get("ch0").unwrap().as_ref(), &vec![4.0, 5.0]);
+}
+
+#[tokio::test]
+async fn test_exponential_forgetting_factor() {
+    let mut node = node(serde_json::json!({"mode": "exponential", "forgetting_factor": 0.75})).await;
+
+    node.process(spectrum(0, vec![4.0])).await.unwrap();
+    let result = node.process(spectrum(1, vec![8.0])).await.unwrap();
+    assert_eq!(result.payload.get("ch0").unwrap().as_ref(), &vec![5.0]);
+}
+
+#[tokio::test]
+async fn test_peak_hold_and_reset() {
+    let mut node = node(serde_json::json!({"mode": "peak_hold"})).await;
+
+    node.process(spectrum(0, vec![1.0, 5.0])).await.unwrap();
+    let held = node.process(spectrum(1, vec![3.0, 2.0])).await.unwrap();
+    assert_eq!(held.payload.get("ch0").unwrap().as_ref(), &vec![3.0, 5.0]);
+
+    // A frame on the Reset input clears the peaks and carries no data
+    let mut reset = DataFrame::new(0, 0);
+    reset.metadata.insert("input_port", "_reset");
+    let result = node.process(reset).await.unwrap();
+    assert!(result.payload.is_empty());
+
+    let after = node.process(spectrum(2, vec![2.0, 1.0])).await.unwrap();
+    assert_eq!(after.payload.get("ch0").unwrap().as_ref(), &vec![2.0, 1.0]);
+
+    let mut marked = spectrum(3, vec![0.5, 0.5]);
+    marked.metadata.insert("reset_average", true);
+    let result = node.process(marked).await.unwrap();
+    assert_eq!(result.payload.get("ch0").unwrap().as_ref(), &vec![0.5, 0.5]);
+    assert_eq!(result.metadata.get_i64("averages"), Some(1));
+}
+
+#[tokio::test]
+async fn test_rejects_invalid_config() {
+    let mut node = AveragingNode::default();
+    assert!(node.on_create(serde_json::json!({"mode": "none"})).await.is_err());
+    assert!(node.on_create(serde_json::json!({"mode": "median"})).await.is_err());
+    assert!(node.on_create(serde_json::json!({"mode": "exponential", "forgetting_factor": 1.0})).await.is_err());
+}
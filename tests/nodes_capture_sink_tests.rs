@@ -0,0 +1,146 @@
+#![cfg(feature = "parquet")]
+
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::nodes::CaptureSinkNode;
+use audiotab::resilience::checkpoint::sidecar_path;
+use audiotab::resilience::recover_file;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::record::RowAccessor;
+use std::fs::File;
+use std::io::Write;
+use std::time::Duration;
+use tempfile::tempdir;
+
+fn capture_frame(sequence_id: u64, start: usize, len: usize) -> DataFrame {
+    let mut frame = DataFrame::new(sequence_id * 10, sequence_id);
+    frame.insert_channel("ch0", (start..start + len).map(|n| n as f64).collect::<Vec<f64>>());
+    frame.insert_channel("ch1", (start..start + len).map(|n| -(n as f64)).collect::<Vec<f64>>());
+    frame.metadata.insert("sample_rate", 48000.0);
+    frame
+}
+
+#[tokio::test]
+async fn test_parquet_capture_with_row_groups() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("capture.parquet");
+
+    let mut sink = CaptureSinkNode::default();
+    sink.on_create(serde_json::json!({
+        "path": path.to_str().unwrap(),
+        "chunk_rows": 100,
+        "compression": "zstd"
+    })).await.unwrap();
+
+    for i in 0..5 {
+        sink.process(capture_frame(i, i as usize * 64, 64)).await.unwrap();
+    }
+    sink.on_destroy().await.unwrap();
+
+    let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+    let metadata = reader.metadata();
+    assert_eq!(metadata.file_metadata().num_rows(), 320);
+    // 128, 128 and the remaining 64 rows
+    assert_eq!(metadata.num_row_groups(), 3);
+
+    let kv = metadata.file_metadata().key_value_metadata().unwrap();
+    assert!(kv.iter().any(|k| k.key == "sample_rate" && k.value.as_deref() == Some("48000")));
+    assert!(kv.iter().any(|k| k.key == "channels" && k.value.as_deref() == Some("ch0,ch1")));
+
+    let rows: Vec<_> = reader.get_row_iter(None).unwrap().map(|r| r.unwrap()).collect();
+    let last = &rows[319];
+    assert_eq!(last.get_long(2).unwrap(), 319);
+    assert_eq!(last.get_double(3).unwrap(), 319.0);
+    assert_eq!(last.get_double(4).unwrap(), -319.0);
+}
+
+#[tokio::test]
+async fn test_clashing_column_names_are_suffixed() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("clash.parquet");
+
+    let mut sink = CaptureSinkNode::default();
+    sink.on_create(serde_json::json!({"path": path.to_str().unwrap()})).await.unwrap();
+    let mut frame = DataFrame::new(0, 0);
+    frame.insert_channel("mic-1", vec![1.0, 2.0]);
+    frame.insert_channel("mic_1", vec![3.0, 4.0]);
+    frame.insert_channel("timestamp", vec![5.0, 6.0]);
+    sink.process(frame).await.unwrap();
+    sink.on_destroy().await.unwrap();
+
+    let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+    let schema = reader.metadata().file_metadata().schema_descr();
+    let columns: Vec<&str> = schema.columns().iter().map(|c| c.name()).collect();
+    assert_eq!(columns, ["sequence_id", "timestamp", "sample_index", "mic_1", "mic_1_2", "timestamp_2"]);
+
+    let rows: Vec<_> = reader.get_row_iter(None).unwrap().map(|r| r.unwrap()).collect();
+    assert_eq!(rows[1].get_double(3).unwrap(), 2.0);
+    assert_eq!(rows[1].get_double(4).unwrap(), 4.0);
+    assert_eq!(rows[1].get_double(5).unwrap(), 6.0);
+}
+
+#[tokio::test]
+async fn test_capture_rejects_unsupported_options() {
+    let mut sink = CaptureSinkNode::default();
+    assert!(sink.on_create(serde_json::json!({"format": "hdf5"})).await.is_err());
+    assert!(sink.on_create(serde_json::json!({"compression": "lz4_raw_x"})).await.is_err());
+}
+
+#[tokio::test]
+async fn test_crashed_capture_is_recovered_to_its_last_row_group() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("capture.parquet");
+
+    let mut sink = CaptureSinkNode::default();
+    sink.on_create(serde_json::json!({
+        "path": path.to_str().unwrap(),
+        "chunk_rows": 100
+    })).await.unwrap();
+    for i in 0..5 {
+        sink.process(capture_frame(i, i as usize * 64, 64)).await.unwrap();
+    }
+    // Two row groups of 128 rows are on disk, 64 rows only in memory
+    assert!(sidecar_path(&path).exists());
+    std::mem::forget(sink);
+    File::options().append(true).open(&path).unwrap().write_all(b"torn page").unwrap();
+    assert!(SerializedFileReader::new(File::open(&path).unwrap()).is_err());
+
+    let recovered = recover_file(&path).unwrap().unwrap();
+    assert!(recovered.discarded_bytes >= 9);
+
+    let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+    assert_eq!(reader.metadata().file_metadata().num_rows(), 256);
+    let kv = reader.metadata().file_metadata().key_value_metadata().unwrap();
+    assert!(kv.iter().any(|k| k.key == "channels" && k.value.as_deref() == Some("ch0,ch1")));
+    let rows: Vec<_> = reader.get_row_iter(None).unwrap().map(|r| r.unwrap()).collect();
+    assert_eq!(rows[255].get_double(3).unwrap(), 255.0);
+}
+
+#[tokio::test]
+async fn test_capture_rotates_segments() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("capture.parquet");
+
+    let mut sink = CaptureSinkNode::default();
+    sink.on_create(serde_json::json!({
+        "path": path.to_str().unwrap(),
+        "segment_ms": 20
+    })).await.unwrap();
+    for i in 0..2 {
+        sink.process(capture_frame(i, i as usize * 64, 64)).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+    }
+    sink.on_destroy().await.unwrap();
+
+    for (segment, first_sample) in [(1, 0), (2, 64)] {
+        let segment_path = dir.path().join(format!("capture.{:04}.parquet", segment));
+        assert!(!sidecar_path(&segment_path).exists());
+        let reader = SerializedFileReader::new(File::open(&segment_path).unwrap()).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 64);
+        let kv = reader.metadata().file_metadata().key_value_metadata().unwrap();
+        let first = first_sample.to_string();
+        assert!(kv.iter().any(|k| k.key == "first_sample_index" && k.value.as_deref() == Some(first.as_str())));
+        let rows: Vec<_> = reader.get_row_iter(None).unwrap().map(|r| r.unwrap()).collect();
+        assert_eq!(rows[0].get_long(2).unwrap(), first_sample);
+    }
+    assert!(!path.exists());
+}
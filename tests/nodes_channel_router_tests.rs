@@ -0,0 +1,103 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::engine::AsyncPipeline;
+use audiotab::nodes::ChannelRouterNode;
+use async_trait::async_trait;
+use serde_json::json;
+use tokio::sync::mpsc;
+
+/// Frame of `count` channels, channel `n` filled with `n`
+fn interface_frame(count: usize) -> DataFrame {
+    let mut frame = DataFrame::new(0, 0);
+    for ch in 0..count {
+        frame.insert_channel(format!("ch{}", ch), vec![ch as f64; 4]);
+    }
+    frame
+}
+
+/// Sink forwarding every frame it receives
+struct ForwardSink(mpsc::UnboundedSender<DataFrame>);
+
+#[async_trait]
+impl ProcessingNode for ForwardSink {
+    async fn process(&mut self, input: DataFrame) -> anyhow::Result<DataFrame> {
+        let _ = self.0.send(input.clone());
+        Ok(input)
+    }
+}
+
+fn names(frame: &DataFrame) -> Vec<&str> {
+    let mut names: Vec<&str> = frame.payload.keys().map(|k| k.as_str()).collect();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn test_picks_and_renames_a_channel() {
+    let mut node = ChannelRouterNode::default();
+    node.on_create(json!({"routes": "ch3 -> reference"})).await.unwrap();
+
+    let out = node.process(interface_frame(8)).await.unwrap();
+    assert_eq!(names(&out), ["reference"]);
+    assert_eq!(out.payload["reference"].samples(), &[3.0; 4]);
+}
+
+#[tokio::test]
+async fn test_duplicates_and_swaps_channels() {
+    let mut node = ChannelRouterNode::default();
+    node.on_create(json!({"routes": ["ch1 -> ch0", "ch0 -> ch1", {"from": "ch0", "to": "mono"}]}))
+        .await
+        .unwrap();
+
+    let out = node.process(interface_frame(2)).await.unwrap();
+    assert_eq!(names(&out), ["ch0", "ch1", "mono"]);
+    assert_eq!(out.payload["ch0"].samples(), &[1.0; 4]);
+    assert_eq!(out.payload["ch1"].samples(), &[0.0; 4]);
+    assert_eq!(out.payload["mono"].samples(), &[0.0; 4]);
+}
+
+#[tokio::test]
+async fn test_keep_unrouted_passes_other_channels_through() {
+    let mut node = ChannelRouterNode::default();
+    node.on_create(json!({"routes": "ch2 -> reference, ch0", "keep_unrouted": true}))
+        .await
+        .unwrap();
+
+    let out = node.process(interface_frame(4)).await.unwrap();
+    assert_eq!(names(&out), ["ch0", "ch1", "ch3", "reference"]);
+    assert_eq!(out.payload["reference"].samples(), &[2.0; 4]);
+}
+
+#[tokio::test]
+async fn test_invalid_tables_and_missing_sources_are_errors() {
+    for routes in [json!(""), json!("ch0 -> a, ch1 -> a"), json!("ch0 ->")] {
+        let mut node = ChannelRouterNode::default();
+        assert!(node.on_create(json!({ "routes": routes })).await.is_err(), "{}", routes);
+    }
+
+    let mut node = ChannelRouterNode::default();
+    node.on_create(json!({"routes": "ch5"})).await.unwrap();
+    let err = node.process(interface_frame(2)).await.unwrap_err();
+    assert!(err.to_string().contains("ch5"));
+}
+
+#[tokio::test]
+async fn test_router_selects_channels_inside_a_graph() {
+    let mut pipeline = AsyncPipeline::from_json(json!({
+        "nodes": [
+            {"id": "router", "type": "ChannelRouter", "config": {"routes": "ch1 -> left"}},
+            {"id": "sink", "type": "Print", "config": {}}
+        ],
+        "connections": [{"from": "router", "to": "sink"}]
+    }))
+    .await
+    .unwrap();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    pipeline.nodes_mut().insert("sink".to_string(), Box::new(ForwardSink(tx)));
+    pipeline.start().await.unwrap();
+
+    pipeline.trigger(interface_frame(2)).await.unwrap();
+    let out = rx.recv().await.unwrap();
+    assert_eq!(names(&out), ["left"]);
+
+    pipeline.stop().await.unwrap();
+}
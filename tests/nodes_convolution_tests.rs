@@ -0,0 +1,101 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::hal::drivers::{Recording, RecordingFormat};
+use audiotab::hal::SampleData;
+use audiotab::nodes::ConvolutionNode;
+use serde_json::json;
+
+fn frame(seq: u64, samples: Vec<f64>, sample_rate: f64) -> DataFrame {
+    let mut frame = DataFrame::new(seq * 10_000_000, seq);
+    frame.insert_channel("ch0", samples);
+    frame.metadata.insert("sample_rate", sample_rate);
+    frame
+}
+
+/// Feed `input` in frames of `block` samples and collect channel ch0
+async fn run(node: &mut ConvolutionNode, input: &[f64], block: usize, sample_rate: f64) -> Vec<f64> {
+    let mut output = Vec::new();
+    for (seq, chunk) in input.chunks(block).enumerate() {
+        let out = node.process(frame(seq as u64, chunk.to_vec(), sample_rate)).await.unwrap();
+        output.extend_from_slice(out.payload["ch0"].samples());
+    }
+    output
+}
+
+fn impulse(len: usize) -> Vec<f64> {
+    let mut x = vec![0.0; len];
+    x[0] = 1.0;
+    x
+}
+
+#[tokio::test]
+async fn test_inline_coefficients_continue_across_frames() {
+    let mut node = ConvolutionNode::default();
+    node.on_create(json!({"coefficients": [0.5, 0.25, -1.0], "gain": 2.0})).await.unwrap();
+    assert_eq!(node.impulse_response(), &[1.0, 0.5, -2.0]);
+
+    let input = [1.0, 0.0, 0.0, 2.0, 1.0];
+    let output = run(&mut node, &input, 2, 48000.0).await;
+    assert_eq!(output, vec![1.0, 0.5, -2.0, 2.0, 2.0]);
+}
+
+#[tokio::test]
+async fn test_long_wav_impulse_response() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("room.wav");
+    // Exponentially decaying alternating response, longer than many partitions
+    let ir: Vec<f64> = (0..3000)
+        .map(|n| 0.9 * (-(n as f64) / 600.0).exp() * if n % 2 == 0 { 1.0 } else { -0.5 })
+        .collect();
+    let recording = Recording { sample_rate: 8000, num_channels: 1, data: SampleData::F64(ir.clone()) };
+    recording.save(&path, RecordingFormat::Wav).unwrap();
+
+    let mut node = ConvolutionNode::default();
+    node.on_create(json!({"ir_path": path.to_str().unwrap(), "partition_size": 64})).await.unwrap();
+    assert_eq!(node.impulse_response().len(), 3000);
+
+    let output = run(&mut node, &impulse(4000), 500, 8000.0).await;
+    for (n, (y, h)) in output.iter().zip(ir.iter().chain(std::iter::repeat(&0.0))).enumerate() {
+        // The WAV stores 32-bit floats
+        assert!((y - h).abs() < 1e-6, "sample {}: {} != {}", n, y, h);
+    }
+
+    // A response recorded at another rate does not apply
+    let mut node = ConvolutionNode::default();
+    node.on_create(json!({"ir_path": path.to_str().unwrap()})).await.unwrap();
+    assert!(node.process(frame(0, vec![0.0; 8], 48000.0)).await.is_err());
+}
+
+#[tokio::test]
+async fn test_coefficient_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("fir.txt");
+    std::fs::write(&path, "# 3-tap smoother\n0.25, 0.5\n0.25 # last tap\n").unwrap();
+
+    let mut node = ConvolutionNode::default();
+    node.on_create(json!({"ir_path": path.to_str().unwrap()})).await.unwrap();
+    assert_eq!(node.impulse_response(), &[0.25, 0.5, 0.25]);
+    let output = run(&mut node, &[4.0, 4.0, 4.0, 4.0], 4, 44100.0).await;
+    assert_eq!(output, vec![1.0, 3.0, 4.0, 4.0]);
+}
+
+#[tokio::test]
+async fn test_invalid_configurations() {
+    let dir = tempfile::tempdir().unwrap();
+    let bad = dir.path().join("bad.txt");
+    std::fs::write(&bad, "0.5, x").unwrap();
+
+    for config in [
+        json!({}),
+        json!({"coefficients": "1.0", "ir_path": "fir.txt"}),
+        json!({"ir_path": dir.path().join("missing.wav").to_str().unwrap()}),
+        json!({"ir_path": bad.to_str().unwrap()}),
+        json!({"coefficients": "  "}),
+    ] {
+        let mut node = ConvolutionNode::default();
+        assert!(node.on_create(config.clone()).await.is_err(), "{}", config);
+    }
+
+    let mut node = ConvolutionNode::default();
+    node.on_create(json!({"coefficients": "1.0", "channels": ["ch1"]})).await.unwrap();
+    assert!(node.process(frame(0, vec![0.0; 4], 48000.0)).await.is_err());
+}
@@ -0,0 +1,172 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::engine::AsyncPipeline;
+use audiotab::nodes::CrossCorrelationNode;
+use async_trait::async_trait;
+use serde_json::json;
+use tokio::sync::mpsc;
+
+/// Deterministic pseudo-random noise (xorshift)
+fn noise(len: usize, seed: u64) -> Vec<f64> {
+    let mut state = seed;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 2000) as f64 / 1000.0 - 1.0
+        })
+        .collect()
+}
+
+fn delayed_pair(len: usize, delay: usize) -> DataFrame {
+    let source = noise(len + delay, 42);
+    let reference = source[delay..].to_vec();
+    let measurement = source[..len].to_vec();
+    let mut frame = DataFrame::new(0, 0);
+    frame.insert_channel("ch0", reference);
+    frame.insert_channel("ch1", measurement);
+    frame.metadata.insert("sample_rate", 48000.0);
+    frame
+}
+
+/// The channels of `delayed_pair` as `ch0` frames from two separate sources
+fn port_frames(len: usize, delay: usize, sequence_id: u64) -> (DataFrame, DataFrame) {
+    let pair = delayed_pair(len, delay);
+    let on_port = |name: &str, port: &str| {
+        let mut frame = DataFrame::new(0, sequence_id);
+        frame.insert_channel("ch0", pair.payload[name].clone());
+        frame.metadata.insert("sample_rate", 48000.0);
+        frame.metadata.insert("input_port", port);
+        frame
+    };
+    (on_port("ch0", "_reference"), on_port("ch1", "_measurement"))
+}
+
+#[tokio::test]
+async fn test_gcc_phat_finds_delay() {
+    let mut xcorr = CrossCorrelationNode::default();
+    xcorr.on_create(serde_json::json!({
+        "window_size": 4096,
+        "weighting": "phat",
+        "max_lag_ms": 10.0
+    })).await.unwrap();
+
+    let result = xcorr.process(delayed_pair(4096, 48)).await.unwrap();
+
+    let lag = result.payload.get("lag_samples").unwrap()[0];
+    let delay_ms = result.payload.get("delay_ms").unwrap()[0];
+    assert!((lag - 48.0).abs() < 0.5, "lag = {}", lag);
+    assert!((delay_ms - 1.0).abs() < 0.02);
+    assert!(result.payload.get("peak").unwrap()[0] > 0.5);
+    // 10 ms at 48 kHz = 480 lags each side
+    assert_eq!(result.payload.get("correlation").unwrap().len(), 961);
+}
+
+#[tokio::test]
+async fn test_plain_correlation_negative_lag() {
+    let mut xcorr = CrossCorrelationNode::default();
+    xcorr.on_create(serde_json::json!({
+        "window_size": 2048,
+        "weighting": "none",
+        "reference_channel": "ch1",
+        "measurement_channel": "ch0"
+    })).await.unwrap();
+
+    let result = xcorr.process(delayed_pair(2048, 20)).await.unwrap();
+    let lag = result.payload.get("lag_samples").unwrap()[0];
+    let peak = result.payload.get("peak").unwrap()[0];
+    assert!((lag + 20.0).abs() < 0.5, "lag = {}", lag);
+    assert!(peak > 0.9 && peak <= 1.0, "peak = {}", peak);
+}
+
+#[tokio::test]
+async fn test_buffers_until_window_complete() {
+    let mut xcorr = CrossCorrelationNode::default();
+    xcorr.on_create(serde_json::json!({"window_size": 4096})).await.unwrap();
+
+    let result = xcorr.process(delayed_pair(1024, 0)).await.unwrap();
+    assert!(result.payload.is_empty());
+
+    let mut missing = DataFrame::new(0, 1);
+    missing.insert_channel("ch0", vec![0.0; 16]);
+    assert!(xcorr.process(missing).await.is_err());
+}
+
+#[tokio::test]
+async fn test_rejects_non_finite_samples() {
+    let mut xcorr = CrossCorrelationNode::default();
+    xcorr.on_create(serde_json::json!({"window_size": 4096, "max_lag_ms": 10.0})).await.unwrap();
+
+    let mut poisoned = delayed_pair(4096, 48);
+    let mut samples = poisoned.payload["ch1"].samples().to_vec();
+    samples[100] = f64::NAN;
+    poisoned.insert_channel("ch1", samples);
+    assert!(xcorr.process(poisoned).await.is_err());
+
+    // The rejected frame left nothing behind
+    let result = xcorr.process(delayed_pair(4096, 48)).await.unwrap();
+    let lag = result.payload.get("lag_samples").unwrap()[0];
+    assert!((lag - 48.0).abs() < 0.5, "lag = {}", lag);
+}
+
+#[tokio::test]
+async fn test_ports_from_separate_sources_are_paired() {
+    let mut xcorr = CrossCorrelationNode::default();
+    xcorr.on_create(json!({"window_size": 4096, "max_lag_ms": 10.0})).await.unwrap();
+
+    // A reference whose measurement was lost upstream is skipped
+    let (stale, _) = port_frames(4096, 48, 0);
+    assert!(xcorr.process(stale).await.unwrap().payload.is_empty());
+
+    let (reference, measurement) = port_frames(4096, 48, 1);
+    assert!(xcorr.process(measurement).await.unwrap().payload.is_empty());
+    let result = xcorr.process(reference).await.unwrap();
+    let lag = result.payload.get("lag_samples").unwrap()[0];
+    assert!((lag - 48.0).abs() < 0.5, "lag = {}", lag);
+}
+
+/// Sink forwarding every frame with a correlation result
+struct ForwardSink(mpsc::UnboundedSender<DataFrame>);
+
+#[async_trait]
+impl ProcessingNode for ForwardSink {
+    async fn process(&mut self, input: DataFrame) -> anyhow::Result<DataFrame> {
+        if input.payload.contains_key("lag_samples") {
+            let _ = self.0.send(input.clone());
+        }
+        Ok(input)
+    }
+}
+
+#[tokio::test]
+async fn test_ports_wired_to_separate_branches() {
+    let mut pipeline = AsyncPipeline::from_json(json!({
+        "nodes": [
+            {"id": "src", "type": "Gain", "config": {}},
+            {"id": "reference", "type": "ChannelRouter", "config": {"routes": "ch0"}},
+            {"id": "measurement", "type": "ChannelRouter", "config": {"routes": "ch1 -> ch0"}},
+            {"id": "xcorr", "type": "CrossCorrelationNode", "config": {"window_size": 4096, "max_lag_ms": 10.0}},
+            {"id": "sink", "type": "Print", "config": {}}
+        ],
+        "connections": [
+            {"from": "src", "to": "reference"},
+            {"from": "src", "to": "measurement"},
+            {"from": "reference", "to": "xcorr", "to_port": "_reference"},
+            {"from": "measurement", "to": "xcorr", "to_port": "_measurement"},
+            {"from": "xcorr", "to": "sink"}
+        ]
+    }))
+    .await
+    .unwrap();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    pipeline.nodes_mut().insert("sink".to_string(), Box::new(ForwardSink(tx)));
+    pipeline.start().await.unwrap();
+
+    pipeline.trigger(delayed_pair(4096, 48)).await.unwrap();
+
+    let result = rx.recv().await.unwrap();
+    let lag = result.payload.get("lag_samples").unwrap()[0];
+    assert!((lag - 48.0).abs() < 0.5, "lag = {}", lag);
+
+    pipeline.stop().await.unwrap();
+}
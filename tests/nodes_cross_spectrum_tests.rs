@@ -0,0 +1,142 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::engine::AsyncPipeline;
+use audiotab::nodes::CrossSpectrumNode;
+use async_trait::async_trait;
+use serde_json::json;
+use std::f64::consts::PI;
+use tokio::sync::mpsc;
+
+const SAMPLE_RATE: f64 = 48000.0;
+
+/// Deterministic white noise in [-1, 1)
+fn noise(seed: u64, len: usize) -> Vec<f64> {
+    let mut state = seed;
+    (0..len)
+        .map(|_| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 11) as f64 / (1u64 << 52) as f64 - 1.0
+        })
+        .collect()
+}
+
+fn frame(a: Vec<f64>, b: Vec<f64>) -> DataFrame {
+    let mut frame = DataFrame::new(0, 0);
+    frame.metadata.insert("sample_rate", SAMPLE_RATE);
+    frame.insert_channel("ch0", a);
+    frame.insert_channel("ch1", b);
+    frame
+}
+
+async fn node(config: serde_json::Value) -> CrossSpectrumNode {
+    let mut node = CrossSpectrumNode::default();
+    node.on_create(config).await.unwrap();
+    node
+}
+
+#[tokio::test]
+async fn test_delayed_copy_is_coherent_with_linear_phase() {
+    let mut node = node(serde_json::json!({"fft_size": 256, "hop_size": 128})).await;
+
+    // B is A delayed by 4 samples
+    let a = noise(1, 4096);
+    let b: Vec<f64> = std::iter::repeat_n(0.0, 4).chain(a[..4092].iter().copied()).collect();
+    let result = node.process(frame(a, b)).await.unwrap();
+
+    assert_eq!(result.metadata.get_i64("averages"), Some(16));
+    let coherence = result.payload.get("coherence").unwrap();
+    let phase = result.payload.get("cross_phase").unwrap();
+    assert_eq!(coherence.len(), 129);
+
+    // Phase of a 4 sample delay at bin k is 2*pi*k*4/256
+    for k in 1..16 {
+        assert!(coherence[k] > 0.9, "bin {} coherence {}", k, coherence[k]);
+        let expected = 2.0 * PI * k as f64 * 4.0 / 256.0;
+        assert!((phase[k] - expected).abs() < 0.05, "bin {} phase {}", k, phase[k]);
+    }
+}
+
+#[tokio::test]
+async fn test_independent_noise_has_low_coherence() {
+    let mut node = node(serde_json::json!({"fft_size": 128, "hop_size": 64, "num_averages": 64})).await;
+
+    let result = node.process(frame(noise(1, 8192), noise(2, 8192))).await.unwrap();
+    let coherence = result.payload.get("coherence").unwrap();
+    let mean = coherence.iter().sum::<f64>() / coherence.len() as f64;
+    assert!(mean < 0.1, "mean coherence {}", mean);
+}
+
+#[tokio::test]
+async fn test_auto_spectrum_density_and_partial_frames() {
+    let mut node = node(serde_json::json!({"fft_size": 1024, "hop_size": 512, "window_type": "rectangular"})).await;
+
+    // Too short for a segment: nothing is emitted yet
+    let first = node.process(frame(noise(3, 600), noise(4, 600))).await.unwrap();
+    assert!(first.payload.is_empty());
+
+    // White noise uniform in [-1, 1) has variance 1/3, spread over fs/2
+    let result = node.process(frame(noise(5, 48000), noise(6, 48000))).await.unwrap();
+    let auto_a = result.payload.get("auto_a").unwrap();
+    let mean = auto_a[1..512].iter().sum::<f64>() / 511.0;
+    let expected = (1.0 / 3.0) / (SAMPLE_RATE / 2.0);
+    assert!((mean / expected - 1.0).abs() < 0.1, "density {} vs {}", mean, expected);
+    assert_eq!(result.metadata.get_f64("bin_resolution_hz"), Some(SAMPLE_RATE / 1024.0));
+}
+
+#[tokio::test]
+async fn test_missing_channel_and_invalid_config() {
+    let mut node = node(serde_json::json!({"channel_b": "mic"})).await;
+    assert!(node.process(frame(vec![0.0; 16], vec![0.0; 16])).await.is_err());
+
+    let mut node = CrossSpectrumNode::default();
+    assert!(node.on_create(serde_json::json!({"hop_size": 0})).await.is_err());
+    assert!(node.on_create(serde_json::json!({"window_type": "kaiser"})).await.is_err());
+}
+
+/// Sink forwarding every frame with spectra
+struct ForwardSink(mpsc::UnboundedSender<DataFrame>);
+
+#[async_trait]
+impl ProcessingNode for ForwardSink {
+    async fn process(&mut self, input: DataFrame) -> anyhow::Result<DataFrame> {
+        if input.payload.contains_key("coherence") {
+            let _ = self.0.send(input.clone());
+        }
+        Ok(input)
+    }
+}
+
+#[tokio::test]
+async fn test_inputs_wired_to_separate_branches() {
+    let mut pipeline = AsyncPipeline::from_json(json!({
+        "nodes": [
+            {"id": "src", "type": "Gain", "config": {}},
+            {"id": "a", "type": "ChannelRouter", "config": {"routes": "ch0"}},
+            {"id": "b", "type": "ChannelRouter", "config": {"routes": "ch1 -> ch0"}},
+            {"id": "spectrum", "type": "CrossSpectrumNode", "config": {"fft_size": 256, "hop_size": 128}},
+            {"id": "sink", "type": "Print", "config": {}}
+        ],
+        "connections": [
+            {"from": "src", "to": "a"},
+            {"from": "src", "to": "b"},
+            {"from": "a", "to": "spectrum", "to_port": "_input_a"},
+            {"from": "b", "to": "spectrum", "to_port": "_input_b"},
+            {"from": "spectrum", "to": "sink"}
+        ]
+    }))
+    .await
+    .unwrap();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    pipeline.nodes_mut().insert("sink".to_string(), Box::new(ForwardSink(tx)));
+    pipeline.start().await.unwrap();
+
+    // A copy of the same noise on both inputs is fully coherent
+    let a = noise(1, 4096);
+    pipeline.trigger(frame(a.clone(), a)).await.unwrap();
+
+    let result = rx.recv().await.unwrap();
+    let coherence = result.payload.get("coherence").unwrap();
+    assert!(coherence[1..64].iter().all(|&c| c > 0.99), "coherence {:?}", &coherence[1..64]);
+    assert_eq!(result.metadata.get_i64("averages"), Some(16));
+
+    pipeline.stop().await.unwrap();
+}
@@ -0,0 +1,139 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::engine::AsyncPipeline;
+use audiotab::nodes::DataExportNode;
+use tempfile::tempdir;
+
+fn level_frame(sequence_id: u64, spl: f64) -> DataFrame {
+    let mut frame = DataFrame::new(sequence_id * 100, sequence_id);
+    frame.insert_channel("spl", vec![spl]);
+    frame.insert_channel("bands", vec![1.0, 2.0]);
+    frame.metadata.insert("frequency_weighting", "A");
+    frame
+}
+
+#[tokio::test]
+async fn test_csv_export_with_header() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("levels.csv");
+
+    let mut export = DataExportNode::default();
+    export.on_create(serde_json::json!({"path": path.to_str().unwrap()})).await.unwrap();
+
+    export.process(level_frame(0, 94.0)).await.unwrap();
+    let mut partial = level_frame(1, 80.5);
+    partial.payload.remove("bands");
+    let passed = export.process(partial).await.unwrap();
+    assert_eq!(passed.payload.get("spl").unwrap()[0], 80.5);
+    export.on_destroy().await.unwrap();
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = contents.lines().collect();
+    assert_eq!(lines, vec![
+        "timestamp,sequence_id,bands_0,bands_1,spl",
+        "0,0,1,2,94",
+        "100,1,,,80.5",
+    ]);
+}
+
+#[tokio::test]
+async fn test_jsonl_export_inferred_from_extension() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("results").join("levels.jsonl");
+
+    let mut export = DataExportNode::default();
+    export.on_create(serde_json::json!({
+        "path": path.to_str().unwrap(),
+        "flush_interval_ms": 0
+    })).await.unwrap();
+    export.process(level_frame(3, 70.0)).await.unwrap();
+
+    // Flushed immediately with a zero interval
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let record: serde_json::Value = serde_json::from_str(contents.lines().next().unwrap()).unwrap();
+    assert_eq!(record["sequence_id"], 3);
+    assert_eq!(record["values"]["spl"], 70.0);
+    assert_eq!(record["values"]["bands"], serde_json::json!([1.0, 2.0]));
+    assert_eq!(record["metadata"]["frequency_weighting"], "A");
+}
+
+#[tokio::test]
+async fn test_export_invalid_format() {
+    let dir = tempdir().unwrap();
+    let mut export = DataExportNode::default();
+    let result = export.on_create(serde_json::json!({
+        "path": dir.path().join("out.txt").to_str().unwrap(),
+        "format": "xlsx"
+    })).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_pipeline_stop_flushes_export() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("run.csv");
+
+    let config = serde_json::json!({
+        "nodes": [
+            {"id": "export", "type": "DataExportNode", "config": {
+                "path": path.to_str().unwrap(),
+                "flush_interval_ms": 60000
+            }}
+        ],
+        "connections": []
+    });
+    let mut pipeline = AsyncPipeline::from_json(config).await.unwrap();
+    pipeline.start().await.unwrap();
+    for i in 0..3 {
+        pipeline.trigger(level_frame(i, 90.0 + i as f64)).await.unwrap();
+    }
+
+    // Nothing has been flushed yet; stopping must flush and close the file
+    pipeline.stop().await.unwrap();
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = contents.lines().collect();
+    assert_eq!(lines, vec![
+        "timestamp,sequence_id,bands_0,bands_1,spl",
+        "0,0,1,2,90",
+        "100,1,1,2,91",
+        "200,2,1,2,92",
+    ]);
+}
+
+#[tokio::test]
+async fn test_csv_fields_are_quoted() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("quoted.csv");
+
+    let mut export = DataExportNode::default();
+    export.on_create(serde_json::json!({"path": path.to_str().unwrap()})).await.unwrap();
+    let mut frame = DataFrame::new(0, 0);
+    frame.insert_channel("level, \"A\"", vec![94.0]);
+    frame.insert_channel("line\nbreak", vec![1.5]);
+    export.process(frame).await.unwrap();
+    export.on_destroy().await.unwrap();
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(contents, "timestamp,sequence_id,\"level, \"\"A\"\"\",\"line\nbreak\"\n0,0,94,1.5\n");
+}
+
+#[tokio::test]
+async fn test_append_writes_header_once() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("runs.csv");
+
+    for run in 0..2 {
+        let mut export = DataExportNode::default();
+        export.on_create(serde_json::json!({"path": path.to_str().unwrap(), "append": true})).await.unwrap();
+        export.process(level_frame(run, 80.0)).await.unwrap();
+        export.on_destroy().await.unwrap();
+    }
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = contents.lines().collect();
+    assert_eq!(lines, vec![
+        "timestamp,sequence_id,bands_0,bands_1,spl",
+        "0,0,1,2,80",
+        "100,1,1,2,80",
+    ]);
+}
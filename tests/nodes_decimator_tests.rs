@@ -0,0 +1,80 @@
+use audiotab::core::{Channel, ChannelRole, DataFrame, FrameDecimator, ProcessingNode};
+use audiotab::nodes::DecimatorNode;
+
+fn sine(freq: f64, sample_rate: f64, start: usize, len: usize) -> Vec<f64> {
+    (start..start + len)
+        .map(|i| (2.0 * std::f64::consts::PI * freq * i as f64 / sample_rate).sin())
+        .collect()
+}
+
+fn frame_from(sequence_id: u64, samples: Vec<f64>) -> DataFrame {
+    let mut frame = DataFrame::new(0, sequence_id);
+    frame.insert_channel("ch0", samples);
+    frame.metadata.insert("sample_rate", 48000.0);
+    frame
+}
+
+fn rms(samples: &[f64]) -> f64 {
+    (samples.iter().map(|s| s * s).sum::<f64>() / samples.len() as f64).sqrt()
+}
+
+#[tokio::test]
+async fn test_decimates_across_frames() {
+    let mut node = DecimatorNode::default();
+    node.on_create(serde_json::json!({"factor": 4, "anti_alias": false})).await.unwrap();
+
+    let first = node.process(frame_from(0, (0..6).map(|i| i as f64).collect())).await.unwrap();
+    assert_eq!(first.payload.get("ch0").unwrap().as_ref(), &vec![0.0, 4.0]);
+    assert_eq!(first.metadata.get_f64("sample_rate"), Some(12000.0));
+
+    // The phase carries over: next kept sample is index 8
+    let second = node.process(frame_from(1, (6..12).map(|i| i as f64).collect())).await.unwrap();
+    assert_eq!(second.payload.get("ch0").unwrap().as_ref(), &vec![8.0]);
+}
+
+#[tokio::test]
+async fn test_anti_alias_filter_passes_band_and_rejects_aliases() {
+    let mut node = DecimatorNode::default();
+    node.on_create(serde_json::json!({"factor": 48})).await.unwrap();
+
+    // 100 Hz is well inside the 1 kHz output band
+    let passed = node.process(frame_from(0, sine(100.0, 48000.0, 0, 48000))).await.unwrap();
+    let out = passed.payload.get("ch0").unwrap();
+    assert_eq!(out.len(), 1000);
+    assert!((rms(&out[100..]) - 0.5f64.sqrt()).abs() < 0.02, "passband rms {}", rms(&out[100..]));
+
+    // 10 kHz would alias to DC-ish frequencies without filtering
+    let mut node = DecimatorNode::default();
+    node.on_create(serde_json::json!({"factor": 48})).await.unwrap();
+    let rejected = node.process(frame_from(0, sine(10000.0, 48000.0, 0, 48000))).await.unwrap();
+    let out = rejected.payload.get("ch0").unwrap();
+    assert!(rms(&out[100..]) < 0.001, "stopband rms {}", rms(&out[100..]));
+}
+
+#[test]
+fn test_non_signal_channels_pass_through() {
+    let mut decimator = FrameDecimator::new(4, true);
+    let spectrum: Vec<f64> = (0..9).map(|k| k as f64).collect();
+
+    // A spectrum-only frame keeps its bins and sample rate
+    let mut fft = DataFrame::new(0, 0);
+    fft.insert_channel("ch0", Channel::new(spectrum.clone()).with_role(ChannelRole::Spectrum));
+    fft.metadata.insert("sample_rate", 48000.0);
+    let out = decimator.push(fft);
+    assert_eq!(out.payload["ch0"].samples(), spectrum.as_slice());
+    assert_eq!(out.metadata.get_f64("sample_rate"), Some(48000.0));
+
+    // Alongside a signal, only the signal is decimated
+    let mut mixed = frame_from(1, vec![1.0; 16]);
+    mixed.insert_channel("level", Channel::scalar(94.0));
+    let out = decimator.push(mixed);
+    assert_eq!(out.payload["ch0"].len(), 4);
+    assert_eq!(out.payload["level"].samples(), &[94.0]);
+    assert_eq!(out.metadata.get_f64("sample_rate"), Some(12000.0));
+}
+
+#[tokio::test]
+async fn test_rejects_zero_factor() {
+    let mut node = DecimatorNode::default();
+    assert!(node.on_create(serde_json::json!({"factor": 0})).await.is_err());
+}
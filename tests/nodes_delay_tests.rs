@@ -0,0 +1,63 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::nodes::DelayNode;
+
+fn ramp_frame(sequence_id: u64, start: usize, len: usize) -> DataFrame {
+    let samples: Vec<f64> = (start..start + len).map(|n| n as f64).collect();
+    let mut frame = DataFrame::new(0, sequence_id);
+    frame.insert_channel("ref", samples.clone());
+    frame.insert_channel("mic", samples);
+    frame.metadata.insert("sample_rate", 1000.0);
+    frame
+}
+
+#[tokio::test]
+async fn test_delay_across_frames() {
+    let mut delay = DelayNode::default();
+    delay.on_create(serde_json::json!({"delay_samples": 3.0})).await.unwrap();
+
+    let first = delay.process(ramp_frame(0, 1, 4)).await.unwrap();
+    assert_eq!(first.payload.get("ref").unwrap().as_ref(), &vec![0.0, 0.0, 0.0, 1.0]);
+
+    let second = delay.process(ramp_frame(1, 5, 4)).await.unwrap();
+    assert_eq!(second.payload.get("ref").unwrap().as_ref(), &vec![2.0, 3.0, 4.0, 5.0]);
+}
+
+#[tokio::test]
+async fn test_fractional_delay_ms_on_selected_channel() {
+    let mut delay = DelayNode::default();
+    // 2.5 ms at 1 kHz is 2.5 samples
+    delay.on_create(serde_json::json!({
+        "delay_ms": 2.5,
+        "channels": ["ref"]
+    })).await.unwrap();
+
+    let result = delay.process(ramp_frame(0, 0, 10)).await.unwrap();
+    let delayed = result.payload.get("ref").unwrap();
+    assert!((delayed[9] - 6.5).abs() < 1e-12);
+
+    // Unselected channels pass through untouched
+    assert_eq!(result.payload.get("mic").unwrap()[9], 9.0);
+    assert_eq!(result.metadata.get_f64("delay_samples"), Some(2.5));
+}
+
+#[tokio::test]
+async fn test_delay_invalid_config() {
+    let mut delay = DelayNode::default();
+    assert!(delay.on_create(serde_json::json!({"delay_samples": -1.0})).await.is_err());
+    assert!(delay.on_create(serde_json::json!({"delay_samples": 1.0, "interpolation": "sinc"})).await.is_err());
+}
+
+#[tokio::test]
+async fn test_delay_emits_tail_at_end_of_stream() {
+    let mut delay = DelayNode::default();
+    delay.on_create(serde_json::json!({"delay_samples": 3.0, "channels": "ref"})).await.unwrap();
+    assert!(delay.on_eos().await.unwrap().is_none());
+
+    delay.process(ramp_frame(4, 1, 4)).await.unwrap();
+    let tail = delay.on_eos().await.unwrap().unwrap();
+    assert_eq!(tail.sequence_id, 5);
+    assert_eq!(tail.payload.get("ref").unwrap().as_ref(), &vec![2.0, 3.0, 4.0]);
+    assert!(!tail.payload.contains_key("mic"));
+    assert_eq!(tail.metadata["tail"], true);
+    assert_eq!(tail.sample_rate(), Some(1000.0));
+}
@@ -0,0 +1,76 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::nodes::{CompressorNode, LimiterNode};
+use std::f64::consts::PI;
+
+fn sine_frame(len: usize, amplitude: f64) -> DataFrame {
+    let samples: Vec<f64> = (0..len)
+        .map(|n| amplitude * (2.0 * PI * 1000.0 * n as f64 / 48000.0).sin())
+        .collect();
+    let mut frame = DataFrame::new(0, 0);
+    frame.insert_channel("ch0", samples);
+    frame.metadata.insert("sample_rate", 48000.0);
+    frame
+}
+
+fn peak(samples: &[f64]) -> f64 {
+    samples.iter().fold(0.0, |m, s| m.max(s.abs()))
+}
+
+#[tokio::test]
+async fn test_compressor_reduces_level_above_threshold() {
+    let mut comp = CompressorNode::default();
+    comp.on_create(serde_json::json!({
+        "threshold_db": -20.0,
+        "ratio": 4.0,
+        "attack_ms": 0.0,
+        "release_ms": 0.0
+    })).await.unwrap();
+
+    // 0 dBFS peak, 20 dB over threshold at 4:1 -> -15 dBFS
+    let result = comp.process(sine_frame(4800, 1.0)).await.unwrap();
+    let out_peak_db = 20.0 * peak(result.payload.get("ch0").unwrap()).log10();
+    assert!((out_peak_db + 15.0).abs() < 0.1, "peak = {} dB", out_peak_db);
+
+    let reduction = result.metadata.get_f64("gain_reduction_db").unwrap();
+    assert!((reduction - 15.0).abs() < 0.1);
+}
+
+#[tokio::test]
+async fn test_compressor_passes_quiet_signal_with_makeup() {
+    let mut comp = CompressorNode::default();
+    comp.on_create(serde_json::json!({"threshold_db": -20.0, "makeup_gain_db": 6.0})).await.unwrap();
+
+    let result = comp.process(sine_frame(480, 0.01)).await.unwrap();
+    let gain = peak(result.payload.get("ch0").unwrap()) / 0.01;
+    assert!((20.0 * gain.log10() - 6.0).abs() < 0.1);
+
+    assert!(comp.on_create(serde_json::json!({"ratio": 0.5})).await.is_err());
+}
+
+#[tokio::test]
+async fn test_limiter_lookahead_never_overshoots() {
+    let mut limiter = LimiterNode::default();
+    limiter.on_create(serde_json::json!({
+        "threshold_db": -6.0,
+        "lookahead_ms": 2.0,
+        "release_ms": 20.0
+    })).await.unwrap();
+
+    // Quiet signal followed by a sudden full-scale burst
+    let mut samples = vec![0.0; 480];
+    samples.extend(sine_frame(4800, 1.0).payload.get("ch0").unwrap().iter());
+    let mut frame = DataFrame::new(0, 0);
+    frame.insert_channel("ch0", samples);
+    frame.metadata.insert("sample_rate", 48000.0);
+
+    let result = limiter.process(frame).await.unwrap();
+    let out = result.payload.get("ch0").unwrap();
+    let ceiling = 10f64.powf(-6.0 / 20.0);
+    assert!(peak(out) <= ceiling + 1e-9, "peak = {}", peak(out));
+    assert!(peak(&out[2000..]) > ceiling * 0.95);
+    assert_eq!(result.metadata.get_i64("limiter_latency_samples"), Some(96));
+
+    // Output is delayed by the lookahead
+    assert_eq!(out.len(), 5280);
+    assert!(out[..480 + 96].iter().all(|&s| s == 0.0));
+}
@@ -0,0 +1,58 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::nodes::EnvelopeFollowerNode;
+use std::f64::consts::PI;
+
+fn sine_frame(len: usize, amplitude: f64) -> DataFrame {
+    let samples: Vec<f64> = (0..len)
+        .map(|n| amplitude * (2.0 * PI * 1000.0 * n as f64 / 48000.0).sin())
+        .collect();
+    let mut frame = DataFrame::new(0, 0);
+    frame.insert_channel("ch0", samples);
+    frame.metadata.insert("sample_rate", 48000.0);
+    frame
+}
+
+#[tokio::test]
+async fn test_rms_envelope_is_decimated() {
+    let mut env = EnvelopeFollowerNode::default();
+    env.on_create(serde_json::json!({
+        "mode": "rms",
+        "attack_ms": 50.0,
+        "release_ms": 50.0,
+        "output_interval_ms": 10.0
+    })).await.unwrap();
+
+    // 1 s of signal -> 100 envelope values at 100 Hz
+    let result = env.process(sine_frame(48000, 1.0)).await.unwrap();
+    let envelope = result.payload.get("ch0").unwrap();
+    assert_eq!(envelope.len(), 100);
+    assert_eq!(result.sample_rate(), Some(100.0));
+
+    let settled = envelope[99];
+    assert!((settled - 1.0 / 2f64.sqrt()).abs() < 0.05, "rms = {}", settled);
+}
+
+#[tokio::test]
+async fn test_release_is_slower_than_attack() {
+    let mut env = EnvelopeFollowerNode::default();
+    env.on_create(serde_json::json!({
+        "attack_ms": 1.0,
+        "release_ms": 200.0,
+        "output_interval_ms": 1.0
+    })).await.unwrap();
+
+    let loud = env.process(sine_frame(4800, 1.0)).await.unwrap();
+    let peak = *loud.payload.get("ch0").unwrap().last().unwrap();
+    assert!(peak > 0.9);
+
+    // 50 ms of silence decays only partially with a 200 ms release
+    let quiet = env.process(sine_frame(2400, 0.0)).await.unwrap();
+    let decayed = *quiet.payload.get("ch0").unwrap().last().unwrap();
+    assert!(decayed > 0.7 && decayed < peak, "decayed = {}", decayed);
+}
+
+#[tokio::test]
+async fn test_envelope_invalid_mode() {
+    let mut env = EnvelopeFollowerNode::default();
+    assert!(env.on_create(serde_json::json!({"mode": "log"})).await.is_err());
+}
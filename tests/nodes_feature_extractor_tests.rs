@@ -0,0 +1,73 @@
+use audiotab::core::{ChannelRole, DataFrame, ProcessingNode};
+use audiotab::nodes::FeatureExtractorNode;
+use serde_json::json;
+use std::f64::consts::PI;
+
+const SAMPLE_RATE: f64 = 16000.0;
+
+fn frame(seq: u64, samples: Vec<f64>) -> DataFrame {
+    let mut frame = DataFrame::new(seq * 64_000_000, seq);
+    frame.insert_channel("mic", samples);
+    frame.metadata.insert("sample_rate", SAMPLE_RATE);
+    frame
+}
+
+fn sine(freq: f64, start: usize, len: usize) -> Vec<f64> {
+    (start..start + len).map(|n| 0.5 * (2.0 * PI * freq * n as f64 / SAMPLE_RATE).sin()).collect()
+}
+
+#[tokio::test]
+async fn test_emits_feature_vector_per_block() {
+    let mut node = FeatureExtractorNode::default();
+    node.on_create(json!({"window_size": 512, "hop_size": 256, "mfcc_count": 8})).await.unwrap();
+
+    // 300 samples complete no block yet
+    let out = node.process(frame(0, sine(1000.0, 0, 300))).await.unwrap();
+    assert!(out.payload.is_empty());
+
+    // 1324 samples buffered: blocks at 0, 256, 512 and 768
+    let out = node.process(frame(1, sine(1000.0, 300, 1024))).await.unwrap();
+    assert_eq!(out.payload.len(), 8 + 4);
+    for (name, channel) in &out.payload {
+        assert_eq!(channel.len(), 4, "{}", name);
+        assert_eq!(channel.role, ChannelRole::Measurement);
+        assert_eq!(channel.sample_rate, Some(SAMPLE_RATE / 256.0));
+    }
+    let centroid = &out.payload["mic_centroid"];
+    assert_eq!(centroid.unit.as_deref(), Some("Hz"));
+    assert!(centroid.samples().iter().all(|c| (c - 1000.0).abs() < 150.0), "{:?}", centroid.samples());
+    assert!(out.payload["mic_rolloff"].samples().iter().all(|r| (r - 1000.0).abs() < 100.0));
+    assert!(out.payload["mic_zcr"].samples().iter().all(|z| (z - 2000.0 / SAMPLE_RATE).abs() < 0.01));
+    // A steady tone barely changes from block to block
+    assert!(out.payload["mic_flux"].samples()[1..].iter().all(|f| *f < 0.05));
+    assert!(out.payload.contains_key("mic_mfcc_7") && !out.payload.contains_key("mic_mfcc_8"));
+}
+
+#[tokio::test]
+async fn test_feature_subset_and_flux_on_change() {
+    let mut node = FeatureExtractorNode::default();
+    node.on_create(json!({"window_size": 256, "hop_size": 256, "features": ["flux", "zcr"]})).await.unwrap();
+    let mut samples = sine(500.0, 0, 512);
+    samples.extend(sine(5000.0, 512, 256));
+    let out = node.process(frame(0, samples)).await.unwrap();
+
+    let mut names: Vec<_> = out.payload.keys().cloned().collect();
+    names.sort();
+    assert_eq!(names, ["mic_flux", "mic_zcr"]);
+    let flux = out.payload["mic_flux"].samples();
+    assert_eq!(flux[0], 0.0);
+    assert!(flux[2] > 10.0 * flux[1], "{:?}", flux);
+}
+
+#[tokio::test]
+async fn test_invalid_configurations() {
+    for config in [
+        json!({"features": "mfcc,loudness"}),
+        json!({"mfcc_count": 30, "mel_bands": 20}),
+        json!({"hop_size": 2048, "window_size": 1024}),
+        json!({"min_frequency": 4000.0, "max_frequency": 300.0}),
+    ] {
+        let mut node = FeatureExtractorNode::default();
+        assert!(node.on_create(config.clone()).await.is_err(), "{}", config);
+    }
+}
@@ -0,0 +1,105 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::nodes::FFTNode;
+use std::f64::consts::PI;
+
+fn sine_frame(sequence_id: u64, start: usize, len: usize, freq: f64, sample_rate: f64) -> DataFrame {
+    let samples: Vec<f64> = (start..start + len)
+        .map(|n| (2.0 * PI * freq * n as f64 / sample_rate).sin())
+        .collect();
+    let mut frame = DataFrame::new(0, sequence_id);
+    frame.insert_channel("ch0", samples);
+    frame.metadata.insert("sample_rate", sample_rate);
+    frame
+}
+
+#[tokio::test]
+async fn test_fft_detects_sine_peak() {
+    let mut fft = FFTNode::default();
+    fft.on_create(serde_json::json!({
+        "fft_size": 1024,
+        "hop_size": 1024,
+        "window_type": "flattop"
+    })).await.unwrap();
+
+    // 1 kHz at 48 kHz lands between bins; flat top keeps amplitude accurate
+    let result = fft.process(sine_frame(0, 0, 1024, 1000.0, 48000.0)).await.unwrap();
+    let spectrum = result.payload.get("ch0").expect("spectrum output");
+
+    assert_eq!(spectrum.len(), 513);
+    let (peak_bin, peak) = spectrum
+        .iter()
+        .enumerate()
+        .fold((0, 0.0), |acc, (i, &v)| if v > acc.1 { (i, v) } else { acc });
+
+    let expected_bin = (1000.0_f64 / (48000.0 / 1024.0)).round() as usize;
+    assert_eq!(peak_bin, expected_bin);
+    assert!((peak - 1.0).abs() < 0.01, "flat top amplitude {} should be ~1.0", peak);
+    assert_eq!(result.metadata.get_f64("bin_resolution_hz"), Some(46.875));
+}
+
+#[tokio::test]
+async fn test_fft_buffers_across_frames_with_overlap() {
+    let mut fft = FFTNode::default();
+    fft.on_create(serde_json::json!({
+        "fft_size": 256,
+        "overlap": 0.5
+    })).await.unwrap();
+    assert_eq!(fft.hop_size, 128);
+
+    // First 200 samples are not enough for a full window
+    let first = fft.process(sine_frame(0, 0, 200, 1000.0, 48000.0)).await.unwrap();
+    assert!(first.payload.is_empty());
+
+    // Next 100 samples complete the first window
+    let second = fft.process(sine_frame(1, 200, 100, 1000.0, 48000.0)).await.unwrap();
+    assert_eq!(second.payload.get("ch0").unwrap().len(), 129);
+}
+
+#[tokio::test]
+async fn test_fft_linear_averaging_is_stable() {
+    let mut fft = FFTNode::default();
+    fft.on_create(serde_json::json!({
+        "fft_size": 128,
+        "hop_size": 128,
+        "averaging": "linear",
+        "num_averages": 4
+    })).await.unwrap();
+
+    let mut frame = DataFrame::new(0, 0);
+    frame.insert_channel("ch0", vec![0.5; 512]);
+    let result = fft.process(frame).await.unwrap();
+
+    // Constant input: only DC bin, averaged value equals a single spectrum
+    let spectrum = result.payload.get("ch0").unwrap();
+    assert!((spectrum[0] - 0.5).abs() < 1e-9);
+    assert!(spectrum[10].abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_fft_counts_skipped_spectra() {
+    let mut fft = FFTNode::default();
+    fft.on_create(serde_json::json!({"fft_size": 128, "hop_size": 128})).await.unwrap();
+
+    // Four hops complete, only the last spectrum is emitted
+    let result = fft.process(sine_frame(0, 0, 512, 1000.0, 48000.0)).await.unwrap();
+    assert_eq!(result.payload.get("ch0").unwrap().len(), 65);
+    assert_eq!(result.metadata.get_i64("spectra_skipped"), Some(3));
+
+    let single = fft.process(sine_frame(1, 512, 128, 1000.0, 48000.0)).await.unwrap();
+    assert_eq!(single.metadata.get_i64("spectra_skipped"), None);
+
+    // Averaged spectra include every hop
+    let mut fft = FFTNode::default();
+    fft.on_create(serde_json::json!({"fft_size": 128, "hop_size": 128, "averaging": "linear"})).await.unwrap();
+    let result = fft.process(sine_frame(0, 0, 512, 1000.0, 48000.0)).await.unwrap();
+    assert_eq!(result.metadata.get_i64("spectra_skipped"), None);
+}
+
+#[tokio::test]
+async fn test_fft_rejects_invalid_config() {
+    let mut fft = FFTNode::default();
+    assert!(fft.on_create(serde_json::json!({"window_type": "kaiser"})).await.is_err());
+
+    let mut fft = FFTNode::default();
+    assert!(fft.on_create(serde_json::json!({"fft_size": 256, "hop_size": 512})).await.is_err());
+}
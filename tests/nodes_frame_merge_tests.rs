@@ -0,0 +1,167 @@
+use audiotab::core::{DataFrame, EventSeries, ProcessingNode};
+use audiotab::engine::AsyncPipeline;
+use audiotab::nodes::FrameMergeNode;
+use async_trait::async_trait;
+use serde_json::json;
+use tokio::sync::mpsc;
+
+const MS: u64 = 1_000_000;
+
+/// Frame arriving on merge input `input`, its `ch0` filled with `value`
+fn frame_on(input: usize, timestamp_ms: u64, value: f64) -> DataFrame {
+    let mut frame = DataFrame::new(timestamp_ms * MS, 0);
+    frame.insert_channel("ch0", vec![value; 4]);
+    frame.metadata.insert("input_port", format!("_inputs_{}", input));
+    frame
+}
+
+async fn merge_node(config: serde_json::Value) -> FrameMergeNode {
+    let mut node = FrameMergeNode::default();
+    node.on_create(config).await.unwrap();
+    node
+}
+
+fn names(frame: &DataFrame) -> Vec<&str> {
+    let mut names: Vec<&str> = frame.payload.keys().map(|k| k.as_str()).collect();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn test_frames_within_tolerance_are_merged() {
+    let mut node = merge_node(json!({"tolerance_ms": 5.0, "prefixes": ["mic", "accel"]})).await;
+
+    let waiting = node.process(frame_on(0, 100, 1.0)).await.unwrap();
+    assert!(waiting.payload.is_empty());
+    assert_eq!(waiting.metadata.get_bool("merged"), Some(false));
+
+    let merged = node.process(frame_on(1, 103, 2.0)).await.unwrap();
+    assert_eq!(names(&merged), ["accel_ch0", "mic_ch0"]);
+    assert_eq!(merged.payload["mic_ch0"].samples(), &[1.0; 4]);
+    assert_eq!(merged.payload["accel_ch0"].samples(), &[2.0; 4]);
+    assert_eq!(merged.timestamp, 100 * MS);
+    assert_eq!(merged.metadata.get_i64("timestamp_skew_ns"), Some(3 * MS as i64));
+    assert_eq!(merged.metadata.get_str("input_port"), None);
+    assert_eq!((waiting.sequence_id, merged.sequence_id), (0, 1));
+}
+
+#[tokio::test]
+async fn test_drop_policy_discards_unmatched_frames() {
+    let mut node = merge_node(json!({"tolerance_ms": 5.0})).await;
+
+    node.process(frame_on(0, 100, 1.0)).await.unwrap();
+    // Input 1 skipped the frame at 100 ms
+    let merged = node.process(frame_on(1, 120, 2.0)).await.unwrap();
+    assert!(merged.payload.is_empty());
+    assert_eq!(node.dropped_frames(), 1);
+
+    let merged = node.process(frame_on(0, 121, 3.0)).await.unwrap();
+    assert_eq!(merged.payload["in0_ch0"].samples(), &[3.0; 4]);
+    assert_eq!(merged.payload["in1_ch0"].samples(), &[2.0; 4]);
+}
+
+#[tokio::test]
+async fn test_zeros_and_hold_fill_a_missing_input() {
+    for (fill, expected) in [("zeros", 0.0), ("hold", 2.0)] {
+        let mut node = merge_node(json!({"fill": fill})).await;
+        node.process(frame_on(0, 0, 1.0)).await.unwrap();
+        node.process(frame_on(1, 0, 2.0)).await.unwrap();
+
+        node.process(frame_on(0, 20, 3.0)).await.unwrap();
+        let filled = node.process(frame_on(1, 40, 4.0)).await.unwrap();
+        assert_eq!(filled.payload["in0_ch0"].samples(), &[3.0; 4], "{}", fill);
+        assert_eq!(filled.payload["in1_ch0"].samples(), &[expected; 4], "{}", fill);
+        assert_eq!(filled.metadata.get_i64("filled_inputs"), Some(1));
+    }
+}
+
+#[tokio::test]
+async fn test_events_are_prefixed_and_not_repeated_by_hold() {
+    let mut node = merge_node(json!({"fill": "hold", "prefixes": ["mic", "tacho"]})).await;
+    let mut pulses = frame_on(1, 0, 2.0);
+    pulses.insert_events("pulse", EventSeries::from_times([MS, 3 * MS]));
+    node.process(frame_on(0, 0, 1.0)).await.unwrap();
+    let merged = node.process(pulses).await.unwrap();
+    assert_eq!(merged.events["tacho_pulse"].len(), 2);
+
+    // The mic frame at 20 ms has no match and holds the last tacho frame
+    node.process(frame_on(0, 20, 3.0)).await.unwrap();
+    let held = node.process(frame_on(1, 40, 4.0)).await.unwrap();
+    assert_eq!(held.metadata.get_i64("filled_inputs"), Some(1));
+    assert_eq!(held.payload["tacho_ch0"].samples(), &[2.0; 4]);
+    assert!(held.events.is_empty());
+}
+
+#[tokio::test]
+async fn test_a_stalled_input_is_filled_after_max_pending() {
+    let mut node = merge_node(json!({"fill": "zeros", "max_pending": 2})).await;
+    node.process(frame_on(0, 0, 1.0)).await.unwrap();
+    node.process(frame_on(1, 0, 2.0)).await.unwrap();
+
+    for i in 1..=2 {
+        let out = node.process(frame_on(0, i * 20, 1.0)).await.unwrap();
+        assert!(out.payload.is_empty());
+    }
+    let out = node.process(frame_on(0, 60, 1.0)).await.unwrap();
+    assert_eq!(out.timestamp, 20 * MS);
+    assert_eq!(out.payload["in1_ch0"].samples(), &[0.0; 4]);
+}
+
+#[tokio::test]
+async fn test_frames_without_a_merge_port_are_rejected() {
+    let mut node = merge_node(json!({})).await;
+    let mut frame = frame_on(0, 0, 1.0);
+    frame.metadata.remove("input_port");
+    assert!(node.process(frame).await.is_err());
+    assert!(node.process(frame_on(2, 0, 1.0)).await.is_err());
+
+    let mut node = FrameMergeNode::default();
+    assert!(node.on_create(json!({"fill": "average"})).await.is_err());
+}
+
+/// Sink forwarding every merged frame
+struct ForwardSink(mpsc::UnboundedSender<DataFrame>);
+
+#[async_trait]
+impl ProcessingNode for ForwardSink {
+    async fn process(&mut self, input: DataFrame) -> anyhow::Result<DataFrame> {
+        if input.metadata.get_bool("merged") == Some(true) {
+            let _ = self.0.send(input.clone());
+        }
+        Ok(input)
+    }
+}
+
+#[tokio::test]
+async fn test_merge_combines_two_branches_of_a_graph() {
+    let mut pipeline = AsyncPipeline::from_json(json!({
+        "nodes": [
+            {"id": "src", "type": "Gain", "config": {}},
+            {"id": "left", "type": "Gain", "config": {"gain_db": 0.0}},
+            {"id": "right", "type": "Gain", "config": {"gain_db": 0.0}},
+            {"id": "merge", "type": "FrameMerge", "config": {"prefixes": "left,right"}, "port_counts": {"_inputs": 2}},
+            {"id": "sink", "type": "Print", "config": {}}
+        ],
+        "connections": [
+            {"from": "src", "to": "left"},
+            {"from": "src", "to": "right"},
+            {"from": "left", "to": "merge", "to_port": "_inputs_0"},
+            {"from": "right", "to": "merge", "to_port": "_inputs_1"},
+            {"from": "merge", "to": "sink"}
+        ]
+    }))
+    .await
+    .unwrap();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    pipeline.nodes_mut().insert("sink".to_string(), Box::new(ForwardSink(tx)));
+    pipeline.start().await.unwrap();
+
+    let mut frame = DataFrame::new(0, 0);
+    frame.insert_channel("ch0", vec![0.5; 16]);
+    pipeline.trigger(frame).await.unwrap();
+
+    let merged = rx.recv().await.unwrap();
+    assert_eq!(names(&merged), ["left_ch0", "right_ch0"]);
+
+    pipeline.stop().await.unwrap();
+}
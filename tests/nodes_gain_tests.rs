@@ -1,6 +1,5 @@
 use audiotab::core::{DataFrame, ProcessingNode};
 use audiotab::nodes::GainNode;
-use std::sync::Arc;
 use tokio::sync::mpsc;
 
 #[tokio::test]
@@ -12,8 +11,7 @@ async fn test_gain_multiplication() {
     gain.on_create(config).await.unwrap();
 
     let mut df = DataFrame::new(0, 0);
-    df.payload
-        .insert("main_channel".to_string(), Arc::new(vec![1.0, 2.0, 3.0]));
+    df.insert_channel("main_channel", vec![1.0, 2.0, 3.0]);
 
     let result = gain.process(df).await.unwrap();
     let output = result.payload.get("main_channel").unwrap().as_ref();
@@ -32,8 +30,7 @@ async fn test_gain_attenuation() {
     gain.on_create(config).await.unwrap();
 
     let mut df = DataFrame::new(0, 0);
-    df.payload
-        .insert("main_channel".to_string(), Arc::new(vec![2.0, 4.0, 6.0]));
+    df.insert_channel("main_channel", vec![2.0, 4.0, 6.0]);
 
     let result = gain.process(df).await.unwrap();
     let output = result.payload.get("main_channel").unwrap().as_ref();
@@ -68,11 +65,11 @@ async fn test_gain_streaming() {
 
     // Send 2 frames
     let mut df1 = DataFrame::new(0, 0);
-    df1.payload.insert("main_channel".to_string(), Arc::new(vec![1.0, 2.0]));
+    df1.insert_channel("main_channel", vec![1.0, 2.0]);
     tx_in.send(df1).await.unwrap();
 
     let mut df2 = DataFrame::new(1000, 1);
-    df2.payload.insert("main_channel".to_string(), Arc::new(vec![3.0, 4.0]));
+    df2.insert_channel("main_channel", vec![3.0, 4.0]);
     tx_in.send(df2).await.unwrap();
 
     drop(tx_in);
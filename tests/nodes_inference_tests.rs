@@ -0,0 +1,171 @@
+#![cfg(feature = "onnx")]
+
+use audiotab::core::{ChannelRole, DataFrame, ProcessingNode};
+use audiotab::nodes::InferenceNode;
+use serde_json::json;
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+// Minimal protobuf writer, enough to build small ONNX models by hand
+
+fn varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn int_field(out: &mut Vec<u8>, field: u64, value: u64) {
+    varint(out, field << 3);
+    varint(out, value);
+}
+
+fn bytes_field(out: &mut Vec<u8>, field: u64, bytes: &[u8]) {
+    varint(out, (field << 3) | 2);
+    varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+/// ValueInfoProto of a float tensor with the given shape
+fn value_info(name: &str, dims: &[u64]) -> Vec<u8> {
+    let mut shape = Vec::new();
+    for &d in dims {
+        let mut dim = Vec::new();
+        int_field(&mut dim, 1, d);
+        bytes_field(&mut shape, 1, &dim);
+    }
+    let mut tensor = Vec::new();
+    int_field(&mut tensor, 1, 1);
+    bytes_field(&mut tensor, 2, &shape);
+    let mut type_proto = Vec::new();
+    bytes_field(&mut type_proto, 1, &tensor);
+    let mut info = Vec::new();
+    bytes_field(&mut info, 1, name.as_bytes());
+    bytes_field(&mut info, 2, &type_proto);
+    info
+}
+
+/// ONNX model computing `y = x · W` for x of shape [1, rows]
+fn matmul_model(weights: &[Vec<f32>]) -> NamedTempFile {
+    let (rows, cols) = (weights.len() as u64, weights[0].len() as u64);
+
+    let mut node = Vec::new();
+    bytes_field(&mut node, 1, b"x");
+    bytes_field(&mut node, 1, b"W");
+    bytes_field(&mut node, 2, b"y");
+    bytes_field(&mut node, 3, b"matmul");
+    bytes_field(&mut node, 4, b"MatMul");
+
+    let mut initializer = Vec::new();
+    int_field(&mut initializer, 1, rows);
+    int_field(&mut initializer, 1, cols);
+    int_field(&mut initializer, 2, 1);
+    let data: Vec<u8> = weights.iter().flatten().flat_map(|w| w.to_le_bytes()).collect();
+    bytes_field(&mut initializer, 4, &data);
+    bytes_field(&mut initializer, 8, b"W");
+
+    let mut graph = Vec::new();
+    bytes_field(&mut graph, 1, &node);
+    bytes_field(&mut graph, 2, b"test");
+    bytes_field(&mut graph, 5, &initializer);
+    bytes_field(&mut graph, 11, &value_info("x", &[1, rows]));
+    bytes_field(&mut graph, 12, &value_info("y", &[1, cols]));
+
+    let mut opset = Vec::new();
+    bytes_field(&mut opset, 1, b"");
+    int_field(&mut opset, 2, 13);
+
+    let mut model = Vec::new();
+    int_field(&mut model, 1, 7);
+    bytes_field(&mut model, 7, &graph);
+    bytes_field(&mut model, 8, &opset);
+
+    let mut file = NamedTempFile::new().unwrap();
+    file.write_all(&model).unwrap();
+    file
+}
+
+fn features_frame() -> DataFrame {
+    let mut frame = DataFrame::new(0, 0);
+    frame.insert_channel("mic_mfcc_0", vec![1.0, 0.0, 0.0]);
+    frame.insert_channel("mic_mfcc_1", vec![0.0, 1.0, 0.0]);
+    frame.insert_channel("mic_mfcc_10", vec![0.0, 0.0, 1.0]);
+    frame
+}
+
+#[tokio::test]
+async fn test_features_layout_scores_each_vector() {
+    // Rows follow the numeric order mfcc_0, mfcc_1, mfcc_10
+    let model = matmul_model(&[vec![1.0, 0.0], vec![0.0, 2.0], vec![3.0, 1.0]]);
+    let mut node = InferenceNode::default();
+    node.on_create(json!({"model_path": model.path(), "labels": ["dog", "siren"]})).await.unwrap();
+
+    let out = node.process(features_frame()).await.unwrap();
+    let mut names: Vec<_> = out.payload.keys().cloned().collect();
+    names.sort();
+    assert_eq!(names, ["dog", "siren", "top_class", "top_score"]);
+    assert_eq!(out.payload["dog"].samples(), &[1.0, 0.0, 3.0]);
+    assert_eq!(out.payload["siren"].samples(), &[0.0, 2.0, 1.0]);
+    assert_eq!(out.payload["top_class"].samples(), &[0.0, 1.0, 0.0]);
+    assert_eq!(out.payload["top_score"].samples(), &[1.0, 2.0, 3.0]);
+    assert_eq!(out.payload["dog"].role, ChannelRole::Measurement);
+}
+
+#[tokio::test]
+async fn test_softmax_and_explicit_inputs() {
+    let model = matmul_model(&[vec![1.0, 0.0], vec![0.0, 1.0]]);
+    let mut node = InferenceNode::default();
+    node.on_create(json!({"model_path": model.path(), "inputs": "b,a", "softmax": true, "keep_inputs": true}))
+        .await
+        .unwrap();
+
+    let mut frame = DataFrame::new(0, 0);
+    frame.insert_channel("a", vec![0.0]);
+    frame.insert_channel("b", vec![2.0_f64.ln()]);
+    let out = node.process(frame).await.unwrap();
+    assert!(out.payload.contains_key("a"));
+    let (p0, p1) = (out.payload["score_0"][0], out.payload["score_1"][0]);
+    assert!((p0 - 2.0 / 3.0).abs() < 1e-6 && (p1 - 1.0 / 3.0).abs() < 1e-6, "{} {}", p0, p1);
+}
+
+#[tokio::test]
+async fn test_window_layout_buffers_samples() {
+    // Sums each window of four samples
+    let model = matmul_model(&[vec![1.0], vec![1.0], vec![1.0], vec![1.0]]);
+    let mut node = InferenceNode::default();
+    node.on_create(json!({"model_path": model.path(), "layout": "window", "window_size": 4, "hop_size": 2}))
+        .await
+        .unwrap();
+
+    let mut frame = DataFrame::new(0, 0);
+    frame.insert_channel("mic", vec![1.0, 2.0, 3.0]);
+    frame.metadata.insert("sample_rate", 8000.0);
+    assert!(node.process(frame).await.unwrap().payload.is_empty());
+
+    let mut frame = DataFrame::new(1, 1);
+    frame.insert_channel("mic", vec![4.0, 5.0, 6.0]);
+    frame.metadata.insert("sample_rate", 8000.0);
+    let out = node.process(frame).await.unwrap();
+    assert_eq!(out.payload["score_0"].samples(), &[10.0, 18.0]);
+    assert_eq!(out.payload["score_0"].sample_rate, Some(4000.0));
+}
+
+#[tokio::test]
+async fn test_invalid_configurations() {
+    let model = matmul_model(&[vec![1.0, 0.0], vec![0.0, 1.0]]);
+    for config in [
+        json!({}),
+        json!({"model_path": "/nonexistent/model.onnx"}),
+        json!({"model_path": model.path(), "layout": "image"}),
+        json!({"model_path": model.path(), "input_shape": "1,x"}),
+    ] {
+        let mut node = InferenceNode::default();
+        assert!(node.on_create(config.clone()).await.is_err(), "{}", config);
+    }
+
+    // Label count and input width are checked against the model
+    let mut node = InferenceNode::default();
+    node.on_create(json!({"model_path": model.path(), "labels": "a,b,c"})).await.unwrap();
+    assert!(node.process(features_frame()).await.is_err());
+}
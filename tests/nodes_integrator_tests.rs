@@ -0,0 +1,101 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::nodes::{IntegrationMode, IntegratorNode};
+use serde_json::json;
+use std::f64::consts::PI;
+
+const SAMPLE_RATE: f64 = 4800.0;
+const BLOCK: usize = 480;
+
+fn frame(seq: u64, samples: Vec<f64>, unit: &str) -> DataFrame {
+    let mut frame = DataFrame::new(seq * 100_000_000, seq);
+    frame.insert_channel("accel", samples);
+    frame.payload.get_mut("accel").unwrap().unit = Some(unit.to_string());
+    frame.metadata.insert("sample_rate", SAMPLE_RATE);
+    frame
+}
+
+/// Feed two seconds of `offset + amplitude * sin(2π f t)` and return the
+/// peak of the output over the last half second, plus the last frame
+async fn run_sine(config: serde_json::Value, freq: f64, amplitude: f64, offset: f64) -> (f64, DataFrame) {
+    let mut node = IntegratorNode::default();
+    node.on_create(config).await.unwrap();
+    let mut peak = 0.0f64;
+    let mut last = None;
+    for seq in 0..20u64 {
+        let samples = (0..BLOCK)
+            .map(|i| {
+                let t = (seq as usize * BLOCK + i) as f64 / SAMPLE_RATE;
+                offset + amplitude * (2.0 * PI * freq * t).sin()
+            })
+            .collect();
+        let out = node.process(frame(seq, samples, "m/s²")).await.unwrap();
+        if seq >= 15 {
+            peak = out.payload["accel"].samples().iter().fold(peak, |p, x| p.max(x.abs()));
+        }
+        last = Some(out);
+    }
+    (peak, last.unwrap())
+}
+
+#[tokio::test]
+async fn test_integrates_acceleration_to_velocity() {
+    // 1 g at 80 Hz with a DC offset that must not turn into a ramp
+    let (peak, out) = run_sine(json!({"mode": "integrate"}), 80.0, 9.81, 0.5).await;
+    let expected = 9.81 / (2.0 * PI * 80.0);
+    assert!((peak / expected - 1.0).abs() < 0.02, "peak {} expected {}", peak, expected);
+    assert_eq!(out.payload["accel"].unit.as_deref(), Some("m/s"));
+}
+
+#[tokio::test]
+async fn test_double_integrates_to_displacement() {
+    let (peak, out) = run_sine(json!({"mode": "double_integrate", "suffix": "_disp"}), 80.0, 9.81, 0.5).await;
+    let expected = 9.81 / (2.0 * PI * 80.0).powi(2);
+    let disp = out.payload["accel_disp"].samples();
+    let disp_peak = disp.iter().fold(0.0f64, |p, x| p.max(x.abs()));
+    assert!((disp_peak / expected - 1.0).abs() < 0.02, "peak {} expected {}", disp_peak, expected);
+    assert_eq!(out.payload["accel_disp"].unit.as_deref(), Some("m"));
+    // The input is kept next to the result
+    assert!((peak - 9.81 - 0.5).abs() < 0.1);
+}
+
+#[tokio::test]
+async fn test_differentiates() {
+    let config = json!({"mode": "differentiate", "output_scale": 1000.0, "output_unit": "mm/s³"});
+    let (peak, out) = run_sine(config, 50.0, 0.01, 0.0).await;
+    let expected = 1000.0 * 0.01 * 2.0 * PI * 50.0;
+    assert!((peak / expected - 1.0).abs() < 0.02, "peak {} expected {}", peak, expected);
+    assert_eq!(out.payload["accel"].unit.as_deref(), Some("mm/s³"));
+}
+
+#[tokio::test]
+async fn test_scaling_without_unit_drops_unit() {
+    let (_, out) = run_sine(json!({"mode": "integrate", "output_scale": 1000.0}), 80.0, 1.0, 0.0).await;
+    assert_eq!(out.payload["accel"].unit, None);
+}
+
+#[tokio::test]
+async fn test_selected_channels_and_errors() {
+    let mut node = IntegratorNode::default();
+    node.on_create(json!({"channels": ["missing"]})).await.unwrap();
+    assert!(node.process(frame(0, vec![0.0; 16], "m/s²")).await.is_err());
+
+    let mut node = IntegratorNode::default();
+    node.on_create(json!({"highpass_hz": 1000.0})).await.unwrap();
+    let mut low_rate = frame(0, vec![0.0; 16], "m/s²");
+    low_rate.metadata.insert("sample_rate", 1000.0);
+    assert!(node.process(low_rate).await.is_err());
+
+    let mut node = IntegratorNode::default();
+    assert!(node.on_create(json!({"mode": "integral"})).await.is_err());
+}
+
+#[test]
+fn test_unit_derivation() {
+    assert_eq!(IntegrationMode::Integrate.output_unit("m/s^2"), Some("m/s"));
+    assert_eq!(IntegrationMode::Integrate.output_unit("mm/s"), Some("mm"));
+    assert_eq!(IntegrationMode::DoubleIntegrate.output_unit("m/s²"), Some("m"));
+    assert_eq!(IntegrationMode::DoubleIntegrate.output_unit("m/s"), None);
+    assert_eq!(IntegrationMode::Differentiate.output_unit("m"), Some("m/s"));
+    assert_eq!(IntegrationMode::Differentiate.output_unit("m/s²"), None);
+    assert_eq!(IntegrationMode::Integrate.output_unit("Pa"), None);
+}
@@ -0,0 +1,70 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::nodes::MathNode;
+use serde_json::json;
+
+fn frame(channels: &[(&str, Vec<f64>)]) -> DataFrame {
+    let mut frame = DataFrame::new(0, 0);
+    for (name, samples) in channels {
+        frame.insert_channel(*name, samples.clone());
+    }
+    frame
+}
+
+async fn run(config: serde_json::Value, input: DataFrame) -> anyhow::Result<DataFrame> {
+    let mut node = MathNode::default();
+    node.on_create(config).await?;
+    node.process(input).await
+}
+
+#[tokio::test]
+async fn test_arithmetic_operations() {
+    let input = || frame(&[("a", vec![1.0, 2.0]), ("b", vec![3.0, -4.0]), ("c", vec![0.5, 0.5])]);
+
+    let sum = run(json!({"operation": "sum"}), input()).await.unwrap();
+    assert_eq!(sum.payload["math"].samples(), &[4.5, -1.5]);
+
+    let diff = run(json!({"operation": "difference", "inputs": ["b", "a"], "output": "d"}), input()).await.unwrap();
+    assert_eq!(diff.payload["d"].samples(), &[2.0, -6.0]);
+    assert_eq!(diff.payload.len(), 4);
+
+    let product = run(json!({"operation": "product", "inputs": "a,b", "scale": 2.0}), input()).await.unwrap();
+    assert_eq!(product.payload["math"].samples(), &[6.0, -16.0]);
+}
+
+#[tokio::test]
+async fn test_abs_and_db() {
+    let abs = run(json!({"operation": "abs", "keep_inputs": false}), frame(&[("x", vec![-0.5, 0.25])])).await.unwrap();
+    assert_eq!(abs.payload.len(), 1);
+    assert_eq!(abs.payload["math"].samples(), &[0.5, 0.25]);
+
+    let spl = run(
+        json!({"operation": "db", "db_reference": 2e-5, "output": "spl"}),
+        frame(&[("p", vec![0.2, -2e-5])]),
+    )
+    .await
+    .unwrap();
+    let spl = &spl.payload["spl"];
+    assert!((spl[0] - 80.0).abs() < 1e-9 && spl[1].abs() < 1e-9, "{:?}", spl.samples());
+    assert_eq!(spl.unit.as_deref(), Some("dB"));
+}
+
+#[tokio::test]
+async fn test_expression_subtracts_reference_and_scales() {
+    let input = frame(&[("mic", vec![0.3, 0.1]), ("ref", vec![0.1, 0.1])]);
+    let out = run(json!({"operation": "expression", "expression": "(mic - ref) / 0.05", "output": "pa"}), input)
+        .await
+        .unwrap();
+    let pa = out.payload["pa"].samples();
+    assert!((pa[0] - 4.0).abs() < 1e-12 && pa[1].abs() < 1e-12, "{:?}", pa);
+}
+
+#[tokio::test]
+async fn test_errors() {
+    let input = || frame(&[("a", vec![1.0, 2.0]), ("b", vec![1.0])]);
+    assert!(run(json!({"operation": "sum"}), input()).await.is_err());
+    assert!(run(json!({"operation": "abs"}), input()).await.is_err());
+    assert!(run(json!({"operation": "sum", "inputs": "a,z"}), input()).await.is_err());
+    assert!(run(json!({"operation": "expression", "expression": "a +"}), input()).await.is_err());
+    assert!(run(json!({"operation": "expression", "expression": "2 * pi"}), input()).await.is_err());
+    assert!(run(json!({"operation": "modulo"}), input()).await.is_err());
+}
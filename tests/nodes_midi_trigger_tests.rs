@@ -0,0 +1,87 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::hal::drivers::MidiMessage;
+use audiotab::hal::{DeviceChannels, PacketBuffer, SampleData};
+use audiotab::nodes::MidiTriggerNode;
+use crossbeam_channel::{unbounded, Receiver, Sender};
+
+fn frame(sequence_id: u64) -> DataFrame {
+    let mut frame = DataFrame::new(0, sequence_id);
+    frame.insert_channel("ch0", vec![0.1, 0.2, 0.3]);
+    frame
+}
+
+fn midi_packet(bytes: &[u8]) -> PacketBuffer {
+    PacketBuffer {
+        data: SampleData::Bytes(bytes.to_vec()),
+        sample_rate: 48000,
+        num_channels: 1,
+        timestamp: None,
+        codec: None,
+    }
+}
+
+/// Node wired to a fake MIDI device: (node, device-side sender, returned buffers)
+async fn node_with_device(config: serde_json::Value) -> (MidiTriggerNode, Sender<PacketBuffer>, Receiver<PacketBuffer>) {
+    let (filled_tx, filled_rx) = unbounded();
+    let (empty_tx, empty_rx) = unbounded();
+    let mut node = MidiTriggerNode::default();
+    node.on_create(config).await.unwrap();
+    node.set_device_channels(Some(DeviceChannels { filled_rx, empty_tx }));
+    (node, filled_tx, empty_rx)
+}
+
+#[test]
+fn test_parse_midi_messages() {
+    assert_eq!(
+        MidiMessage::parse(&[0x92, 60, 100]),
+        Some(MidiMessage::NoteOn { channel: 3, note: 60, velocity: 100 })
+    );
+    // Note-on with zero velocity is a note-off
+    assert_eq!(MidiMessage::parse(&[0x90, 60, 0]), Some(MidiMessage::NoteOff { channel: 1, note: 60 }));
+    assert_eq!(
+        MidiMessage::parse(&[0xB0, 7, 127]),
+        Some(MidiMessage::ControlChange { channel: 1, controller: 7, value: 127 })
+    );
+    assert_eq!(MidiMessage::parse(&[0x90, 60]), None);
+    assert_eq!(MidiMessage::parse(&[0xF8]), None);
+}
+
+#[tokio::test]
+async fn test_midi_trigger_gates_frames_on_note() {
+    let (mut node, device, returned) = node_with_device(serde_json::json!({"note": 36, "midi_channel": 10})).await;
+
+    let idle = node.process(frame(0)).await.unwrap();
+    assert!(idle.payload.is_empty());
+    assert_eq!(idle.metadata["midi_trigger"], false);
+
+    // Wrong note, wrong channel, then the matching pad hit
+    device.send(midi_packet(&[0x99, 38, 90])).unwrap();
+    device.send(midi_packet(&[0x90, 36, 90])).unwrap();
+    device.send(midi_packet(&[0x99, 36, 127])).unwrap();
+    let fired = node.process(frame(1)).await.unwrap();
+    assert_eq!(fired.metadata["midi_trigger"], true);
+    assert_eq!(fired.metadata.get_i64("midi_note"), Some(36));
+    assert_eq!(fired.metadata.get_f64("midi_velocity"), Some(1.0));
+    assert_eq!(fired.payload["ch0"].len(), 3);
+    assert_eq!(node.trigger_count(), 1);
+
+    // Every packet is handed back to the device
+    assert_eq!(returned.try_iter().count(), 3);
+    assert!(node.process(frame(2)).await.unwrap().payload.is_empty());
+}
+
+#[tokio::test]
+async fn test_midi_trigger_tracks_controller_without_gating() {
+    let (mut node, device, _returned) = node_with_device(serde_json::json!({"cc_number": 7, "gate": false})).await;
+
+    device.send(midi_packet(&[0xB0, 7, 127])).unwrap();
+    device.send(midi_packet(&[0xB0, 1, 10])).unwrap();
+    let out = node.process(frame(0)).await.unwrap();
+    assert_eq!(out.metadata.get_f64("midi_cc"), Some(1.0));
+    assert_eq!(out.metadata["midi_trigger"], false);
+    assert_eq!(out.payload["ch0"].len(), 3);
+
+    // The controller value persists until it changes
+    let out = node.process(frame(1)).await.unwrap();
+    assert_eq!(out.metadata.get_f64("midi_cc"), Some(1.0));
+}
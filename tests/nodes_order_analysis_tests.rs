@@ -0,0 +1,127 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::engine::AsyncPipeline;
+use audiotab::nodes::OrderAnalysisNode;
+use async_trait::async_trait;
+use serde_json::json;
+use std::f64::consts::PI;
+use tokio::sync::mpsc;
+
+const SAMPLE_RATE: f64 = 48000.0;
+
+/// Sine at `order` times the shaft speed, with the tacho channel built by
+/// `tacho`, for the samples `start..start + samples` of a continuous run
+fn frame(start: usize, samples: usize, rpm: f64, order: f64, tacho: impl Fn(usize) -> f64) -> DataFrame {
+    let mut frame = DataFrame::new(0, 0);
+    frame.metadata.insert("sample_rate", SAMPLE_RATE);
+    let range = start..start + samples;
+    let signal: Vec<f64> = range.clone()
+        .map(|i| (2.0 * PI * order * rpm / 60.0 * i as f64 / SAMPLE_RATE).sin())
+        .collect();
+    frame.insert_channel("ch0", signal);
+    frame.insert_channel("ch1", range.map(tacho).collect::<Vec<f64>>());
+    frame
+}
+
+fn peak_bin(spectrum: &[f64]) -> usize {
+    spectrum
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+        .map(|(k, _)| k)
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_order_peak_at_constant_rpm() {
+    let mut node = OrderAnalysisNode::default();
+    node.on_create(serde_json::json!({"samples_per_rev": 32, "revolutions": 8})).await.unwrap();
+
+    // 3000 rpm = 50 rev/s, so 8 revolutions take 7680 samples
+    let result = node.process(frame(0, 9600, 3000.0, 3.0, |_| 3000.0)).await.unwrap();
+    let spectrum = result.payload.get("ch0").expect("one complete block");
+
+    assert_eq!(spectrum.len(), 32 * 8 / 2 + 1);
+    assert_eq!(peak_bin(spectrum), 3 * 8);
+    assert!((spectrum[24] - 1.0).abs() < 0.05, "amplitude {}", spectrum[24]);
+    assert_eq!(result.metadata.get_f64("order_resolution"), Some(0.125));
+    assert_eq!(result.metadata.get_f64("max_order"), Some(16.0));
+    assert!((result.metadata.get_f64("rpm").unwrap() - 3000.0).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_pulse_tacho_and_incomplete_block() {
+    let mut node = OrderAnalysisNode::default();
+    node.on_create(serde_json::json!({
+        "tacho_type": "pulse",
+        "pulses_per_rev": 2,
+        "samples_per_rev": 32,
+        "revolutions": 4,
+    })).await.unwrap();
+
+    // 1800 rpm with two pulses per revolution: one pulse every 800 samples
+    let pulses = |i: usize| if i % 800 < 10 { 1.0 } else { 0.0 };
+    let first = node.process(frame(0, 2000, 1800.0, 2.0, pulses)).await.unwrap();
+    assert!(first.payload.is_empty());
+
+    let second = node.process(frame(2000, 9600, 1800.0, 2.0, pulses)).await.unwrap();
+    let spectrum = second.payload.get("ch0").expect("one complete block");
+    assert_eq!(peak_bin(spectrum), 2 * 4);
+    assert!((second.metadata.get_f64("rpm").unwrap() - 1800.0).abs() < 1.0);
+}
+
+#[tokio::test]
+async fn test_missing_channel_and_invalid_config() {
+    let mut node = OrderAnalysisNode::default();
+    node.on_create(serde_json::json!({"tacho_channel": "rpm"})).await.unwrap();
+    assert!(node.process(frame(0, 100, 600.0, 1.0, |_| 600.0)).await.is_err());
+
+    let mut node = OrderAnalysisNode::default();
+    assert!(node.on_create(serde_json::json!({"tacho_type": "encoder"})).await.is_err());
+    assert!(node.on_create(serde_json::json!({"revolutions": 0})).await.is_err());
+}
+
+/// Sink forwarding every frame with an order spectrum
+struct ForwardSink(mpsc::UnboundedSender<DataFrame>);
+
+#[async_trait]
+impl ProcessingNode for ForwardSink {
+    async fn process(&mut self, input: DataFrame) -> anyhow::Result<DataFrame> {
+        if !input.payload.is_empty() {
+            let _ = self.0.send(input.clone());
+        }
+        Ok(input)
+    }
+}
+
+#[tokio::test]
+async fn test_signal_and_tacho_from_separate_branches() {
+    let mut pipeline = AsyncPipeline::from_json(json!({
+        "nodes": [
+            {"id": "src", "type": "Gain", "config": {}},
+            {"id": "signal", "type": "ChannelRouter", "config": {"routes": "ch0"}},
+            {"id": "tacho", "type": "ChannelRouter", "config": {"routes": "ch1 -> ch0"}},
+            {"id": "orders", "type": "OrderAnalysisNode", "config": {"samples_per_rev": 32, "revolutions": 8}},
+            {"id": "sink", "type": "Print", "config": {}}
+        ],
+        "connections": [
+            {"from": "src", "to": "signal"},
+            {"from": "src", "to": "tacho"},
+            {"from": "signal", "to": "orders", "to_port": "_signal"},
+            {"from": "tacho", "to": "orders", "to_port": "_tacho"},
+            {"from": "orders", "to": "sink"}
+        ]
+    }))
+    .await
+    .unwrap();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    pipeline.nodes_mut().insert("sink".to_string(), Box::new(ForwardSink(tx)));
+    pipeline.start().await.unwrap();
+
+    pipeline.trigger(frame(0, 9600, 3000.0, 3.0, |_| 3000.0)).await.unwrap();
+
+    let result = rx.recv().await.unwrap();
+    assert_eq!(peak_bin(result.payload.get("ch0").unwrap()), 3 * 8);
+    assert!((result.metadata.get_f64("rpm").unwrap() - 3000.0).abs() < 1e-9);
+
+    pipeline.stop().await.unwrap();
+}
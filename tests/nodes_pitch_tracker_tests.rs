@@ -0,0 +1,82 @@
+use audiotab::core::{ChannelRole, DataFrame, ProcessingNode};
+use audiotab::nodes::PitchTrackerNode;
+use serde_json::json;
+use std::f64::consts::PI;
+
+const SAMPLE_RATE: f64 = 16000.0;
+
+/// Sine whose frequency steps from `f1` to `f2` halfway, phase continuous
+fn glide(f1: f64, f2: f64, len: usize) -> Vec<f64> {
+    let mut phase = 0.0;
+    (0..len)
+        .map(|n| {
+            let f = if n < len / 2 { f1 } else { f2 };
+            phase += 2.0 * PI * f / SAMPLE_RATE;
+            0.5 * phase.sin()
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_tracks_frequency_steps_per_block() {
+    let mut node = PitchTrackerNode::default();
+    node.on_create(json!({"window_size": 1024, "hop_size": 256, "min_frequency": 40.0})).await.unwrap();
+
+    let signal = glide(110.0, 330.0, 32_000);
+    let (mut pitch, mut confidence) = (Vec::new(), Vec::new());
+    for (seq, chunk) in signal.chunks(1000).enumerate() {
+        let mut frame = DataFrame::new(seq as u64 * 62_500_000, seq as u64);
+        frame.insert_channel("ch0", chunk.to_vec());
+        frame.metadata.insert("sample_rate", SAMPLE_RATE);
+        let out = node.process(frame).await.unwrap();
+        if let Some(p) = out.payload.get("ch0_pitch") {
+            assert_eq!(p.unit.as_deref(), Some("Hz"));
+            assert_eq!(p.role, ChannelRole::Measurement);
+            assert_eq!(p.sample_rate, Some(SAMPLE_RATE / 256.0));
+            pitch.extend_from_slice(p.samples());
+            confidence.extend_from_slice(out.payload["ch0_confidence"].samples());
+        } else {
+            assert!(out.payload.is_empty());
+        }
+    }
+
+    // One estimate per hop once the first window is full
+    assert_eq!(pitch.len(), (32_000 - 1024) / 256 + 1);
+    let step_block = 16_000 / 256;
+    for (i, f) in pitch.iter().enumerate() {
+        // Skip blocks whose window straddles the step
+        if i + 4 < step_block {
+            assert!((f - 110.0).abs() < 1.0, "block {}: {}", i, f);
+        } else if i > step_block {
+            assert!((f - 330.0).abs() < 2.0, "block {}: {}", i, f);
+        }
+    }
+    assert!(confidence.iter().all(|c| (0.0..=1.0).contains(c)));
+}
+
+#[tokio::test]
+async fn test_selected_channels_only() {
+    let mut node = PitchTrackerNode::default();
+    node.on_create(json!({"channels": ["tacho"], "window_size": 512, "hop_size": 512})).await.unwrap();
+    let mut frame = DataFrame::new(0, 0);
+    frame.insert_channel("tacho", glide(200.0, 200.0, 1024));
+    frame.insert_channel("mic", vec![0.0; 1024]);
+    frame.metadata.insert("sample_rate", SAMPLE_RATE);
+    let out = node.process(frame).await.unwrap();
+    let mut names: Vec<_> = out.payload.keys().cloned().collect();
+    names.sort();
+    assert_eq!(names, ["tacho_confidence", "tacho_pitch"]);
+    assert_eq!(out.payload["tacho_pitch"].len(), 2);
+}
+
+#[tokio::test]
+async fn test_invalid_configurations() {
+    for config in [
+        json!({"hop_size": 4096, "window_size": 1024}),
+        json!({"min_frequency": 500.0, "max_frequency": 100.0}),
+        json!({"window_size": 2}),
+    ] {
+        let mut node = PitchTrackerNode::default();
+        assert!(node.on_create(config.clone()).await.is_err(), "{}", config);
+    }
+}
@@ -0,0 +1,24 @@
+#![cfg(feature = "plugin-host")]
+
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::nodes::PluginHostNode;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_requires_a_clap_plugin() {
+    let mut node = PluginHostNode::default();
+    node.on_create(json!({})).await.unwrap();
+    let err = node.process(DataFrame::new(0, 0)).await.unwrap_err();
+    assert!(err.to_string().contains("requires a plugin path"), "{}", err);
+
+    let mut vst3 = PluginHostNode::default();
+    let err = vst3.on_create(json!({"path": "/plugins/Eq.vst3"})).await.unwrap_err();
+    assert!(err.to_string().contains("VST3 plugins are not supported"), "{}", err);
+
+    let dir = tempfile::tempdir().unwrap();
+    let fake = dir.path().join("fake.clap");
+    std::fs::write(&fake, b"not a library").unwrap();
+    let mut node = PluginHostNode::default();
+    assert!(node.on_create(json!({"path": fake})).await.is_err());
+    assert!(node.parameters().is_empty());
+}
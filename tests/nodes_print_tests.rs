@@ -1,6 +1,5 @@
 use audiotab::core::{DataFrame, ProcessingNode};
 use audiotab::nodes::DebugSinkNode;
-use std::sync::Arc;
 
 #[tokio::test]
 async fn test_print_passthrough() {
@@ -10,8 +9,7 @@ async fn test_print_passthrough() {
     debug_sink.on_create(config).await.unwrap();
 
     let mut df = DataFrame::new(1000, 1);
-    df.payload
-        .insert("main_channel".to_string(), Arc::new(vec![1.0, 2.0, 3.0]));
+    df.insert_channel("main_channel", vec![1.0, 2.0, 3.0]);
 
     let result = debug_sink.process(df.clone()).await.unwrap();
 
@@ -0,0 +1,65 @@
+use audiotab::core::{ChannelRole, DataFrame, ProcessingNode};
+use audiotab::hal::{DeviceChannels, PacketBuffer, SampleData};
+use audiotab::nodes::RawBytesNode;
+use crossbeam_channel::{unbounded, Receiver, Sender};
+
+fn packet(data: SampleData) -> PacketBuffer {
+    PacketBuffer {
+        data,
+        sample_rate: 9600,
+        num_channels: 1,
+        timestamp: None,
+        codec: Some("csv".to_string()),
+    }
+}
+
+/// Node wired to a fake byte device: (node, device-side sender, returned buffers)
+async fn node_with_device(config: serde_json::Value) -> (RawBytesNode, Sender<PacketBuffer>, Receiver<PacketBuffer>) {
+    let (filled_tx, filled_rx) = unbounded();
+    let (empty_tx, empty_rx) = unbounded();
+    let mut node = RawBytesNode::default();
+    node.on_create(config).await.unwrap();
+    node.set_device_channels(Some(DeviceChannels { filled_rx, empty_tx }));
+    (node, filled_tx, empty_rx)
+}
+
+#[tokio::test]
+async fn test_emits_undecoded_bytes_in_order() {
+    let (mut node, device, returned) = node_with_device(serde_json::json!({})).await;
+    device.send(packet(SampleData::Bytes(b"1,2".to_vec()))).unwrap();
+    device.send(packet(SampleData::F32(vec![0.5]))).unwrap();
+    device.send(packet(SampleData::Bytes(b"\n".to_vec()))).unwrap();
+
+    let mut input = DataFrame::new(0, 7);
+    input.insert_channel("ch0", vec![0.1]);
+    let out = node.process(input).await.unwrap();
+    assert_eq!(out.payload["bytes"].samples(), &[49.0, 44.0, 50.0, 10.0]);
+    assert_eq!(out.payload["bytes"].role, ChannelRole::Control);
+    assert_eq!(out.metadata.get_i64("byte_count"), Some(4));
+    // Upstream channels pass through
+    assert_eq!(out.payload["ch0"].samples(), &[0.1]);
+    // Buffers go back to the device
+    assert_eq!(returned.try_iter().count(), 3);
+
+    // Nothing pending gives an empty channel
+    let out = node.process(DataFrame::new(0, 8)).await.unwrap();
+    assert!(out.payload["bytes"].samples().is_empty());
+    assert_eq!(node.total_bytes(), 4);
+}
+
+#[tokio::test]
+async fn test_keeps_newest_bytes_over_limit() {
+    let (mut node, device, _returned) = node_with_device(serde_json::json!({"channel": "serial", "max_bytes": 3})).await;
+    device.send(packet(SampleData::Bytes(vec![1, 2, 3, 4, 5]))).unwrap();
+    let out = node.process(DataFrame::new(0, 0)).await.unwrap();
+    assert_eq!(out.payload["serial"].samples(), &[3.0, 4.0, 5.0]);
+    assert_eq!(out.metadata.get_i64("dropped_bytes"), Some(2));
+}
+
+#[tokio::test]
+async fn test_invalid_configurations() {
+    for config in [serde_json::json!({"channel": ""}), serde_json::json!({"max_bytes": 0})] {
+        let mut node = RawBytesNode::default();
+        assert!(node.on_create(config.clone()).await.is_err(), "{}", config);
+    }
+}
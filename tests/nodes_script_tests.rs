@@ -0,0 +1,88 @@
+use audiotab::core::{Channel, DataFrame, ProcessingNode};
+use audiotab::nodes::ScriptNode;
+use serde_json::json;
+use std::time::{Duration, Instant};
+
+async fn node(config: serde_json::Value) -> ScriptNode {
+    let mut node = ScriptNode::default();
+    node.on_create(config).await.unwrap();
+    node
+}
+
+fn frame(samples: Vec<f64>) -> DataFrame {
+    let mut frame = DataFrame::new(0, 0);
+    frame.insert_channel("ch0", Channel::new(samples).with_unit("Pa").with_sample_rate(48000.0));
+    frame
+}
+
+#[tokio::test]
+async fn test_reads_and_writes_channels_and_metadata() {
+    let script = r#"
+        let x = frame.channel("ch0");
+        frame.set_channel("ch0", x.map(|s| s * 2.0));
+        frame.set_channel("squared", x.map(|s| s * s));
+        frame.set_meta("ch0_peak", peak(x));
+        frame.set_meta("label", "scaled");
+    "#;
+    let mut node = node(json!({ "script": script })).await;
+    let out = node.process(frame(vec![0.5, -1.0, 0.25])).await.unwrap();
+
+    assert_eq!(out.payload["ch0"].samples(), &[1.0, -2.0, 0.5]);
+    // A rewritten channel keeps its unit and sample rate
+    assert_eq!(out.payload["ch0"].unit.as_deref(), Some("Pa"));
+    assert_eq!(out.channel_sample_rate("ch0"), Some(48000.0));
+    assert_eq!(out.payload["squared"].samples(), &[0.25, 1.0, 0.0625]);
+    assert_eq!(out.metadata.get_f64("ch0_peak"), Some(1.0));
+    assert_eq!(out.metadata.get_str("label"), Some("scaled"));
+}
+
+#[tokio::test]
+async fn test_state_persists_and_false_drops_channels() {
+    let script = r#"
+        state.frames = (state.frames ?? 0) + 1;
+        frame.set_meta("frames", state.frames);
+        state.frames % 2 == 1
+    "#;
+    let mut node = node(json!({ "script": script })).await;
+
+    let first = node.process(frame(vec![1.0])).await.unwrap();
+    let second = node.process(frame(vec![1.0])).await.unwrap();
+    assert_eq!(first.metadata.get_i64("frames"), Some(1));
+    assert_eq!(first.payload.len(), 1);
+    assert_eq!(second.metadata.get_i64("frames"), Some(2));
+    assert!(second.payload.is_empty());
+}
+
+#[tokio::test]
+async fn test_script_errors() {
+    let mut bad = ScriptNode::default();
+    let err = bad.on_create(json!({"script": "let x = ;"})).await.unwrap_err();
+    assert!(err.to_string().starts_with("Script error"), "{}", err);
+
+    // eval is disabled in the sandbox
+    let mut sandboxed = ScriptNode::default();
+    assert!(sandboxed.on_create(json!({"script": "eval(\"1\")"})).await.is_err());
+
+    let mut node = node(json!({"script": "frame.channel(\"missing\")"})).await;
+    let err = node.process(frame(vec![1.0])).await.unwrap_err();
+    assert!(err.to_string().contains("No channel 'missing'"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_budgets_stop_runaway_scripts() {
+    let mut ops = node(json!({"script": "loop {}", "max_operations": 10000})).await;
+    let err = ops.process(frame(vec![1.0])).await.unwrap_err();
+    assert!(err.to_string().contains("operations"), "{}", err);
+
+    let mut timed = node(json!({"script": "loop {}", "max_operations": 1000000000u64, "time_budget_ms": 5.0})).await;
+    let start = Instant::now();
+    let err = timed.process(frame(vec![1.0])).await.unwrap_err();
+    assert!(err.to_string().contains("time budget exceeded"), "{}", err);
+    assert!(start.elapsed() < Duration::from_secs(2));
+
+    // The next frame gets a fresh budget
+    let mut node = node(json!({"script": "let n = 0; while n < 100 { n += 1; } frame.set_meta(\"n\", n);", "time_budget_ms": 50.0})).await;
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    let out = node.process(frame(vec![1.0])).await.unwrap();
+    assert_eq!(out.metadata.get_i64("n"), Some(100));
+}
@@ -0,0 +1,59 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::nodes::{SignalDetectorNode, SignalEventKind};
+
+/// 10 ms frame at 48 kHz with a constant-magnitude square wave
+fn level_frame(sequence_id: u64, amplitude: f64) -> DataFrame {
+    let samples: Vec<f64> = (0..480)
+        .map(|n| if n % 2 == 0 { amplitude } else { -amplitude })
+        .collect();
+    let mut frame = DataFrame::new(sequence_id * 10, sequence_id);
+    frame.insert_channel("ch0", samples);
+    frame.metadata.insert("sample_rate", 48000.0);
+    frame
+}
+
+#[tokio::test]
+async fn test_detector_requires_minimum_durations() {
+    let mut detector = SignalDetectorNode::default();
+    detector.on_create(serde_json::json!({
+        "threshold_db": -40.0,
+        "min_signal_ms": 30.0,
+        "min_silence_ms": 50.0
+    })).await.unwrap();
+    let mut events = detector.subscribe();
+
+    // A 20 ms burst is too short to count as signal
+    let mut last = None;
+    for seq in 1..=2 {
+        last = Some(detector.process(level_frame(seq, 0.1)).await.unwrap());
+    }
+    assert_eq!(last.unwrap().metadata["signal_present"], false);
+
+    detector.process(level_frame(3, 0.0)).await.unwrap();
+    let mut start_frame = None;
+    for seq in 4..=8 {
+        let out = detector.process(level_frame(seq, 0.1)).await.unwrap();
+        if out.metadata.get("signal_event").map(|e| e == "start").unwrap_or(false) {
+            start_frame = Some(out.sequence_id);
+        }
+    }
+    // Third loud frame completes 30 ms
+    assert_eq!(start_frame, Some(6));
+    assert!(detector.is_signal_present());
+
+    for seq in 9..=13 {
+        detector.process(level_frame(seq, 0.0)).await.unwrap();
+    }
+    assert!(!detector.is_signal_present());
+
+    let start = events.try_recv().unwrap();
+    assert_eq!(start.kind, SignalEventKind::SignalStart);
+    assert!((start.rms_db + 20.0).abs() < 1e-9);
+    assert_eq!(events.try_recv().unwrap().kind, SignalEventKind::SignalStop);
+}
+
+#[tokio::test]
+async fn test_detector_invalid_config() {
+    let mut detector = SignalDetectorNode::default();
+    assert!(detector.on_create(serde_json::json!({"min_signal_ms": -1.0})).await.is_err());
+}
@@ -0,0 +1,86 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::nodes::SignalGeneratorNode;
+
+async fn node(config: serde_json::Value) -> SignalGeneratorNode {
+    let mut node = SignalGeneratorNode::default();
+    node.on_create(config).await.unwrap();
+    node
+}
+
+async fn run(node: &mut SignalGeneratorNode, frames: usize) -> Vec<f64> {
+    let mut samples = Vec::new();
+    for i in 0..frames {
+        let frame = node.process(DataFrame::new(0, i as u64)).await.unwrap();
+        samples.extend_from_slice(frame.payload.get("ch0").unwrap());
+    }
+    samples
+}
+
+#[tokio::test]
+async fn test_sine_is_phase_continuous_across_frames() {
+    // 1 kHz at 48 kHz does not divide a 100 sample frame evenly
+    let mut node = node(serde_json::json!({"frequency": 1000.0, "buffer_size": 100, "num_channels": 2})).await;
+    let samples = run(&mut node, 5).await;
+
+    for (i, &s) in samples.iter().enumerate() {
+        let expected = (2.0 * std::f64::consts::PI * 1000.0 * i as f64 / 48000.0).sin();
+        assert!((s - expected).abs() < 1e-9, "sample {} is {}, expected {}", i, s, expected);
+    }
+
+    let frame = node.process(DataFrame::new(0, 0)).await.unwrap();
+    assert_eq!(frame.payload.len(), 2);
+    assert_eq!(frame.sample_rate(), Some(48000.0));
+}
+
+#[tokio::test]
+async fn test_square_triangle_and_saw() {
+    // 12 samples per period
+    let mut square = node(serde_json::json!({"waveform": "square", "frequency": 4000.0, "duty_cycle": 0.25, "buffer_size": 12})).await;
+    let samples = run(&mut square, 1).await;
+    assert_eq!(samples.iter().filter(|&&s| s > 0.0).count(), 3);
+
+    let mut triangle = node(serde_json::json!({"waveform": "triangle", "frequency": 4000.0, "amplitude": 0.5, "buffer_size": 12})).await;
+    let samples = run(&mut triangle, 1).await;
+    assert!((samples[3] - 0.5).abs() < 1e-12);
+    assert!((samples[9] + 0.5).abs() < 1e-12);
+
+    let mut saw = node(serde_json::json!({"waveform": "saw", "frequency": 4000.0, "buffer_size": 12})).await;
+    let samples = run(&mut saw, 1).await;
+    assert!(samples[..6].windows(2).all(|w| w[1] > w[0]));
+}
+
+#[tokio::test]
+async fn test_multitone_crest_factor() {
+    let mut low = node(serde_json::json!({"waveform": "multitone", "tone_count": 16, "tone_spacing": "linear", "start_frequency": 100.0, "stop_frequency": 10000.0})).await;
+    let frame = low.process(DataFrame::new(0, 0)).await.unwrap();
+    let crest = frame.metadata.get_f64("crest_factor").unwrap();
+    assert!(crest < 2.0, "crest factor {}", crest);
+    let peak = frame.payload.get("ch0").unwrap().iter().fold(0.0f64, |m, s| m.max(s.abs()));
+    assert!(peak <= 1.0 + 1e-12);
+
+    let mut high = node(serde_json::json!({"waveform": "multitone", "tone_count": 16, "tone_spacing": "linear", "start_frequency": 100.0, "stop_frequency": 10000.0, "crest_factor": 4.0})).await;
+    let frame = high.process(DataFrame::new(0, 0)).await.unwrap();
+    let crest = frame.metadata.get_f64("crest_factor").unwrap();
+    assert!((crest - 4.0).abs() < 0.5, "crest factor {}", crest);
+}
+
+#[tokio::test]
+async fn test_burst_gating_spans_frames() {
+    // 1 ms on (48 samples), 2 ms off (96 samples), frames of 100 samples
+    let mut node = node(serde_json::json!({"waveform": "square", "frequency": 100.0, "burst_on_ms": 1.0, "burst_off_ms": 2.0, "buffer_size": 100})).await;
+    let samples = run(&mut node, 3).await;
+
+    for (i, &s) in samples.iter().enumerate() {
+        let on = i % 144 < 48;
+        assert_eq!(s != 0.0, on, "sample {}", i);
+    }
+}
+
+#[tokio::test]
+async fn test_rejects_invalid_config() {
+    let mut node = SignalGeneratorNode::default();
+    assert!(node.on_create(serde_json::json!({"waveform": "noise"})).await.is_err());
+    assert!(node.on_create(serde_json::json!({"frequency": 30000.0})).await.is_err());
+    assert!(node.on_create(serde_json::json!({"waveform": "square", "duty_cycle": 1.5})).await.is_err());
+    assert!(node.on_create(serde_json::json!({"waveform": "multitone", "start_frequency": 5000.0, "stop_frequency": 100.0})).await.is_err());
+}
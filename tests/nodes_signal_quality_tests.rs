@@ -0,0 +1,122 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::engine::AsyncPipeline;
+use audiotab::nodes::SignalQualityNode;
+use serde_json::json;
+use std::f64::consts::PI;
+
+const SAMPLE_RATE: f64 = 8000.0;
+
+fn frame(seq: u64, channels: &[(&str, Vec<f64>)]) -> DataFrame {
+    let mut frame = DataFrame::new(seq * 1_000_000, seq);
+    for (name, samples) in channels {
+        frame.insert_channel(*name, samples.clone());
+    }
+    frame.metadata.insert("sample_rate", SAMPLE_RATE);
+    frame
+}
+
+fn sine(amplitude: f64, len: usize) -> Vec<f64> {
+    (0..len).map(|n| amplitude * (2.0 * PI * 100.0 * n as f64 / SAMPLE_RATE).sin()).collect()
+}
+
+#[tokio::test]
+async fn test_clean_signal_passes_unflagged() {
+    let mut node = SignalQualityNode::default();
+    node.on_create(json!({})).await.unwrap();
+    let out = node.process(frame(0, &[("ch0", sine(0.5, 800))])).await.unwrap();
+    assert!(out.metadata.get("quality_issue").is_none());
+    assert_eq!(out.payload["ch0"].samples(), sine(0.5, 800).as_slice());
+    let report = node.report();
+    assert_eq!(report["ch0"].clip_events, 0);
+    assert!((report["ch0"].peak - 0.5).abs() < 1e-3);
+}
+
+#[tokio::test]
+async fn test_flags_clipping_runs_per_channel() {
+    let mut node = SignalQualityNode::default();
+    node.on_create(json!({"clip_samples": 4})).await.unwrap();
+
+    // A hard-clipped sine sits at full scale for many samples per peak
+    let clipped: Vec<f64> = sine(2.0, 800).into_iter().map(|x| x.clamp(-1.0, 1.0)).collect();
+    // Single full-scale samples are not clipping
+    let mut spiky = sine(0.3, 800);
+    spiky[100] = 1.0;
+    spiky[300] = -1.0;
+
+    let out = node.process(frame(0, &[("clipped", clipped), ("spiky", spiky)])).await.unwrap();
+    assert_eq!(out.metadata.get_bool("quality_issue"), Some(true));
+    assert_eq!(out.metadata.get_str("clipping_channels"), Some("clipped"));
+    let report = node.report();
+    // Two peaks per 100 Hz period, 10 periods
+    assert_eq!(report["clipped"].clip_events, 20);
+    assert!(report["clipped"].clipped_samples > 20 * 4);
+    assert_eq!(report["spiky"].clip_events, 0);
+}
+
+#[tokio::test]
+async fn test_flags_dc_offset() {
+    let mut node = SignalQualityNode::default();
+    node.on_create(json!({"dc_threshold": 0.05})).await.unwrap();
+    let offset: Vec<f64> = sine(0.3, 800).into_iter().map(|x| x + 0.1).collect();
+    let out = node.process(frame(0, &[("ok", sine(0.3, 800)), ("offset", offset)])).await.unwrap();
+    assert_eq!(out.metadata.get_str("dc_offset_channels"), Some("offset"));
+    assert!((node.report()["offset"].dc_offset - 0.1).abs() < 0.02);
+}
+
+#[tokio::test]
+async fn test_flags_dropouts_after_signal_across_frames() {
+    let mut node = SignalQualityNode::default();
+    node.on_create(json!({"dropout_ms": 20.0, "raise_alert": true})).await.unwrap();
+
+    // Leading silence is not a dropout
+    let out = node.process(frame(0, &[("ch0", vec![0.0; 400])])).await.unwrap();
+    assert!(out.metadata.get("quality_issue").is_none());
+
+    // 20 ms at 8 kHz is 160 samples, spread over two frames
+    let mut samples = sine(0.5, 400);
+    samples.extend(vec![0.0; 100]);
+    let out = node.process(frame(1, &[("ch0", samples)])).await.unwrap();
+    assert!(out.metadata.get("quality_issue").is_none());
+    let out = node.process(frame(2, &[("ch0", vec![0.0; 100])])).await.unwrap();
+    assert_eq!(out.metadata.get_str("dropout_channels"), Some("ch0"));
+    assert_eq!(out.metadata.get_str("alert"), Some("signal_quality"));
+    assert_eq!(out.metadata.get_str("alert_message"), Some("Signal quality: dropout on ch0"));
+
+    // The same gap is reported once
+    let out = node.process(frame(3, &[("ch0", vec![0.0; 400])])).await.unwrap();
+    assert!(out.metadata.get("quality_issue").is_none());
+    assert_eq!(node.report()["ch0"].dropouts, 1);
+}
+
+#[tokio::test]
+async fn test_flagged_frames_are_counted_in_metrics() {
+    let config = json!({
+        "nodes": [
+            {"id": "quality", "type": "SignalQuality", "config": {}},
+            {"id": "gain", "type": "Gain", "config": {"gain": 1.0}}
+        ],
+        "connections": [{"from": "quality", "to": "gain"}]
+    });
+    let mut pipeline = AsyncPipeline::from_json(config).await.unwrap();
+    pipeline.start().await.unwrap();
+    for seq in 0..4 {
+        // A square wave, clipped at full scale on every other frame
+        let level = if seq % 2 == 0 { 1.0 } else { 0.5 };
+        let square = [level, level, level, level, -level, -level, -level, -level];
+        pipeline.trigger(frame(seq, &[("ch0", square.to_vec())])).await.unwrap();
+    }
+    pipeline.stop().await.unwrap();
+
+    let snapshot = pipeline.get_monitor().unwrap().collector().snapshot();
+    assert_eq!(snapshot["quality"].quality_issues, 2);
+    // Nodes passing a flagged frame on do not count it again
+    assert_eq!(snapshot["gain"].quality_issues, 0);
+}
+
+#[tokio::test]
+async fn test_invalid_configurations() {
+    for config in [json!({"clip_samples": 0}), json!({"dc_time_ms": 0.0}), json!({"dropout_ms": -1.0})] {
+        let mut node = SignalQualityNode::default();
+        assert!(node.on_create(config.clone()).await.is_err(), "{}", config);
+    }
+}
@@ -0,0 +1,115 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::nodes::SplMeterNode;
+use std::f64::consts::PI;
+
+fn sine_frame(sequence_id: u64, start: usize, len: usize, freq: f64, amplitude: f64) -> DataFrame {
+    let samples: Vec<f64> = (start..start + len)
+        .map(|n| amplitude * (2.0 * PI * freq * n as f64 / 48000.0).sin())
+        .collect();
+    let mut frame = DataFrame::new(0, sequence_id);
+    frame.insert_channel("ch0", samples);
+    frame.metadata.insert("sample_rate", 48000.0);
+    frame
+}
+
+fn level(frame: &DataFrame, key: &str) -> f64 {
+    frame.payload.get(key).unwrap_or_else(|| panic!("missing {}", key))[0]
+}
+
+#[tokio::test]
+async fn test_spl_94db_calibrator_tone() {
+    let mut meter = SplMeterNode::default();
+    meter.on_create(serde_json::json!({
+        "frequency_weighting": "A",
+        "time_weighting": "fast"
+    })).await.unwrap();
+
+    // 1 Pa RMS at 1 kHz is 94 dB SPL, A-weighting is 0 dB at 1 kHz
+    let amplitude = 2f64.sqrt();
+    let mut result = None;
+    for i in 0..48 {
+        result = Some(meter.process(sine_frame(i, i as usize * 1000, 1000, 1000.0, amplitude)).await.unwrap());
+    }
+    let result = result.unwrap();
+
+    assert!((level(&result, "ch0_spl") - 94.0).abs() < 0.3);
+    assert!((level(&result, "ch0_leq") - 94.0).abs() < 0.3);
+    assert!(level(&result, "ch0_lmax") >= level(&result, "ch0_spl"));
+    assert!(level(&result, "ch0_lmin") <= level(&result, "ch0_spl"));
+    assert_eq!(result.metadata["frequency_weighting"], "A");
+}
+
+#[tokio::test]
+async fn test_spl_calibration_metadata_and_weighting() {
+    let mut meter = SplMeterNode::default();
+    meter.on_create(serde_json::json!({
+        "frequency_weighting": "A",
+        "time_weighting": "slow"
+    })).await.unwrap();
+
+    // 100 Hz is attenuated by about 19.1 dB under A-weighting
+    let mut result = None;
+    for i in 0..480 {
+        let mut frame = sine_frame(i, i as usize * 1000, 1000, 100.0, 2f64.sqrt());
+        frame.metadata.insert("calibration_gain", 10.0);
+        result = Some(meter.process(frame).await.unwrap());
+    }
+    let spl = level(&result.unwrap(), "ch0_spl");
+
+    // +20 dB from calibration gain, -19.1 dB from weighting
+    assert!((spl - (94.0 + 20.0 - 19.1)).abs() < 0.5, "spl = {}", spl);
+}
+
+#[tokio::test]
+async fn test_lmax_catches_burst_within_frame() {
+    let mut meter = SplMeterNode::default();
+    meter.on_create(serde_json::json!({
+        "frequency_weighting": "Z",
+        "time_weighting": "fast"
+    })).await.unwrap();
+
+    // 0.5 s of quiet tone with a 50 ms 94 dB burst in the middle
+    let quiet = sine_frame(0, 0, 24000, 1000.0, 0.002);
+    let burst = sine_frame(0, 0, 24000, 1000.0, 2f64.sqrt());
+    let mut samples = quiet.payload["ch0"].samples().to_vec();
+    samples[10800..13200].copy_from_slice(&burst.payload["ch0"].samples()[10800..13200]);
+    let mut frame = DataFrame::new(0, 0);
+    frame.insert_channel("ch0", samples);
+    frame.metadata.insert("sample_rate", 48000.0);
+
+    let result = meter.process(frame).await.unwrap();
+    let spl = level(&result, "ch0_spl");
+    let lmax = level(&result, "ch0_lmax");
+
+    // Fast weighting reaches 1 - e^-0.4 of the burst's power: about 89 dB
+    assert!((lmax - 89.2).abs() < 1.0, "lmax = {}", lmax);
+    // By the end of the frame the level has decayed well below the peak
+    assert!(spl < lmax - 5.0, "spl = {}, lmax = {}", spl, lmax);
+}
+
+#[tokio::test]
+async fn test_lmin_of_steady_tone_matches_leq() {
+    let mut meter = SplMeterNode::default();
+    meter.on_create(serde_json::json!({
+        "frequency_weighting": "A",
+        "time_weighting": "fast"
+    })).await.unwrap();
+
+    // The level must not start from silence, even within the first frame
+    let mut result = None;
+    for i in 0..10 {
+        result = Some(meter.process(sine_frame(i, i as usize * 1000, 1000, 1000.0, 2f64.sqrt())).await.unwrap());
+        let lmin = level(result.as_ref().unwrap(), "ch0_lmin");
+        assert!((lmin - 94.0).abs() < 0.5, "frame {}: lmin = {}", i, lmin);
+    }
+    let result = result.unwrap();
+    let (lmin, leq) = (level(&result, "ch0_lmin"), level(&result, "ch0_leq"));
+    assert!((lmin - leq).abs() < 0.5, "lmin = {}, leq = {}", lmin, leq);
+}
+
+#[tokio::test]
+async fn test_spl_invalid_weighting() {
+    let mut meter = SplMeterNode::default();
+    assert!(meter.on_create(serde_json::json!({"frequency_weighting": "B"})).await.is_err());
+    assert!(meter.on_create(serde_json::json!({"frequency_weighting": "Z", "time_weighting": "medium"})).await.is_err());
+}
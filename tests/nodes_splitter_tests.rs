@@ -0,0 +1,102 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::engine::AsyncPipeline;
+use audiotab::nodes::SplitterNode;
+use async_trait::async_trait;
+use serde_json::json;
+use tokio::sync::mpsc;
+
+/// Sink forwarding every frame it receives
+struct ForwardSink(mpsc::UnboundedSender<DataFrame>);
+
+#[async_trait]
+impl ProcessingNode for ForwardSink {
+    async fn process(&mut self, input: DataFrame) -> anyhow::Result<DataFrame> {
+        let _ = self.0.send(input.clone());
+        Ok(input)
+    }
+}
+
+/// Source -> splitter with two outputs, each feeding a collecting sink
+async fn split_pipeline(
+    branches: serde_json::Value,
+    extra: serde_json::Value,
+) -> (AsyncPipeline, mpsc::UnboundedReceiver<DataFrame>, mpsc::UnboundedReceiver<DataFrame>) {
+    let mut fft_edge = json!({"from": "split", "from_port": "_outputs_0", "to": "fft"});
+    fft_edge.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+    let mut pipeline = AsyncPipeline::from_json(json!({
+        "nodes": [
+            {"id": "src", "type": "Gain", "config": {}},
+            {"id": "split", "type": "Splitter", "config": {"branches": branches}},
+            {"id": "fft", "type": "Print", "config": {}},
+            {"id": "meter", "type": "Print", "config": {}}
+        ],
+        "connections": [
+            {"from": "src", "to": "split"},
+            fft_edge,
+            {"from": "split", "from_port": "_outputs_1", "to": "meter"}
+        ]
+    }))
+    .await
+    .unwrap();
+
+    let (fft_tx, fft_rx) = mpsc::unbounded_channel();
+    let (meter_tx, meter_rx) = mpsc::unbounded_channel();
+    pipeline.nodes_mut().insert("fft".to_string(), Box::new(ForwardSink(fft_tx)));
+    pipeline.nodes_mut().insert("meter".to_string(), Box::new(ForwardSink(meter_tx)));
+    pipeline.start().await.unwrap();
+    (pipeline, fft_rx, meter_rx)
+}
+
+fn frame(sequence_id: u64, len: usize) -> DataFrame {
+    let mut frame = DataFrame::new(0, sequence_id);
+    frame.insert_channel("ch0", vec![0.25; len]);
+    frame.metadata.insert("sample_rate", 48000.0);
+    frame
+}
+
+#[tokio::test]
+async fn test_branches_get_their_own_block_size_and_decimation() {
+    let (mut pipeline, mut fft_rx, mut meter_rx) = split_pipeline(json!("16, 4/2"), json!({})).await;
+    for i in 0..4 {
+        pipeline.trigger(frame(i, 8)).await.unwrap();
+    }
+
+    for _ in 0..2 {
+        let block = fft_rx.recv().await.unwrap();
+        assert_eq!(block.payload["ch0"].len(), 16);
+        assert_eq!(block.sample_rate(), Some(48000.0));
+    }
+    // 32 samples decimated by 2 come out as four 4-sample blocks at 24 kHz
+    for _ in 0..4 {
+        let block = meter_rx.recv().await.unwrap();
+        assert_eq!(block.payload["ch0"].len(), 4);
+        assert_eq!(block.sample_rate(), Some(24000.0));
+        assert_eq!(block.metadata.get_i64("decimation_factor"), Some(2));
+    }
+
+    pipeline.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_connection_settings_override_the_branch() {
+    let branches = json!([{"block_size": 16}, {"block_size": 4, "decimation": 2}]);
+    let (mut pipeline, mut fft_rx, _meter_rx) = split_pipeline(branches, json!({"block_size": 8})).await;
+    pipeline.trigger(frame(0, 8)).await.unwrap();
+
+    assert_eq!(fft_rx.recv().await.unwrap().payload["ch0"].len(), 8);
+
+    pipeline.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_invalid_branches_are_rejected() {
+    for branches in ["0", "64/0", "64/x", "1, 2, 3"] {
+        let mut node = SplitterNode::default();
+        assert!(node.on_create(json!({ "branches": branches })).await.is_err(), "{}", branches);
+    }
+
+    let mut node = SplitterNode::default();
+    node.on_create(json!({"branches": ", /8"})).await.unwrap();
+    assert_eq!(node.output_shape("_outputs_0"), Default::default());
+    assert_eq!(node.output_shape("_outputs_1").decimation, Some(8));
+}
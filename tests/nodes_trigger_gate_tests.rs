@@ -0,0 +1,72 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::nodes::TriggerGateNode;
+
+fn frame_from(sequence_id: u64, samples: Vec<f64>) -> DataFrame {
+    let mut frame = DataFrame::new(0, sequence_id);
+    let aux: Vec<f64> = samples.iter().map(|s| s * 10.0).collect();
+    frame.insert_channel("ch0", samples);
+    frame.insert_channel("ch1", aux);
+    frame.metadata.insert("sample_rate", 1000.0);
+    frame
+}
+
+#[tokio::test]
+async fn test_gate_blocks_until_trigger() {
+    let mut gate = TriggerGateNode::default();
+    gate.on_create(serde_json::json!({"threshold": 0.5, "post_trigger_samples": 4})).await.unwrap();
+
+    let result = gate.process(frame_from(0, vec![0.0, 0.1, 0.2, 0.1])).await.unwrap();
+    assert!(result.payload.is_empty());
+    assert_eq!(result.metadata["triggered"], false);
+}
+
+#[tokio::test]
+async fn test_pre_trigger_and_capture_across_frames() {
+    let mut gate = TriggerGateNode::default();
+    gate.on_create(serde_json::json!({
+        "threshold": 0.5,
+        "pre_trigger_samples": 2,
+        "post_trigger_samples": 4
+    })).await.unwrap();
+
+    let first = gate.process(frame_from(0, vec![0.0, 0.1, 0.2, 0.9, 1.0])).await.unwrap();
+    assert_eq!(first.payload.get("ch0").unwrap().as_ref(), &vec![0.1, 0.2, 0.9, 1.0]);
+    assert_eq!(first.payload.get("ch1").unwrap().as_ref(), &vec![1.0, 2.0, 9.0, 10.0]);
+    assert_eq!(first.metadata.get_i64("trigger_offset"), Some(2));
+
+    // Capture continues for the remaining two samples
+    let second = gate.process(frame_from(1, vec![0.8, 0.7, 0.6, 0.5])).await.unwrap();
+    assert_eq!(second.payload.get("ch0").unwrap().as_ref(), &vec![0.8, 0.7]);
+    assert!(!second.metadata.contains_key("trigger_offset"));
+}
+
+#[tokio::test]
+async fn test_hysteresis_and_holdoff() {
+    let mut gate = TriggerGateNode::default();
+    gate.on_create(serde_json::json!({
+        "threshold": 0.5,
+        "hysteresis": 0.2,
+        "holdoff_ms": 5.0,
+        "post_trigger_samples": 1
+    })).await.unwrap();
+
+    // Dips to 0.4 do not re-arm (hysteresis), the dip at index 4 is inside hold-off
+    let signal = vec![0.0, 0.6, 0.4, 0.6, 0.0, 0.6, 0.0, 0.0, 0.0, 0.0, 0.6];
+    let result = gate.process(frame_from(0, signal)).await.unwrap();
+    assert_eq!(result.payload.get("ch0").unwrap().as_ref(), &vec![0.6, 0.6]);
+    assert_eq!(result.metadata.get_i64("trigger_events"), Some(2));
+}
+
+#[tokio::test]
+async fn test_falling_edge_and_invalid_config() {
+    let mut gate = TriggerGateNode::default();
+    gate.on_create(serde_json::json!({
+        "edge": "falling",
+        "threshold": 0.0,
+        "post_trigger_samples": 2
+    })).await.unwrap();
+    let result = gate.process(frame_from(0, vec![0.5, 0.2, -0.3, -0.6])).await.unwrap();
+    assert_eq!(result.payload.get("ch0").unwrap().as_ref(), &vec![-0.3, -0.6]);
+
+    assert!(gate.on_create(serde_json::json!({"edge": "sideways"})).await.is_err());
+}
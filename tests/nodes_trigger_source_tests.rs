@@ -0,0 +1,118 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::hal::{DeviceChannels, PacketBuffer, SampleData};
+use audiotab::nodes::TriggerSourceNode;
+use crossbeam_channel::unbounded;
+
+fn frame_from(sequence_id: u64, samples: Vec<f64>) -> DataFrame {
+    let mut frame = DataFrame::new(0, sequence_id);
+    frame.insert_channel("ch0", samples);
+    frame.metadata.insert("sample_rate", 1000.0);
+    frame
+}
+
+fn packet(bytes: &[u8]) -> PacketBuffer {
+    PacketBuffer {
+        data: SampleData::Bytes(bytes.to_vec()),
+        sample_rate: 48000,
+        num_channels: 1,
+        timestamp: None,
+        codec: None,
+    }
+}
+
+#[tokio::test]
+async fn test_periodic_events_by_sample_count() {
+    let mut node = TriggerSourceNode::default();
+    node.on_create(serde_json::json!({
+        "mode": "periodic",
+        "interval_ms": 5,
+        "post_trigger_samples": 2
+    })).await.unwrap();
+
+    // 5 ms at 1 kHz: events at samples 0 and 5, then 10 in the next frame
+    let samples: Vec<f64> = (0..8).map(|i| i as f64).collect();
+    let first = node.process(frame_from(0, samples)).await.unwrap();
+    assert_eq!(first.payload.get("ch0").unwrap().as_ref(), &vec![0.0, 1.0, 5.0, 6.0]);
+    assert_eq!(first.metadata.get_i64("trigger_events"), Some(2));
+
+    let samples: Vec<f64> = (8..12).map(|i| i as f64).collect();
+    let second = node.process(frame_from(1, samples)).await.unwrap();
+    assert_eq!(second.payload.get("ch0").unwrap().as_ref(), &vec![10.0, 11.0]);
+    assert_eq!(node.trigger_count(), 3);
+}
+
+#[tokio::test]
+async fn test_manual_trigger_with_pre_trigger_window() {
+    let mut node = TriggerSourceNode::default();
+    node.on_create(serde_json::json!({
+        "mode": "manual",
+        "pre_trigger_samples": 2,
+        "post_trigger_samples": 3
+    })).await.unwrap();
+
+    let idle = node.process(frame_from(0, vec![1.0, 2.0, 3.0])).await.unwrap();
+    assert!(idle.payload.is_empty());
+    assert_eq!(idle.metadata["triggered"], false);
+
+    let mut marked = frame_from(1, vec![4.0, 5.0, 6.0, 7.0]);
+    marked.metadata.insert("manual_trigger", true);
+    let fired = node.process(marked).await.unwrap();
+    assert_eq!(fired.payload.get("ch0").unwrap().as_ref(), &vec![2.0, 3.0, 4.0, 5.0, 6.0]);
+    assert_eq!(fired.metadata.get_i64("trigger_offset"), Some(2));
+
+    // A bare trigger frame, as sent by trigger_pipeline, is passed on as an event
+    let mut bare = DataFrame::new(0, 0);
+    bare.metadata.insert("manual_trigger", true);
+    let result = node.process(bare).await.unwrap();
+    assert_eq!(result.metadata["triggered"], true);
+    assert_eq!(node.trigger_count(), 2);
+}
+
+#[tokio::test]
+async fn test_level_mode_arms_on_monitored_channel() {
+    let mut node = TriggerSourceNode::default();
+    node.on_create(serde_json::json!({
+        "mode": "level",
+        "threshold": 0.5,
+        "pre_trigger_samples": 1,
+        "post_trigger_samples": 2
+    })).await.unwrap();
+
+    let result = node.process(frame_from(0, vec![0.0, 0.2, 0.9, 0.8, 0.1])).await.unwrap();
+    assert_eq!(result.payload.get("ch0").unwrap().as_ref(), &vec![0.2, 0.9, 0.8]);
+    assert_eq!(result.metadata["triggered"], true);
+
+    let mut other = DataFrame::new(0, 1);
+    other.insert_channel("ch1", vec![1.0]);
+    assert!(node.process(other).await.is_err());
+}
+
+#[tokio::test]
+async fn test_external_trigger_from_midi_and_serial() {
+    let (filled_tx, filled_rx) = unbounded();
+    let (empty_tx, _empty_rx) = unbounded();
+    let mut node = TriggerSourceNode::default();
+    node.on_create(serde_json::json!({"mode": "external", "post_trigger_samples": 2})).await.unwrap();
+    node.set_device_channels(Some(DeviceChannels { filled_rx, empty_tx }));
+
+    // Note-offs do not fire
+    filled_tx.send(packet(&[0x80, 60, 0])).unwrap();
+    let result = node.process(frame_from(0, vec![1.0, 2.0, 3.0])).await.unwrap();
+    assert!(result.payload.is_empty());
+
+    filled_tx.send(packet(&[0x90, 60, 100])).unwrap();
+    let result = node.process(frame_from(1, vec![4.0, 5.0, 6.0])).await.unwrap();
+    assert_eq!(result.payload.get("ch0").unwrap().as_ref(), &vec![4.0, 5.0]);
+
+    // Any other bytes come from a serial trigger line
+    filled_tx.send(packet(b"T\n")).unwrap();
+    let result = node.process(frame_from(2, vec![7.0, 8.0, 9.0])).await.unwrap();
+    assert_eq!(result.payload.get("ch0").unwrap().as_ref(), &vec![7.0, 8.0]);
+    assert_eq!(node.trigger_count(), 2);
+}
+
+#[tokio::test]
+async fn test_rejects_unknown_mode() {
+    let mut node = TriggerSourceNode::default();
+    assert!(node.on_create(serde_json::json!({"mode": "random"})).await.is_err());
+}
@@ -0,0 +1,226 @@
+use audiotab::core::DataFrame;
+use audiotab::engine::AsyncPipeline;
+use audiotab::observability::{
+    read_session, Alert, AlertConfig, AlertRouter, AlertSink, EventKind, EventLog, EventQuery, PipelineEvent,
+    Severity, WebhookSink,
+};
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use tempfile::TempDir;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Keeps what it is given
+#[derive(Default)]
+struct RecordingSink {
+    min_severity: Severity,
+    alerts: Mutex<Vec<Alert>>,
+}
+
+#[async_trait]
+impl AlertSink for RecordingSink {
+    fn min_severity(&self) -> Severity {
+        self.min_severity
+    }
+
+    async fn deliver(&self, alert: &Alert) -> anyhow::Result<()> {
+        self.alerts.lock().unwrap().push(alert.clone());
+        Ok(())
+    }
+}
+
+fn alert_at(timestamp_ms: u64, name: &str, severity: Severity) -> Alert {
+    let mut alert = Alert::new("detector", name, severity, format!("{} raised", name));
+    alert.timestamp_ms = timestamp_ms;
+    alert
+}
+
+fn router(debounce_ms: u64, sink: &Arc<RecordingSink>) -> AlertRouter {
+    let config = AlertConfig { debounce_ms, ..AlertConfig::default() };
+    AlertRouter::new(&config).unwrap().with_sink(sink.clone())
+}
+
+#[tokio::test]
+async fn test_repeats_are_debounced_unless_more_severe() {
+    let sink = Arc::new(RecordingSink::default());
+    let mut router = router(1000, &sink);
+
+    assert!(router.route(alert_at(0, "overload", Severity::Warning)).await);
+    assert!(!router.route(alert_at(300, "overload", Severity::Warning)).await);
+    assert!(!router.route(alert_at(600, "overload", Severity::Info)).await);
+    // Other alert names are debounced on their own
+    assert!(router.route(alert_at(700, "siren", Severity::Info)).await);
+    // Escalation passes the debounce
+    assert!(router.route(alert_at(800, "overload", Severity::Critical)).await);
+    assert!(!router.route(alert_at(1500, "overload", Severity::Warning)).await);
+    assert!(router.route(alert_at(1800, "overload", Severity::Warning)).await);
+
+    let delivered: Vec<(u64, String, u64)> =
+        sink.alerts.lock().unwrap().iter().map(|a| (a.timestamp_ms, a.name.clone(), a.suppressed)).collect();
+    assert_eq!(
+        delivered,
+        vec![
+            (0, "overload".to_string(), 0),
+            (700, "siren".to_string(), 0),
+            (800, "overload".to_string(), 2),
+            (1800, "overload".to_string(), 1),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_severity_filters() {
+    let all = Arc::new(RecordingSink::default());
+    let critical = Arc::new(RecordingSink { min_severity: Severity::Critical, ..Default::default() });
+    let config = AlertConfig { min_severity: Severity::Warning, debounce_ms: 0, ..AlertConfig::default() };
+    let mut router = AlertRouter::new(&config).unwrap().with_sink(all.clone()).with_sink(critical.clone());
+
+    assert!(!router.route(alert_at(0, "a", Severity::Info)).await);
+    assert!(router.route(alert_at(0, "b", Severity::Warning)).await);
+    assert!(router.route(alert_at(0, "c", Severity::Critical)).await);
+
+    assert_eq!(all.alerts.lock().unwrap().len(), 2);
+    let critical = critical.alerts.lock().unwrap();
+    assert_eq!(critical.len(), 1);
+    assert_eq!(critical[0].name, "c");
+}
+
+#[tokio::test]
+async fn test_webhook_posts_alert_json() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        let mut buffer = [0u8; 1024];
+        // Read until the JSON body is complete
+        while !request.ends_with(b"}") {
+            let n = stream.read(&mut buffer).await.unwrap();
+            request.extend_from_slice(&buffer[..n]);
+        }
+        stream.write_all(b"HTTP/1.1 204 No Content\r\n\r\n").await.unwrap();
+        String::from_utf8(request).unwrap()
+    });
+
+    let webhook = WebhookSink::new(&format!("http://127.0.0.1:{}/hooks/audio", port), Severity::Info).unwrap();
+    webhook.deliver(&alert_at(42, "siren", Severity::Critical)).await.unwrap();
+
+    let request = server.await.unwrap();
+    let (head, body) = request.split_once("\r\n\r\n").unwrap();
+    assert!(head.starts_with("POST /hooks/audio HTTP/1.1\r\n"), "{}", head);
+    assert!(head.contains("Content-Type: application/json"));
+    let alert: Alert = serde_json::from_str(body).unwrap();
+    assert_eq!(alert.name, "siren");
+    assert_eq!(alert.severity, Severity::Critical);
+    assert_eq!(alert.timestamp_ms, 42);
+}
+
+#[tokio::test]
+async fn test_webhook_reports_error_status() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut buffer = [0u8; 4096];
+        let _ = stream.read(&mut buffer).await;
+        let _ = stream.write_all(b"HTTP/1.1 500 Internal Server Error\r\n\r\n").await;
+    });
+
+    let webhook = WebhookSink::new(&format!("http://127.0.0.1:{}", port), Severity::Info).unwrap();
+    let error = webhook.deliver(&alert_at(0, "siren", Severity::Warning)).await.unwrap_err();
+    assert!(format!("{:#}", error).contains("500"), "{:#}", error);
+}
+
+#[test]
+fn test_config_parsing() {
+    assert_eq!(AlertConfig::from_json(&serde_json::Value::Null).unwrap(), AlertConfig::default());
+
+    let config = AlertConfig::from_json(&serde_json::json!({
+        "debounce_ms": 250,
+        "webhooks": [{"url": "http://localhost:8080/alerts", "min_severity": "critical"}]
+    }))
+    .unwrap();
+    assert_eq!(config.debounce_ms, 250);
+    assert_eq!(config.webhooks[0].min_severity, Severity::Critical);
+    assert_eq!(config.notify_severity, Severity::Warning);
+
+    for invalid in [
+        serde_json::json!({"webhooks": [{"url": "https://example.com/hook"}]}),
+        serde_json::json!({"webhooks": [{"url": "http://:80/"}]}),
+        serde_json::json!({"min_severity": "urgent"}),
+    ] {
+        assert!(AlertConfig::from_json(&invalid).is_err(), "{}", invalid);
+    }
+}
+
+#[tokio::test]
+async fn test_event_log_records_alerts() {
+    let dir = TempDir::new().unwrap();
+    let log = EventLog::create(dir.path(), "run-1").unwrap();
+    let mut alert = alert_at(1234, "overload", Severity::Critical);
+    alert.pipeline_id = Some("p1".to_string());
+    log.deliver(&alert).await.unwrap();
+
+    let query = EventQuery { kinds: vec![EventKind::Alert], ..Default::default() };
+    let events = read_session(dir.path(), "run-1", &query).unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].timestamp_ms, 1234);
+    assert_eq!(events[0].pipeline_id.as_deref(), Some("p1"));
+    assert_eq!(events[0].message, "overload raised");
+    assert_eq!(events[0].data["severity"], "critical");
+}
+
+#[tokio::test]
+async fn test_pipeline_publishes_alerts_of_the_raising_node() {
+    let config = serde_json::json!({
+        "pipeline_config": {"alerts": {"debounce_ms": 60000}},
+        "nodes": [
+            {"id": "limit", "type": "Alert", "config": {"name": "too_loud", "channel": "ch0", "threshold": 0.9}},
+            {"id": "gain", "type": "Gain", "config": {"gain": 1.0}}
+        ],
+        "connections": [{"from": "limit", "to": "gain"}]
+    });
+    let mut pipeline = AsyncPipeline::from_json(config).await.unwrap();
+    assert_eq!(pipeline.alert_config().debounce_ms, 60000);
+    let sink = Arc::new(RecordingSink::default());
+    let routing = AlertRouter::new(pipeline.alert_config())
+        .unwrap()
+        .with_sink(sink.clone())
+        .follow("p1", pipeline.subscribe_events());
+    let mut events = pipeline.subscribe_events();
+
+    pipeline.start().await.unwrap();
+    for (seq, level) in [0.5, 1.0, 0.2, 1.2].into_iter().enumerate() {
+        let mut frame = DataFrame::new(0, seq as u64);
+        frame.insert_channel("ch0", vec![level; 4]);
+        pipeline.trigger(frame).await.unwrap();
+    }
+    pipeline.stop().await.unwrap();
+
+    // Both loud frames raise the alert at the Alert node only
+    let alerts: Vec<Alert> = std::iter::from_fn(|| events.try_recv().ok())
+        .filter_map(|e| match e {
+            PipelineEvent::Alert(alert) => Some(alert),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(alerts.len(), 2);
+    assert!(alerts.iter().all(|a| a.node_id == "limit" && a.name == "too_loud" && a.severity == Severity::Warning));
+
+    // The router delivers the first and debounces the second
+    drop(pipeline);
+    routing.await.unwrap();
+    let delivered = sink.alerts.lock().unwrap();
+    assert_eq!(delivered.len(), 1);
+    assert_eq!(delivered[0].pipeline_id.as_deref(), Some("p1"));
+}
+
+#[tokio::test]
+async fn test_invalid_alert_config_is_rejected() {
+    let config = serde_json::json!({
+        "pipeline_config": {"alerts": {"webhooks": [{"url": "ftp://example.com"}]}},
+        "nodes": [],
+        "connections": []
+    });
+    assert!(AsyncPipeline::from_json(config).await.is_err());
+}
@@ -0,0 +1,170 @@
+use audiotab::engine::{AsyncPipeline, AudioKernelRuntime};
+use audiotab::hal::*;
+use audiotab::observability::{
+    list_sessions, read_session, EventKind, EventLog, EventQuery, LoggedEvent, NodeThroughput, PipelineEvent,
+    PipelineMetrics,
+};
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+
+fn kinds(events: &[LoggedEvent]) -> Vec<EventKind> {
+    events.iter().map(|e| e.kind).collect()
+}
+
+fn throughput(node_id: &str, frames_dropped: u64) -> NodeThroughput {
+    NodeThroughput {
+        node_id: node_id.to_string(),
+        frames_processed: 100,
+        frames_per_sec: 0.0,
+        errors_count: 0,
+        restarts_count: 0,
+        frames_dropped,
+        frames_missing: 0,
+        frames_out_of_order: 0,
+        triggers: 0,
+        quality_issues: 0,
+        avg_latency_us: 0,
+    }
+}
+
+#[test]
+fn test_events_persist_and_can_be_queried() {
+    let dir = TempDir::new().unwrap();
+    let log = EventLog::create(dir.path(), "run-1").unwrap();
+    log.record(LoggedEvent::new(EventKind::DeviceStarted, "Device started").device("mic"));
+    log.record(LoggedEvent::new(EventKind::PipelineDeployed, "Pipeline deployed").pipeline("p1"));
+    log.record(LoggedEvent::new(EventKind::Error, "Device disconnected").device("mic"));
+    log.end();
+    drop(log);
+
+    let all = read_session(dir.path(), "run-1", &EventQuery::default()).unwrap();
+    assert_eq!(
+        kinds(&all),
+        [
+            EventKind::SessionStarted,
+            EventKind::DeviceStarted,
+            EventKind::PipelineDeployed,
+            EventKind::Error,
+            EventKind::SessionEnded,
+        ]
+    );
+
+    let device = EventQuery { device_id: Some("mic".to_string()), ..Default::default() };
+    assert_eq!(kinds(&read_session(dir.path(), "run-1", &device).unwrap()), [EventKind::DeviceStarted, EventKind::Error]);
+
+    let latest_error = EventQuery { kinds: vec![EventKind::Error, EventKind::SessionEnded], limit: Some(1), ..Default::default() };
+    assert_eq!(kinds(&read_session(dir.path(), "run-1", &latest_error).unwrap()), [EventKind::SessionEnded]);
+
+    let sessions = list_sessions(dir.path()).unwrap();
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].session_id, "run-1");
+    assert_eq!(sessions[0].event_count, 5);
+}
+
+#[test]
+fn test_session_ids_cannot_leave_the_directory() {
+    let dir = TempDir::new().unwrap();
+    assert!(EventLog::create(dir.path(), "../escape").is_err());
+    assert!(read_session(dir.path(), "a/b", &EventQuery::default()).is_err());
+}
+
+#[test]
+fn test_truncated_lines_are_skipped() {
+    let dir = TempDir::new().unwrap();
+    let log = EventLog::create(dir.path(), "crashed").unwrap();
+    std::fs::OpenOptions::new()
+        .append(true)
+        .open(log.path())
+        .and_then(|mut f| std::io::Write::write_all(&mut f, b"{\"timestamp_ms\":1,\"ki"))
+        .unwrap();
+
+    let events = log.query(&EventQuery::default()).unwrap();
+    assert_eq!(kinds(&events), [EventKind::SessionStarted]);
+}
+
+#[tokio::test]
+async fn test_follow_records_state_changes_and_overruns() {
+    let dir = TempDir::new().unwrap();
+    let log = EventLog::create(dir.path(), "follow").unwrap();
+    let (tx, rx) = tokio::sync::broadcast::channel(16);
+    let task = log.follow("p1", rx);
+
+    tx.send(PipelineEvent::StateChanged { from: "Idle".into(), to: "Running".into() }).unwrap();
+    for dropped in [0, 4, 4] {
+        tx.send(PipelineEvent::Metrics(PipelineMetrics {
+            frames_processed: 100,
+            elapsed_ms: 0,
+            nodes: vec![throughput("fft", dropped)],
+        }))
+        .unwrap();
+    }
+    tx.send(PipelineEvent::NodeError { node_id: "fft".into(), error: "bad frame".into(), fatal: false }).unwrap();
+    tx.send(PipelineEvent::NodeError { node_id: "fft".into(), error: "crashed".into(), fatal: true }).unwrap();
+    drop(tx);
+    task.await.unwrap();
+
+    let events = log.query(&EventQuery { pipeline_id: Some("p1".to_string()), ..Default::default() }).unwrap();
+    assert_eq!(kinds(&events), [EventKind::PipelineState, EventKind::Overrun, EventKind::Error]);
+    assert_eq!(events[1].data["frames_dropped"], 4);
+}
+
+#[tokio::test]
+async fn test_follow_records_a_running_pipeline() {
+    let dir = TempDir::new().unwrap();
+    let log = EventLog::create(dir.path(), "pipeline").unwrap();
+    let mut pipeline = AsyncPipeline::from_json(serde_json::json!({
+        "nodes": [{"id": "gain", "type": "Gain", "config": {}}]
+    }))
+    .await
+    .unwrap();
+    let task = log.follow("p1", pipeline.subscribe_events());
+
+    pipeline.start().await.unwrap();
+    pipeline.stop().await.unwrap();
+    drop(pipeline);
+    task.await.unwrap();
+
+    let states = log.query(&EventQuery { kinds: vec![EventKind::PipelineState], ..Default::default() }).unwrap();
+    assert!(states.iter().any(|e| e.data["to"] == "Running"));
+}
+
+#[tokio::test]
+async fn test_kernel_records_device_start_and_stop() {
+    let dir = TempDir::new().unwrap();
+    let log = EventLog::create(dir.path(), "kernel").unwrap();
+
+    let mut registry = HardwareRegistry::new();
+    registry.register(LoopbackDriver::new());
+    let config = HardwareConfig {
+        version: "1.0".to_string(),
+        registered_devices: vec![RegisteredHardware {
+            registration_id: "mic".to_string(),
+            device_id: "loopback-input".to_string(),
+            hardware_name: "Loopback".to_string(),
+            driver_id: "loopback".to_string(),
+            hardware_type: HardwareType::Acoustic,
+            direction: Direction::Input,
+            user_name: "Mic".to_string(),
+            enabled: true,
+            protocol: None,
+            sample_rate: 48000,
+            channels: 1,
+            channel_mapping: ChannelMapping::default(),
+            calibration: Calibration { gain: 2.0, offset: 0.0, calibrated_at: None },
+            calibration_history: Vec::new(),
+            max_voltage: 0.0,
+            buffer_count: 4,
+            latency_mode: LatencyMode::LowLatency,
+            notes: String::new(),
+        }],
+    };
+    let mut kernel = AudioKernelRuntime::with_shared_registry(Arc::new(RwLock::new(registry)), config);
+    kernel.set_event_log(Some(log.clone()));
+    kernel.start().await.unwrap();
+    kernel.shutdown().await.unwrap();
+
+    let events = log.query(&EventQuery { device_id: Some("mic".to_string()), ..Default::default() }).unwrap();
+    assert_eq!(kinds(&events), [EventKind::DeviceStarted, EventKind::DeviceStopped]);
+    assert_eq!(events[0].data["calibration"]["gain"], 2.0);
+}
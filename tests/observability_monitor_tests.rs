@@ -1,5 +1,6 @@
 use audiotab::observability::{NodeMetrics, MetricsCollector, PipelineMonitor};
 use std::sync::Arc;
+use std::time::Duration;
 
 #[test]
 fn test_monitor_report() {
@@ -24,3 +25,45 @@ fn test_monitor_report() {
     assert!(report.contains("2 frames"));
     assert!(report.contains("1 error"));
 }
+
+#[test]
+fn test_monitor_node_metrics_average_throughput() {
+    let mut collector = MetricsCollector::new();
+    let gain = Arc::new(NodeMetrics::new("gain"));
+    let gen = Arc::new(NodeMetrics::new("gen"));
+    for _ in 0..10 {
+        gen.record_frame_processed();
+    }
+    gain.record_error();
+    collector.register("gain", gain);
+    collector.register("gen", gen);
+
+    let monitor = PipelineMonitor::new(collector).with_elapsed(Duration::from_secs(2));
+    let nodes = monitor.node_metrics();
+
+    assert_eq!(nodes.len(), 2);
+    assert_eq!(nodes[0].node_id, "gain");
+    assert_eq!(nodes[0].errors_count, 1);
+    assert_eq!(nodes[1].frames_processed, 10);
+    assert_eq!(nodes[1].frames_per_sec, 5.0);
+
+    let json = serde_json::to_value(&nodes[1]).unwrap();
+    assert_eq!(json["avg_latency_us"], 0);
+}
+
+#[test]
+fn test_monitor_triggers_counts_most_triggering_node() {
+    let mut collector = MetricsCollector::new();
+    let source = Arc::new(NodeMetrics::new("trigger"));
+    let gate = Arc::new(NodeMetrics::new("gate"));
+    for _ in 0..3 {
+        source.record_trigger();
+    }
+    gate.record_trigger();
+    collector.register("trigger", source);
+    collector.register("gate", gate);
+
+    let monitor = PipelineMonitor::new(collector);
+    assert_eq!(monitor.triggers(), 3);
+    assert!(monitor.generate_report().contains("Triggers: 3"));
+}
@@ -0,0 +1,20 @@
+#![cfg(feature = "otel")]
+
+use audiotab::observability::OtlpExporter;
+use std::time::{Duration, Instant};
+use tracing_subscriber::layer::SubscriberExt;
+
+#[tokio::test]
+async fn test_exporter_flushes_on_drop_without_collector() {
+    // Nothing listens on the discard port; export fails but must not block or panic
+    let exporter = OtlpExporter::new("audiotab-test", Some("http://127.0.0.1:9/v1/traces")).unwrap();
+    let subscriber = tracing_subscriber::registry().with(exporter.layer());
+    tracing::subscriber::with_default(subscriber, || {
+        let _node = tracing::info_span!("node", node = "gain").entered();
+        let _frame = tracing::debug_span!("process", sequence_id = 0).entered();
+    });
+
+    let started = Instant::now();
+    drop(exporter);
+    assert!(started.elapsed() < Duration::from_secs(15));
+}
@@ -0,0 +1,108 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::engine::AsyncPipeline;
+use audiotab::observability::PipelineEvent;
+use async_trait::async_trait;
+use tokio::sync::broadcast::Receiver;
+
+struct FailingNode;
+
+#[async_trait]
+impl ProcessingNode for FailingNode {
+    async fn process(&mut self, _input: DataFrame) -> anyhow::Result<DataFrame> {
+        anyhow::bail!("sensor unplugged")
+    }
+}
+
+fn drain_events(events: &mut Receiver<PipelineEvent>) -> Vec<PipelineEvent> {
+    std::iter::from_fn(|| events.try_recv().ok()).collect()
+}
+
+#[tokio::test]
+async fn test_pipeline_publishes_state_changes_and_metrics() {
+    let config = serde_json::json!({
+        "pipeline_config": {"metrics_interval_ms": 20},
+        "nodes": [
+            {"id": "gain", "type": "Gain", "config": {"gain": 2.0}},
+            {"id": "print", "type": "Print", "config": {}}
+        ],
+        "connections": [{"from": "gain", "to": "print"}]
+    });
+    let mut pipeline = AsyncPipeline::from_json(config).await.unwrap();
+    let mut events = pipeline.subscribe_events();
+
+    pipeline.start().await.unwrap();
+    for i in 0..5 {
+        pipeline.trigger(DataFrame::new(0, i)).await.unwrap();
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(60)).await;
+    pipeline.stop().await.unwrap();
+
+    let events = drain_events(&mut events);
+    let transitions: Vec<(String, String)> = events
+        .iter()
+        .filter_map(|e| match e {
+            PipelineEvent::StateChanged { from, to } => Some((from.clone(), to.clone())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        transitions,
+        vec![
+            ("Idle".to_string(), "Initializing".to_string()),
+            ("Initializing".to_string(), "Running".to_string()),
+            ("Running".to_string(), "Completed".to_string()),
+        ]
+    );
+
+    // Periodic samples while running, and a final one after draining
+    let metrics: Vec<_> = events
+        .iter()
+        .filter_map(|e| match e {
+            PipelineEvent::Metrics(m) => Some(m),
+            _ => None,
+        })
+        .collect();
+    assert!(metrics.len() >= 2);
+    let last = metrics.last().unwrap();
+    assert_eq!(last.frames_processed, 5);
+    let ids: Vec<&str> = last.nodes.iter().map(|n| n.node_id.as_str()).collect();
+    assert_eq!(ids, vec!["gain", "print"]);
+    assert_eq!(last.nodes[1].frames_processed, 5);
+
+    let json = serde_json::to_value(&events[0]).unwrap();
+    assert_eq!(json["type"], "state_changed");
+}
+
+#[tokio::test]
+async fn test_pipeline_publishes_node_errors() {
+    let config = serde_json::json!({
+        "nodes": [{"id": "sensor", "type": "Gain", "config": {}, "error_policy": "skip_frame"}],
+        "connections": []
+    });
+    let mut pipeline = AsyncPipeline::from_json(config).await.unwrap();
+    pipeline.nodes_mut().insert("sensor".to_string(), Box::new(FailingNode));
+    let mut events = pipeline.subscribe_events();
+
+    pipeline.start().await.unwrap();
+    pipeline.trigger(DataFrame::new(0, 0)).await.unwrap();
+    pipeline.stop().await.unwrap();
+
+    let errors: Vec<_> = drain_events(&mut events)
+        .into_iter()
+        .filter_map(|e| match e {
+            PipelineEvent::NodeError { node_id, error, fatal } => Some((node_id, error, fatal)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(errors, vec![("sensor".to_string(), "sensor unplugged".to_string(), false)]);
+}
+
+#[tokio::test]
+async fn test_zero_metrics_interval_is_rejected() {
+    let config = serde_json::json!({
+        "pipeline_config": {"metrics_interval_ms": 0},
+        "nodes": [],
+        "connections": []
+    });
+    assert!(AsyncPipeline::from_json(config).await.is_err());
+}
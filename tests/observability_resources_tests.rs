@@ -0,0 +1,88 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::engine::AsyncPipeline;
+use async_trait::async_trait;
+use serde_json::json;
+use std::time::Duration;
+
+/// Sink that takes `delay` for each frame
+struct SlowSink {
+    delay: Duration,
+}
+
+#[async_trait]
+impl ProcessingNode for SlowSink {
+    async fn process(&mut self, input: DataFrame) -> anyhow::Result<DataFrame> {
+        tokio::time::sleep(self.delay).await;
+        Ok(input)
+    }
+}
+
+fn frame(sequence_id: u64) -> DataFrame {
+    let mut frame = DataFrame::new(0, sequence_id);
+    frame.insert_channel("ch0", vec![0.0; 1024]);
+    frame.insert_channel("ch1", vec![0.0; 1024]);
+    frame
+}
+
+#[tokio::test]
+async fn test_frames_queued_for_a_slow_node_are_counted() {
+    let config = json!({
+        "nodes": [
+            {"id": "src", "type": "Gain", "config": {}},
+            {"id": "sink", "type": "Print", "config": {}}
+        ],
+        "connections": [{"from": "src", "to": "sink"}],
+        "pipeline_config": {"channel_capacity": 8}
+    });
+    let mut pipeline = AsyncPipeline::from_json(config).await.unwrap();
+    pipeline.nodes_mut().insert("sink".to_string(), Box::new(SlowSink { delay: Duration::from_millis(200) }));
+    pipeline.start().await.unwrap();
+
+    for i in 0..5 {
+        pipeline.trigger(frame(i)).await.unwrap();
+    }
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let usage = pipeline.resource_usage().unwrap();
+    let sink = usage.channels.iter().find(|c| c.channel == "sink").unwrap();
+    assert_eq!(sink.capacity, 8);
+    assert!(sink.queued >= 2, "queued {}", sink.queued);
+    assert_eq!(sink.queued_bytes, sink.queued as u64 * 2 * 1024 * 8);
+    // The fullest channel comes first
+    assert_eq!(usage.channels[0].channel, "sink");
+    assert_eq!(usage.frames_in_flight, usage.channels.iter().map(|c| c.queued).sum::<usize>());
+    assert!(usage.bytes_in_flight >= sink.queued_bytes);
+    assert!(usage.frame_pool.max_idle_buffers > 0);
+    #[cfg(target_os = "linux")]
+    assert!(usage.process_rss_bytes.is_some_and(|rss| rss > 0));
+
+    pipeline.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_lossy_edge_queues_are_reported() {
+    let config = json!({
+        "nodes": [
+            {"id": "src", "type": "Gain", "config": {}},
+            {"id": "scope", "type": "Print", "config": {}}
+        ],
+        "connections": [{"from": "src", "to": "scope", "backpressure": "drop_oldest"}],
+        "pipeline_config": {"channel_capacity": 2}
+    });
+    let mut pipeline = AsyncPipeline::from_json(config).await.unwrap();
+    pipeline.nodes_mut().insert("scope".to_string(), Box::new(SlowSink { delay: Duration::from_millis(200) }));
+    pipeline.start().await.unwrap();
+
+    for i in 0..10 {
+        pipeline.trigger(frame(i)).await.unwrap();
+    }
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let usage = pipeline.resource_usage().unwrap();
+    let edge = usage.channels.iter().find(|c| c.channel == "src -> scope").unwrap();
+    assert_eq!(edge.node_id, "scope");
+    assert_eq!((edge.queued, edge.capacity), (2, 2));
+    assert_eq!(edge.fill(), 1.0);
+
+    pipeline.stop().await.unwrap();
+}
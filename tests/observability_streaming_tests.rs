@@ -0,0 +1,90 @@
+#![cfg(feature = "streaming")]
+
+use audiotab::observability::streaming::{encode_waveform, WAVEFORM_HEADER_SIZE};
+use audiotab::observability::{PipelineEvent, StreamMessageKind, StreamingConfig, StreamingServer};
+use audiotab::visualization::RingBufferWriter;
+use futures_util::StreamExt;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+
+fn f32_at(bytes: &[u8], offset: usize) -> f32 {
+    f32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+async fn next_binary<S>(client: &mut S) -> Vec<u8>
+where
+    S: futures_util::Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    loop {
+        let message = tokio::time::timeout(Duration::from_secs(5), client.next())
+            .await
+            .expect("no message from server")
+            .unwrap()
+            .unwrap();
+        if let Message::Binary(bytes) = message {
+            return bytes;
+        }
+    }
+}
+
+#[test]
+fn test_waveform_message_layout() {
+    let samples = vec![(0..10).map(|n| n as f64).collect::<Vec<f64>>(), vec![-1.0; 10]];
+    let bytes = encode_waveform(8000.0, 42, &samples, 4);
+
+    assert_eq!(&bytes[0..4], b"ATWS");
+    assert_eq!(bytes[5], StreamMessageKind::Waveform as u8);
+    assert_eq!(f64::from_le_bytes(bytes[8..16].try_into().unwrap()), 8000.0);
+    assert_eq!(u64::from_le_bytes(bytes[16..24].try_into().unwrap()), 42);
+    assert_eq!(u32_at(&bytes, 24), 2);
+    // 10 samples in points of 3: the last point covers one sample
+    assert_eq!(u32_at(&bytes, 28), 4);
+    assert_eq!(u32_at(&bytes, 32), 3);
+    assert_eq!(bytes.len(), WAVEFORM_HEADER_SIZE + 2 * 4 * 8);
+
+    let pair = |ch: usize, point: usize| {
+        let offset = WAVEFORM_HEADER_SIZE + (ch * 4 + point) * 8;
+        (f32_at(&bytes, offset), f32_at(&bytes, offset + 4))
+    };
+    assert_eq!(pair(0, 0), (0.0, 2.0));
+    assert_eq!(pair(0, 3), (9.0, 9.0));
+    assert_eq!(pair(1, 2), (-1.0, -1.0));
+}
+
+#[tokio::test]
+async fn test_server_streams_waveforms_and_events() {
+    let dir = tempfile::tempdir().unwrap();
+    let ring = Arc::new(RingBufferWriter::new(dir.path().join("ring"), 1000, 1, 1).unwrap());
+    ring.write(&[vec![0.5; 500]]).unwrap();
+    let (events, events_rx) = tokio::sync::broadcast::channel(8);
+
+    let config = StreamingConfig {
+        window: Duration::from_millis(200),
+        points: 20,
+        interval: Duration::from_millis(10),
+    };
+    let server = StreamingServer::bind("127.0.0.1:0", Some(ring), Some(events_rx), config).await.unwrap();
+    let url = format!("ws://{}", server.local_addr());
+    let (mut client, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+
+    let waveform = next_binary(&mut client).await;
+    assert_eq!(waveform[5], StreamMessageKind::Waveform as u8);
+    assert_eq!(u32_at(&waveform, 28), 20);
+    assert_eq!(u32_at(&waveform, 32), 10);
+    assert_eq!(f32_at(&waveform, WAVEFORM_HEADER_SIZE), 0.5);
+    assert_eq!(server.client_count(), 1);
+
+    events
+        .send(PipelineEvent::StateChanged { from: "Idle".into(), to: "Running".into() })
+        .unwrap();
+    let event = next_binary(&mut client).await;
+    assert_eq!(event[5], StreamMessageKind::Event as u8);
+    let json: serde_json::Value = serde_json::from_slice(&event[8..]).unwrap();
+    assert_eq!(json["type"], "state_changed");
+    assert_eq!(json["to"], "Running");
+}
@@ -0,0 +1,162 @@
+#![cfg(feature = "plugins")]
+
+use audiotab::core::{Channel, DataFrame, ProcessingNode};
+use audiotab::engine::AsyncPipeline;
+use audiotab::plugin::abi::{HostApi, HostCall, PluginFrame, PluginVTable, ABI_VERSION};
+use audiotab::plugin::{self, Plugin};
+use audiotab::registry::{NodeMetadata, PresetStore};
+use async_trait::async_trait;
+use serde_json::json;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::sync::{Arc, Mutex, Once};
+use std::time::Duration;
+
+/// Instance of the test plugin's "acme_scale" node
+struct Scale {
+    factor: f64,
+}
+
+unsafe extern "C" fn describe() -> *const c_char {
+    c"[{
+        \"id\": \"acme_scale\",
+        \"name\": \"Acme Scale\",
+        \"category\": \"Vendor\",
+        \"inputs\": [{\"id\": \"in\", \"name\": \"In\", \"data_type\": \"audio_frame\"}],
+        \"outputs\": [{\"id\": \"out\", \"name\": \"Out\", \"data_type\": \"audio_frame\"}],
+        \"parameters\": [{\"name\": \"factor\", \"type\": \"float\", \"default\": 1.0}],
+        \"presets\": [{\"name\": \"Double\", \"parameters\": {\"factor\": 2.0}}]
+    }]"
+    .as_ptr()
+}
+
+unsafe extern "C" fn create(node_type: *const c_char, config: *const c_char, host: *const HostApi, call: *mut HostCall) -> *mut c_void {
+    assert_eq!(CStr::from_ptr(node_type).to_str().unwrap(), "acme_scale");
+    let config: serde_json::Value = serde_json::from_str(CStr::from_ptr(config).to_str().unwrap()).unwrap();
+    let factor = config["factor"].as_f64().unwrap_or(1.0);
+    if factor < 0.0 {
+        let message = CString::new(format!("factor must be non-negative, got {}", factor)).unwrap();
+        ((*host).set_error)(call, message.as_ptr());
+        return std::ptr::null_mut();
+    }
+    Box::into_raw(Box::new(Scale { factor })) as *mut c_void
+}
+
+unsafe extern "C" fn process(instance: *mut c_void, input: *const PluginFrame, host: *const HostApi, call: *mut HostCall) -> i32 {
+    let scale = &*(instance as *const Scale);
+    let input = &*input;
+    let metadata = CStr::from_ptr(input.metadata_json).to_str().unwrap();
+    if metadata.contains("\"fail\"") {
+        ((*host).set_error)(call, c"asked to fail".as_ptr());
+        return 1;
+    }
+    for channel in std::slice::from_raw_parts(input.channels, input.channel_count) {
+        let samples = std::slice::from_raw_parts(channel.samples, channel.len);
+        let scaled: Vec<f64> = samples.iter().map(|s| s * scale.factor).collect();
+        ((*host).set_channel)(call, channel.name, scaled.as_ptr(), scaled.len());
+    }
+    let factor = CString::new(scale.factor.to_string()).unwrap();
+    ((*host).set_metadata)(call, c"scaled_by".as_ptr(), factor.as_ptr())
+}
+
+unsafe extern "C" fn destroy(instance: *mut c_void) {
+    drop(Box::from_raw(instance as *mut Scale));
+}
+
+static VTABLE: PluginVTable = PluginVTable { abi_version: ABI_VERSION, describe, create, process, destroy };
+
+fn register_plugin() {
+    static REGISTER: Once = Once::new();
+    REGISTER.call_once(|| {
+        let plugin = unsafe { Plugin::from_vtable("acme", &VTABLE) }.unwrap();
+        assert_eq!(plugin.register().unwrap(), vec!["acme_scale"]);
+    });
+}
+
+/// Sink that keeps every frame it receives
+struct CollectSink(Arc<Mutex<Vec<DataFrame>>>);
+
+#[async_trait]
+impl ProcessingNode for CollectSink {
+    async fn process(&mut self, input: DataFrame) -> anyhow::Result<DataFrame> {
+        self.0.lock().unwrap().push(input.clone());
+        Ok(input)
+    }
+}
+
+fn frame(samples: Vec<f64>) -> DataFrame {
+    let mut frame = DataFrame::new(0, 0);
+    frame.insert_channel("ch0", Channel::new(samples).with_unit("V"));
+    frame
+}
+
+#[tokio::test]
+async fn test_runs_plugin_node_in_async_pipeline() {
+    register_plugin();
+
+    let meta = NodeMetadata::find("acme_scale").unwrap();
+    assert_eq!(meta.category, "Vendor");
+    assert_eq!(meta.parameters[0].name, "factor");
+    assert!(NodeMetadata::all().iter().any(|m| m.id == "acme_scale"));
+    let preset = PresetStore::with_builtins().get("acme_scale", "Double").cloned().unwrap();
+    assert_eq!(preset.parameters, json!({"factor": 2.0}));
+
+    let mut pipeline = AsyncPipeline::from_json(json!({
+        "nodes": [
+            {"id": "scale", "type": "acme_scale", "config": {"factor": 3.0}},
+            {"id": "sink", "type": "Print", "config": {}}
+        ],
+        "connections": [{"from": "scale", "to": "sink"}]
+    }))
+    .await
+    .unwrap();
+    let frames = Arc::new(Mutex::new(Vec::new()));
+    pipeline.nodes_mut().insert("sink".to_string(), Box::new(CollectSink(frames.clone())));
+
+    pipeline.start().await.unwrap();
+    pipeline.trigger(frame(vec![0.5, -1.0])).await.unwrap();
+    for _ in 0..200 {
+        if !frames.lock().unwrap().is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    pipeline.stop().await.unwrap();
+
+    let frames = frames.lock().unwrap();
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0].payload["ch0"].samples(), &[1.5, -3.0]);
+    // A rewritten channel keeps its unit
+    assert_eq!(frames[0].payload["ch0"].unit.as_deref(), Some("V"));
+    assert_eq!(frames[0].metadata.get_f64("scaled_by"), Some(3.0));
+}
+
+#[tokio::test]
+async fn test_plugin_errors() {
+    register_plugin();
+
+    // Ids are unique across built-in and plugin nodes
+    let again = unsafe { Plugin::from_vtable("acme", &VTABLE) }.unwrap();
+    assert!(again.register().unwrap_err().to_string().contains("acme"));
+
+    let mut node = NodeMetadata::find("acme_scale").unwrap().create_instance();
+    let err = node.on_create(json!({"factor": -1.0})).await.unwrap_err();
+    assert!(err.to_string().contains("factor must be non-negative"), "{}", err);
+
+    node.on_create(json!({"factor": 2.0})).await.unwrap();
+    let mut failing = frame(vec![1.0]);
+    failing.metadata.insert("fail", true);
+    let err = node.process(failing).await.unwrap_err();
+    assert!(err.to_string().contains("asked to fail"), "{}", err);
+    assert_eq!(node.process(frame(vec![1.0])).await.unwrap().payload["ch0"].samples(), &[2.0]);
+
+    static OLD: PluginVTable = PluginVTable { abi_version: ABI_VERSION + 1, describe, create, process, destroy };
+    let err = unsafe { Plugin::from_vtable("old", &OLD) }.err().unwrap();
+    assert!(err.to_string().contains("ABI version"), "{}", err);
+
+    let dir = tempfile::tempdir().unwrap();
+    assert!(plugin::load_dir(dir.path()).unwrap().is_empty());
+    let fake = dir.path().join(format!("fake.{}", std::env::consts::DLL_EXTENSION));
+    std::fs::write(&fake, b"not a library").unwrap();
+    assert!(plugin::load_dir(dir.path()).is_err());
+    assert!(Plugin::load(&fake).is_err());
+}
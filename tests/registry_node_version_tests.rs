@@ -0,0 +1,56 @@
+use audiotab::engine::{AsyncPipeline, GraphDocument};
+use audiotab::registry::NodeMetadata;
+use serde_json::json;
+
+#[test]
+fn test_registered_nodes_expose_version() {
+    let _ = audiotab::nodes::GainNode::default();
+
+    let meta = NodeMetadata::find("GainNode").expect("GainNode registered");
+    assert_eq!(meta.version, 1);
+    assert!(!meta.deprecated);
+
+    let meta = NodeMetadata::new("oldnode", "Old", "Processors").with_version(3).deprecated();
+    assert_eq!(meta.version, 3);
+    assert!(meta.deprecated);
+}
+
+#[tokio::test]
+async fn test_pipeline_rejects_newer_node_versions() {
+    let _ = audiotab::nodes::GainNode::default();
+
+    let pipeline = |version: serde_json::Value| {
+        json!({
+            "nodes": [{"id": "gain", "type": "GainNode", "version": version, "config": {"gain_db": 0.0}}],
+            "connections": []
+        })
+    };
+
+    assert!(AsyncPipeline::from_json(pipeline(json!(1))).await.is_ok());
+    assert!(AsyncPipeline::from_json(pipeline(json!(null))).await.is_ok());
+    let err = AsyncPipeline::from_json(pipeline(json!(7))).await.err().unwrap();
+    assert!(err.to_string().contains("version 7"));
+}
+
+#[test]
+fn test_graph_document_records_node_versions() {
+    let _ = audiotab::nodes::GainNode::default();
+
+    let mut doc = GraphDocument::from_value(json!({
+        "schema_version": 1,
+        "nodes": [
+            {"id": "gain", "type": "GainNode", "parameters": {"gain_db": 3.0}},
+            {"id": "custom", "type": "UnknownNode", "parameters": {}}
+        ],
+        "edges": []
+    }))
+    .unwrap();
+    doc.stamp_node_versions();
+
+    assert_eq!(doc.nodes[0].extra["version"], 1);
+    assert!(!doc.nodes[1].extra.contains_key("version"));
+
+    let pipeline = doc.to_pipeline_json();
+    assert_eq!(pipeline["nodes"][0]["version"], 1);
+    assert!(pipeline["nodes"][1].get("version").is_none());
+}
@@ -0,0 +1,66 @@
+use audiotab::engine::AsyncPipeline;
+use audiotab::registry::{NodePreset, PresetStore};
+use serde_json::json;
+use tempfile::tempdir;
+
+#[test]
+fn test_builtin_presets_from_derive() {
+    let _ = audiotab::nodes::SplMeterNode::default();
+    let store = PresetStore::with_builtins();
+
+    let presets = store.list("SplMeterNode");
+    let fast = presets.iter().find(|p| p.name == "A-weighted Fast").expect("built-in preset");
+    assert!(fast.builtin);
+    assert_eq!(fast.parameters["frequency_weighting"], "A");
+    assert_eq!(fast.parameters["time_weighting"], "fast");
+}
+
+#[test]
+fn test_user_presets_persist() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("presets.json");
+
+    let mut store = PresetStore::open(path.clone()).unwrap();
+    store.save_preset(NodePreset::new("GainNode", "Boost", json!({"gain_db": 12.0}))).unwrap();
+
+    let reopened = PresetStore::open(path).unwrap();
+    let preset = reopened.get("gainnode", "Boost").expect("saved preset");
+    assert!(!preset.builtin);
+    assert_eq!(preset.parameters["gain_db"], 12.0);
+
+    // Built-ins are protected
+    let mut store = reopened;
+    assert!(store.delete_preset("splmeternode", "A-weighted Fast").is_err());
+    assert!(store.save_preset(NodePreset::new("SplMeterNode", "A-weighted Fast", json!({}))).is_err());
+    store.delete_preset("GainNode", "Boost").unwrap();
+    assert!(store.get("GainNode", "Boost").is_none());
+}
+
+#[test]
+fn test_resolve_config_overrides_preset() {
+    let mut store = PresetStore::new();
+    store.save_preset(NodePreset::new("FFTNode", "Big", json!({"fft_size": 8192, "hop_size": 4096}))).unwrap();
+
+    let config = store.resolve_config("FFTNode", &json!({"preset": "Big", "hop_size": 2048})).unwrap();
+    assert_eq!(config, json!({"fft_size": 8192, "hop_size": 2048}));
+
+    assert!(store.resolve_config("FFTNode", &json!({"preset": "Missing"})).is_err());
+    assert_eq!(store.resolve_config("FFTNode", &json!({"fft_size": 64})).unwrap(), json!({"fft_size": 64}));
+}
+
+#[tokio::test]
+async fn test_pipeline_applies_builtin_preset() {
+    let config = json!({
+        "nodes": [
+            {"id": "fft", "type": "FFTNode", "config": {"preset": "Amplitude accurate"}}
+        ],
+        "connections": []
+    });
+    assert!(AsyncPipeline::from_json(config).await.is_ok());
+
+    let bad = json!({
+        "nodes": [{"id": "fft", "type": "FFTNode", "config": {"preset": "Nope"}}],
+        "connections": []
+    });
+    assert!(AsyncPipeline::from_json(bad).await.is_err());
+}
@@ -0,0 +1,45 @@
+use audiotab::registry::{hardware_config_schema, node_schemas, pipeline_schema, NodeMetadata};
+use serde_json::json;
+
+#[test]
+fn test_parameter_schema_from_metadata() {
+    let _ = audiotab::nodes::GainNode::default();
+    let schemas = node_schemas();
+    let gain = &schemas["gainnode"]["properties"]["gain_db"];
+    assert_eq!(gain["type"], "number");
+    assert_eq!(gain["minimum"], -80.0);
+    assert_eq!(gain["maximum"], 80.0);
+    assert_eq!(gain["description"], "In dB");
+
+    let averaging = NodeMetadata::find("AveragingNode").unwrap().config_schema();
+    let mode = averaging["properties"].as_object().unwrap().values().find(|p| p.get("enum").is_some()).unwrap();
+    assert_eq!(mode["enum"], json!(["linear", "exponential", "peak_hold"]));
+}
+
+#[test]
+fn test_parameter_check_matches_schema() {
+    let meta = NodeMetadata::find("GainNode").unwrap();
+    let gain = meta.parameters.iter().find(|p| p.name == "gain_db").unwrap();
+    assert!(gain.check(&json!(-6)).is_ok());
+    assert!(gain.check(&json!(81.0)).is_err());
+    assert!(gain.check(&json!("loud")).is_err());
+}
+
+#[test]
+fn test_pipeline_schema_matches_aliases() {
+    let schema = pipeline_schema();
+    let rules = schema["$defs"]["node"]["allOf"].as_array().unwrap();
+    let gain = rules
+        .iter()
+        .find(|rule| rule["then"]["properties"]["config"]["properties"].get("gain_db").is_some())
+        .unwrap();
+    let pattern = gain["if"]["properties"]["type"]["pattern"].as_str().unwrap();
+    assert!(pattern.contains("|Gain)"), "{}", pattern);
+    assert_eq!(schema["$defs"]["connection"]["required"], json!(["from", "to"]));
+}
+
+#[test]
+fn test_hardware_schema_describes_registered_devices() {
+    let schema = hardware_config_schema();
+    assert!(schema["properties"].get("registered_devices").is_some(), "{}", schema);
+}
@@ -0,0 +1,113 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::engine::AsyncPipeline;
+use audiotab::registry::{NodeMetadata, PortMultiplicity};
+use async_trait::async_trait;
+use serde_json::json;
+
+fn mixer_metadata() -> NodeMetadata {
+    NodeMetadata::new("mixernode", "Mixer", "Processors")
+        .add_variadic_input("inputs", "Audio In", "audio_frame", 2, 16)
+        .add_output("output", "Audio Out", "audio_frame")
+}
+
+#[test]
+fn test_variadic_port_instances() {
+    let meta = mixer_metadata();
+
+    let (inputs, outputs) = meta.instantiate_ports(&json!({"inputs": 4})).unwrap();
+    assert_eq!(inputs, vec!["inputs_0", "inputs_1", "inputs_2", "inputs_3"]);
+    assert_eq!(outputs, vec!["output"]);
+
+    // Defaults to the minimum count
+    let (inputs, _) = meta.instantiate_ports(&json!(null)).unwrap();
+    assert_eq!(inputs.len(), 2);
+}
+
+#[test]
+fn test_variadic_port_bounds() {
+    let meta = mixer_metadata();
+
+    assert!(meta.instantiate_ports(&json!({"inputs": 1})).is_err());
+    assert!(meta.instantiate_ports(&json!({"inputs": 17})).is_err());
+    assert!(meta.instantiate_ports(&json!({"output": 2})).is_err());
+    assert!(meta.instantiate_ports(&json!({"sidechain": 1})).is_err());
+}
+
+#[test]
+fn test_multiplicity_serialization() {
+    let meta = mixer_metadata();
+
+    let input = serde_json::to_value(&meta.inputs[0]).unwrap();
+    assert_eq!(input["multiplicity"], json!({"kind": "variadic", "min": 2, "max": 16}));
+    assert_eq!(meta.outputs[0].multiplicity, PortMultiplicity::Single);
+
+    // Ports saved without a multiplicity are single
+    let port: audiotab::registry::PortMetadata =
+        serde_json::from_value(json!({"id": "in", "name": "In", "data_type": "any"})).unwrap();
+    assert!(!port.is_variadic());
+}
+
+#[tokio::test]
+async fn test_pipeline_validates_connection_ports() {
+    let _ = audiotab::nodes::GainNode::default();
+
+    let graph = |to_port: &str| {
+        json!({
+            "nodes": [
+                {"id": "gain1", "type": "GainNode", "config": {"gain_db": 0.0}},
+                {"id": "gain2", "type": "GainNode", "config": {"gain_db": 0.0}}
+            ],
+            "connections": [{"from": "gain1", "to": "gain2", "to_port": to_port}]
+        })
+    };
+
+    assert!(AsyncPipeline::from_json(graph("_input")).await.is_ok());
+    assert!(AsyncPipeline::from_json(graph("sidechain")).await.is_err());
+
+    let fixed_port_count = json!({
+        "nodes": [
+            {"id": "gain1", "type": "GainNode", "config": {}, "port_counts": {"_input": 2}}
+        ],
+        "connections": []
+    });
+    assert!(AsyncPipeline::from_json(fixed_port_count).await.is_err());
+}
+
+/// Sink forwarding the frames it receives
+struct ForwardSink(tokio::sync::mpsc::UnboundedSender<DataFrame>);
+
+#[async_trait]
+impl ProcessingNode for ForwardSink {
+    async fn process(&mut self, input: DataFrame) -> anyhow::Result<DataFrame> {
+        let _ = self.0.send(input.clone());
+        Ok(input)
+    }
+}
+
+#[tokio::test]
+async fn test_port_tag_is_not_passed_downstream() {
+    let mut pipeline = AsyncPipeline::from_json(json!({
+        "nodes": [
+            {"id": "src", "type": "GainNode", "config": {}},
+            {"id": "gain", "type": "GainNode", "config": {}},
+            {"id": "sink", "type": "Print", "config": {}}
+        ],
+        "connections": [
+            {"from": "src", "to": "gain", "to_port": "_input"},
+            {"from": "gain", "to": "sink"}
+        ]
+    }))
+    .await
+    .unwrap();
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    pipeline.nodes_mut().insert("sink".to_string(), Box::new(ForwardSink(tx)));
+    pipeline.start().await.unwrap();
+
+    let mut frame = DataFrame::new(0, 0);
+    frame.insert_channel("ch0", vec![1.0]);
+    pipeline.trigger(frame).await.unwrap();
+
+    let received = rx.recv().await.unwrap();
+    assert_eq!(received.metadata.get_str("input_port"), None);
+    pipeline.stop().await.unwrap();
+}
@@ -0,0 +1,64 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::nodes::DataExportNode;
+use audiotab::resilience::checkpoint::{sidecar_path, write_checkpoint};
+use audiotab::resilience::{recover_dir, recover_file};
+use std::fs::File;
+use std::io::Write;
+use tempfile::tempdir;
+
+#[test]
+fn test_recover_cuts_back_to_checkpoint_and_appends_trailer() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("data.bin");
+    let mut file = File::create(&path).unwrap();
+    file.write_all(b"complete").unwrap();
+    write_checkpoint(&path, &file, 8, b"|end").unwrap();
+    file.write_all(b"partial record").unwrap();
+    drop(file);
+
+    let recovered = recover_file(&path).unwrap().unwrap();
+    assert_eq!(recovered.kept_bytes, 8);
+    assert_eq!(recovered.discarded_bytes, 14);
+    assert_eq!(std::fs::read(&path).unwrap(), b"complete|end");
+    assert!(!sidecar_path(&path).exists());
+
+    // Nothing left to recover
+    assert!(recover_file(&path).unwrap().is_none());
+}
+
+#[test]
+fn test_recover_dir_skips_files_without_checkpoint() {
+    let dir = tempdir().unwrap();
+    std::fs::write(dir.path().join("finished.csv"), "a\n").unwrap();
+    let crashed = dir.path().join("crashed.csv");
+    let file = File::create(&crashed).unwrap();
+    write_checkpoint(&crashed, &file, 0, &[]).unwrap();
+
+    let recovered = recover_dir(dir.path()).unwrap();
+    assert_eq!(recovered.len(), 1);
+    assert_eq!(recovered[0].path, crashed);
+    assert!(recover_dir(dir.path().join("missing")).unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_export_recovers_to_last_flushed_record() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("levels.csv");
+
+    let mut export = DataExportNode::default();
+    export.on_create(serde_json::json!({
+        "path": path.to_str().unwrap(),
+        "flush_interval_ms": 0
+    })).await.unwrap();
+    let mut frame = DataFrame::new(0, 0);
+    frame.insert_channel("spl", vec![94.0]);
+    export.process(frame).await.unwrap();
+    assert!(sidecar_path(&path).exists());
+
+    // A crash in the middle of the next record
+    std::fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(b"100,1,8").unwrap();
+    drop(export);
+
+    recover_file(&path).unwrap().unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "timestamp,sequence_id,spl\n0,0,94\n");
+}
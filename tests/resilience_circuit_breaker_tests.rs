@@ -0,0 +1,74 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::observability::NodeMetrics;
+use audiotab::resilience::{CircuitBreaker, CircuitState, ErrorPolicy, ResilientNode};
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Fails while `broken` is set, counting calls
+struct FlakyDevice {
+    broken: Arc<AtomicBool>,
+    calls: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl ProcessingNode for FlakyDevice {
+    async fn process(&mut self, input: DataFrame) -> anyhow::Result<DataFrame> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        if self.broken.load(Ordering::SeqCst) {
+            anyhow::bail!("USB transfer failed");
+        }
+        Ok(input)
+    }
+}
+
+#[test]
+fn test_circuit_breaker_state_machine() {
+    let mut breaker = CircuitBreaker::new(2, Duration::ZERO);
+    assert!(breaker.allow());
+    assert_eq!(breaker.record_failure(), None);
+    assert_eq!(breaker.record_failure(), Some(CircuitState::Open));
+
+    // Zero open time: the next frame is a half-open trial, and a failure re-opens
+    assert!(breaker.allow());
+    assert_eq!(breaker.state(), CircuitState::HalfOpen);
+    assert_eq!(breaker.record_failure(), Some(CircuitState::Open));
+    assert!(breaker.allow());
+    assert_eq!(breaker.record_success(), Some(CircuitState::Closed));
+    assert_eq!(breaker.consecutive_errors(), 0);
+
+    let mut held_open = CircuitBreaker::new(1, Duration::from_secs(60));
+    held_open.record_failure();
+    assert!(!held_open.allow());
+    assert_eq!(held_open.state(), CircuitState::Open);
+}
+
+#[tokio::test]
+async fn test_resilient_node_trips_and_recovers() {
+    let broken = Arc::new(AtomicBool::new(true));
+    let calls = Arc::new(AtomicUsize::new(0));
+    let device = Box::new(FlakyDevice { broken: broken.clone(), calls: calls.clone() });
+    let metrics = Arc::new(NodeMetrics::new("usb"));
+    let (events_tx, mut events) = tokio::sync::broadcast::channel(16);
+    let mut node = ResilientNode::new(device, metrics.clone(), ErrorPolicy::CircuitBreaker { error_threshold: 3, open_ms: 30 })
+        .with_circuit_events(events_tx);
+
+    for i in 0..10 {
+        assert!(node.process(DataFrame::new(0, i)).await.is_ok());
+    }
+    // Only the frames up to the trip reach the device
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    assert_eq!(metrics.errors_count(), 3);
+    let tripped = events.try_recv().unwrap();
+    assert_eq!(tripped.node_id, "usb");
+    assert_eq!(tripped.state, CircuitState::Open);
+    assert_eq!(tripped.error.as_deref(), Some("USB transfer failed"));
+
+    tokio::time::sleep(Duration::from_millis(40)).await;
+    broken.store(false, Ordering::SeqCst);
+    node.process(DataFrame::new(0, 10)).await.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+    assert_eq!(events.try_recv().unwrap().state, CircuitState::HalfOpen);
+    assert_eq!(events.try_recv().unwrap().state, CircuitState::Closed);
+}
@@ -0,0 +1,84 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::engine::AsyncPipeline;
+use audiotab::resilience::DeadLetterQueue;
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+
+fn frame(sequence_id: u64) -> DataFrame {
+    let mut frame = DataFrame::new(sequence_id * 1000, sequence_id);
+    frame.insert_channel("ch0", vec![0.0; 4]);
+    frame
+}
+
+/// Fails odd frames the first time it sees them, recording what it processed
+struct FailOnceNode {
+    seen: Arc<Mutex<Vec<u64>>>,
+    failed: Vec<u64>,
+}
+
+#[async_trait]
+impl ProcessingNode for FailOnceNode {
+    async fn process(&mut self, input: DataFrame) -> anyhow::Result<DataFrame> {
+        if input.sequence_id % 2 == 1 && !self.failed.contains(&input.sequence_id) {
+            self.failed.push(input.sequence_id);
+            anyhow::bail!("conversion failed");
+        }
+        self.seen.lock().unwrap().push(input.sequence_id);
+        Ok(input)
+    }
+}
+
+#[test]
+fn test_dead_letter_queue_is_bounded() {
+    let queue = DeadLetterQueue::new(2);
+    for i in 0..3 {
+        queue.push("node", format!("error {}", i), frame(i));
+    }
+
+    assert_eq!(queue.len(), 2);
+    assert_eq!(queue.dropped(), 1);
+    let letters = queue.list();
+    assert_eq!(letters[0].id, 1);
+    assert_eq!(letters[0].error, "error 1");
+    assert_eq!(letters[0].sequence_id, 1);
+    assert_eq!(letters[0].channels, vec![("ch0".to_string(), 4)]);
+
+    let taken = queue.take(2).unwrap();
+    assert_eq!(taken.frame.sequence_id, 2);
+    assert!(queue.take(2).is_none());
+    assert_eq!(queue.len(), 1);
+}
+
+#[tokio::test]
+async fn test_pipeline_stores_and_reinjects_failed_frames() {
+    let config = serde_json::json!({
+        "nodes": [{"id": "convert", "type": "Print", "config": {}, "error_policy": "skip_frame"}],
+        "connections": []
+    });
+    let mut pipeline = AsyncPipeline::from_json(config).await.unwrap();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    pipeline.nodes_mut().insert(
+        "convert".to_string(),
+        Box::new(FailOnceNode { seen: seen.clone(), failed: Vec::new() }),
+    );
+
+    pipeline.start().await.unwrap();
+    for i in 0..4 {
+        pipeline.trigger(frame(i)).await.unwrap();
+    }
+    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+    let letters = pipeline.dead_letters().list();
+    assert_eq!(letters.len(), 2);
+    assert_eq!(letters[0].node_id, "convert");
+    assert_eq!(letters[0].error, "conversion failed");
+    assert_eq!(letters[0].sequence_id, 1);
+
+    pipeline.reinject(letters[0].id).await.unwrap();
+    assert!(pipeline.reinject(letters[0].id).await.is_err());
+    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+    pipeline.stop().await.unwrap();
+
+    assert_eq!(*seen.lock().unwrap(), vec![0, 2, 1]);
+    assert_eq!(pipeline.dead_letters().len(), 1);
+}
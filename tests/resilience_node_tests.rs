@@ -1,9 +1,11 @@
-use audiotab::resilience::{ResilientNode, ErrorPolicy};
+use audiotab::resilience::{ResilientNode, ErrorPolicy, RestartStrategy};
 use audiotab::core::{ProcessingNode, DataFrame};
 use audiotab::nodes::GainNode;
 use audiotab::observability::NodeMetrics;
 use tokio::sync::mpsc;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use async_trait::async_trait;
 
 #[tokio::test]
 async fn test_resilient_node_success() {
@@ -22,7 +24,7 @@ async fn test_resilient_node_success() {
 
     // Send test frame
     let mut frame = DataFrame::new(0, 0);
-    frame.payload.insert("main_channel".to_string(), Arc::new(vec![1.0, 2.0]));
+    frame.insert_channel("main_channel", vec![1.0, 2.0]);
     tx_in.send(frame).await.unwrap();
     drop(tx_in);
 
@@ -51,3 +53,67 @@ async fn test_resilient_node_success() {
     assert_eq!(metrics.frames_processed(), 1);
     assert_eq!(metrics.errors_count(), 0);
 }
+
+/// Fails every frame whose sequence id is odd, counting `on_create` calls
+struct FlakyNode {
+    creates: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl ProcessingNode for FlakyNode {
+    async fn on_create(&mut self, _config: serde_json::Value) -> anyhow::Result<()> {
+        self.creates.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn process(&mut self, input: DataFrame) -> anyhow::Result<DataFrame> {
+        if input.sequence_id % 2 == 1 {
+            anyhow::bail!("frame {} failed", input.sequence_id);
+        }
+        Ok(input)
+    }
+}
+
+fn flaky(policy: ErrorPolicy) -> (ResilientNode, Arc<AtomicUsize>) {
+    let creates = Arc::new(AtomicUsize::new(0));
+    let node = Box::new(FlakyNode { creates: creates.clone() });
+    (ResilientNode::new(node, Arc::new(NodeMetrics::new("flaky")), policy), creates)
+}
+
+fn frame(sequence_id: u64) -> DataFrame {
+    let mut frame = DataFrame::new(0, sequence_id);
+    frame.insert_channel("ch0", vec![0.5, -0.5, 0.25]);
+    frame
+}
+
+#[tokio::test]
+async fn test_resilient_node_substitutes_silence() {
+    let (mut node, _) = flaky(ErrorPolicy::SubstituteSilence);
+
+    let output = node.process(frame(1)).await.unwrap();
+    assert_eq!(output.sequence_id, 1);
+    assert_eq!(&output.payload["ch0"][..], &[0.0, 0.0, 0.0]);
+    assert_eq!(&node.process(frame(2)).await.unwrap().payload["ch0"][..], &[0.5, -0.5, 0.25]);
+}
+
+#[tokio::test]
+async fn test_resilient_node_restarts_until_attempts_run_out() {
+    let (node, creates) = flaky(ErrorPolicy::Restart(RestartStrategy::Exponential {
+        base_ms: 1,
+        max_ms: 4,
+        max_attempts: 2,
+    }));
+    let mut node = node.with_config(serde_json::json!({"gain": 1.0}));
+
+    // Each failure re-creates the node and skips the frame
+    assert!(node.process(frame(1)).await.is_ok());
+    assert!(node.process(frame(3)).await.is_ok());
+    assert_eq!(creates.load(Ordering::SeqCst), 2);
+
+    // A success resets the attempt count
+    assert!(node.process(frame(2)).await.is_ok());
+    assert!(node.process(frame(5)).await.is_ok());
+    assert!(node.process(frame(7)).await.is_ok());
+    assert!(node.process(frame(9)).await.is_err());
+    assert_eq!(creates.load(Ordering::SeqCst), 4);
+}
@@ -1,4 +1,6 @@
-use audiotab::resilience::ErrorPolicy;
+use audiotab::resilience::{ErrorPolicy, RestartStrategy};
+use audiotab::engine::AsyncPipeline;
+use serde_json::json;
 use audiotab::core::DataFrame;
 
 #[test]
@@ -33,3 +35,40 @@ fn test_error_policy_use_default() {
         _ => panic!("Wrong variant"),
     }
 }
+
+#[test]
+fn test_error_policy_from_json() {
+    assert!(matches!(ErrorPolicy::from_json(&json!("skip-frame")).unwrap(), ErrorPolicy::SkipFrame));
+    assert!(matches!(
+        ErrorPolicy::from_json(&json!({"type": "substitute_silence"})).unwrap(),
+        ErrorPolicy::SubstituteSilence
+    ));
+    assert!(matches!(
+        ErrorPolicy::from_json(&json!({"type": "restart", "base_ms": 50, "max_attempts": 3})).unwrap(),
+        ErrorPolicy::Restart(RestartStrategy::Exponential { base_ms: 50, max_ms: 5000, max_attempts: 3 })
+    ));
+    assert!(matches!(
+        ErrorPolicy::from_json(&json!({"type": "restart", "strategy": "immediate"})).unwrap(),
+        ErrorPolicy::Restart(RestartStrategy::Immediate)
+    ));
+    assert!(ErrorPolicy::from_json(&json!("retry")).is_err());
+    assert!(ErrorPolicy::from_json(&json!(3)).is_err());
+}
+
+#[tokio::test]
+async fn test_pipeline_rejects_unknown_node_error_policy() {
+    let graph = json!({
+        "nodes": [{"id": "gain", "type": "GainNode", "config": {}, "error_policy": "retry"}],
+        "connections": []
+    });
+    let err = AsyncPipeline::from_json(graph).await.err().unwrap();
+    assert!(err.to_string().contains("Node 'gain'"));
+}
+
+#[test]
+fn test_circuit_breaker_policy_from_json() {
+    assert!(matches!(
+        ErrorPolicy::from_json(&json!({"type": "circuit-breaker", "error_threshold": 10})).unwrap(),
+        ErrorPolicy::CircuitBreaker { error_threshold: 10, open_ms: 1000 }
+    ));
+}
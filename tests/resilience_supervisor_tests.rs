@@ -0,0 +1,114 @@
+use audiotab::core::{DataFrame, ProcessingNode};
+use audiotab::engine::AsyncPipeline;
+use audiotab::observability::NodeMetrics;
+use audiotab::resilience::{RestartStrategy, Supervisor};
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Records sequence ids and panics on frame 1
+struct PanickyNode {
+    seen: Arc<Mutex<Vec<u64>>>,
+}
+
+#[async_trait]
+impl ProcessingNode for PanickyNode {
+    async fn process(&mut self, input: DataFrame) -> anyhow::Result<DataFrame> {
+        if input.sequence_id == 1 {
+            panic!("bad frame");
+        }
+        self.seen.lock().unwrap().push(input.sequence_id);
+        Ok(input)
+    }
+}
+
+#[test]
+fn test_restart_strategy_backoff() {
+    let strategy = RestartStrategy::Exponential { base_ms: 100, max_ms: 300, max_attempts: 3 };
+    assert_eq!(strategy.backoff_ms(1), Some(100));
+    assert_eq!(strategy.backoff_ms(2), Some(200));
+    assert_eq!(strategy.backoff_ms(3), Some(300));
+    assert_eq!(strategy.backoff_ms(4), None);
+    assert_eq!(RestartStrategy::Never.backoff_ms(1), None);
+
+    let breaker = RestartStrategy::CircuitBreaker { error_threshold: 2, timeout_ms: 500 };
+    assert_eq!(breaker.backoff_ms(1), Some(0));
+    assert_eq!(breaker.backoff_ms(2), Some(500));
+}
+
+#[tokio::test]
+async fn test_supervisor_restarts_panicking_task() {
+    let supervisor = Supervisor::new(RestartStrategy::Immediate);
+    let mut events = supervisor.subscribe();
+    let metrics = Arc::new(NodeMetrics::new("node"));
+    let runs = Arc::new(AtomicUsize::new(0));
+
+    let counter = runs.clone();
+    let handle = supervisor.supervise("node", metrics.clone(), move || {
+        let run = counter.fetch_add(1, Ordering::SeqCst);
+        async move {
+            if run < 2 {
+                panic!("run {} crashed", run);
+            }
+            Ok(())
+        }
+    });
+
+    handle.await.unwrap().unwrap();
+    assert_eq!(runs.load(Ordering::SeqCst), 3);
+    assert_eq!(metrics.restarts_count(), 2);
+
+    let first = events.recv().await.unwrap();
+    assert_eq!(first.node_id, "node");
+    assert_eq!(first.attempt, 1);
+    assert!(first.error.contains("run 0 crashed"));
+    assert!(!first.gave_up);
+    assert_eq!(events.recv().await.unwrap().attempt, 2);
+}
+
+#[tokio::test]
+async fn test_supervisor_gives_up_when_restarts_exhausted() {
+    let supervisor = Supervisor::new(RestartStrategy::Exponential { base_ms: 1, max_ms: 1, max_attempts: 1 });
+    let mut events = supervisor.subscribe();
+    let metrics = Arc::new(NodeMetrics::new("node"));
+
+    let handle = supervisor.supervise("node", metrics.clone(), || async { anyhow::bail!("device lost") });
+
+    let err = handle.await.unwrap().unwrap_err();
+    assert!(err.to_string().contains("device lost"));
+    assert_eq!(metrics.restarts_count(), 1);
+    assert!(!events.recv().await.unwrap().gave_up);
+    assert!(events.recv().await.unwrap().gave_up);
+}
+
+#[tokio::test]
+async fn test_pipeline_restarts_panicked_node_and_keeps_queued_frames() {
+    let config = serde_json::json!({
+        "nodes": [{"id": "sink", "type": "Print", "config": {}}],
+        "connections": [],
+        "pipeline_config": {"restart": {"base_ms": 1}}
+    });
+    let mut pipeline = AsyncPipeline::from_json(config).await.unwrap();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    pipeline
+        .nodes_mut()
+        .insert("sink".to_string(), Box::new(PanickyNode { seen: seen.clone() }));
+    let mut restarts = pipeline.subscribe_restarts();
+
+    pipeline.start().await.unwrap();
+    for i in 0..4 {
+        pipeline.trigger(DataFrame::new(0, i)).await.unwrap();
+    }
+    let event = restarts.recv().await.unwrap();
+    assert_eq!(event.node_id, "sink");
+    assert!(event.error.contains("bad frame"));
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+    let monitor = pipeline.get_monitor().unwrap();
+    pipeline.stop().await.unwrap();
+
+    // Only the panicking frame is lost
+    assert_eq!(*seen.lock().unwrap(), vec![0, 2, 3]);
+    let metrics = monitor.collector().get_node_metrics("sink").unwrap();
+    assert_eq!(metrics.restarts_count(), 1);
+}
@@ -1,38 +1,171 @@
 use wasm_bindgen::prelude::*;
 
+mod measure;
+mod spectrum;
 mod stft;
+use spectrum::{Spectrum, Window};
 use stft::compute_stft;
 
+const HEADER_SIZE: usize = 4096;
+const WRITE_SEQUENCE_OFFSET: usize = 40;
+const COMMIT_POSITION_OFFSET: usize = 48;
+const WRITE_EPOCH_OFFSET: usize = 56;
+const CHANNEL_TABLE_OFFSET: usize = 128;
+const CHANNEL_ENTRY_SIZE: usize = 64;
+const CHANNEL_NAME_LEN: usize = 40;
+const CHANNEL_UNIT_LEN: usize = 16;
+
+/// Samples per write assumed by version 1 buffers, which had no commit position
+const V1_SAMPLES_PER_WRITE: u64 = 1024;
+
+/// Windowed FFT for computing spectra in the UI
+///
+/// Plans the FFT once; reuse one analyzer per view. Magnitudes are single
+/// sided and corrected for the window gain, so a full-scale sine reads 1.0
+/// (0 dB with `db`).
+#[wasm_bindgen]
+pub struct SpectrumAnalyzer {
+    spectrum: Spectrum,
+}
+
+#[wasm_bindgen]
+impl SpectrumAnalyzer {
+    /// `window`: rectangular, hann, hamming, blackman or flattop
+    #[wasm_bindgen(constructor)]
+    pub fn new(fft_size: usize, window: &str, db: bool) -> Result<SpectrumAnalyzer, JsError> {
+        if fft_size < 2 {
+            return Err(JsError::new("fft_size must be at least 2"));
+        }
+        let window = Window::parse(window)
+            .ok_or_else(|| JsError::new(&format!("Unknown window type: {}", window)))?;
+        Ok(Self { spectrum: Spectrum::new(fft_size, window, db) })
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn fft_size(&self) -> usize {
+        self.spectrum.fft_size()
+    }
+
+    /// Spectrum of the last `fft_size` samples (zero-padded if fewer)
+    #[wasm_bindgen]
+    pub fn compute(&self, samples: &[f64]) -> Vec<f64> {
+        self.spectrum.compute(samples)
+    }
+
+    /// Centre frequency of each bin in Hz
+    #[wasm_bindgen]
+    pub fn frequencies(&self, sample_rate: f64) -> Vec<f64> {
+        let size = self.spectrum.fft_size();
+        (0..=size / 2).map(|bin| bin as f64 * sample_rate / size as f64).collect()
+    }
+}
+
+/// Whether a copy whose header holds `epoch_before` is free of writes,
+/// given the epoch read again after the copy
+fn copy_is_consistent(epoch_before: u64, epoch_after: u64) -> bool {
+    epoch_before.is_multiple_of(2) && epoch_after == epoch_before
+}
+
 #[wasm_bindgen]
 pub struct RingBufferReader {
     memory: Vec<u8>,
     sample_rate: u64,
     channels: usize,
     capacity: usize,
+    /// Samples per channel committed by the writer when the copy was taken
+    written: u64,
+    /// Header channel table; empty for buffers older than version 3
+    labels: Vec<ChannelLabel>,
+}
+
+/// Channel table entry: name, unit, and calibrated units per sample unit
+/// (0 when uncalibrated)
+#[derive(Debug, Clone, Default)]
+struct ChannelLabel {
+    name: String,
+    unit: String,
+    scale: f64,
+}
+
+impl ChannelLabel {
+    fn decode(entry: &[u8]) -> Self {
+        let text = |bytes: &[u8]| {
+            let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+            String::from_utf8_lossy(&bytes[..len]).into_owned()
+        };
+        let scale_at = CHANNEL_NAME_LEN + CHANNEL_UNIT_LEN;
+        Self {
+            name: text(&entry[..CHANNEL_NAME_LEN]),
+            unit: text(&entry[CHANNEL_NAME_LEN..scale_at]),
+            scale: f64::from_le_bytes(entry[scale_at..scale_at + 8].try_into().unwrap()),
+        }
+    }
 }
 
 #[wasm_bindgen]
 impl RingBufferReader {
+    /// Read a copy of the ring buffer file
+    ///
+    /// `epoch_after` is the write epoch read from the live buffer once the
+    /// copy is complete. A copy that overlapped a write (odd epoch, or one
+    /// that changed during the copy) is rejected; take a new copy and retry.
     #[wasm_bindgen(constructor)]
-    pub fn new(buffer: &[u8]) -> Self {
+    pub fn new(buffer: &[u8], epoch_after: u64) -> Result<RingBufferReader, JsError> {
         // Buffer length validation
-        assert!(buffer.len() >= 4096, "Buffer too small: expected at least 4096 bytes for header");
+        if buffer.len() < HEADER_SIZE {
+            return Err(JsError::new("Buffer too small: expected at least 4096 bytes for header"));
+        }
 
         // Magic number check
-        let magic = &buffer[0..8];
-        assert_eq!(magic, b"AUDITAB!", "Invalid magic number: expected 'AUDITAB!'");
+        if &buffer[0..8] != b"AUDITAB!" {
+            return Err(JsError::new("Invalid magic number: expected 'AUDITAB!'"));
+        }
 
         // Parse header
-        let sample_rate = u64::from_le_bytes(buffer[16..24].try_into().unwrap());
-        let channels = u64::from_le_bytes(buffer[24..32].try_into().unwrap()) as usize;
-        let capacity = u64::from_le_bytes(buffer[32..40].try_into().unwrap()) as usize;
+        let header = |offset: usize| u64::from_le_bytes(buffer[offset..offset + 8].try_into().unwrap());
+        let version = header(8);
+        let sample_rate = header(16);
+        let channels = header(24) as usize;
+        let capacity = header(32) as usize;
+        if buffer.len() < HEADER_SIZE + channels * capacity * 8 {
+            return Err(JsError::new(&format!(
+                "Buffer too small: expected {} channels of {} samples",
+                channels, capacity
+            )));
+        }
 
-        Self {
+        // Seqlock check: the header is copied first, so its epoch is the one
+        // before the copy; a write in progress or one that started since may
+        // have mixed old and new samples
+        let written = if version >= 2 {
+            if !copy_is_consistent(header(WRITE_EPOCH_OFFSET), epoch_after) {
+                return Err(JsError::new("Torn read: ring buffer copied during a write"));
+            }
+            header(COMMIT_POSITION_OFFSET)
+        } else {
+            header(WRITE_SEQUENCE_OFFSET) * V1_SAMPLES_PER_WRITE
+        };
+
+        let labels = if version >= 3 {
+            let labeled = channels.min((HEADER_SIZE - CHANNEL_TABLE_OFFSET) / CHANNEL_ENTRY_SIZE);
+            (0..labeled)
+                .map(|ch| {
+                    let offset = CHANNEL_TABLE_OFFSET + ch * CHANNEL_ENTRY_SIZE;
+                    ChannelLabel::decode(&buffer[offset..offset + CHANNEL_ENTRY_SIZE])
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self {
             memory: buffer.to_vec(),
             sample_rate,
             channels,
             capacity,
-        }
+            written,
+            labels,
+        })
     }
 
     #[wasm_bindgen(getter)]
@@ -53,28 +186,17 @@ impl RingBufferReader {
         // CRITICAL ISSUE 2: Validate num_points to prevent division by zero
         assert!(num_points > 0 && num_points <= self.capacity, "num_points must be between 1 and {}", self.capacity);
 
-        let ch_offset = 4096 + (channel * self.capacity * 8);
         let decimation = self.capacity / num_points;
+        let oldest = self.written.saturating_sub(self.capacity as u64);
 
-        let mut result = Vec::with_capacity(num_points);
-        for i in 0..num_points {
-            let idx = (i * decimation) % self.capacity;
-            let offset = ch_offset + (idx * 8);
-
-            if offset + 8 <= self.memory.len() {
-                let sample = f64::from_le_bytes(
-                    self.memory[offset..offset + 8].try_into().unwrap()
-                );
-                result.push(sample);
-            }
-        }
-
-        result
+        (0..num_points)
+            .map(|i| self.sample(channel, oldest + (i * decimation) as u64))
+            .collect()
     }
 
     #[wasm_bindgen]
     pub fn get_write_sequence(&self) -> u64 {
-        u64::from_le_bytes(self.memory[40..48].try_into().unwrap())
+        u64::from_le_bytes(self.memory[WRITE_SEQUENCE_OFFSET..WRITE_SEQUENCE_OFFSET + 8].try_into().unwrap())
     }
 
     #[wasm_bindgen]
@@ -93,21 +215,165 @@ impl RingBufferReader {
         compute_stft(&samples, window_size, hop_size)
     }
 
-    fn read_channel_samples(&self, channel: usize, count: usize) -> Vec<f64> {
-        let ch_offset = 4096 + (channel * self.capacity * 8);
-        let mut samples = Vec::with_capacity(count);
+    /// Spectrum of the newest `analyzer.fft_size` samples of a channel
+    #[wasm_bindgen]
+    pub fn get_spectrum(&self, channel: usize, analyzer: &SpectrumAnalyzer) -> Vec<f64> {
+        assert!(channel < self.channels, "Channel {} out of range", channel);
+        let samples = self.read_channel_samples(channel, analyzer.fft_size());
+        analyzer.compute(&samples)
+    }
 
-        for i in 0..count {
-            let idx = i % self.capacity;
-            let offset = ch_offset + (idx * 8);
+    // Measurements. Times are seconds from the first sample shown by
+    // get_waveform (the oldest retained sample); ranges are clamped to the
+    // buffer.
 
-            if offset + 8 <= self.memory.len() {
-                samples.push(f64::from_le_bytes(
-                    self.memory[offset..offset + 8].try_into().unwrap()
-                ));
-            }
+    /// Seconds of audio the buffer holds
+    #[wasm_bindgen]
+    pub fn duration(&self) -> f64 {
+        self.capacity as f64 / self.sample_rate as f64
+    }
+
+    /// Raw sample value at `time` seconds
+    #[wasm_bindgen]
+    pub fn value_at(&self, channel: usize, time: f64) -> f64 {
+        assert!(channel < self.channels, "Channel {} out of range", channel);
+        let index = self.time_to_index(time).min(self.capacity.saturating_sub(1));
+        self.sample(channel, self.oldest() + index as u64)
+    }
+
+    /// RMS of the raw samples between `start` and `end` seconds
+    #[wasm_bindgen]
+    pub fn rms(&self, channel: usize, start: f64, end: f64) -> f64 {
+        let (_, samples) = self.range_samples(channel, start, end);
+        measure::rms(&samples)
+    }
+
+    /// Up to `max_peaks` peaks of |x| between `start` and `end` seconds,
+    /// largest first, at least `min_separation` seconds apart
+    ///
+    /// Returned flat as `[time, value, time, value, ...]` with raw values.
+    #[wasm_bindgen]
+    pub fn find_peaks(&self, channel: usize, start: f64, end: f64, max_peaks: usize, min_separation: f64) -> Vec<f64> {
+        let (first, samples) = self.range_samples(channel, start, end);
+        let min_distance = self.time_to_index(min_separation);
+        measure::find_peaks(&samples, max_peaks, min_distance)
+            .into_iter()
+            .flat_map(|i| [(first + i) as f64 / self.sample_rate as f64, samples[i]])
+            .collect()
+    }
+
+    /// Level of a raw value in dB relative to full scale
+    #[wasm_bindgen]
+    pub fn to_dbfs(&self, value: f64) -> f64 {
+        measure::dbfs(value)
+    }
+
+    /// Raw value in the channel's calibrated unit, unchanged when uncalibrated
+    #[wasm_bindgen]
+    pub fn to_calibrated(&self, channel: usize, value: f64) -> f64 {
+        if self.is_calibrated(channel) {
+            value * self.label(channel).scale
+        } else {
+            value
+        }
+    }
+
+    /// Whether the writer recorded a calibration for the channel
+    #[wasm_bindgen]
+    pub fn is_calibrated(&self, channel: usize) -> bool {
+        self.label(channel).scale > 0.0
+    }
+
+    /// Channel name from the header, `ch<N>` when unnamed
+    #[wasm_bindgen]
+    pub fn channel_name(&self, channel: usize) -> String {
+        let name = &self.label(channel).name;
+        if name.is_empty() {
+            format!("ch{}", channel)
+        } else {
+            name.clone()
         }
+    }
+
+    /// Unit of calibrated values, e.g. "Pa"; empty when unset
+    #[wasm_bindgen]
+    pub fn channel_unit(&self, channel: usize) -> String {
+        self.label(channel).unit.clone()
+    }
 
-        samples
+    /// Plot label such as "Mic 1 [Pa]", or just the name without a unit
+    #[wasm_bindgen]
+    pub fn channel_label(&self, channel: usize) -> String {
+        let unit = &self.label(channel).unit;
+        if unit.is_empty() {
+            self.channel_name(channel)
+        } else {
+            format!("{} [{}]", self.channel_name(channel), unit)
+        }
+    }
+
+    fn label(&self, channel: usize) -> ChannelLabel {
+        assert!(channel < self.channels, "Channel {} out of range", channel);
+        self.labels.get(channel).cloned().unwrap_or_default()
+    }
+
+    /// Absolute position of the sample at time 0
+    fn oldest(&self) -> u64 {
+        self.written.saturating_sub(self.capacity as u64)
+    }
+
+    fn time_to_index(&self, time: f64) -> usize {
+        (time.max(0.0) * self.sample_rate as f64).round() as usize
+    }
+
+    /// Index of the first sample in the range and the samples from `start` to `end` seconds
+    fn range_samples(&self, channel: usize, start: f64, end: f64) -> (usize, Vec<f64>) {
+        assert!(channel < self.channels, "Channel {} out of range", channel);
+        let first = self.time_to_index(start.min(end)).min(self.capacity);
+        let last = self.time_to_index(start.max(end)).min(self.capacity);
+        let oldest = self.oldest();
+        let samples = (first..last).map(|i| self.sample(channel, oldest + i as u64)).collect();
+        (first, samples)
+    }
+
+    /// The newest `count` samples of a channel, oldest first
+    fn read_channel_samples(&self, channel: usize, count: usize) -> Vec<f64> {
+        let count = count.min(self.capacity);
+        let start = self.written.saturating_sub(count as u64);
+        (0..count as u64).map(|i| self.sample(channel, start + i)).collect()
+    }
+
+    /// Sample at absolute position `position` (samples since the writer was created)
+    fn sample(&self, channel: usize, position: u64) -> f64 {
+        let idx = (position % self.capacity as u64) as usize;
+        let offset = HEADER_SIZE + (channel * self.capacity + idx) * 8;
+        f64::from_le_bytes(self.memory[offset..offset + 8].try_into().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_copy_checked_against_epoch_after() {
+        assert!(copy_is_consistent(4, 4));
+        // A write was in progress when the header was copied
+        assert!(!copy_is_consistent(5, 5));
+        // A write started after the header was copied
+        assert!(!copy_is_consistent(4, 5));
+        assert!(!copy_is_consistent(4, 6));
+    }
+
+    #[test]
+    fn test_reads_consistent_copy() {
+        let mut buffer = vec![0u8; HEADER_SIZE + 4 * 8];
+        buffer[0..8].copy_from_slice(b"AUDITAB!");
+        for (offset, value) in [(8, 3u64), (16, 4), (24, 1), (32, 4), (COMMIT_POSITION_OFFSET, 4), (WRITE_EPOCH_OFFSET, 2)] {
+            buffer[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+        }
+        let reader = RingBufferReader::new(&buffer, 2).ok().unwrap();
+        assert_eq!(reader.channels(), 1);
+        assert_eq!(reader.channel_name(0), "ch0");
     }
 }
@@ -0,0 +1,62 @@
+/// Level of a sample magnitude relative to full scale (1.0), floored at -200 dB
+pub fn dbfs(value: f64) -> f64 {
+    20.0 * (value.abs() + 1e-10).log10()
+}
+
+/// Root mean square, 0 for an empty range
+pub fn rms(samples: &[f64]) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    (samples.iter().map(|s| s * s).sum::<f64>() / samples.len() as f64).sqrt()
+}
+
+/// Indices of the largest local maxima of |x|, largest first
+///
+/// A peak is at least as large as both neighbours. Peaks closer than
+/// `min_distance` samples to a larger one already picked are skipped.
+pub fn find_peaks(samples: &[f64], max_peaks: usize, min_distance: usize) -> Vec<usize> {
+    let magnitude = |i: usize| samples[i].abs();
+    let mut candidates: Vec<usize> = (0..samples.len())
+        .filter(|&i| {
+            let left = i == 0 || magnitude(i) >= magnitude(i - 1);
+            let right = i + 1 == samples.len() || magnitude(i) >= magnitude(i + 1);
+            left && right && magnitude(i) > 0.0
+        })
+        .collect();
+    candidates.sort_by(|&a, &b| magnitude(b).partial_cmp(&magnitude(a)).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut peaks: Vec<usize> = Vec::new();
+    for i in candidates {
+        if peaks.len() == max_peaks {
+            break;
+        }
+        if peaks.iter().all(|&p| p.abs_diff(i) >= min_distance.max(1)) {
+            peaks.push(i);
+        }
+    }
+    peaks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rms_and_dbfs() {
+        let square = [1.0, -1.0, 1.0, -1.0];
+        assert_eq!(rms(&square), 1.0);
+        assert_eq!(rms(&[]), 0.0);
+        assert!(dbfs(1.0).abs() < 1e-6);
+        assert!((dbfs(-0.5) + 6.0206).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_find_peaks_orders_and_separates() {
+        let samples = [0.0, 0.2, 0.0, -0.9, 0.0, 0.5, 0.6, 0.0, 0.3, 0.0];
+        assert_eq!(find_peaks(&samples, 3, 1), vec![3, 6, 8]);
+        // The 0.6 peak is 3 samples from -0.9
+        assert_eq!(find_peaks(&samples, 2, 3), vec![3, 6]);
+        assert_eq!(find_peaks(&samples, 2, 4), vec![3, 8]);
+    }
+}
@@ -0,0 +1,144 @@
+use rustfft::{Fft, FftPlanner, num_complex::Complex};
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+/// Floor for dB conversion, -200 dB
+const DB_FLOOR: f64 = 1e-10;
+
+/// Analysis windows, matching the backend's `window_type` names
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Window {
+    Rectangular,
+    Hann,
+    Hamming,
+    Blackman,
+    FlatTop,
+}
+
+impl Window {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "rectangular" | "rect" | "none" => Some(Window::Rectangular),
+            "hann" | "hanning" => Some(Window::Hann),
+            "hamming" => Some(Window::Hamming),
+            "blackman" => Some(Window::Blackman),
+            "flattop" | "flat_top" => Some(Window::FlatTop),
+            _ => None,
+        }
+    }
+
+    /// `size` periodic (DFT-even) window coefficients
+    pub fn coefficients(&self, size: usize) -> Vec<f64> {
+        let cosine_terms: &[f64] = match self {
+            Window::Rectangular => &[1.0],
+            Window::Hann => &[0.5, 0.5],
+            Window::Hamming => &[0.54, 0.46],
+            Window::Blackman => &[0.42, 0.5, 0.08],
+            Window::FlatTop => &[
+                0.215_578_95,
+                0.416_631_58,
+                0.277_263_158,
+                0.083_578_947,
+                0.006_947_368,
+            ],
+        };
+
+        (0..size)
+            .map(|n| {
+                let phase = 2.0 * PI * n as f64 / size as f64;
+                cosine_terms
+                    .iter()
+                    .enumerate()
+                    .map(|(k, &a)| if k % 2 == 0 { a } else { -a } * (k as f64 * phase).cos())
+                    .sum()
+            })
+            .collect()
+    }
+}
+
+/// Single-sided amplitude spectrum of one block
+///
+/// Magnitudes are corrected for the window's coherent gain, so a sine of
+/// amplitude A peaks at A (0 dB for a full-scale sine in dB mode).
+pub struct Spectrum {
+    fft: Arc<dyn Fft<f64>>,
+    window: Vec<f64>,
+    /// 2 / sum(window): single-sided amplitude scaling
+    scale: f64,
+    db: bool,
+}
+
+impl Spectrum {
+    pub fn new(fft_size: usize, window: Window, db: bool) -> Self {
+        let window = window.coefficients(fft_size);
+        let scale = 2.0 / window.iter().sum::<f64>();
+        Self {
+            fft: FftPlanner::new().plan_fft_forward(fft_size),
+            window,
+            scale,
+            db,
+        }
+    }
+
+    pub fn fft_size(&self) -> usize {
+        self.window.len()
+    }
+
+    /// `fft_size / 2 + 1` bins from DC to Nyquist
+    ///
+    /// Uses the last `fft_size` samples; shorter input is zero-padded at the end.
+    pub fn compute(&self, samples: &[f64]) -> Vec<f64> {
+        let size = self.fft_size();
+        let samples = &samples[samples.len().saturating_sub(size)..];
+        let mut buffer: Vec<Complex<f64>> = (0..size)
+            .map(|i| Complex::new(samples.get(i).copied().unwrap_or(0.0) * self.window[i], 0.0))
+            .collect();
+        self.fft.process(&mut buffer);
+
+        buffer
+            .iter()
+            .take(size / 2 + 1)
+            .enumerate()
+            .map(|(bin, c)| {
+                // DC and Nyquist have no mirrored half
+                let edge = bin == 0 || 2 * bin == size;
+                let magnitude = c.norm() * if edge { self.scale / 2.0 } else { self.scale };
+                if self.db {
+                    20.0 * (magnitude + DB_FLOOR).log10()
+                } else {
+                    magnitude
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sine_amplitude_is_window_independent() {
+        // 1 kHz at 32 kHz lands on bin 32 of a 1024-point FFT
+        let samples: Vec<f64> = (0..1024)
+            .map(|n| 0.5 * (2.0 * PI * 1000.0 * n as f64 / 32000.0).sin())
+            .collect();
+
+        for window in [Window::Rectangular, Window::Hann, Window::Blackman, Window::FlatTop] {
+            let spectrum = Spectrum::new(1024, window, false).compute(&samples);
+            assert_eq!(spectrum.len(), 513);
+            assert!((spectrum[32] - 0.5).abs() < 1e-9, "{:?}: {}", window, spectrum[32]);
+        }
+
+        let db = Spectrum::new(1024, Window::Hann, true).compute(&samples);
+        assert!((db[32] - 20.0 * 0.5f64.log10()).abs() < 1e-6);
+        assert!(db[200] < -150.0);
+    }
+
+    #[test]
+    fn test_window_names() {
+        assert_eq!(Window::parse("Hann"), Some(Window::Hann));
+        assert_eq!(Window::parse("flat_top"), Some(Window::FlatTop));
+        assert_eq!(Window::parse("kaiser"), None);
+    }
+}